@@ -1,4 +1,4 @@
-use std::{array, borrow::Borrow, cmp::max, iter::once};
+use std::{array, borrow::Borrow, cmp::max, iter::once, marker::PhantomData};
 
 use openvm_circuit_primitives::{
     bitwise_op_lookup::BitwiseOperationLookupBus,
@@ -15,41 +15,88 @@ use openvm_stark_backend::{
 
 use super::{
     big_sig0_field, big_sig1_field, ch_field, compose, maj_field, small_sig0_field,
-    small_sig1_field, u32_into_limbs, Sha256DigestCols, Sha256RoundCols, SHA256_DIGEST_WIDTH,
-    SHA256_H, SHA256_HASH_WORDS, SHA256_K, SHA256_ROUNDS_PER_ROW, SHA256_ROUND_WIDTH,
-    SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S,
+    small_sig1_field, word_into_limbs, Sha256Config, Sha2Config, Sha2DigestCols, Sha2RoundCols,
+    Sha384Config, Sha512Config, SHA256_ROW_VAR_CNT, SHA256_WORD_BITS, SHA256_WORD_U16S,
+    SHA256_WORD_U8S, SHA2_HASH_WORDS, SHA2_ROUNDS_PER_ROW, SHA512_ROW_VAR_CNT, SHA512_WORD_BITS,
+    SHA512_WORD_U16S, SHA512_WORD_U8S,
 };
 use crate::constraint_word_addition;
 
-/// Expects the message to be padded to a multiple of 512 bits
+/// The compression function of a SHA-2 hash function, with the parameters given by `C` and the
+/// column shapes given by the const generics, see [Sha2RoundCols].
+/// Expects the message to be padded to a multiple of [Sha2Config::BLOCK_BITS] bits
 #[derive(Clone, Debug)]
-pub struct Sha256Air {
+pub struct Sha2Air<
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+> {
     pub bitwise_lookup_bus: BitwiseOperationLookupBus,
     pub row_idx_encoder: Encoder,
     /// Internal bus for self-interactions in this AIR.
     bus: PermutationCheckBus,
+    _config: PhantomData<C>,
 }
 
-impl Sha256Air {
+pub type Sha256Air =
+    Sha2Air<Sha256Config, SHA256_WORD_BITS, SHA256_WORD_U8S, SHA256_WORD_U16S, SHA256_ROW_VAR_CNT>;
+pub type Sha512Air =
+    Sha2Air<Sha512Config, SHA512_WORD_BITS, SHA512_WORD_U8S, SHA512_WORD_U16S, SHA512_ROW_VAR_CNT>;
+pub type Sha384Air =
+    Sha2Air<Sha384Config, SHA512_WORD_BITS, SHA512_WORD_U8S, SHA512_WORD_U16S, SHA512_ROW_VAR_CNT>;
+
+impl<
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     pub fn new(bitwise_lookup_bus: BitwiseOperationLookupBus, self_bus_idx: BusIndex) -> Self {
+        assert_eq!(WORD_BITS, C::WORD_BITS);
+        assert_eq!(WORD_U8S, C::WORD_U8S);
+        assert_eq!(WORD_U16S, C::WORD_U16S);
+        // One more flag for the padding rows
+        let row_idx_encoder = Encoder::new(C::ROWS_PER_BLOCK + 1, 2, false);
+        assert_eq!(row_idx_encoder.width(), ROW_VAR_CNT);
         Self {
             bitwise_lookup_bus,
-            row_idx_encoder: Encoder::new(18, 2, false),
+            row_idx_encoder,
             bus: PermutationCheckBus::new(self_bus_idx),
+            _config: PhantomData,
         }
     }
 }
 
-impl<F> BaseAir<F> for Sha256Air {
+impl<
+        F,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > BaseAir<F> for Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     fn width(&self) -> usize {
         max(
-            Sha256RoundCols::<F>::width(),
-            Sha256DigestCols::<F>::width(),
+            Sha2RoundCols::<F, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>::width(),
+            Sha2DigestCols::<F, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>::width(),
         )
     }
 }
 
-impl<AB: InteractionBuilder> SubAir<AB> for Sha256Air {
+impl<
+        AB: InteractionBuilder,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > SubAir<AB> for Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     /// The start column for the sub-air to use
     type AirContext<'a>
         = usize
@@ -69,7 +116,19 @@ impl<AB: InteractionBuilder> SubAir<AB> for Sha256Air {
     }
 }
 
-impl Sha256Air {
+impl<
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
+    const ROUND_WIDTH: usize =
+        Sha2RoundCols::<u8, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>::width();
+    const DIGEST_WIDTH: usize =
+        Sha2DigestCols::<u8, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>::width();
+
     /// Implements the single row constraints (i.e. imposes constraints only on local)
     /// Implements some sanity constraints on the row index, flags, and work variables
     fn eval_row<AB: InteractionBuilder>(&self, builder: &mut AB, start_col: usize) {
@@ -78,8 +137,8 @@ impl Sha256Air {
 
         // Doesn't matter which column struct we use here as we are only interested in the common
         // columns
-        let local_cols: &Sha256DigestCols<AB::Var> =
-            local[start_col..start_col + SHA256_DIGEST_WIDTH].borrow();
+        let local_cols: &Sha2DigestCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> =
+            local[start_col..start_col + Self::DIGEST_WIDTH].borrow();
         let flags = &local_cols.flags;
         builder.assert_bool(flags.is_round_row);
         builder.assert_bool(flags.is_first_4_rows);
@@ -91,7 +150,7 @@ impl Sha256Air {
             .eval(builder, &local_cols.flags.row_idx);
         builder.assert_one(
             self.row_idx_encoder
-                .contains_flag_range::<AB>(&local_cols.flags.row_idx, 0..=17),
+                .contains_flag_range::<AB>(&local_cols.flags.row_idx, 0..=C::ROWS_PER_BLOCK),
         );
         builder.assert_eq(
             self.row_idx_encoder
@@ -100,25 +159,25 @@ impl Sha256Air {
         );
         builder.assert_eq(
             self.row_idx_encoder
-                .contains_flag_range::<AB>(&local_cols.flags.row_idx, 0..=15),
+                .contains_flag_range::<AB>(&local_cols.flags.row_idx, 0..=C::ROUND_ROWS - 1),
             flags.is_round_row,
         );
         builder.assert_eq(
             self.row_idx_encoder
-                .contains_flag::<AB>(&local_cols.flags.row_idx, &[16]),
+                .contains_flag::<AB>(&local_cols.flags.row_idx, &[C::ROUND_ROWS]),
             flags.is_digest_row,
         );
-        // If padding row we want the row_idx to be 17
+        // If padding row we want the row_idx to be ROWS_PER_BLOCK
         builder.assert_eq(
             self.row_idx_encoder
-                .contains_flag::<AB>(&local_cols.flags.row_idx, &[17]),
+                .contains_flag::<AB>(&local_cols.flags.row_idx, &[C::ROWS_PER_BLOCK]),
             flags.is_padding_row(),
         );
 
         // Constrain a, e, being composed of bits: we make sure a and e are always in the same place
         // in the trace matrix Note: this has to be true for every row, even padding rows
-        for i in 0..SHA256_ROUNDS_PER_ROW {
-            for j in 0..SHA256_WORD_BITS {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
+            for j in 0..WORD_BITS {
                 builder.assert_bool(local_cols.hash.a[i][j]);
                 builder.assert_bool(local_cols.hash.e[i][j]);
             }
@@ -128,24 +187,24 @@ impl Sha256Air {
     /// Implements constraints for a digest row that ensure proper state transitions between blocks
     /// This validates that:
     /// The work variables are correctly initialized for the next message block
-    /// For the last message block, the initial state matches SHA256_H constants
+    /// For the last message block, the initial state matches [Sha2Config::H] constants
     fn eval_digest_row<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256RoundCols<AB::Var>,
-        next: &Sha256DigestCols<AB::Var>,
+        local: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+        next: &Sha2DigestCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
     ) {
         // Check that if this is the last row of a message or an inpadding row, the hash should be
-        // the [SHA256_H]
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+        // the [Sha2Config::H]
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             let a = next.hash.a[i].map(|x| x.into());
             let e = next.hash.e[i].map(|x| x.into());
-            for j in 0..SHA256_WORD_U16S {
+            for j in 0..WORD_U16S {
                 let a_limb = compose::<AB::Expr>(&a[j * 16..(j + 1) * 16], 1);
                 let e_limb = compose::<AB::Expr>(&e[j * 16..(j + 1) * 16], 1);
 
                 // If it is a padding row or the last row of a message, the `hash` should be the
-                // [SHA256_H]
+                // [Sha2Config::H]
                 builder
                     .when(
                         next.flags.is_padding_row()
@@ -154,7 +213,7 @@ impl Sha256Air {
                     .assert_eq(
                         a_limb,
                         AB::Expr::from_canonical_u32(
-                            u32_into_limbs::<2>(SHA256_H[SHA256_ROUNDS_PER_ROW - i - 1])[j],
+                            word_into_limbs::<C, WORD_U16S>(C::H[SHA2_ROUNDS_PER_ROW - i - 1])[j],
                         ),
                     );
 
@@ -166,7 +225,7 @@ impl Sha256Air {
                     .assert_eq(
                         e_limb,
                         AB::Expr::from_canonical_u32(
-                            u32_into_limbs::<2>(SHA256_H[SHA256_ROUNDS_PER_ROW - i + 3])[j],
+                            word_into_limbs::<C, WORD_U16S>(C::H[SHA2_ROUNDS_PER_ROW - i + 3])[j],
                         ),
                     );
             }
@@ -174,13 +233,13 @@ impl Sha256Air {
 
         // Check if last row of a non-last block, the `hash` should be equal to the final hash of
         // the current block
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             let prev_a = next.hash.a[i].map(|x| x.into());
             let prev_e = next.hash.e[i].map(|x| x.into());
-            let cur_a = next.final_hash[SHA256_ROUNDS_PER_ROW - i - 1].map(|x| x.into());
+            let cur_a = next.final_hash[SHA2_ROUNDS_PER_ROW - i - 1].map(|x| x.into());
 
-            let cur_e = next.final_hash[SHA256_ROUNDS_PER_ROW - i + 3].map(|x| x.into());
-            for j in 0..SHA256_WORD_U8S {
+            let cur_e = next.final_hash[SHA2_ROUNDS_PER_ROW - i + 3].map(|x| x.into());
+            for j in 0..WORD_U8S {
                 let prev_a_limb = compose::<AB::Expr>(&prev_a[j * 8..(j + 1) * 8], 1);
                 let prev_e_limb = compose::<AB::Expr>(&prev_e[j * 8..(j + 1) * 8], 1);
 
@@ -196,18 +255,18 @@ impl Sha256Air {
 
         // Assert that the previous hash + work vars == final hash.
         // That is, `next.prev_hash[i] + local.work_vars[i] == next.final_hash[i]`
-        // where addition is done modulo 2^32
-        for i in 0..SHA256_HASH_WORDS {
+        // where addition is done modulo 2^WORD_BITS
+        for i in 0..SHA2_HASH_WORDS {
             let mut carry = AB::Expr::ZERO;
-            for j in 0..SHA256_WORD_U16S {
-                let work_var_limb = if i < SHA256_ROUNDS_PER_ROW {
+            for j in 0..WORD_U16S {
+                let work_var_limb = if i < SHA2_ROUNDS_PER_ROW {
                     compose::<AB::Expr>(
-                        &local.work_vars.a[SHA256_ROUNDS_PER_ROW - 1 - i][j * 16..(j + 1) * 16],
+                        &local.work_vars.a[SHA2_ROUNDS_PER_ROW - 1 - i][j * 16..(j + 1) * 16],
                         1,
                     )
                 } else {
                     compose::<AB::Expr>(
-                        &local.work_vars.e[SHA256_ROUNDS_PER_ROW + 3 - i][j * 16..(j + 1) * 16],
+                        &local.work_vars.e[SHA2_ROUNDS_PER_ROW + 3 - i][j * 16..(j + 1) * 16],
                         1,
                     )
                 };
@@ -236,14 +295,14 @@ impl Sha256Air {
         let next = main.row_slice(1);
 
         // Doesn't matter what column structs we use here
-        let local_cols: &Sha256RoundCols<AB::Var> =
-            local[start_col..start_col + SHA256_ROUND_WIDTH].borrow();
-        let next_cols: &Sha256RoundCols<AB::Var> =
-            next[start_col..start_col + SHA256_ROUND_WIDTH].borrow();
+        let local_cols: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> =
+            local[start_col..start_col + Self::ROUND_WIDTH].borrow();
+        let next_cols: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> =
+            next[start_col..start_col + Self::ROUND_WIDTH].borrow();
 
         let local_is_padding_row = local_cols.flags.is_padding_row();
         // Note that there will always be a padding row in the trace since the unpadded height is a
-        // multiple of 17. So the next row is padding iff the current block is the last
+        // multiple of ROWS_PER_BLOCK. So the next row is padding iff the current block is the last
         // block in the trace.
         let next_is_padding_row = next_cols.flags.is_padding_row();
 
@@ -274,24 +333,24 @@ impl Sha256Air {
         // Constrain how much the row index changes by
         // round->round: 1
         // round->digest: 1
-        // digest->round: -16
+        // digest->round: -ROUND_ROWS
         // digest->padding: 1
         // padding->padding: 0
         // Other transitions are not allowed by the above constraints
         let delta = local_cols.flags.is_round_row * AB::Expr::ONE
             + local_cols.flags.is_digest_row
                 * next_cols.flags.is_round_row
-                * AB::Expr::from_canonical_u32(16)
+                * AB::Expr::from_canonical_usize(C::ROUND_ROWS)
                 * AB::Expr::NEG_ONE
             + local_cols.flags.is_digest_row * next_is_padding_row.clone() * AB::Expr::ONE;
 
         let local_row_idx = self.row_idx_encoder.flag_with_val::<AB>(
             &local_cols.flags.row_idx,
-            &(0..18).map(|i| (i, i)).collect::<Vec<_>>(),
+            &(0..=C::ROWS_PER_BLOCK).map(|i| (i, i)).collect::<Vec<_>>(),
         );
         let next_row_idx = self.row_idx_encoder.flag_with_val::<AB>(
             &next_cols.flags.row_idx,
-            &(0..18).map(|i| (i, i)).collect::<Vec<_>>(),
+            &(0..=C::ROWS_PER_BLOCK).map(|i| (i, i)).collect::<Vec<_>>(),
         );
 
         builder
@@ -353,11 +412,11 @@ impl Sha256Air {
 
         self.eval_message_schedule::<AB>(builder, local_cols, next_cols);
         self.eval_work_vars::<AB>(builder, local_cols, next_cols);
-        let next_cols: &Sha256DigestCols<AB::Var> =
-            next[start_col..start_col + SHA256_DIGEST_WIDTH].borrow();
+        let next_cols: &Sha2DigestCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> =
+            next[start_col..start_col + Self::DIGEST_WIDTH].borrow();
         self.eval_digest_row(builder, local_cols, next_cols);
-        let local_cols: &Sha256DigestCols<AB::Var> =
-            local[start_col..start_col + SHA256_DIGEST_WIDTH].borrow();
+        let local_cols: &Sha2DigestCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> =
+            local[start_col..start_col + Self::DIGEST_WIDTH].borrow();
         self.eval_prev_hash::<AB>(builder, local_cols, next_is_padding_row);
     }
 
@@ -366,17 +425,17 @@ impl Sha256Air {
     fn eval_prev_hash<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256DigestCols<AB::Var>,
+        local: &Sha2DigestCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
         is_last_block_of_trace: AB::Expr, /* note this indicates the last block of the trace,
                                            * not the last block of the message */
     ) {
         // Constrain that next block's `prev_hash` is equal to the current block's `hash`
-        let composed_hash: [[<AB as AirBuilder>::Expr; SHA256_WORD_U16S]; SHA256_HASH_WORDS] =
+        let composed_hash: [[<AB as AirBuilder>::Expr; WORD_U16S]; SHA2_HASH_WORDS] =
             array::from_fn(|i| {
-                let hash_bits = if i < SHA256_ROUNDS_PER_ROW {
-                    local.hash.a[SHA256_ROUNDS_PER_ROW - 1 - i].map(|x| x.into())
+                let hash_bits = if i < SHA2_ROUNDS_PER_ROW {
+                    local.hash.a[SHA2_ROUNDS_PER_ROW - 1 - i].map(|x| x.into())
                 } else {
-                    local.hash.e[SHA256_ROUNDS_PER_ROW + 3 - i].map(|x| x.into())
+                    local.hash.e[SHA2_ROUNDS_PER_ROW + 3 - i].map(|x| x.into())
                 };
                 array::from_fn(|j| compose::<AB::Expr>(&hash_bits[j * 16..(j + 1) * 16], 1))
             });
@@ -409,25 +468,25 @@ impl Sha256Air {
     }
 
     /// Constrain the message schedule additions for `next` row
-    /// Note: For every addition we need to constrain the following for each of [SHA256_WORD_U16S]
+    /// Note: For every addition we need to constrain the following for each of `WORD_U16S`
     /// limbs sig_1(w_{t-2})[i] + w_{t-7}[i] + sig_0(w_{t-15})[i] + w_{t-16}[i] +
     /// carry_w[t][i-1] - carry_w[t][i] * 2^16 - w_t[i] == 0 Refer to [https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf]
     fn eval_message_schedule<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256RoundCols<AB::Var>,
-        next: &Sha256RoundCols<AB::Var>,
+        local: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+        next: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
     ) {
         // This `w` array contains 8 message schedule words - w_{idx}, ..., w_{idx+7} for some idx
         let w = [local.message_schedule.w, next.message_schedule.w].concat();
 
         // Constrain `w_3` for `next` row
-        for i in 0..SHA256_ROUNDS_PER_ROW - 1 {
+        for i in 0..SHA2_ROUNDS_PER_ROW - 1 {
             // here we constrain the w_3 of the i_th word of the next row
             // w_3 of next is w[i+4-3] = w[i+1]
             let w_3 = w[i + 1].map(|x| x.into());
             let expected_w_3 = next.schedule_helper.w_3[i];
-            for j in 0..SHA256_WORD_U16S {
+            for j in 0..WORD_U16S {
                 let w_3_limb = compose::<AB::Expr>(&w_3[j * 16..(j + 1) * 16], 1);
                 builder
                     .when(local.flags.is_round_row)
@@ -436,41 +495,41 @@ impl Sha256Air {
         }
 
         // Constrain intermed for `next` row
-        // We will only constrain intermed_12 for rows [3, 14], and let it be unconstrained for
-        // other rows Other rows should put the needed value in intermed_12 to make the
-        // below summation constraint hold
-        let is_row_3_14 = self
+        // We will only constrain intermed_12 for rows [3, ROUND_ROWS - 2], and let it be
+        // unconstrained for other rows Other rows should put the needed value in intermed_12 to
+        // make the below summation constraint hold
+        let is_row_intermed_12 = self
             .row_idx_encoder
-            .contains_flag_range::<AB>(&next.flags.row_idx, 3..=14);
-        // We will only constrain intermed_8 for rows [2, 13], and let it unconstrained for other
-        // rows
-        let is_row_2_13 = self
+            .contains_flag_range::<AB>(&next.flags.row_idx, 3..=C::ROUND_ROWS - 2);
+        // We will only constrain intermed_8 for rows [2, ROUND_ROWS - 3], and let it unconstrained
+        // for other rows
+        let is_row_intermed_8 = self
             .row_idx_encoder
-            .contains_flag_range::<AB>(&next.flags.row_idx, 2..=13);
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+            .contains_flag_range::<AB>(&next.flags.row_idx, 2..=C::ROUND_ROWS - 3);
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             // w_idx
             let w_idx = w[i].map(|x| x.into());
             // sig_0(w_{idx+1})
-            let sig_w = small_sig0_field::<AB::Expr>(&w[i + 1]);
-            for j in 0..SHA256_WORD_U16S {
+            let sig_w = small_sig0_field::<C, AB::Expr, WORD_BITS>(&w[i + 1]);
+            for j in 0..WORD_U16S {
                 let w_idx_limb = compose::<AB::Expr>(&w_idx[j * 16..(j + 1) * 16], 1);
                 let sig_w_limb = compose::<AB::Expr>(&sig_w[j * 16..(j + 1) * 16], 1);
 
-                // We would like to constrain this only on rows 0..16, but we can't do a conditional
+                // We would like to constrain this only on round rows, but we can't do a conditional
                 // check because the degree is already 3. So we must fill in
-                // `intermed_4` with dummy values on rows 0 and 16 to ensure the constraint holds on
-                // these rows.
+                // `intermed_4` with dummy values on row 0 and the digest row to ensure the
+                // constraint holds on these rows.
                 builder.when_transition().assert_eq(
                     next.schedule_helper.intermed_4[i][j],
                     w_idx_limb + sig_w_limb,
                 );
 
-                builder.when(is_row_2_13.clone()).assert_eq(
+                builder.when(is_row_intermed_8.clone()).assert_eq(
                     next.schedule_helper.intermed_8[i][j],
                     local.schedule_helper.intermed_4[i][j],
                 );
 
-                builder.when(is_row_3_14.clone()).assert_eq(
+                builder.when(is_row_intermed_12.clone()).assert_eq(
                     next.schedule_helper.intermed_12[i][j],
                     local.schedule_helper.intermed_8[i][j],
                 );
@@ -478,7 +537,7 @@ impl Sha256Air {
         }
 
         // Constrain the message schedule additions for `next` row
-        for i in 0..SHA256_ROUNDS_PER_ROW {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
             // Note, here by w_{t} we mean the i_th word of the `next` row
             // w_{t-7}
             let w_7 = if i < 3 {
@@ -496,33 +555,33 @@ impl Sha256Air {
             });
 
             // Constrain `W_{idx} = sig_1(W_{idx-2}) + W_{idx-7} + sig_0(W_{idx-15}) + W_{idx-16}`
-            // We would like to constrain this only on rows 4..16, but we can't do a conditional
-            // check because the degree of sum is already 3 So we must fill in
-            // `intermed_12` with dummy values on rows 0..3 and 15 and 16 to ensure the constraint
-            // holds on rows 0..4 and 16. Note that the dummy value goes in the previous
-            // row to make the current row's constraint hold.
+            // We would like to constrain this only on rows 4..ROUND_ROWS, but we can't do a
+            // conditional check because the degree of sum is already 3 So we must fill in
+            // `intermed_12` with dummy values on rows 0..3 and the last round row and the digest
+            // row to ensure the constraint holds on rows 0..4 and the digest row. Note that the
+            // dummy value goes in the previous row to make the current row's constraint hold.
             constraint_word_addition(
                 // Note: here we can't do a conditional check because the degree of sum is already
                 // 3
                 &mut builder.when_transition(),
-                &[&small_sig1_field::<AB::Expr>(&w[i + 2])],
+                &[&small_sig1_field::<C, AB::Expr, WORD_BITS>(&w[i + 2])],
                 &[&w_7, &intermed_16],
                 &w[i + 4],
                 &carries,
             );
 
-            for j in 0..SHA256_WORD_U16S {
-                // When on rows 4..16 message schedule carries should be 0 or 1
-                let is_row_4_15 = next.flags.is_round_row - next.flags.is_first_4_rows;
+            for j in 0..WORD_U16S {
+                // When on rows 4..ROUND_ROWS message schedule carries should be 0 or 1
+                let is_row_4_last_round = next.flags.is_round_row - next.flags.is_first_4_rows;
                 builder
-                    .when(is_row_4_15.clone())
+                    .when(is_row_4_last_round.clone())
                     .assert_bool(next.message_schedule.carry_or_buffer[i][j * 2]);
                 builder
-                    .when(is_row_4_15)
+                    .when(is_row_4_last_round)
                     .assert_bool(next.message_schedule.carry_or_buffer[i][j * 2 + 1]);
             }
             // Constrain w being composed of bits
-            for j in 0..SHA256_WORD_BITS {
+            for j in 0..WORD_BITS {
                 builder
                     .when(next.flags.is_round_row)
                     .assert_bool(next.message_schedule.w[i][j]);
@@ -530,18 +589,18 @@ impl Sha256Air {
         }
     }
 
-    /// Constrain the work vars on `next` row according to the sha256 documentation
+    /// Constrain the work vars on `next` row according to the SHA-2 documentation
     /// Refer to [https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf]
     fn eval_work_vars<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256RoundCols<AB::Var>,
-        next: &Sha256RoundCols<AB::Var>,
+        local: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+        next: &Sha2RoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
     ) {
         let a = [local.work_vars.a, next.work_vars.a].concat();
         let e = [local.work_vars.e, next.work_vars.e].concat();
-        for i in 0..SHA256_ROUNDS_PER_ROW {
-            for j in 0..SHA256_WORD_U16S {
+        for i in 0..SHA2_ROUNDS_PER_ROW {
+            for j in 0..WORD_U16S {
                 // Although we need carry_a <= 6 and carry_e <= 5, constraining carry_a, carry_e in
                 // [0, 2^8) is enough to prevent overflow and ensure the soundness
                 // of the addition we want to check
@@ -557,12 +616,12 @@ impl Sha256Air {
             let k_limbs = array::from_fn(|j| {
                 self.row_idx_encoder.flag_with_val::<AB>(
                     &next.flags.row_idx,
-                    &(0..16)
+                    &(0..C::ROUND_ROWS)
                         .map(|rw_idx| {
                             (
                                 rw_idx,
-                                u32_into_limbs::<SHA256_WORD_U16S>(
-                                    SHA256_K[rw_idx * SHA256_ROUNDS_PER_ROW + i],
+                                word_into_limbs::<C, WORD_U16S>(
+                                    C::K[rw_idx * SHA2_ROUNDS_PER_ROW + i],
                                 )[j] as usize,
                             )
                         })
@@ -577,13 +636,16 @@ impl Sha256Air {
             constraint_word_addition(
                 builder,
                 &[
-                    &e[i].map(|x| x.into()),                // previous `h`
-                    &big_sig1_field::<AB::Expr>(&e[i + 3]), // sig_1 of previous `e`
-                    &ch_field::<AB::Expr>(&e[i + 3], &e[i + 2], &e[i + 1]), /* Ch of previous
-                                                             * `e`, `f`, `g` */
-                    &big_sig0_field::<AB::Expr>(&a[i + 3]), // sig_0 of previous `a`
-                    &maj_field::<AB::Expr>(&a[i + 3], &a[i + 2], &a[i + 1]), /* Maj of previous
-                                                             * a, b, c */
+                    // previous `h`
+                    &e[i].map(|x| x.into()),
+                    // sig_1 of previous `e`
+                    &big_sig1_field::<C, AB::Expr, WORD_BITS>(&e[i + 3]),
+                    // Ch of previous `e`, `f`, `g`
+                    &ch_field::<AB::Expr, WORD_BITS>(&e[i + 3], &e[i + 2], &e[i + 1]),
+                    // sig_0 of previous `a`
+                    &big_sig0_field::<C, AB::Expr, WORD_BITS>(&a[i + 3]),
+                    // Maj of previous a, b, c
+                    &maj_field::<AB::Expr, WORD_BITS>(&a[i + 3], &a[i + 2], &a[i + 1]),
                 ],
                 &[&w_limbs, &k_limbs],      // K and W
                 &a[i + 4],                  // new `a`
@@ -597,12 +659,14 @@ impl Sha256Air {
             constraint_word_addition(
                 builder,
                 &[
-                    &a[i].map(|x| x.into()), // previous `d`
-                    &e[i].map(|x| x.into()), // previous `h`
-                    &big_sig1_field::<AB::Expr>(&e[i + 3]), /* sig_1 of previous
-                                              * `e` */
-                    &ch_field::<AB::Expr>(&e[i + 3], &e[i + 2], &e[i + 1]), /* Ch of previous
-                                                                             * `e`, `f`, `g` */
+                    // previous `d`
+                    &a[i].map(|x| x.into()),
+                    // previous `h`
+                    &e[i].map(|x| x.into()),
+                    // sig_1 of previous `e`
+                    &big_sig1_field::<C, AB::Expr, WORD_BITS>(&e[i + 3]),
+                    // Ch of previous `e`, `f`, `g`
+                    &ch_field::<AB::Expr, WORD_BITS>(&e[i + 3], &e[i + 2], &e[i + 1]),
                 ],
                 &[&w_limbs, &k_limbs],      // K and W
                 &e[i + 4],                  // new `e`
@@ -1,4 +1,5 @@
-//! Implementation of the SHA256 compression function without padding
+//! Implementation of the SHA-2 family (SHA256, SHA512 and SHA384) compression functions without
+//! padding
 //! This this AIR doesn't constrain any of the message padding
 
 mod air;
@@ -13,6 +13,7 @@ use openvm_stark_backend::{
     interaction::{BusIndex, InteractionBuilder},
     p3_air::{Air, BaseAir},
     p3_field::{Field, FieldAlgebra, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
     p3_maybe_rayon::prelude::{IndexedParallelIterator, ParallelIterator, ParallelSliceMut},
     prover::types::AirProofInput,
     rap::{get_air_name, BaseAirWithPublicValues, PartitionedBaseAir},
@@ -22,39 +23,103 @@ use openvm_stark_sdk::utils::create_seeded_rng;
 use rand::Rng;
 
 use crate::{
-    compose, small_sig0_field, Sha256Air, Sha256RoundCols, SHA256_BLOCK_U8S, SHA256_DIGEST_WIDTH,
-    SHA256_HASH_WORDS, SHA256_ROUNDS_PER_ROW, SHA256_ROUND_WIDTH, SHA256_ROWS_PER_BLOCK,
-    SHA256_WORD_U16S, SHA256_WORD_U8S,
+    compose, small_sig0_field, Sha256Air, Sha256Config, Sha256RoundCols, Sha2Air, Sha2Config,
+    Sha384Air, Sha512Air, SHA256_BLOCK_U8S, SHA256_DIGEST_WIDTH, SHA256_HASH_WORDS,
+    SHA256_ROUNDS_PER_ROW, SHA256_ROUND_WIDTH, SHA256_ROWS_PER_BLOCK, SHA256_ROW_VAR_CNT,
+    SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S,
 };
 
 // A wrapper AIR purely for testing purposes
 #[derive(Clone, Debug)]
-pub struct Sha256TestAir {
-    pub sub_air: Sha256Air,
+pub struct Sha2TestAir<
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+> {
+    pub sub_air: Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
 }
 
-impl<F: Field> BaseAirWithPublicValues<F> for Sha256TestAir {}
-impl<F: Field> PartitionedBaseAir<F> for Sha256TestAir {}
-impl<F: Field> BaseAir<F> for Sha256TestAir {
+pub type Sha256TestAir = Sha2TestAir<
+    Sha256Config,
+    SHA256_WORD_BITS,
+    SHA256_WORD_U8S,
+    SHA256_WORD_U16S,
+    SHA256_ROW_VAR_CNT,
+>;
+
+impl<
+        F: Field,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > BaseAirWithPublicValues<F> for Sha2TestAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
+}
+impl<
+        F: Field,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > PartitionedBaseAir<F> for Sha2TestAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
+}
+impl<
+        F: Field,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > BaseAir<F> for Sha2TestAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     fn width(&self) -> usize {
-        <Sha256Air as BaseAir<F>>::width(&self.sub_air)
+        <Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> as BaseAir<F>>::width(
+            &self.sub_air,
+        )
     }
 }
 
-impl<AB: InteractionBuilder> Air<AB> for Sha256TestAir {
+impl<
+        AB: InteractionBuilder,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > Air<AB> for Sha2TestAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     fn eval(&self, builder: &mut AB) {
         self.sub_air.eval(builder, 0);
     }
 }
 
 // A wrapper Chip purely for testing purposes
-pub struct Sha256TestChip {
-    pub air: Sha256TestAir,
+pub struct Sha2TestChip<
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+> {
+    pub air: Sha2TestAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
     pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
-    pub records: Vec<([u8; SHA256_BLOCK_U8S], bool)>,
+    pub records: Vec<(Vec<u8>, bool)>,
 }
 
-impl<SC: StarkGenericConfig> Chip<SC> for Sha256TestChip
+impl<
+        SC: StarkGenericConfig,
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > Chip<SC> for Sha2TestChip<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
 where
     Val<SC>: PrimeField32,
 {
@@ -63,7 +128,7 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        let trace = crate::generate_trace::<Val<SC>>(
+        let trace: RowMajorMatrix<Val<SC>> = crate::generate_trace(
             &self.air.sub_air,
             self.bitwise_lookup_chip.clone(),
             self.records,
@@ -72,39 +137,54 @@ where
     }
 }
 
-impl ChipUsageGetter for Sha256TestChip {
+impl<
+        C: Sha2Config,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+    > ChipUsageGetter for Sha2TestChip<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>
+{
     fn air_name(&self) -> String {
         get_air_name(&self.air)
     }
     fn current_trace_height(&self) -> usize {
-        self.records.len() * SHA256_ROWS_PER_BLOCK
+        self.records.len() * C::ROWS_PER_BLOCK
     }
 
     fn trace_width(&self) -> usize {
-        max(SHA256_ROUND_WIDTH, SHA256_DIGEST_WIDTH)
+        <Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT> as BaseAir<u8>>::width(
+            &self.air.sub_air,
+        )
     }
 }
 
 const SELF_BUS_IDX: BusIndex = 28;
-#[test]
-fn rand_sha256_test() {
+
+fn rand_sha2_test<
+    C: Sha2Config,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+>(
+    sub_air: Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+) {
     let mut rng = create_seeded_rng();
     let tester = VmChipTestBuilder::default();
-    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
-    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let bitwise_chip =
+        SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(sub_air.bitwise_lookup_bus);
     let len = rng.gen_range(1..100);
     let random_records: Vec<_> = (0..len)
         .map(|i| {
             (
-                array::from_fn(|_| rng.gen::<u8>()),
+                (0..C::BLOCK_U8S).map(|_| rng.gen::<u8>()).collect(),
                 rng.gen::<bool>() || i == len - 1,
             )
         })
         .collect();
-    let chip = Sha256TestChip {
-        air: Sha256TestAir {
-            sub_air: Sha256Air::new(bitwise_bus, SELF_BUS_IDX),
-        },
+    let chip = Sha2TestChip {
+        air: Sha2TestAir { sub_air },
         bitwise_lookup_chip: bitwise_chip.clone(),
         records: random_records,
     };
@@ -113,12 +193,30 @@ fn rand_sha256_test() {
     tester.simple_test().expect("Verification failed");
 }
 
+#[test]
+fn rand_sha256_test() {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    rand_sha2_test(Sha256Air::new(bitwise_bus, SELF_BUS_IDX));
+}
+
+#[test]
+fn rand_sha512_test() {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    rand_sha2_test(Sha512Air::new(bitwise_bus, SELF_BUS_IDX));
+}
+
+#[test]
+fn rand_sha384_test() {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    rand_sha2_test(Sha384Air::new(bitwise_bus, SELF_BUS_IDX));
+}
+
 // A wrapper Chip to test that the final_hash is properly constrained.
 // This chip implements a malicious trace gen that violates the final_hash constraints.
 pub struct Sha256TestBadFinalHashChip {
     pub air: Sha256TestAir,
     pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
-    pub records: Vec<([u8; SHA256_BLOCK_U8S], bool)>,
+    pub records: Vec<(Vec<u8>, bool)>,
 }
 
 impl<SC: StarkGenericConfig> Chip<SC> for Sha256TestBadFinalHashChip
@@ -130,7 +228,7 @@ where
     }
 
     fn generate_air_proof_input(self) -> AirProofInput<SC> {
-        let mut trace = crate::generate_trace::<Val<SC>>(
+        let mut trace: RowMajorMatrix<Val<SC>> = crate::generate_trace(
             &self.air.sub_air,
             self.bitwise_lookup_chip.clone(),
             self.records.clone(),
@@ -187,7 +285,7 @@ fn generate_intermed_4<F: PrimeField32>(
         .map(|x| array::from_fn(|i| compose::<F>(&x[i * 16..(i + 1) * 16], 1)))
         .collect();
     for i in 0..SHA256_ROUNDS_PER_ROW {
-        let sig_w = small_sig0_field::<F>(&w[i + 1]);
+        let sig_w = small_sig0_field::<Sha256Config, F, SHA256_WORD_BITS>(&w[i + 1]);
         let sig_w_limbs: [F; SHA256_WORD_U16S] =
             array::from_fn(|j| compose::<F>(&sig_w[j * 16..(j + 1) * 16], 1));
         for (j, sig_w_limb) in sig_w_limbs.iter().enumerate() {
@@ -218,7 +316,12 @@ fn test_sha256_final_hash_constraints() {
     let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
     let len = rng.gen_range(1..100);
     let random_records: Vec<_> = (0..len)
-        .map(|_| (array::from_fn(|_| rng.gen::<u8>()), true))
+        .map(|_| {
+            (
+                (0..SHA256_BLOCK_U8S).map(|_| rng.gen::<u8>()).collect(),
+                true,
+            )
+        })
         .collect();
     let chip = Sha256TestBadFinalHashChip {
         air: Sha256TestAir {
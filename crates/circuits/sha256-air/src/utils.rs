@@ -1,4 +1,4 @@
-use std::array;
+use std::{array, fmt::Debug};
 
 pub use openvm_circuit_primitives::utils::compose;
 use openvm_circuit_primitives::{
@@ -7,10 +7,20 @@ use openvm_circuit_primitives::{
 };
 use openvm_stark_backend::{p3_air::AirBuilder, p3_field::FieldAlgebra};
 use rand::{rngs::StdRng, Rng};
+use sha2::{compress256, compress512, digest::generic_array::GenericArray};
 
-use super::{Sha256DigestCols, Sha256RoundCols};
+use super::{Sha256DigestCols, Sha256RoundCols, Sha512DigestCols, Sha512RoundCols};
 
 // ==== Do not change these constants! ====
+/// Number of words in a SHA-2 block
+pub const SHA2_BLOCK_WORDS: usize = 16;
+/// Number of rounds per row
+pub const SHA2_ROUNDS_PER_ROW: usize = 4;
+/// Number of words in a SHA-2 hash
+pub const SHA2_HASH_WORDS: usize = 8;
+/// Number of rows at the start of each block which hold the message words
+pub const SHA2_MESSAGE_ROWS: usize = SHA2_BLOCK_WORDS / SHA2_ROUNDS_PER_ROW;
+
 /// Number of bits in a SHA256 word
 pub const SHA256_WORD_BITS: usize = 32;
 /// Number of 16-bit limbs in a SHA256 word
@@ -18,7 +28,7 @@ pub const SHA256_WORD_U16S: usize = SHA256_WORD_BITS / 16;
 /// Number of 8-bit limbs in a SHA256 word
 pub const SHA256_WORD_U8S: usize = SHA256_WORD_BITS / 8;
 /// Number of words in a SHA256 block
-pub const SHA256_BLOCK_WORDS: usize = 16;
+pub const SHA256_BLOCK_WORDS: usize = SHA2_BLOCK_WORDS;
 /// Number of cells in a SHA256 block
 pub const SHA256_BLOCK_U8S: usize = SHA256_BLOCK_WORDS * SHA256_WORD_U8S;
 /// Number of bits in a SHA256 block
@@ -26,9 +36,9 @@ pub const SHA256_BLOCK_BITS: usize = SHA256_BLOCK_WORDS * SHA256_WORD_BITS;
 /// Number of rows per block
 pub const SHA256_ROWS_PER_BLOCK: usize = 17;
 /// Number of rounds per row
-pub const SHA256_ROUNDS_PER_ROW: usize = 4;
+pub const SHA256_ROUNDS_PER_ROW: usize = SHA2_ROUNDS_PER_ROW;
 /// Number of words in a SHA256 hash
-pub const SHA256_HASH_WORDS: usize = 8;
+pub const SHA256_HASH_WORDS: usize = SHA2_HASH_WORDS;
 /// Number of vars needed to encode the row index with [Encoder]
 pub const SHA256_ROW_VAR_CNT: usize = 5;
 /// Width of the Sha256RoundCols
@@ -43,22 +53,178 @@ pub const SHA256_WIDTH: usize = if SHA256_ROUND_WIDTH > SHA256_DIGEST_WIDTH {
 } else {
     SHA256_DIGEST_WIDTH
 };
-/// We can notice that `carry_a`'s and `carry_e`'s are always the same on invalid rows
-/// To optimize the trace generation of invalid rows, we have those values precomputed here
-pub(crate) const SHA256_INVALID_CARRY_A: [[u32; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW] = [
-    [1230919683, 1162494304],
-    [266373122, 1282901987],
-    [1519718403, 1008990871],
-    [923381762, 330807052],
-];
-pub(crate) const SHA256_INVALID_CARRY_E: [[u32; SHA256_WORD_U16S]; SHA256_ROUNDS_PER_ROW] = [
-    [204933122, 1994683449],
-    [443873282, 1544639095],
-    [719953922, 1888246508],
-    [194580482, 1075725211],
-];
+
+/// Number of bits in a SHA512 word
+pub const SHA512_WORD_BITS: usize = 64;
+/// Number of 16-bit limbs in a SHA512 word
+pub const SHA512_WORD_U16S: usize = SHA512_WORD_BITS / 16;
+/// Number of 8-bit limbs in a SHA512 word
+pub const SHA512_WORD_U8S: usize = SHA512_WORD_BITS / 8;
+/// Number of words in a SHA512 block
+pub const SHA512_BLOCK_WORDS: usize = SHA2_BLOCK_WORDS;
+/// Number of cells in a SHA512 block
+pub const SHA512_BLOCK_U8S: usize = SHA512_BLOCK_WORDS * SHA512_WORD_U8S;
+/// Number of bits in a SHA512 block
+pub const SHA512_BLOCK_BITS: usize = SHA512_BLOCK_WORDS * SHA512_WORD_BITS;
+/// Number of rows per block
+pub const SHA512_ROWS_PER_BLOCK: usize = 21;
+/// Number of vars needed to encode the row index with [Encoder]
+pub const SHA512_ROW_VAR_CNT: usize = 6;
+/// Width of the Sha512RoundCols
+pub const SHA512_ROUND_WIDTH: usize = Sha512RoundCols::<u8>::width();
+/// Width of the Sha512DigestCols
+pub const SHA512_DIGEST_WIDTH: usize = Sha512DigestCols::<u8>::width();
+/// Size of the buffer of the first 4 rows of a block (each row's size)
+pub const SHA512_BUFFER_SIZE: usize = SHA2_ROUNDS_PER_ROW * SHA512_WORD_U16S * 2;
+/// Width of the Sha512Cols
+pub const SHA512_WIDTH: usize = if SHA512_ROUND_WIDTH > SHA512_DIGEST_WIDTH {
+    SHA512_ROUND_WIDTH
+} else {
+    SHA512_DIGEST_WIDTH
+};
+
+/// The parameters of a function of the SHA-2 family.
+///
+/// The column layout of [crate::Sha2Air] only depends on [Self::WORD_BITS], and is passed to it
+/// as const generics next to the config, see [crate::Sha256Air] and [crate::Sha512Air].
+pub trait Sha2Config: Clone + Copy + Debug + Send + Sync + 'static {
+    /// Number of bits in a word
+    const WORD_BITS: usize;
+    /// Number of rounds of the compression function
+    const ROUNDS_PER_BLOCK: usize;
+    /// Number of bytes of the digest, which is a prefix of the final hash
+    const DIGEST_BYTES: usize;
+    /// Round constants
+    const K: &'static [u64];
+    /// Initial hash values
+    const H: [u64; SHA2_HASH_WORDS];
+    /// Rotation amounts of Σ0 and Σ1
+    const BIG_SIG0_ROT: [usize; 3];
+    const BIG_SIG1_ROT: [usize; 3];
+    /// Rotation amounts followed by the shift amount of σ0 and σ1
+    const SMALL_SIG0_ROT_SHR: [usize; 3];
+    const SMALL_SIG1_ROT_SHR: [usize; 3];
+    /// We can notice that `carry_a`'s and `carry_e`'s are always the same on invalid rows
+    /// To optimize the trace generation of invalid rows, we have those values precomputed here
+    const INVALID_CARRY_A: [&'static [u32]; SHA2_ROUNDS_PER_ROW];
+    const INVALID_CARRY_E: [&'static [u32]; SHA2_ROUNDS_PER_ROW];
+
+    /// Number of 8-bit limbs in a word
+    const WORD_U8S: usize = Self::WORD_BITS / 8;
+    /// Number of 16-bit limbs in a word
+    const WORD_U16S: usize = Self::WORD_BITS / 16;
+    /// Number of cells in a block
+    const BLOCK_U8S: usize = SHA2_BLOCK_WORDS * Self::WORD_U8S;
+    /// Number of bits in a block
+    const BLOCK_BITS: usize = SHA2_BLOCK_WORDS * Self::WORD_BITS;
+    /// Number of rows doing the rounds of a block
+    const ROUND_ROWS: usize = Self::ROUNDS_PER_BLOCK / SHA2_ROUNDS_PER_ROW;
+    /// Number of rows per block: the round rows followed by a digest row
+    const ROWS_PER_BLOCK: usize = Self::ROUND_ROWS + 1;
+
+    /// Updates `hash` by the compression function on `block`, which must be [Self::BLOCK_U8S]
+    /// bytes long
+    fn compress(hash: &mut [u64; SHA2_HASH_WORDS], block: &[u8]);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sha256Config;
+
+impl Sha2Config for Sha256Config {
+    const WORD_BITS: usize = SHA256_WORD_BITS;
+    const ROUNDS_PER_BLOCK: usize = 64;
+    const DIGEST_BYTES: usize = 32;
+    const K: &'static [u64] = &SHA256_K;
+    const H: [u64; SHA2_HASH_WORDS] = SHA256_H;
+    const BIG_SIG0_ROT: [usize; 3] = [2, 13, 22];
+    const BIG_SIG1_ROT: [usize; 3] = [6, 11, 25];
+    const SMALL_SIG0_ROT_SHR: [usize; 3] = [7, 18, 3];
+    const SMALL_SIG1_ROT_SHR: [usize; 3] = [17, 19, 10];
+    const INVALID_CARRY_A: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[1230919683, 1162494304],
+        &[266373122, 1282901987],
+        &[1519718403, 1008990871],
+        &[923381762, 330807052],
+    ];
+    const INVALID_CARRY_E: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[204933122, 1994683449],
+        &[443873282, 1544639095],
+        &[719953922, 1888246508],
+        &[194580482, 1075725211],
+    ];
+
+    fn compress(hash: &mut [u64; SHA2_HASH_WORDS], block: &[u8]) {
+        let mut state = hash.map(|x| x as u32);
+        compress256(&mut state, &[*GenericArray::from_slice(block)]);
+        *hash = state.map(u64::from);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sha512Config;
+
+impl Sha2Config for Sha512Config {
+    const WORD_BITS: usize = SHA512_WORD_BITS;
+    const ROUNDS_PER_BLOCK: usize = 80;
+    const DIGEST_BYTES: usize = 64;
+    const K: &'static [u64] = &SHA512_K;
+    const H: [u64; SHA2_HASH_WORDS] = SHA512_H;
+    const BIG_SIG0_ROT: [usize; 3] = [28, 34, 39];
+    const BIG_SIG1_ROT: [usize; 3] = [14, 18, 41];
+    const SMALL_SIG0_ROT_SHR: [usize; 3] = [1, 8, 7];
+    const SMALL_SIG1_ROT_SHR: [usize; 3] = [19, 61, 6];
+    const INVALID_CARRY_A: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[55971842, 827997017, 993005918, 512731953],
+        &[227512322, 1697529235, 1936430385, 940122990],
+        &[1939875843, 1173318562, 826201586, 1513494849],
+        &[891955202, 1732283693, 1736658755, 223514501],
+    ];
+    const INVALID_CARRY_E: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[1384427522, 1509509767, 153131516, 102514978],
+        &[1527552003, 1041677071, 837289497, 843522538],
+        &[775188482, 1620184630, 744892564, 892058728],
+        &[1801267202, 1393118048, 1846108940, 830635531],
+    ];
+
+    fn compress(hash: &mut [u64; SHA2_HASH_WORDS], block: &[u8]) {
+        compress512(hash, &[*GenericArray::from_slice(block)]);
+    }
+}
+
+/// SHA384 is SHA512 with different initial hash values and a truncated digest
+#[derive(Clone, Copy, Debug)]
+pub struct Sha384Config;
+
+impl Sha2Config for Sha384Config {
+    const WORD_BITS: usize = SHA512_WORD_BITS;
+    const ROUNDS_PER_BLOCK: usize = Sha512Config::ROUNDS_PER_BLOCK;
+    const DIGEST_BYTES: usize = 48;
+    const K: &'static [u64] = Sha512Config::K;
+    const H: [u64; SHA2_HASH_WORDS] = SHA384_H;
+    const BIG_SIG0_ROT: [usize; 3] = Sha512Config::BIG_SIG0_ROT;
+    const BIG_SIG1_ROT: [usize; 3] = Sha512Config::BIG_SIG1_ROT;
+    const SMALL_SIG0_ROT_SHR: [usize; 3] = Sha512Config::SMALL_SIG0_ROT_SHR;
+    const SMALL_SIG1_ROT_SHR: [usize; 3] = Sha512Config::SMALL_SIG1_ROT_SHR;
+    const INVALID_CARRY_A: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[1571481603, 1428841901, 1050676523, 793575075],
+        &[1233315842, 1822329223, 112923808, 1874228927],
+        &[1245603842, 927240770, 1579759431, 70557227],
+        &[195532801, 594312107, 1429379950, 220407092],
+    ];
+    const INVALID_CARRY_E: [&'static [u32]; SHA2_ROUNDS_PER_ROW] = [
+        &[1067980802, 1508061099, 1418826213, 1232569491],
+        &[1453086722, 1702524575, 152427899, 238512408],
+        &[1623674882, 701393097, 1002035664, 4776891],
+        &[1888911362, 184963225, 1151849224, 1034237098],
+    ];
+
+    fn compress(hash: &mut [u64; SHA2_HASH_WORDS], block: &[u8]) {
+        Sha512Config::compress(hash, block);
+    }
+}
+
 /// SHA256 constant K's
-pub const SHA256_K: [u32; 64] = [
+pub const SHA256_K: [u64; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
     0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
@@ -70,42 +236,165 @@ pub const SHA256_K: [u32; 64] = [
 ];
 
 /// SHA256 initial hash values
-pub const SHA256_H: [u32; 8] = [
+pub const SHA256_H: [u64; 8] = [
     0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
-/// Convert a u32 into a list of limbs in little endian
-pub fn u32_into_limbs<const NUM_LIMBS: usize>(num: u32) -> [u32; NUM_LIMBS] {
-    let limb_bits = 32 / NUM_LIMBS;
-    array::from_fn(|i| (num >> (limb_bits * i)) & ((1 << limb_bits) - 1))
+/// SHA512 constant K's
+pub const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// SHA512 initial hash values
+pub const SHA512_H: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// SHA384 initial hash values
+pub const SHA384_H: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// Convert a word into a list of limbs in little endian
+pub fn word_into_limbs<C: Sha2Config, const NUM_LIMBS: usize>(num: u64) -> [u32; NUM_LIMBS] {
+    let limb_bits = C::WORD_BITS / NUM_LIMBS;
+    array::from_fn(|i| ((num >> (limb_bits * i)) & ((1 << limb_bits) - 1)) as u32)
 }
 
-/// Convert a list of limbs in little endian into a u32
-pub fn limbs_into_u32<const NUM_LIMBS: usize>(limbs: [u32; NUM_LIMBS]) -> u32 {
-    let limb_bits = 32 / NUM_LIMBS;
+/// Convert a list of limbs in little endian into a word
+pub fn limbs_into_word<C: Sha2Config, const NUM_LIMBS: usize>(limbs: [u32; NUM_LIMBS]) -> u64 {
+    let limb_bits = C::WORD_BITS / NUM_LIMBS;
     limbs
         .iter()
         .rev()
-        .fold(0, |acc, &limb| (acc << limb_bits) | limb)
+        .fold(0, |acc, &limb| (acc << limb_bits) | limb as u64)
+}
+
+/// Adds words modulo 2^[Sha2Config::WORD_BITS]
+pub fn add_words<C: Sha2Config>(words: &[u64]) -> u64 {
+    words.iter().fold(0, |acc: u64, &x| acc.wrapping_add(x)) & word_mask::<C>()
+}
+
+#[inline]
+fn word_mask<C: Sha2Config>() -> u64 {
+    u64::MAX >> (64 - C::WORD_BITS)
+}
+
+#[inline]
+fn rotr_word<C: Sha2Config>(x: u64, n: usize) -> u64 {
+    ((x >> n) | (x << (C::WORD_BITS - n))) & word_mask::<C>()
 }
 
 /// Rotates `bits` right by `n` bits, assumes `bits` is in little-endian
 #[inline]
-pub(crate) fn rotr<F: FieldAlgebra + Clone>(
-    bits: &[impl Into<F> + Clone; SHA256_WORD_BITS],
+pub(crate) fn rotr<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    bits: &[impl Into<F> + Clone; WORD_BITS],
     n: usize,
-) -> [F; SHA256_WORD_BITS] {
-    array::from_fn(|i| bits[(i + n) % SHA256_WORD_BITS].clone().into())
+) -> [F; WORD_BITS] {
+    array::from_fn(|i| bits[(i + n) % WORD_BITS].clone().into())
 }
 
 /// Shifts `bits` right by `n` bits, assumes `bits` is in little-endian
 #[inline]
-pub(crate) fn shr<F: FieldAlgebra + Clone>(
-    bits: &[impl Into<F> + Clone; SHA256_WORD_BITS],
+pub(crate) fn shr<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    bits: &[impl Into<F> + Clone; WORD_BITS],
     n: usize,
-) -> [F; SHA256_WORD_BITS] {
+) -> [F; WORD_BITS] {
     array::from_fn(|i| {
-        if i + n < SHA256_WORD_BITS {
+        if i + n < WORD_BITS {
             bits[i + n].clone().into()
         } else {
             F::ZERO
@@ -127,44 +416,44 @@ pub(crate) fn xor_bit<F: FieldAlgebra + Clone>(
         + (not::<F>(x) * not::<F>(y) * z)
 }
 
-/// Computes x ^ y ^ z, where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes x ^ y ^ z, where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn xor<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn xor<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| xor_bit(x[i].clone(), y[i].clone(), z[i].clone()))
 }
 
-/// Choose function from SHA256
+/// Choose function from SHA-2, the inputs are assumed to fit in a word
 #[inline]
-pub fn ch(x: u32, y: u32, z: u32) -> u32 {
+pub fn ch(x: u64, y: u64, z: u64) -> u64 {
     (x & y) ^ ((!x) & z)
 }
 
-/// Computes Ch(x,y,z), where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes Ch(x,y,z), where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn ch_field<F: FieldAlgebra>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn ch_field<F: FieldAlgebra, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| select(x[i].clone(), y[i].clone(), z[i].clone()))
 }
 
-/// Majority function from SHA256
-pub fn maj(x: u32, y: u32, z: u32) -> u32 {
+/// Majority function from SHA-2
+pub fn maj(x: u64, y: u64, z: u64) -> u64 {
     (x & y) ^ (x & z) ^ (y & z)
 }
 
-/// Computes Maj(x,y,z), where x, y, z are [SHA256_WORD_BITS] bit numbers
+/// Computes Maj(x,y,z), where x, y, z are `WORD_BITS` bit numbers
 #[inline]
-pub(crate) fn maj_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    y: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-    z: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
+pub(crate) fn maj_field<F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+    y: &[impl Into<F> + Clone; WORD_BITS],
+    z: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
     array::from_fn(|i| {
         let (x, y, z) = (
             x[i].clone().into(),
@@ -175,56 +464,80 @@ pub(crate) fn maj_field<F: FieldAlgebra + Clone>(
     })
 }
 
-/// Big sigma_0 function from SHA256
-pub fn big_sig0(x: u32) -> u32 {
-    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+/// Big sigma_0 function from SHA-2
+pub fn big_sig0<C: Sha2Config>(x: u64) -> u64 {
+    let [r0, r1, r2] = C::BIG_SIG0_ROT;
+    rotr_word::<C>(x, r0) ^ rotr_word::<C>(x, r1) ^ rotr_word::<C>(x, r2)
 }
 
-/// Computes BigSigma0(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes BigSigma0(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn big_sig0_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 2), &rotr::<F>(x, 13), &rotr::<F>(x, 22))
+pub(crate) fn big_sig0_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    let [r0, r1, r2] = C::BIG_SIG0_ROT;
+    xor(
+        &rotr::<F, WORD_BITS>(x, r0),
+        &rotr::<F, WORD_BITS>(x, r1),
+        &rotr::<F, WORD_BITS>(x, r2),
+    )
 }
 
-/// Big sigma_1 function from SHA256
-pub fn big_sig1(x: u32) -> u32 {
-    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+/// Big sigma_1 function from SHA-2
+pub fn big_sig1<C: Sha2Config>(x: u64) -> u64 {
+    let [r0, r1, r2] = C::BIG_SIG1_ROT;
+    rotr_word::<C>(x, r0) ^ rotr_word::<C>(x, r1) ^ rotr_word::<C>(x, r2)
 }
 
-/// Computes BigSigma1(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes BigSigma1(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn big_sig1_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 6), &rotr::<F>(x, 11), &rotr::<F>(x, 25))
+pub(crate) fn big_sig1_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    let [r0, r1, r2] = C::BIG_SIG1_ROT;
+    xor(
+        &rotr::<F, WORD_BITS>(x, r0),
+        &rotr::<F, WORD_BITS>(x, r1),
+        &rotr::<F, WORD_BITS>(x, r2),
+    )
 }
 
-/// Small sigma_0 function from SHA256
-pub fn small_sig0(x: u32) -> u32 {
-    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+/// Small sigma_0 function from SHA-2
+pub fn small_sig0<C: Sha2Config>(x: u64) -> u64 {
+    let [r0, r1, s] = C::SMALL_SIG0_ROT_SHR;
+    rotr_word::<C>(x, r0) ^ rotr_word::<C>(x, r1) ^ (x >> s)
 }
 
-/// Computes SmallSigma0(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes SmallSigma0(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn small_sig0_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 7), &rotr::<F>(x, 18), &shr::<F>(x, 3))
+pub(crate) fn small_sig0_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    let [r0, r1, s] = C::SMALL_SIG0_ROT_SHR;
+    xor(
+        &rotr::<F, WORD_BITS>(x, r0),
+        &rotr::<F, WORD_BITS>(x, r1),
+        &shr::<F, WORD_BITS>(x, s),
+    )
 }
 
-/// Small sigma_1 function from SHA256
-pub fn small_sig1(x: u32) -> u32 {
-    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+/// Small sigma_1 function from SHA-2
+pub fn small_sig1<C: Sha2Config>(x: u64) -> u64 {
+    let [r0, r1, s] = C::SMALL_SIG1_ROT_SHR;
+    rotr_word::<C>(x, r0) ^ rotr_word::<C>(x, r1) ^ (x >> s)
 }
 
-/// Computes SmallSigma1(x), where x is a [SHA256_WORD_BITS] bit number in little-endian
+/// Computes SmallSigma1(x), where x is a `WORD_BITS` bit number in little-endian
 #[inline]
-pub(crate) fn small_sig1_field<F: FieldAlgebra + Clone>(
-    x: &[impl Into<F> + Clone; SHA256_WORD_BITS],
-) -> [F; SHA256_WORD_BITS] {
-    xor(&rotr::<F>(x, 17), &rotr::<F>(x, 19), &shr::<F>(x, 10))
+pub(crate) fn small_sig1_field<C: Sha2Config, F: FieldAlgebra + Clone, const WORD_BITS: usize>(
+    x: &[impl Into<F> + Clone; WORD_BITS],
+) -> [F; WORD_BITS] {
+    let [r0, r1, s] = C::SMALL_SIG1_ROT_SHR;
+    xor(
+        &rotr::<F, WORD_BITS>(x, r0),
+        &rotr::<F, WORD_BITS>(x, r1),
+        &shr::<F, WORD_BITS>(x, s),
+    )
 }
 
 /// Generate a random message of a given length
@@ -239,17 +552,17 @@ pub fn get_flag_pt_array<const N: usize>(encoder: &Encoder, flag_idx: usize) ->
     encoder.get_flag_pt(flag_idx).try_into().unwrap()
 }
 
-/// Constrain the addition of [SHA256_WORD_BITS] bit words in 16-bit limbs
+/// Constrain the addition of `WORD_BITS` bit words in 16-bit limbs
 /// It takes in the terms some in bits some in 16-bit limbs,
 /// the expected sum in bits and the carries
-pub fn constraint_word_addition<AB: AirBuilder>(
+pub fn constraint_word_addition<AB: AirBuilder, const WORD_BITS: usize, const WORD_U16S: usize>(
     builder: &mut AB,
-    terms_bits: &[&[impl Into<AB::Expr> + Clone; SHA256_WORD_BITS]],
-    terms_limb: &[&[impl Into<AB::Expr> + Clone; SHA256_WORD_U16S]],
-    expected_sum: &[impl Into<AB::Expr> + Clone; SHA256_WORD_BITS],
-    carries: &[impl Into<AB::Expr> + Clone; SHA256_WORD_U16S],
+    terms_bits: &[&[impl Into<AB::Expr> + Clone; WORD_BITS]],
+    terms_limb: &[&[impl Into<AB::Expr> + Clone; WORD_U16S]],
+    expected_sum: &[impl Into<AB::Expr> + Clone; WORD_BITS],
+    carries: &[impl Into<AB::Expr> + Clone; WORD_U16S],
 ) {
-    for i in 0..SHA256_WORD_U16S {
+    for i in 0..WORD_U16S {
         let mut limb_sum = if i == 0 {
             AB::Expr::ZERO
         } else {
@@ -23,12 +23,14 @@ pub struct VmCli {
 pub enum VmCliCommands {
     Build(BuildCmd),
     Commit(CommitCmd),
+    Inspect(InspectCmd),
     Keygen(KeygenCmd),
     Init(InitCmd),
     Prove(ProveCmd),
     Run(RunCmd),
     #[cfg(feature = "evm-verify")]
     Setup(SetupCmd),
+    TraceDiff(TraceDiffCmd),
     Verify(VerifyCmd),
 }
 
@@ -40,12 +42,14 @@ async fn main() -> Result<()> {
     match command {
         VmCliCommands::Build(cmd) => cmd.run(),
         VmCliCommands::Commit(cmd) => cmd.run(),
+        VmCliCommands::Inspect(cmd) => cmd.run(),
         VmCliCommands::Keygen(cmd) => cmd.run(),
         VmCliCommands::Init(cmd) => cmd.run(),
         VmCliCommands::Prove(cmd) => cmd.run(),
         VmCliCommands::Run(cmd) => cmd.run(),
         #[cfg(feature = "evm-verify")]
         VmCliCommands::Setup(cmd) => cmd.run().await,
+        VmCliCommands::TraceDiff(cmd) => cmd.run(),
         VmCliCommands::Verify(cmd) => cmd.run(),
     }
 }
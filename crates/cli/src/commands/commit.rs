@@ -74,6 +74,15 @@ impl CommitCmd {
             init_file_name: self.init_file_name.clone(),
             input: None,
             signatures: None,
+            gdb: None,
+            record: None,
+            trace_heights: false,
+            trace_heights_json: None,
+            guest_profile: None,
+            guest_profile_cells: None,
+            kv_dir: vec![],
+            kv_index: vec![],
+            backtrace_len: None,
         };
         let (committed_exe, target_name) =
             load_or_build_and_commit_exe(&sdk, &run_args, &self.cargo_args, &app_pk)?;
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+use openvm_circuit::arch::VmConfig;
+use openvm_sdk::{
+    fs::read_app_config_from_file,
+    inspect::{inspect_proof_file, ProofInspection, ProofKind, ProofPartSize},
+    F,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "inspect",
+    about = "Print the contents of an app, STARK or EVM proof"
+)]
+pub struct InspectCmd {
+    #[arg(help = "Path to the proof, its kind is detected from its contents")]
+    proof: PathBuf,
+
+    #[arg(
+        long,
+        help = "Path to the OpenVM config .toml file of the app, used to name the AIRs of app proofs",
+        help_heading = "OpenVM Options"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(long, help = "Print the summary as JSON", help_heading = "Output")]
+    json: bool,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<()> {
+        let mut inspection = inspect_proof_file(&self.proof)?;
+        if let Some(config) = &self.config {
+            if inspection.kind == ProofKind::App {
+                let app_config = read_app_config_from_file(config)?;
+                let chip_complex = VmConfig::<F>::create_chip_complex(&app_config.app_vm_config)?;
+                inspection.set_air_names(&chip_complex.air_names());
+            }
+        }
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&inspection)?);
+        } else {
+            print_inspection(&inspection);
+        }
+        Ok(())
+    }
+}
+
+fn print_inspection(inspection: &ProofInspection) {
+    print!("{} proof", inspection.kind);
+    if let Some(version) = inspection.codec_version {
+        print!(" (codec version {version})");
+    }
+    println!();
+    if let Some(app_commit) = &inspection.app_commit {
+        println!("exe commit: {:?}", app_commit.app_exe_commit.to_bn254());
+        println!("vm commit: {:?}", app_commit.app_vm_commit.to_bn254());
+    }
+    let public_values = &inspection.user_public_values;
+    print!("user public values ({}): ", public_values.len());
+    if public_values.iter().all(|&v| v <= u8::MAX as u32) {
+        let bytes: Vec<u8> = public_values.iter().map(|&v| v as u8).collect();
+        println!("0x{}", hex::encode(bytes));
+    } else {
+        println!("{public_values:?}");
+    }
+    println!("size: {} bytes", inspection.total_size);
+    print_sizes(&inspection.size);
+
+    for (idx, proof) in inspection.stark_proofs.iter().enumerate() {
+        match inspection.kind {
+            ProofKind::App => println!("\nSegment {idx}:"),
+            _ => println!("\nRoot proof:"),
+        }
+        let fri = &proof.fri;
+        let log_blowup = fri.log_blowup.map_or_else(
+            || "unknown".to_string(),
+            |log_blowup| log_blowup.to_string(),
+        );
+        println!(
+            "  FRI: log_blowup {log_blowup}, {} queries, {} commit phase rounds, \
+             log_final_poly_len {}",
+            fri.num_queries, fri.num_commit_phase_rounds, fri.log_final_poly_len
+        );
+        println!("  size: {} bytes", proof.total_size);
+        print_sizes(&proof.size);
+        let name_width = proof
+            .airs
+            .iter()
+            .map(|air| air.air_name.as_ref().map_or(0, |name| name.len()))
+            .max()
+            .unwrap_or(0)
+            .max(3);
+        println!(
+            "  {:>6}  {:<name_width$}  {:>10}  {:>14}",
+            "air_id", "air", "height", "public values"
+        );
+        for air in &proof.airs {
+            println!(
+                "  {:>6}  {:<name_width$}  {:>10}  {:>14}",
+                air.air_id,
+                air.air_name.as_deref().unwrap_or("-"),
+                air.height,
+                air.num_public_values
+            );
+        }
+    }
+}
+
+fn print_sizes(size: &[ProofPartSize]) {
+    for part in size {
+        println!("    {:<40}  {:>10}", part.part, part.bytes);
+    }
+}
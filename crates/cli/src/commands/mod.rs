@@ -4,6 +4,9 @@ pub use build::*;
 mod commit;
 pub use commit::*;
 
+mod inspect;
+pub use inspect::*;
+
 mod keygen;
 pub use keygen::*;
 
@@ -21,5 +24,8 @@ mod setup;
 #[cfg(feature = "evm-verify")]
 pub use setup::*;
 
+mod trace_diff;
+pub use trace_diff::*;
+
 mod verify;
 pub use verify::*;
@@ -1,7 +1,13 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Child, Command},
+    sync::Arc,
+};
 
 use clap::Parser;
 use eyre::Result;
+use openvm_circuit::arch::ContinuationVmProof;
 #[cfg(feature = "evm-prove")]
 use openvm_sdk::fs::write_evm_proof_to_file;
 use openvm_sdk::{
@@ -9,18 +15,19 @@ use openvm_sdk::{
     config::{AggregationTreeConfig, SdkVmConfig},
     fs::{
         read_agg_stark_pk_from_file, read_app_pk_from_file, read_exe_from_file,
-        write_app_proof_to_file, write_to_file_json,
+        write_app_proof_to_file, write_exe_to_file, write_to_file_json,
     },
     keygen::AppProvingKey,
+    prover::{AppProver, SegmentTaskQueue},
     types::VmStarkProofBytes,
-    NonRootCommittedExe, Sdk,
+    NonRootCommittedExe, Sdk, StdIn, SC,
 };
+use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Engine;
 
 use super::{RunArgs, RunCargoArgs};
 use crate::{
     commands::build,
     default::default_agg_stark_pk_path,
-    input::read_to_stdin,
     util::{get_app_pk_path, get_manifest_path_and_dir, get_single_target_name, get_target_dir},
 };
 #[cfg(feature = "evm-prove")]
@@ -52,12 +59,50 @@ enum ProveSubCommand {
         )]
         app_pk: Option<PathBuf>,
 
+        #[arg(
+            long,
+            action,
+            conflicts_with = "workers",
+            help = "Directory to save the proving progress in and to resume an interrupted proof from, the checkpoints are removed once the proof is written",
+            help_heading = "OpenVM Options"
+        )]
+        checkpoint_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            action,
+            conflicts_with = "checkpoint_dir",
+            help = "Number of worker processes to prove the segments in, by default the segments are proven in this process",
+            help_heading = "OpenVM Options"
+        )]
+        workers: Option<usize>,
+
+        #[arg(
+            long,
+            action,
+            requires = "workers",
+            help = "Directory of the segment proving tasks shared with the workers, by default a temporary directory",
+            help_heading = "OpenVM Options"
+        )]
+        queue_dir: Option<PathBuf>,
+
         #[command(flatten)]
         run_args: RunArgs,
 
         #[command(flatten)]
         cargo_args: RunCargoArgs,
     },
+    #[command(about = "Prove the segment tasks queued by 'cargo openvm prove app --workers'")]
+    SegmentWorker {
+        #[arg(long, action, help = "Directory of the segment proving tasks")]
+        queue_dir: PathBuf,
+
+        #[arg(long, action, help = "Path to app proving key")]
+        app_pk: PathBuf,
+
+        #[arg(long, action, help = "Path to the OpenVM executable")]
+        exe: PathBuf,
+    },
     Stark {
         #[arg(
             long,
@@ -75,6 +120,14 @@ enum ProveSubCommand {
         )]
         app_pk: Option<PathBuf>,
 
+        #[arg(
+            long,
+            action,
+            help = "Directory to save the proving progress in and to resume an interrupted proof from, the checkpoints are removed once the proof is written",
+            help_heading = "OpenVM Options"
+        )]
+        checkpoint_dir: Option<PathBuf>,
+
         #[command(flatten)]
         run_args: RunArgs,
 
@@ -102,6 +155,14 @@ enum ProveSubCommand {
         )]
         app_pk: Option<PathBuf>,
 
+        #[arg(
+            long,
+            action,
+            help = "Directory to save the proving progress in and to resume an interrupted proof from, the checkpoints are removed once the proof is written",
+            help_heading = "OpenVM Options"
+        )]
+        checkpoint_dir: Option<PathBuf>,
+
         #[command(flatten)]
         run_args: RunArgs,
 
@@ -119,16 +180,30 @@ impl ProveCmd {
             ProveSubCommand::App {
                 app_pk,
                 proof,
+                checkpoint_dir,
+                workers,
+                queue_dir,
                 run_args,
                 cargo_args,
             } => {
-                let sdk = Sdk::new();
-                let app_pk = load_app_pk(app_pk, cargo_args)?;
+                let sdk = with_checkpoint_dir(Sdk::new(), checkpoint_dir);
+                let app_pk_path = get_app_pk_path_or_default(app_pk, cargo_args)?;
+                let app_pk = Arc::new(read_app_pk_from_file(&app_pk_path)?);
                 let (committed_exe, target_name) =
                     load_or_build_and_commit_exe(&sdk, run_args, cargo_args, &app_pk)?;
 
-                let app_proof =
-                    sdk.generate_app_proof(app_pk, committed_exe, read_to_stdin(&run_args.input)?)?;
+                let app_proof = if let Some(workers) = workers {
+                    generate_app_proof_with_workers(
+                        &app_pk_path,
+                        app_pk,
+                        committed_exe,
+                        run_args.read_stdin()?,
+                        *workers,
+                        queue_dir,
+                    )?
+                } else {
+                    sdk.generate_app_proof(app_pk, committed_exe, run_args.read_stdin()?)?
+                };
 
                 let proof_path = if let Some(proof) = proof {
                     proof
@@ -136,15 +211,33 @@ impl ProveCmd {
                     &PathBuf::from(format!("{}.app.proof", target_name))
                 };
                 write_app_proof_to_file(app_proof, proof_path)?;
+                remove_checkpoints(&sdk)?;
+            }
+            ProveSubCommand::SegmentWorker {
+                queue_dir,
+                app_pk,
+                exe,
+            } => {
+                let app_pk = read_app_pk_from_file::<SdkVmConfig, _>(app_pk)?;
+                let committed_exe =
+                    Sdk::new().commit_app_exe(app_pk.app_fri_params(), read_exe_from_file(exe)?)?;
+                let app_prover = AppProver::<_, BabyBearPoseidon2Engine>::new(
+                    app_pk.app_vm_pk.clone(),
+                    committed_exe,
+                );
+                let num_proven = SegmentTaskQueue::new(queue_dir).run_worker(&app_prover)?;
+                println!("Proved {num_proven} segments");
             }
             ProveSubCommand::Stark {
                 app_pk,
                 proof,
+                checkpoint_dir,
                 run_args,
                 cargo_args,
                 agg_tree_config,
             } => {
                 let sdk = Sdk::new().with_agg_tree_config(*agg_tree_config);
+                let sdk = with_checkpoint_dir(sdk, checkpoint_dir);
                 let app_pk = load_app_pk(app_pk, cargo_args)?;
                 let (committed_exe, target_name) =
                     load_or_build_and_commit_exe(&sdk, run_args, cargo_args, &app_pk)?;
@@ -164,7 +257,7 @@ impl ProveCmd {
                     app_pk,
                     committed_exe,
                     agg_stark_pk,
-                    read_to_stdin(&run_args.input)?,
+                    run_args.read_stdin()?,
                 )?;
 
                 let stark_proof_bytes = VmStarkProofBytes::new(commits, stark_proof)?;
@@ -175,11 +268,13 @@ impl ProveCmd {
                     &PathBuf::from(format!("{}.stark.proof", target_name))
                 };
                 write_to_file_json(proof_path, stark_proof_bytes)?;
+                remove_checkpoints(&sdk)?;
             }
             #[cfg(feature = "evm-prove")]
             ProveSubCommand::Evm {
                 app_pk,
                 proof,
+                checkpoint_dir,
                 run_args,
                 cargo_args,
                 agg_tree_config,
@@ -187,6 +282,7 @@ impl ProveCmd {
                 use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
 
                 let sdk = Sdk::new().with_agg_tree_config(*agg_tree_config);
+                let sdk = with_checkpoint_dir(sdk, checkpoint_dir);
                 let app_pk = load_app_pk(app_pk, cargo_args)?;
                 let (committed_exe, target_name) =
                     load_or_build_and_commit_exe(&sdk, run_args, cargo_args, &app_pk)?;
@@ -209,7 +305,7 @@ impl ProveCmd {
                     app_pk,
                     committed_exe,
                     agg_pk,
-                    read_to_stdin(&run_args.input)?,
+                    run_args.read_stdin()?,
                 )?;
 
                 let proof_path = if let Some(proof) = proof {
@@ -218,25 +314,113 @@ impl ProveCmd {
                     &PathBuf::from(format!("{}.evm.proof", target_name))
                 };
                 write_evm_proof_to_file(evm_proof, proof_path)?;
+                remove_checkpoints(&sdk)?;
             }
         }
         Ok(())
     }
 }
 
-pub(crate) fn load_app_pk(
+fn with_checkpoint_dir(sdk: Sdk, checkpoint_dir: &Option<PathBuf>) -> Sdk {
+    match checkpoint_dir {
+        Some(checkpoint_dir) => sdk.with_checkpoint_dir(checkpoint_dir),
+        None => sdk,
+    }
+}
+
+fn remove_checkpoints(sdk: &Sdk) -> Result<()> {
+    sdk.checkpoint_dir()
+        .map_or(Ok(()), |checkpoints| checkpoints.remove())
+}
+
+/// Proves the segments of the app proof in `workers` child processes running `cargo openvm prove
+/// segment-worker`, while this process executes the program and queues the segments.
+fn generate_app_proof_with_workers(
+    app_pk_path: &Path,
+    app_pk: Arc<AppProvingKey<SdkVmConfig>>,
+    committed_exe: Arc<NonRootCommittedExe>,
+    input: StdIn,
+    workers: usize,
+    queue_dir: &Option<PathBuf>,
+) -> Result<ContinuationVmProof<SC>> {
+    let is_temp_dir = queue_dir.is_none();
+    let queue_dir = queue_dir
+        .clone()
+        .unwrap_or_else(|| env::temp_dir().join(format!("openvm-segment-queue-{}", process::id())));
+    let queue = SegmentTaskQueue::new(queue_dir);
+    // Discards the tasks and proofs left by an interrupted run.
+    queue.remove()?;
+    let exe_path = queue.path().join("app.vmexe");
+    write_exe_to_file(committed_exe.exe.clone(), &exe_path)?;
+
+    let mut children = WorkerProcesses(Vec::with_capacity(workers));
+    for _ in 0..workers {
+        let child = Command::new(env::current_exe()?)
+            .args(["openvm", "prove", "segment-worker", "--queue-dir"])
+            .arg(queue.path())
+            .arg("--app-pk")
+            .arg(app_pk_path)
+            .arg("--exe")
+            .arg(&exe_path)
+            .spawn()?;
+        children.0.push(child);
+    }
+
+    let app_prover =
+        AppProver::<_, BabyBearPoseidon2Engine>::new(app_pk.app_vm_pk.clone(), committed_exe);
+    let num_segments = queue.plan(&app_prover, input)?;
+    println!("Queued {num_segments} segments for {workers} workers");
+    let proof = queue.wait_for_proof_while(|| children.any_running())?;
+    for child in &mut children.0 {
+        child.wait()?;
+    }
+    queue.remove()?;
+    fs::remove_file(&exe_path)?;
+    if is_temp_dir {
+        fs::remove_dir(queue.path())?;
+    }
+    Ok(proof)
+}
+
+/// Kills the worker processes which are still running when dropped, e.g. on an error.
+struct WorkerProcesses(Vec<Child>);
+
+impl WorkerProcesses {
+    fn any_running(&mut self) -> Result<bool> {
+        for child in &mut self.0 {
+            if child.try_wait()?.is_none() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Drop for WorkerProcesses {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+        }
+    }
+}
+
+fn get_app_pk_path_or_default(
     app_pk: &Option<PathBuf>,
     cargo_args: &RunCargoArgs,
-) -> Result<Arc<AppProvingKey<SdkVmConfig>>> {
+) -> Result<PathBuf> {
+    if let Some(app_pk) = app_pk {
+        return Ok(app_pk.to_path_buf());
+    }
     let (manifest_path, _) = get_manifest_path_and_dir(&cargo_args.manifest_path)?;
     let target_dir = get_target_dir(&cargo_args.target_dir, &manifest_path);
+    Ok(get_app_pk_path(&target_dir))
+}
 
-    let app_pk_path = if let Some(app_pk) = app_pk {
-        app_pk.to_path_buf()
-    } else {
-        get_app_pk_path(&target_dir)
-    };
-
+pub(crate) fn load_app_pk(
+    app_pk: &Option<PathBuf>,
+    cargo_args: &RunCargoArgs,
+) -> Result<Arc<AppProvingKey<SdkVmConfig>>> {
+    let app_pk_path = get_app_pk_path_or_default(app_pk, cargo_args)?;
     Ok(Arc::new(read_app_pk_from_file(app_pk_path)?))
 }
 
@@ -1,9 +1,26 @@
-use std::path::PathBuf;
+use std::{
+    fs, io,
+    net::TcpStream,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use clap::Parser;
-use eyre::Result;
-use openvm_circuit::arch::OPENVM_DEFAULT_INIT_FILE_NAME;
-use openvm_sdk::{fs::read_exe_from_file, Sdk};
+use eyre::{bail, Result, WrapErr};
+use openvm_circuit::{
+    arch::{
+        gdb::GdbStub,
+        profiler::{GuestProfiler, ProfileWeight},
+        recording::ExecutionRecorder,
+        BlobDirKvStore, ExitCode, MmapKvStore, VmMemoryState, OPENVM_DEFAULT_INIT_FILE_NAME,
+    },
+    system::memory::Rv32MemoryView,
+};
+use openvm_sdk::{
+    fs::{read_exe_from_file, write_to_file_json},
+    types::{AirCostReport, ExecutionReport},
+    Sdk, StdIn, F,
+};
 
 use super::{build, BuildArgs, BuildCargoArgs};
 use crate::{
@@ -67,6 +84,100 @@ pub struct RunArgs {
         help_heading = "OpenVM Options"
     )]
     pub signatures: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "Wait for GDB to connect on the given local port and run the program under its control",
+        help_heading = "OpenVM Options"
+    )]
+    pub gdb: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Record the executed instructions to the given file, to compare them with `cargo openvm trace-diff`",
+        help_heading = "OpenVM Options"
+    )]
+    pub record: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["gdb", "record", "signatures"],
+        help = "Print the trace height, padded height, cell count and estimated proving time share of every chip, per segment and in total",
+        help_heading = "OpenVM Options"
+    )]
+    pub trace_heights: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["gdb", "record", "signatures"],
+        help = "Write the execution report with the trace heights of every chip as JSON to the given file",
+        help_heading = "OpenVM Options"
+    )]
+    pub trace_heights_json: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["trace_heights", "trace_heights_json"],
+        help = "Write the cycles spent in each guest call stack to the given file in the collapsed stack format, for flamegraph tools",
+        help_heading = "OpenVM Options"
+    )]
+    pub guest_profile: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["trace_heights", "trace_heights_json"],
+        help = "Write the trace cells added by each guest call stack to the given file in the collapsed stack format, for flamegraph tools",
+        help_heading = "OpenVM Options"
+    )]
+    pub guest_profile_cells: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Serve `hint_load_by_key` lookups missing from the input from a directory of content-addressed blobs, may be repeated",
+        help_heading = "OpenVM Options"
+    )]
+    pub kv_dir: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Serve `hint_load_by_key` lookups missing from the input and the --kv-dir stores from a sorted, memory-mapped key-value index file, may be repeated",
+        help_heading = "OpenVM Options"
+    )]
+    pub kv_index: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Number of most recently executed instructions to include in the guest backtrace on failure, overriding `backtrace_len` in the config file, 0 disables guest backtraces",
+        help_heading = "OpenVM Options"
+    )]
+    pub backtrace_len: Option<usize>,
+}
+
+impl RunArgs {
+    /// Reads the input and attaches the file-backed key-value stores.
+    pub fn read_stdin(&self) -> Result<StdIn> {
+        let mut stdin = read_to_stdin(&self.input)?;
+        for dir in &self.kv_dir {
+            let store = BlobDirKvStore::open(dir).wrap_err_with(|| {
+                format!("Failed to open key-value store directory {}", dir.display())
+            })?;
+            stdin.add_kv_store(store);
+        }
+        for path in &self.kv_index {
+            let store = MmapKvStore::open(path)
+                .wrap_err_with(|| format!("Failed to open key-value index {}", path.display()))?;
+            stdin.add_kv_store(store);
+        }
+        Ok(stdin)
+    }
 }
 
 impl From<RunArgs> for BuildArgs {
@@ -248,30 +359,166 @@ impl RunCmd {
         };
 
         let (_, manifest_dir) = get_manifest_path_and_dir(&self.cargo_args.manifest_path)?;
-        let app_config = read_config_toml_or_default(
+        let mut app_config = read_config_toml_or_default(
             self.run_args
                 .config
                 .to_owned()
                 .unwrap_or_else(|| manifest_dir.join("openvm.toml")),
         )?;
         let exe = read_exe_from_file(exe_path)?;
+        let inputs = self.run_args.read_stdin()?;
+        if let Some(backtrace_len) = self.run_args.backtrace_len {
+            app_config.app_vm_config.system.config.backtrace_len = backtrace_len;
+        }
 
-        let sdk = Sdk::new();
-        let output = if let Some(signature_path) = &self.run_args.signatures {
-            sdk.execute_with_signature(
-                exe,
-                app_config.app_vm_config,
-                read_to_stdin(&self.run_args.input)?,
-                Some(signature_path),
-            )?
-        } else {
-            sdk.execute(
-                exe,
-                app_config.app_vm_config,
-                read_to_stdin(&self.run_args.input)?,
-            )?
-        };
+        if self.run_args.trace_heights || self.run_args.trace_heights_json.is_some() {
+            let report =
+                Sdk::new().execute_with_report(exe, app_config.app_vm_config, inputs, true)?;
+            if self.run_args.trace_heights {
+                print_trace_heights(&report);
+            }
+            if let Some(path) = &self.run_args.trace_heights_json {
+                write_to_file_json(path, &report)?;
+            }
+            if report.exit_code != ExitCode::Success as u32 {
+                bail!("program exit code {}", report.exit_code);
+            }
+            println!("Execution output: {:?}", report.public_values);
+            return Ok(());
+        }
+
+        let gdb = self.run_args.gdb.map(wait_for_gdb).transpose()?;
+        let recorder = self
+            .run_args
+            .record
+            .as_ref()
+            .map(|path| {
+                ExecutionRecorder::create(path)
+                    .wrap_err_with(|| format!("Failed to create {}", path.display()))
+            })
+            .transpose()?
+            .map(|recorder| Arc::new(Mutex::new(recorder)));
+        let profiles = [
+            (&self.run_args.guest_profile, ProfileWeight::Cycles),
+            (
+                &self.run_args.guest_profile_cells,
+                ProfileWeight::TraceCells,
+            ),
+        ];
+        let profiler = profiles.iter().any(|(path, _)| path.is_some()).then(|| {
+            if exe.fn_bounds.is_empty() {
+                eprintln!(
+                    "Warning: the executable has no function bounds, all cycles will be \
+                     attributed to an unknown function. Build the guest without stripping symbols."
+                );
+            }
+            Arc::new(Mutex::new(GuestProfiler::new(exe.fn_bounds.clone())))
+        });
+        let result = Sdk::new().execute_with_setup(exe, app_config.app_vm_config, inputs, |vm| {
+            if let Some(stub) = &gdb {
+                vm.set_execution_hook(stub.clone());
+            }
+            if let Some(recorder) = &recorder {
+                vm.set_execution_recorder(recorder.clone());
+            }
+            if let Some(profiler) = &profiler {
+                vm.set_execution_profiler(profiler.clone());
+            }
+        });
+        if let Some(stub) = &gdb {
+            stub.lock().unwrap().report_result(&result)?;
+        }
+        if let Some(recorder) = &recorder {
+            let mut recorder = recorder.lock().unwrap();
+            recorder.flush()?;
+            println!("Recorded {} instructions", recorder.num_records());
+        }
+        if let Some(profiler) = &profiler {
+            let profiler = profiler.lock().unwrap();
+            for (path, weight) in profiles {
+                if let Some(path) = path {
+                    let file = fs::File::create(path)
+                        .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+                    profiler.write_collapsed(weight, io::BufWriter::new(file))?;
+                    println!(
+                        "Wrote guest profile of {} {} to {}",
+                        profiler.total(weight),
+                        match weight {
+                            ProfileWeight::Cycles => "cycles",
+                            ProfileWeight::TraceCells => "trace cells",
+                        },
+                        path.display()
+                    );
+                }
+            }
+        }
+        let (output, final_memory) = result?;
+
+        if let Some(signature_path) = &self.run_args.signatures {
+            write_signature(signature_path, &final_memory)?;
+        }
         println!("Execution output: {:?}", output);
         Ok(())
     }
 }
+
+fn print_trace_heights(report: &ExecutionReport) {
+    for (idx, segment) in report.segments.iter().enumerate() {
+        println!("Segment {idx}: {} cycles", segment.cycles);
+        print_air_costs(&segment.airs);
+    }
+    println!(
+        "Total: {} cycles in {} segments",
+        report.total_cycles, report.num_segments
+    );
+    print_air_costs(&report.airs);
+}
+
+/// Prints the AIRs with a non-empty trace, the most expensive first.
+fn print_air_costs(airs: &[AirCostReport]) {
+    let mut airs: Vec<_> = airs.iter().filter(|air| air.height > 0).collect();
+    airs.sort_by(|a, b| b.cells.cmp(&a.cells));
+    let name_width = airs.iter().map(|air| air.air_name.len()).max().unwrap_or(0);
+    println!(
+        "  {:<name_width$}  {:>10}  {:>10}  {:>12}  {:>7}",
+        "chip", "height", "padded", "cells", "share"
+    );
+    for air in airs {
+        println!(
+            "  {:<name_width$}  {:>10}  {:>10}  {:>12}  {:>6.2}%",
+            air.air_name,
+            air.height,
+            air.padded_height,
+            air.cells,
+            air.proving_time_share * 100.0
+        );
+    }
+}
+
+fn wait_for_gdb(port: u16) -> Result<Arc<Mutex<GdbStub<TcpStream>>>> {
+    println!("Waiting for GDB to connect on localhost:{port}");
+    let stub = GdbStub::listen(("127.0.0.1", port))
+        .wrap_err_with(|| format!("Failed to accept a GDB connection on port {port}"))?;
+    println!("GDB connected");
+    Ok(Arc::new(Mutex::new(stub)))
+}
+
+/// Writes the RISCOF signature, i.e. the `RISC0_SIG_SIZE` bytes of memory starting at
+/// `RISC0_SIG_BEGIN_ADDR`, as one hex word per line.
+fn write_signature(path: &Path, final_memory: &VmMemoryState<F>) -> Result<()> {
+    let env_u32 = |name: &str| -> Result<u32> {
+        let value = std::env::var(name).wrap_err_with(|| format!("{name} must be set"))?;
+        value
+            .parse()
+            .wrap_err_with(|| format!("{name} is not a valid u32: {value}"))
+    };
+    let begin = env_u32("RISC0_SIG_BEGIN_ADDR")?;
+    let size = env_u32("RISC0_SIG_SIZE")?;
+    let signature: String = Rv32MemoryView::new(final_memory)
+        .read_words(begin, size as usize / 4)
+        .into_iter()
+        .map(|word| format!("{word:08x}\n"))
+        .collect();
+    fs::write(path, signature)?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::{eyre, Result, WrapErr};
+use openvm_circuit::arch::recording::{first_divergence, ExecutionRecordReader};
+
+#[derive(Parser)]
+#[command(
+    name = "trace-diff",
+    about = "Compare two execution recordings made by `cargo openvm run --record` and report the first divergence"
+)]
+pub struct TraceDiffCmd {
+    #[arg(help = "Path to the first recording")]
+    left: PathBuf,
+
+    #[arg(help = "Path to the second recording")]
+    right: PathBuf,
+}
+
+impl TraceDiffCmd {
+    pub fn run(&self) -> Result<()> {
+        let open = |path: &PathBuf| {
+            ExecutionRecordReader::open(path)
+                .wrap_err_with(|| format!("Failed to open recording {}", path.display()))
+        };
+        let (mut left, mut right) = (open(&self.left)?, open(&self.right)?);
+        match first_divergence(&mut left, &mut right)? {
+            None => {
+                println!("Recordings are identical");
+                Ok(())
+            }
+            Some(divergence) => Err(eyre!("{divergence}")),
+        }
+    }
+}
@@ -1,12 +1,23 @@
-use std::{fs::read, path::PathBuf, str::FromStr};
+use std::{
+    fmt,
+    fs::read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use eyre::Result;
+use eyre::{bail, eyre, Result, WrapErr};
 use openvm_sdk::{StdIn, F};
-use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+use serde::{
+    ser::{SerializeTuple, Serializer},
+    Serialize,
+};
+use serde_json::Value;
 
 /// Input can be either:
 /// (1) one single hex string
 /// (2) A JSON file containing an array of hex strings.
+/// (3) A TOML or JSON input document with typed entries, see [read_input_file].
 /// Each hex string (either in the file or the direct input) is either:
 /// - Hex strings of bytes, which is prefixed with 0x01
 /// - Hex strings of native field elements (represented as u32, little endian), prefixed with 0x02
@@ -78,30 +89,7 @@ pub fn read_bytes_into_stdin(stdin: &mut StdIn, bytes: &[u8]) -> Result<()> {
 
 pub fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
     match input {
-        Some(Input::FilePath(path)) => {
-            let mut stdin = StdIn::default();
-            // read the json
-            let bytes = read(path)?;
-            let json: serde_json::Value = serde_json::from_slice(&bytes)?;
-            json["input"]
-                .as_array()
-                .ok_or_else(|| eyre::eyre!("Input must be an array under 'input' key"))?
-                .iter()
-                .try_for_each(|inner| {
-                    inner
-                        .as_str()
-                        .ok_or_else(|| eyre::eyre!("Each value must be a hex string"))
-                        .and_then(|s| {
-                            if !is_valid_hex_string(s) {
-                                return Err(eyre::eyre!("Invalid hex string"));
-                            }
-                            let bytes = decode_hex_string(s)?;
-                            read_bytes_into_stdin(&mut stdin, &bytes)
-                        })
-                })?;
-
-            Ok(stdin)
-        }
+        Some(Input::FilePath(path)) => read_input_file(path),
         Some(Input::HexBytes(hex_str)) => {
             let mut stdin = StdIn::default();
             let bytes = decode_hex_string(hex_str)?;
@@ -111,3 +99,503 @@ pub fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
         None => Ok(StdIn::default()),
     }
 }
+
+/// Reads an input document. Files with a `.toml` extension are parsed as TOML, everything else
+/// as JSON. The document has an `input` array whose entries are pushed onto the input stream in
+/// order, and an optional `kv` array of entries for the key-value store.
+///
+/// Each `input` entry is either a legacy hex string prefixed with `0x01`/`0x02`, or a table with
+/// exactly one of the following keys:
+/// - `type` (with `value`): the value is serialized like [StdIn::write] would serialize a Rust
+///   value of that type, so that the guest can `openvm::io::read::<T>()` it.
+/// - `bytes`: a hex string written with [StdIn::write_bytes].
+/// - `text`: a UTF-8 string written with [StdIn::write_bytes].
+/// - `file`: the raw contents of a file, relative to the input document.
+/// - `fields`: an array of native field elements written with [StdIn::write_field].
+///
+/// Each `kv` entry has a `key` and a `value`. Either may be a string, which is used as UTF-8
+/// bytes, or a table with a `type`, `bytes`, `text` or `file` key as above.
+pub fn read_input_file(path: &Path) -> Result<StdIn> {
+    let contents =
+        read(path).wrap_err_with(|| format!("Failed to read input file {}", path.display()))?;
+    let document: Value = if path.extension().is_some_and(|ext| ext == "toml") {
+        let document: toml::Value = toml::from_str(std::str::from_utf8(&contents)?)?;
+        serde_json::to_value(document)?
+    } else {
+        serde_json::from_slice(&contents)?
+    };
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut stdin = StdIn::default();
+    let entries = match document.get("input") {
+        Some(input) => input
+            .as_array()
+            .ok_or_else(|| eyre!("Input must be an array under 'input' key"))?
+            .as_slice(),
+        None => &[],
+    };
+    for (i, entry) in entries.iter().enumerate() {
+        read_entry_into_stdin(&mut stdin, entry, base_dir)
+            .wrap_err_with(|| format!("Invalid input entry {i}"))?;
+    }
+    let kv_entries = match document.get("kv") {
+        Some(kv) => kv
+            .as_array()
+            .ok_or_else(|| eyre!("Key-value entries must be an array under 'kv' key"))?
+            .as_slice(),
+        None => &[],
+    };
+    for (i, entry) in kv_entries.iter().enumerate() {
+        let (key, value) =
+            read_kv_entry(entry, base_dir).wrap_err_with(|| format!("Invalid kv entry {i}"))?;
+        stdin.add_key_value(key, value);
+    }
+    Ok(stdin)
+}
+
+fn read_entry_into_stdin(stdin: &mut StdIn, entry: &Value, base_dir: &Path) -> Result<()> {
+    if let Some(s) = entry.as_str() {
+        if !is_valid_hex_string(s) {
+            bail!("Invalid hex string");
+        }
+        let bytes = decode_hex_string(s)?;
+        return read_bytes_into_stdin(stdin, &bytes);
+    }
+    match read_payload(entry, base_dir, true)? {
+        Payload::Bytes(bytes) => stdin.write_bytes(&bytes),
+        Payload::Fields(fields) => stdin.write_field(&fields),
+    }
+    Ok(())
+}
+
+fn read_kv_entry(entry: &Value, base_dir: &Path) -> Result<(Vec<u8>, Vec<u8>)> {
+    let table = entry
+        .as_object()
+        .ok_or_else(|| eyre!("Expected a table with `key` and `value`"))?;
+    if let Some(unknown) = table.keys().find(|k| *k != "key" && *k != "value") {
+        bail!("Unknown key `{unknown}`");
+    }
+    let side = |name: &str| -> Result<Vec<u8>> {
+        let value = table.get(name).ok_or_else(|| eyre!("Missing `{name}`"))?;
+        if let Some(text) = value.as_str() {
+            return Ok(text.as_bytes().to_vec());
+        }
+        match read_payload(value, base_dir, false).wrap_err_with(|| format!("Invalid `{name}`"))? {
+            Payload::Bytes(bytes) => Ok(bytes),
+            Payload::Fields(_) => unreachable!("fields are not allowed in kv entries"),
+        }
+    };
+    Ok((side("key")?, side("value")?))
+}
+
+enum Payload {
+    Bytes(Vec<u8>),
+    Fields(Vec<F>),
+}
+
+const PAYLOAD_SOURCES: [&str; 5] = ["type", "bytes", "text", "file", "fields"];
+
+fn read_payload(entry: &Value, base_dir: &Path, allow_fields: bool) -> Result<Payload> {
+    let table = entry
+        .as_object()
+        .ok_or_else(|| eyre!("Expected a string or a table, found {entry}"))?;
+    if let Some(unknown) = table
+        .keys()
+        .find(|k| *k != "value" && !PAYLOAD_SOURCES.contains(&k.as_str()))
+    {
+        bail!("Unknown key `{unknown}`");
+    }
+    let mut sources = PAYLOAD_SOURCES
+        .into_iter()
+        .filter(|source| table.contains_key(*source));
+    let source = match (sources.next(), sources.next()) {
+        (Some(source), None) => source,
+        _ => bail!("Expected exactly one of `type`, `bytes`, `text`, `file` or `fields`"),
+    };
+    if source != "type" && table.contains_key("value") {
+        bail!("`value` is only allowed together with `type`");
+    }
+    let as_str = |key: &str| {
+        table[key]
+            .as_str()
+            .ok_or_else(|| eyre!("`{key}` must be a string"))
+    };
+
+    let payload = match source {
+        "type" => {
+            let ty: InputType = as_str("type")?.parse()?;
+            let value = table.get("value").unwrap_or(&Value::Null);
+            let value = TypedValue::new(&ty, value)
+                .wrap_err_with(|| format!("Invalid value for `{ty}`"))?;
+            Payload::Bytes(value.to_bytes()?)
+        }
+        "bytes" => {
+            let s = as_str("bytes")?;
+            if !is_valid_hex_string(s) {
+                bail!("Invalid hex string");
+            }
+            Payload::Bytes(decode_hex_string(s)?)
+        }
+        "text" => Payload::Bytes(as_str("text")?.as_bytes().to_vec()),
+        "file" => {
+            let path = base_dir.join(as_str("file")?);
+            Payload::Bytes(
+                read(&path).wrap_err_with(|| format!("Failed to read {}", path.display()))?,
+            )
+        }
+        "fields" => {
+            if !allow_fields {
+                bail!("`fields` is only allowed in input entries");
+            }
+            let fields = table["fields"]
+                .as_array()
+                .ok_or_else(|| eyre!("`fields` must be an array"))?
+                .iter()
+                .map(|v| {
+                    let v = as_u128(v)?;
+                    if v >= F::ORDER_U32 as u128 {
+                        bail!("{v} is not a canonical field element");
+                    }
+                    Ok(F::from_canonical_u32(v as u32))
+                })
+                .collect::<Result<_>>()?;
+            Payload::Fields(fields)
+        }
+        _ => unreachable!(),
+    };
+    Ok(payload)
+}
+
+/// The type of a value read by the guest with `openvm::io::read::<T>()`, parsed from a Rust type
+/// such as `(u64, String, Vec<[u8; 4]>)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputType {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Char,
+    String,
+    Option(Box<InputType>),
+    Vec(Box<InputType>),
+    Array(Box<InputType>, usize),
+    Tuple(Vec<InputType>),
+}
+
+impl FromStr for InputType {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let (ty, rest) = parse_type(&compact).wrap_err_with(|| format!("Invalid type `{s}`"))?;
+        if !rest.is_empty() {
+            bail!("Invalid type `{s}`: unexpected `{rest}`");
+        }
+        Ok(ty)
+    }
+}
+
+fn parse_type(s: &str) -> Result<(InputType, &str)> {
+    if let Some(mut rest) = s.strip_prefix('(') {
+        let mut elems = Vec::new();
+        loop {
+            if let Some(r) = rest.strip_prefix(')') {
+                let ty = if elems.is_empty() {
+                    InputType::Unit
+                } else {
+                    InputType::Tuple(elems)
+                };
+                return Ok((ty, r));
+            }
+            let (elem, r) = parse_type(rest)?;
+            elems.push(elem);
+            rest = match r.strip_prefix(',') {
+                Some(r) => r,
+                None if r.starts_with(')') => r,
+                None => bail!("expected `,` or `)`"),
+            };
+        }
+    }
+    if let Some(rest) = s.strip_prefix('[') {
+        let (elem, rest) = parse_type(rest)?;
+        let rest = rest
+            .strip_prefix(';')
+            .ok_or_else(|| eyre!("expected `;` in array type"))?;
+        let end = rest
+            .find(']')
+            .ok_or_else(|| eyre!("expected `]` in array type"))?;
+        let len = rest[..end].parse().wrap_err("invalid array length")?;
+        return Ok((InputType::Array(Box::new(elem), len), &rest[end + 1..]));
+    }
+
+    let end = s
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len());
+    let (name, rest) = s.split_at(end);
+    let ty = match name {
+        "bool" => InputType::Bool,
+        "u8" => InputType::U8,
+        "u16" => InputType::U16,
+        "u32" => InputType::U32,
+        "u64" => InputType::U64,
+        "u128" => InputType::U128,
+        "i8" => InputType::I8,
+        "i16" => InputType::I16,
+        "i32" => InputType::I32,
+        "i64" => InputType::I64,
+        "i128" => InputType::I128,
+        "char" => InputType::Char,
+        "String" => InputType::String,
+        "Vec" | "Option" => {
+            let rest = rest
+                .strip_prefix('<')
+                .ok_or_else(|| eyre!("expected `<` after `{name}`"))?;
+            let (inner, rest) = parse_type(rest)?;
+            let rest = rest
+                .strip_prefix('>')
+                .ok_or_else(|| eyre!("expected `>` after `{name}<{inner}`"))?;
+            let ty = if name == "Vec" {
+                InputType::Vec(Box::new(inner))
+            } else {
+                InputType::Option(Box::new(inner))
+            };
+            return Ok((ty, rest));
+        }
+        "" => bail!("expected a type"),
+        _ => bail!("unsupported type `{name}`"),
+    };
+    Ok((ty, rest))
+}
+
+impl fmt::Display for InputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputType::Unit => write!(f, "()"),
+            InputType::Bool => write!(f, "bool"),
+            InputType::U8 => write!(f, "u8"),
+            InputType::U16 => write!(f, "u16"),
+            InputType::U32 => write!(f, "u32"),
+            InputType::U64 => write!(f, "u64"),
+            InputType::U128 => write!(f, "u128"),
+            InputType::I8 => write!(f, "i8"),
+            InputType::I16 => write!(f, "i16"),
+            InputType::I32 => write!(f, "i32"),
+            InputType::I64 => write!(f, "i64"),
+            InputType::I128 => write!(f, "i128"),
+            InputType::Char => write!(f, "char"),
+            InputType::String => write!(f, "String"),
+            InputType::Option(inner) => write!(f, "Option<{inner}>"),
+            InputType::Vec(inner) => write!(f, "Vec<{inner}>"),
+            InputType::Array(inner, len) => write!(f, "[{inner}; {len}]"),
+            InputType::Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{elem}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A value checked against an [InputType]. It serializes exactly like the corresponding Rust value,
+/// so [StdIn::write] produces the bytes the guest expects.
+///
+/// Integers may be given as numbers or as decimal or `0x`-prefixed hex strings, which is needed
+/// for values that do not fit into a TOML integer. `Option<T>` is written as an empty array for
+/// `None` (or `null` in JSON) and a one-element array for `Some`. `Vec<u8>` and `[u8; N]` also
+/// accept a hex string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypedValue {
+    Unit,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Char(char),
+    String(String),
+    Option(Option<Box<TypedValue>>),
+    Seq(Vec<TypedValue>),
+    Tuple(Vec<TypedValue>),
+}
+
+impl TypedValue {
+    pub fn new(ty: &InputType, value: &Value) -> Result<Self> {
+        let typed = match ty {
+            InputType::Unit => match value {
+                Value::Null => TypedValue::Unit,
+                Value::Array(elems) if elems.is_empty() => TypedValue::Unit,
+                _ => bail!("expected `[]`, found {value}"),
+            },
+            InputType::Bool => TypedValue::Bool(
+                value
+                    .as_bool()
+                    .ok_or_else(|| eyre!("expected a boolean, found {value}"))?,
+            ),
+            InputType::U8 => TypedValue::U8(narrow(as_u128(value)?, ty)?),
+            InputType::U16 => TypedValue::U16(narrow(as_u128(value)?, ty)?),
+            InputType::U32 => TypedValue::U32(narrow(as_u128(value)?, ty)?),
+            InputType::U64 => TypedValue::U64(narrow(as_u128(value)?, ty)?),
+            InputType::U128 => TypedValue::U128(as_u128(value)?),
+            InputType::I8 => TypedValue::I8(narrow(as_i128(value)?, ty)?),
+            InputType::I16 => TypedValue::I16(narrow(as_i128(value)?, ty)?),
+            InputType::I32 => TypedValue::I32(narrow(as_i128(value)?, ty)?),
+            InputType::I64 => TypedValue::I64(narrow(as_i128(value)?, ty)?),
+            InputType::I128 => TypedValue::I128(as_i128(value)?),
+            InputType::Char => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| eyre!("expected a character, found {value}"))?;
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => TypedValue::Char(c),
+                    _ => bail!("expected a single character, found {value}"),
+                }
+            }
+            InputType::String => TypedValue::String(
+                value
+                    .as_str()
+                    .ok_or_else(|| eyre!("expected a string, found {value}"))?
+                    .to_string(),
+            ),
+            InputType::Option(inner) => match value {
+                Value::Null => TypedValue::Option(None),
+                Value::Array(elems) if elems.is_empty() => TypedValue::Option(None),
+                Value::Array(elems) if elems.len() == 1 => {
+                    TypedValue::Option(Some(Box::new(TypedValue::new(inner, &elems[0])?)))
+                }
+                _ => bail!("expected `[]` or a one-element array, found {value}"),
+            },
+            InputType::Vec(inner) => TypedValue::Seq(elements(inner, value, None)?),
+            InputType::Array(inner, len) => TypedValue::Tuple(elements(inner, value, Some(*len))?),
+            InputType::Tuple(elems) => {
+                let values = value
+                    .as_array()
+                    .ok_or_else(|| eyre!("expected an array, found {value}"))?;
+                if values.len() != elems.len() {
+                    bail!("expected {} elements, found {}", elems.len(), values.len());
+                }
+                TypedValue::Tuple(
+                    elems
+                        .iter()
+                        .zip(values)
+                        .map(|(ty, value)| TypedValue::new(ty, value))
+                        .collect::<Result<_>>()?,
+                )
+            }
+        };
+        Ok(typed)
+    }
+
+    /// The bytes that [StdIn::write] would push for this value.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let words = openvm::serde::to_vec(self).map_err(|e| eyre!("{e}"))?;
+        Ok(words.into_iter().flat_map(|w| w.to_le_bytes()).collect())
+    }
+}
+
+fn elements(ty: &InputType, value: &Value, len: Option<usize>) -> Result<Vec<TypedValue>> {
+    let elems = match value {
+        Value::String(s) if *ty == InputType::U8 => {
+            if !is_valid_hex_string(s) {
+                bail!("invalid hex string {value}");
+            }
+            decode_hex_string(s)?
+                .into_iter()
+                .map(TypedValue::U8)
+                .collect()
+        }
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                TypedValue::new(ty, value).wrap_err_with(|| format!("invalid element {i}"))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        _ => bail!("expected an array, found {value}"),
+    };
+    if let Some(len) = len {
+        if elems.len() != len {
+            bail!("expected {len} elements, found {}", elems.len());
+        }
+    }
+    Ok(elems)
+}
+
+fn as_u128(value: &Value) -> Result<u128> {
+    let parsed = match value {
+        Value::Number(n) => n.as_u64().map(u128::from),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => u128::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    };
+    parsed.ok_or_else(|| eyre!("expected an unsigned integer, found {value}"))
+}
+
+fn as_i128(value: &Value) -> Result<i128> {
+    let parsed = match value {
+        Value::Number(n) => n.as_i64().map(i128::from),
+        Value::String(s) => match s.strip_prefix("0x") {
+            // Hex strings are the two's complement bit pattern, like `0xff` for `-1i8`.
+            Some(hex) => u128::from_str_radix(hex, 16).ok().map(|v| v as i128),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    };
+    parsed.ok_or_else(|| eyre!("expected an integer, found {value}"))
+}
+
+fn narrow<T: TryFrom<N>, N: fmt::Display + Copy>(n: N, ty: &InputType) -> Result<T> {
+    T::try_from(n).map_err(|_| eyre!("{n} is out of range for `{ty}`"))
+}
+
+impl Serialize for TypedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            TypedValue::Unit => serializer.serialize_unit(),
+            TypedValue::Bool(v) => serializer.serialize_bool(*v),
+            TypedValue::U8(v) => serializer.serialize_u8(*v),
+            TypedValue::U16(v) => serializer.serialize_u16(*v),
+            TypedValue::U32(v) => serializer.serialize_u32(*v),
+            TypedValue::U64(v) => serializer.serialize_u64(*v),
+            TypedValue::U128(v) => serializer.serialize_u128(*v),
+            TypedValue::I8(v) => serializer.serialize_i8(*v),
+            TypedValue::I16(v) => serializer.serialize_i16(*v),
+            TypedValue::I32(v) => serializer.serialize_i32(*v),
+            TypedValue::I64(v) => serializer.serialize_i64(*v),
+            TypedValue::I128(v) => serializer.serialize_i128(*v),
+            TypedValue::Char(v) => serializer.serialize_char(*v),
+            TypedValue::String(v) => serializer.serialize_str(v),
+            TypedValue::Option(None) => serializer.serialize_none(),
+            TypedValue::Option(Some(v)) => serializer.serialize_some(v),
+            TypedValue::Seq(elems) => serializer.collect_seq(elems),
+            TypedValue::Tuple(elems) => {
+                let mut tuple = serializer.serialize_tuple(elems.len())?;
+                for elem in elems {
+                    tuple.serialize_element(elem)?;
+                }
+                tuple.end()
+            }
+        }
+    }
+}
@@ -122,6 +122,26 @@ fn test_cli_app_e2e_simplified() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_prove_workers_conflict_with_checkpoint_dir() -> Result<()> {
+    run_cmd("cargo", &["install", "--path", ".", "--force"])?;
+    let checkpoint_dir = tempdir()?;
+    let result = run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "prove",
+            "app",
+            "--workers",
+            "2",
+            "--checkpoint-dir",
+            checkpoint_dir.path().to_str().unwrap(),
+        ],
+    );
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[test]
 fn test_cli_init_build() -> Result<()> {
     let temp_dir = tempdir()?;
@@ -0,0 +1,149 @@
+use std::fs::write;
+
+use cargo_openvm::input::{read_input_file, InputType};
+use eyre::Result;
+use openvm_sdk::{StdIn, F};
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use tempfile::tempdir;
+
+fn assert_same_stdin(actual: &StdIn, expected: &StdIn) {
+    assert_eq!(actual.buffer, expected.buffer);
+    assert_eq!(actual.kv_store, expected.kv_store);
+}
+
+#[test]
+fn test_typed_toml_input() -> Result<()> {
+    let dir = tempdir()?;
+    write(dir.path().join("blob.bin"), [0xde, 0xad, 0xbe, 0xef, 0x01])?;
+    let path = dir.path().join("input.toml");
+    write(
+        &path,
+        r#"
+[[input]]
+type = "(u64, String, Vec<u8>)"
+value = [18446744073709551, "hello", "0x0102ff"]
+
+[[input]]
+type = "Option<[i16; 3]>"
+value = [[-1, 2, -3]]
+
+[[input]]
+type = "(u128, i64, char, bool, Option<u32>, ())"
+value = ["0xffffffffffffffffffffffffffffffff", -5, "z", true, [], []]
+
+[[input]]
+type = "Vec<(u32, Vec<u64>)>"
+value = [[7, [1, 2]], [8, []]]
+
+[[input]]
+file = "blob.bin"
+
+[[input]]
+text = "raw"
+
+[[input]]
+fields = [1, 2, 2013265920]
+
+[[input]]
+bytes = "0xaabb"
+
+[[kv]]
+key = "config"
+value = { type = "(u32, String)", value = [3, "abc"] }
+
+[[kv]]
+key = { bytes = "0x00ff" }
+value = { file = "blob.bin" }
+"#,
+    )?;
+
+    let mut expected = StdIn::default();
+    expected.write(&(
+        18446744073709551u64,
+        "hello".to_string(),
+        vec![1u8, 2, 0xff],
+    ));
+    expected.write(&Some([-1i16, 2, -3]));
+    expected.write(&(u128::MAX, -5i64, 'z', true, None::<u32>, ()));
+    expected.write(&vec![(7u32, vec![1u64, 2]), (8, vec![])]);
+    expected.write_bytes(&[0xde, 0xad, 0xbe, 0xef, 0x01]);
+    expected.write_bytes(b"raw");
+    expected.write_field(&[F::ONE, F::TWO, F::from_canonical_u32(2013265920)]);
+    expected.write_bytes(&[0xaa, 0xbb]);
+    let kv_value: Vec<u8> = openvm::serde::to_vec(&(3u32, "abc".to_string()))
+        .unwrap()
+        .into_iter()
+        .flat_map(|w| w.to_le_bytes())
+        .collect();
+    expected.add_key_value(b"config".to_vec(), kv_value);
+    expected.add_key_value(vec![0x00, 0xff], vec![0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+    assert_same_stdin(&read_input_file(&path)?, &expected);
+    Ok(())
+}
+
+#[test]
+fn test_json_input_mixes_legacy_and_typed_entries() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("input.json");
+    write(
+        &path,
+        r#"{
+            "input": [
+                "0x01aabb",
+                "0x0203000000",
+                { "type": "Option<String>", "value": null },
+                { "type": "[u8; 2]", "value": [1, 2] }
+            ]
+        }"#,
+    )?;
+
+    let mut expected = StdIn::default();
+    expected.write_bytes(&[0xaa, 0xbb]);
+    expected.write_field(&[F::from_canonical_u32(3)]);
+    expected.write(&None::<String>);
+    expected.write(&[1u8, 2]);
+
+    assert_same_stdin(&read_input_file(&path)?, &expected);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_typed_input() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("input.toml");
+    for (entry, expected) in [
+        ("type = \"u8\"\nvalue = 256", "out of range"),
+        ("type = \"[u32; 2]\"\nvalue = [1]", "expected 2 elements"),
+        ("type = \"Vec<u32\"\nvalue = []", "expected `>`"),
+        ("type = \"f32\"\nvalue = 1", "unsupported type"),
+        ("text = \"a\"\nbytes = \"0x00\"", "exactly one of"),
+        (
+            "text = \"a\"\nvalue = 1",
+            "only allowed together with `type`",
+        ),
+    ] {
+        write(&path, format!("[[input]]\n{entry}\n"))?;
+        let err = format!("{:?}", read_input_file(&path).unwrap_err());
+        assert!(err.contains(expected), "{err} does not contain {expected}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_input_type_round_trip() -> Result<()> {
+    for ty in [
+        "u32",
+        "()",
+        "(u64, String, Vec<u8>)",
+        "Option<[i16; 3]>",
+        "Vec<Vec<(bool, char)>>",
+    ] {
+        assert_eq!(ty.parse::<InputType>()?.to_string(), ty);
+    }
+    assert_eq!(
+        " Vec < [ u8 ; 4 ] > ".parse::<InputType>()?,
+        InputType::Vec(Box::new(InputType::Array(Box::new(InputType::U8), 4)))
+    );
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::sync::{Condvar, Mutex};
+
+/// Caps the memory of the jobs proving at the same time. Jobs reserve their estimated memory
+/// before proving and wait until enough of the budget is free.
+pub struct MemoryBudget {
+    total: u64,
+    available: Mutex<u64>,
+    released: Condvar,
+}
+
+impl MemoryBudget {
+    pub fn new(total: u64) -> Self {
+        Self {
+            total,
+            available: Mutex::new(total),
+            released: Condvar::new(),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Blocks until `bytes` are available and reserves them until the returned guard is
+    /// dropped. Reservations larger than the budget are capped to it, so that such a job runs
+    /// alone instead of never running.
+    pub fn reserve(&self, bytes: u64) -> MemoryReservation<'_> {
+        let bytes = bytes.min(self.total);
+        let mut available = self
+            .released
+            .wait_while(self.available.lock().unwrap(), |available| {
+                *available < bytes
+            })
+            .unwrap();
+        *available -= bytes;
+        MemoryReservation {
+            budget: self,
+            bytes,
+        }
+    }
+
+    pub fn available(&self) -> u64 {
+        *self.available.lock().unwrap()
+    }
+}
+
+pub struct MemoryReservation<'a> {
+    budget: &'a MemoryBudget,
+    bytes: u64,
+}
+
+impl MemoryReservation<'_> {
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryReservation<'_> {
+    fn drop(&mut self) {
+        *self.budget.available.lock().unwrap() += self.bytes;
+        self.budget.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn test_reservations_wait_for_budget() {
+        let budget = Arc::new(MemoryBudget::new(10));
+        // Reservations larger than the budget are capped.
+        assert_eq!(budget.reserve(100).bytes(), 10);
+        let first = budget.reserve(6);
+        let waiter = {
+            let budget = budget.clone();
+            thread::spawn(move || budget.reserve(8).bytes())
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        assert_eq!(budget.available(), 4);
+        drop(first);
+        assert_eq!(waiter.join().unwrap(), 8);
+        assert_eq!(budget.available(), 10);
+    }
+}
@@ -0,0 +1,152 @@
+//! Just enough HTTP/1.1 for a local JSON API: one request per connection, bodies with a
+//! `Content-Length` and no percent-decoding of query parameters.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Read, Write},
+};
+
+use serde::Serialize;
+
+/// Upper bound on request bodies, which are program inputs.
+const MAX_BODY_LEN: usize = 1 << 30;
+const MAX_HEADER_LINES: usize = 100;
+
+pub struct Request {
+    pub method: String,
+    /// Path segments without the query, e.g. `["jobs", "3"]` for `/jobs/3?x=y`.
+    pub path: Vec<String>,
+    pub query: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec_pretty(value).unwrap(),
+        }
+    }
+
+    pub fn error(status: u16, message: impl ToString) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message.to_string() }))
+    }
+
+    pub fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body,
+        }
+    }
+}
+
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(bad_request("malformed request line"));
+    };
+    let method = method.to_string();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+    let query = query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+
+    let mut content_length = 0;
+    for _ in 0..MAX_HEADER_LINES {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            return Ok(Request {
+                method,
+                path,
+                query,
+                body,
+            });
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("invalid Content-Length"))?;
+                if content_length > MAX_BODY_LEN {
+                    return Err(bad_request("request body too large"));
+                }
+            }
+        }
+    }
+    Err(bad_request("too many headers"))
+}
+
+pub fn write_response(writer: &mut impl Write, response: &Response) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    )?;
+    writer.write_all(&response.body)?;
+    writer.flush()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
+
+fn bad_request(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let raw = b"POST /jobs/?exe_commit=0xab&target=stark HTTP/1.1\r\n\
+            Host: localhost\r\ncontent-length: 3\r\n\r\nabcdef";
+        let request = read_request(&mut &raw[..]).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, ["jobs"]);
+        assert_eq!(request.query["exe_commit"], "0xab");
+        assert_eq!(request.query["target"], "stark");
+        assert_eq!(request.body, b"abc");
+
+        assert!(read_request(&mut &b"GET\r\n\r\n"[..]).is_err());
+        assert!(read_request(&mut &b"GET / HTTP/1.1\r\nContent-Length: x\r\n\r\n"[..]).is_err());
+    }
+}
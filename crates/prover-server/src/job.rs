@@ -0,0 +1,279 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use eyre::{bail, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+const JOB_FILE_NAME: &str = "job.json";
+const INPUT_FILE_NAME: &str = "input.bin";
+
+pub type JobId = u64;
+
+/// The last proof a job produces. Each target includes the proofs of the targets before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofTarget {
+    /// App proof of every segment, as written by `cargo openvm prove app`.
+    App,
+    /// Aggregated STARK proof, as written by `cargo openvm prove stark`.
+    Stark,
+    /// Halo2 proof for on-chain verification, as written by `cargo openvm prove evm`.
+    Evm,
+}
+
+impl ProofTarget {
+    pub const ALL: [Self; 3] = [Self::App, Self::Stark, Self::Evm];
+
+    /// Name of the artifact of this target in the job directory and the HTTP API.
+    pub fn artifact_name(self) -> &'static str {
+        match self {
+            Self::App => "app.proof",
+            Self::Stark => "stark.proof",
+            Self::Evm => "evm.proof",
+        }
+    }
+}
+
+impl fmt::Display for ProofTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::App => write!(f, "app"),
+            Self::Stark => write!(f, "stark"),
+            Self::Evm => write!(f, "evm"),
+        }
+    }
+}
+
+impl FromStr for ProofTarget {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "app" => Ok(Self::App),
+            "stark" => Ok(Self::Stark),
+            "evm" => Ok(Self::Evm),
+            _ => bail!("unknown proof target {s:?}, expected app, stark or evm"),
+        }
+    }
+}
+
+/// The stages of a proving job, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Execution of the guest, to check that it succeeds and to size the job.
+    Execute,
+    /// App proofs of all segments.
+    AppSegments,
+    /// Leaf aggregation proofs.
+    Leaf,
+    /// Internal aggregation proofs, ending with the aggregated STARK proof.
+    Internal,
+    /// Wrapping and root verifier proof.
+    Root,
+    /// Halo2 proof of the root proof.
+    Halo2,
+}
+
+impl Stage {
+    pub const ALL: [Self; 6] = [
+        Self::Execute,
+        Self::AppSegments,
+        Self::Leaf,
+        Self::Internal,
+        Self::Root,
+        Self::Halo2,
+    ];
+
+    /// Returns whether a job with the given target runs this stage.
+    pub fn is_needed_for(self, target: ProofTarget) -> bool {
+        match self {
+            Self::Execute | Self::AppSegments => true,
+            Self::Leaf | Self::Internal => target >= ProofTarget::Stark,
+            Self::Root | Self::Halo2 => target >= ProofTarget::Evm,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Done,
+    /// Not needed for the target of the job.
+    Skipped,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StageRecord {
+    pub stage: Stage,
+    pub status: StageStatus,
+    /// Unix timestamps in seconds.
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// A proving job and its progress, persisted as `job.json` in the job directory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Job {
+    pub id: JobId,
+    /// Hex encoded app exe commit of the program to prove.
+    pub exe_commit: String,
+    pub target: ProofTarget,
+    pub state: JobState,
+    /// Unix timestamp in seconds.
+    pub submitted_at: u64,
+    pub stages: Vec<StageRecord>,
+    /// Number of segments, known after execution.
+    pub num_segments: Option<usize>,
+    /// Memory reserved for the job: a fixed amount while executing, then the proving memory
+    /// estimated after execution.
+    pub memory_reservation: Option<u64>,
+    /// Proofs which can be fetched.
+    pub artifacts: Vec<ProofTarget>,
+    pub error: Option<String>,
+}
+
+impl Job {
+    fn new(id: JobId, exe_commit: String, target: ProofTarget) -> Self {
+        let mut job = Self {
+            id,
+            exe_commit,
+            target,
+            state: JobState::Queued,
+            submitted_at: unix_time(),
+            stages: vec![],
+            num_segments: None,
+            memory_reservation: None,
+            artifacts: vec![],
+            error: None,
+        };
+        job.reset();
+        job
+    }
+
+    /// Resets the progress of the job, to run it again from the start.
+    pub fn reset(&mut self) {
+        self.state = JobState::Queued;
+        self.stages = Stage::ALL
+            .into_iter()
+            .map(|stage| StageRecord {
+                stage,
+                status: if stage.is_needed_for(self.target) {
+                    StageStatus::Pending
+                } else {
+                    StageStatus::Skipped
+                },
+                started_at: None,
+                finished_at: None,
+            })
+            .collect();
+        self.num_segments = None;
+        self.memory_reservation = None;
+        self.artifacts.clear();
+        self.error = None;
+    }
+
+    pub fn stage_mut(&mut self, stage: Stage) -> &mut StageRecord {
+        self.stages.iter_mut().find(|s| s.stage == stage).unwrap()
+    }
+
+    pub fn start_stage(&mut self, stage: Stage) {
+        let record = self.stage_mut(stage);
+        record.status = StageStatus::Running;
+        record.started_at = Some(unix_time());
+    }
+
+    pub fn finish_stage(&mut self, stage: Stage, status: StageStatus) {
+        let record = self.stage_mut(stage);
+        record.status = status;
+        record.finished_at = Some(unix_time());
+    }
+}
+
+/// Jobs persisted in a directory, one subdirectory per job with the job status, the input and
+/// the finished proofs.
+pub struct JobStore {
+    dir: PathBuf,
+}
+
+impl JobStore {
+    /// Opens the store in `dir`, creating the directory if it does not exist, and returns the
+    /// jobs in it sorted by id.
+    pub fn open(dir: impl AsRef<Path>) -> Result<(Self, Vec<Job>)> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .wrap_err_with(|| format!("Failed to create job directory {}", dir.display()))?;
+        let mut jobs = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let job_file = entry?.path().join(JOB_FILE_NAME);
+            if job_file.exists() {
+                let job = fs::read(&job_file)
+                    .map_err(eyre::Report::from)
+                    .and_then(|json| Ok(serde_json::from_slice::<Job>(&json)?))
+                    .wrap_err_with(|| format!("Failed to read job {}", job_file.display()))?;
+                jobs.push(job);
+            }
+        }
+        jobs.sort_by_key(|job| job.id);
+        Ok((Self { dir }, jobs))
+    }
+
+    /// Creates a job with the given id and persists its input, encoded like
+    /// [write_stdin_to_file](openvm_sdk::fs::write_stdin_to_file) does.
+    pub fn create(
+        &self,
+        id: JobId,
+        exe_commit: String,
+        target: ProofTarget,
+        input: &[u8],
+    ) -> Result<Job> {
+        let job = Job::new(id, exe_commit, target);
+        fs::create_dir_all(self.job_dir(id))?;
+        fs::write(self.input_path(id), input)?;
+        // The job file is written last, jobs without one are ignored when the store is opened.
+        self.save(&job)?;
+        Ok(job)
+    }
+
+    pub fn save(&self, job: &Job) -> Result<()> {
+        let path = self.job_dir(job.id).join(JOB_FILE_NAME);
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(job)?)?;
+        fs::rename(&tmp_path, &path)
+            .wrap_err_with(|| format!("Failed to write job {}", path.display()))
+    }
+
+    pub fn input_path(&self, id: JobId) -> PathBuf {
+        self.job_dir(id).join(INPUT_FILE_NAME)
+    }
+
+    pub fn artifact_path(&self, id: JobId, target: ProofTarget) -> PathBuf {
+        self.job_dir(id).join(target.artifact_name())
+    }
+
+    fn job_dir(&self, id: JobId) -> PathBuf {
+        self.dir.join(format!("{id:08}"))
+    }
+}
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
@@ -0,0 +1,358 @@
+//! A local proving service. Programs are loaded when the server starts, and proving jobs for
+//! them are submitted, monitored and collected through a small JSON API over HTTP:
+//!
+//! - `GET /programs` lists the loaded programs by app exe commit.
+//! - `POST /jobs?exe_commit=<hex>&target=<app|stark|evm>` submits a job, with the input in the
+//!   body, encoded like [write_stdin_to_file](openvm_sdk::fs::write_stdin_to_file) does.
+//! - `GET /jobs` lists all jobs and `GET /jobs/<id>` returns one job with the status of each of
+//!   its stages.
+//! - `GET /jobs/<id>/artifacts/<app|stark|evm>` returns a finished proof, in the same format as
+//!   `cargo openvm prove` writes it.
+//!
+//! Jobs are persisted in a directory, and jobs which were queued or running when the server
+//! stopped are run again from the start when it restarts. Jobs are run by a fixed number of
+//! workers, and only prove at the same time while their estimated memory fits in the budget.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs,
+    io::BufReader,
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+use eyre::{bail, eyre, Result};
+use openvm_circuit::arch::{instructions::exe::VmExe, VmConfig};
+#[cfg(feature = "evm-prove")]
+use openvm_sdk::prover::Halo2Prover;
+use openvm_sdk::{
+    commit::AppExecutionCommit,
+    config::{AggregationTreeConfig, SdkVmConfig},
+    keygen::{AggStarkProvingKey, AppProvingKey},
+    prover::{AggStarkProver, AppProver},
+    Sdk, StdIn, F,
+};
+use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Engine;
+use serde::Serialize;
+
+/// Memory budget shared by the jobs proving at the same time.
+mod budget;
+/// Minimal HTTP/1.1 request parsing and response writing.
+mod http;
+/// Persisted proving jobs and their stages.
+mod job;
+/// The proving stages of a job.
+mod prove;
+
+pub use budget::*;
+pub use job::*;
+
+use crate::http::{read_request, write_response, Request, Response};
+
+type E = BabyBearPoseidon2Engine;
+
+pub struct ServerConfig {
+    /// Directory the jobs, their inputs and their proofs are persisted in.
+    pub jobs_dir: PathBuf,
+    /// Number of jobs run at the same time, if the memory budget allows it.
+    pub workers: usize,
+    /// Total memory in bytes the jobs proving at the same time may use.
+    pub memory_budget: u64,
+    /// Estimated proving memory per trace cell of the largest segment, used to size the memory
+    /// reservation of a job after its execution.
+    pub bytes_per_trace_cell: u64,
+    pub agg_tree_config: AggregationTreeConfig,
+}
+
+impl ServerConfig {
+    pub const DEFAULT_BYTES_PER_TRACE_CELL: u64 = 64;
+
+    pub fn new(jobs_dir: impl Into<PathBuf>, memory_budget: u64) -> Self {
+        Self {
+            jobs_dir: jobs_dir.into(),
+            workers: 2,
+            memory_budget,
+            bytes_per_trace_cell: Self::DEFAULT_BYTES_PER_TRACE_CELL,
+            agg_tree_config: AggregationTreeConfig::default(),
+        }
+    }
+}
+
+/// A program which jobs can be submitted for.
+struct Program {
+    commit: AppExecutionCommit,
+    exe: VmExe<F>,
+    app_prover: AppProver<SdkVmConfig, E>,
+    /// `None` if the server has no aggregation proving key.
+    agg_prover: Option<AggStarkProver<E>>,
+}
+
+#[derive(Serialize)]
+struct ProgramSummary {
+    #[serde(flatten)]
+    commit: AppExecutionCommit,
+    targets: Vec<ProofTarget>,
+}
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: BTreeMap<JobId, Job>,
+    queued: VecDeque<JobId>,
+}
+
+pub struct ProverServer {
+    config: ServerConfig,
+    store: JobStore,
+    programs: HashMap<String, Arc<Program>>,
+    agg_stark_pk: Option<AggStarkProvingKey>,
+    #[cfg(feature = "evm-prove")]
+    halo2_prover: Option<Halo2Prover>,
+    queue: Mutex<JobQueue>,
+    job_queued: Condvar,
+    budget: MemoryBudget,
+}
+
+impl ProverServer {
+    /// Opens the job directory and queues the unfinished jobs in it. Without `agg_stark_pk`,
+    /// only app proofs can be generated.
+    pub fn new(config: ServerConfig, agg_stark_pk: Option<AggStarkProvingKey>) -> Result<Self> {
+        if config.workers == 0 {
+            bail!("the server needs at least one worker");
+        }
+        let (store, jobs) = JobStore::open(&config.jobs_dir)?;
+        let mut queue = JobQueue::default();
+        for mut job in jobs {
+            if matches!(job.state, JobState::Queued | JobState::Running) {
+                job.reset();
+                store.save(&job)?;
+                queue.queued.push_back(job.id);
+            }
+            queue.jobs.insert(job.id, job);
+        }
+        Ok(Self {
+            budget: MemoryBudget::new(config.memory_budget),
+            config,
+            store,
+            programs: HashMap::new(),
+            agg_stark_pk,
+            #[cfg(feature = "evm-prove")]
+            halo2_prover: None,
+            queue: Mutex::new(queue),
+            job_queued: Condvar::new(),
+        })
+    }
+
+    /// Enables EVM proofs.
+    #[cfg(feature = "evm-prove")]
+    pub fn set_halo2_prover(&mut self, halo2_prover: Halo2Prover) {
+        self.halo2_prover = Some(halo2_prover);
+    }
+
+    /// Loads a program and returns its hex encoded app exe commit, which jobs refer to it by.
+    pub fn add_program(
+        &mut self,
+        app_pk: AppProvingKey<SdkVmConfig>,
+        exe: VmExe<F>,
+    ) -> Result<String> {
+        let sdk = Sdk::new();
+        let committed_exe = sdk.commit_app_exe(app_pk.app_fri_params(), exe.clone())?;
+        let commit = AppExecutionCommit::compute(
+            &app_pk.app_vm_pk.vm_config,
+            &committed_exe,
+            &app_pk.leaf_committed_exe,
+        );
+        let agg_prover = match &self.agg_stark_pk {
+            Some(agg_stark_pk) => {
+                if app_pk.leaf_fri_params != agg_stark_pk.leaf_vm_pk.fri_params {
+                    bail!("the leaf FRI parameters of the app differ from the aggregation key");
+                }
+                if app_pk.app_vm_pk.vm_config.system().num_public_values
+                    != agg_stark_pk.num_user_public_values()
+                {
+                    bail!(
+                        "the number of public values of the app differs from the aggregation key"
+                    );
+                }
+                Some(AggStarkProver::new(
+                    agg_stark_pk.clone(),
+                    app_pk.leaf_committed_exe.clone(),
+                    self.config.agg_tree_config,
+                ))
+            }
+            None => None,
+        };
+        let exe_commit = hex::encode(commit.app_exe_commit.as_slice());
+        let program = Program {
+            commit,
+            exe,
+            app_prover: AppProver::new(app_pk.app_vm_pk, committed_exe),
+            agg_prover,
+        };
+        self.programs.insert(exe_commit.clone(), Arc::new(program));
+        Ok(exe_commit)
+    }
+
+    /// Returns all jobs sorted by id.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.queue.lock().unwrap().jobs.values().cloned().collect()
+    }
+
+    /// Starts the workers and serves the API on `listener` until it fails.
+    pub fn serve(self, listener: TcpListener) -> Result<()> {
+        let server = Arc::new(self);
+        for _ in 0..server.config.workers {
+            let server = server.clone();
+            thread::spawn(move || server.run_worker());
+        }
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = server.clone();
+            thread::spawn(move || server.handle_connection(stream));
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) {
+        let response = match read_request(&mut BufReader::new(&mut stream)) {
+            Ok(request) => self.handle(request),
+            Err(e) => Response::error(400, e),
+        };
+        if let Err(e) = write_response(&mut stream, &response) {
+            tracing::warn!("failed to write response: {e}");
+        }
+    }
+
+    fn handle(&self, request: Request) -> Response {
+        let path: Vec<&str> = request.path.iter().map(String::as_str).collect();
+        match (request.method.as_str(), path.as_slice()) {
+            ("GET", ["programs"]) => Response::json(200, &self.program_summaries()),
+            ("GET", ["jobs"]) => Response::json(200, &self.jobs()),
+            ("POST", ["jobs"]) => self.submit(&request),
+            ("GET", ["jobs", id]) => match self.job(id) {
+                Ok(job) => Response::json(200, &job),
+                Err(e) => Response::error(404, e),
+            },
+            ("GET", ["jobs", id, "artifacts", target]) => self.artifact(id, target),
+            (_, ["programs" | "jobs", ..]) => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn program_summaries(&self) -> Vec<ProgramSummary> {
+        let mut summaries: Vec<_> = self
+            .programs
+            .values()
+            .map(|program| ProgramSummary {
+                commit: program.commit,
+                targets: ProofTarget::ALL
+                    .into_iter()
+                    .filter(|&target| self.check_target(program, target).is_ok())
+                    .collect(),
+            })
+            .collect();
+        summaries.sort_by_key(|summary| *summary.commit.app_exe_commit.as_slice());
+        summaries
+    }
+
+    fn check_target(&self, program: &Program, target: ProofTarget) -> Result<()> {
+        if target >= ProofTarget::Stark && program.agg_prover.is_none() {
+            bail!("the server has no aggregation proving key for {target} proofs");
+        }
+        #[cfg(feature = "evm-prove")]
+        if target == ProofTarget::Evm && self.halo2_prover.is_none() {
+            bail!("the server has no halo2 proving key for evm proofs");
+        }
+        #[cfg(not(feature = "evm-prove"))]
+        if target == ProofTarget::Evm {
+            bail!("the server was built without the evm-prove feature");
+        }
+        Ok(())
+    }
+
+    fn submit(&self, request: &Request) -> Response {
+        let Some(exe_commit) = request.query.get("exe_commit") else {
+            return Response::error(400, "missing exe_commit parameter");
+        };
+        let exe_commit = exe_commit.trim_start_matches("0x").to_ascii_lowercase();
+        let Some(program) = self.programs.get(&exe_commit) else {
+            return Response::error(404, format!("no program with exe commit {exe_commit}"));
+        };
+        let target = match request
+            .query
+            .get("target")
+            .map_or(Ok(ProofTarget::Stark), |target| target.parse())
+        {
+            Ok(target) => target,
+            Err(e) => return Response::error(400, e),
+        };
+        if let Err(e) = self.check_target(program, target) {
+            return Response::error(400, e);
+        }
+        if let Err(e) = bitcode::deserialize::<StdIn>(&request.body) {
+            return Response::error(400, format!("invalid input: {e}"));
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+        let id = queue.jobs.keys().next_back().map_or(1, |id| id + 1);
+        match self.store.create(id, exe_commit, target, &request.body) {
+            Ok(job) => {
+                queue.jobs.insert(id, job.clone());
+                queue.queued.push_back(id);
+                self.job_queued.notify_one();
+                Response::json(201, &job)
+            }
+            Err(e) => Response::error(500, format!("failed to persist job: {e}")),
+        }
+    }
+
+    fn job(&self, id: &str) -> Result<Job> {
+        let queue = self.queue.lock().unwrap();
+        id.parse()
+            .ok()
+            .and_then(|id: JobId| queue.jobs.get(&id).cloned())
+            .ok_or_else(|| eyre!("no job with id {id}"))
+    }
+
+    fn artifact(&self, id: &str, target: &str) -> Response {
+        let job = match self.job(id) {
+            Ok(job) => job,
+            Err(e) => return Response::error(404, e),
+        };
+        let target: ProofTarget = match target.parse() {
+            Ok(target) => target,
+            Err(e) => return Response::error(404, e),
+        };
+        if !job.artifacts.contains(&target) {
+            return Response::error(409, format!("the {target} proof of job {id} is not ready"));
+        }
+        match fs::read(self.store.artifact_path(job.id, target)) {
+            Ok(bytes) => Response::bytes(bytes),
+            Err(e) => Response::error(500, format!("failed to read the {target} proof: {e}")),
+        }
+    }
+
+    fn run_worker(&self) {
+        loop {
+            let id = {
+                let mut queue = self
+                    .job_queued
+                    .wait_while(self.queue.lock().unwrap(), |queue| queue.queued.is_empty())
+                    .unwrap();
+                queue.queued.pop_front().unwrap()
+            };
+            self.run_job(id);
+        }
+    }
+
+    /// Applies `update` to the job and persists it.
+    fn update_job(&self, id: JobId, update: impl FnOnce(&mut Job)) {
+        let mut queue = self.queue.lock().unwrap();
+        let job = queue.jobs.get_mut(&id).unwrap();
+        update(job);
+        if let Err(e) = self.store.save(job) {
+            tracing::error!("failed to persist job {id}: {e:?}");
+        }
+    }
+}
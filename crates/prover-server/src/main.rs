@@ -0,0 +1,123 @@
+use std::{net::TcpListener, path::PathBuf};
+
+use clap::Parser;
+use eyre::{eyre, Result, WrapErr};
+use openvm_prover_server::{ProverServer, ServerConfig};
+use openvm_sdk::{
+    config::AggregationTreeConfig,
+    fs::{
+        read_agg_stark_pk_from_file, read_app_config_from_file, read_app_pk_from_file,
+        read_exe_from_file,
+    },
+    Sdk,
+};
+use openvm_stark_sdk::config::setup_tracing_with_log_level;
+use tracing::Level;
+
+/// Serves a local HTTP API to submit proving jobs, poll their progress and fetch their proofs.
+#[derive(Parser)]
+#[command(name = "openvm-prover-server")]
+struct ServerArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:3030")]
+    listen: String,
+
+    /// Directory to persist jobs, inputs and proofs in
+    #[arg(long)]
+    jobs_dir: PathBuf,
+
+    /// Program to accept jobs for, as `<EXE>,<CONFIG>` or `<EXE>,<CONFIG>,<APP_PK>` with the
+    /// transpiled program, its `openvm.toml` and optionally its app proving key, which is
+    /// otherwise generated. May be repeated
+    #[arg(long = "program", value_name = "EXE,CONFIG[,APP_PK]", required = true)]
+    programs: Vec<String>,
+
+    /// Path to the aggregation STARK proving key, required for STARK and EVM proofs
+    #[arg(long)]
+    agg_stark_pk: Option<PathBuf>,
+
+    /// Path to the halo2 proving key, required for EVM proofs
+    #[cfg(feature = "evm-prove")]
+    #[arg(long, requires = "agg_stark_pk")]
+    halo2_pk: Option<PathBuf>,
+
+    /// Directory of the KZG parameters for EVM proofs
+    #[cfg(feature = "evm-prove")]
+    #[arg(long, requires = "halo2_pk")]
+    params_dir: Option<PathBuf>,
+
+    /// Number of jobs to run at the same time, if the memory budget allows it
+    #[arg(long, default_value_t = 2)]
+    workers: usize,
+
+    /// Memory in GiB the jobs proving at the same time may use in total
+    #[arg(long)]
+    memory_budget_gib: u64,
+
+    /// Estimated proving memory per trace cell of the largest segment of a job
+    #[arg(long, default_value_t = ServerConfig::DEFAULT_BYTES_PER_TRACE_CELL)]
+    bytes_per_trace_cell: u64,
+
+    #[command(flatten)]
+    agg_tree_config: AggregationTreeConfig,
+}
+
+fn main() -> Result<()> {
+    setup_tracing_with_log_level(Level::INFO);
+    let args = ServerArgs::parse();
+
+    let config = ServerConfig {
+        jobs_dir: args.jobs_dir,
+        workers: args.workers,
+        memory_budget: args.memory_budget_gib << 30,
+        bytes_per_trace_cell: args.bytes_per_trace_cell,
+        agg_tree_config: args.agg_tree_config,
+    };
+    let agg_stark_pk = args
+        .agg_stark_pk
+        .as_ref()
+        .map(read_agg_stark_pk_from_file)
+        .transpose()?;
+    let mut server = ProverServer::new(config, agg_stark_pk)?;
+
+    #[cfg(feature = "evm-prove")]
+    if let Some(halo2_pk) = &args.halo2_pk {
+        use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
+        use openvm_sdk::{fs::read_agg_halo2_pk_from_file, prover::Halo2Prover};
+
+        let params_dir = args
+            .params_dir
+            .as_ref()
+            .ok_or_else(|| eyre!("--params-dir is required with --halo2-pk"))?;
+        let halo2_pk = read_agg_halo2_pk_from_file(halo2_pk)?;
+        let reader = CacheHalo2ParamsReader::new(params_dir);
+        server.set_halo2_prover(Halo2Prover::new(&reader, halo2_pk));
+    }
+
+    for program in &args.programs {
+        let paths: Vec<&str> = program.split(',').collect();
+        let (exe, config, app_pk) = match paths.as_slice() {
+            [exe, config] => (exe, config, None),
+            [exe, config, app_pk] => (exe, config, Some(app_pk)),
+            _ => {
+                return Err(eyre!(
+                    "expected EXE,CONFIG[,APP_PK] for --program, got {program}"
+                ))
+            }
+        };
+        let exe = read_exe_from_file(exe)?;
+        let app_pk = match app_pk {
+            Some(app_pk) => read_app_pk_from_file(app_pk)?,
+            None => Sdk::new().app_keygen(read_app_config_from_file(config)?)?,
+        };
+        let exe_commit = server
+            .add_program(app_pk, exe)
+            .wrap_err_with(|| format!("Failed to load program {program}"))?;
+        tracing::info!("loaded program {program} with exe commit {exe_commit}");
+    }
+
+    let listener = TcpListener::bind(&args.listen)
+        .wrap_err_with(|| format!("Failed to listen on {}", args.listen))?;
+    tracing::info!("listening on {}", listener.local_addr()?);
+    server.serve(listener)
+}
@@ -0,0 +1,161 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use eyre::{bail, eyre, Result};
+use openvm_circuit::arch::ExitCode;
+#[cfg(feature = "evm-prove")]
+use openvm_sdk::fs::write_evm_proof_to_file;
+use openvm_sdk::{
+    fs::{read_stdin_from_file, write_app_proof_to_file, write_to_file_json},
+    types::VmStarkProofBytes,
+    Sdk, StdIn,
+};
+
+use crate::{Job, JobId, JobState, Program, ProofTarget, ProverServer, Stage, StageStatus};
+
+/// Memory reserved at least per job, for the aggregation stages whose size does not depend on
+/// the program. Execution reserves this much as well.
+const MIN_JOB_MEMORY: u64 = 1 << 30;
+
+impl ProverServer {
+    pub(crate) fn run_job(&self, id: JobId) {
+        let job = self.queue.lock().unwrap().jobs[&id].clone();
+        self.update_job(id, |job| job.state = JobState::Running);
+        tracing::info!(
+            "running job {id} for {} proof of {}",
+            job.target,
+            job.exe_commit
+        );
+
+        let result = match self.programs.get(&job.exe_commit) {
+            Some(program) => catch_unwind(AssertUnwindSafe(|| self.prove_job(&job, program)))
+                .unwrap_or_else(|panic| {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    Err(eyre!("proving panicked: {message}"))
+                }),
+            None => Err(eyre!("the program is no longer loaded")),
+        };
+        self.update_job(id, |job| match result {
+            Ok(()) => job.state = JobState::Done,
+            Err(e) => {
+                tracing::error!("job {id} failed: {e:?}");
+                for stage in &mut job.stages {
+                    if stage.status == StageStatus::Running {
+                        stage.status = StageStatus::Failed;
+                    }
+                }
+                job.state = JobState::Failed;
+                job.error = Some(format!("{e:#}"));
+            }
+        });
+    }
+
+    fn prove_job(&self, job: &Job, program: &Program) -> Result<()> {
+        let id = job.id;
+        let input = read_stdin_from_file(self.store.input_path(id))?;
+
+        // Execution is accounted for with the minimum reservation of a job. It is released
+        // before reserving the memory of the proof, so that jobs waiting for more memory cannot
+        // hold on to it and deadlock.
+        let execution_reservation = self.budget.reserve(MIN_JOB_MEMORY);
+        self.update_job(id, |job| {
+            job.memory_reservation = Some(execution_reservation.bytes());
+            job.start_stage(Stage::Execute);
+        });
+        let report = Sdk::new().execute_with_report(
+            program.exe.clone(),
+            program.app_prover.vm_config().clone(),
+            input.clone(),
+            true,
+        )?;
+        if report.exit_code != ExitCode::Success as u32 {
+            bail!("guest exited with code {}", report.exit_code);
+        }
+        let max_segment_cells = report
+            .segments
+            .iter()
+            .map(|segment| segment.airs.iter().map(|air| air.cells as u64).sum::<u64>())
+            .max()
+            .unwrap_or(0);
+        let estimate = (max_segment_cells * self.config.bytes_per_trace_cell).max(MIN_JOB_MEMORY);
+        self.update_job(id, |job| {
+            job.num_segments = Some(report.num_segments);
+            job.finish_stage(Stage::Execute, StageStatus::Done);
+        });
+        drop(execution_reservation);
+
+        let reservation = self.budget.reserve(estimate);
+        self.update_job(id, |job| {
+            job.memory_reservation = Some(reservation.bytes());
+            job.start_stage(Stage::AppSegments);
+        });
+        self.prove_stages(job, program, input)
+    }
+
+    fn prove_stages(&self, job: &Job, program: &Program, input: StdIn) -> Result<()> {
+        let id = job.id;
+        let app_proof = program.app_prover.generate_app_proof(input);
+        write_app_proof_to_file(
+            app_proof.clone(),
+            self.store.artifact_path(id, ProofTarget::App),
+        )?;
+        self.finish_stage(id, Stage::AppSegments, ProofTarget::App, Stage::Leaf);
+        if job.target == ProofTarget::App {
+            return Ok(());
+        }
+
+        let agg_prover = program.agg_prover.as_ref().unwrap();
+        let leaf_proofs = agg_prover.generate_leaf_proofs(&app_proof);
+        self.update_job(id, |job| {
+            job.finish_stage(Stage::Leaf, StageStatus::Done);
+            job.start_stage(Stage::Internal);
+        });
+        let e2e_stark_proof = agg_prover
+            .aggregate_leaf_proofs(leaf_proofs, app_proof.user_public_values.public_values);
+        write_to_file_json(
+            self.store.artifact_path(id, ProofTarget::Stark),
+            VmStarkProofBytes::new(program.commit, e2e_stark_proof.clone())?,
+        )?;
+        self.finish_stage(id, Stage::Internal, ProofTarget::Stark, Stage::Root);
+        if job.target == ProofTarget::Stark {
+            return Ok(());
+        }
+
+        #[cfg(feature = "evm-prove")]
+        {
+            let root_input = agg_prover.wrap_e2e_stark_proof(e2e_stark_proof);
+            let root_proof = agg_prover.generate_root_proof_from_verifier_input(root_input);
+            self.update_job(id, |job| {
+                job.finish_stage(Stage::Root, StageStatus::Done);
+                job.start_stage(Stage::Halo2);
+            });
+            let evm_proof = self
+                .halo2_prover
+                .as_ref()
+                .unwrap()
+                .prove_for_evm(&root_proof);
+            write_evm_proof_to_file(evm_proof, self.store.artifact_path(id, ProofTarget::Evm))?;
+            self.update_job(id, |job| {
+                job.finish_stage(Stage::Halo2, StageStatus::Done);
+                job.artifacts.push(ProofTarget::Evm);
+            });
+            return Ok(());
+        }
+        #[cfg(not(feature = "evm-prove"))]
+        bail!("the server was built without the evm-prove feature")
+    }
+
+    /// Marks `stage` as done with the proof of `target` available, and starts `next`.
+    fn finish_stage(&self, id: JobId, stage: Stage, target: ProofTarget, next: Stage) {
+        self.update_job(id, |job| {
+            job.finish_stage(stage, StageStatus::Done);
+            job.artifacts.push(target);
+            if job.target > target {
+                job.start_stage(next);
+            }
+        });
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::{ContinuationVmProof, SystemConfig};
+use openvm_native_compiler::{asm::AsmBuilder, ir::Felt};
+use openvm_prover_server::{JobState, ProverServer, ServerConfig, Stage, StageStatus};
+use openvm_sdk::{
+    codec::Decode,
+    config::{AppConfig, SdkSystemConfig, SdkVmConfig},
+    fs::write_stdin_to_file,
+    Sdk, StdIn, F, SC,
+};
+use openvm_stark_sdk::{
+    config::FriParameters,
+    openvm_stark_backend::p3_field::{extension::BinomialExtensionField, FieldAlgebra},
+};
+use serde_json::Value;
+
+/// Sends a request and returns the status code and the body of the response.
+fn request(addr: SocketAddr, method: &str, path: &str, body: &[u8]) -> Result<(u16, Vec<u8>)> {
+    let mut stream = TcpStream::connect(addr)?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    let mut response = vec![];
+    stream.read_to_end(&mut response)?;
+    let header_end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let status_line = String::from_utf8_lossy(&response[..header_end]);
+    let status = status_line.split_whitespace().nth(1).unwrap().parse()?;
+    Ok((status, response[header_end + 4..].to_vec()))
+}
+
+fn get_json(addr: SocketAddr, path: &str) -> Result<Value> {
+    let (status, body) = request(addr, "GET", path, &[])?;
+    assert_eq!(status, 200);
+    Ok(serde_json::from_slice(&body)?)
+}
+
+fn serve(server: ProverServer) -> Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    thread::spawn(move || server.serve(listener));
+    Ok(addr)
+}
+
+#[test]
+fn test_app_proof_job() -> Result<()> {
+    let program = {
+        let mut builder = AsmBuilder::<F, BinomialExtensionField<F, 4>>::default();
+        let a: Felt<F> = builder.eval(F::ZERO);
+        let b: Felt<F> = builder.eval(F::ONE);
+        let c: Felt<F> = builder.uninit();
+        builder.range(0, 200).for_each(|_, builder| {
+            builder.assign(&c, a + b);
+            builder.assign(&a, b);
+            builder.assign(&b, c);
+        });
+        builder.halt();
+        builder.compile_isa()
+    };
+    let vm_config = SdkVmConfig::builder()
+        .system(SdkSystemConfig {
+            config: SystemConfig::default()
+                .with_max_segment_len(200)
+                .with_continuations(),
+        })
+        .native(Default::default())
+        .build();
+    let sdk = Sdk::new();
+    let app_pk = sdk.app_keygen(AppConfig::new(FriParameters::standard_fast(), vm_config))?;
+    let app_vk = app_pk.get_app_vk();
+
+    let dir = tempfile::tempdir()?;
+    let jobs_dir = dir.path().join("jobs");
+    let mut server = ProverServer::new(ServerConfig::new(&jobs_dir, 4 << 30), None)?;
+    let exe_commit = server.add_program(app_pk, program.into())?;
+    let addr = serve(server)?;
+
+    let programs = get_json(addr, "/programs")?;
+    assert_eq!(programs[0]["app_exe_commit"], exe_commit.as_str());
+    assert_eq!(programs[0]["targets"], serde_json::json!(["app"]));
+
+    let input_path = dir.path().join("input.bin");
+    write_stdin_to_file(&StdIn::default(), &input_path)?;
+    let input = std::fs::read(&input_path)?;
+    let submit = |query: &str, body: &[u8]| request(addr, "POST", &format!("/jobs?{query}"), body);
+    assert_eq!(submit("exe_commit=00&target=app", &input)?.0, 404);
+    let stark_query = format!("exe_commit={exe_commit}&target=stark");
+    assert_eq!(submit(&stark_query, &input)?.0, 400);
+    let app_query = format!("exe_commit=0x{exe_commit}&target=app");
+    assert_eq!(submit(&app_query, b"not an input")?.0, 400);
+
+    let (status, body) = submit(&app_query, &input)?;
+    assert_eq!(status, 201);
+    let id = serde_json::from_slice::<Value>(&body)?["id"]
+        .as_u64()
+        .unwrap();
+    let mut job = get_json(addr, &format!("/jobs/{id}"))?;
+    for _ in 0..600 {
+        if job["state"] == "done" || job["state"] == "failed" {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+        job = get_json(addr, &format!("/jobs/{id}"))?;
+    }
+    assert_eq!(job["state"], "done", "{job}");
+    assert!(job["num_segments"].as_u64().unwrap() > 1);
+    let statuses: Vec<_> = job["stages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|stage| stage["status"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        statuses,
+        ["done", "done", "skipped", "skipped", "skipped", "skipped"]
+    );
+
+    let (status, proof) = request(addr, "GET", &format!("/jobs/{id}/artifacts/app"), &[])?;
+    assert_eq!(status, 200);
+    let proof = ContinuationVmProof::<SC>::decode_from_bytes(&proof)?;
+    sdk.verify_app_proof(&app_vk, &proof)?;
+    let stark_artifact = format!("/jobs/{id}/artifacts/stark");
+    assert_eq!(request(addr, "GET", &stark_artifact, &[])?.0, 409);
+
+    // Finished jobs are kept when the server restarts.
+    let server = ProverServer::new(ServerConfig::new(&jobs_dir, 4 << 30), None)?;
+    let jobs = server.jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].state, JobState::Done);
+    assert_eq!(
+        jobs[0].stages[0].stage,
+        Stage::Execute,
+        "stages are listed in order"
+    );
+    assert_eq!(jobs[0].stages[1].status, StageStatus::Done);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Runner for RISC-V architectural compliance tests ([riscv-arch-test]).
+//!
+//! An architectural test is a self-checking ELF which writes the results of the instructions under
+//! test into a memory region delimited by the `begin_signature` and `end_signature` symbols. The
+//! test passes if that region matches a reference signature produced by a golden model.
+//!
+//! [riscv-arch-test]: https://github.com/riscv-non-isa/riscv-arch-test
+use std::{fmt, str::FromStr};
+
+use openvm_circuit::{
+    arch::{instructions::exe::VmExe, ExecutionError, ExitCode, VmExecutor},
+    system::memory::Rv32MemoryView,
+};
+use openvm_transpiler::{elf::Elf, openvm_platform::memory::MEM_SIZE, FromElf};
+use thiserror::Error;
+
+use crate::{
+    config::{SdkVmConfig, UnitStruct},
+    StdIn, F,
+};
+
+/// Symbol marking the start (inclusive) of the signature region.
+pub const BEGIN_SIGNATURE_SYMBOL: &str = "begin_signature";
+/// Symbol marking the end (exclusive) of the signature region.
+pub const END_SIGNATURE_SYMBOL: &str = "end_signature";
+/// Symbol of the host communication word used by `riscv-tests` style environments.
+pub const TOHOST_SYMBOL: &str = "tohost";
+
+#[derive(Error, Debug)]
+pub enum ArchTestError {
+    #[error("failed to decode ELF: {0}")]
+    Elf(eyre::Report),
+    #[error("symbol `{0}` not found in ELF")]
+    MissingSymbol(&'static str),
+    #[error("invalid signature bounds [{begin:#x}, {end:#x})")]
+    InvalidSignatureBounds { begin: u32, end: u32 },
+    #[error(transparent)]
+    Transpiler(#[from] openvm_transpiler::transpiler::TranspilerError),
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+}
+
+/// Signature region of an architectural test, as 32-bit little-endian words.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArchTestSignature {
+    pub words: Vec<u32>,
+}
+
+/// First difference between a signature and its reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureMismatch {
+    Length {
+        expected: usize,
+        actual: usize,
+    },
+    Word {
+        index: usize,
+        expected: u32,
+        actual: u32,
+    },
+}
+
+impl ArchTestSignature {
+    /// Returns the first mismatch against `reference`, or `None` if the signatures are equal.
+    pub fn diff(&self, reference: &ArchTestSignature) -> Option<SignatureMismatch> {
+        if let Some((index, (&actual, &expected))) = self
+            .words
+            .iter()
+            .zip(reference.words.iter())
+            .enumerate()
+            .find(|(_, (actual, expected))| actual != expected)
+        {
+            return Some(SignatureMismatch::Word {
+                index,
+                expected,
+                actual,
+            });
+        }
+        (self.words.len() != reference.words.len()).then_some(SignatureMismatch::Length {
+            expected: reference.words.len(),
+            actual: self.words.len(),
+        })
+    }
+}
+
+/// Formats the signature in the reference output format: one 8-digit hex word per line.
+impl fmt::Display for ArchTestSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for word in &self.words {
+            writeln!(f, "{word:08x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ArchTestSignature {
+    type Err = std::num::ParseIntError;
+
+    /// Parses a reference output file. Empty lines are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| u32::from_str_radix(line, 16))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { words })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchTestStatus {
+    Passed,
+    /// The test terminated with a non-zero exit code or reported a failure through `tohost`.
+    Failed {
+        exit_code: u32,
+        tohost: Option<u32>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct ArchTestResult {
+    pub status: ArchTestStatus,
+    pub signature: ArchTestSignature,
+    /// Address of `begin_signature`.
+    pub signature_begin: u32,
+}
+
+impl ArchTestResult {
+    pub fn is_pass(&self) -> bool {
+        self.status == ArchTestStatus::Passed
+    }
+}
+
+/// Runs architectural test ELFs on the OpenVM executor and extracts their signatures.
+#[derive(Clone, Debug)]
+pub struct ArchTestHarness {
+    vm_config: SdkVmConfig,
+    max_mem: u32,
+}
+
+impl Default for ArchTestHarness {
+    /// Harness for the RV32IM tests.
+    fn default() -> Self {
+        let vm_config = SdkVmConfig::builder()
+            .system(Default::default())
+            .rv32i(UnitStruct {})
+            .rv32m(Default::default())
+            .io(UnitStruct {})
+            .build();
+        Self::new(vm_config)
+    }
+}
+
+impl ArchTestHarness {
+    pub fn new(vm_config: SdkVmConfig) -> Self {
+        Self {
+            vm_config,
+            max_mem: MEM_SIZE as u32,
+        }
+    }
+
+    /// Sets the maximum memory address accepted when decoding test ELFs.
+    pub fn with_max_mem(mut self, max_mem: u32) -> Self {
+        self.max_mem = max_mem;
+        self
+    }
+
+    pub fn vm_config(&self) -> &SdkVmConfig {
+        &self.vm_config
+    }
+
+    /// Decodes, transpiles and executes the test ELF, returning its signature and status.
+    ///
+    /// A test fails if it terminates with a non-zero exit code, or if it wrote a `riscv-tests`
+    /// failure code (any value other than `0` or `1`) into `tohost`. Executions that do not
+    /// terminate at all are reported as [ArchTestError::Execution].
+    pub fn run(&self, elf_bytes: &[u8]) -> Result<ArchTestResult, ArchTestError> {
+        let elf = Elf::decode(elf_bytes, self.max_mem).map_err(ArchTestError::Elf)?;
+        let begin = elf
+            .symbol_address(BEGIN_SIGNATURE_SYMBOL)
+            .ok_or(ArchTestError::MissingSymbol(BEGIN_SIGNATURE_SYMBOL))?;
+        let end = elf
+            .symbol_address(END_SIGNATURE_SYMBOL)
+            .ok_or(ArchTestError::MissingSymbol(END_SIGNATURE_SYMBOL))?;
+        if end < begin || (end - begin) % 4 != 0 {
+            return Err(ArchTestError::InvalidSignatureBounds { begin, end });
+        }
+        let tohost = elf.symbol_address(TOHOST_SYMBOL);
+
+        let exe = VmExe::from_elf(elf, self.vm_config.transpiler())?;
+        let executor = VmExecutor::<F, _>::new(self.vm_config.clone());
+        let mut last = None;
+        executor.execute_and_then(
+            exe,
+            StdIn::default(),
+            |_, seg| {
+                last = Some(seg);
+                Ok(())
+            },
+            |err| err,
+        )?;
+        let last = last.expect("at least one segment must be executed");
+        let end_state =
+            last.chip_complex.connector_chip().boundary_states[1].expect("end state must be set");
+        if end_state.is_terminate != 1 {
+            return Err(ExecutionError::DidNotTerminate.into());
+        }
+        let memory = last
+            .final_memory
+            .expect("final memory must be set on the last segment");
+
+        let view = Rv32MemoryView::new(&memory);
+        let words = view.read_words(begin, ((end - begin) / 4) as usize);
+        let tohost = tohost.map(|addr| view.read_u32(addr));
+        let exit_code = end_state.exit_code;
+        let status = if exit_code == ExitCode::Success as u32 && tohost.is_none_or(|v| v <= 1) {
+            ArchTestStatus::Passed
+        } else {
+            ArchTestStatus::Failed { exit_code, tohost }
+        };
+        Ok(ArchTestResult {
+            status,
+            signature: ArchTestSignature { words },
+            signature_begin: begin,
+        })
+    }
+}
@@ -1,3 +1,110 @@
-fn main() {
-    println!("Hello, world!");
+use std::{path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use eyre::{bail, Result};
+use openvm_circuit::arch::ExitCode;
+use openvm_sdk::{
+    commit::AppExecutionCommit,
+    fs::{
+        read_app_config_from_file, read_app_pk_from_file, read_exe_from_file, read_stdin_from_file,
+        write_app_proof_to_file, write_to_file_json,
+    },
+    types::ExecutionReport,
+    Sdk, StdIn,
+};
+use serde::Serialize;
+
+/// Executes a transpiled program, generates an app proof and reports the execution and the
+/// proof as JSON.
+#[derive(Debug, Parser)]
+struct AppProverArgs {
+    /// Path to the transpiled program
+    #[arg(long)]
+    exe: PathBuf,
+
+    /// Path to the app config in the `openvm.toml` format
+    #[arg(long)]
+    config: PathBuf,
+
+    /// Path to the program input, written with `openvm_sdk::fs::write_stdin_to_file`
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Path to the app proving key, by default it is generated from the app config
+    #[arg(long)]
+    app_pk: Option<PathBuf>,
+
+    /// Path to write the app proof to
+    #[arg(long)]
+    proof: PathBuf,
+
+    /// Path to write the JSON report to, by default it is printed to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct AppProverReport {
+    execution: ExecutionReport,
+    /// `None` if the guest exited with a non-zero exit code, which cannot be proven.
+    proof: Option<AppProofReport>,
+}
+
+#[derive(Serialize)]
+struct AppProofReport {
+    path: PathBuf,
+    num_segments: usize,
+    #[serde(flatten)]
+    commit: AppExecutionCommit,
+}
+
+fn main() -> Result<()> {
+    let args = AppProverArgs::parse();
+    let sdk = Sdk::new();
+    let app_config = read_app_config_from_file(&args.config)?;
+    let exe = read_exe_from_file(&args.exe)?;
+    let inputs = match &args.input {
+        Some(input) => read_stdin_from_file(input)?,
+        None => StdIn::default(),
+    };
+
+    let execution = sdk.execute_with_report(
+        exe.clone(),
+        app_config.app_vm_config.clone(),
+        inputs.clone(),
+        false,
+    )?;
+    let proof = if execution.exit_code == ExitCode::Success as u32 {
+        let app_pk = match &args.app_pk {
+            Some(app_pk) => read_app_pk_from_file(app_pk)?,
+            None => sdk.app_keygen(app_config)?,
+        };
+        let committed_exe = sdk.commit_app_exe(app_pk.app_fri_params(), exe)?;
+        let commit = AppExecutionCommit::compute(
+            &app_pk.app_vm_pk.vm_config,
+            &committed_exe,
+            &app_pk.leaf_committed_exe,
+        );
+        let app_proof = sdk.generate_app_proof(Arc::new(app_pk), committed_exe, inputs)?;
+        let num_segments = app_proof.per_segment.len();
+        write_app_proof_to_file(app_proof, &args.proof)?;
+        Some(AppProofReport {
+            path: args.proof.clone(),
+            num_segments,
+            commit,
+        })
+    } else {
+        None
+    };
+
+    let exit_code = execution.exit_code;
+    let report = AppProverReport { execution, proof };
+    match &args.output {
+        Some(output) => write_to_file_json(output, &report)?,
+        None => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    if report.proof.is_none() {
+        bail!("guest exited with code {exit_code}, no proof was generated");
+    }
+    Ok(())
 }
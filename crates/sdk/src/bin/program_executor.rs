@@ -1,16 +1,55 @@
 use std::path::PathBuf;
 
 use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{
+    fs::{read_app_config_from_file, read_exe_from_file, read_stdin_from_file, write_to_file_json},
+    Sdk, StdIn,
+};
 
+/// Executes a transpiled program and reports the exit code, public values, cycle counts and
+/// segments as JSON.
 #[derive(Debug, Parser)]
 struct ExecutorArgs {
+    /// Path to the transpiled program
     #[arg(long)]
-    program_dir: PathBuf,
-    // input -> what type?
+    exe: PathBuf,
+
+    /// Path to the app config in the `openvm.toml` format
+    #[arg(long)]
+    config: PathBuf,
+
+    /// Path to the program input, written with `openvm_sdk::fs::write_stdin_to_file`
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Also report the trace heights and estimated proving cost of every AIR
+    #[arg(long)]
+    trace_heights: bool,
+
+    /// Path to write the JSON report to, by default it is printed to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
 }
 
-fn main() {
-    let _args = ExecutorArgs::parse();
-    // 1. get the exe from program dir
-    println!("Hello, world!");
+fn main() -> Result<()> {
+    let args = ExecutorArgs::parse();
+    let app_config = read_app_config_from_file(&args.config)?;
+    let exe = read_exe_from_file(&args.exe)?;
+    let inputs = match &args.input {
+        Some(input) => read_stdin_from_file(input)?,
+        None => StdIn::default(),
+    };
+
+    let report = Sdk::new().execute_with_report(
+        exe,
+        app_config.app_vm_config,
+        inputs,
+        args.trace_heights,
+    )?;
+    match &args.output {
+        Some(output) => write_to_file_json(output, report)?,
+        None => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(())
 }
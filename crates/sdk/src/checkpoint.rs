@@ -0,0 +1,370 @@
+//! Checkpoints which let a long proof resume after the prover was interrupted.
+//!
+//! A [CheckpointDir] holds the progress of a single proof:
+//! - `app/progress.bin`: the hash of the input, the number of proven segments and the state to
+//!   start the next one from
+//! - `app/segment-<idx>.proof`: the proof of each finished segment
+//! - `app/public_values.proof`: the user public values proof, once all segments are proven
+//! - `agg/tree.json`: the aggregation tree shape and the hashes of the app proof and of the leaf
+//!   proofs the aggregation proofs were generated from
+//! - `agg/<layer>/<idx>.proof`: the proof of each finished leaf or internal node
+//!
+//! Every file is written to a temporary path and then renamed, so that a checkpoint is never
+//! read half-written. Checkpoints of another input or app proof are discarded when resuming.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use eyre::{bail, Result, WrapErr};
+use openvm_circuit::{
+    arch::VmSegmentCheckpoint, system::memory::tree::public_values::UserPublicValuesProof,
+};
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    codec::{Decode, Encode},
+    StdIn, F, SC,
+};
+
+const APP_DIR: &str = "app";
+const AGG_DIR: &str = "agg";
+const APP_PROGRESS_FILE: &str = "progress.bin";
+const APP_PUBLIC_VALUES_FILE: &str = "public_values.proof";
+const AGG_TREE_FILE: &str = "tree.json";
+
+/// A directory to persist the progress of a proof in, and to resume it from. Only the `app` and
+/// `agg` subdirectories are written to.
+#[derive(Clone, Debug)]
+pub struct CheckpointDir {
+    dir: PathBuf,
+}
+
+/// The progress of an app proof.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AppProgress {
+    /// Commitment to the program the segments are proven for.
+    pub program_commit: [F; DIGEST_SIZE],
+    /// Hash of the input the segments are proven for, see [hash_input].
+    pub input_hash: [u8; 32],
+    /// Number of times the segmentation was made stricter because a segment was too large.
+    pub segmentation_retries: usize,
+    /// Number of segments proven so far.
+    pub num_segments: usize,
+    /// State to execute the next segment from, or `None` once the last segment is proven.
+    pub next_state: Option<VmSegmentCheckpoint<F>>,
+}
+
+/// The shape of the aggregation tree and the proofs it aggregates, which determine the inputs of
+/// each aggregation proof.
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct AggTree {
+    num_children_leaf: usize,
+    num_children_internal: usize,
+    /// Hash of the app proof the leaf proofs are generated from.
+    app_proof_hash: Option<[u8; 32]>,
+    /// Hash of the leaf proofs the internal proofs are generated from.
+    leaf_proofs_hash: Option<[u8; 32]>,
+}
+
+impl CheckpointDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Deletes all checkpoints, e.g. once the proof is written. Other files in the directory are
+    /// left untouched.
+    pub fn remove(&self) -> Result<()> {
+        remove_dir_if_exists(&self.app_dir())?;
+        remove_dir_if_exists(&self.agg_dir())
+    }
+
+    /// Returns the saved progress of the app proof of the program with `program_commit` and the
+    /// proofs of its finished segments. Discards the progress made for another input.
+    pub(crate) fn load_app_progress(
+        &self,
+        program_commit: [F; DIGEST_SIZE],
+        input_hash: [u8; 32],
+    ) -> Result<Option<(AppProgress, Vec<Proof<SC>>)>> {
+        let path = self.app_dir().join(APP_PROGRESS_FILE);
+        let Some(bytes) = read_if_exists(&path)? else {
+            return Ok(None);
+        };
+        let progress: AppProgress = bitcode::deserialize(&bytes)
+            .wrap_err_with(|| format!("Failed to read checkpoint {}", path.display()))?;
+        if progress.program_commit != program_commit {
+            bail!(
+                "checkpoints in {} are for another program",
+                self.dir.display()
+            );
+        }
+        if progress.input_hash != input_hash {
+            tracing::info!("discarding app proof checkpoints of another input");
+            self.clear_app()?;
+            return Ok(None);
+        }
+        let per_segment = (0..progress.num_segments)
+            .map(|idx| {
+                let path = self.segment_proof_path(idx);
+                read_proof(&path)?.ok_or_else(|| {
+                    eyre::eyre!("Missing segment proof checkpoint {}", path.display())
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some((progress, per_segment)))
+    }
+
+    pub(crate) fn save_app_progress(&self, progress: &AppProgress) -> Result<()> {
+        write_atomic(
+            &self.app_dir().join(APP_PROGRESS_FILE),
+            &bitcode::serialize(progress)?,
+        )
+    }
+
+    pub(crate) fn save_segment_proof(&self, segment_idx: usize, proof: &Proof<SC>) -> Result<()> {
+        write_atomic(
+            &self.segment_proof_path(segment_idx),
+            &proof.encode_to_vec()?,
+        )
+    }
+
+    pub(crate) fn load_app_public_values(
+        &self,
+    ) -> Result<Option<UserPublicValuesProof<DIGEST_SIZE, F>>> {
+        let path = self.app_dir().join(APP_PUBLIC_VALUES_FILE);
+        read_if_exists(&path)?
+            .map(|bytes| {
+                UserPublicValuesProof::decode_from_bytes(&bytes)
+                    .wrap_err_with(|| format!("Failed to read checkpoint {}", path.display()))
+            })
+            .transpose()
+    }
+
+    pub(crate) fn save_app_public_values(
+        &self,
+        proof: &UserPublicValuesProof<DIGEST_SIZE, F>,
+    ) -> Result<()> {
+        write_atomic(
+            &self.app_dir().join(APP_PUBLIC_VALUES_FILE),
+            &proof.encode_to_vec()?,
+        )
+    }
+
+    /// Removes the app proof checkpoints and the aggregation proofs of them, to start the app
+    /// proof over.
+    pub(crate) fn clear_app(&self) -> Result<()> {
+        self.remove()
+    }
+
+    /// Removes the aggregation proofs if they were generated for another tree shape or from
+    /// another app proof, and records the current ones.
+    pub(crate) fn check_agg_leaf_inputs(
+        &self,
+        num_children_leaf: usize,
+        num_children_internal: usize,
+        app_proof_hash: [u8; 32],
+    ) -> Result<()> {
+        let saved = self.load_agg_tree()?;
+        if let Some(saved) = &saved {
+            if (saved.num_children_leaf, saved.num_children_internal)
+                == (num_children_leaf, num_children_internal)
+                && saved.app_proof_hash == Some(app_proof_hash)
+            {
+                return Ok(());
+            }
+            tracing::info!("discarding aggregation checkpoints of another app proof or tree");
+        }
+        remove_dir_if_exists(&self.agg_dir())?;
+        self.save_agg_tree(&AggTree {
+            num_children_leaf,
+            num_children_internal,
+            app_proof_hash: Some(app_proof_hash),
+            leaf_proofs_hash: None,
+        })
+    }
+
+    /// Removes the internal proofs if they were generated for another tree shape or from other
+    /// leaf proofs, and records the current ones. The leaf proofs are kept unless the tree shape
+    /// changed.
+    pub(crate) fn check_agg_internal_inputs(
+        &self,
+        num_children_leaf: usize,
+        num_children_internal: usize,
+        leaf_proofs_hash: [u8; 32],
+    ) -> Result<()> {
+        let mut tree = match self.load_agg_tree()? {
+            Some(tree)
+                if (tree.num_children_leaf, tree.num_children_internal)
+                    == (num_children_leaf, num_children_internal) =>
+            {
+                tree
+            }
+            saved => {
+                if saved.is_some() {
+                    tracing::info!("discarding aggregation checkpoints of another tree");
+                }
+                remove_dir_if_exists(&self.agg_dir())?;
+                AggTree {
+                    num_children_leaf,
+                    num_children_internal,
+                    app_proof_hash: None,
+                    leaf_proofs_hash: None,
+                }
+            }
+        };
+        if tree.leaf_proofs_hash == Some(leaf_proofs_hash) {
+            return Ok(());
+        }
+        if tree.leaf_proofs_hash.is_some() {
+            tracing::info!("discarding internal proof checkpoints of other leaf proofs");
+        }
+        if let Ok(entries) = fs::read_dir(self.agg_dir()) {
+            for entry in entries {
+                let path = entry?.path();
+                if path.is_dir() && path.file_name().is_some_and(|name| name != "leaf") {
+                    remove_dir_if_exists(&path)?;
+                }
+            }
+        }
+        tree.leaf_proofs_hash = Some(leaf_proofs_hash);
+        self.save_agg_tree(&tree)
+    }
+
+    fn load_agg_tree(&self) -> Result<Option<AggTree>> {
+        let path = self.agg_dir().join(AGG_TREE_FILE);
+        // An unreadable tree is treated as a missing one, discarding the aggregation proofs.
+        Ok(read_if_exists(&path)?.and_then(|bytes| serde_json::from_slice(&bytes).ok()))
+    }
+
+    fn save_agg_tree(&self, tree: &AggTree) -> Result<()> {
+        write_atomic(
+            &self.agg_dir().join(AGG_TREE_FILE),
+            &serde_json::to_vec_pretty(tree)?,
+        )
+    }
+
+    /// Returns the saved proof of node `idx` of aggregation `layer`, e.g. `leaf` or `internal.0`.
+    pub(crate) fn load_agg_proof(&self, layer: &str, idx: usize) -> Result<Option<Proof<SC>>> {
+        read_proof(&self.agg_proof_path(layer, idx))
+    }
+
+    pub(crate) fn save_agg_proof(&self, layer: &str, idx: usize, proof: &Proof<SC>) -> Result<()> {
+        write_atomic(&self.agg_proof_path(layer, idx), &proof.encode_to_vec()?)
+    }
+
+    fn app_dir(&self) -> PathBuf {
+        self.dir.join(APP_DIR)
+    }
+
+    fn agg_dir(&self) -> PathBuf {
+        self.dir.join(AGG_DIR)
+    }
+
+    fn segment_proof_path(&self, segment_idx: usize) -> PathBuf {
+        self.app_dir().join(format!("segment-{segment_idx}.proof"))
+    }
+
+    fn agg_proof_path(&self, layer: &str, idx: usize) -> PathBuf {
+        self.agg_dir().join(layer).join(format!("{idx}.proof"))
+    }
+}
+
+/// Hashes the inputs in `input` and its key-value store entries. The input provider and the
+/// key-value stores added with [StdIn::add_kv_store] are hashed by their fingerprints, and
+/// checkpoints are refused for those without one.
+pub(crate) fn hash_input(input: &StdIn) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update((input.buffer.len() as u64).to_le_bytes());
+    for data in &input.buffer {
+        hasher.update((data.len() as u64).to_le_bytes());
+        for x in data {
+            hasher.update(x.as_canonical_u32().to_le_bytes());
+        }
+    }
+    let mut kv_entries: Vec<_> = input.kv_store.iter().collect();
+    kv_entries.sort_unstable();
+    for (key, value) in kv_entries {
+        for bytes in [key, value] {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+    }
+    let provider = input
+        .provider
+        .iter()
+        .map(|provider| (provider.fingerprint(), "an input provider"));
+    let stores = input
+        .kv_stores
+        .iter()
+        .map(|store| (store.fingerprint(), "a key-value store"));
+    for (fingerprint, source) in provider.chain(stores) {
+        let Some(fingerprint) = fingerprint else {
+            bail!("cannot checkpoint a proof reading from {source} without a fingerprint");
+        };
+        hasher.update((fingerprint.len() as u64).to_le_bytes());
+        hasher.update(fingerprint);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Hashes the encodings of `proofs`, to detect checkpoints generated from other proofs.
+pub(crate) fn hash_proofs<'a, T: Encode + 'a>(
+    proofs: impl IntoIterator<Item = &'a T>,
+) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    for proof in proofs {
+        hasher.update(proof.encode_to_vec()?);
+    }
+    Ok(hasher.finalize().into())
+}
+
+pub(crate) fn remove_dir_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_dir_all(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(e).wrap_err_with(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to read checkpoint {}", path.display())),
+    }
+}
+
+fn read_proof(path: &Path) -> Result<Option<Proof<SC>>> {
+    read_if_exists(path)?
+        .map(|bytes| {
+            Proof::decode_from_bytes(&bytes)
+                .wrap_err_with(|| format!("Failed to read checkpoint {}", path.display()))
+        })
+        .transpose()
+}
+
+/// Writes `bytes` to a temporary file unique to the call and renames it to `path`, so that
+/// concurrent writers do not clobber each other's temporary files.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static NEXT_TMP_ID: AtomicU64 = AtomicU64::new(0);
+    let write = || {
+        fs::create_dir_all(path.parent().unwrap())?;
+        let tmp_id = NEXT_TMP_ID.fetch_add(1, Ordering::Relaxed);
+        let mut tmp_name = path.file_name().unwrap().to_os_string();
+        tmp_name.push(format!(".{}-{tmp_id}.tmp", std::process::id()));
+        let tmp_path = path.with_file_name(tmp_name);
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, path)
+    };
+    write().wrap_err_with(|| format!("Failed to write checkpoint {}", path.display()))
+}
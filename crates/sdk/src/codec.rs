@@ -24,7 +24,7 @@ type Challenge = BinomialExtensionField<F, 4>;
 
 /// Codec version should change only when proof system or proof format changes.
 /// It does correspond to the main openvm version (which may change more frequently).
-const CODEC_VERSION: u32 = 1;
+pub const CODEC_VERSION: u32 = 1;
 
 /// Hardware and language independent encoding.
 /// Uses the Writer pattern for more efficient encoding without intermediate buffers.
@@ -143,7 +143,7 @@ fn encode_opening_proof<W: Write>(
 ///   - each matrix
 ///     - each point to open at
 ///       - evaluations for each column of matrix at that point
-fn encode_opened_values<W: Write>(
+pub(crate) fn encode_opened_values<W: Write>(
     opened_values: &OpenedValues<Challenge>,
     writer: &mut W,
 ) -> Result<()> {
@@ -281,7 +281,7 @@ impl Encode for Challenge {
 }
 
 /// Encodes length of slice and then each commitment
-fn encode_commitments<W: Write>(commitments: &[Com<SC>], writer: &mut W) -> Result<()> {
+pub(crate) fn encode_commitments<W: Write>(commitments: &[Com<SC>], writer: &mut W) -> Result<()> {
     let coms: Vec<[F; DIGEST_SIZE]> = commitments.iter().copied().map(Into::into).collect();
     encode_slice(&coms, writer)
 }
@@ -7,6 +7,8 @@ use openvm_algebra_circuit::{
 use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256Executor, Int256Periphery};
 use openvm_bigint_transpiler::Int256TranspilerExtension;
+use openvm_blake_circuit::{Blake, BlakeExecutor, BlakePeriphery};
+use openvm_blake_transpiler::BlakeTranspilerExtension;
 use openvm_circuit::{
     arch::{
         InitFileGenerator, SystemConfig, SystemExecutor, SystemPeriphery, VmChipComplex, VmConfig,
@@ -16,11 +18,14 @@ use openvm_circuit::{
     derive::{AnyEnum, InstructionExecutor},
 };
 use openvm_ecc_circuit::{
+    TwistedEdwardsExtension, TwistedEdwardsExtensionExecutor, TwistedEdwardsExtensionPeriphery,
     WeierstrassExtension, WeierstrassExtensionExecutor, WeierstrassExtensionPeriphery,
 };
-use openvm_ecc_transpiler::EccTranspilerExtension;
-use openvm_keccak256_circuit::{Keccak256, Keccak256Executor, Keccak256Periphery};
-use openvm_keccak256_transpiler::Keccak256TranspilerExtension;
+use openvm_ecc_transpiler::{EccTranspilerExtension, EdwardsTranspilerExtension};
+use openvm_keccak256_circuit::{
+    Keccak256, Keccak256Executor, Keccak256Periphery, Keccakf, KeccakfExecutor, KeccakfPeriphery,
+};
+use openvm_keccak256_transpiler::{Keccak256TranspilerExtension, KeccakfTranspilerExtension};
 use openvm_native_circuit::{
     CastFExtension, CastFExtensionExecutor, CastFExtensionPeriphery, Native, NativeExecutor,
     NativePeriphery,
@@ -30,32 +35,61 @@ use openvm_pairing_circuit::{
     PairingExtension, PairingExtensionExecutor, PairingExtensionPeriphery,
 };
 use openvm_pairing_transpiler::PairingTranspilerExtension;
+use openvm_rv32b_circuit::{Rv32B, Rv32BExecutor, Rv32BPeriphery};
+use openvm_rv32b_transpiler::Rv32BTranspilerExtension;
 use openvm_rv32im_circuit::{
-    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
-    Rv32MExecutor, Rv32MPeriphery,
+    Rv32A, Rv32AExecutor, Rv32APeriphery, Rv32C, Rv32CExecutor, Rv32CPeriphery, Rv32I,
+    Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M, Rv32MExecutor,
+    Rv32MPeriphery, Rv32Poseidon2, Rv32Poseidon2Executor, Rv32Poseidon2Periphery,
 };
 use openvm_rv32im_transpiler::{
-    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    Rv32ATranspilerExtension, Rv32CompressedHandler, Rv32ITranspilerExtension,
+    Rv32IoTranspilerExtension, Rv32MTranspilerExtension, Rv32Poseidon2TranspilerExtension,
+    Rv32TrapHandler,
+};
+use openvm_sha256_circuit::{
+    Sha256, Sha256Executor, Sha256Periphery, Sha512, Sha512Executor, Sha512Periphery,
 };
-use openvm_sha256_circuit::{Sha256, Sha256Executor, Sha256Periphery};
-use openvm_sha256_transpiler::Sha256TranspilerExtension;
+use openvm_sha256_transpiler::{Sha256TranspilerExtension, Sha512TranspilerExtension};
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::transpiler::Transpiler;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::F;
 
+/// Misaligned loads and stores trap if and only if `traps` is set:
+/// `system.config.trap_misaligned` is derived from `traps` when the config is built or
+/// deserialized.
 #[derive(Builder, Clone, Debug, Serialize, Deserialize)]
+#[builder(finish_fn(vis = "", name = build_internal))]
+#[serde(remote = "Self")]
 pub struct SdkVmConfig {
     #[serde(default)]
     pub system: SdkSystemConfig,
 
     pub rv32i: Option<UnitStruct>,
     pub io: Option<UnitStruct>,
+    /// Atomic memory operations (`LR.W`, `SC.W` and `AMO*.W`). Requires `rv32i`.
+    pub rv32a: Option<UnitStruct>,
+    /// Compressed instructions, for guests built with the `C` target feature. Requires `rv32i`.
+    pub rv32c: Option<UnitStruct>,
+    /// Bit-manipulation instructions of the `Zba`, `Zbb` and `Zbs` extensions. Requires `rv32i`.
+    pub rv32b: Option<UnitStruct>,
+    /// BabyBear Poseidon2 compression, used by `openvm::poseidon2`.
+    pub poseidon2: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
+    /// Raw keccak-f\[1600\] permutation, used for SHA-3 and SHAKE.
+    pub keccakf: Option<UnitStruct>,
     pub sha256: Option<UnitStruct>,
+    /// SHA-512 and SHA-384 hashing.
+    pub sha512: Option<UnitStruct>,
+    /// BLAKE2s and BLAKE3 compression functions.
+    pub blake: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
     pub castf: Option<UnitStruct>,
+    /// Opt-in trap semantics for `ecall`, `ebreak`, illegal instructions and misaligned loads and
+    /// stores.
+    pub traps: Option<Rv32TrapHandler>,
 
     pub rv32m: Option<Rv32M>,
     pub bigint: Option<Int256>,
@@ -63,6 +97,8 @@ pub struct SdkVmConfig {
     pub fp2: Option<Fp2Extension>,
     pub pairing: Option<PairingExtension>,
     pub ecc: Option<WeierstrassExtension>,
+    /// Twisted Edwards curve arithmetic, used by `openvm-ed25519`.
+    pub te: Option<TwistedEdwardsExtension>,
 }
 
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
@@ -74,10 +110,24 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     #[any_enum]
     Io(Rv32IoExecutor<F>),
     #[any_enum]
+    Rv32a(Rv32AExecutor<F>),
+    #[any_enum]
+    Rv32c(Rv32CExecutor<F>),
+    #[any_enum]
+    Rv32b(Rv32BExecutor<F>),
+    #[any_enum]
+    Poseidon2(Rv32Poseidon2Executor<F>),
+    #[any_enum]
     Keccak(Keccak256Executor<F>),
     #[any_enum]
+    Keccakf(KeccakfExecutor<F>),
+    #[any_enum]
     Sha256(Sha256Executor<F>),
     #[any_enum]
+    Sha512(Sha512Executor<F>),
+    #[any_enum]
+    Blake(BlakeExecutor<F>),
+    #[any_enum]
     Native(NativeExecutor<F>),
     #[any_enum]
     Rv32m(Rv32MExecutor<F>),
@@ -92,6 +142,8 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     #[any_enum]
     Ecc(WeierstrassExtensionExecutor<F>),
     #[any_enum]
+    Te(TwistedEdwardsExtensionExecutor<F>),
+    #[any_enum]
     CastF(CastFExtensionExecutor<F>),
 }
 
@@ -104,10 +156,24 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     #[any_enum]
     Io(Rv32IoPeriphery<F>),
     #[any_enum]
+    Rv32a(Rv32APeriphery<F>),
+    #[any_enum]
+    Rv32c(Rv32CPeriphery<F>),
+    #[any_enum]
+    Rv32b(Rv32BPeriphery<F>),
+    #[any_enum]
+    Poseidon2(Rv32Poseidon2Periphery<F>),
+    #[any_enum]
     Keccak(Keccak256Periphery<F>),
     #[any_enum]
+    Keccakf(KeccakfPeriphery<F>),
+    #[any_enum]
     Sha256(Sha256Periphery<F>),
     #[any_enum]
+    Sha512(Sha512Periphery<F>),
+    #[any_enum]
+    Blake(BlakePeriphery<F>),
+    #[any_enum]
     Native(NativePeriphery<F>),
     #[any_enum]
     Rv32m(Rv32MPeriphery<F>),
@@ -122,10 +188,35 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     #[any_enum]
     Ecc(WeierstrassExtensionPeriphery<F>),
     #[any_enum]
+    Te(TwistedEdwardsExtensionPeriphery<F>),
+    #[any_enum]
     CastF(CastFExtensionPeriphery<F>),
 }
 
+impl<S: sdk_vm_config_builder::IsComplete> SdkVmConfigBuilder<S> {
+    pub fn build(self) -> SdkVmConfig {
+        self.build_internal().normalized()
+    }
+}
+
+impl Serialize for SdkVmConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SdkVmConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer).map(Self::normalized)
+    }
+}
+
 impl SdkVmConfig {
+    fn normalized(mut self) -> Self {
+        self.system.config.trap_misaligned = self.traps.is_some();
+        self
+    }
+
     pub fn transpiler(&self) -> Transpiler<F> {
         let mut transpiler = Transpiler::default();
         if self.rv32i.is_some() {
@@ -134,12 +225,33 @@ impl SdkVmConfig {
         if self.io.is_some() {
             transpiler = transpiler.with_extension(Rv32IoTranspilerExtension);
         }
+        if self.rv32a.is_some() {
+            transpiler = transpiler.with_extension(Rv32ATranspilerExtension);
+        }
+        if self.rv32c.is_some() {
+            transpiler = transpiler.with_compressed_handler(Rv32CompressedHandler);
+        }
+        if self.rv32b.is_some() {
+            transpiler = transpiler.with_extension(Rv32BTranspilerExtension);
+        }
+        if self.poseidon2.is_some() {
+            transpiler = transpiler.with_extension(Rv32Poseidon2TranspilerExtension);
+        }
         if self.keccak.is_some() {
             transpiler = transpiler.with_extension(Keccak256TranspilerExtension);
         }
+        if self.keccakf.is_some() {
+            transpiler = transpiler.with_extension(KeccakfTranspilerExtension);
+        }
         if self.sha256.is_some() {
             transpiler = transpiler.with_extension(Sha256TranspilerExtension);
         }
+        if self.sha512.is_some() {
+            transpiler = transpiler.with_extension(Sha512TranspilerExtension);
+        }
+        if self.blake.is_some() {
+            transpiler = transpiler.with_extension(BlakeTranspilerExtension);
+        }
         if self.native.is_some() {
             transpiler = transpiler.with_extension(LongFormTranspilerExtension);
         }
@@ -161,6 +273,12 @@ impl SdkVmConfig {
         if self.ecc.is_some() {
             transpiler = transpiler.with_extension(EccTranspilerExtension);
         }
+        if self.te.is_some() {
+            transpiler = transpiler.with_extension(EdwardsTranspilerExtension);
+        }
+        if let Some(traps) = self.traps {
+            transpiler = transpiler.with_trap_handler(traps);
+        }
         transpiler
     }
 }
@@ -188,12 +306,33 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if self.io.is_some() {
             complex = complex.extend(&Rv32Io)?;
         }
+        if self.rv32a.is_some() {
+            complex = complex.extend(&Rv32A)?;
+        }
+        if self.rv32c.is_some() {
+            complex = complex.extend(&Rv32C)?;
+        }
+        if self.rv32b.is_some() {
+            complex = complex.extend(&Rv32B)?;
+        }
+        if self.poseidon2.is_some() {
+            complex = complex.extend(&Rv32Poseidon2)?;
+        }
         if self.keccak.is_some() {
             complex = complex.extend(&Keccak256)?;
         }
+        if self.keccakf.is_some() {
+            complex = complex.extend(&Keccakf)?;
+        }
         if self.sha256.is_some() {
             complex = complex.extend(&Sha256)?;
         }
+        if self.sha512.is_some() {
+            complex = complex.extend(&Sha512)?;
+        }
+        if self.blake.is_some() {
+            complex = complex.extend(&Blake)?;
+        }
         if self.native.is_some() {
             complex = complex.extend(&Native)?;
         }
@@ -233,6 +372,9 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if let Some(ref ecc) = self.ecc {
             complex = complex.extend(ecc)?;
         }
+        if let Some(ref te) = self.te {
+            complex = complex.extend(te)?;
+        }
 
         Ok(complex)
     }
@@ -240,7 +382,7 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
 
 impl InitFileGenerator for SdkVmConfig {
     fn generate_init_file_contents(&self) -> Option<String> {
-        if self.modular.is_some() || self.fp2.is_some() || self.ecc.is_some() {
+        if self.modular.is_some() || self.fp2.is_some() || self.ecc.is_some() || self.te.is_some() {
             let mut contents = String::new();
             contents.push_str(
                 "// This file is automatically generated by cargo openvm. Do not rename or edit.\n",
@@ -266,6 +408,11 @@ impl InitFileGenerator for SdkVmConfig {
                 contents.push('\n');
             }
 
+            if let Some(te_config) = &self.te {
+                contents.push_str(&te_config.generate_te_init());
+                contents.push('\n');
+            }
+
             Some(contents)
         } else {
             None
@@ -312,18 +459,60 @@ impl From<Rv32Io> for UnitStruct {
     }
 }
 
+impl From<Rv32A> for UnitStruct {
+    fn from(_: Rv32A) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32C> for UnitStruct {
+    fn from(_: Rv32C) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32B> for UnitStruct {
+    fn from(_: Rv32B) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32Poseidon2> for UnitStruct {
+    fn from(_: Rv32Poseidon2) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Keccak256> for UnitStruct {
     fn from(_: Keccak256) -> Self {
         UnitStruct {}
     }
 }
 
+impl From<Keccakf> for UnitStruct {
+    fn from(_: Keccakf) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Sha256> for UnitStruct {
     fn from(_: Sha256) -> Self {
         UnitStruct {}
     }
 }
 
+impl From<Sha512> for UnitStruct {
+    fn from(_: Sha512) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Blake> for UnitStruct {
+    fn from(_: Blake) -> Self {
+        UnitStruct {}
+    }
+}
+
 impl From<Native> for UnitStruct {
     fn from(_: Native) -> Self {
         UnitStruct {}
@@ -1,5 +1,5 @@
 use std::{
-    fs::{create_dir_all, read, write, File},
+    fs::{create_dir_all, read, read_to_string, write, File},
     path::Path,
 };
 
@@ -12,8 +12,9 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     codec::{Decode, Encode},
+    config::{AppConfig, SdkVmConfig},
     keygen::{AggStarkProvingKey, AppProvingKey, AppVerifyingKey},
-    F, SC,
+    StdIn, F, SC,
 };
 #[cfg(feature = "evm-prove")]
 use crate::{
@@ -35,6 +36,21 @@ pub fn write_exe_to_file<P: AsRef<Path>>(exe: VmExe<F>, path: P) -> Result<()> {
     write_to_file_bitcode(&path, exe)
 }
 
+pub fn read_stdin_from_file<P: AsRef<Path>>(path: P) -> Result<StdIn> {
+    read_from_file_bitcode(&path)
+}
+
+pub fn write_stdin_to_file<P: AsRef<Path>>(stdin: &StdIn, path: P) -> Result<()> {
+    write_to_file_bitcode(&path, stdin)
+}
+
+/// Reads an app config in the `openvm.toml` format.
+pub fn read_app_config_from_file<P: AsRef<Path>>(path: P) -> Result<AppConfig<SdkVmConfig>> {
+    read_to_string(&path)
+        .map_err(|e| read_error(&path, e.into()))
+        .and_then(|toml| toml::from_str(&toml).map_err(|e| read_error(&path, e.into())))
+}
+
 pub fn read_app_pk_from_file<VC: VmConfig<F>, P: AsRef<Path>>(
     path: P,
 ) -> Result<AppProvingKey<VC>> {
@@ -110,8 +126,6 @@ pub fn write_evm_proof_to_file<P: AsRef<Path>>(proof: EvmProof, path: P) -> Resu
 
 #[cfg(feature = "evm-prove")]
 pub fn read_evm_halo2_verifier_from_folder<P: AsRef<Path>>(folder: P) -> Result<EvmHalo2Verifier> {
-    use std::fs::read_to_string;
-
     let folder = folder
         .as_ref()
         .join("src")
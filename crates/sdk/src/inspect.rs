@@ -0,0 +1,353 @@
+//! Summaries of stored proofs, to debug proofs rejected by a verifier without access to the
+//! proving keys.
+
+use std::{
+    fmt,
+    io::{Cursor, Result as IoResult},
+    path::Path,
+};
+
+use eyre::{bail, Result, WrapErr};
+use openvm_circuit::arch::ContinuationVmProof;
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
+use serde::Serialize;
+
+use crate::{
+    codec::{
+        decode_vec, encode_commitments, encode_opened_values, encode_slice, Decode, Encode,
+        CODEC_VERSION,
+    },
+    commit::AppExecutionCommit,
+    types::VmStarkProofBytes,
+    F, OPENVM_VERSION, SC,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofKind {
+    /// A [ContinuationVmProof], as written by
+    /// [write_app_proof_to_file](crate::fs::write_app_proof_to_file).
+    App,
+    /// A [VmStarkProofBytes] in JSON.
+    Stark,
+    /// An `EvmProof` in JSON.
+    Evm,
+}
+
+impl fmt::Display for ProofKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofKind::App => write!(f, "app"),
+            ProofKind::Stark => write!(f, "STARK"),
+            ProofKind::Evm => write!(f, "EVM"),
+        }
+    }
+}
+
+/// Contents of a proof, see [inspect_proof].
+#[derive(Clone, Debug, Serialize)]
+pub struct ProofInspection {
+    pub kind: ProofKind,
+    /// Version of the proof encoding. EVM proofs are not versioned.
+    pub codec_version: Option<u32>,
+    /// Commitments to the app executable and VM config. App proofs do not contain them.
+    pub app_commit: Option<AppExecutionCommit>,
+    /// User public values, as field elements for app and STARK proofs and as bytes for EVM
+    /// proofs.
+    pub user_public_values: Vec<u32>,
+    /// The STARK proof of every segment for app proofs, and the root proof for STARK proofs.
+    pub stark_proofs: Vec<StarkProofSummary>,
+    /// Size in bytes of each part of the proof.
+    pub size: Vec<ProofPartSize>,
+    pub total_size: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProofPartSize {
+    pub part: &'static str,
+    pub bytes: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StarkProofSummary {
+    /// The AIRs with a non-empty trace, in the order of the proof.
+    pub airs: Vec<AirProofSummary>,
+    pub fri: FriProofSummary,
+    /// Size in bytes of each part of the proof.
+    pub size: Vec<ProofPartSize>,
+    pub total_size: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AirProofSummary {
+    pub air_id: usize,
+    /// Only known for app proofs if the VM config is given, see
+    /// [ProofInspection::set_air_names].
+    pub air_name: Option<String>,
+    pub height: usize,
+    pub num_public_values: usize,
+}
+
+/// FRI parameters as far as they can be read from a proof.
+#[derive(Clone, Debug, Serialize)]
+pub struct FriProofSummary {
+    /// Derived from the depth of the Merkle proofs of the tallest trace, `None` if the proof has
+    /// no queries.
+    pub log_blowup: Option<usize>,
+    pub num_queries: usize,
+    pub num_commit_phase_rounds: usize,
+    pub log_final_poly_len: usize,
+}
+
+/// Reads a proof written by `cargo openvm prove` or the [fs](crate::fs) functions and summarizes
+/// it. The kind of proof is detected from its contents.
+pub fn inspect_proof_file<P: AsRef<Path>>(path: P) -> Result<ProofInspection> {
+    let bytes = std::fs::read(&path)
+        .wrap_err_with(|| format!("Failed to read {}", path.as_ref().display()))?;
+    inspect_proof(&bytes).wrap_err_with(|| format!("Failed to inspect {}", path.as_ref().display()))
+}
+
+/// Summarizes an encoded app, STARK or EVM proof. The kind of proof is detected from its contents.
+pub fn inspect_proof(bytes: &[u8]) -> Result<ProofInspection> {
+    // App proofs use the binary codec, the other proofs are stored as JSON.
+    match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(json) if json.get("proof_data").is_some() => inspect_evm_proof(json),
+        Ok(json) if json.get("proof").is_some() => {
+            inspect_stark_proof(serde_json::from_value(json).wrap_err("Invalid STARK proof")?)
+        }
+        Ok(_) => bail!("JSON document is neither a STARK proof nor an EVM proof"),
+        Err(_) => inspect_app_proof(bytes),
+    }
+}
+
+impl ProofInspection {
+    /// Names the AIRs of an app proof, with the AIR names of the app VM in the order of AIR ids.
+    pub fn set_air_names(&mut self, air_names: &[String]) {
+        for air in self
+            .stark_proofs
+            .iter_mut()
+            .flat_map(|proof| &mut proof.airs)
+        {
+            air.air_name = air_names.get(air.air_id).cloned();
+        }
+    }
+}
+
+fn inspect_app_proof(bytes: &[u8]) -> Result<ProofInspection> {
+    // The number of segments is followed by the proof of the first segment, which starts with the
+    // codec version.
+    let codec_version = bytes.get(4..8).map(read_u32);
+    if let Some(version) = codec_version {
+        check_codec_version(version)?;
+    }
+    let mut reader = Cursor::new(bytes);
+    let proof = ContinuationVmProof::<SC>::decode(&mut reader).wrap_err("Invalid app proof")?;
+    if reader.position() != bytes.len() as u64 {
+        bail!(
+            "Invalid app proof: {} trailing bytes",
+            bytes.len() as u64 - reader.position()
+        );
+    }
+
+    let stark_proofs = proof
+        .per_segment
+        .iter()
+        .map(StarkProofSummary::new)
+        .collect::<IoResult<Vec<_>>>()?;
+    let public_values_proof_size = proof.user_public_values.encode_to_vec()?.len();
+    Ok(ProofInspection {
+        kind: ProofKind::App,
+        codec_version,
+        app_commit: None,
+        user_public_values: to_u32s(&proof.user_public_values.public_values),
+        stark_proofs,
+        size: vec![
+            ProofPartSize {
+                part: "segment proofs",
+                bytes: bytes.len() - public_values_proof_size,
+            },
+            ProofPartSize {
+                part: "user public values proof",
+                bytes: public_values_proof_size,
+            },
+        ],
+        total_size: bytes.len(),
+    })
+}
+
+fn inspect_stark_proof(proof: VmStarkProofBytes) -> Result<ProofInspection> {
+    let codec_version = proof.proof.get(..4).map(read_u32);
+    if let Some(version) = codec_version {
+        check_codec_version(version)?;
+    }
+    let stark_proof =
+        Proof::<SC>::decode_from_bytes(&proof.proof).wrap_err("Invalid STARK proof")?;
+    let user_public_values: Vec<F> = decode_vec(&mut Cursor::new(&proof.user_public_values))
+        .wrap_err("Invalid user public values")?;
+    Ok(ProofInspection {
+        kind: ProofKind::Stark,
+        codec_version,
+        app_commit: Some(proof.app_commit),
+        user_public_values: to_u32s(&user_public_values),
+        stark_proofs: vec![StarkProofSummary::new(&stark_proof)?],
+        size: vec![
+            ProofPartSize {
+                part: "root proof",
+                bytes: proof.proof.len(),
+            },
+            ProofPartSize {
+                part: "user public values",
+                bytes: proof.user_public_values.len(),
+            },
+        ],
+        total_size: proof.proof.len() + proof.user_public_values.len(),
+    })
+}
+
+#[cfg(feature = "evm-prove")]
+fn inspect_evm_proof(json: serde_json::Value) -> Result<ProofInspection> {
+    use openvm_native_recursion::halo2::RawEvmProof;
+
+    use crate::types::EvmProof;
+
+    let proof: EvmProof = serde_json::from_value(json).wrap_err("Invalid EVM proof")?;
+    // The conversion checks the lengths expected by the verifier of this version.
+    RawEvmProof::try_from(proof.clone()).wrap_err_with(|| {
+        format!("Invalid EVM proof for OpenVM {OPENVM_VERSION}, it may be from another version")
+    })?;
+    let size = vec![
+        ProofPartSize {
+            part: "KZG accumulator",
+            bytes: proof.proof_data.accumulator.len(),
+        },
+        ProofPartSize {
+            part: "halo2 proof",
+            bytes: proof.proof_data.proof.len(),
+        },
+        ProofPartSize {
+            part: "user public values",
+            bytes: proof.user_public_values.len(),
+        },
+    ];
+    Ok(ProofInspection {
+        kind: ProofKind::Evm,
+        codec_version: None,
+        app_commit: Some(proof.app_commit),
+        user_public_values: proof.user_public_values.iter().map(|&b| b as u32).collect(),
+        stark_proofs: vec![],
+        total_size: size.iter().map(|part| part.bytes).sum(),
+        size,
+    })
+}
+
+#[cfg(not(feature = "evm-prove"))]
+fn inspect_evm_proof(_json: serde_json::Value) -> Result<ProofInspection> {
+    bail!("Inspecting EVM proofs requires the `evm-prove` feature")
+}
+
+fn check_codec_version(version: u32) -> Result<()> {
+    if version != CODEC_VERSION {
+        bail!(
+            "Unsupported proof codec version {version}, OpenVM {OPENVM_VERSION} reads version \
+             {CODEC_VERSION}. The proof was generated by another OpenVM version, or the file is \
+             not a proof."
+        );
+    }
+    Ok(())
+}
+
+impl StarkProofSummary {
+    fn new(proof: &Proof<SC>) -> IoResult<Self> {
+        let airs = proof
+            .per_air
+            .iter()
+            .map(|air| AirProofSummary {
+                air_id: air.air_id,
+                air_name: None,
+                height: air.degree,
+                num_public_values: air.public_values.len(),
+            })
+            .collect();
+
+        let fri_proof = &proof.opening.proof;
+        let max_log_height = proof.per_air.iter().map(|air| log2(air.degree)).max();
+        // The Merkle proofs of the tallest trace go down its low degree extension, whose height is
+        // the trace height times the blowup.
+        let max_opening_depth = fri_proof.query_proofs.first().and_then(|query| {
+            query
+                .input_proof
+                .iter()
+                .map(|batch| batch.opening_proof.len())
+                .max()
+        });
+        let fri = FriProofSummary {
+            log_blowup: max_opening_depth
+                .zip(max_log_height)
+                .map(|(depth, log_height)| depth.saturating_sub(log_height)),
+            num_queries: fri_proof.query_proofs.len(),
+            num_commit_phase_rounds: fri_proof.commit_phase_commits.len(),
+            log_final_poly_len: log2(fri_proof.final_poly.len()),
+        };
+
+        let mut commitments = Vec::new();
+        encode_commitments(&proof.commitments.main_trace, &mut commitments)?;
+        encode_commitments(&proof.commitments.after_challenge, &mut commitments)?;
+        let quotient_commit: [F; DIGEST_SIZE] = proof.commitments.quotient.into();
+        quotient_commit.encode(&mut commitments)?;
+        let mut opened_values = Vec::new();
+        encode_opened_values(&proof.opening.values, &mut opened_values)?;
+        let mut query_proofs = Vec::new();
+        encode_slice(&fri_proof.query_proofs, &mut query_proofs)?;
+        let fri_size = fri_proof.encode_to_vec()?.len();
+        let mut per_air = Vec::new();
+        encode_slice(&proof.per_air, &mut per_air)?;
+        let total_size = proof.encode_to_vec()?.len();
+
+        let mut size = vec![
+            ProofPartSize {
+                part: "commitments",
+                bytes: commitments.len(),
+            },
+            ProofPartSize {
+                part: "opened values",
+                bytes: opened_values.len(),
+            },
+            ProofPartSize {
+                part: "FRI query proofs",
+                bytes: query_proofs.len(),
+            },
+            ProofPartSize {
+                part: "FRI commitments and final polynomial",
+                bytes: fri_size - query_proofs.len(),
+            },
+            ProofPartSize {
+                part: "per-AIR data",
+                bytes: per_air.len(),
+            },
+        ];
+        size.push(ProofPartSize {
+            part: "other",
+            bytes: total_size - size.iter().map(|part| part.bytes).sum::<usize>(),
+        });
+        Ok(Self {
+            airs,
+            fri,
+            size,
+            total_size,
+        })
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn to_u32s(values: &[F]) -> Vec<u32> {
+    values.iter().map(|v| v.as_canonical_u32()).collect()
+}
+
+/// Rounds up to a power of two, since proven traces are padded to one.
+fn log2(n: usize) -> usize {
+    n.next_power_of_two().trailing_zeros() as usize
+}
@@ -1,7 +1,14 @@
-use std::{borrow::Borrow, fs::read, marker::PhantomData, path::Path, sync::Arc};
+use std::{
+    borrow::Borrow,
+    fs::read,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[cfg(feature = "evm-verify")]
 use alloy_sol_types::sol;
+use checkpoint::CheckpointDir;
 use commit::{commit_app_exe, AppExecutionCommit};
 use config::{AggregationTreeConfig, AppConfig};
 use eyre::Result;
@@ -13,9 +20,9 @@ use openvm_circuit::{
     arch::{
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
         instructions::exe::VmExe,
-        verify_segments, ContinuationVmProof, ExecutionError, InitFileGenerator,
-        VerifiedExecutionPayload, VmConfig, VmExecutor, CONNECTOR_AIR_ID, PROGRAM_AIR_ID,
-        PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
+        verify_segments, ContinuationVmProof, ExecutionError, ExitCode, InitFileGenerator,
+        VerifiedExecutionPayload, VmConfig, VmExecutor, VmMemoryState, CONNECTOR_AIR_ID,
+        PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX, PUBLIC_VALUES_AIR_ID,
     },
     system::{
         memory::{tree::public_values::extract_public_values, CHUNK},
@@ -35,10 +42,7 @@ use openvm_continuations::verifier::{
 pub use openvm_continuations::{RootSC, C, F, SC};
 #[cfg(feature = "evm-prove")]
 use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
-use openvm_stark_backend::{
-    p3_field::{FieldAlgebra, PrimeField32},
-    proof::Proof,
-};
+use openvm_stark_backend::{p3_field::PrimeField32, proof::Proof};
 use openvm_stark_sdk::{
     config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
     engine::StarkFriEngine,
@@ -60,11 +64,15 @@ use crate::{
     config::{AggStarkConfig, SdkVmConfig},
     keygen::{asm::program_to_asm, AggStarkProvingKey},
     prover::{AppProver, StarkProver},
+    types::{AirCostReport, ExecutionReport, SegmentReport},
 };
 
+pub mod arch_test;
+pub mod checkpoint;
 pub mod codec;
 pub mod commit;
 pub mod config;
+pub mod inspect;
 pub mod keygen;
 pub mod prover;
 
@@ -108,6 +116,7 @@ pub struct VerifiedContinuationVmPayload {
 
 pub struct GenericSdk<E: StarkFriEngine<SC>> {
     agg_tree_config: AggregationTreeConfig,
+    checkpoint_dir: Option<CheckpointDir>,
     _phantom: PhantomData<E>,
 }
 
@@ -115,6 +124,7 @@ impl<E: StarkFriEngine<SC>> Default for GenericSdk<E> {
     fn default() -> Self {
         Self {
             agg_tree_config: AggregationTreeConfig::default(),
+            checkpoint_dir: None,
             _phantom: PhantomData,
         }
     }
@@ -136,6 +146,17 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
         &self.agg_tree_config
     }
 
+    /// Saves the progress of app, STARK and EVM proofs in `checkpoint_dir`, and resumes proofs
+    /// from the progress saved there. See [CheckpointDir].
+    pub fn with_checkpoint_dir(mut self, checkpoint_dir: impl Into<PathBuf>) -> Self {
+        self.checkpoint_dir = Some(CheckpointDir::new(checkpoint_dir));
+        self
+    }
+
+    pub fn checkpoint_dir(&self) -> Option<&CheckpointDir> {
+        self.checkpoint_dir.as_ref()
+    }
+
     pub fn build<P: AsRef<Path>>(
         &self,
         guest_opts: GuestOptions,
@@ -181,73 +202,105 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let vm = VmExecutor::new(vm_config);
-        let final_memory = vm.execute(exe, inputs)?;
+        self.execute_with_setup(exe, vm_config, inputs, |_| {})
+            .map(|(public_values, _)| public_values)
+    }
+
+    /// Executes the program like [Self::execute], after calling `setup` on the executor, e.g. to
+    /// set an execution hook or recorder. Returns the public values together with the final
+    /// memory.
+    pub fn execute_with_setup<VC: VmConfig<F>>(
+        &self,
+        exe: VmExe<F>,
+        vm_config: VC,
+        inputs: StdIn,
+        setup: impl FnOnce(&mut VmExecutor<F, VC>),
+    ) -> Result<(Vec<F>, VmMemoryState<F>), ExecutionError>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let mut vm = VmExecutor::new(vm_config);
+        setup(&mut vm);
+        let final_memory = vm.execute(exe, inputs)?.unwrap();
         let public_values = extract_public_values(
             &vm.config.system().memory_config.memory_dimensions(),
             vm.config.system().num_public_values,
-            final_memory.as_ref().unwrap(),
+            &final_memory,
         );
-        Ok(public_values)
+        Ok((public_values, final_memory))
     }
 
-    pub fn execute_with_signature<VC: VmConfig<F>>(
+    /// Executes the program and summarizes the execution. Unlike [Self::execute], a non-zero
+    /// exit code of the guest is reported in [ExecutionReport::exit_code] instead of as an error.
+    ///
+    /// If `trace_heights` is set, the report also contains the trace cost of every AIR per
+    /// segment and in total. This finalizes the memory of every segment, which makes execution
+    /// slower.
+    pub fn execute_with_report<VC: VmConfig<F>>(
         &self,
         exe: VmExe<F>,
         vm_config: VC,
         inputs: StdIn,
-        signature_path: Option<&Path>,
-    ) -> Result<Vec<F>, ExecutionError>
+        trace_heights: bool,
+    ) -> Result<ExecutionReport, ExecutionError>
     where
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
         let vm = VmExecutor::new(vm_config);
-        let final_memory = vm.execute(exe, inputs)?;
-        let public_values = extract_public_values(
-            &vm.config.system().memory_config.memory_dimensions(),
-            vm.config.system().num_public_values,
-            final_memory.as_ref().unwrap(),
-        );
-        
-        // Extract and write signature if requested
-        if let Some(sig_path) = signature_path {
-            use std::fs::File;
-            use std::io::Write;
-            
-            // Get signature addresses from environment variables (set by the test runner)
-            let sig_begin = std::env::var("RISC0_SIG_BEGIN_ADDR")
-                .ok()
-                .and_then(|s| s.parse::<u32>().ok())
-                .expect("RISC0_SIG_BEGIN_ADDR environment variable must be set with signature start address");
-            
-            let sig_size = std::env::var("RISC0_SIG_SIZE")
-                .ok()
-                .and_then(|s| s.parse::<usize>().ok())
-                .expect("RISC0_SIG_SIZE environment variable must be set with signature size");
-            
-            let mut sig_file = File::create(sig_path)
-                .map_err(|_| ExecutionError::Fail { pc: 0 })?;
-            let memory_state = final_memory.as_ref().unwrap();
-            
-            // Read signature memory region
-            for i in 0..sig_size/4 {
-                let addr = sig_begin + (i as u32) * 4;
-                // AddressMap get method takes (address_space, pointer) tuple
-                // RV32_MEMORY_AS (2) is for RISC-V memory
-                // Read 4 bytes and combine into a word (little-endian)
-                let byte0 = memory_state.get(&(2, addr)).unwrap_or(&F::ZERO).as_canonical_u32();
-                let byte1 = memory_state.get(&(2, addr + 1)).unwrap_or(&F::ZERO).as_canonical_u32();
-                let byte2 = memory_state.get(&(2, addr + 2)).unwrap_or(&F::ZERO).as_canonical_u32();
-                let byte3 = memory_state.get(&(2, addr + 3)).unwrap_or(&F::ZERO).as_canonical_u32();
-                let word = byte0 | (byte1 << 8) | (byte2 << 16) | (byte3 << 24);
-                // Write as hex value
-                writeln!(sig_file, "{:08x}", word)
-                    .map_err(|_| ExecutionError::Fail { pc: 0 })?;
-            }
-        }
-        
-        Ok(public_values)
+        let mut exit_code = ExitCode::Success as u32;
+        let mut final_memory = None;
+        let segments = vm.execute_and_then(
+            exe,
+            inputs,
+            |_, mut segment| {
+                let [start, end] = segment
+                    .chip_complex
+                    .connector_chip()
+                    .boundary_states
+                    .map(|state| state.expect("boundary states must be set"));
+                exit_code = end.exit_code;
+                final_memory = segment.final_memory.take();
+                let airs = if trace_heights {
+                    AirCostReport::from_trace_shapes(&segment.air_trace_shapes())
+                } else {
+                    vec![]
+                };
+                Ok(SegmentReport {
+                    initial_pc: start.pc,
+                    final_pc: end.pc,
+                    cycles: segment
+                        .chip_complex
+                        .program_chip()
+                        .execution_frequencies
+                        .iter()
+                        .sum(),
+                    airs,
+                })
+            },
+            |err| err,
+        )?;
+        let public_values = final_memory
+            .map(|final_memory| {
+                extract_public_values(
+                    &vm.config.system().memory_config.memory_dimensions(),
+                    vm.config.system().num_public_values,
+                    &final_memory,
+                )
+                .iter()
+                .map(|value| value.as_canonical_u32())
+                .collect()
+            })
+            .unwrap_or_default();
+        Ok(ExecutionReport {
+            exit_code,
+            public_values,
+            total_cycles: segments.iter().map(|segment| segment.cycles).sum(),
+            num_segments: segments.len(),
+            airs: AirCostReport::total(segments.iter().map(|segment| segment.airs.as_slice())),
+            segments,
+        })
     }
 
     pub fn commit_app_exe(
@@ -279,6 +332,9 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
         VC::Periphery: Chip<SC>,
     {
         let app_prover = AppProver::<VC, E>::new(app_pk.app_vm_pk.clone(), app_committed_exe);
+        if let Some(checkpoints) = &self.checkpoint_dir {
+            return app_prover.generate_app_proof_with_checkpoints(inputs, checkpoints);
+        }
         let proof = app_prover.generate_app_proof(inputs);
         Ok(proof)
     }
@@ -370,6 +426,13 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
     {
         let stark_prover =
             StarkProver::<VC, E>::new(app_pk, app_exe, agg_stark_pk, self.agg_tree_config);
+        if let Some(checkpoints) = &self.checkpoint_dir {
+            let e2e_stark_proof =
+                stark_prover.generate_e2e_stark_proof_with_checkpoints(inputs, checkpoints)?;
+            return Ok(stark_prover
+                .agg_prover
+                .wrap_e2e_stark_proof(e2e_stark_proof));
+        }
         let proof = stark_prover.generate_root_verifier_input(inputs);
         Ok(proof)
     }
@@ -387,6 +450,9 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
     {
         let stark_prover =
             StarkProver::<VC, E>::new(app_pk, app_exe, agg_stark_pk, self.agg_tree_config);
+        if let Some(checkpoints) = &self.checkpoint_dir {
+            return stark_prover.generate_e2e_stark_proof_with_checkpoints(inputs, checkpoints);
+        }
         let proof = stark_prover.generate_e2e_stark_proof(inputs);
         Ok(proof)
     }
@@ -506,6 +572,9 @@ impl<E: StarkFriEngine<SC>> GenericSdk<E> {
     {
         let e2e_prover =
             EvmHalo2Prover::<VC, E>::new(reader, app_pk, app_exe, agg_pk, self.agg_tree_config);
+        if let Some(checkpoints) = &self.checkpoint_dir {
+            return e2e_prover.generate_proof_for_evm_with_checkpoints(inputs, checkpoints);
+        }
         let proof = e2e_prover.generate_proof_for_evm(inputs);
         Ok(proof)
     }
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use eyre::Result;
 use openvm_circuit::arch::ContinuationVmProof;
 use openvm_continuations::verifier::{
     internal::types::{InternalVmVerifierInput, VmStarkProof},
@@ -13,6 +14,7 @@ use openvm_stark_sdk::{engine::StarkFriEngine, openvm_stark_backend::proof::Proo
 use tracing::info_span;
 
 use crate::{
+    checkpoint::{hash_proofs, CheckpointDir},
     config::AggregationTreeConfig,
     keygen::AggStarkProvingKey,
     prover::{
@@ -82,7 +84,7 @@ impl<E: StarkFriEngine<SC>> AggStarkProver<E> {
     /// Generate the root proof for outer recursion.
     pub fn generate_root_proof(&self, app_proofs: ContinuationVmProof<SC>) -> Proof<RootSC> {
         let root_verifier_input = self.generate_root_verifier_input(app_proofs);
-        self.generate_root_proof_impl(root_verifier_input)
+        self.generate_root_proof_from_verifier_input(root_verifier_input)
     }
 
     pub fn generate_leaf_proofs(&self, app_proofs: &ContinuationVmProof<SC>) -> Vec<Proof<SC>> {
@@ -90,6 +92,22 @@ impl<E: StarkFriEngine<SC>> AggStarkProver<E> {
             .generate_proof(&self.leaf_prover, app_proofs)
     }
 
+    /// Like [Self::generate_leaf_proofs], but saves each leaf proof in `checkpoints` and reuses
+    /// the leaf proofs saved there.
+    pub fn generate_leaf_proofs_with_checkpoints(
+        &self,
+        app_proofs: &ContinuationVmProof<SC>,
+        checkpoints: &CheckpointDir,
+    ) -> Result<Vec<Proof<SC>>> {
+        checkpoints.check_agg_leaf_inputs(
+            self.leaf_controller.num_children,
+            self.num_children_internal,
+            hash_proofs([app_proofs])?,
+        )?;
+        self.leaf_controller
+            .generate_proof_impl(&self.leaf_prover, app_proofs, Some(checkpoints))
+    }
+
     pub fn generate_root_verifier_input(
         &self,
         app_proofs: ContinuationVmProof<SC>,
@@ -105,6 +123,31 @@ impl<E: StarkFriEngine<SC>> AggStarkProver<E> {
         leaf_proofs: Vec<Proof<SC>>,
         public_values: Vec<F>,
     ) -> VmStarkProof<SC> {
+        without_checkpoints(self.aggregate_leaf_proofs_impl(leaf_proofs, public_values, None))
+    }
+
+    /// Like [Self::aggregate_leaf_proofs], but saves each internal proof in `checkpoints` and
+    /// reuses the internal proofs saved there.
+    pub fn aggregate_leaf_proofs_with_checkpoints(
+        &self,
+        leaf_proofs: Vec<Proof<SC>>,
+        public_values: Vec<F>,
+        checkpoints: &CheckpointDir,
+    ) -> Result<VmStarkProof<SC>> {
+        checkpoints.check_agg_internal_inputs(
+            self.leaf_controller.num_children,
+            self.num_children_internal,
+            hash_proofs(&leaf_proofs)?,
+        )?;
+        self.aggregate_leaf_proofs_impl(leaf_proofs, public_values, Some(checkpoints))
+    }
+
+    fn aggregate_leaf_proofs_impl(
+        &self,
+        leaf_proofs: Vec<Proof<SC>>,
+        public_values: Vec<F>,
+        checkpoints: Option<&CheckpointDir>,
+    ) -> Result<VmStarkProof<SC>> {
         let mut internal_node_idx = -1;
         let mut internal_node_height = 0;
         let mut proofs = leaf_proofs;
@@ -130,22 +173,26 @@ impl<E: StarkFriEngine<SC>> AggStarkProver<E> {
                         .absolute(self.internal_prover.fri_params().log_blowup as u64);
                     metrics::counter!("num_children").absolute(self.num_children_internal as u64);
                 }
+                let layer = format!("internal.{internal_node_height}");
                 internal_inputs
                     .into_iter()
-                    .map(|input| {
+                    .enumerate()
+                    .map(|(idx, input)| {
                         internal_node_idx += 1;
                         info_span!("single_internal_agg", idx = internal_node_idx,).in_scope(|| {
-                            SingleSegmentVmProver::prove(&self.internal_prover, input.write())
+                            prove_with_checkpoint(checkpoints, &layer, idx, || {
+                                SingleSegmentVmProver::prove(&self.internal_prover, input.write())
+                            })
                         })
                     })
-                    .collect()
-            });
+                    .collect::<Result<_>>()
+            })?;
             internal_node_height += 1;
         }
-        VmStarkProof {
+        Ok(VmStarkProof {
             proof: proofs.pop().unwrap(),
             user_public_values: public_values,
-        }
+        })
     }
 
     /// Wrap the e2e stark proof until its heights meet the requirements of the root verifier.
@@ -167,7 +214,12 @@ impl<E: StarkFriEngine<SC>> AggStarkProver<E> {
         )
     }
 
-    fn generate_root_proof_impl(&self, root_input: RootVmVerifierInput<SC>) -> Proof<RootSC> {
+    /// Generate the root proof from the output of [Self::generate_root_verifier_input] or
+    /// [Self::wrap_e2e_stark_proof].
+    pub fn generate_root_proof_from_verifier_input(
+        &self,
+        root_input: RootVmVerifierInput<SC>,
+    ) -> Proof<RootSC> {
         info_span!("agg_layer", group = "root", idx = 0).in_scope(|| {
             let input = root_input.write();
             #[cfg(feature = "bench-metrics")]
@@ -189,6 +241,15 @@ impl LeafProvingController {
         prover: &VmLocalProver<SC, NativeConfig, E>,
         app_proofs: &ContinuationVmProof<SC>,
     ) -> Vec<Proof<SC>> {
+        without_checkpoints(self.generate_proof_impl(prover, app_proofs, None))
+    }
+
+    fn generate_proof_impl<E: StarkFriEngine<SC>>(
+        &self,
+        prover: &VmLocalProver<SC, NativeConfig, E>,
+        app_proofs: &ContinuationVmProof<SC>,
+        checkpoints: Option<&CheckpointDir>,
+    ) -> Result<Vec<Proof<SC>>> {
         info_span!("agg_layer", group = "leaf").in_scope(|| {
             #[cfg(feature = "bench-metrics")]
             {
@@ -202,14 +263,42 @@ impl LeafProvingController {
                 .into_iter()
                 .enumerate()
                 .map(|(leaf_node_idx, input)| {
-                    info_span!("single_leaf_agg", idx = leaf_node_idx)
-                        .in_scope(|| SingleSegmentVmProver::prove(prover, input.write_to_stream()))
+                    info_span!("single_leaf_agg", idx = leaf_node_idx).in_scope(|| {
+                        prove_with_checkpoint(checkpoints, "leaf", leaf_node_idx, || {
+                            SingleSegmentVmProver::prove(prover, input.write_to_stream())
+                        })
+                    })
                 })
-                .collect::<Vec<_>>()
+                .collect()
         })
     }
 }
 
+/// Unwraps the result of proving without checkpoints, which cannot fail since only reading and
+/// writing checkpoints can.
+fn without_checkpoints<T>(result: Result<T>) -> T {
+    result.expect("proving without checkpoints cannot fail")
+}
+
+/// Returns the proof saved in `checkpoints` for node `idx` of `layer`, or generates and saves it.
+fn prove_with_checkpoint(
+    checkpoints: Option<&CheckpointDir>,
+    layer: &str,
+    idx: usize,
+    prove: impl FnOnce() -> Proof<SC>,
+) -> Result<Proof<SC>> {
+    let Some(checkpoints) = checkpoints else {
+        return Ok(prove());
+    };
+    if let Some(proof) = checkpoints.load_agg_proof(layer, idx)? {
+        tracing::info!("reusing {layer} proof {idx} from checkpoint");
+        return Ok(proof);
+    }
+    let proof = prove();
+    checkpoints.save_agg_proof(layer, idx, &proof)?;
+    Ok(proof)
+}
+
 /// Wrap the e2e stark proof until its heights meet the requirements of the root verifier.
 pub fn wrap_e2e_stark_proof<E: StarkFriEngine<SC>>(
     internal_prover: &VmLocalProver<SC, NativeConfig, E>,
@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
+use eyre::Result;
 use getset::Getters;
-use openvm_circuit::arch::{ContinuationVmProof, VmConfig};
+use openvm_circuit::{
+    arch::{ContinuationVmProof, VmConfig},
+    system::memory::{tree::public_values::UserPublicValuesProof, CHUNK},
+};
 use openvm_stark_backend::{proof::Proof, Chip};
 use openvm_stark_sdk::engine::StarkFriEngine;
 use tracing::info_span;
 
-use super::vm::SingleSegmentVmProver;
+use super::{vm::SingleSegmentVmProver, SegmentProvingTask};
 use crate::{
+    checkpoint::CheckpointDir,
     prover::vm::{local::VmLocalProver, types::VmProvingKey, ContinuationVmProver},
     NonRootCommittedExe, StdIn, F, SC,
 };
@@ -67,6 +72,65 @@ impl<VC, E: StarkFriEngine<SC>> AppProver<VC, E> {
         })
     }
 
+    /// Generates proof for every continuation segment, saving the progress in `checkpoints` and
+    /// resuming from the last proven segment saved there. The same input must be given when
+    /// resuming.
+    pub fn generate_app_proof_with_checkpoints(
+        &self,
+        input: StdIn,
+        checkpoints: &CheckpointDir,
+    ) -> Result<ContinuationVmProof<SC>>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        assert!(
+            self.vm_config().system().continuation_enabled,
+            "Use generate_app_proof_without_continuations instead."
+        );
+        info_span!(
+            "app proof",
+            group = self
+                .program_name
+                .as_ref()
+                .unwrap_or(&"app_proof".to_string())
+        )
+        .in_scope(|| {
+            #[cfg(feature = "bench-metrics")]
+            metrics::counter!("fri.log_blowup")
+                .absolute(self.app_prover.pk.fri_params.log_blowup as u64);
+            self.app_prover.prove_with_checkpoints(input, checkpoints)
+        })
+    }
+
+    /// Executes the program and passes a task to prove every continuation segment to `emit`.
+    /// Returns the proof of the user public values, which completes the proofs of the tasks into
+    /// the app proof. See [SegmentTaskQueue](crate::prover::SegmentTaskQueue).
+    pub fn plan_segment_tasks(
+        &self,
+        input: StdIn,
+        emit: impl FnMut(SegmentProvingTask) -> Result<()>,
+    ) -> Result<UserPublicValuesProof<{ CHUNK }, F>>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        self.app_prover.plan_segment_tasks(input, emit)
+    }
+
+    /// Proves a single task of [Self::plan_segment_tasks].
+    pub fn prove_segment_task(&self, task: SegmentProvingTask) -> Result<Proof<SC>>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        info_span!("segment task", segment = task.segment_idx)
+            .in_scope(|| self.app_prover.prove_segment_task(task))
+    }
+
     pub fn generate_app_proof_without_continuations(&self, input: StdIn) -> Proof<SC>
     where
         VC: VmConfig<F>,
@@ -0,0 +1,388 @@
+//! Proving the segments of an app proof in several processes. A coordinator executes the program
+//! once and queues a [SegmentProvingTask] per segment in a [SegmentTaskQueue] directory. Workers,
+//! possibly on other machines sharing the directory, claim and prove the tasks, and the
+//! coordinator assembles their proofs into a [ContinuationVmProof].
+//!
+//! The queue directory contains:
+//! - `tasks/<idx>.task`: the tasks which are not claimed yet
+//! - `claimed/<idx>.task`: the tasks a worker is proving
+//! - `proofs/<idx>.proof`: the proofs of the finished tasks
+//! - `failed/<idx>.txt`: the errors of the tasks which could not be proven
+//! - `public_values.proof` and `plan.json`: written once all tasks are queued
+//!
+//! A worker refreshes the modification time of its claimed task while proving it. Claims which
+//! were not refreshed within the claim timeout, e.g. because their worker died, are queued again.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use eyre::{bail, Result, WrapErr};
+use openvm_circuit::{
+    arch::{ContinuationVmProof, KvStore, VmConfig, VmSegmentCheckpoint},
+    system::memory::tree::public_values::UserPublicValuesProof,
+};
+use openvm_stark_backend::{proof::Proof, Chip};
+use openvm_stark_sdk::engine::StarkFriEngine;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    checkpoint::{read_if_exists, remove_dir_if_exists, write_atomic},
+    codec::{Decode, Encode},
+    prover::AppProver,
+    StdIn, F, SC,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const CLAIM_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_CLAIM_TIMEOUT: Duration = Duration::from_secs(60);
+
+const QUEUE_SUBDIRS: [&str; 4] = ["tasks", "claimed", "proofs", "failed"];
+const QUEUE_FILES: [&str; 2] = ["public_values.proof", "plan.json"];
+
+/// A continuation segment which can be proven on its own: the state of the VM at the start of
+/// the segment, with the inputs and the key-value store entries it reads.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SegmentProvingTask {
+    pub segment_idx: usize,
+    /// Number of times the segmentation was made stricter to keep the segments within the trace
+    /// height limits.
+    pub segmentation_retries: usize,
+    pub state: VmSegmentCheckpoint<F>,
+    pub kv_entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Records the entries read from a [KvStore], to give them to a [SegmentProvingTask].
+pub(crate) struct RecordingKvStore {
+    inner: Arc<dyn KvStore>,
+    reads: Mutex<Vec<(Vec<u8>, Vec<u8>)>>,
+}
+
+impl RecordingKvStore {
+    pub(crate) fn new(inner: Arc<dyn KvStore>) -> Self {
+        Self {
+            inner,
+            reads: Mutex::default(),
+        }
+    }
+
+    /// Returns the entries read since the last call.
+    pub(crate) fn take_reads(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        std::mem::take(&mut self.reads.lock().unwrap())
+    }
+
+    fn record(&self, key: &[u8], value: &[u8]) {
+        let mut reads = self.reads.lock().unwrap();
+        reads.push((key.to_vec(), value.to_vec()));
+    }
+}
+
+impl KvStore for RecordingKvStore {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let value = self.inner.get(key)?;
+        self.record(key, value);
+        Some(value)
+    }
+
+    fn try_get(&self, key: &[u8]) -> io::Result<Option<Cow<'_, [u8]>>> {
+        let value = self.inner.try_get(key)?;
+        if let Some(value) = &value {
+            self.record(key, value);
+        }
+        Ok(value)
+    }
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        self.inner.fingerprint()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Plan {
+    num_segments: usize,
+}
+
+/// A directory of [SegmentProvingTask]s shared by a coordinator and its workers.
+#[derive(Clone, Debug)]
+pub struct SegmentTaskQueue {
+    dir: PathBuf,
+    claim_timeout: Duration,
+}
+
+impl SegmentTaskQueue {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            claim_timeout: DEFAULT_CLAIM_TIMEOUT,
+        }
+    }
+
+    /// Sets the time after which a claimed task whose worker stopped refreshing it is queued
+    /// again. The clocks of the machines sharing the directory must agree to within it.
+    pub fn with_claim_timeout(mut self, claim_timeout: Duration) -> Self {
+        self.claim_timeout = claim_timeout;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Executes the program and queues a task for every segment, which workers can prove while
+    /// the next segments are executed. Returns the number of segments.
+    pub fn plan<VC, E: StarkFriEngine<SC>>(
+        &self,
+        app_prover: &AppProver<VC, E>,
+        input: StdIn,
+    ) -> Result<usize>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let mut num_segments = 0;
+        let user_public_values = app_prover.plan_segment_tasks(input, |task| {
+            num_segments += 1;
+            let path = self.task_path("tasks", task.segment_idx);
+            write_atomic(&path, &bitcode::serialize(&task)?)
+        })?;
+        tracing::info!("queued {num_segments} segment proving tasks");
+        write_atomic(
+            &self.dir.join("public_values.proof"),
+            &user_public_values.encode_to_vec()?,
+        )?;
+        write_atomic(
+            &self.dir.join("plan.json"),
+            &serde_json::to_vec(&Plan { num_segments })?,
+        )?;
+        Ok(num_segments)
+    }
+
+    /// Proves the queued tasks until all tasks are queued and proven, or one of them failed. The
+    /// tasks of dead workers are claimed again once their claims are stale. Returns the number
+    /// of tasks proven by this worker.
+    pub fn run_worker<VC, E: StarkFriEngine<SC>>(
+        &self,
+        app_prover: &AppProver<VC, E>,
+    ) -> Result<usize>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let mut num_proven = 0;
+        loop {
+            if !self.list("failed")?.is_empty() {
+                return Ok(num_proven);
+            }
+            let planned = self.dir.join("plan.json").exists();
+            let Some((segment_idx, task)) = self.claim()? else {
+                // The claims are listed before the tasks, so that a task queued again in between
+                // is seen.
+                if planned && self.list("claimed")?.is_empty() && self.list("tasks")?.is_empty() {
+                    return Ok(num_proven);
+                }
+                self.requeue_stale_claims()?;
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            };
+            tracing::info!("proving segment {segment_idx}");
+            let claimed_path = self.task_path("claimed", segment_idx);
+            let result = thread::scope(|s| {
+                let (done, heartbeat) = mpsc::channel::<()>();
+                let claimed_path = &claimed_path;
+                s.spawn(move || {
+                    while let Err(RecvTimeoutError::Timeout) =
+                        heartbeat.recv_timeout(CLAIM_HEARTBEAT_INTERVAL)
+                    {
+                        // Fails if the claim was queued again, which is harmless.
+                        let _ = touch(claimed_path);
+                    }
+                });
+                let result = app_prover.prove_segment_task(task);
+                drop(done);
+                result
+            });
+            match result {
+                Ok(proof) => {
+                    let path = self.proof_path(segment_idx);
+                    write_atomic(&path, &proof.encode_to_vec()?)?;
+                    num_proven += 1;
+                }
+                Err(err) => {
+                    let path = self.dir.join("failed").join(format!("{segment_idx}.txt"));
+                    write_atomic(&path, format!("{err:#}").as_bytes())?;
+                }
+            }
+            remove_file_if_exists(&claimed_path)?;
+        }
+    }
+
+    /// Waits until all tasks are proven, and returns the app proof.
+    pub fn wait_for_proof(&self) -> Result<ContinuationVmProof<SC>> {
+        self.wait_for_proof_while(|| Ok(true))
+    }
+
+    /// Like [Self::wait_for_proof], but fails once `workers_running` returns false before all
+    /// tasks are proven, e.g. because the worker processes exited. Stale claims are queued again
+    /// meanwhile.
+    pub fn wait_for_proof_while(
+        &self,
+        mut workers_running: impl FnMut() -> Result<bool>,
+    ) -> Result<ContinuationVmProof<SC>> {
+        loop {
+            // Checked before the proofs, so that the proofs of the workers which exited are seen.
+            let running = workers_running()?;
+            if let Some(&segment_idx) = self.list("failed")?.first() {
+                let path = self.dir.join("failed").join(format!("{segment_idx}.txt"));
+                let error = fs::read_to_string(path)?;
+                bail!("segment {segment_idx} could not be proven: {error}");
+            }
+            if let Some(bytes) = read_if_exists(&self.dir.join("plan.json"))? {
+                let plan: Plan = serde_json::from_slice(&bytes)?;
+                if self.list("proofs")?.len() == plan.num_segments {
+                    return self.collect_proof(plan.num_segments);
+                }
+            }
+            if !running {
+                bail!("all workers exited before the segments were proven");
+            }
+            self.requeue_stale_claims()?;
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Deletes the tasks, proofs and other files of the queue. Other files in the directory are
+    /// left untouched.
+    pub fn remove(&self) -> Result<()> {
+        for subdir in QUEUE_SUBDIRS {
+            remove_dir_if_exists(&self.dir.join(subdir))?;
+        }
+        for file in QUEUE_FILES {
+            remove_file_if_exists(&self.dir.join(file))?;
+        }
+        Ok(())
+    }
+
+    /// Claims the first unclaimed task, by moving it so that no other worker can claim it.
+    fn claim(&self) -> Result<Option<(usize, SegmentProvingTask)>> {
+        fs::create_dir_all(self.dir.join("claimed"))?;
+        for segment_idx in self.list("tasks")? {
+            let claimed_path = self.task_path("claimed", segment_idx);
+            match fs::rename(self.task_path("tasks", segment_idx), &claimed_path) {
+                Ok(()) => {}
+                // Another worker claimed it first.
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            }
+            // Renaming keeps the modification time of when the task was queued.
+            let bytes = match touch(&claimed_path).and_then(|()| fs::read(&claimed_path)) {
+                Ok(bytes) => bytes,
+                // Queued again as stale before it was refreshed.
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let task = bitcode::deserialize(&bytes)
+                .wrap_err_with(|| format!("Failed to read task {segment_idx}"))?;
+            return Ok(Some((segment_idx, task)));
+        }
+        Ok(None)
+    }
+
+    /// Queues the claimed tasks again whose claims were not refreshed within the claim timeout.
+    fn requeue_stale_claims(&self) -> Result<()> {
+        for segment_idx in self.list("claimed")? {
+            let claimed_path = self.task_path("claimed", segment_idx);
+            let modified = match fs::metadata(&claimed_path).and_then(|m| m.modified()) {
+                Ok(modified) => modified,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if modified.elapsed().unwrap_or_default() < self.claim_timeout {
+                continue;
+            }
+            if self.proof_path(segment_idx).exists() {
+                // The worker exited after writing the proof.
+                remove_file_if_exists(&claimed_path)?;
+                continue;
+            }
+            tracing::warn!("the worker proving segment {segment_idx} stopped; queueing it again");
+            match fs::rename(&claimed_path, self.task_path("tasks", segment_idx)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_proof(&self, num_segments: usize) -> Result<ContinuationVmProof<SC>> {
+        let per_segment = (0..num_segments)
+            .map(|segment_idx| {
+                let bytes = fs::read(self.proof_path(segment_idx))?;
+                Ok(Proof::<SC>::decode_from_bytes(&bytes)?)
+            })
+            .collect::<Result<_>>()?;
+        let bytes = fs::read(self.dir.join("public_values.proof"))?;
+        Ok(ContinuationVmProof {
+            per_segment,
+            user_public_values: UserPublicValuesProof::decode_from_bytes(&bytes)?,
+        })
+    }
+
+    /// Returns the segment indices of the files in `subdir`, in order.
+    fn list(&self, subdir: &str) -> Result<Vec<usize>> {
+        let entries = match fs::read_dir(self.dir.join(subdir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut indices = BTreeSet::new();
+        for entry in entries {
+            let path = entry?.path();
+            // Skips the temporary files of writes in progress.
+            if path.extension().is_some_and(|ext| ext == "tmp") {
+                continue;
+            }
+            if let Some(idx) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.parse().ok())
+            {
+                indices.insert(idx);
+            }
+        }
+        Ok(indices.into_iter().collect())
+    }
+
+    fn task_path(&self, subdir: &str, segment_idx: usize) -> PathBuf {
+        self.dir.join(subdir).join(format!("{segment_idx}.task"))
+    }
+
+    fn proof_path(&self, segment_idx: usize) -> PathBuf {
+        self.dir.join("proofs").join(format!("{segment_idx}.proof"))
+    }
+}
+
+/// Sets the modification time of the file at `path` to now.
+fn touch(path: &Path) -> io::Result<()> {
+    fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(e).wrap_err_with(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
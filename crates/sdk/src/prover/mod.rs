@@ -1,5 +1,6 @@
 mod agg;
 mod app;
+mod distributed;
 #[cfg(feature = "evm-prove")]
 mod halo2;
 mod root;
@@ -8,6 +9,7 @@ pub mod vm;
 
 pub use agg::*;
 pub use app::*;
+pub use distributed::*;
 #[cfg(feature = "evm-prove")]
 pub use evm::*;
 #[cfg(feature = "evm-prove")]
@@ -19,12 +21,14 @@ pub use stark::*;
 mod evm {
     use std::sync::Arc;
 
+    use eyre::Result;
     use openvm_circuit::arch::VmConfig;
     use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
     use openvm_stark_sdk::{engine::StarkFriEngine, openvm_stark_backend::Chip};
 
     use super::{Halo2Prover, StarkProver};
     use crate::{
+        checkpoint::CheckpointDir,
         config::AggregationTreeConfig,
         keygen::{AggProvingKey, AppProvingKey},
         stdin::StdIn,
@@ -74,5 +78,26 @@ mod evm {
             let root_proof = self.stark_prover.generate_proof_for_outer_recursion(input);
             self.halo2_prover.prove_for_evm(&root_proof)
         }
+
+        /// Like [Self::generate_proof_for_evm], but saves the app, leaf and internal proofs in
+        /// `checkpoints` as they are generated and resumes from the ones saved there.
+        pub fn generate_proof_for_evm_with_checkpoints(
+            &self,
+            input: StdIn,
+            checkpoints: &CheckpointDir,
+        ) -> Result<EvmProof>
+        where
+            VC: VmConfig<F>,
+            VC::Executor: Chip<SC>,
+            VC::Periphery: Chip<SC>,
+        {
+            let e2e_stark_proof = self
+                .stark_prover
+                .generate_e2e_stark_proof_with_checkpoints(input, checkpoints)?;
+            let agg_prover = &self.stark_prover.agg_prover;
+            let root_input = agg_prover.wrap_e2e_stark_proof(e2e_stark_proof);
+            let root_proof = agg_prover.generate_root_proof_from_verifier_input(root_input);
+            Ok(self.halo2_prover.prove_for_evm(&root_proof))
+        }
     }
 }
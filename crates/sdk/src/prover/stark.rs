@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use eyre::Result;
 use openvm_circuit::arch::VmConfig;
 use openvm_continuations::verifier::{
     internal::types::VmStarkProof, root::types::RootVmVerifierInput,
@@ -8,6 +9,7 @@ use openvm_stark_backend::{proof::Proof, Chip};
 use openvm_stark_sdk::engine::StarkFriEngine;
 
 use crate::{
+    checkpoint::CheckpointDir,
     config::AggregationTreeConfig,
     keygen::{AggStarkProvingKey, AppProvingKey},
     prover::{agg::AggStarkProver, app::AppProver},
@@ -82,4 +84,29 @@ impl<VC, E: StarkFriEngine<SC>> StarkProver<VC, E> {
         self.agg_prover
             .aggregate_leaf_proofs(leaf_proofs, app_proof.user_public_values.public_values)
     }
+
+    /// Like [Self::generate_e2e_stark_proof], but saves the app, leaf and internal proofs in
+    /// `checkpoints` as they are generated and resumes from the ones saved there.
+    pub fn generate_e2e_stark_proof_with_checkpoints(
+        &self,
+        input: StdIn,
+        checkpoints: &CheckpointDir,
+    ) -> Result<VmStarkProof<SC>>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let app_proof = self
+            .app_prover
+            .generate_app_proof_with_checkpoints(input, checkpoints)?;
+        let leaf_proofs = self
+            .agg_prover
+            .generate_leaf_proofs_with_checkpoints(&app_proof, checkpoints)?;
+        self.agg_prover.aggregate_leaf_proofs_with_checkpoints(
+            leaf_proofs,
+            app_proof.user_public_values.public_values,
+            checkpoints,
+        )
+    }
 }
@@ -1,25 +1,37 @@
-use std::{marker::PhantomData, mem, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, mem, sync::Arc};
 
 use async_trait::async_trait;
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, GenerationError, SingleSegmentVmExecutor, Streams,
-        VirtualMachine, VmComplexTraceHeights, VmConfig,
+        hasher::poseidon2::vm_poseidon2_hasher, ExecutionSegment, GenerationError,
+        SingleSegmentVmExecutor, Streams, VirtualMachine, VmComplexTraceHeights, VmConfig,
+        VmExecutorNextSegmentState, VmExecutorOneSegmentResult,
+    },
+    system::{
+        memory::{tree::public_values::UserPublicValuesProof, MemoryImage, CHUNK},
+        program::trace::VmCommittedExe,
     },
-    system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     p3_field::PrimeField32,
     proof::Proof,
+    prover::types::CommittedTraceData,
     Chip,
 };
 use openvm_stark_sdk::{config::FriParameters, engine::StarkFriEngine};
 use tracing::info_span;
 
-use crate::prover::vm::{
-    types::VmProvingKey, AsyncContinuationVmProver, AsyncSingleSegmentVmProver,
-    ContinuationVmProof, ContinuationVmProver, SingleSegmentVmProver,
+use crate::{
+    checkpoint::{hash_input, AppProgress, CheckpointDir},
+    prover::{
+        vm::{
+            types::VmProvingKey, AsyncContinuationVmProver, AsyncSingleSegmentVmProver,
+            ContinuationVmProof, ContinuationVmProver, SingleSegmentVmProver,
+        },
+        RecordingKvStore, SegmentProvingTask,
+    },
+    StdIn, F, SC,
 };
 
 pub struct VmLocalProver<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> {
@@ -67,23 +79,280 @@ impl<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
 
 const MAX_SEGMENTATION_RETRIES: usize = 4;
 
-impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> ContinuationVmProver<SC>
-    for VmLocalProver<SC, VC, E>
+impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
 where
     Val<SC>: PrimeField32,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
-    fn prove(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC> {
-        assert!(self.pk.vm_config.system().continuation_enabled);
+    fn new_continuation_vm(&self) -> VirtualMachine<SC, E, VC> {
         let e = E::new(self.pk.fri_params);
-        let trace_height_constraints = self.pk.vm_pk.trace_height_constraints.clone();
         let mut vm = VirtualMachine::new_with_overridden_trace_heights(
             e,
             self.pk.vm_config.clone(),
             self.overridden_heights.clone(),
         );
-        vm.set_trace_height_constraints(trace_height_constraints.clone());
+        vm.set_trace_height_constraints(self.pk.vm_pk.trace_height_constraints.clone());
+        vm
+    }
+
+    fn prove_segment(
+        &self,
+        vm: &VirtualMachine<SC, E, VC>,
+        committed_program: &CommittedTraceData<SC>,
+        seg_idx: usize,
+        seg: ExecutionSegment<Val<SC>, VC>,
+    ) -> Result<Proof<SC>, GenerationError> {
+        let proof_input = info_span!("trace_gen", segment = seg_idx)
+            .in_scope(|| seg.generate_proof_input(Some(committed_program.clone())))?;
+        info_span!("prove_segment", segment = seg_idx)
+            .in_scope(|| Ok(vm.engine.prove(&self.pk.vm_pk, proof_input)))
+    }
+
+    fn compute_user_public_values(
+        &self,
+        final_memory: &MemoryImage<Val<SC>>,
+    ) -> UserPublicValuesProof<{ CHUNK }, Val<SC>> {
+        UserPublicValuesProof::compute(
+            self.pk.vm_config.system().memory_config.memory_dimensions(),
+            self.pk.vm_config.system().num_public_values,
+            &vm_poseidon2_hasher(),
+            final_memory,
+        )
+    }
+}
+
+impl<VC: VmConfig<F>, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
+where
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    /// Like [ContinuationVmProver::prove], but saves the proof of every segment and the state to
+    /// execute the next segment from in `checkpoints`, and resumes after the last saved segment.
+    /// Checkpoints saved for another input are discarded.
+    pub fn prove_with_checkpoints(
+        &self,
+        input: StdIn,
+        checkpoints: &CheckpointDir,
+    ) -> eyre::Result<ContinuationVmProof<SC>> {
+        assert!(self.pk.vm_config.system().continuation_enabled);
+        let mut vm = self.new_continuation_vm();
+        let VmCommittedExe {
+            exe,
+            committed_program,
+        } = self.committed_exe.as_ref();
+        let program_commit = self.committed_exe.get_program_commit().into();
+        let input_hash = hash_input(&input)?;
+        let input: Streams<F> = input.into();
+
+        let (mut progress, mut per_segment, mut state) = match checkpoints
+            .load_app_progress(program_commit, input_hash)?
+        {
+            Some((mut progress, per_segment)) => {
+                for _ in 0..progress.segmentation_retries {
+                    use_stricter_segmentation(&mut vm);
+                }
+                let Some(checkpoint) = progress.next_state.take() else {
+                    // The public values proof is saved before the progress of the last
+                    // segment.
+                    let user_public_values = checkpoints
+                        .load_app_public_values()?
+                        .ok_or_else(|| eyre::eyre!("missing public values checkpoint"))?;
+                    return Ok(ContinuationVmProof {
+                        per_segment,
+                        user_public_values,
+                    });
+                };
+                tracing::info!("resuming from segment {}", progress.num_segments);
+                let state = VmExecutorNextSegmentState::from_checkpoint(checkpoint, input.clone())?;
+                (progress, per_segment, state)
+            }
+            None => {
+                let progress = AppProgress {
+                    program_commit,
+                    input_hash,
+                    segmentation_retries: 0,
+                    num_segments: 0,
+                    next_state: None,
+                };
+                (
+                    progress,
+                    vec![],
+                    vm.executor.initial_state(exe, input.clone()),
+                )
+            }
+        };
+        let user_public_values = loop {
+            let seg_idx = progress.num_segments;
+            let VmExecutorOneSegmentResult {
+                mut segment,
+                next_state,
+            } = info_span!("execute_segment", segment = seg_idx)
+                .in_scope(|| vm.executor.execute_until_segment(exe.clone(), state))
+                .map_err(|err| eyre::eyre!("execution error: {err}"))?;
+            let final_memory = mem::take(&mut segment.final_memory);
+            let proof = match self.prove_segment(&vm, committed_program, seg_idx, segment) {
+                Ok(proof) => proof,
+                Err(GenerationError::Execution(err)) => eyre::bail!("execution error: {err}"),
+                Err(GenerationError::TraceHeightsLimitExceeded) => {
+                    // The segments have to be proven again with the stricter segmentation.
+                    progress.segmentation_retries += 1;
+                    check_segmentation_retries(progress.segmentation_retries);
+                    use_stricter_segmentation(&mut vm);
+                    checkpoints.clear_app()?;
+                    progress.num_segments = 0;
+                    per_segment.clear();
+                    state = vm.executor.initial_state(exe, input.clone());
+                    continue;
+                }
+            };
+            checkpoints.save_segment_proof(seg_idx, &proof)?;
+            per_segment.push(proof);
+            progress.num_segments += 1;
+            progress.next_state = next_state.as_ref().map(|state| state.checkpoint());
+            let Some(next_state) = next_state else {
+                let user_public_values =
+                    self.compute_user_public_values(final_memory.as_ref().unwrap());
+                checkpoints.save_app_public_values(&user_public_values)?;
+                checkpoints.save_app_progress(&progress)?;
+                break user_public_values;
+            };
+            checkpoints.save_app_progress(&progress)?;
+            state = next_state;
+        };
+        Ok(ContinuationVmProof {
+            per_segment,
+            user_public_values,
+        })
+    }
+
+    /// Executes the program without generating traces, and passes a [SegmentProvingTask] for
+    /// every segment to `emit`, in order. Returns the proof of the user public values, which
+    /// completes the proofs of the tasks into a [ContinuationVmProof].
+    ///
+    /// A segment exceeding the trace height limits is executed again with a stricter
+    /// segmentation, which is then used for the next segments too.
+    pub fn plan_segment_tasks(
+        &self,
+        input: impl Into<Streams<F>>,
+        mut emit: impl FnMut(SegmentProvingTask) -> eyre::Result<()>,
+    ) -> eyre::Result<UserPublicValuesProof<{ CHUNK }, F>> {
+        assert!(self.pk.vm_config.system().continuation_enabled);
+        let mut vm = self.new_continuation_vm();
+        let exe = &self.committed_exe.exe;
+        let mut input = input.into();
+        let kv_store = Arc::new(RecordingKvStore::new(input.kv_store.clone()));
+        input.kv_store = kv_store.clone();
+
+        let mut state = vm.executor.initial_state(exe, input);
+        let mut segment_idx = 0;
+        let mut segmentation_retries = 0;
+        loop {
+            let mut task_state = state.checkpoint();
+            let mut provider_inputs = state.input.input_stream.clone();
+            let segment_input = state.input.clone();
+            let VmExecutorOneSegmentResult {
+                mut segment,
+                next_state,
+            } = info_span!("execute_segment", segment = segment_idx)
+                .in_scope(|| vm.executor.execute_until_segment(exe.clone(), state))
+                .map_err(|err| eyre::eyre!("execution error: {err}"))?;
+            match segment.check_trace_heights() {
+                Ok(()) => {}
+                Err(GenerationError::Execution(err)) => eyre::bail!("execution error: {err}"),
+                Err(GenerationError::TraceHeightsLimitExceeded) => {
+                    segmentation_retries += 1;
+                    check_segmentation_retries(segmentation_retries);
+                    use_stricter_segmentation(&mut vm);
+                    state = VmExecutorNextSegmentState::from_checkpoint(task_state, segment_input)?;
+                    continue;
+                }
+            }
+            let provider_inputs_read = match &next_state {
+                Some(next_state) => next_state.input.input_stream.provider_inputs_read(),
+                None => segment
+                    .chip_complex
+                    .take_streams()
+                    .input_stream
+                    .provider_inputs_read(),
+            };
+            // The task gets the inputs read from the provider during the segment after the
+            // inputs in memory, so that it does not need the provider.
+            provider_inputs.restore(vec![], task_state.provider_inputs_read)?;
+            for _ in task_state.provider_inputs_read..provider_inputs_read {
+                task_state.input_buffer.extend(provider_inputs.pop_front()?);
+            }
+            task_state.provider_inputs_read = 0;
+            emit(SegmentProvingTask {
+                segment_idx,
+                segmentation_retries,
+                state: task_state,
+                kv_entries: kv_store.take_reads(),
+            })?;
+
+            match next_state {
+                Some(next_state) => state = next_state,
+                None => {
+                    let final_memory = segment.final_memory.take().unwrap();
+                    return Ok(self.compute_user_public_values(&final_memory));
+                }
+            }
+            segment_idx += 1;
+        }
+    }
+
+    /// Proves a task of [Self::plan_segment_tasks], e.g. in another process.
+    pub fn prove_segment_task(&self, task: SegmentProvingTask) -> eyre::Result<Proof<SC>> {
+        let mut vm = self.new_continuation_vm();
+        for _ in 0..task.segmentation_retries {
+            use_stricter_segmentation(&mut vm);
+        }
+        let VmCommittedExe {
+            exe,
+            committed_program,
+        } = self.committed_exe.as_ref();
+        let streams = Streams {
+            kv_store: Arc::new(task.kv_entries.into_iter().collect::<HashMap<_, _>>()),
+            ..Default::default()
+        };
+        let state = VmExecutorNextSegmentState::from_checkpoint(task.state, streams)?;
+        let seg_idx = task.segment_idx;
+        let segment = info_span!("execute_segment", segment = seg_idx)
+            .in_scope(|| vm.executor.execute_until_segment(exe.clone(), state))
+            .map_err(|err| eyre::eyre!("execution error: {err}"))?
+            .segment;
+        self.prove_segment(&vm, committed_program, seg_idx, segment)
+            .map_err(|err| eyre::eyre!("failed to prove segment {seg_idx}: {err}"))
+    }
+}
+
+fn check_segmentation_retries(retries: usize) {
+    if retries > MAX_SEGMENTATION_RETRIES {
+        panic!("trace heights limit exceeded after {MAX_SEGMENTATION_RETRIES} retries");
+    }
+    tracing::info!("trace heights limit exceeded; retrying execution (attempt {retries})");
+}
+
+fn use_stricter_segmentation<SC: StarkGenericConfig, E, VC: VmConfig<Val<SC>>>(
+    vm: &mut VirtualMachine<SC, E, VC>,
+) where
+    Val<SC>: PrimeField32,
+{
+    let sys_config = vm.executor.config.system_mut();
+    let new_seg_strat = sys_config.segmentation_strategy.stricter_strategy();
+    sys_config.set_segmentation_strategy(new_seg_strat);
+}
+
+impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> ContinuationVmProver<SC>
+    for VmLocalProver<SC, VC, E>
+where
+    Val<SC>: PrimeField32,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    fn prove(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC> {
+        assert!(self.pk.vm_config.system().continuation_enabled);
+        let mut vm = self.new_continuation_vm();
         let mut final_memory = None;
         let VmCommittedExe {
             exe,
@@ -100,39 +369,22 @@ where
                 input.clone(),
                 |seg_idx, mut seg| {
                     final_memory = mem::take(&mut seg.final_memory);
-                    let proof_input = info_span!("trace_gen", segment = seg_idx)
-                        .in_scope(|| seg.generate_proof_input(Some(committed_program.clone())))?;
-                    info_span!("prove_segment", segment = seg_idx)
-                        .in_scope(|| Ok(vm.engine.prove(&self.pk.vm_pk, proof_input)))
+                    self.prove_segment(&vm, committed_program, seg_idx, seg)
                 },
                 GenerationError::Execution,
             ) {
                 Ok(per_segment) => break per_segment,
                 Err(GenerationError::Execution(err)) => panic!("execution error: {err}"),
                 Err(GenerationError::TraceHeightsLimitExceeded) => {
-                    if retries >= MAX_SEGMENTATION_RETRIES {
-                        panic!(
-                            "trace heights limit exceeded after {MAX_SEGMENTATION_RETRIES} retries"
-                        );
-                    }
                     retries += 1;
-                    tracing::info!(
-                        "trace heights limit exceeded; retrying execution (attempt {retries})"
-                    );
-                    let sys_config = vm.executor.config.system_mut();
-                    let new_seg_strat = sys_config.segmentation_strategy.stricter_strategy();
-                    sys_config.set_segmentation_strategy(new_seg_strat);
+                    check_segmentation_retries(retries);
+                    use_stricter_segmentation(&mut vm);
                     // continue
                 }
             };
         };
 
-        let user_public_values = UserPublicValuesProof::compute(
-            self.pk.vm_config.system().memory_config.memory_dimensions(),
-            self.pk.vm_config.system().num_public_values,
-            &vm_poseidon2_hasher(),
-            final_memory.as_ref().unwrap(),
-        );
+        let user_public_values = self.compute_user_public_values(final_memory.as_ref().unwrap());
         ContinuationVmProof {
             per_segment,
             user_public_values,
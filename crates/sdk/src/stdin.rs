@@ -3,16 +3,43 @@ use std::{
     sync::Arc,
 };
 
-use openvm_circuit::arch::Streams;
+use openvm_circuit::arch::{ChainedKvStore, InputProvider, KvStore, Streams};
 use openvm_stark_backend::p3_field::FieldAlgebra;
-use serde::{Deserialize, Serialize};
+use serde::{
+    ser::{Error as _, SerializeStruct},
+    Deserialize, Serialize, Serializer,
+};
 
 use crate::F;
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+/// Inputs of the guest program. Serializing fails if an input provider is set, since the lazily
+/// read inputs cannot be serialized without reading them all.
+#[derive(Clone, Default, Deserialize)]
 pub struct StdIn {
     pub buffer: VecDeque<Vec<F>>,
     pub kv_store: HashMap<Vec<u8>, Vec<u8>>,
+    /// Key-value stores looked up in order for keys not in `kv_store`, e.g. backed by files.
+    /// Not serialized.
+    #[serde(skip)]
+    pub kv_stores: Vec<Arc<dyn KvStore>>,
+    /// Inputs read lazily during execution, after the inputs in `buffer`. Serializing a `StdIn`
+    /// with a provider is an error.
+    #[serde(skip)]
+    pub provider: Option<Box<dyn InputProvider>>,
+}
+
+impl Serialize for StdIn {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.provider.is_some() {
+            return Err(S::Error::custom(
+                "cannot serialize StdIn with an input provider",
+            ));
+        }
+        let mut state = serializer.serialize_struct("StdIn", 2)?;
+        state.serialize_field("buffer", &self.buffer)?;
+        state.serialize_field("kv_store", &self.kv_store)?;
+        state.end()
+    }
 }
 
 impl StdIn {
@@ -43,6 +70,19 @@ impl StdIn {
     pub fn add_key_value(&mut self, key: Vec<u8>, value: Vec<u8>) {
         self.kv_store.insert(key, value);
     }
+
+    /// Looks up keys not added with [Self::add_key_value] in `store`, after the stores added
+    /// before it. Use a file-backed store such as
+    /// [MmapKvStore](openvm_circuit::arch::MmapKvStore) for values too large to hold in memory.
+    pub fn add_kv_store(&mut self, store: impl KvStore + 'static) {
+        self.kv_stores.push(Arc::new(store));
+    }
+
+    /// Reads further inputs from `provider` once the written inputs are consumed, e.g. to stream
+    /// large inputs from a file instead of holding them in memory.
+    pub fn set_provider(&mut self, provider: impl InputProvider + 'static) {
+        self.provider = Some(Box::new(provider));
+    }
 }
 
 impl From<StdIn> for Streams<F> {
@@ -52,7 +92,16 @@ impl From<StdIn> for Streams<F> {
             data.push(input);
         }
         let mut ret = Streams::new(data);
-        ret.kv_store = Arc::new(std_in.kv_store);
+        if let Some(provider) = std_in.provider {
+            ret.input_stream.set_provider(provider);
+        }
+        ret.kv_store = if std_in.kv_stores.is_empty() {
+            Arc::new(std_in.kv_store)
+        } else {
+            let mut stores: Vec<Arc<dyn KvStore>> = vec![Arc::new(std_in.kv_store)];
+            stores.extend(std_in.kv_stores);
+            Arc::new(ChainedKvStore(stores))
+        };
         ret
     }
 }
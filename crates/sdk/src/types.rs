@@ -1,6 +1,7 @@
 use std::io::Cursor;
 
 use eyre::Result;
+use openvm_circuit::arch::AirTraceShape;
 use openvm_continuations::{verifier::internal::types::VmStarkProof, SC};
 use openvm_stark_backend::proof::Proof;
 use serde::{Deserialize, Serialize};
@@ -254,3 +255,96 @@ impl TryFrom<VmStarkProofBytes> for VmStarkProof<SC> {
         })
     }
 }
+
+/// Machine-readable summary of a guest execution.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExecutionReport {
+    /// Exit code of the guest. Only executions with exit code 0 can be proven.
+    pub exit_code: u32,
+    /// User public values as canonical field elements. Empty if continuations are disabled.
+    pub public_values: Vec<u32>,
+    /// Total number of executed instructions.
+    pub total_cycles: usize,
+    pub num_segments: usize,
+    pub segments: Vec<SegmentReport>,
+    /// Trace cost of each AIR summed over all segments. Only collected on request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub airs: Vec<AirCostReport>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SegmentReport {
+    pub initial_pc: u32,
+    pub final_pc: u32,
+    /// Number of instructions executed in this segment.
+    pub cycles: usize,
+    /// Trace cost of each AIR in this segment. Only collected on request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub airs: Vec<AirCostReport>,
+}
+
+/// Trace size of one AIR, as an estimate of its proving cost.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AirCostReport {
+    pub air_name: String,
+    pub height: usize,
+    /// Height after padding to a power of two, which is what gets proven.
+    pub padded_height: usize,
+    /// Number of main trace cells after padding.
+    pub cells: usize,
+    /// Estimated share of the proving time, i.e. the share of `cells` among all AIRs.
+    pub proving_time_share: f64,
+}
+
+impl AirCostReport {
+    /// Computes the costs of a segment from the trace shapes of its AIRs.
+    pub fn from_trace_shapes(shapes: &[AirTraceShape]) -> Vec<Self> {
+        let costs = shapes
+            .iter()
+            .map(|shape| {
+                let padded_height = if shape.height == 0 {
+                    0
+                } else {
+                    shape.height.next_power_of_two()
+                };
+                Self {
+                    air_name: shape.air_name.clone(),
+                    height: shape.height,
+                    padded_height,
+                    cells: padded_height * shape.width,
+                    proving_time_share: 0.0,
+                }
+            })
+            .collect();
+        Self::with_shares(costs)
+    }
+
+    /// Sums the costs of the same AIRs over all segments.
+    pub fn total<'a>(segments: impl IntoIterator<Item = &'a [AirCostReport]>) -> Vec<Self> {
+        let mut total: Vec<Self> = Vec::new();
+        for segment in segments {
+            if total.is_empty() {
+                total = segment.to_vec();
+                continue;
+            }
+            for (sum, air) in total.iter_mut().zip(segment) {
+                sum.height += air.height;
+                sum.padded_height += air.padded_height;
+                sum.cells += air.cells;
+            }
+        }
+        Self::with_shares(total)
+    }
+
+    fn with_shares(mut costs: Vec<Self>) -> Vec<Self> {
+        let total_cells: usize = costs.iter().map(|air| air.cells).sum();
+        for air in &mut costs {
+            air.proving_time_share = if total_cells == 0 {
+                0.0
+            } else {
+                air.cells as f64 / total_cells as f64
+            };
+        }
+        costs
+    }
+}
@@ -1,11 +1,12 @@
-use std::{borrow::Borrow, path::PathBuf, sync::Arc};
+use std::{borrow::Borrow, panic::AssertUnwindSafe, path::PathBuf, sync::Arc, time::Duration};
 
 use eyre::Result;
 use openvm_build::GuestOptions;
 use openvm_circuit::{
     arch::{
         hasher::poseidon2::vm_poseidon2_hasher, ContinuationVmProof, ExecutionError,
-        GenerationError, SingleSegmentVmExecutor, SystemConfig, VmConfig, VmExecutor,
+        GenerationError, InputProvider, IterInputProvider, SingleSegmentVmExecutor, SystemConfig,
+        VmConfig, VmExecutor,
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
@@ -20,9 +21,13 @@ use openvm_rv32im_transpiler::{
     Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
 };
 use openvm_sdk::{
+    checkpoint::CheckpointDir,
     codec::{Decode, Encode},
     config::{AggStarkConfig, AppConfig, SdkSystemConfig, SdkVmConfig},
+    fs::{read_stdin_from_file, write_stdin_to_file},
+    inspect::{inspect_proof, ProofKind},
     keygen::AppProvingKey,
+    prover::{AppProver, SegmentTaskQueue},
     Sdk, StdIn,
 };
 use openvm_stark_backend::{keygen::types::LinearConstraint, p3_matrix::Matrix};
@@ -32,7 +37,10 @@ use openvm_stark_sdk::{
         setup_tracing, FriParameters,
     },
     engine::{StarkEngine, StarkFriEngine},
-    openvm_stark_backend::{p3_field::FieldAlgebra, Chip},
+    openvm_stark_backend::{
+        p3_field::{FieldAlgebra, PrimeField32},
+        Chip,
+    },
     p3_baby_bear::BabyBear,
 };
 use openvm_transpiler::transpiler::Transpiler;
@@ -522,6 +530,74 @@ fn test_sdk_guest_build_and_transpile() {
     let _exe = sdk.transpile(one, transpiler).unwrap();
 }
 
+#[test]
+fn test_execute_with_report() -> Result<()> {
+    let sdk = Sdk::new();
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.push("guest/fib");
+
+    let vm_config = SdkVmConfig::builder()
+        .system(SdkSystemConfig {
+            config: SystemConfig::default()
+                .with_max_segment_len(200)
+                .with_continuations()
+                .with_public_values(NUM_PUB_VALUES),
+        })
+        .rv32i(Default::default())
+        .rv32m(Default::default())
+        .io(Default::default())
+        .native(Default::default())
+        .build();
+    let elf = sdk.build(
+        Default::default(),
+        &vm_config,
+        pkg_dir,
+        &Default::default(),
+        None,
+    )?;
+    let exe = sdk.transpile(elf, vm_config.transpiler())?;
+
+    let dir = tempfile::tempdir()?;
+    let input_path = dir.path().join("input.bin");
+    write_stdin_to_file(&StdIn::default(), &input_path)?;
+    let inputs = read_stdin_from_file(&input_path)?;
+
+    let public_values = sdk.execute(exe.clone(), vm_config.clone(), inputs.clone())?;
+    let report = sdk.execute_with_report(exe, vm_config, inputs, true)?;
+    assert_eq!(report.exit_code, 0);
+    assert_eq!(
+        report.public_values,
+        public_values
+            .iter()
+            .map(|value| value.as_canonical_u32())
+            .collect::<Vec<_>>()
+    );
+    assert!(report.num_segments > 1);
+    assert_eq!(report.segments.len(), report.num_segments);
+    assert_eq!(
+        report.total_cycles,
+        report.segments.iter().map(|segment| segment.cycles).sum()
+    );
+    for (prev, next) in report.segments.iter().zip(report.segments.iter().skip(1)) {
+        assert_eq!(prev.final_pc, next.initial_pc);
+    }
+
+    let total_cells: usize = report.airs.iter().map(|air| air.cells).sum();
+    assert!(total_cells > 0);
+    assert_eq!(
+        total_cells,
+        report
+            .segments
+            .iter()
+            .flat_map(|segment| &segment.airs)
+            .map(|air| air.cells)
+            .sum::<usize>()
+    );
+    let total_share: f64 = report.airs.iter().map(|air| air.proving_time_share).sum();
+    assert!((total_share - 1.0).abs() < 1e-9);
+    Ok(())
+}
+
 #[test]
 fn test_inner_proof_codec_roundtrip() -> eyre::Result<()> {
     // generate a proof
@@ -568,6 +644,67 @@ fn test_inner_proof_codec_roundtrip() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_inspect_app_proof() -> eyre::Result<()> {
+    let sdk = Sdk::new();
+    let mut pkg_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    pkg_dir.push("guest/fib");
+
+    let vm_config = SdkVmConfig::builder()
+        .system(SdkSystemConfig {
+            config: SystemConfig::default()
+                .with_max_segment_len(200)
+                .with_continuations()
+                .with_public_values(NUM_PUB_VALUES),
+        })
+        .rv32i(Default::default())
+        .rv32m(Default::default())
+        .io(Default::default())
+        .native(Default::default())
+        .build();
+    let elf = sdk.build(
+        Default::default(),
+        &vm_config,
+        pkg_dir,
+        &Default::default(),
+        None,
+    )?;
+    let exe = sdk.transpile(elf, vm_config.transpiler())?;
+    let fri_params = FriParameters::standard_fast();
+    let committed_exe = sdk.commit_app_exe(fri_params, exe)?;
+    let app_pk = Arc::new(sdk.app_keygen(AppConfig::new(fri_params, vm_config.clone()))?);
+    let app_proof = sdk.generate_app_proof(app_pk, committed_exe, StdIn::default())?;
+    let app_proof_bytes = app_proof.encode_to_vec()?;
+
+    let mut inspection = inspect_proof(&app_proof_bytes)?;
+    assert_eq!(inspection.kind, ProofKind::App);
+    assert_eq!(inspection.stark_proofs.len(), app_proof.per_segment.len());
+    assert_eq!(inspection.user_public_values.len(), NUM_PUB_VALUES);
+    assert_eq!(inspection.total_size, app_proof_bytes.len());
+    for (summary, proof) in inspection.stark_proofs.iter().zip(&app_proof.per_segment) {
+        assert_eq!(summary.fri.log_blowup, Some(fri_params.log_blowup));
+        assert_eq!(summary.fri.num_queries, fri_params.num_queries);
+        let heights: Vec<_> = proof.per_air.iter().map(|air| air.degree).collect();
+        let summary_heights: Vec<_> = summary.airs.iter().map(|air| air.height).collect();
+        assert_eq!(summary_heights, heights);
+        let part_sizes: usize = summary.size.iter().map(|part| part.bytes).sum();
+        assert_eq!(part_sizes, summary.total_size);
+    }
+    let air_names = VmConfig::<BabyBear>::create_chip_complex(&vm_config)?.air_names();
+    inspection.set_air_names(&air_names);
+    assert!(inspection.stark_proofs[0]
+        .airs
+        .iter()
+        .all(|air| air.air_name.is_some()));
+
+    // Proofs from another codec version are rejected with a clear error.
+    let mut other_version = app_proof_bytes.clone();
+    other_version[4..8].copy_from_slice(&2u32.to_le_bytes());
+    let err = inspect_proof(&other_version).unwrap_err().to_string();
+    assert!(err.contains("Unsupported proof codec version 2"), "{err}");
+    Ok(())
+}
+
 #[test]
 fn test_segmentation_retry() {
     setup_tracing();
@@ -623,3 +760,270 @@ fn test_segmentation_retry() {
         .sum();
     assert!(new_total_height < total_height);
 }
+
+/// Provides `num_inputs` inputs of a single byte, and panics before the input at
+/// `interrupt_at`, if set.
+#[derive(Clone)]
+struct InterruptingInputProvider {
+    num_read: usize,
+    num_inputs: usize,
+    interrupt_at: Option<usize>,
+}
+
+impl InputProvider for InterruptingInputProvider {
+    fn next_input(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        if Some(self.num_read) == self.interrupt_at {
+            panic!("interrupted");
+        }
+        if self.num_read == self.num_inputs {
+            return Ok(None);
+        }
+        self.num_read += 1;
+        Ok(Some(vec![1]))
+    }
+
+    fn box_clone(&self) -> Box<dyn InputProvider> {
+        Box::new(self.clone())
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        Some(format!("{}/{}", self.num_read, self.num_inputs).into_bytes())
+    }
+}
+
+#[test]
+fn test_stdin_with_provider_not_serializable() {
+    let mut stdin = StdIn::default();
+    stdin.write(&1u32);
+    let json = serde_json::to_string(&stdin).unwrap();
+    let restored: StdIn = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.buffer, stdin.buffer);
+
+    stdin.set_provider(InterruptingInputProvider {
+        num_read: 0,
+        num_inputs: 1,
+        interrupt_at: None,
+    });
+    assert!(serde_json::to_string(&stdin).is_err());
+}
+
+#[test]
+fn test_app_proof_resumes_from_checkpoint() -> Result<()> {
+    let num_inputs = 200;
+    let program = {
+        let mut builder = Builder::<C>::default();
+        let sum: Felt<F> = builder.eval(F::ZERO);
+        builder.range(0, num_inputs).for_each(|_, builder| {
+            let x = builder.hint_felt();
+            builder.assign(&sum, sum + x);
+        });
+        builder.halt();
+        builder.compile_isa()
+    };
+    let app_log_blowup = 3;
+    let app_pk = AppProvingKey::keygen(small_test_app_config(app_log_blowup));
+    let committed_exe = Sdk::new().commit_app_exe(
+        FriParameters::new_for_testing(app_log_blowup),
+        program.into(),
+    )?;
+    let app_prover =
+        AppProver::<_, BabyBearPoseidon2Engine>::new(app_pk.app_vm_pk.clone(), committed_exe);
+    let input = |interrupt_at| {
+        let mut stdin = StdIn::default();
+        stdin.set_provider(InterruptingInputProvider {
+            num_read: 0,
+            num_inputs,
+            interrupt_at,
+        });
+        stdin
+    };
+
+    let dir = tempfile::tempdir()?;
+    let unrelated_file = dir.path().join("unrelated");
+    std::fs::write(&unrelated_file, b"not a checkpoint")?;
+    let checkpoints = CheckpointDir::new(dir.path());
+    let interrupted = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        app_prover.generate_app_proof_with_checkpoints(input(Some(150)), &checkpoints)
+    }));
+    assert!(interrupted.is_err());
+    let first_segment = dir.path().join("app/segment-0.proof");
+    let first_segment_time = std::fs::metadata(&first_segment)?.modified()?;
+
+    let proof = app_prover.generate_app_proof_with_checkpoints(input(None), &checkpoints)?;
+    assert!(proof.per_segment.len() > 2);
+    assert_eq!(
+        std::fs::metadata(&first_segment)?.modified()?,
+        first_segment_time,
+        "the proven segments are not proven again"
+    );
+    Sdk::new().verify_app_proof(&app_pk.get_app_vk(), &proof)?;
+
+    // Once finished, the saved proof is returned.
+    let resumed = app_prover.generate_app_proof_with_checkpoints(input(Some(0)), &checkpoints)?;
+    assert_eq!(resumed.encode_to_vec()?, proof.encode_to_vec()?);
+
+    // The checkpoints of another input are discarded.
+    let mut other_input = StdIn::default();
+    other_input.write_field(&[F::TWO]);
+    other_input.set_provider(InterruptingInputProvider {
+        num_read: 0,
+        num_inputs: num_inputs - 1,
+        interrupt_at: None,
+    });
+    let other_proof = app_prover.generate_app_proof_with_checkpoints(other_input, &checkpoints)?;
+    assert_ne!(
+        std::fs::metadata(&first_segment)?.modified()?,
+        first_segment_time,
+        "the segments are proven again"
+    );
+    Sdk::new().verify_app_proof(&app_pk.get_app_vk(), &other_proof)?;
+
+    // Inputs which cannot be identified are not checkpointed.
+    let mut unidentified = StdIn::default();
+    unidentified.set_provider(IterInputProvider(std::iter::repeat_n(vec![1], num_inputs)));
+    assert!(app_prover
+        .generate_app_proof_with_checkpoints(unidentified, &checkpoints)
+        .is_err());
+
+    checkpoints.remove()?;
+    assert!(!dir.path().join("app").exists());
+    assert!(unrelated_file.exists());
+    Ok(())
+}
+
+#[test]
+fn test_segment_tasks_proven_by_workers() -> Result<()> {
+    let num_inputs = 200;
+    let program = {
+        let mut builder = Builder::<C>::default();
+        let sum: Felt<F> = builder.eval(F::ZERO);
+        builder.range(0, num_inputs).for_each(|_, builder| {
+            let x = builder.hint_felt();
+            builder.assign(&sum, sum + x);
+        });
+        builder.halt();
+        builder.compile_isa()
+    };
+    let app_log_blowup = 3;
+    let app_pk = AppProvingKey::keygen(small_test_app_config(app_log_blowup));
+    let committed_exe = Sdk::new().commit_app_exe(
+        FriParameters::new_for_testing(app_log_blowup),
+        program.into(),
+    )?;
+    let app_prover =
+        AppProver::<_, BabyBearPoseidon2Engine>::new(app_pk.app_vm_pk.clone(), committed_exe);
+    let mut stdin = StdIn::default();
+    stdin.set_provider(InterruptingInputProvider {
+        num_read: 0,
+        num_inputs,
+        interrupt_at: None,
+    });
+
+    let dir = tempfile::tempdir()?;
+    let queue = SegmentTaskQueue::new(dir.path());
+    let (num_segments, num_proven) = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..2)
+            .map(|_| s.spawn(|| queue.run_worker(&app_prover)))
+            .collect();
+        let num_segments = queue.plan(&app_prover, stdin.clone())?;
+        let num_proven = workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .sum::<Result<usize>>()?;
+        Ok::<_, eyre::Report>((num_segments, num_proven))
+    })?;
+    assert!(num_segments > 2);
+    assert_eq!(num_proven, num_segments);
+
+    let proof = queue.wait_for_proof()?;
+    Sdk::new().verify_app_proof(&app_pk.get_app_vk(), &proof)?;
+    let expected = app_prover.generate_app_proof(stdin);
+    assert_eq!(
+        proof.user_public_values.encode_to_vec()?,
+        expected.user_public_values.encode_to_vec()?
+    );
+    queue.remove()?;
+    Ok(())
+}
+
+#[test]
+fn test_segment_task_planning_retries_segmentation() -> Result<()> {
+    let app_log_blowup = 3;
+    let app_pk = AppProvingKey::keygen(small_test_app_config(app_log_blowup));
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let app_vm = VmExecutor::new(app_pk.app_vm_pk.vm_config.clone());
+    let app_vm_result = app_vm
+        .execute_and_generate_with_cached_program(app_committed_exe.clone(), vec![])
+        .unwrap();
+    let first_segment_height: usize = app_vm_result.per_segment[0]
+        .per_air
+        .iter()
+        .map(|(_, input)| match input.raw.cached_mains.first() {
+            Some(main) => main.height(),
+            None => input
+                .raw
+                .common_main
+                .as_ref()
+                .map_or(0, |main| main.height()),
+        })
+        .sum();
+
+    // The first segment violates the constraint with the default segmentation.
+    let mut app_vm_pk = (*app_pk.app_vm_pk).clone();
+    let num_airs = app_vm_pk.vm_pk.per_air.len();
+    app_vm_pk.vm_pk.trace_height_constraints = vec![LinearConstraint {
+        coefficients: vec![1; num_airs],
+        threshold: first_segment_height as u32,
+    }];
+    let app_prover =
+        AppProver::<_, BabyBearPoseidon2Engine>::new(Arc::new(app_vm_pk), app_committed_exe);
+    let mut tasks = vec![];
+    let user_public_values = app_prover.plan_segment_tasks(StdIn::default(), |task| {
+        tasks.push(task);
+        Ok(())
+    })?;
+    assert!(tasks.iter().all(|task| task.segmentation_retries > 0));
+
+    let per_segment = tasks
+        .into_iter()
+        .map(|task| app_prover.prove_segment_task(task))
+        .collect::<Result<_>>()?;
+    let proof = ContinuationVmProof {
+        per_segment,
+        user_public_values,
+    };
+    Sdk::new().verify_app_proof(&app_pk.get_app_vk(), &proof)?;
+    Ok(())
+}
+
+#[test]
+fn test_segment_task_queue_requeues_stale_claims() -> Result<()> {
+    let app_log_blowup = 3;
+    let app_pk = AppProvingKey::keygen(small_test_app_config(app_log_blowup));
+    let app_prover = AppProver::<_, BabyBearPoseidon2Engine>::new(
+        app_pk.app_vm_pk.clone(),
+        app_committed_exe_for_test(app_log_blowup),
+    );
+
+    let dir = tempfile::tempdir()?;
+    let unrelated_file = dir.path().join("unrelated");
+    std::fs::write(&unrelated_file, b"not a queue file")?;
+    let queue = SegmentTaskQueue::new(dir.path()).with_claim_timeout(Duration::ZERO);
+    let num_segments = queue.plan(&app_prover, StdIn::default())?;
+    // A worker claimed the first task and died.
+    std::fs::create_dir_all(dir.path().join("claimed"))?;
+    std::fs::rename(
+        dir.path().join("tasks/0.task"),
+        dir.path().join("claimed/0.task"),
+    )?;
+    assert!(queue.wait_for_proof_while(|| Ok(false)).is_err());
+
+    assert_eq!(queue.run_worker(&app_prover)?, num_segments);
+    let proof = queue.wait_for_proof_while(|| Ok(false))?;
+    Sdk::new().verify_app_proof(&app_pk.get_app_vk(), &proof)?;
+
+    queue.remove()?;
+    assert!(!dir.path().join("proofs").exists());
+    assert!(unrelated_file.exists());
+    Ok(())
+}
@@ -17,4 +17,19 @@ pub enum SysPhantom {
     CtStart,
     /// End tracing
     CtEnd,
+    /// Raises a [TrapCause], which the runtime reports as an execution error. The `a` and `b`
+    /// operands hold the lower and upper 16 bits of the faulting RISC-V instruction, and the
+    /// upper 16 bits of `c` hold the cause.
+    Trap,
+}
+
+/// Exception causes, numbered as in the RISC-V `mcause` register.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromRepr)]
+#[repr(u16)]
+pub enum TrapCause {
+    IllegalInstruction = 2,
+    Breakpoint = 3,
+    LoadAddressMisaligned = 4,
+    StoreAddressMisaligned = 6,
+    EnvironmentCall = 11,
 }
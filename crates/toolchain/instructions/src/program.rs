@@ -10,6 +10,8 @@ pub const PC_BITS: usize = 30;
 /// We use default PC step of 4 whenever possible for consistency with RISC-V, where 4 comes
 /// from the fact that each standard RISC-V instruction is 32-bits = 4 bytes.
 pub const DEFAULT_PC_STEP: u32 = 4;
+/// PC step of programs containing RISC-V compressed instructions, which are 16-bits = 2 bytes.
+pub const COMPRESSED_PC_STEP: u32 = 2;
 pub const MAX_ALLOWED_PC: u32 = (1 << PC_BITS) - 1;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -5,3 +5,9 @@ pub const RV32_CELL_BITS: usize = 8;
 pub const RV32_IMM_AS: u32 = 0;
 pub const RV32_REGISTER_AS: u32 = 1;
 pub const RV32_MEMORY_AS: u32 = 2;
+
+/// The counter CSRs are offset by a 64-bit cycle base stored as little-endian bytes in the
+/// register address space, right after the 32 registers. With continuations, the VM connector
+/// adds the number of cycles of each segment to it, so counters continue across segments.
+pub const RV32_CYCLE_BASE_PTR: u32 = 32 * RV32_REGISTER_NUM_LIMBS as u32;
+pub const RV32_CYCLE_BASE_NUM_LIMBS: usize = 8;
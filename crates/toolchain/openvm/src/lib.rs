@@ -23,6 +23,7 @@ mod getrandom;
 pub mod io;
 #[cfg(all(feature = "std", target_os = "zkvm"))]
 pub mod pal_abi;
+pub mod poseidon2;
 pub mod process;
 pub mod serde;
 
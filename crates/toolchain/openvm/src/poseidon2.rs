@@ -0,0 +1,33 @@
+//! Poseidon2 compression over BabyBear, executed by the VM's Poseidon2 chip.
+//!
+//! This is the compression function of the VM memory Merkle tree. It is a good fit for Merkle
+//! trees and commitments that are verified inside the guest, but on its own it is **not** a
+//! hash of variable-length input: it does not add any padding.
+
+/// Number of BabyBear elements in each input and in the output of [compress].
+pub const CHUNK: usize = 8;
+
+/// The BabyBear modulus. All elements are represented as `u32`s less than this value.
+pub const BABY_BEAR_MODULUS: u32 = 0x78000001;
+
+/// Poseidon2 compression of two chunks of BabyBear elements: the first [CHUNK] elements of the
+/// Poseidon2 permutation of `lhs || rhs`.
+///
+/// Every element of `lhs` and `rhs` must be less than [BABY_BEAR_MODULUS], otherwise the VM
+/// terminates with an error. The output elements are always less than [BABY_BEAR_MODULUS].
+#[allow(unused_variables)]
+#[inline(always)]
+pub fn compress(lhs: &[u32; CHUNK], rhs: &[u32; CHUNK]) -> [u32; CHUNK] {
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut output = core::mem::MaybeUninit::<[u32; CHUNK]>::uninit();
+        openvm_rv32im_guest::native_poseidon2_compress(
+            output.as_mut_ptr() as *mut u32,
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+        );
+        unsafe { output.assume_init() }
+    }
+    #[cfg(not(target_os = "zkvm"))]
+    panic!("poseidon2::compress cannot run on non-zkVM platforms");
+}
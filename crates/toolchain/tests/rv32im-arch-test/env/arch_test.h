@@ -0,0 +1,82 @@
+// Minimal subset of the riscv-arch-test macros used by the vendored signature tests.
+// Every test macro writes exactly the signature words listed in its comment.
+
+#ifndef _OPENVM_ARCH_TEST_H
+#define _OPENVM_ARCH_TEST_H
+
+#define RVTEST_ISA(isa)
+
+#define RVTEST_CODE_BEGIN
+#define RVTEST_CODE_END
+
+#define RVTEST_DATA_BEGIN .data; .align 4;
+#define RVTEST_DATA_END
+
+#define RVTEST_SIGBASE(basereg, label) la basereg, label;
+
+// 1 word: `inst(val1, val2)`.
+#define TEST_RR_OP(inst, rd, rs1, rs2, val1, val2, swreg, offset)       \
+        li rs1, val1;                                                   \
+        li rs2, val2;                                                   \
+        inst rd, rs1, rs2;                                              \
+        sw rd, offset(swreg);
+
+// 1 word: `inst(val1, imm)`.
+#define TEST_IMM_OP(inst, rd, rs1, val1, imm, swreg, offset)            \
+        li rs1, val1;                                                   \
+        inst rd, rs1, imm;                                              \
+        sw rd, offset(swreg);
+
+// 1 word: 1 if the branch is taken, 0 otherwise.
+#define TEST_BRANCH_OP(inst, rd, rs1, rs2, val1, val2, swreg, offset)   \
+        li rs1, val1;                                                   \
+        li rs2, val2;                                                   \
+        li rd, 1;                                                       \
+        inst rs1, rs2, 1f;                                              \
+        li rd, 0;                                                       \
+1:      sw rd, offset(swreg);
+
+// 1 word: `imm << 12`.
+#define TEST_LUI_OP(rd, imm, swreg, offset)                             \
+        lui rd, imm;                                                    \
+        sw rd, offset(swreg);
+
+// 1 word: the value loaded from `label + off`.
+#define TEST_LOAD_OP(inst, rd, rs1, label, off, swreg, offset)          \
+        la rs1, label;                                                  \
+        inst rd, off(rs1);                                              \
+        sw rd, offset(swreg);
+
+// 1 word: a zeroed word with `val` stored at byte offset `off`.
+#define TEST_STORE_OP(inst, rs2, val, off, swreg, offset)               \
+        li rs2, val;                                                    \
+        sw x0, offset(swreg);                                           \
+        inst rs2, offset+off(swreg);
+
+// 2 words: the link address minus the return label (0), and 1 if the jump target was reached.
+#define TEST_JAL_OP(rd, rs, swreg, offset)                              \
+        li rs, 0;                                                       \
+        jal rd, 2f;                                                     \
+1:      li rs, 0x7ff;                                                   \
+        j 3f;                                                           \
+2:      li rs, 1;                                                       \
+3:      la x6, 1b;                                                      \
+        sub rd, rd, x6;                                                 \
+        sw rd, offset(swreg);                                           \
+        sw rs, offset+4(swreg);
+
+// 2 words: the link address minus the return label (0), and 1 if the jump target was reached.
+#define TEST_JALR_OP(rd, rs, imm, swreg, offset)                        \
+        la rs, 2f;                                                      \
+        addi rs, rs, -(imm);                                            \
+        li x7, 0;                                                       \
+        jalr rd, imm(rs);                                               \
+1:      li x7, 0x7ff;                                                   \
+        j 3f;                                                           \
+2:      li x7, 1;                                                       \
+3:      la x6, 1b;                                                      \
+        sub rd, rd, x6;                                                 \
+        sw rd, offset(swreg);                                           \
+        sw x7, offset+4(swreg);
+
+#endif
@@ -0,0 +1,23 @@
+// Model definitions for running the signature tests on OpenVM.
+
+#ifndef _OPENVM_MODEL_TEST_H
+#define _OPENVM_MODEL_TEST_H
+
+// OpenVM has no boot sequence: execution starts at the ELF entry point.
+#define RVMODEL_BOOT
+
+// Terminates the VM with exit code 0 via the custom `TERMINATE` instruction.
+#define RVMODEL_HALT                                                    \
+        fence;                                                          \
+        .insn i 0x0b, 0, x0, x0, 0;
+
+#define RVMODEL_DATA_BEGIN                                              \
+        .align 4; .global begin_signature; begin_signature:
+
+#define RVMODEL_DATA_END                                                \
+        .global end_signature; end_signature:                           \
+        .pushsection .tohost,"aw",@progbits;                            \
+        .align 6; .global tohost; tohost: .word 0; .size tohost, 4;     \
+        .popsection;
+
+#endif
@@ -0,0 +1,45 @@
+// Needs the ELFs built with the makefile in `rv32im-arch-test`.
+#![cfg(feature = "riscv-arch-tests")]
+
+use std::{
+    fs::{read, read_dir, read_to_string},
+    path::PathBuf,
+};
+
+use eyre::Result;
+use openvm_sdk::arch_test::{ArchTestHarness, ArchTestSignature};
+use openvm_stark_sdk::config::setup_tracing;
+
+#[test]
+fn test_rv32im_riscv_arch_test_signatures() -> Result<()> {
+    setup_tracing();
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("rv32im-arch-test");
+    let harness = ArchTestHarness::default();
+    let mut failures = vec![];
+    for entry in read_dir(dir.join("build"))? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().unwrap_or_default() != "elf" {
+            continue;
+        }
+        let test_name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        tracing::info!("running {test_name}");
+        let reference: ArchTestSignature = read_to_string(
+            dir.join("references")
+                .join(format!("{test_name}.reference_output")),
+        )?
+        .parse()?;
+
+        let result = harness.run(&read(&path)?)?;
+        if !result.is_pass() {
+            tracing::error!("{test_name} failed with status {:?}", result.status);
+            failures.push(test_name);
+        } else if let Some(mismatch) = result.signature.diff(&reference) {
+            tracing::error!("{test_name} failed with signature mismatch {mismatch:?}");
+            failures.push(test_name);
+        } else {
+            tracing::info!("{test_name} passed");
+        }
+    }
+    assert!(failures.is_empty(), "failed tests: {failures:?}");
+    Ok(())
+}
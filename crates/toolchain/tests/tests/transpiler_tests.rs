@@ -12,23 +12,45 @@ use openvm_algebra_circuit::{
 use openvm_algebra_transpiler::{Fp2TranspilerExtension, ModularTranspilerExtension};
 use openvm_bigint_circuit::{Int256, Int256Executor, Int256Periphery};
 use openvm_circuit::{
-    arch::{InitFileGenerator, SystemConfig, VmExecutor},
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        ExecutionError, InitFileGenerator, Streams, SystemConfig, VmExecutor,
+    },
     derive::VmConfig,
-    utils::air_test,
+    system::memory::Rv32MemoryView,
+    utils::{air_test, air_test_with_min_segments},
 };
 use openvm_ecc_circuit::{SECP256K1_MODULUS, SECP256K1_ORDER};
-use openvm_instructions::exe::VmExe;
+use openvm_instructions::{
+    exe::VmExe,
+    program::{Program, COMPRESSED_PC_STEP, DEFAULT_PC_STEP},
+    LocalOpcode, SystemOpcode, TrapCause,
+};
 use openvm_platform::memory::MEM_SIZE;
+use openvm_rv32b_circuit::Rv32BConfig;
+use openvm_rv32b_transpiler::{
+    ByteOpcode, CountOpcode, LogicNotOpcode, Rv32BTranspilerExtension, SingleBitOpcode,
+};
 use openvm_rv32im_circuit::{
-    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32ImConfig, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery,
-    Rv32M, Rv32MExecutor, Rv32MPeriphery,
+    Rv32A, Rv32AExecutor, Rv32APeriphery, Rv32C, Rv32CExecutor, Rv32CPeriphery, Rv32I,
+    Rv32IExecutor, Rv32IPeriphery, Rv32ImConfig, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+    Rv32MExecutor, Rv32MPeriphery, Rv32Poseidon2, Rv32Poseidon2Executor, Rv32Poseidon2Periphery,
 };
 use openvm_rv32im_transpiler::{
-    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    BaseAluOpcode, Rv32ATranspilerExtension, Rv32AmoOpcode, Rv32CBaseAluOpcode, Rv32CJalLuiOpcode,
+    Rv32CompressedHandler, Rv32CsrOpcode, Rv32ITranspilerExtension, Rv32IoTranspilerExtension,
+    Rv32JalLuiOpcode, Rv32LoadStoreOpcode, Rv32MTranspilerExtension, Rv32Poseidon2Opcode,
+    Rv32Poseidon2TranspilerExtension, Rv32TrapHandler, EBREAK, ECALL,
 };
-use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
 use openvm_stark_sdk::p3_baby_bear::BabyBear;
-use openvm_transpiler::{elf::Elf, transpiler::Transpiler, FromElf};
+use openvm_transpiler::{
+    elf::Elf,
+    rvc,
+    transpiler::{Transpiler, TranspilerError},
+    util::trap,
+    FromElf,
+};
 use serde::{Deserialize, Serialize};
 use test_case::test_case;
 
@@ -68,6 +90,468 @@ fn test_generate_program(elf_path: &str) -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_rv32_traps() -> Result<()> {
+    const ADDI: u32 = 0x00100513; // addi a0, zero, 1
+    const ILLEGAL: u32 = 0xffffffff;
+    let words = [ADDI, ECALL, EBREAK, ILLEGAL];
+    let transpiler = || Transpiler::<F>::default().with_extension(Rv32ITranspilerExtension);
+    assert!(matches!(
+        transpiler().transpile(&words),
+        Err(TranspilerError::ParseError(ILLEGAL))
+    ));
+
+    let instructions = transpiler()
+        .with_trap_handler(Rv32TrapHandler::default())
+        .transpile(&words)?;
+    assert_eq!(
+        instructions[1..],
+        [
+            Some(trap(TrapCause::EnvironmentCall, ECALL)),
+            Some(trap(TrapCause::Breakpoint, EBREAK)),
+            Some(trap(TrapCause::IllegalInstruction, ILLEGAL)),
+        ]
+    );
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+    let executor = VmExecutor::<F, _>::new(Rv32ImConfig::default());
+    match executor.execute(exe, vec![]) {
+        Err(ExecutionError::Trap {
+            pc,
+            cause,
+            instruction,
+            ..
+        }) => {
+            assert_eq!(pc, DEFAULT_PC_STEP);
+            assert_eq!(cause, TrapCause::EnvironmentCall);
+            assert_eq!(instruction, ECALL);
+        }
+        res => panic!("expected trap, got {:?}", res.err()),
+    }
+
+    // In trap vector mode, traps are jumps and can be transpiled only within `JAL` range.
+    let instructions = transpiler()
+        .with_trap_handler(Rv32TrapHandler::with_trap_vector(0x1000))
+        .transpile_from(&words, 0x800)?;
+    assert!(instructions.iter().all(Option::is_some));
+    assert!(matches!(
+        transpiler()
+            .with_trap_handler(Rv32TrapHandler::with_trap_vector(0x1000_0000))
+            .transpile(&words),
+        Err(TranspilerError::TrapVectorOutOfRange {
+            pc: 4,
+            trap_vector: 0x1000_0000
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_rv32_counters() -> Result<()> {
+    const RDCYCLE_A0: u32 = 0xc0002573; // rdcycle a0
+    const RDINSTRETH_A1: u32 = 0xc82025f3; // rdinstreth a1
+    const RDTIME_ZERO: u32 = 0xc0102073; // rdtime zero
+    const TERMINATE: u32 = 0x0000000b;
+    let instructions = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .transpile(&[RDCYCLE_A0, RDINSTRETH_A1, RDTIME_ZERO, TERMINATE])?;
+    let opcodes: Vec<_> = instructions
+        .iter()
+        .map(|instruction| instruction.as_ref().unwrap().opcode)
+        .collect();
+    assert_eq!(opcodes[0], Rv32CsrOpcode::RDCYCLE.global_opcode());
+    assert_eq!(opcodes[1], Rv32CsrOpcode::RDINSTRETH.global_opcode());
+    assert_eq!(opcodes[2], SystemOpcode::PHANTOM.global_opcode());
+
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+    let config = Rv32ImConfig::default();
+    let executor = VmExecutor::<F, _>::new(config.clone());
+    let memory = executor
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    // The first instruction starts at timestamp 1.
+    assert_eq!(view.read_register(10), 1);
+    assert_eq!(view.read_register(11), 0);
+    air_test(config, exe);
+    Ok(())
+}
+
+#[test]
+fn test_rv32_counters_across_segments() -> Result<()> {
+    const RDCYCLE_A0: u32 = 0xc0002573; // rdcycle a0
+    const LI_T0_300: u32 = 0x12c00293; // li t0, 300
+    const ADDI_T0_T0_NEG1: u32 = 0xfff28293; // addi t0, t0, -1
+    const BNEZ_T0_NEG4: u32 = 0xfe029ee3; // bnez t0, -4
+    const RDCYCLE_A1: u32 = 0xc00025f3; // rdcycle a1
+    const RDCYCLEH_A2: u32 = 0xc8002673; // rdcycleh a2
+    const TERMINATE: u32 = 0x0000000b;
+    let instructions = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .transpile(&[
+            RDCYCLE_A0,
+            LI_T0_300,
+            ADDI_T0_T0_NEG1,
+            BNEZ_T0_NEG4,
+            RDCYCLE_A1,
+            RDCYCLEH_A2,
+            TERMINATE,
+        ])?;
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+
+    // Reference values from a single segment.
+    let memory = VmExecutor::<F, _>::new(Rv32ImConfig::default())
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    let (start, end) = (view.read_register(10), view.read_register(11));
+    assert_eq!(start, 1);
+    assert!(end > start);
+    assert_eq!(view.read_register(12), 0);
+
+    // Timestamps restart at each segment, but the counters continue.
+    let mut config = Rv32ImConfig::default();
+    config.rv32i.system = config.rv32i.system.with_max_segment_len(200);
+    let memory = air_test_with_min_segments(config, exe, Streams::default(), 2)
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    assert_eq!(view.read_register(10), start);
+    assert_eq!(view.read_register(11), end);
+    assert_eq!(view.read_register(12), 0);
+    Ok(())
+}
+
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct Rv32ImaConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub mul: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub atomic: Rv32A,
+}
+
+impl InitFileGenerator for Rv32ImaConfig {}
+
+#[test]
+fn test_rv32_atomics() -> Result<()> {
+    let words = [
+        0x10000513, // addi a0, zero, 256
+        0x00500593, // addi a1, zero, 5
+        0x00b5262f, // amoadd.w a2, a1, (a0)
+        0x00b526af, // amoadd.w a3, a1, (a0)
+        0x1005272f, // lr.w a4, (a0)
+        0x18b527af, // sc.w a5, a1, (a0)
+        0x0000000b, // terminate
+    ];
+    let instructions = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32ATranspilerExtension)
+        .transpile(&words)?;
+    let opcodes: Vec<_> = instructions
+        .iter()
+        .map(|instruction| instruction.as_ref().unwrap().opcode)
+        .collect();
+    assert_eq!(opcodes[2], Rv32AmoOpcode::AMOADD_W.global_opcode());
+    assert_eq!(opcodes[4], Rv32LoadStoreOpcode::LOADW.global_opcode());
+    assert_eq!(opcodes[5], Rv32AmoOpcode::SC_W.global_opcode());
+
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+    let config = Rv32ImaConfig {
+        system: SystemConfig::default().with_continuations(),
+        base: Default::default(),
+        mul: Default::default(),
+        io: Default::default(),
+        atomic: Rv32A,
+    };
+    let executor = VmExecutor::<F, _>::new(config.clone());
+    let memory = executor
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    assert_eq!(view.read_register(12), 0);
+    assert_eq!(view.read_register(13), 5);
+    assert_eq!(view.read_register(14), 10);
+    // Store-conditional always succeeds.
+    assert_eq!(view.read_register(15), 0);
+    assert_eq!(view.read_u32(256), 5);
+    air_test(config, exe);
+    Ok(())
+}
+
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct Rv32IPoseidon2Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub poseidon2: Rv32Poseidon2,
+}
+
+impl InitFileGenerator for Rv32IPoseidon2Config {}
+
+// With continuations the system Poseidon2 chip is shared with persistent memory, otherwise the
+// extension adds its own.
+#[test_case(true)]
+#[test_case(false)]
+fn test_rv32_poseidon2(continuations: bool) -> Result<()> {
+    let words = [
+        0x10000513, // addi a0, zero, 256
+        0x00100293, // addi t0, zero, 1
+        0x00552023, // sw t0, 0(a0)
+        0x12000593, // addi a1, zero, 288
+        0x14000613, // addi a2, zero, 320
+        0x0eb5460b, // poseidon2 a2, a0, a1
+        0x0000000b, // terminate
+    ];
+    let instructions = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32Poseidon2TranspilerExtension)
+        .transpile(&words)?;
+    assert_eq!(
+        instructions[5].as_ref().unwrap().opcode,
+        Rv32Poseidon2Opcode::COMPRESS.global_opcode()
+    );
+
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+    let system = if continuations {
+        SystemConfig::default().with_continuations()
+    } else {
+        SystemConfig::default()
+    };
+    let config = Rv32IPoseidon2Config {
+        system,
+        base: Default::default(),
+        io: Default::default(),
+        poseidon2: Rv32Poseidon2,
+    };
+    let executor = VmExecutor::<F, _>::new(config.clone());
+    let memory = executor
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    let mut lhs = [F::ZERO; 8];
+    lhs[0] = F::ONE;
+    let expected = vm_poseidon2_hasher()
+        .compress(&lhs, &[F::ZERO; 8])
+        .map(|x| x.as_canonical_u32());
+    assert_eq!(view.read_words(320, 8), expected);
+    air_test(config, exe);
+    Ok(())
+}
+
+#[test]
+fn test_rv32_bitmanip() -> Result<()> {
+    let words = [
+        0xff000513, // addi a0, zero, -16
+        0x12300593, // addi a1, zero, 0x123
+        0x40b57633, // andn a2, a0, a1
+        0x20a5c6b3, // sh2add a3, a1, a0
+        0x0ab56733, // max a4, a0, a1
+        0x0ab577b3, // maxu a5, a0, a1
+        0x60059813, // clz a6, a1
+        0x60251893, // cpop a7, a0
+        0x6985d913, // rev8 s2, a1
+        0x6045d993, // rori s3, a1, 4
+        0x29f01a13, // bseti s4, zero, 31
+        0x4885da93, // bexti s5, a1, 8
+        0x0000000b, // terminate
+    ];
+    let instructions = Transpiler::<F>::default()
+        .with_extension(Rv32ITranspilerExtension)
+        .with_extension(Rv32BTranspilerExtension)
+        .transpile(&words)?;
+    let opcodes: Vec<_> = instructions
+        .iter()
+        .map(|instruction| instruction.as_ref().unwrap().opcode)
+        .collect();
+    assert_eq!(opcodes[2], LogicNotOpcode::ANDN.global_opcode());
+    assert_eq!(opcodes[6], CountOpcode::CLZ.global_opcode());
+    assert_eq!(opcodes[8], ByteOpcode::REV8.global_opcode());
+    assert_eq!(opcodes[11], SingleBitOpcode::BEXT.global_opcode());
+
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        DEFAULT_PC_STEP,
+        0,
+    ));
+    let config = Rv32BConfig::default();
+    let executor = VmExecutor::<F, _>::new(config.clone());
+    let memory = executor
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    assert_eq!(view.read_register(12), 0xfffffed0);
+    assert_eq!(view.read_register(13), 0x47c);
+    assert_eq!(view.read_register(14), 0x123);
+    assert_eq!(view.read_register(15), 0xfffffff0);
+    assert_eq!(view.read_register(16), 23);
+    assert_eq!(view.read_register(17), 28);
+    assert_eq!(view.read_register(18), 0x23010000);
+    assert_eq!(view.read_register(19), 0x30000012);
+    assert_eq!(view.read_register(20), 0x80000000);
+    assert_eq!(view.read_register(21), 1);
+    air_test(config, exe);
+    Ok(())
+}
+
+#[test]
+fn test_rvc_expand() {
+    // (compressed, expanded) encodings, as assembled by `llvm-mc -triple=riscv32 -mattr=+c`
+    let cases = [
+        (0x1fe0, 0x3fc10413), // c.addi4spn s0, sp, 1020
+        (0x5f7c, 0x07c72783), // c.lw a5, 124(a4)
+        (0xc0bc, 0x04f4a023), // c.sw a5, 64(s1)
+        (0x0001, 0x00000013), // c.nop
+        (0x1281, 0xfe028293), // c.addi t0, -32
+        (0x3001, 0x801ff0ef), // c.jal -2048
+        (0x2ffd, 0x7fe000ef), // c.jal 2046
+        (0x497d, 0x01f00913), // c.li s2, 31
+        (0x7101, 0xe0010113), // c.addi16sp sp, -512
+        (0x617d, 0x1f010113), // c.addi16sp sp, 496
+        (0x6505, 0x00001537), // c.lui a0, 1
+        (0x7301, 0xfffe0337), // c.lui t1, 0xfffe0
+        (0x81fd, 0x01f5d593), // c.srli a1, 31
+        (0x849d, 0x4074d493), // c.srai s1, 7
+        (0x9a3d, 0xfef67613), // c.andi a2, -17
+        (0x8c1d, 0x40f40433), // c.sub s0, a5
+        (0x8d2d, 0x00b54533), // c.xor a0, a1
+        (0x8ed9, 0x00e6e6b3), // c.or a3, a4
+        (0x8ce9, 0x00a4f4b3), // c.and s1, a0
+        (0xbfc5, 0xff1ff06f), // c.j -16
+        (0xd101, 0xf00500e3), // c.beqz a0, -256
+        (0xecfd, 0x0e049f63), // c.bnez s1, 254
+        (0x03b6, 0x00d39393), // c.slli t2, 13
+        (0x50fe, 0x0fc12083), // c.lwsp ra, 252(sp)
+        (0x8282, 0x00028067), // c.jr t0
+        (0x855e, 0x01700533), // c.mv a0, s7
+        (0x9002, 0x00100073), // c.ebreak
+        (0x9782, 0x000780e7), // c.jalr a5
+        (0x947e, 0x01f40433), // c.add s0, t6
+        (0xdf72, 0x0bc12e23), // c.swsp t3, 188(sp)
+    ];
+    for (compressed, expanded) in cases {
+        assert!(rvc::is_compressed(compressed));
+        assert_eq!(rvc::expand(compressed), Some(expanded), "{compressed:#06x}");
+    }
+    // Illegal, reserved and floating-point encodings
+    for compressed in [0x0000, 0x2000, 0x6081, 0x8002, 0x1082] {
+        assert_eq!(rvc::expand(compressed), None, "{compressed:#06x}");
+    }
+}
+
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct Rv32IcConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub compressed: Rv32C,
+}
+
+impl InitFileGenerator for Rv32IcConfig {}
+
+#[test]
+fn test_rv32_compressed() -> Result<()> {
+    // 0x00: c.li a0, 5
+    // 0x02: c.li a1, 0
+    // 0x04: c.nop
+    // 0x06: c.add a1, a0
+    // 0x08: c.addi a0, -1
+    // 0x0a: c.bnez a0, 0x06
+    // 0x0c: addi a2, zero, 256
+    // 0x10: c.sw a1, 0(a2)
+    // 0x12: c.lw a3, 0(a2)
+    // 0x14: c.jal 0x1c
+    // 0x16: addi a5, zero, 7
+    // 0x1a: c.j 0x20
+    // 0x1c: c.mv a4, ra
+    // 0x1e: c.jr ra
+    // 0x20: terminate
+    let words = [
+        0x45814515, 0x95aa0001, 0xfd75157d, 0x10000613, 0x4214c20c, 0x07932021, 0xa0190070,
+        0x80828706, 0x0000000b,
+    ];
+    let transpiler = Transpiler::<F>::default().with_extension(Rv32ITranspilerExtension);
+    assert!(matches!(
+        transpiler.transpile_compressed_from(&words, 0),
+        Err(TranspilerError::CompressedParseError(0x4515))
+    ));
+    let instructions = transpiler
+        .with_compressed_handler(Rv32CompressedHandler)
+        .transpile_compressed_from(&words, 0)?;
+    assert_eq!(instructions.len(), 2 * words.len());
+    // Every 32-bit instruction is followed by a gap, including the one at the unaligned pc 0x16.
+    assert!(instructions[6].is_some() && instructions[7].is_none());
+    assert!(instructions[11].is_some() && instructions[12].is_none());
+    let opcode = |idx: usize| instructions[idx].as_ref().unwrap().opcode;
+    assert_eq!(opcode(6), BaseAluOpcode::ADD.global_opcode());
+    assert_eq!(
+        opcode(10),
+        Rv32CJalLuiOpcode(Rv32JalLuiOpcode::JAL).global_opcode()
+    );
+    assert_eq!(
+        opcode(2),
+        Rv32CBaseAluOpcode(BaseAluOpcode::ADD).global_opcode()
+    );
+
+    let exe = VmExe::new(Program::new_without_debug_infos_with_option(
+        &instructions,
+        COMPRESSED_PC_STEP,
+        0,
+    ));
+    let config = Rv32IcConfig {
+        system: SystemConfig::default().with_continuations(),
+        base: Default::default(),
+        io: Default::default(),
+        compressed: Rv32C,
+    };
+    let executor = VmExecutor::<F, _>::new(config.clone());
+    let memory = executor
+        .execute(exe.clone(), vec![])?
+        .expect("final memory must be set");
+    let view = Rv32MemoryView::new(&memory);
+    assert_eq!(view.read_register(10), 0);
+    assert_eq!(view.read_register(11), 15);
+    assert_eq!(view.read_register(13), 15);
+    // The return address of c.jal is the address of the next instruction, pc + 2.
+    assert_eq!(view.read_register(1), 0x16);
+    assert_eq!(view.read_register(14), 0x16);
+    assert_eq!(view.read_register(15), 7);
+    assert_eq!(view.read_u32(256), 15);
+    air_test(config, exe);
+    Ok(())
+}
+
 #[test_case("tests/data/rv32im-exp-from-as")]
 #[test_case("tests/data/rv32im-fib-from-as")]
 fn test_rv32im_runtime(elf_path: &str) -> Result<()> {
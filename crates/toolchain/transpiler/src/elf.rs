@@ -2,23 +2,24 @@
 // and https://github.com/risc0/risc0/blob/f61379bf69b24d56e49d6af96a3b284961dcc498/risc0/binfmt/src/elf.rs#L34 under Apache License
 use std::{cmp::min, collections::BTreeMap, fmt::Debug};
 #[cfg(feature = "function-span")]
-use std::{
-    collections::{hash_map::Entry, HashMap},
-    io::Write,
-};
+use std::{collections::HashMap, io::Write};
 
 use elf::{
-    abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD},
+    abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD, SHN_UNDEF, STT_FILE, STT_FUNC, STT_SECTION},
     endian::LittleEndian,
     file::Class,
     ElfBytes,
 };
 use eyre::{self, bail, ContextCompat};
-#[cfg(feature = "function-span")]
-use openvm_instructions::exe::FnBound;
-use openvm_instructions::{exe::FnBounds, program::MAX_ALLOWED_PC};
+use openvm_instructions::{
+    exe::{FnBound, FnBounds},
+    program::{COMPRESSED_PC_STEP, MAX_ALLOWED_PC},
+};
 use openvm_platform::WORD_SIZE;
 
+/// `e_flags` bit set by the RISC-V toolchain when the code may contain compressed instructions.
+const EF_RISCV_RVC: u32 = 0x0001;
+
 /// RISC-V 32IM ELF (Executable and Linkable Format) File.
 ///
 /// This file represents a binary in the ELF format, specifically the RISC-V 32IM architecture
@@ -30,7 +31,8 @@ use openvm_platform::WORD_SIZE;
 /// This format is commonly used in embedded systems and is supported by many compilers.
 #[derive(Debug, Clone)]
 pub struct Elf {
-    /// The instructions of the program encoded as 32-bits.
+    /// The instructions of the program encoded as 32-bits. If the ELF is
+    /// [compressed](Self::is_compressed), these are the 32-bit words of the code instead.
     pub instructions: Vec<u32>,
     /// The start address of the program.
     pub(crate) pc_start: u32,
@@ -38,8 +40,12 @@ pub struct Elf {
     pub(crate) pc_base: u32,
     /// The initial memory image, useful for global constants.
     pub(crate) memory_image: BTreeMap<u32, u32>,
-    /// Debug info for spanning benchmark metrics by function.
+    /// Bounds of the functions in the ELF symbol table, for guest backtraces and profiling.
     pub(crate) fn_bounds: FnBounds,
+    /// Addresses of the named, defined symbols in the ELF symbol table.
+    pub(crate) symbols: BTreeMap<String, u32>,
+    /// Whether the code may contain RV32C compressed instructions.
+    pub(crate) compressed: bool,
 }
 
 impl Elf {
@@ -50,6 +56,8 @@ impl Elf {
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
         fn_bounds: FnBounds,
+        symbols: BTreeMap<String, u32>,
+        compressed: bool,
     ) -> Self {
         Self {
             instructions,
@@ -57,9 +65,27 @@ impl Elf {
             pc_base,
             memory_image,
             fn_bounds,
+            symbols,
+            compressed,
         }
     }
 
+    /// Returns whether the ELF is flagged as possibly containing RV32C compressed instructions, in
+    /// which case instructions are only 2-byte aligned.
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Returns the address of the symbol `name`, if it is defined in the ELF symbol table.
+    pub fn symbol_address(&self, name: &str) -> Option<u32> {
+        self.symbols.get(name).copied()
+    }
+
+    /// Returns the named, defined symbols of the ELF together with their addresses.
+    pub fn symbols(&self) -> &BTreeMap<String, u32> {
+        &self.symbols
+    }
+
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory
     /// address.
     ///
@@ -84,60 +110,71 @@ impl Elf {
             bail!("Invalid ELF type, must be executable");
         }
 
-        #[cfg(not(feature = "function-span"))]
-        let fn_bounds = Default::default();
+        let compressed = elf.ehdr.e_flags & EF_RISCV_RVC != 0;
+        let instruction_alignment = if compressed {
+            COMPRESSED_PC_STEP
+        } else {
+            WORD_SIZE as u32
+        };
 
-        #[cfg(feature = "function-span")]
-        let mut fn_bounds = FnBounds::new();
-        #[cfg(feature = "function-span")]
-        {
-            if let Some((symtab, stringtab)) = elf.symbol_table()? {
-                let mut fn_names = Vec::new();
-                for symbol in symtab.iter() {
-                    if symbol.st_symtype() == elf::abi::STT_FUNC {
-                        let raw_name = stringtab.get(symbol.st_name as usize).unwrap().to_string();
-                        let demangled_name = rustc_demangle::demangle(&raw_name).to_string();
-                        fn_names.push((demangled_name, symbol.st_name));
-                    }
+        let mut symbols = BTreeMap::new();
+        if let Some((symtab, stringtab)) = elf.symbol_table()? {
+            for symbol in symtab.iter() {
+                if symbol.st_shndx == SHN_UNDEF
+                    || matches!(symbol.st_symtype(), STT_SECTION | STT_FILE)
+                {
+                    continue;
                 }
-
-                let mut buf = Vec::new();
-                let mut offsets = HashMap::new();
-                buf.push(0);
-                for (name, st_name) in fn_names {
-                    if let Entry::Vacant(e) = offsets.entry(st_name) {
-                        let offset = buf.len();
-                        e.insert(offset);
-                        buf.extend_from_slice(name.as_bytes());
-                        buf.push(0);
-                    }
+                let name = stringtab.get(symbol.st_name as usize)?;
+                if !name.is_empty() {
+                    symbols.insert(name.to_string(), symbol.st_value as u32);
                 }
+            }
+        }
 
-                for symbol in symtab.iter() {
-                    if symbol.st_symtype() == elf::abi::STT_FUNC {
-                        fn_bounds.insert(
-                            symbol.st_value as u32,
-                            FnBound {
-                                start: symbol.st_value as u32,
-                                end: (symbol.st_value + symbol.st_size - (WORD_SIZE as u64)) as u32,
-                                name: offsets[&symbol.st_name].to_string(),
-                            },
-                        );
-                    }
+        // Function bounds with demangled names, used for guest backtraces and profiling.
+        let mut fn_bounds = FnBounds::new();
+        if let Some((symtab, stringtab)) = elf.symbol_table()? {
+            for symbol in symtab.iter() {
+                if symbol.st_symtype() == STT_FUNC && symbol.st_size > 0 {
+                    let raw_name = stringtab.get(symbol.st_name as usize)?;
+                    fn_bounds.insert(
+                        symbol.st_value as u32,
+                        FnBound {
+                            start: symbol.st_value as u32,
+                            end: (symbol.st_value + symbol.st_size - instruction_alignment as u64)
+                                as u32,
+                            name: rustc_demangle::demangle(raw_name).to_string(),
+                        },
+                    );
                 }
+            }
+        }
 
-                let guest_symbols_path = std::env::var("GUEST_SYMBOLS_PATH")
-                    .map_err(|e| eyre::eyre!("{e}: GUEST_SYMBOLS_PATH"))?;
-                let mut guest_symbols_file =
-                    std::fs::File::create(&guest_symbols_path).map_err(|e| {
-                        eyre::eyre!(
-                            "Failed to create guest symbols file at {guest_symbols_path}: {e}"
-                        )
-                    })?;
-                guest_symbols_file.write_all(buf.as_slice())?;
-            } else {
-                println!("No symbol table found");
+        // For profiling, function names are replaced by their offsets in a symbols file written
+        // to `GUEST_SYMBOLS_PATH`, to keep metric labels small.
+        #[cfg(feature = "function-span")]
+        if !fn_bounds.is_empty() {
+            let mut buf = vec![0];
+            let mut offsets = HashMap::new();
+            for bound in fn_bounds.values_mut() {
+                let name = std::mem::take(&mut bound.name);
+                let offset = *offsets.entry(name).or_insert_with_key(|name: &String| {
+                    let offset = buf.len();
+                    buf.extend_from_slice(name.as_bytes());
+                    buf.push(0);
+                    offset
+                });
+                bound.name = offset.to_string();
             }
+
+            let guest_symbols_path = std::env::var("GUEST_SYMBOLS_PATH")
+                .map_err(|e| eyre::eyre!("{e}: GUEST_SYMBOLS_PATH"))?;
+            let mut guest_symbols_file =
+                std::fs::File::create(&guest_symbols_path).map_err(|e| {
+                    eyre::eyre!("Failed to create guest symbols file at {guest_symbols_path}: {e}")
+                })?;
+            guest_symbols_file.write_all(buf.as_slice())?;
         }
 
         // Get the entrypoint of the ELF file as an u32.
@@ -148,7 +185,7 @@ impl Elf {
             .map_err(|err| eyre::eyre!("e_entry was larger than 32 bits. {err}"))?;
 
         // Make sure the entrypoint is valid.
-        if entry >= max_mem || entry % WORD_SIZE as u32 != 0 {
+        if entry >= max_mem || entry % instruction_alignment != 0 {
             bail!("Invalid entrypoint");
         }
 
@@ -232,6 +269,8 @@ impl Elf {
             base_address,
             image,
             fn_bounds,
+            symbols,
+            compressed,
         ))
     }
 }
@@ -1,4 +1,6 @@
-use openvm_instructions::instruction::Instruction;
+use openvm_instructions::{instruction::Instruction, TrapCause};
+
+use crate::transpiler::TranspilerError;
 
 /// Trait to add custom RISC-V instruction transpilation to OpenVM instruction format.
 /// RISC-V instructions always come in 32-bit chunks.
@@ -14,6 +16,36 @@ pub trait TranspilerExtension<F> {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>>;
 }
 
+/// Opt-in handling of RISC-V instructions that raise an exception, such as `ecall`, `ebreak` and
+/// illegal encodings. See
+/// [Transpiler::with_trap_handler](crate::transpiler::Transpiler::with_trap_handler).
+pub trait TrapHandler<F> {
+    /// Returns the exception unconditionally raised by `instruction_u32`, if any. This takes
+    /// precedence over the [TranspilerExtension]s of the transpiler.
+    fn trap_cause(&self, instruction_u32: u32) -> Option<TrapCause>;
+
+    /// Transpiles `instruction_u32`, located at `pc`, into an instruction raising `cause`.
+    fn trap(
+        &self,
+        pc: u32,
+        cause: TrapCause,
+        instruction_u32: u32,
+    ) -> Result<Instruction<F>, TranspilerError>;
+}
+
+/// Support for the RV32C compressed instruction set. See
+/// [Transpiler::with_compressed_handler](crate::transpiler::Transpiler::with_compressed_handler).
+///
+/// A compressed instruction is first expanded to its 32-bit equivalent and transpiled as such. The
+/// resulting instruction advances the pc by 4, while the compressed instruction only occupies 2
+/// bytes.
+pub trait CompressedHandler<F> {
+    /// Converts `instruction`, the transpilation of an expanded compressed instruction, into an
+    /// equivalent instruction whose next pc and link address are `pc + 2`. Returns `None` if there
+    /// is no such instruction.
+    fn compress(&self, instruction: Instruction<F>) -> Option<Instruction<F>>;
+}
+
 pub struct TranspilerOutput<F> {
     pub instructions: Vec<Option<Instruction<F>>>,
     pub used_u32s: usize,
@@ -3,7 +3,7 @@
 use elf::Elf;
 use openvm_instructions::{
     exe::VmExe,
-    program::{Program, DEFAULT_PC_STEP},
+    program::{Program, COMPRESSED_PC_STEP, DEFAULT_PC_STEP},
 };
 pub use openvm_platform;
 use openvm_stark_backend::p3_field::PrimeField32;
@@ -12,11 +12,12 @@ use transpiler::{Transpiler, TranspilerError};
 use crate::util::elf_memory_image_to_openvm_memory_image;
 
 pub mod elf;
+pub mod rvc;
 pub mod transpiler;
 pub mod util;
 
 mod extension;
-pub use extension::{TranspilerExtension, TranspilerOutput};
+pub use extension::{CompressedHandler, TranspilerExtension, TranspilerOutput, TrapHandler};
 
 pub trait FromElf {
     type ElfContext;
@@ -28,12 +29,16 @@ pub trait FromElf {
 impl<F: PrimeField32> FromElf for VmExe<F> {
     type ElfContext = Transpiler<F>;
     fn from_elf(elf: Elf, transpiler: Self::ElfContext) -> Result<Self, TranspilerError> {
-        let instructions = transpiler.transpile(&elf.instructions)?;
-        let program = Program::new_without_debug_infos_with_option(
-            &instructions,
-            DEFAULT_PC_STEP,
-            elf.pc_base,
-        );
+        let (instructions, step) = if elf.compressed {
+            let instructions =
+                transpiler.transpile_compressed_from(&elf.instructions, elf.pc_base)?;
+            (instructions, COMPRESSED_PC_STEP)
+        } else {
+            let instructions = transpiler.transpile_from(&elf.instructions, elf.pc_base)?;
+            (instructions, DEFAULT_PC_STEP)
+        };
+        let program =
+            Program::new_without_debug_infos_with_option(&instructions, step, elf.pc_base);
         let init_memory = elf_memory_image_to_openvm_memory_image(elf.memory_image);
 
         Ok(VmExe {
@@ -0,0 +1,216 @@
+//! Expansion of the RV32C compressed instruction set into equivalent 32-bit RISC-V instructions.
+//!
+//! Only the integer subset of RV32C is supported: the floating-point loads and stores are
+//! rejected like any other reserved encoding.
+
+const LOAD: u32 = 0b0000011;
+const OP_IMM: u32 = 0b0010011;
+const STORE: u32 = 0b0100011;
+const OP: u32 = 0b0110011;
+const LUI: u32 = 0b0110111;
+const BRANCH: u32 = 0b1100011;
+const JALR: u32 = 0b1100111;
+const JAL: u32 = 0b1101111;
+
+const EBREAK: u32 = 0x00100073;
+
+/// Register `x1`, the link register of `C.JAL` and `C.JALR`.
+const RA: u32 = 1;
+/// Register `x2`, the stack pointer.
+const SP: u32 = 2;
+
+/// Returns whether the halfword `instruction` is the start of a 16-bit instruction. Halfwords
+/// whose two least significant bits are `0b11` start a 32-bit instruction.
+pub fn is_compressed(instruction: u16) -> bool {
+    instruction & 0b11 != 0b11
+}
+
+/// Expands the 16-bit `instruction` into the 32-bit instruction it is an alias of, or returns
+/// `None` if the encoding is illegal, reserved or not part of RV32C.
+pub fn expand(instruction: u16) -> Option<u32> {
+    let x = instruction as u32;
+    let funct3 = bits(x, 15, 13);
+    // Registers x8-x15 of the CIW, CL, CS, CA and CB formats.
+    let rd_prime = bits(x, 4, 2) + 8;
+    let rs1_prime = bits(x, 9, 7) + 8;
+    // Registers of the CR, CI and CSS formats.
+    let rd = bits(x, 11, 7);
+    let rs2 = bits(x, 6, 2);
+
+    match (x & 0b11, funct3) {
+        // C.ADDI4SPN
+        (0b00, 0b000) => {
+            let imm = (bits(x, 12, 11) << 4)
+                | (bits(x, 10, 7) << 6)
+                | (bit(x, 6) << 2)
+                | (bit(x, 5) << 3);
+            (imm != 0).then(|| i_type(imm as i32, SP, 0b000, rd_prime, OP_IMM))
+        }
+        // C.LW
+        (0b00, 0b010) => Some(i_type(
+            cl_offset(x) as i32,
+            rs1_prime,
+            0b010,
+            rd_prime,
+            LOAD,
+        )),
+        // C.SW
+        (0b00, 0b110) => Some(s_type(cl_offset(x) as i32, rd_prime, rs1_prime, 0b010)),
+        // C.NOP, C.ADDI
+        (0b01, 0b000) => Some(i_type(ci_imm(x), rd, 0b000, rd, OP_IMM)),
+        // C.JAL
+        (0b01, 0b001) => Some(j_type(cj_offset(x), RA)),
+        // C.LI
+        (0b01, 0b010) => Some(i_type(ci_imm(x), 0, 0b000, rd, OP_IMM)),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == SP => {
+            let imm = (bit(x, 12) << 9)
+                | (bit(x, 6) << 4)
+                | (bit(x, 5) << 6)
+                | (bits(x, 4, 3) << 7)
+                | (bit(x, 2) << 5);
+            (imm != 0).then(|| i_type(sign_extend(imm, 10), SP, 0b000, SP, OP_IMM))
+        }
+        // C.LUI
+        (0b01, 0b011) => {
+            let imm = (bit(x, 12) << 17) | (bits(x, 6, 2) << 12);
+            (imm != 0).then(|| ((sign_extend(imm, 18) as u32) & 0xfffff000) | (rd << 7) | LUI)
+        }
+        (0b01, 0b100) => {
+            let shamt = bits(x, 6, 2);
+            match bits(x, 11, 10) {
+                // C.SRLI, where shamt[5] must be zero on RV32
+                0b00 => (bit(x, 12) == 0)
+                    .then(|| i_type(shamt as i32, rs1_prime, 0b101, rs1_prime, OP_IMM)),
+                // C.SRAI, where shamt[5] must be zero on RV32
+                0b01 => (bit(x, 12) == 0)
+                    .then(|| i_type((0x400 | shamt) as i32, rs1_prime, 0b101, rs1_prime, OP_IMM)),
+                // C.ANDI
+                0b10 => Some(i_type(ci_imm(x), rs1_prime, 0b111, rs1_prime, OP_IMM)),
+                // C.SUB, C.XOR, C.OR, C.AND. The encodings with bit 12 set are RV64 only.
+                _ => (bit(x, 12) == 0).then(|| {
+                    let (funct7, funct3) = match bits(x, 6, 5) {
+                        0b00 => (0b0100000, 0b000),
+                        0b01 => (0, 0b100),
+                        0b10 => (0, 0b110),
+                        _ => (0, 0b111),
+                    };
+                    r_type(funct7, rd_prime, rs1_prime, funct3, rs1_prime)
+                }),
+            }
+        }
+        // C.J
+        (0b01, 0b101) => Some(j_type(cj_offset(x), 0)),
+        // C.BEQZ, C.BNEZ
+        (0b01, 0b110 | 0b111) => {
+            let offset = (bit(x, 12) << 8)
+                | (bits(x, 11, 10) << 3)
+                | (bits(x, 6, 5) << 6)
+                | (bits(x, 4, 3) << 1)
+                | (bit(x, 2) << 5);
+            Some(b_type(sign_extend(offset, 9), rs1_prime, funct3 & 1))
+        }
+        // C.SLLI, where shamt[5] must be zero on RV32
+        (0b10, 0b000) => (bit(x, 12) == 0).then(|| i_type(rs2 as i32, rd, 0b001, rd, OP_IMM)),
+        // C.LWSP
+        (0b10, 0b010) => {
+            let offset = (bit(x, 12) << 5) | (bits(x, 6, 4) << 2) | (bits(x, 3, 2) << 6);
+            (rd != 0).then(|| i_type(offset as i32, SP, 0b010, rd, LOAD))
+        }
+        (0b10, 0b100) => match (bit(x, 12), rd, rs2) {
+            // Reserved
+            (0, 0, 0) => None,
+            // C.JR
+            (0, _, 0) => Some(i_type(0, rd, 0b000, 0, JALR)),
+            // C.MV
+            (0, _, _) => Some(r_type(0, rs2, 0, 0b000, rd)),
+            // C.EBREAK
+            (_, 0, 0) => Some(EBREAK),
+            // C.JALR
+            (_, _, 0) => Some(i_type(0, rd, 0b000, RA, JALR)),
+            // C.ADD
+            _ => Some(r_type(0, rs2, rd, 0b000, rd)),
+        },
+        // C.SWSP
+        (0b10, 0b110) => {
+            let offset = (bits(x, 12, 9) << 2) | (bits(x, 8, 7) << 6);
+            Some(s_type(offset as i32, rs2, SP, 0b010))
+        }
+        _ => None,
+    }
+}
+
+fn bits(x: u32, hi: u32, lo: u32) -> u32 {
+    (x >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+fn bit(x: u32, i: u32) -> u32 {
+    (x >> i) & 1
+}
+
+fn sign_extend(x: u32, width: u32) -> i32 {
+    ((x << (32 - width)) as i32) >> (32 - width)
+}
+
+/// Immediate of the CI format, as used by `C.ADDI`, `C.LI` and `C.ANDI`.
+fn ci_imm(x: u32) -> i32 {
+    sign_extend((bit(x, 12) << 5) | bits(x, 6, 2), 6)
+}
+
+/// Offset of `C.LW` and `C.SW`.
+fn cl_offset(x: u32) -> u32 {
+    (bits(x, 12, 10) << 3) | (bit(x, 6) << 2) | (bit(x, 5) << 6)
+}
+
+/// Jump offset of `C.J` and `C.JAL`.
+fn cj_offset(x: u32) -> i32 {
+    let offset = (bit(x, 12) << 11)
+        | (bit(x, 11) << 4)
+        | (bits(x, 10, 9) << 8)
+        | (bit(x, 8) << 10)
+        | (bit(x, 7) << 6)
+        | (bit(x, 6) << 7)
+        | (bits(x, 5, 3) << 1)
+        | (bit(x, 2) << 5);
+    sign_extend(offset, 12)
+}
+
+fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | OP
+}
+
+fn i_type(imm: i32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    (((imm as u32) & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+fn s_type(imm: i32, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    let imm = imm as u32;
+    (bits(imm, 11, 5) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 0) << 7)
+        | STORE
+}
+
+/// Compares `rs1` with `x0`: `funct3` is 0 for `BEQ` and 1 for `BNE`.
+fn b_type(imm: i32, rs1: u32, funct3: u32) -> u32 {
+    let imm = imm as u32;
+    (bit(imm, 12) << 31)
+        | (bits(imm, 10, 5) << 25)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 1) << 8)
+        | (bit(imm, 11) << 7)
+        | BRANCH
+}
+
+fn j_type(imm: i32, rd: u32) -> u32 {
+    let imm = imm as u32;
+    (bit(imm, 20) << 31)
+        | (bits(imm, 10, 1) << 21)
+        | (bit(imm, 11) << 20)
+        | (bits(imm, 19, 12) << 12)
+        | (rd << 7)
+        | JAL
+}
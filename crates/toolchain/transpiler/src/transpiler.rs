@@ -1,15 +1,21 @@
 use std::rc::Rc;
 
-use openvm_instructions::instruction::Instruction;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::{COMPRESSED_PC_STEP, DEFAULT_PC_STEP},
+    TrapCause,
+};
 use openvm_stark_backend::p3_field::PrimeField32;
 use thiserror::Error;
 
-use crate::TranspilerExtension;
+use crate::{rvc, CompressedHandler, TranspilerExtension, TranspilerOutput, TrapHandler};
 
 /// Collection of [`TranspilerExtension`]s.
 /// The transpiler can be configured to transpile any ELF in 32-bit chunks.
 pub struct Transpiler<F> {
     processors: Vec<Rc<dyn TranspilerExtension<F>>>,
+    trap_handler: Option<Rc<dyn TrapHandler<F>>>,
+    compressed_handler: Option<Rc<dyn CompressedHandler<F>>>,
 }
 
 impl<F: PrimeField32> Default for Transpiler<F> {
@@ -24,23 +30,50 @@ pub enum TranspilerError {
     AmbiguousNextInstruction,
     #[error("couldn't parse the next instruction: {0:032b}")]
     ParseError(u32),
+    #[error("couldn't parse the next compressed instruction: {0:016b}")]
+    CompressedParseError(u16),
+    #[error("trap vector {trap_vector:#x} is out of range of the instruction at pc {pc:#x}")]
+    TrapVectorOutOfRange { pc: u32, trap_vector: u32 },
 }
 
 impl<F: PrimeField32> Transpiler<F> {
     pub fn new() -> Self {
-        Self { processors: vec![] }
+        Self {
+            processors: vec![],
+            trap_handler: None,
+            compressed_handler: None,
+        }
     }
 
-    pub fn with_processor(self, proc: Rc<dyn TranspilerExtension<F>>) -> Self {
-        let mut procs = self.processors;
-        procs.push(proc);
-        Self { processors: procs }
+    pub fn with_processor(mut self, proc: Rc<dyn TranspilerExtension<F>>) -> Self {
+        self.processors.push(proc);
+        self
     }
 
     pub fn with_extension<T: TranspilerExtension<F> + 'static>(self, ext: T) -> Self {
         self.with_processor(Rc::new(ext))
     }
 
+    /// Enables trap semantics: instructions for which `handler` reports a [TrapCause], as well as
+    /// instructions that no extension can transpile, are transpiled by `handler` instead of
+    /// producing an error. Without a trap handler, unparseable instructions are a
+    /// [TranspilerError::ParseError].
+    pub fn with_trap_handler<T: TrapHandler<F> + 'static>(mut self, handler: T) -> Self {
+        self.trap_handler = Some(Rc::new(handler));
+        self
+    }
+
+    /// Enables the RV32C compressed instructions in
+    /// [transpile_compressed_from](Self::transpile_compressed_from). Without a compressed
+    /// handler, compressed instructions are a [TranspilerError::CompressedParseError].
+    pub fn with_compressed_handler<T: CompressedHandler<F> + 'static>(
+        mut self,
+        handler: T,
+    ) -> Self {
+        self.compressed_handler = Some(Rc::new(handler));
+        self
+    }
+
     /// Iterates over a sequence of 32-bit RISC-V instructions `instructions_u32`. The iterator
     /// applies every processor in the [`Transpiler`] to determine if one of them knows how to
     /// transpile the current instruction (and possibly a contiguous section of following
@@ -50,26 +83,144 @@ impl<F: PrimeField32> Transpiler<F> {
     pub fn transpile(
         &self,
         instructions_u32: &[u32],
+    ) -> Result<Vec<Option<Instruction<F>>>, TranspilerError> {
+        self.transpile_from(instructions_u32, 0)
+    }
+
+    /// Same as [transpile](Self::transpile), for a program whose first instruction is located at
+    /// `pc_base`. The pc is only needed by the trap handler.
+    pub fn transpile_from(
+        &self,
+        instructions_u32: &[u32],
+        pc_base: u32,
     ) -> Result<Vec<Option<Instruction<F>>>, TranspilerError> {
         let mut instructions = Vec::new();
         let mut ptr = 0;
         while ptr < instructions_u32.len() {
-            let mut options = self
-                .processors
-                .iter()
-                .map(|proc| proc.process_custom(&instructions_u32[ptr..]))
-                .filter(|opt| opt.is_some())
-                .collect::<Vec<_>>();
-            if options.is_empty() {
-                return Err(TranspilerError::ParseError(instructions_u32[ptr]));
-            }
-            if options.len() > 1 {
-                return Err(TranspilerError::AmbiguousNextInstruction);
-            }
-            let transpiler_output = options.pop().unwrap().unwrap();
+            let pc = pc_base + instructions.len() as u32 * DEFAULT_PC_STEP;
+            let transpiler_output = self.transpile_next(&instructions_u32[ptr..], pc)?;
             instructions.extend(transpiler_output.instructions);
             ptr += transpiler_output.used_u32s;
         }
         Ok(instructions)
     }
+
+    /// Transpiles code containing RV32C compressed instructions, located at `pc_base`. The code is
+    /// given as 32-bit little-endian words, but instructions are only required to be 2-byte
+    /// aligned.
+    ///
+    /// The result is meant for a program with a pc step of [COMPRESSED_PC_STEP]: every 32-bit
+    /// instruction is followed by a gap, so that the index of an instruction remains `(pc -
+    /// pc_base) / 2`. Compressed instructions are transpiled through the
+    /// [CompressedHandler] of the transpiler.
+    pub fn transpile_compressed_from(
+        &self,
+        instructions_u32: &[u32],
+        pc_base: u32,
+    ) -> Result<Vec<Option<Instruction<F>>>, TranspilerError> {
+        let halfwords: Vec<u16> = instructions_u32
+            .iter()
+            .flat_map(|&word| [word as u16, (word >> 16) as u16])
+            .collect();
+        // The words starting at odd halfwords, for 32-bit instructions that are not 4-byte aligned.
+        let unaligned_u32s: Vec<u32> = halfwords[halfwords.len().min(1)..]
+            .chunks_exact(2)
+            .map(|pair| pair[0] as u32 | ((pair[1] as u32) << 16))
+            .collect();
+
+        let mut instructions = Vec::new();
+        let mut ptr = 0;
+        while ptr < halfwords.len() {
+            let pc = pc_base + instructions.len() as u32 * COMPRESSED_PC_STEP;
+            if rvc::is_compressed(halfwords[ptr]) {
+                // The all-zero halfword is an illegal instruction, but also the padding of code
+                // whose size is not a multiple of 4. It is only transpiled when trapping.
+                let instruction = if halfwords[ptr] == 0 && self.trap_handler.is_none() {
+                    None
+                } else {
+                    Some(self.transpile_compressed(halfwords[ptr], pc)?)
+                };
+                instructions.push(instruction);
+                ptr += 1;
+                continue;
+            }
+            let stream = if ptr % 2 == 0 {
+                &instructions_u32[ptr / 2..]
+            } else {
+                &unaligned_u32s[ptr / 2..]
+            };
+            if stream.is_empty() {
+                return Err(TranspilerError::ParseError(halfwords[ptr] as u32));
+            }
+            let transpiler_output = self.transpile_next(stream, pc)?;
+            for instruction in transpiler_output.instructions {
+                instructions.extend([instruction, None]);
+            }
+            ptr += 2 * transpiler_output.used_u32s;
+        }
+        Ok(instructions)
+    }
+
+    /// Transpiles the instructions at the start of `instructions_u32`, located at `pc`.
+    fn transpile_next(
+        &self,
+        instructions_u32: &[u32],
+        pc: u32,
+    ) -> Result<TranspilerOutput<F>, TranspilerError> {
+        if let Some(handler) = &self.trap_handler {
+            if let Some(cause) = handler.trap_cause(instructions_u32[0]) {
+                let instruction = handler.trap(pc, cause, instructions_u32[0])?;
+                return Ok(TranspilerOutput::one_to_one(instruction));
+            }
+        }
+        let mut options = self
+            .processors
+            .iter()
+            .map(|proc| proc.process_custom(instructions_u32))
+            .filter(|opt| opt.is_some())
+            .collect::<Vec<_>>();
+        if options.is_empty() {
+            let Some(handler) = &self.trap_handler else {
+                return Err(TranspilerError::ParseError(instructions_u32[0]));
+            };
+            let instruction =
+                handler.trap(pc, TrapCause::IllegalInstruction, instructions_u32[0])?;
+            return Ok(TranspilerOutput::one_to_one(instruction));
+        }
+        if options.len() > 1 {
+            return Err(TranspilerError::AmbiguousNextInstruction);
+        }
+        Ok(options.pop().unwrap().unwrap())
+    }
+
+    /// Transpiles the compressed instruction `instruction_u16`, located at `pc`.
+    fn transpile_compressed(
+        &self,
+        instruction_u16: u16,
+        pc: u32,
+    ) -> Result<Instruction<F>, TranspilerError> {
+        let Some(compressed_handler) = &self.compressed_handler else {
+            return Err(TranspilerError::CompressedParseError(instruction_u16));
+        };
+        let instruction = match rvc::expand(instruction_u16) {
+            Some(expanded) => {
+                let mut transpiler_output = self.transpile_next(&[expanded], pc)?;
+                match transpiler_output.instructions.pop() {
+                    Some(Some(instruction)) if transpiler_output.instructions.is_empty() => {
+                        instruction
+                    }
+                    _ => return Err(TranspilerError::CompressedParseError(instruction_u16)),
+                }
+            }
+            None => {
+                let Some(handler) = &self.trap_handler else {
+                    return Err(TranspilerError::CompressedParseError(instruction_u16));
+                };
+                handler.trap(pc, TrapCause::IllegalInstruction, instruction_u16 as u32)?
+            }
+        };
+        compressed_handler
+            .compress(instruction)
+            .ok_or(TranspilerError::CompressedParseError(instruction_u16))
+    }
 }
@@ -5,7 +5,7 @@ use openvm_instructions::{
     instruction::Instruction,
     riscv::{RV32_MEMORY_AS, RV32_REGISTER_NUM_LIMBS},
     utils::isize_to_field,
-    LocalOpcode, SystemOpcode, VmOpcode,
+    LocalOpcode, PhantomDiscriminant, SysPhantom, SystemOpcode, TrapCause, VmOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use rrs_lib::instruction_formats::{BType, IType, ITypeShamt, JType, RType, SType, UType};
@@ -158,6 +158,16 @@ pub fn unimp<F: PrimeField32>() -> Instruction<F> {
     }
 }
 
+/// Create a new [`Instruction`] that raises `cause` for the RISC-V instruction `instruction_u32`.
+pub fn trap<F: PrimeField32>(cause: TrapCause, instruction_u32: u32) -> Instruction<F> {
+    Instruction::phantom(
+        PhantomDiscriminant(SysPhantom::Trap as u16),
+        F::from_canonical_u32(instruction_u32 & 0xffff),
+        F::from_canonical_u32(instruction_u32 >> 16),
+        cause as u16,
+    )
+}
+
 pub fn nop<F: PrimeField32>() -> Instruction<F> {
     Instruction {
         opcode: SystemOpcode::PHANTOM.global_opcode(),
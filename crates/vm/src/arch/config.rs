@@ -119,6 +119,15 @@ pub struct SystemConfig {
     /// Whether to collect detailed profiling metrics.
     /// **Warning**: this slows down the runtime.
     pub profiling: bool,
+    /// Number of most recently executed instructions to include in the guest backtrace attached
+    /// to execution failures. Zero, the default, disables guest backtraces.
+    /// **Warning**: a nonzero value slows down the runtime.
+    #[serde(default)]
+    pub backtrace_len: usize,
+    /// Whether misaligned loads and stores raise a trap, reported as `ExecutionError::Trap`,
+    /// instead of being treated as invalid guest execution.
+    #[serde(default)]
+    pub trap_misaligned: bool,
     /// Segmentation strategy
     /// This field is skipped in serde as it's only used in execution and
     /// not needed after any serialize/deserialize.
@@ -154,6 +163,8 @@ impl SystemConfig {
             num_public_values,
             segmentation_strategy,
             profiling: false,
+            backtrace_len: 0,
+            trap_misaligned: false,
         }
     }
 
@@ -198,6 +209,16 @@ impl SystemConfig {
         self
     }
 
+    pub fn with_backtrace_len(mut self, backtrace_len: usize) -> Self {
+        self.backtrace_len = backtrace_len;
+        self
+    }
+
+    pub fn with_misaligned_traps(mut self) -> Self {
+        self.trap_misaligned = true;
+        self
+    }
+
     pub fn has_public_values_chip(&self) -> bool {
         !self.continuation_enabled && self.num_public_values > 0
     }
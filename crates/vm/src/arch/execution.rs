@@ -2,7 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use openvm_circuit_primitives_derive::AlignedBorrow;
 use openvm_instructions::{
-    instruction::Instruction, program::DEFAULT_PC_STEP, PhantomDiscriminant, VmOpcode,
+    instruction::Instruction, program::DEFAULT_PC_STEP, PhantomDiscriminant, TrapCause, VmOpcode,
 };
 use openvm_stark_backend::{
     interaction::{BusIndex, InteractionBuilder, PermutationCheckBus},
@@ -11,15 +11,20 @@ use openvm_stark_backend::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::Streams;
+use super::{GuestBacktrace, Streams};
 use crate::system::{memory::MemoryController, program::ProgramBus};
 
 pub type Result<T> = std::result::Result<T, ExecutionError>;
 
 #[derive(Error, Debug)]
 pub enum ExecutionError {
-    #[error("execution failed at pc {pc}")]
-    Fail { pc: u32 },
+    #[error("execution failed at pc {pc}{}", fmt_guest_backtrace(.backtrace))]
+    Fail {
+        pc: u32,
+        /// Only set when [SystemConfig::backtrace_len](super::SystemConfig::backtrace_len) is
+        /// nonzero.
+        backtrace: Option<Box<GuestBacktrace>>,
+    },
     #[error("pc {pc} not found for program of length {program_len}, with pc_base {pc_base} and step = {step}")]
     PcNotFound {
         pc: u32,
@@ -62,10 +67,70 @@ pub enum ExecutionError {
         discriminant: PhantomDiscriminant,
         inner: eyre::Error,
     },
+    /// The guest raised a RISC-V exception. `instruction` holds the bits of the faulting RISC-V
+    /// instruction.
+    #[error(
+        "at pc {pc:#x}, trap {cause:?} raised by instruction {instruction:#010x}{}",
+        fmt_guest_backtrace(.backtrace)
+    )]
+    Trap {
+        pc: u32,
+        cause: TrapCause,
+        instruction: u32,
+        /// Only set when [SystemConfig::backtrace_len](super::SystemConfig::backtrace_len) is
+        /// nonzero.
+        backtrace: Option<Box<GuestBacktrace>>,
+    },
+    #[error("at pc {pc:#x}, trap raised with unknown cause {cause}")]
+    InvalidTrapCause { pc: u32, cause: u16 },
+    #[error("at pc {pc:#x}, execution hook error: {inner}")]
+    Hook { pc: u32, inner: eyre::Error },
+    #[error("at pc {pc:#x}, failed to record execution: {source}")]
+    Recording { pc: u32, source: std::io::Error },
     #[error("program must terminate")]
     DidNotTerminate,
-    #[error("program exit code {0}")]
-    FailedWithExitCode(u32),
+    #[error("program exit code {exit_code}{}", fmt_guest_backtrace(.backtrace))]
+    FailedWithExitCode {
+        exit_code: u32,
+        /// Only set when [SystemConfig::backtrace_len](super::SystemConfig::backtrace_len) is
+        /// nonzero.
+        backtrace: Option<Box<GuestBacktrace>>,
+    },
+}
+
+impl ExecutionError {
+    /// Attaches `backtrace` to [ExecutionError::Fail], [ExecutionError::Trap] and
+    /// [ExecutionError::FailedWithExitCode]. Other errors are returned unchanged.
+    pub fn with_guest_backtrace(mut self, backtrace: Option<Box<GuestBacktrace>>) -> Self {
+        if let Self::Fail {
+            backtrace: slot, ..
+        }
+        | Self::Trap {
+            backtrace: slot, ..
+        }
+        | Self::FailedWithExitCode {
+            backtrace: slot, ..
+        } = &mut self
+        {
+            *slot = backtrace;
+        }
+        self
+    }
+
+    pub fn guest_backtrace(&self) -> Option<&GuestBacktrace> {
+        match self {
+            Self::Fail { backtrace, .. }
+            | Self::Trap { backtrace, .. }
+            | Self::FailedWithExitCode { backtrace, .. } => backtrace.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn fmt_guest_backtrace(backtrace: &Option<Box<GuestBacktrace>>) -> String {
+    backtrace
+        .as_ref()
+        .map_or_else(String::new, |backtrace| format!("\n{backtrace}"))
 }
 
 pub trait InstructionExecutor<F> {
@@ -441,7 +441,7 @@ pub struct VmChipComplex<F: PrimeField32, E, P> {
     /// Absolute maximum value a trace height can be and still be provable.
     max_trace_height: usize,
 
-    streams: Arc<Mutex<Streams<F>>>,
+    pub(crate) streams: Arc<Mutex<Streams<F>>>,
     bus_idx_mgr: BusIndexManager,
 }
 
@@ -559,12 +559,15 @@ impl<F: PrimeField32> SystemComplex<F> {
         let memory_bridge = memory_controller.memory_bridge();
         let offline_memory = memory_controller.offline_memory();
         let program_chip = ProgramChip::new(program_bus);
-        let connector_chip = VmConnectorChip::new(
+        let mut connector_chip = VmConnectorChip::new(
             execution_bus,
             program_bus,
             range_checker.clone(),
             config.memory_config.clk_max_bits,
         );
+        if config.continuation_enabled {
+            connector_chip = connector_chip.with_cycle_base(memory_bridge, offline_memory.clone());
+        }
 
         let mut inventory = VmInventory::new();
         // PublicValuesChip is required when num_public_values > 0 in single segment mode.
@@ -585,9 +588,8 @@ impl<F: PrimeField32> SystemComplex<F> {
         if config.continuation_enabled {
             assert_eq!(inventory.periphery().len(), Self::POSEIDON2_PERIPHERY_IDX);
             // Add direct poseidon2 chip for persistent memory.
-            // This is **not** an instruction executor.
-            // Currently we never use poseidon2 opcodes when continuations is enabled: we will need
-            // special handling when that happens
+            // This is **not** an instruction executor. Extensions may record compressions in it
+            // through `Poseidon2PeripheryChip::shared`.
             let direct_bus_idx = memory_controller
                 .interface_chip
                 .compression_bus()
@@ -838,7 +840,7 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
     }
 
     /// Return air names of all chips in order.
-    pub(crate) fn air_names(&self) -> Vec<String>
+    pub fn air_names(&self) -> Vec<String>
     where
         E: ChipUsageGetter,
         P: ChipUsageGetter,
@@ -869,6 +871,21 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             .collect()
     }
 
+    /// Return main trace widths of all chips in order corresponding to `air_names`.
+    pub(crate) fn trace_widths(&self) -> Vec<usize>
+    where
+        E: ChipUsageGetter,
+        P: ChipUsageGetter,
+    {
+        once(self.program_chip().trace_width())
+            .chain([self.connector_chip().trace_width()])
+            .chain(self._public_values_chip().map(|c| c.trace_width()))
+            .chain(self.memory_controller().trace_widths())
+            .chain(self.chips_excluding_pv_chip().map(|c| c.trace_width()))
+            .chain([self.range_checker_chip().trace_width()])
+            .collect()
+    }
+
     /// Return trace heights of (SystemBase, Inventory). Usually this is for aggregation and not
     /// useful for regular users.
     ///
@@ -997,20 +1014,16 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
             .collect()
     }
 
-    pub(crate) fn generate_proof_input<SC: StarkGenericConfig>(
-        mut self,
-        cached_program: Option<CommittedTraceData<SC>>,
+    /// Checks the padded trace heights against the maximum trace height and
+    /// `trace_height_constraints`. Should be called after the memory is finalized.
+    pub(crate) fn check_trace_heights(
+        &self,
         trace_height_constraints: &[LinearConstraint],
-        #[cfg(feature = "bench-metrics")] metrics: &mut VmMetrics,
-    ) -> Result<ProofInput<SC>, GenerationError>
+    ) -> Result<(), GenerationError>
     where
-        Domain<SC>: PolynomialSpace<Val = F>,
-        E: Chip<SC>,
-        P: AnyEnum + Chip<SC>,
+        E: ChipUsageGetter,
+        P: ChipUsageGetter,
     {
-        // System: Finalize memory.
-        self.finalize_memory();
-
         let trace_heights = self
             .current_trace_heights()
             .iter()
@@ -1046,6 +1059,23 @@ impl<F: PrimeField32, E, P> VmChipComplex<F, E, P> {
                 return Err(GenerationError::TraceHeightsLimitExceeded);
             }
         }
+        Ok(())
+    }
+
+    pub(crate) fn generate_proof_input<SC: StarkGenericConfig>(
+        mut self,
+        cached_program: Option<CommittedTraceData<SC>>,
+        trace_height_constraints: &[LinearConstraint],
+        #[cfg(feature = "bench-metrics")] metrics: &mut VmMetrics,
+    ) -> Result<ProofInput<SC>, GenerationError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+        E: Chip<SC>,
+        P: AnyEnum + Chip<SC>,
+    {
+        // System: Finalize memory.
+        self.finalize_memory();
+        self.check_trace_heights(trace_height_constraints)?;
 
         #[cfg(feature = "bench-metrics")]
         self.finalize_metrics(metrics);
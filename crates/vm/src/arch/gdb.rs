@@ -0,0 +1,523 @@
+//! A minimal stub for the GDB remote serial protocol, to debug RV32 guests with `riscv32-gdb`
+//! under the exact VM semantics.
+//!
+//! The stub is an [ExecutionHook]: it pauses execution before an instruction and serves GDB
+//! requests until GDB resumes. It supports breakpoints, single-stepping, interrupts, and reading
+//! registers and memory. Writing registers or memory is not supported since it would diverge
+//! from the proven execution.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, BufReader, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+};
+
+use eyre::eyre;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{ExecutionError, ExecutionHook};
+use crate::system::memory::{MemoryImage, Rv32MemoryView};
+
+/// GDB register number of the pc. Registers `0..32` are `x0` to `x31`.
+const PC_REGNUM: usize = 32;
+const REGISTER_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+const SIGABRT: u8 = 6;
+
+/// Maximum packet size advertised to GDB, in bytes.
+const PACKET_SIZE: usize = 0x4000;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Packet(String),
+    BadChecksum,
+    Interrupt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RunMode {
+    Stopped,
+    Step,
+    Continue,
+    Detached,
+}
+
+enum Response {
+    Reply(String),
+    Resume,
+    Kill,
+}
+
+pub struct GdbStub<W> {
+    events: Receiver<Event>,
+    writer: W,
+    mode: RunMode,
+    last_signal: u8,
+    breakpoints: BTreeSet<u32>,
+    no_ack: bool,
+}
+
+impl GdbStub<TcpStream> {
+    /// Waits for GDB to connect on `addr`. Execution is stopped before the first instruction
+    /// until GDB resumes it.
+    pub fn listen(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (stream, _) = listener.accept()?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream.try_clone()?, stream))
+    }
+}
+
+impl<W: Write + Send> GdbStub<W> {
+    /// Creates a stub talking to GDB over `reader` and `writer`. Incoming data is read on a
+    /// separate thread, so that GDB can interrupt a running program.
+    pub fn new(reader: impl Read + Send + 'static, writer: W) -> Self {
+        let (sender, events) = mpsc::channel();
+        thread::spawn(move || read_events(reader, sender));
+        Self {
+            events,
+            writer,
+            mode: RunMode::Stopped,
+            last_signal: SIGTRAP,
+            breakpoints: BTreeSet::new(),
+            no_ack: false,
+        }
+    }
+
+    /// Reports the outcome of the execution to GDB, unless GDB already detached.
+    pub fn report_result<T>(&mut self, result: &Result<T, ExecutionError>) -> io::Result<()> {
+        if self.mode == RunMode::Detached {
+            return Ok(());
+        }
+        self.mode = RunMode::Detached;
+        let reply = match result {
+            Ok(_) => "W00".to_string(),
+            // GDB only understands 8-bit exit statuses.
+            Err(ExecutionError::FailedWithExitCode { exit_code, .. }) => {
+                format!("W{:02x}", exit_code & 0xff)
+            }
+            Err(_) => format!("X{SIGABRT:02x}"),
+        };
+        self.send(&reply)
+    }
+
+    fn interrupt_requested(&mut self) -> eyre::Result<bool> {
+        loop {
+            match self.events.try_recv() {
+                Ok(Event::Interrupt) => return Ok(true),
+                // GDB does not send packets while the program is running.
+                Ok(_) => continue,
+                Err(TryRecvError::Empty) => return Ok(false),
+                Err(TryRecvError::Disconnected) => {
+                    self.mode = RunMode::Detached;
+                    return Err(eyre!("GDB disconnected"));
+                }
+            }
+        }
+    }
+
+    /// Serves GDB requests while stopped at `pc`, until GDB resumes execution.
+    fn serve<F: PrimeField32>(&mut self, pc: u32, memory: Rv32MemoryView<F>) -> eyre::Result<()> {
+        loop {
+            let packet = match self.events.recv() {
+                Ok(Event::Packet(packet)) => packet,
+                Ok(Event::BadChecksum) => {
+                    // Without acknowledgments, GDB does not retransmit, so the packet is dropped.
+                    if !self.no_ack {
+                        self.write_raw(b"-")?;
+                    }
+                    continue;
+                }
+                Ok(Event::Interrupt) => continue,
+                Err(_) => {
+                    self.mode = RunMode::Detached;
+                    return Err(eyre!("GDB disconnected"));
+                }
+            };
+            if !self.no_ack {
+                self.write_raw(b"+")?;
+            }
+            match self.handle_packet(&packet, pc, memory) {
+                Response::Reply(reply) => {
+                    self.send(&reply)?;
+                    if self.mode == RunMode::Detached {
+                        return Ok(());
+                    }
+                }
+                Response::Resume => return Ok(()),
+                Response::Kill => {
+                    self.mode = RunMode::Detached;
+                    return Err(eyre!("program killed by GDB"));
+                }
+            }
+        }
+    }
+
+    fn handle_packet<F: PrimeField32>(
+        &mut self,
+        packet: &str,
+        pc: u32,
+        memory: Rv32MemoryView<F>,
+    ) -> Response {
+        let reply = match packet {
+            "?" => format!("S{:02x}", self.last_signal),
+            "QStartNoAckMode" => {
+                self.no_ack = true;
+                "OK".to_string()
+            }
+            "qAttached" => "1".to_string(),
+            "qC" => "QC1".to_string(),
+            "qfThreadInfo" => "m1".to_string(),
+            "qsThreadInfo" => "l".to_string(),
+            "g" => memory
+                .registers()
+                .into_iter()
+                .chain([pc])
+                .map(hex_u32)
+                .collect(),
+            "c" => {
+                self.mode = RunMode::Continue;
+                return Response::Resume;
+            }
+            "s" => {
+                self.mode = RunMode::Step;
+                return Response::Resume;
+            }
+            "k" => return Response::Kill,
+            "D" => {
+                self.mode = RunMode::Detached;
+                "OK".to_string()
+            }
+            _ if packet.starts_with("qSupported") => {
+                format!("PacketSize={PACKET_SIZE:x};QStartNoAckMode+;qXfer:features:read+")
+            }
+            _ if packet.starts_with("vKill") => return Response::Kill,
+            // There is a single thread, so thread selection and liveness always succeed.
+            _ if packet.starts_with('H') || packet.starts_with('T') => "OK".to_string(),
+            _ => {
+                if let Some(args) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+                    read_target_xml(args).unwrap_or_else(|| "E01".to_string())
+                } else if let Some(regnum) = packet.strip_prefix('p') {
+                    read_register(regnum, pc, memory).unwrap_or_else(|| "E01".to_string())
+                } else if let Some(args) = packet.strip_prefix('m') {
+                    read_memory(args, memory).unwrap_or_else(|| "E01".to_string())
+                } else if let Some(args) = packet.strip_prefix('Z') {
+                    match parse_breakpoint(args) {
+                        Some(addr) => {
+                            self.breakpoints.insert(addr);
+                            "OK".to_string()
+                        }
+                        None => String::new(),
+                    }
+                } else if let Some(args) = packet.strip_prefix('z') {
+                    match parse_breakpoint(args) {
+                        Some(addr) => {
+                            self.breakpoints.remove(&addr);
+                            "OK".to_string()
+                        }
+                        None => String::new(),
+                    }
+                } else {
+                    // An empty reply tells GDB that the packet is not supported.
+                    String::new()
+                }
+            }
+        };
+        Response::Reply(reply)
+    }
+
+    fn send(&mut self, data: &str) -> io::Result<()> {
+        let packet = format!("${data}#{:02x}", checksum(data.as_bytes()));
+        self.write_raw(packet.as_bytes())
+    }
+
+    fn write_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)?;
+        self.writer.flush()
+    }
+}
+
+impl<F: PrimeField32, W: Write + Send> ExecutionHook<F> for GdbStub<W> {
+    fn before_instruction(&mut self, pc: u32, memory: &MemoryImage<F>) -> eyre::Result<()> {
+        let signal = match self.mode {
+            RunMode::Detached => return Ok(()),
+            // GDB queries the initial stop reason itself.
+            RunMode::Stopped => None,
+            RunMode::Step => Some(SIGTRAP),
+            RunMode::Continue => {
+                if self.breakpoints.contains(&pc) {
+                    Some(SIGTRAP)
+                } else if self.interrupt_requested()? {
+                    Some(SIGINT)
+                } else {
+                    return Ok(());
+                }
+            }
+        };
+        if let Some(signal) = signal {
+            self.last_signal = signal;
+            self.send(&format!("S{signal:02x}"))?;
+        }
+        self.mode = RunMode::Stopped;
+        self.serve(pc, Rv32MemoryView::new(memory))
+    }
+}
+
+/// Splits the bytes received from GDB into [Event]s.
+fn read_events(reader: impl Read, sender: Sender<Event>) {
+    let mut bytes = BufReader::new(reader).bytes().map_while(Result::ok);
+    while let Some(byte) = bytes.next() {
+        let event = match byte {
+            0x03 => Event::Interrupt,
+            b'$' => {
+                let data: Vec<u8> = bytes.by_ref().take_while(|&byte| byte != b'#').collect();
+                let expected = bytes.by_ref().take(2).collect::<Vec<_>>();
+                let valid = std::str::from_utf8(&expected)
+                    .ok()
+                    .and_then(|expected| u8::from_str_radix(expected, 16).ok())
+                    == Some(checksum(&data));
+                match String::from_utf8(data) {
+                    Ok(packet) if valid => Event::Packet(packet),
+                    _ => Event::BadChecksum,
+                }
+            }
+            // Acknowledgements, which are not needed over TCP.
+            _ => continue,
+        };
+        if sender.send(event).is_err() {
+            return;
+        }
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+/// Encodes `value` in target (little-endian) byte order.
+fn hex_u32(value: u32) -> String {
+    hex_bytes(&value.to_le_bytes())
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_hex(s: &str) -> Option<u32> {
+    u32::from_str_radix(s, 16).ok()
+}
+
+/// Parses the `addr,length` arguments of a packet.
+fn parse_addr_len(args: &str) -> Option<(u32, usize)> {
+    let (addr, len) = args.split_once(',')?;
+    Some((parse_hex(addr)?, parse_hex(len)? as usize))
+}
+
+fn read_register<F: PrimeField32>(
+    regnum: &str,
+    pc: u32,
+    memory: Rv32MemoryView<F>,
+) -> Option<String> {
+    let value = match usize::from_str_radix(regnum, 16).ok()? {
+        0 => 0,
+        PC_REGNUM => pc,
+        regnum if regnum < PC_REGNUM => memory.read_register(regnum),
+        _ => return None,
+    };
+    Some(hex_u32(value))
+}
+
+fn read_memory<F: PrimeField32>(args: &str, memory: Rv32MemoryView<F>) -> Option<String> {
+    let (addr, len) = parse_addr_len(args)?;
+    let end = (addr as usize).checked_add(len)?;
+    (end <= memory.memory_len()).then(|| hex_bytes(&memory.read_bytes(addr, len)))
+}
+
+/// Parses the `type,addr,kind` arguments of a `Z` or `z` packet. Software and hardware
+/// breakpoints are both supported; watchpoints are not.
+fn parse_breakpoint(args: &str) -> Option<u32> {
+    let mut args = args.split(',');
+    let kind = args.next()?;
+    let addr = parse_hex(args.next()?)?;
+    matches!(kind, "0" | "1").then_some(addr)
+}
+
+fn read_target_xml(args: &str) -> Option<String> {
+    let (offset, len) = parse_addr_len(args)?;
+    let xml = target_xml();
+    let start = (offset as usize).min(xml.len());
+    let end = start.saturating_add(len).min(xml.len());
+    let marker = if end == xml.len() { 'l' } else { 'm' };
+    Some(format!("{marker}{}", &xml[start..end]))
+}
+
+/// Target description announcing a plain RV32 core, so that GDB does not expect floating point
+/// registers.
+fn target_xml() -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\">\
+         <target version=\"1.0\"><architecture>riscv:rv32</architecture>\
+         <feature name=\"org.gnu.gdb.riscv.cpu\">",
+    );
+    for (regnum, name) in REGISTER_NAMES.iter().enumerate() {
+        let ty = match *name {
+            "sp" | "gp" | "tp" | "fp" => "data_ptr",
+            "ra" => "code_ptr",
+            _ => "int",
+        };
+        xml += &format!("<reg name=\"{name}\" bitsize=\"32\" type=\"{ty}\" regnum=\"{regnum}\"/>");
+    }
+    xml += &format!("<reg name=\"pc\" bitsize=\"32\" type=\"code_ptr\" regnum=\"{PC_REGNUM}\"/>");
+    xml += "</feature></target>";
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS};
+    use openvm_stark_backend::p3_field::FieldAlgebra;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::*;
+    use crate::arch::MemoryConfig;
+
+    type F = BabyBear;
+
+    fn packet(data: &str) -> String {
+        format!("${data}#{:02x}", checksum(data.as_bytes()))
+    }
+
+    fn stub(packets: &[&str]) -> GdbStub<Vec<u8>> {
+        let input: String = packets.iter().map(|data| packet(data)).collect();
+        GdbStub::new(Cursor::new(input.into_bytes()), Vec::new())
+    }
+
+    fn take_output(stub: &mut GdbStub<Vec<u8>>) -> String {
+        String::from_utf8(std::mem::take(&mut stub.writer)).unwrap()
+    }
+
+    fn memory() -> MemoryImage<F> {
+        let mut image = MemoryImage::from_mem_config(&MemoryConfig::default());
+        for (i, byte) in 0x12345678u32.to_le_bytes().into_iter().enumerate() {
+            // x2 = 0x12345678
+            image.insert(
+                &(RV32_REGISTER_AS, 8 + i as u32),
+                F::from_canonical_u8(byte),
+            );
+            image.insert(
+                &(RV32_MEMORY_AS, 0x1000 + i as u32),
+                F::from_canonical_u8(byte),
+            );
+        }
+        image
+    }
+
+    #[test]
+    fn test_read_events() {
+        let (sender, receiver) = mpsc::channel();
+        let input = format!("+{}\x03$g#00", packet("qC"));
+        read_events(Cursor::new(input.into_bytes()), sender);
+        let events: Vec<_> = receiver.iter().collect();
+        assert_eq!(
+            events,
+            [
+                Event::Packet("qC".to_string()),
+                Event::Interrupt,
+                Event::BadChecksum
+            ]
+        );
+    }
+
+    #[test]
+    fn test_breakpoint_step_and_kill() {
+        let memory = memory();
+        let mut stub = stub(&["?", "Z0,104,4", "c", "p2", "m1000,4", "s", "g", "k"]);
+
+        // Stopped at the entry point until GDB continues.
+        ExecutionHook::<F>::before_instruction(&mut stub, 0x100, &memory).unwrap();
+        assert_eq!(
+            take_output(&mut stub),
+            format!("+{}+{}+", packet("S05"), packet("OK"))
+        );
+        // Running until the breakpoint.
+        ExecutionHook::<F>::before_instruction(&mut stub, 0x104, &memory).unwrap();
+        assert_eq!(
+            take_output(&mut stub),
+            format!(
+                "{}+{}+{}+",
+                packet("S05"),
+                packet("78563412"),
+                packet("78563412")
+            )
+        );
+        // Single step, then GDB kills the program.
+        let err = ExecutionHook::<F>::before_instruction(&mut stub, 0x108, &memory).unwrap_err();
+        assert_eq!(err.to_string(), "program killed by GDB");
+        let output = take_output(&mut stub);
+        let registers = output
+            .strip_prefix(&format!("{}+$", packet("S05")))
+            .unwrap();
+        assert_eq!(&registers[16..24], "78563412");
+        assert_eq!(&registers[256..264], "08010000");
+        // Nothing is reported after GDB killed the program.
+        stub.report_result(&Ok(())).unwrap();
+        assert_eq!(take_output(&mut stub), "");
+    }
+
+    #[test]
+    fn test_detach_and_report_exit() {
+        let memory = memory();
+        // The packet with a bad checksum is dropped silently in no-ack mode.
+        let input = [
+            packet("QStartNoAckMode"),
+            "$m1000,4#00".to_string(),
+            packet("m7fffffff,2"),
+            packet("c"),
+        ]
+        .concat();
+        let mut stub = GdbStub::new(Cursor::new(input.into_bytes()), Vec::new());
+        ExecutionHook::<F>::before_instruction(&mut stub, 0x100, &memory).unwrap();
+        assert_eq!(
+            take_output(&mut stub),
+            format!("+{}{}", packet("OK"), packet("E01"))
+        );
+        stub.report_result(&Err::<(), _>(ExecutionError::FailedWithExitCode {
+            exit_code: 0x101,
+            backtrace: None,
+        }))
+        .unwrap();
+        assert_eq!(take_output(&mut stub), packet("W01"));
+
+        let mut stub = stub_detached(&memory);
+        stub.report_result(&Ok(())).unwrap();
+        assert_eq!(take_output(&mut stub), "");
+    }
+
+    fn stub_detached(memory: &MemoryImage<F>) -> GdbStub<Vec<u8>> {
+        let mut stub = stub(&["D"]);
+        ExecutionHook::<F>::before_instruction(&mut stub, 0x100, memory).unwrap();
+        assert_eq!(take_output(&mut stub), format!("+{}", packet("OK")));
+        // A detached stub no longer stops execution.
+        ExecutionHook::<F>::before_instruction(&mut stub, 0x104, memory).unwrap();
+        stub
+    }
+
+    #[test]
+    fn test_target_xml() {
+        let xml = target_xml();
+        let first = read_target_xml("0,10").unwrap();
+        assert_eq!(first, format!("m{}", &xml[..16]));
+        let rest = read_target_xml(&format!("10,{:x}", xml.len())).unwrap();
+        assert_eq!(rest, format!("l{}", &xml[16..]));
+    }
+}
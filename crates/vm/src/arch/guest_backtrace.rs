@@ -0,0 +1,313 @@
+use std::{collections::VecDeque, fmt};
+
+use openvm_instructions::exe::{FnBound, FnBounds};
+use serde::{Deserialize, Serialize};
+
+/// Suggested number of most recently executed instructions to keep in a [GuestBacktrace] when
+/// debugging. Guest backtraces are disabled by default in [SystemConfig](super::SystemConfig)
+/// since tracking the execution history slows down execution for proving.
+pub const DEFAULT_BACKTRACE_LEN: usize = 32;
+
+/// Maximum depth of the shadow call stack. When exceeded, the outermost frames are dropped.
+const MAX_CALL_DEPTH: usize = 1 << 12;
+
+/// Snapshot of the guest state when execution fails. It is attached to
+/// [ExecutionError::Fail](super::ExecutionError::Fail),
+/// [ExecutionError::Trap](super::ExecutionError::Trap) and
+/// [ExecutionError::FailedWithExitCode](super::ExecutionError::FailedWithExitCode).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GuestBacktrace {
+    /// Call stack at the point of failure, innermost frame first.
+    pub frames: Vec<GuestFrame>,
+    /// The most recently executed instructions, oldest first. The last one is the instruction
+    /// at which execution failed.
+    pub recent_instructions: Vec<ExecutedInstruction>,
+    /// Values of the RV32 registers at the point of failure, with `x0` first.
+    pub registers: [u32; 32],
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuestFrame {
+    /// The failing pc for the innermost frame, and the pc of the call site for the other frames.
+    pub pc: u32,
+    /// Name of the function containing `pc`, if the executable has function bounds.
+    pub function: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutedInstruction {
+    pub pc: u32,
+    pub opcode: String,
+    /// The operands `a` to `g` of the instruction.
+    pub operands: [u32; 7],
+    /// The DSL instruction this instruction was compiled from, if the program has debug info.
+    pub dsl_instruction: Option<String>,
+}
+
+impl fmt::Display for GuestBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "guest backtrace:")?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let function = frame.function.as_deref().unwrap_or("<unknown>");
+            writeln!(f, "  {i:>3}: {:#010x} in {function}", frame.pc)?;
+        }
+        writeln!(
+            f,
+            "last {} executed instructions:",
+            self.recent_instructions.len()
+        )?;
+        for instruction in &self.recent_instructions {
+            write!(
+                f,
+                "  {:#010x}: {} {:?}",
+                instruction.pc, instruction.opcode, instruction.operands
+            )?;
+            if let Some(dsl_instruction) = &instruction.dsl_instruction {
+                write!(f, " ({dsl_instruction})")?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "registers:")?;
+        for (i, value) in self.registers.iter().enumerate() {
+            if i % 4 == 0 {
+                write!(f, "\n ")?;
+            }
+            write!(f, " x{i:<2} = {value:#010x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Execution history used to build a [GuestBacktrace]. It is carried over between segments.
+#[derive(Clone, Debug, Default)]
+pub struct GuestBacktraceState {
+    recent_pcs: VecDeque<u32>,
+    call_stack: ShadowCallStack,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CallFrame {
+    pub(crate) start: u32,
+    end: u32,
+    /// `None` for the outermost frame, whose caller is unknown.
+    call_site: Option<u32>,
+}
+
+impl CallFrame {
+    fn new(bound: &FnBound, call_site: Option<u32>) -> Self {
+        Self {
+            start: bound.start,
+            end: bound.end,
+            call_site,
+        }
+    }
+
+    fn contains(&self, pc: u32) -> bool {
+        (self.start..=self.end).contains(&pc)
+    }
+}
+
+/// Call stack reconstructed from the control flow and the function bounds of the executable. A
+/// jump to the start of a function is treated as a call, and a jump to just after the call site
+/// of the innermost frame as a return.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ShadowCallStack {
+    /// Outermost frame first.
+    frames: Vec<CallFrame>,
+}
+
+impl ShadowCallStack {
+    /// Updates the stack for the instruction at `from_pc`, which continued at `to_pc`. Returns
+    /// whether the stack changed.
+    pub(crate) fn record(&mut self, fn_bounds: &FnBounds, from_pc: u32, to_pc: u32) -> bool {
+        let frames = &mut self.frames;
+        // Both 4-byte and 2-byte (compressed) instructions fall through to the next one.
+        let sequential = matches!(to_pc.wrapping_sub(from_pc), 2 | 4);
+        if sequential {
+            if frames.last().is_some_and(|frame| frame.contains(to_pc)) {
+                return false;
+            }
+        } else {
+            if let Some(call_site) = frames.last().and_then(|frame| frame.call_site) {
+                if matches!(to_pc.wrapping_sub(call_site), 2 | 4) {
+                    frames.pop();
+                    return true;
+                }
+            }
+            if let Some(bound) = fn_bounds.get(&to_pc) {
+                if frames.len() == MAX_CALL_DEPTH {
+                    frames.remove(0);
+                }
+                frames.push(CallFrame::new(bound, Some(from_pc)));
+                return true;
+            }
+        }
+        // Unwind to the innermost frame containing `to_pc`, e.g. after a tail call returned.
+        let depth = frames.len();
+        while frames.last().is_some_and(|frame| !frame.contains(to_pc)) {
+            frames.pop();
+        }
+        let unwound = frames.len() != depth;
+        self.enter(fn_bounds, to_pc) || unwound
+    }
+
+    /// Pushes the function containing `pc` as the outermost frame if the stack is empty. Returns
+    /// whether the stack changed.
+    pub(crate) fn enter(&mut self, fn_bounds: &FnBounds, pc: u32) -> bool {
+        if !self.frames.is_empty() {
+            return false;
+        }
+        match function_at(fn_bounds, pc) {
+            Some(bound) => {
+                self.frames.push(CallFrame::new(bound, None));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the frames, outermost first.
+    pub(crate) fn frames(&self) -> &[CallFrame] {
+        &self.frames
+    }
+}
+
+/// Tracks the last executed instructions and, if function bounds are available, a
+/// [ShadowCallStack].
+pub(crate) struct GuestBacktraceTracker {
+    fn_bounds: FnBounds,
+    capacity: usize,
+    pub(crate) state: GuestBacktraceState,
+}
+
+impl GuestBacktraceTracker {
+    pub(crate) fn new(fn_bounds: FnBounds, capacity: usize) -> Self {
+        Self {
+            fn_bounds,
+            capacity,
+            state: Default::default(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Records the execution of the instruction at `from_pc`, which continued at `to_pc`.
+    pub(crate) fn record(&mut self, from_pc: u32, to_pc: u32) {
+        if !self.is_enabled() {
+            return;
+        }
+        let recent_pcs = &mut self.state.recent_pcs;
+        if recent_pcs.len() == self.capacity {
+            recent_pcs.pop_front();
+        }
+        recent_pcs.push_back(from_pc);
+
+        if !self.fn_bounds.is_empty() {
+            self.state
+                .call_stack
+                .record(&self.fn_bounds, from_pc, to_pc);
+        }
+    }
+
+    /// Returns the recorded pcs, oldest first.
+    pub(crate) fn recent_pcs(&self) -> impl Iterator<Item = u32> + '_ {
+        self.state.recent_pcs.iter().copied()
+    }
+
+    /// Returns the symbolized call stack for a failure at `pc`, innermost frame first.
+    pub(crate) fn frames(&self, pc: u32) -> Vec<GuestFrame> {
+        let call_sites = self
+            .state
+            .call_stack
+            .frames()
+            .iter()
+            .rev()
+            .filter_map(|frame| frame.call_site);
+        std::iter::once(pc)
+            .chain(call_sites)
+            .map(|pc| GuestFrame {
+                pc,
+                function: function_at(&self.fn_bounds, pc).map(|bound| bound.name.clone()),
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn function_at(fn_bounds: &FnBounds, pc: u32) -> Option<&FnBound> {
+    fn_bounds
+        .range(..=pc)
+        .next_back()
+        .map(|(_, bound)| bound)
+        .filter(|bound| pc <= bound.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(start: u32, end: u32, name: &str) -> (u32, FnBound) {
+        (
+            start,
+            FnBound {
+                start,
+                end,
+                name: name.to_string(),
+            },
+        )
+    }
+
+    fn function_names(frames: &[GuestFrame]) -> Vec<&str> {
+        frames
+            .iter()
+            .map(|frame| frame.function.as_deref().unwrap_or("?"))
+            .collect()
+    }
+
+    #[test]
+    fn test_shadow_call_stack() {
+        let fn_bounds = FnBounds::from_iter([
+            bound(0x100, 0x1fc, "main"),
+            bound(0x200, 0x2fc, "foo"),
+            bound(0x300, 0x3fc, "bar"),
+        ]);
+        let mut tracker = GuestBacktraceTracker::new(fn_bounds, 4);
+        tracker.record(0x100, 0x104);
+        // main calls foo, which calls bar
+        tracker.record(0x104, 0x200);
+        tracker.record(0x200, 0x204);
+        tracker.record(0x204, 0x300);
+        let frames = tracker.frames(0x308);
+        assert_eq!(function_names(&frames), ["bar", "foo", "main"]);
+        assert_eq!(frames[1].pc, 0x204);
+        assert_eq!(frames[2].pc, 0x104);
+        // bar returns to foo
+        tracker.record(0x308, 0x208);
+        assert_eq!(function_names(&tracker.frames(0x208)), ["foo", "main"]);
+        // foo tail calls bar, which returns to main
+        tracker.record(0x208, 0x300);
+        tracker.record(0x300, 0x108);
+        assert_eq!(function_names(&tracker.frames(0x108)), ["main"]);
+
+        let recent_pcs: Vec<_> = tracker.recent_pcs().collect();
+        assert_eq!(recent_pcs, [0x204, 0x308, 0x208, 0x300]);
+    }
+
+    #[test]
+    fn test_recursive_calls() {
+        let fn_bounds =
+            FnBounds::from_iter([bound(0x100, 0x1fc, "main"), bound(0x200, 0x2fc, "f")]);
+        let mut tracker = GuestBacktraceTracker::new(fn_bounds, 4);
+        tracker.record(0x100, 0x104);
+        tracker.record(0x104, 0x200);
+        tracker.record(0x210, 0x200);
+        tracker.record(0x210, 0x200);
+        assert_eq!(
+            function_names(&tracker.frames(0x220)),
+            ["f", "f", "f", "main"]
+        );
+        tracker.record(0x220, 0x214);
+        assert_eq!(function_names(&tracker.frames(0x214)), ["f", "f", "main"]);
+    }
+}
@@ -0,0 +1,16 @@
+use std::sync::{Arc, Mutex};
+
+use crate::system::memory::MemoryImage;
+
+/// Callback invoked by [execute_from_pc](super::ExecutionSegment::execute_from_pc) before each
+/// instruction is executed, e.g. to pause execution in a debugger.
+///
+/// The hook only observes the VM state, so it does not affect the generated traces.
+pub trait ExecutionHook<F>: Send {
+    /// Called with the pc of the next instruction and the current memory. Returning an error
+    /// aborts execution with [ExecutionError::Hook](super::ExecutionError::Hook).
+    fn before_instruction(&mut self, pc: u32, memory: &MemoryImage<F>) -> eyre::Result<()>;
+}
+
+/// An [ExecutionHook] shared between the segments of an execution.
+pub type SharedExecutionHook<F> = Arc<Mutex<dyn ExecutionHook<F>>>;
@@ -0,0 +1,474 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::UNIX_EPOCH,
+};
+
+use memmap2::Mmap;
+use openvm_stark_backend::p3_field::FieldAlgebra;
+
+/// A source of guest inputs which are only read when the guest asks for them, so that large
+/// inputs never have to be held in memory at once. Each input is read as bytes and converted to
+/// field elements, one per byte, when it is popped from the [InputStream].
+pub trait InputProvider: Send {
+    /// Reads the next input, or returns `None` once all inputs have been read.
+    fn next_input(&mut self) -> io::Result<Option<Vec<u8>>>;
+
+    /// Returns an independent provider at the same position, used when the [InputStream] is
+    /// cloned.
+    fn box_clone(&self) -> Box<dyn InputProvider>;
+
+    /// Bytes identifying the remaining inputs, e.g. the path, length and modification time of the
+    /// file they are read from, so that a proof is only resumed from checkpoints with the same
+    /// inputs. Defaults to `None`: the inputs cannot be identified, and cannot be checkpointed.
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl Clone for Box<dyn InputProvider> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// The inputs of the guest, consumed one at a time by the input hint instructions. Inputs in
+/// memory are served first, then the inputs of the [InputProvider], if any.
+#[derive(Clone)]
+pub struct InputStream<F> {
+    buffer: VecDeque<Vec<F>>,
+    /// Set to `None` once exhausted.
+    provider: Option<Box<dyn InputProvider>>,
+    /// Number of inputs read from `provider` so far.
+    provider_inputs_read: u64,
+}
+
+impl<F> InputStream<F> {
+    pub fn new(buffer: impl Into<VecDeque<Vec<F>>>) -> Self {
+        Self {
+            buffer: buffer.into(),
+            provider: None,
+            provider_inputs_read: 0,
+        }
+    }
+
+    /// Serves the inputs of `provider` after the inputs in memory.
+    pub fn set_provider(&mut self, provider: Box<dyn InputProvider>) {
+        self.provider = Some(provider);
+    }
+
+    /// Puts `input` in front of the stream, to be popped next.
+    pub fn push_front(&mut self, input: Vec<F>) {
+        self.buffer.push_front(input);
+    }
+
+    /// Adds `input` after the inputs in memory, but before the inputs of the provider.
+    pub fn push_back(&mut self, input: Vec<F>) {
+        self.buffer.push_back(input);
+    }
+
+    /// Returns `true` if no input is left. An [InputProvider] is only known to be exhausted
+    /// after [Self::pop_front] tried to read past its last input.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.provider.is_none()
+    }
+
+    /// Returns the inputs in memory which have not been popped yet.
+    pub fn buffered(&self) -> impl Iterator<Item = &Vec<F>> {
+        self.buffer.iter()
+    }
+
+    /// Returns the number of inputs read from the [InputProvider] so far.
+    pub fn provider_inputs_read(&self) -> u64 {
+        self.provider_inputs_read
+    }
+}
+
+impl<F: FieldAlgebra> InputStream<F> {
+    /// Removes the next input, reading it from the provider if no input is left in memory.
+    pub fn pop_front(&mut self) -> io::Result<Option<Vec<F>>> {
+        if let Some(input) = self.buffer.pop_front() {
+            return Ok(Some(input));
+        }
+        let Some(provider) = &mut self.provider else {
+            return Ok(None);
+        };
+        match provider.next_input()? {
+            Some(bytes) => {
+                self.provider_inputs_read += 1;
+                Ok(Some(bytes.into_iter().map(F::from_canonical_u8).collect()))
+            }
+            None => {
+                self.provider = None;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Continues where another stream over the same inputs stopped: replaces the inputs in
+    /// memory with `buffer` and skips the first `provider_inputs_read` inputs of the provider.
+    pub fn restore(
+        &mut self,
+        buffer: impl Into<VecDeque<Vec<F>>>,
+        provider_inputs_read: u64,
+    ) -> io::Result<()> {
+        self.buffer.clear();
+        for _ in self.provider_inputs_read..provider_inputs_read {
+            if self.pop_front()?.is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the input provider has fewer inputs than were already read",
+                ));
+            }
+        }
+        self.buffer = buffer.into();
+        Ok(())
+    }
+}
+
+impl<F> Default for InputStream<F> {
+    fn default() -> Self {
+        Self::new(VecDeque::new())
+    }
+}
+
+impl<F> From<VecDeque<Vec<F>>> for InputStream<F> {
+    fn from(buffer: VecDeque<Vec<F>>) -> Self {
+        Self::new(buffer)
+    }
+}
+
+impl<F> From<Vec<Vec<F>>> for InputStream<F> {
+    fn from(buffer: Vec<Vec<F>>) -> Self {
+        Self::new(buffer)
+    }
+}
+
+/// How a file or byte stream is split into inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFraming {
+    /// Each input is preceded by its length in bytes, as a little-endian `u32`.
+    LengthPrefixed,
+    /// The data is split into inputs of the given number of bytes. The last input may be shorter.
+    Chunks(usize),
+}
+
+impl InputFraming {
+    fn fingerprint(self) -> [u8; 8] {
+        match self {
+            InputFraming::LengthPrefixed => u64::MAX,
+            InputFraming::Chunks(chunk_size) => chunk_size as u64,
+        }
+        .to_le_bytes()
+    }
+
+    fn validate(self) -> io::Result<Self> {
+        if self == InputFraming::Chunks(0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "input chunk size must be positive",
+            ));
+        }
+        Ok(self)
+    }
+}
+
+/// Provides the inputs yielded by an iterator, e.g. one generating them on the fly.
+#[derive(Clone)]
+pub struct IterInputProvider<I>(pub I);
+
+impl<I> InputProvider for IterInputProvider<I>
+where
+    I: Iterator<Item = Vec<u8>> + Clone + Send + 'static,
+{
+    fn next_input(&mut self) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.0.next())
+    }
+
+    fn box_clone(&self) -> Box<dyn InputProvider> {
+        Box::new(self.clone())
+    }
+}
+
+/// Reads the inputs from a file, one input at a time.
+#[derive(Clone)]
+pub struct FileInputProvider {
+    /// Shared with clones, which seek to their own position before every read.
+    file: Arc<Mutex<File>>,
+    file_fingerprint: Arc<[u8]>,
+    len: u64,
+    offset: u64,
+    framing: InputFraming,
+}
+
+impl FileInputProvider {
+    pub fn open(path: impl AsRef<Path>, framing: InputFraming) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        let metadata = file.metadata()?;
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+            file_fingerprint: file_fingerprint(path.as_ref(), &metadata)?.into(),
+            len: metadata.len(),
+            offset: 0,
+            framing: framing.validate()?,
+        })
+    }
+}
+
+impl InputProvider for FileInputProvider {
+    fn next_input(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if self.offset == self.len {
+            return Ok(None);
+        }
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(self.offset))?;
+        let input_len = match self.framing {
+            InputFraming::LengthPrefixed => {
+                let mut len_bytes = [0u8; 4];
+                file.read_exact(&mut len_bytes)?;
+                self.offset += 4;
+                u32::from_le_bytes(len_bytes) as u64
+            }
+            InputFraming::Chunks(chunk_size) => (chunk_size as u64).min(self.len - self.offset),
+        };
+        if input_len > self.len - self.offset {
+            return Err(truncated_input());
+        }
+        let mut input = vec![0u8; input_len as usize];
+        file.read_exact(&mut input)?;
+        self.offset += input_len;
+        Ok(Some(input))
+    }
+
+    fn box_clone(&self) -> Box<dyn InputProvider> {
+        Box::new(self.clone())
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        Some(
+            [
+                &self.framing.fingerprint()[..],
+                &self.offset.to_le_bytes(),
+                &self.file_fingerprint,
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// Reads the inputs from a memory-mapped file, leaving it to the OS to page the data in and out.
+#[derive(Clone)]
+pub struct MmapInputProvider {
+    mmap: Arc<Mmap>,
+    file_fingerprint: Arc<[u8]>,
+    offset: usize,
+    framing: InputFraming,
+}
+
+impl MmapInputProvider {
+    /// The file must not be modified while the provider or one of its clones is alive.
+    pub fn open(path: impl AsRef<Path>, framing: InputFraming) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        // SAFETY: the file is only read, and callers guarantee that it is not modified while it is
+        // mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            mmap: Arc::new(mmap),
+            file_fingerprint: file_fingerprint(path.as_ref(), &file.metadata()?)?.into(),
+            offset: 0,
+            framing: framing.validate()?,
+        })
+    }
+}
+
+impl InputProvider for MmapInputProvider {
+    fn next_input(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let data = &self.mmap[self.offset..];
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let (start, input_len) = match self.framing {
+            InputFraming::LengthPrefixed => {
+                let len_bytes = data.get(..4).ok_or_else(truncated_input)?;
+                (
+                    4,
+                    u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize,
+                )
+            }
+            InputFraming::Chunks(chunk_size) => (0, chunk_size.min(data.len())),
+        };
+        let input = data
+            .get(start..start + input_len)
+            .ok_or_else(truncated_input)?;
+        self.offset += start + input_len;
+        Ok(Some(input.to_vec()))
+    }
+
+    fn box_clone(&self) -> Box<dyn InputProvider> {
+        Box::new(self.clone())
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        let offset = self.offset as u64;
+        Some(
+            [
+                &self.framing.fingerprint()[..],
+                &offset.to_le_bytes(),
+                &self.file_fingerprint,
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// Identifies the file at `path` by its length, modification time and canonical path.
+pub(crate) fn file_fingerprint(path: &Path, metadata: &Metadata) -> io::Result<Vec<u8>> {
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok([
+        &metadata.len().to_le_bytes()[..],
+        &modified.as_nanos().to_le_bytes(),
+        fs::canonicalize(path)?.as_os_str().as_encoded_bytes(),
+    ]
+    .concat())
+}
+
+fn truncated_input() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "input file ends in the middle of an input",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn bytes_to_fields(bytes: &[u8]) -> Vec<F> {
+        bytes.iter().map(|&b| F::from_canonical_u8(b)).collect()
+    }
+
+    fn drain(stream: &mut InputStream<F>) -> Vec<Vec<F>> {
+        std::iter::from_fn(|| stream.pop_front().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_buffered_inputs_come_first() {
+        let mut stream = InputStream::new(vec![vec![F::ONE]]);
+        let inputs = vec![vec![1u8, 2], vec![3]];
+        stream.set_provider(Box::new(IterInputProvider(inputs.into_iter())));
+        stream.push_front(vec![F::ZERO]);
+        assert!(!stream.is_empty());
+
+        let mut clone = stream.clone();
+        let expected = vec![
+            vec![F::ZERO],
+            vec![F::ONE],
+            bytes_to_fields(&[1, 2]),
+            bytes_to_fields(&[3]),
+        ];
+        assert_eq!(drain(&mut stream), expected);
+        assert!(stream.is_empty());
+        // Clones read the provider independently.
+        assert_eq!(drain(&mut clone), expected);
+    }
+
+    #[test]
+    fn test_restore() {
+        let inputs = vec![vec![1u8], vec![2], vec![3]];
+        let mut stream = InputStream::new(vec![vec![F::ZERO]]);
+        stream.set_provider(Box::new(IterInputProvider(inputs.clone().into_iter())));
+        stream.pop_front().unwrap();
+        stream.pop_front().unwrap();
+        stream.push_front(vec![F::TWO]);
+        assert_eq!(stream.provider_inputs_read(), 1);
+
+        let mut restored = InputStream::new(vec![vec![F::ZERO]]);
+        restored.set_provider(Box::new(IterInputProvider(inputs.clone().into_iter())));
+        let buffered: Vec<_> = stream.buffered().cloned().collect();
+        restored
+            .restore(buffered, stream.provider_inputs_read())
+            .unwrap();
+        assert_eq!(drain(&mut restored), drain(&mut stream));
+
+        let mut short = InputStream::<F>::default();
+        short.set_provider(Box::new(IterInputProvider(inputs.into_iter())));
+        assert!(short.restore(vec![], 4).is_err());
+    }
+
+    #[test]
+    fn test_file_providers() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for input in [&b"abc"[..], b"", b"defgh"] {
+            file.write_all(&(input.len() as u32).to_le_bytes()).unwrap();
+            file.write_all(input).unwrap();
+        }
+        file.flush().unwrap();
+        let path = file.path();
+
+        let framed = [b"abc".to_vec(), vec![], b"defgh".to_vec()];
+        let mut chunks = vec![];
+        for chunk in std::fs::read(path).unwrap().chunks(5) {
+            chunks.push(chunk.to_vec());
+        }
+        let providers: [(Box<dyn InputProvider>, &[Vec<u8>]); 4] = [
+            (
+                Box::new(FileInputProvider::open(path, InputFraming::LengthPrefixed).unwrap()),
+                &framed,
+            ),
+            (
+                Box::new(MmapInputProvider::open(path, InputFraming::LengthPrefixed).unwrap()),
+                &framed,
+            ),
+            (
+                Box::new(FileInputProvider::open(path, InputFraming::Chunks(5)).unwrap()),
+                &chunks,
+            ),
+            (
+                Box::new(MmapInputProvider::open(path, InputFraming::Chunks(5)).unwrap()),
+                &chunks,
+            ),
+        ];
+        for (mut provider, expected) in providers {
+            let inputs: Vec<_> = std::iter::from_fn(|| provider.next_input().unwrap()).collect();
+            assert_eq!(inputs, expected);
+        }
+
+        // A length prefix past the end of the file is an error.
+        file.write_all(&10u32.to_le_bytes()).unwrap();
+        file.flush().unwrap();
+        let mut provider = MmapInputProvider::open(path, InputFraming::LengthPrefixed).unwrap();
+        for _ in 0..3 {
+            provider.next_input().unwrap();
+        }
+        assert!(provider.next_input().is_err());
+        assert!(FileInputProvider::open(path, InputFraming::Chunks(0)).is_err());
+
+        // Fingerprints depend on the framing and the position.
+        let provider = FileInputProvider::open(path, InputFraming::LengthPrefixed).unwrap();
+        let mut advanced = provider.clone();
+        advanced.next_input().unwrap();
+        let fingerprints = [
+            provider.fingerprint(),
+            advanced.fingerprint(),
+            FileInputProvider::open(path, InputFraming::Chunks(5))
+                .unwrap()
+                .fingerprint(),
+        ];
+        assert!(fingerprints.iter().all(Option::is_some));
+        assert_ne!(fingerprints[0], fingerprints[1]);
+        assert_ne!(fingerprints[0], fingerprints[2]);
+        assert_eq!(provider.fingerprint(), provider.clone().fingerprint());
+    }
+}
@@ -0,0 +1,462 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use memmap2::Mmap;
+use sha2::{Digest, Sha256};
+
+use super::input::file_fingerprint;
+
+/// A trait for key-value store for `Streams`.
+pub trait KvStore: Send + Sync {
+    /// Returns the value of `key` if the store holds it in memory. Stores which read values on
+    /// demand may not support it, so executors should use [KvStore::try_get].
+    fn get(&self, key: &[u8]) -> Option<&[u8]>;
+
+    /// Looks up the value of `key`, returning an error if the store cannot read it. Stores
+    /// backed by files override this to return values without keeping them in memory. Defaults
+    /// to [KvStore::get].
+    fn try_get(&self, key: &[u8]) -> io::Result<Option<Cow<'_, [u8]>>> {
+        Ok(self.get(key).map(Cow::Borrowed))
+    }
+
+    /// Bytes identifying the entries, e.g. a hash of them or the length, modification time and
+    /// path of the file holding them, so that a proof is only resumed from checkpoints with the
+    /// same entries. Defaults to `None`: the entries cannot be identified, and cannot be
+    /// checkpointed.
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl KvStore for HashMap<Vec<u8>, Vec<u8>> {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.get(key).map(|v| v.as_slice())
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable();
+        let mut hasher = Sha256::new();
+        for (key, value) in entries {
+            for bytes in [key, value] {
+                hasher.update((bytes.len() as u64).to_le_bytes());
+                hasher.update(bytes);
+            }
+        }
+        Some(hasher.finalize().to_vec())
+    }
+}
+
+/// Looks up keys in each store in order and returns the first value found.
+#[derive(Clone, Default)]
+pub struct ChainedKvStore(pub Vec<Arc<dyn KvStore>>);
+
+impl KvStore for ChainedKvStore {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.0.iter().find_map(|store| store.get(key))
+    }
+
+    fn try_get(&self, key: &[u8]) -> io::Result<Option<Cow<'_, [u8]>>> {
+        for store in &self.0 {
+            if let Some(value) = store.try_get(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        let mut hasher = Sha256::new();
+        for store in &self.0 {
+            let fingerprint = store.fingerprint()?;
+            hasher.update((fingerprint.len() as u64).to_le_bytes());
+            hasher.update(fingerprint);
+        }
+        Some(hasher.finalize().to_vec())
+    }
+}
+
+/// A key-value store in a directory of content-addressed blobs, so that values shared by many keys
+/// are only stored once. Values are only read when looked up.
+///
+/// The value of a key is stored in `blobs/<sha256(value)>` and `keys/<sha256(key)>` holds the
+/// 32-byte hash of the value, with hashes written in hex and split after the first byte to keep
+/// directories small.
+///
+/// Values are only returned by [KvStore::try_get]. [KvStore::get] is not supported, since values
+/// are not kept in memory, and always returns `None`.
+pub struct BlobDirKvStore {
+    dir: PathBuf,
+}
+
+impl BlobDirKvStore {
+    const KEYS_DIR: &'static str = "keys";
+    const BLOBS_DIR: &'static str = "blobs";
+
+    /// Opens an existing store in `dir`, without modifying the directory.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        for subdir in [Self::KEYS_DIR, Self::BLOBS_DIR] {
+            if !dir.join(subdir).is_dir() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "{} is not a key-value store directory: missing {subdir}/",
+                        dir.display()
+                    ),
+                ));
+            }
+        }
+        Ok(Self { dir })
+    }
+
+    /// Creates an empty store in `dir`, or opens the store already in it.
+    pub fn create(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join(Self::KEYS_DIR))?;
+        fs::create_dir_all(dir.join(Self::BLOBS_DIR))?;
+        Ok(Self { dir })
+    }
+
+    /// Sets the value of `key`, replacing its previous value.
+    pub fn insert(&self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        let value_hash = Sha256::digest(value);
+        let blob_path = self.hash_path(Self::BLOBS_DIR, &value_hash);
+        if !blob_path.exists() {
+            write_file_atomic(&blob_path, value)?;
+        }
+        write_file_atomic(
+            &self.hash_path(Self::KEYS_DIR, &Sha256::digest(key)),
+            &value_hash,
+        )
+    }
+
+    fn hash_path(&self, kind: &str, hash: &[u8]) -> PathBuf {
+        let hex = hash.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        self.dir.join(kind).join(&hex[..2]).join(&hex[2..])
+    }
+}
+
+impl KvStore for BlobDirKvStore {
+    fn get(&self, _key: &[u8]) -> Option<&[u8]> {
+        tracing::warn!(
+            "BlobDirKvStore at {} does not support KvStore::get, use KvStore::try_get",
+            self.dir.display()
+        );
+        None
+    }
+
+    fn try_get(&self, key: &[u8]) -> io::Result<Option<Cow<'_, [u8]>>> {
+        let value_hash = match fs::read(self.hash_path(Self::KEYS_DIR, &Sha256::digest(key))) {
+            Ok(value_hash) => value_hash,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if value_hash.len() != 32 {
+            return Err(invalid_data("key entry is not a SHA-256 hash"));
+        }
+        let value = fs::read(self.hash_path(Self::BLOBS_DIR, &value_hash))?;
+        if Sha256::digest(&value).as_slice() != value_hash {
+            return Err(invalid_data("blob does not match its hash"));
+        }
+        Ok(Some(Cow::Owned(value)))
+    }
+
+    /// Hashes the key entries, which change whenever a value does since blobs are
+    /// content-addressed. Returns `None` if they cannot be read.
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        let mut entries = Vec::new();
+        for subdir in fs::read_dir(self.dir.join(Self::KEYS_DIR)).ok()? {
+            let subdir = subdir.ok()?.path();
+            for entry in fs::read_dir(&subdir).ok()? {
+                let path = entry.ok()?.path();
+                // Skip temporary files of concurrent writers.
+                if path.extension().is_none() {
+                    entries.push((
+                        path.strip_prefix(&self.dir).ok()?.to_path_buf(),
+                        fs::read(&path).ok()?,
+                    ));
+                }
+            }
+        }
+        entries.sort_unstable();
+        let mut hasher = Sha256::new();
+        for (path, value_hash) in entries {
+            hasher.update(path.as_os_str().as_encoded_bytes());
+            hasher.update(value_hash);
+        }
+        Some(hasher.finalize().to_vec())
+    }
+}
+
+/// Writes to a temporary file first, so that readers never see a partially written file. The
+/// temporary file is unique to the call, so that concurrent writers do not clobber each other.
+fn write_file_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static NEXT_TMP_ID: AtomicU64 = AtomicU64::new(0);
+    fs::create_dir_all(path.parent().unwrap())?;
+    let tmp_id = NEXT_TMP_ID.fetch_add(1, Ordering::Relaxed);
+    let mut tmp_name = path.file_name().unwrap().to_os_string();
+    tmp_name.push(format!(".{}-{tmp_id}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, contents)?;
+    fs::rename(tmp_path, path)
+}
+
+/// A key-value store in a single memory-mapped file with an index sorted by key, written with
+/// [MmapKvStore::write]. Keys are found by binary search and values are served straight from the
+/// mapping, leaving it to the OS to page the data in and out.
+///
+/// The file starts with the magic bytes, the offset of the index and the number of entries. The
+/// keys and values follow, and the index at the end has one `(key offset, key length, value
+/// offset, value length)` entry per key, sorted by key. All numbers are little-endian `u64`s.
+pub struct MmapKvStore {
+    mmap: Mmap,
+    file_fingerprint: Vec<u8>,
+    index_offset: usize,
+    len: usize,
+}
+
+impl MmapKvStore {
+    const MAGIC: &'static [u8; 8] = b"OVMKVIDX";
+    const HEADER_LEN: usize = 24;
+    const ENTRY_LEN: usize = 32;
+
+    /// Opens the index file at `path` and checks that every entry is in bounds and that the keys
+    /// are sorted, so that lookups cannot fail. The file must not be modified while the store is
+    /// alive.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        // SAFETY: the file is only read, and callers guarantee that it is not modified while it is
+        // mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < Self::HEADER_LEN || &mmap[..8] != Self::MAGIC {
+            return Err(invalid_data("not a key-value index file"));
+        }
+        let index_offset = read_u64(&mmap, 8)?;
+        let len = read_u64(&mmap, 16)?;
+        let index_len = len.checked_mul(Self::ENTRY_LEN);
+        if index_len.and_then(|l| l.checked_add(index_offset)) != Some(mmap.len()) {
+            return Err(invalid_data("key-value index does not match the file size"));
+        }
+        let mut prev_key: Option<&[u8]> = None;
+        for idx in 0..len {
+            let key = Self::entry_slice(&mmap, index_offset, idx, 0)?;
+            Self::entry_slice(&mmap, index_offset, idx, 1)?;
+            if prev_key.is_some_and(|prev_key| prev_key >= key) {
+                return Err(invalid_data("key-value index is not sorted by key"));
+            }
+            prev_key = Some(key);
+        }
+        Ok(Self {
+            mmap,
+            file_fingerprint: file_fingerprint(path.as_ref(), &file.metadata()?)?,
+            index_offset,
+            len,
+        })
+    }
+
+    /// Writes the entries to a new index file at `path`. Values are written as they come and
+    /// only the keys are kept in memory, to sort them. Duplicate keys are rejected.
+    pub fn write(
+        path: impl AsRef<Path>,
+        entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&[0; Self::HEADER_LEN])?;
+        let mut offset = Self::HEADER_LEN as u64;
+        let mut index = Vec::new();
+        for (key, value) in entries {
+            writer.write_all(&key)?;
+            writer.write_all(&value)?;
+            let key_offset = offset;
+            offset += (key.len() + value.len()) as u64;
+            index.push((key, key_offset, key_offset + key.len() as u64, value.len()));
+        }
+        index.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        if index.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "duplicate key in key-value index",
+            ));
+        }
+        for (key, key_offset, value_offset, value_len) in &index {
+            for n in [
+                *key_offset,
+                key.len() as u64,
+                *value_offset,
+                *value_len as u64,
+            ] {
+                writer.write_all(&n.to_le_bytes())?;
+            }
+        }
+        writer.seek(SeekFrom::Start(0))?;
+        writer.write_all(Self::MAGIC)?;
+        writer.write_all(&offset.to_le_bytes())?;
+        writer.write_all(&(index.len() as u64).to_le_bytes())?;
+        writer.into_inner().map_err(|e| e.into_error())?.sync_all()
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the key (`field == 0`) or the value (`field == 1`) of the `idx`-th index entry.
+    fn entry_slice(
+        mmap: &[u8],
+        index_offset: usize,
+        idx: usize,
+        field: usize,
+    ) -> io::Result<&[u8]> {
+        let entry = index_offset + idx * Self::ENTRY_LEN;
+        let offset = read_u64(mmap, entry + 16 * field)?;
+        let len = read_u64(mmap, entry + 16 * field + 8)?;
+        offset
+            .checked_add(len)
+            .filter(|&end| end <= index_offset)
+            .map(|end| &mmap[offset..end])
+            .ok_or_else(|| invalid_data("key-value index entry out of bounds"))
+    }
+
+    fn entry(&self, idx: usize, field: usize) -> &[u8] {
+        Self::entry_slice(&self.mmap, self.index_offset, idx, field)
+            .expect("index entries are checked when opening")
+    }
+}
+
+impl KvStore for MmapKvStore {
+    fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.entry(mid, 0).cmp(key) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(self.entry(mid, 1)),
+            }
+        }
+        None
+    }
+
+    fn fingerprint(&self) -> Option<Vec<u8>> {
+        Some(self.file_fingerprint.clone())
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> io::Result<usize> {
+    let bytes = data
+        .get(offset..offset + 8)
+        .ok_or_else(|| invalid_data("key-value index entry out of bounds"))?;
+    usize::try_from(u64::from_le_bytes(bytes.try_into().unwrap()))
+        .map_err(|_| invalid_data("key-value index offset too large"))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+        vec![
+            (b"b".to_vec(), b"value b".to_vec()),
+            (b"a".to_vec(), vec![]),
+            (b"aa".to_vec(), b"value b".to_vec()),
+            (vec![], b"empty key".to_vec()),
+        ]
+    }
+
+    fn assert_entries(store: &dyn KvStore) {
+        for (key, value) in entries() {
+            assert_eq!(store.try_get(&key).unwrap().as_deref(), Some(&value[..]));
+        }
+        for key in [&b"c"[..], b"ab"] {
+            assert_eq!(store.try_get(key).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_blob_dir_kv_store() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(BlobDirKvStore::open(dir.path()).is_err());
+        assert!(fs::read_dir(dir.path()).unwrap().next().is_none());
+        let store = BlobDirKvStore::create(dir.path()).unwrap();
+        for (key, value) in entries() {
+            store.insert(&key, &value).unwrap();
+        }
+        assert_entries(&store);
+        let fingerprint = store.fingerprint().unwrap();
+        // Equal values share a blob.
+        let num_blobs: usize = fs::read_dir(dir.path().join("blobs"))
+            .unwrap()
+            .map(|subdir| fs::read_dir(subdir.unwrap().path()).unwrap().count())
+            .sum();
+        assert_eq!(num_blobs, 3);
+
+        store.insert(b"b", b"new value").unwrap();
+        assert_eq!(
+            store.try_get(b"b").unwrap().as_deref(),
+            Some(&b"new value"[..])
+        );
+        let reopened = BlobDirKvStore::open(dir.path()).unwrap();
+        assert_ne!(reopened.fingerprint().unwrap(), fingerprint);
+        assert_eq!(reopened.fingerprint(), store.fingerprint());
+        assert_eq!(
+            reopened.try_get(b"b").unwrap().as_deref(),
+            Some(&b"new value"[..])
+        );
+        assert_eq!(reopened.get(b"b"), None);
+    }
+
+    #[test]
+    fn test_mmap_kv_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kv.idx");
+        MmapKvStore::write(&path, entries()).unwrap();
+        let store = MmapKvStore::open(&path).unwrap();
+        assert_eq!(store.len(), 4);
+        assert_entries(&store);
+        assert_eq!(store.get(b"aa"), Some(&b"value b"[..]));
+
+        let chained = ChainedKvStore(vec![
+            Arc::new(HashMap::from([(b"b".to_vec(), b"override".to_vec())])),
+            Arc::new(store),
+        ]);
+        assert_eq!(chained.get(b"b"), Some(&b"override"[..]));
+        assert!(chained.fingerprint().is_some());
+        assert_eq!(
+            chained.try_get(b"aa").unwrap().as_deref(),
+            Some(&b"value b"[..])
+        );
+
+        // Swap the keys of the first two index entries.
+        let mut data = fs::read(&path).unwrap();
+        let index_offset = read_u64(&data, 8).unwrap();
+        let (first, second) = data[index_offset..].split_at_mut(MmapKvStore::ENTRY_LEN);
+        first[..16].swap_with_slice(&mut second[..16]);
+        let unsorted_path = dir.path().join("unsorted.idx");
+        fs::write(&unsorted_path, data).unwrap();
+        assert!(MmapKvStore::open(&unsorted_path).is_err());
+
+        let mut duplicates = entries();
+        duplicates.push((b"a".to_vec(), vec![1]));
+        assert!(MmapKvStore::write(&path, duplicates).is_err());
+        fs::write(&path, b"OVMKVIDX").unwrap();
+        assert!(MmapKvStore::open(&path).is_err());
+    }
+}
@@ -4,8 +4,22 @@ mod config;
 mod execution;
 /// Traits and builders to compose collections of chips into a virtual machine.
 mod extensions;
+/// GDB remote serial protocol stub for debugging RV32 guests.
+pub mod gdb;
+/// Guest call stacks and state captured on execution failure.
+mod guest_backtrace;
+/// Callbacks into the execution loop.
+mod hook;
+/// Guest input streams and lazily read input providers.
+mod input;
 /// Traits and wrappers to facilitate VM chip integration
 mod integration_api;
+/// Key-value stores for hints, in memory and backed by files.
+mod kv_store;
+/// Cycle and trace cell profiles of guest functions.
+pub mod profiler;
+/// Recording of executed instructions for comparing executions.
+pub mod recording;
 /// Runtime execution and segmentation
 pub mod segment;
 /// Top level [VirtualMachine] constructor and API.
@@ -21,6 +35,10 @@ pub mod testing;
 pub use config::*;
 pub use execution::*;
 pub use extensions::*;
+pub use guest_backtrace::*;
+pub use hook::*;
+pub use input::*;
 pub use integration_api::*;
+pub use kv_store::*;
 pub use segment::*;
 pub use vm::*;
@@ -0,0 +1,242 @@
+//! Attribution of executed instructions to guest functions, for flamegraphs.
+//!
+//! The [GuestProfiler] follows the guest call stack with a [ShadowCallStack] built from the
+//! function bounds of the executable, and charges every executed instruction and the trace cells
+//! it added to the call stack it was executed in. The result is written in the collapsed stack
+//! format, one `outer;inner <value>` line per call stack, which is understood by `inferno`,
+//! `flamegraph.pl` and speedscope.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use openvm_instructions::exe::FnBounds;
+
+use super::guest_backtrace::ShadowCallStack;
+
+/// Name of the call stack for instructions outside of any known function.
+const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// What a collapsed stack profile counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileWeight {
+    /// Number of executed instructions.
+    Cycles,
+    /// Number of trace cells added, summed over all AIRs with a variable trace height.
+    TraceCells,
+}
+
+#[derive(Clone, Debug, Default)]
+struct StackCost {
+    /// Start pcs of the functions on the stack, outermost first.
+    starts: Vec<u32>,
+    cycles: u64,
+    trace_cells: u64,
+}
+
+/// Profiles an execution by guest function. It is shared between the segments of an execution,
+/// see [VmExecutor::set_execution_profiler](super::VmExecutor::set_execution_profiler).
+pub struct GuestProfiler {
+    fn_bounds: FnBounds,
+    call_stack: ShadowCallStack,
+    /// Index in `stacks` of the current call stack, `None` if it changed since it was looked up.
+    current: Option<usize>,
+    stack_ids: HashMap<Vec<u32>, usize>,
+    stacks: Vec<StackCost>,
+    /// Total trace cells of the segment after the previous instruction.
+    prev_trace_cells: usize,
+}
+
+/// A [GuestProfiler] shared between the segments of an execution.
+pub type SharedGuestProfiler = Arc<Mutex<GuestProfiler>>;
+
+impl GuestProfiler {
+    /// Creates a profiler for an executable with the given function bounds. Without function
+    /// bounds, everything is attributed to a single unknown function.
+    pub fn new(fn_bounds: FnBounds) -> Self {
+        Self {
+            fn_bounds,
+            call_stack: Default::default(),
+            current: None,
+            stack_ids: HashMap::new(),
+            stacks: vec![],
+            prev_trace_cells: 0,
+        }
+    }
+
+    /// Called at the start of each segment with the total trace cells of the new segment.
+    pub fn begin_segment(&mut self, trace_cells: usize) {
+        self.prev_trace_cells = trace_cells;
+    }
+
+    /// Records the execution of the instruction at `from_pc`, which continued at `to_pc` and
+    /// brought the total trace cells of the segment to `trace_cells`.
+    pub fn record(&mut self, from_pc: u32, to_pc: u32, trace_cells: usize) {
+        if self.call_stack.enter(&self.fn_bounds, from_pc) {
+            self.current = None;
+        }
+        let current = match self.current {
+            Some(current) => current,
+            None => self.lookup_current(),
+        };
+        let cost = &mut self.stacks[current];
+        cost.cycles += 1;
+        cost.trace_cells += trace_cells.saturating_sub(self.prev_trace_cells) as u64;
+        self.prev_trace_cells = trace_cells;
+
+        if self.call_stack.record(&self.fn_bounds, from_pc, to_pc) {
+            self.current = None;
+        }
+    }
+
+    fn lookup_current(&mut self) -> usize {
+        let starts: Vec<u32> = self
+            .call_stack
+            .frames()
+            .iter()
+            .map(|frame| frame.start)
+            .collect();
+        let stacks = &mut self.stacks;
+        let current = *self.stack_ids.entry(starts).or_insert_with_key(|starts| {
+            stacks.push(StackCost {
+                starts: starts.clone(),
+                ..Default::default()
+            });
+            stacks.len() - 1
+        });
+        self.current = Some(current);
+        current
+    }
+
+    /// Returns the total of `weight` over all call stacks.
+    pub fn total(&self, weight: ProfileWeight) -> u64 {
+        self.stacks.iter().map(|cost| cost.get(weight)).sum()
+    }
+
+    /// Returns the collapsed call stacks with a non-zero `weight`, sorted by call stack. Frames are
+    /// separated by `;`, outermost first.
+    pub fn collapsed_stacks(&self, weight: ProfileWeight) -> BTreeMap<String, u64> {
+        let mut collapsed = BTreeMap::new();
+        for cost in &self.stacks {
+            let value = cost.get(weight);
+            if value == 0 {
+                continue;
+            }
+            *collapsed.entry(self.stack_name(&cost.starts)).or_default() += value;
+        }
+        collapsed
+    }
+
+    /// Writes the profile in the collapsed stack format.
+    pub fn write_collapsed(&self, weight: ProfileWeight, mut writer: impl Write) -> io::Result<()> {
+        for (stack, value) in self.collapsed_stacks(weight) {
+            writeln!(writer, "{stack} {value}")?;
+        }
+        writer.flush()
+    }
+
+    fn stack_name(&self, starts: &[u32]) -> String {
+        if starts.is_empty() {
+            return UNKNOWN_FUNCTION.to_string();
+        }
+        starts
+            .iter()
+            .map(|start| match self.fn_bounds.get(start) {
+                // `;` separates frames and a trailing space the value, so they cannot appear in
+                // names. Demangled Rust names contain neither except in rare generic arguments.
+                Some(bound) => bound.name.replace(';', ":").replace(' ', ""),
+                None => format!("{start:#x}"),
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+impl StackCost {
+    fn get(&self, weight: ProfileWeight) -> u64 {
+        match weight {
+            ProfileWeight::Cycles => self.cycles,
+            ProfileWeight::TraceCells => self.trace_cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::exe::FnBound;
+
+    use super::*;
+
+    fn bound(start: u32, end: u32, name: &str) -> (u32, FnBound) {
+        (
+            start,
+            FnBound {
+                start,
+                end,
+                name: name.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_collapsed_stacks() {
+        let fn_bounds = FnBounds::from_iter([
+            bound(0x100, 0x1fc, "main"),
+            bound(0x200, 0x2fc, "foo"),
+            bound(0x300, 0x3fc, "bar<u8; 4>"),
+        ]);
+        let mut profiler = GuestProfiler::new(fn_bounds);
+        profiler.begin_segment(10);
+        profiler.record(0x100, 0x104, 10);
+        // main calls foo, which calls bar twice
+        profiler.record(0x104, 0x200, 20);
+        profiler.record(0x200, 0x300, 20);
+        profiler.record(0x300, 0x204, 50);
+        profiler.record(0x204, 0x300, 50);
+        profiler.record(0x300, 0x208, 60);
+        // foo returns to main
+        profiler.record(0x208, 0x108, 60);
+        // the next segment starts in main
+        profiler.begin_segment(5);
+        profiler.record(0x108, 0x10c, 8);
+
+        assert_eq!(
+            profiler.collapsed_stacks(ProfileWeight::Cycles),
+            BTreeMap::from_iter([
+                ("main".to_string(), 3),
+                ("main;foo".to_string(), 3),
+                ("main;foo;bar<u8:4>".to_string(), 2),
+            ])
+        );
+        assert_eq!(
+            profiler.collapsed_stacks(ProfileWeight::TraceCells),
+            BTreeMap::from_iter([
+                ("main".to_string(), 13),
+                ("main;foo;bar<u8:4>".to_string(), 40),
+            ])
+        );
+        assert_eq!(profiler.total(ProfileWeight::Cycles), 8);
+
+        let mut collapsed = Vec::new();
+        profiler
+            .write_collapsed(ProfileWeight::Cycles, &mut collapsed)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(collapsed).unwrap(),
+            "main 3\nmain;foo 3\nmain;foo;bar<u8:4> 2\n"
+        );
+    }
+
+    #[test]
+    fn test_without_fn_bounds() {
+        let mut profiler = GuestProfiler::new(FnBounds::new());
+        profiler.record(0x100, 0x104, 0);
+        profiler.record(0x104, 0x200, 0);
+        assert_eq!(
+            profiler.collapsed_stacks(ProfileWeight::Cycles),
+            BTreeMap::from_iter([(UNKNOWN_FUNCTION.to_string(), 2)])
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Recording of executed instructions, to compare an execution against another run or another
+//! OpenVM version.
+//!
+//! A recording starts with a magic number and the format version, followed by one entry per
+//! executed instruction. The name of each opcode is stored once, before its first use, so that
+//! recordings from VM versions with different opcode numbering can still be compared. Integers are
+//! encoded as unsigned LEB128.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Cursor, Read, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use openvm_instructions::instruction::Instruction;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use crate::system::memory::online::MemoryLogEntry;
+
+const MAGIC: &[u8; 8] = b"OVMTRACE";
+pub const RECORDING_VERSION: u32 = 1;
+
+const TAG_INSTRUCTION: u8 = 0;
+const TAG_OPCODE_NAME: u8 = 1;
+
+/// One executed instruction together with its effects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionRecord {
+    pub pc: u32,
+    pub opcode: usize,
+    /// Name of the opcode, if the recording contains it.
+    pub opcode_name: Option<String>,
+    /// The operands `a` to `g` of the instruction.
+    pub operands: [u32; 7],
+    /// Memory writes done by the instruction, in order.
+    pub memory_writes: Vec<MemoryWriteRecord>,
+    /// Values the instruction consumed from the hint stream.
+    pub hint_reads: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryWriteRecord {
+    pub address_space: u32,
+    pub pointer: u32,
+    pub data: Vec<u32>,
+}
+
+impl InstructionRecord {
+    /// Returns the names of the fields in which `self` and `other` differ. Opcodes are compared by
+    /// name when both records have one.
+    pub fn differences(&self, other: &Self) -> Vec<&'static str> {
+        let same_opcode = match (&self.opcode_name, &other.opcode_name) {
+            (Some(name), Some(other_name)) => name == other_name,
+            _ => self.opcode == other.opcode,
+        };
+        [
+            ("pc", self.pc == other.pc),
+            ("opcode", same_opcode),
+            ("operands", self.operands == other.operands),
+            ("memory writes", self.memory_writes == other.memory_writes),
+            ("hint reads", self.hint_reads == other.hint_reads),
+        ]
+        .into_iter()
+        .filter_map(|(field, same)| (!same).then_some(field))
+        .collect()
+    }
+}
+
+impl fmt::Display for InstructionRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pc {:#010x}: ", self.pc)?;
+        match &self.opcode_name {
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "opcode {}", self.opcode)?,
+        }
+        write!(f, " {:?}", self.operands)?;
+        for write in &self.memory_writes {
+            write!(
+                f,
+                "\n    write [{}]{:#x} <- {:?}",
+                write.address_space, write.pointer, write.data
+            )?;
+        }
+        if !self.hint_reads.is_empty() {
+            write!(f, "\n    hint reads {:?}", self.hint_reads)?;
+        }
+        Ok(())
+    }
+}
+
+/// Streams the executed instructions of an execution into a recording. Set it with
+/// [VmExecutor::set_execution_recorder](super::VmExecutor::set_execution_recorder).
+pub struct ExecutionRecorder {
+    writer: Box<dyn Write + Send>,
+    named_opcodes: HashSet<usize>,
+    num_records: u64,
+}
+
+/// An [ExecutionRecorder] shared between the segments of an execution.
+pub type SharedExecutionRecorder = Arc<Mutex<ExecutionRecorder>>;
+
+impl ExecutionRecorder {
+    pub fn new(writer: impl Write + Send + 'static) -> io::Result<Self> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        writer.write_all(MAGIC)?;
+        write_varint(&mut writer, RECORDING_VERSION as u64)?;
+        Ok(Self {
+            writer,
+            named_opcodes: HashSet::new(),
+            num_records: 0,
+        })
+    }
+
+    /// Creates a recorder writing to a new file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Number of instructions recorded so far.
+    pub fn num_records(&self) -> u64 {
+        self.num_records
+    }
+
+    /// Must be called after execution, since the recording may be buffered.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Records the execution of `instruction` at `pc`. `memory_log` holds the memory accesses of
+    /// the instruction, and `hint_reads` the words it popped from the hint stream.
+    pub(crate) fn record<F: PrimeField32>(
+        &mut self,
+        pc: u32,
+        instruction: &Instruction<F>,
+        opcode_name: impl FnOnce() -> String,
+        memory_log: &[MemoryLogEntry<F>],
+        hint_reads: &[F],
+    ) -> io::Result<()> {
+        let opcode = instruction.opcode.as_usize();
+        if self.named_opcodes.insert(opcode) {
+            self.writer.write_all(&[TAG_OPCODE_NAME])?;
+            write_varint(&mut self.writer, opcode as u64)?;
+            let name = opcode_name();
+            write_varint(&mut self.writer, name.len() as u64)?;
+            self.writer.write_all(name.as_bytes())?;
+        }
+
+        let Instruction {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            ..
+        } = instruction;
+        let memory_writes = memory_log
+            .iter()
+            .filter_map(|entry| match entry {
+                MemoryLogEntry::Write {
+                    address_space,
+                    pointer,
+                    data,
+                } => Some(MemoryWriteRecord {
+                    address_space: *address_space,
+                    pointer: *pointer,
+                    data: data.iter().map(|x| x.as_canonical_u32()).collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+        let record = InstructionRecord {
+            pc,
+            opcode,
+            opcode_name: None,
+            operands: [a, b, c, d, e, f, g].map(|x| x.as_canonical_u32()),
+            memory_writes,
+            hint_reads: hint_reads.iter().map(|x| x.as_canonical_u32()).collect(),
+        };
+        self.writer.write_all(&[TAG_INSTRUCTION])?;
+        write_record(&mut self.writer, &record)?;
+        self.num_records += 1;
+        Ok(())
+    }
+}
+
+/// An in-memory recording, which can be read after an [ExecutionRecorder] took ownership of a
+/// clone.
+#[derive(Clone, Default)]
+pub struct SharedRecordingBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedRecordingBuffer {
+    /// Reads the recording written so far.
+    pub fn reader(&self) -> io::Result<ExecutionRecordReader<Cursor<Vec<u8>>>> {
+        ExecutionRecordReader::new(Cursor::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+impl Write for SharedRecordingBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the instructions of a recording made by an [ExecutionRecorder].
+pub struct ExecutionRecordReader<R> {
+    reader: R,
+    opcode_names: HashMap<usize, String>,
+}
+
+impl ExecutionRecordReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> ExecutionRecordReader<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an OpenVM execution recording"));
+        }
+        let version = read_varint(&mut reader)?;
+        if version != RECORDING_VERSION as u64 {
+            return Err(invalid_data(format!(
+                "unsupported recording version {version}, expected {RECORDING_VERSION}"
+            )));
+        }
+        Ok(Self {
+            reader,
+            opcode_names: HashMap::new(),
+        })
+    }
+
+    /// Returns the next recorded instruction, or `None` at the end of the recording.
+    pub fn next_record(&mut self) -> io::Result<Option<InstructionRecord>> {
+        loop {
+            let Some(tag) = self.read_tag()? else {
+                return Ok(None);
+            };
+            match tag {
+                TAG_OPCODE_NAME => {
+                    let opcode = read_varint(&mut self.reader)? as usize;
+                    let name = String::from_utf8(read_bytes(&mut self.reader)?)
+                        .map_err(|_| invalid_data("opcode name is not valid UTF-8"))?;
+                    self.opcode_names.insert(opcode, name);
+                }
+                TAG_INSTRUCTION => {
+                    let mut record = read_record(&mut self.reader)?;
+                    record.opcode_name = self.opcode_names.get(&record.opcode).cloned();
+                    return Ok(Some(record));
+                }
+                _ => return Err(invalid_data(format!("unknown entry tag {tag}"))),
+            }
+        }
+    }
+
+    fn read_tag(&mut self) -> io::Result<Option<u8>> {
+        let mut tag = [0u8];
+        loop {
+            match self.reader.read(&mut tag) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(tag[0])),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for ExecutionRecordReader<R> {
+    type Item = io::Result<InstructionRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+/// The first instruction at which two recordings differ. A missing record means that the
+/// recording ended before.
+#[derive(Clone, Debug)]
+pub struct Divergence {
+    /// Number of instructions executed identically before the divergence.
+    pub index: u64,
+    pub left: Option<InstructionRecord>,
+    pub right: Option<InstructionRecord>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "recordings diverge at instruction {}", self.index)?;
+        if let (Some(left), Some(right)) = (&self.left, &self.right) {
+            write!(f, " (different {})", left.differences(right).join(", "))?;
+        }
+        for (side, record) in [("left", &self.left), ("right", &self.right)] {
+            match record {
+                Some(record) => write!(f, "\n  {side}: {record}")?,
+                None => write!(f, "\n  {side}: <end of recording>")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares two recordings instruction by instruction and returns the first divergence, or
+/// `None` if they are equivalent.
+pub fn first_divergence(
+    left: &mut ExecutionRecordReader<impl Read>,
+    right: &mut ExecutionRecordReader<impl Read>,
+) -> io::Result<Option<Divergence>> {
+    let mut index = 0;
+    loop {
+        match (left.next_record()?, right.next_record()?) {
+            (None, None) => return Ok(None),
+            (Some(l), Some(r)) if l.differences(&r).is_empty() => index += 1,
+            (left, right) => return Ok(Some(Divergence { index, left, right })),
+        }
+    }
+}
+
+fn write_record(writer: &mut impl Write, record: &InstructionRecord) -> io::Result<()> {
+    write_varint(writer, record.pc as u64)?;
+    write_varint(writer, record.opcode as u64)?;
+    for &operand in &record.operands {
+        write_varint(writer, operand as u64)?;
+    }
+    write_varint(writer, record.memory_writes.len() as u64)?;
+    for write in &record.memory_writes {
+        write_varint(writer, write.address_space as u64)?;
+        write_varint(writer, write.pointer as u64)?;
+        write_u32s(writer, &write.data)?;
+    }
+    write_u32s(writer, &record.hint_reads)
+}
+
+fn read_record(reader: &mut impl Read) -> io::Result<InstructionRecord> {
+    let pc = read_u32(reader)?;
+    let opcode = read_varint(reader)? as usize;
+    let mut operands = [0; 7];
+    for operand in &mut operands {
+        *operand = read_u32(reader)?;
+    }
+    let num_writes = read_varint(reader)?;
+    let memory_writes = (0..num_writes)
+        .map(|_| {
+            Ok(MemoryWriteRecord {
+                address_space: read_u32(reader)?,
+                pointer: read_u32(reader)?,
+                data: read_u32s(reader)?,
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    Ok(InstructionRecord {
+        pc,
+        opcode,
+        opcode_name: None,
+        operands,
+        memory_writes,
+        hint_reads: read_u32s(reader)?,
+    })
+}
+
+fn write_varint(writer: &mut impl Write, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint is too long"))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    u32::try_from(read_varint(reader)?).map_err(|_| invalid_data("value does not fit in u32"))
+}
+
+fn write_u32s(writer: &mut impl Write, values: &[u32]) -> io::Result<()> {
+    write_varint(writer, values.len() as u64)?;
+    values
+        .iter()
+        .try_for_each(|&value| write_varint(writer, value as u64))
+}
+
+fn read_u32s(reader: &mut impl Read) -> io::Result<Vec<u32>> {
+    let len = read_varint(reader)?;
+    (0..len).map(|_| read_u32(reader)).collect()
+}
+
+fn read_bytes(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = read_varint(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_instructions::VmOpcode;
+    use openvm_stark_backend::p3_field::FieldAlgebra;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn instruction(opcode: usize, a: u32) -> Instruction<F> {
+        Instruction::from_usize(VmOpcode::from_usize(opcode), [a as usize, 1, 2])
+    }
+
+    /// Records `(opcode, a, written value, hint reads)` per instruction, naming opcode `n` as
+    /// `OP{n}`.
+    fn record(
+        instructions: &[(usize, u32, u32, &[u32])],
+    ) -> ExecutionRecordReader<Cursor<Vec<u8>>> {
+        let buffer = SharedRecordingBuffer::default();
+        let mut recorder = ExecutionRecorder::new(buffer.clone()).unwrap();
+        for (i, &(opcode, a, value, hint_reads)) in instructions.iter().enumerate() {
+            let log = [
+                MemoryLogEntry::Read {
+                    address_space: 1,
+                    pointer: 0,
+                    len: 4,
+                },
+                MemoryLogEntry::Write {
+                    address_space: 1,
+                    pointer: 4 * a,
+                    data: vec![F::from_canonical_u32(value)],
+                },
+            ];
+            recorder
+                .record(
+                    4 * i as u32,
+                    &instruction(opcode, a),
+                    || format!("OP{opcode}"),
+                    &log,
+                    &hint_reads
+                        .iter()
+                        .map(|&x| F::from_canonical_u32(x))
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap();
+        }
+        assert_eq!(recorder.num_records(), instructions.len() as u64);
+        buffer.reader().unwrap()
+    }
+
+    #[test]
+    fn test_recording_roundtrip() {
+        let mut reader = record(&[(7, 1, 300, &[]), (9, 2, 0, &[5, 6]), (7, 3, 1, &[7])]);
+        let records: Vec<_> = reader.by_ref().collect::<io::Result<_>>().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].pc, 0);
+        assert_eq!(records[0].opcode_name.as_deref(), Some("OP7"));
+        assert_eq!(records[0].operands, [1, 1, 2, 0, 0, 0, 0]);
+        assert_eq!(
+            records[0].memory_writes,
+            [MemoryWriteRecord {
+                address_space: 1,
+                pointer: 4,
+                data: vec![300]
+            }]
+        );
+        assert_eq!(records[0].hint_reads, Vec::<u32>::new());
+        assert_eq!(records[1].hint_reads, [5, 6]);
+        assert_eq!(records[2].opcode_name.as_deref(), Some("OP7"));
+        assert_eq!(records[2].hint_reads, [7]);
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let instructions: [(usize, u32, u32, &[u32]); 3] =
+            [(7, 1, 1, &[]), (9, 2, 2, &[]), (7, 3, 3, &[])];
+        assert!(
+            first_divergence(&mut record(&instructions), &mut record(&instructions))
+                .unwrap()
+                .is_none()
+        );
+
+        let mut changed = instructions;
+        changed[1].2 = 5;
+        let divergence = first_divergence(&mut record(&instructions), &mut record(&changed))
+            .unwrap()
+            .unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(
+            divergence
+                .left
+                .unwrap()
+                .differences(&divergence.right.unwrap()),
+            ["memory writes"]
+        );
+
+        let divergence =
+            first_divergence(&mut record(&instructions), &mut record(&instructions[..2]))
+                .unwrap()
+                .unwrap();
+        assert_eq!(divergence.index, 2);
+        assert!(divergence.right.is_none());
+    }
+
+    #[test]
+    fn test_invalid_recording() {
+        assert!(ExecutionRecordReader::new(&b"OVMTRACE\x02"[..]).is_err());
+        assert!(ExecutionRecordReader::new(&b"not a recording"[..]).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use backtrace::Backtrace;
 use openvm_instructions::{
@@ -17,14 +17,15 @@ use openvm_stark_backend::{
 };
 
 use super::{
-    ExecutionError, GenerationError, Streams, SystemBase, SystemConfig, VmChipComplex,
-    VmComplexTraceHeights, VmConfig,
+    profiler::SharedGuestProfiler, recording::SharedExecutionRecorder, ExecutedInstruction,
+    ExecutionError, GenerationError, GuestBacktrace, GuestBacktraceTracker, SharedExecutionHook,
+    Streams, SystemBase, SystemConfig, VmChipComplex, VmComplexTraceHeights, VmConfig,
 };
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
 use crate::{
     arch::{instructions::*, ExecutionState, InstructionExecutor},
-    system::memory::MemoryImage,
+    system::memory::{online::MemoryLogEntry, MemoryImage, Rv32MemoryView},
 };
 
 /// Check segment every 100 instructions.
@@ -152,11 +153,28 @@ where
 
     /// Air names for debug purposes only.
     pub(crate) air_names: Vec<String>,
+    /// Execution history for guest backtraces on failure.
+    pub(crate) backtrace: GuestBacktraceTracker,
+    /// Called before each instruction, if set.
+    pub execution_hook: Option<SharedExecutionHook<F>>,
+    /// Records each executed instruction, if set.
+    pub execution_recorder: Option<SharedExecutionRecorder>,
+    /// Profiles each executed instruction by guest function, if set.
+    pub execution_profiler: Option<SharedGuestProfiler>,
     /// Metrics collected for this execution segment alone.
     #[cfg(feature = "bench-metrics")]
     pub metrics: VmMetrics,
 }
 
+/// Trace height and main trace width of one AIR in a segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AirTraceShape {
+    pub air_name: String,
+    /// Trace height before padding to a power of two.
+    pub height: usize,
+    pub width: usize,
+}
+
 pub struct ExecutionSegmentState {
     pub pc: u32,
     pub is_terminated: bool,
@@ -170,7 +188,7 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         init_streams: Streams<F>,
         initial_memory: Option<MemoryImage<F>>,
         trace_height_constraints: Vec<LinearConstraint>,
-        #[allow(unused_variables)] fn_bounds: FnBounds,
+        fn_bounds: FnBounds,
     ) -> Self {
         let mut chip_complex = config.create_chip_complex().unwrap();
         chip_complex.set_streams(init_streams);
@@ -185,11 +203,17 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
             chip_complex.set_initial_memory(initial_memory);
         }
         let air_names = chip_complex.air_names();
+        let backtrace =
+            GuestBacktraceTracker::new(fn_bounds.clone(), config.system().backtrace_len);
 
         Self {
             chip_complex,
             final_memory: None,
             air_names,
+            backtrace,
+            execution_hook: None,
+            execution_recorder: None,
+            execution_profiler: None,
             trace_height_constraints,
             #[cfg(feature = "bench-metrics")]
             metrics: VmMetrics {
@@ -222,14 +246,33 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         self.chip_complex
             .connector_chip_mut()
             .begin(ExecutionState::new(pc, timestamp));
+        if let Some(profiler) = &self.execution_profiler {
+            let trace_cells = self.chip_complex.current_trace_cells().into_iter().sum();
+            profiler.lock().unwrap().begin_segment(trace_cells);
+        }
 
+        if self.execution_recorder.is_some() {
+            let mut streams = self.chip_complex.streams.lock().unwrap();
+            streams.hint_reads.get_or_insert_with(Vec::new);
+        }
         let mut did_terminate = false;
 
         loop {
+            let from_pc = pc;
+            if let Some(hook) = &self.execution_hook {
+                let memory = self.chip_complex.base.memory_controller.memory_image();
+                hook.lock()
+                    .unwrap()
+                    .before_instruction(pc, memory)
+                    .map_err(|inner| ExecutionError::Hook { pc, inner })?;
+            }
+
             #[allow(unused_variables)]
             let (opcode, dsl_instr) = {
                 let Self {
                     chip_complex,
+                    backtrace,
+                    execution_recorder,
                     #[cfg(feature = "bench-metrics")]
                     metrics,
                     ..
@@ -252,10 +295,30 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                      }| (Some(dsl_instruction), trace.as_ref()),
                 );
 
-                let &Instruction { opcode, c, .. } = instruction;
+                let &Instruction {
+                    opcode, a, b, c, ..
+                } = instruction;
+                let memory_log_start = memory_controller.get_memory_logs().len();
                 if opcode == SystemOpcode::TERMINATE.global_opcode() {
+                    if let Some(recorder) = execution_recorder {
+                        record_instruction(
+                            recorder,
+                            &chip_complex.streams,
+                            pc,
+                            instruction,
+                            || format!("{:?}", SystemOpcode::TERMINATE),
+                            &[],
+                        )?;
+                    }
                     did_terminate = true;
-                    self.chip_complex.connector_chip_mut().end(
+                    self.profile_instruction(pc, pc);
+                    let SystemBase {
+                        connector_chip,
+                        memory_controller,
+                        ..
+                    } = &mut self.chip_complex.base;
+                    connector_chip.end(
+                        memory_controller,
                         ExecutionState::new(pc, timestamp),
                         Some(c.as_canonical_u32()),
                     );
@@ -269,6 +332,19 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                     let discriminant = c.as_canonical_u32() as u16;
                     let phantom = SysPhantom::from_repr(discriminant);
                     tracing::trace!("pc: {pc:#x} | system phantom: {phantom:?}");
+                    // These stop the execution, so they are recorded here.
+                    if let (Some(SysPhantom::DebugPanic | SysPhantom::Trap), Some(recorder)) =
+                        (phantom, execution_recorder.as_ref())
+                    {
+                        record_instruction(
+                            recorder,
+                            &chip_complex.streams,
+                            pc,
+                            instruction,
+                            || format!("{:?}", SystemOpcode::PHANTOM),
+                            &[],
+                        )?;
+                    }
                     match phantom {
                         Some(SysPhantom::DebugPanic) => {
                             if let Some(mut backtrace) = prev_backtrace {
@@ -277,7 +353,22 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                             } else {
                                 eprintln!("openvm program failure; no backtrace");
                             }
-                            return Err(ExecutionError::Fail { pc });
+                            return Err(ExecutionError::Fail {
+                                pc,
+                                backtrace: self.guest_backtrace(pc),
+                            });
+                        }
+                        Some(SysPhantom::Trap) => {
+                            let cause = (c.as_canonical_u32() >> 16) as u16;
+                            let Some(cause) = TrapCause::from_repr(cause) else {
+                                return Err(ExecutionError::InvalidTrapCause { pc, cause });
+                            };
+                            return Err(ExecutionError::Trap {
+                                pc,
+                                cause,
+                                instruction: a.as_canonical_u32() | (b.as_canonical_u32() << 16),
+                                backtrace: self.guest_backtrace(pc),
+                            });
                         }
                         Some(SysPhantom::CtStart) =>
                         {
@@ -299,13 +390,37 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 prev_backtrace = trace.cloned();
 
                 if let Some(executor) = chip_complex.inventory.get_mut_executor(&opcode) {
-                    let next_state = InstructionExecutor::execute(
+                    let result = InstructionExecutor::execute(
                         executor,
                         memory_controller,
                         instruction,
                         ExecutionState::new(pc, timestamp),
-                    )?;
+                    );
+                    // Instructions failing with a guest error are recorded too.
+                    if let (
+                        Ok(_) | Err(ExecutionError::Fail { .. } | ExecutionError::Trap { .. }),
+                        Some(recorder),
+                    ) = (&result, execution_recorder.as_ref())
+                    {
+                        record_instruction(
+                            recorder,
+                            &chip_complex.streams,
+                            pc,
+                            instruction,
+                            || executor.get_opcode_name(opcode.as_usize()),
+                            &memory_controller.get_memory_logs()[memory_log_start..],
+                        )?;
+                    }
+                    let next_state = match result {
+                        Ok(next_state) => next_state,
+                        // Executors cannot see the execution history, so attach it here.
+                        Err(err @ (ExecutionError::Fail { .. } | ExecutionError::Trap { .. })) => {
+                            return Err(err.with_guest_backtrace(self.guest_backtrace(pc)));
+                        }
+                        Err(err) => return Err(err),
+                    };
                     assert!(next_state.timestamp > timestamp);
+                    backtrace.record(pc, next_state.pc);
                     pc = next_state.pc;
                     timestamp = next_state.timestamp;
                 } else {
@@ -314,13 +429,17 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
                 (opcode, dsl_instr.cloned())
             };
 
+            self.profile_instruction(from_pc, pc);
             #[cfg(feature = "bench-metrics")]
             self.update_instruction_metrics(pc, opcode, dsl_instr);
 
             if self.should_segment() {
-                self.chip_complex
-                    .connector_chip_mut()
-                    .end(ExecutionState::new(pc, timestamp), None);
+                let SystemBase {
+                    connector_chip,
+                    memory_controller,
+                    ..
+                } = &mut self.chip_complex.base;
+                connector_chip.end(memory_controller, ExecutionState::new(pc, timestamp), None);
                 break;
             }
         }
@@ -381,7 +500,118 @@ impl<F: PrimeField32, VC: VmConfig<F>> ExecutionSegment<F, VC> {
         )
     }
 
+    fn profile_instruction(&self, from_pc: u32, to_pc: u32) {
+        if let Some(profiler) = &self.execution_profiler {
+            let trace_cells = self.chip_complex.current_trace_cells().into_iter().sum();
+            profiler.lock().unwrap().record(from_pc, to_pc, trace_cells);
+        }
+    }
+
+    /// Finalizes the memory and checks the trace heights as [Self::generate_proof_input] would,
+    /// without generating the traces. Should be called after ::execute.
+    pub fn check_trace_heights(&mut self) -> Result<(), GenerationError> {
+        self.chip_complex.finalize_memory();
+        self.chip_complex
+            .check_trace_heights(&self.trace_height_constraints)
+    }
+
     pub fn current_trace_cells(&self) -> Vec<usize> {
         self.chip_complex.current_trace_cells()
     }
+
+    /// Finalizes the memory and returns the trace shape of every AIR in the order of AIR ids, as
+    /// used by trace generation. Should be called after ::execute.
+    pub fn air_trace_shapes(&mut self) -> Vec<AirTraceShape> {
+        self.chip_complex.finalize_memory();
+        let heights = self.chip_complex.current_trace_heights();
+        let widths = self.chip_complex.trace_widths();
+        self.air_names
+            .iter()
+            .zip(heights)
+            .zip(widths)
+            .map(|((air_name, height), width)| AirTraceShape {
+                air_name: air_name.clone(),
+                height,
+                width,
+            })
+            .collect()
+    }
+
+    /// Captures the guest call stack, the most recently executed instructions and the registers
+    /// for a failure at `pc`. Returns `None` if guest backtraces are disabled by
+    /// [SystemConfig::backtrace_len].
+    pub fn guest_backtrace(&self, pc: u32) -> Option<Box<GuestBacktrace>> {
+        if !self.backtrace.is_enabled() {
+            return None;
+        }
+        let SystemBase {
+            program_chip,
+            memory_controller,
+            ..
+        } = &self.chip_complex.base;
+        let recent_instructions = self
+            .backtrace
+            .recent_pcs()
+            .chain(std::iter::once(pc))
+            .filter_map(|pc| {
+                let (instruction, debug_info) = program_chip.instruction_at(pc)?;
+                let opcode = instruction.opcode;
+                let opcode_name = self
+                    .chip_complex
+                    .inventory
+                    .get_executor(opcode)
+                    .map_or_else(
+                        || opcode.to_string(),
+                        |executor| executor.get_opcode_name(opcode.as_usize()),
+                    );
+                let Instruction {
+                    a,
+                    b,
+                    c,
+                    d,
+                    e,
+                    f,
+                    g,
+                    ..
+                } = instruction;
+                Some(ExecutedInstruction {
+                    pc,
+                    opcode: opcode_name,
+                    operands: [a, b, c, d, e, f, g].map(|operand| operand.as_canonical_u32()),
+                    dsl_instruction: debug_info
+                        .as_ref()
+                        .map(|debug_info| debug_info.dsl_instruction.clone()),
+                })
+            })
+            .collect();
+        let registers = Rv32MemoryView::new(memory_controller.memory_image()).registers();
+        Some(Box::new(GuestBacktrace {
+            frames: self.backtrace.frames(pc),
+            recent_instructions,
+            registers,
+        }))
+    }
+}
+
+/// Records `instruction` together with the hint words it popped since the previous record.
+fn record_instruction<F: PrimeField32>(
+    recorder: &SharedExecutionRecorder,
+    streams: &Mutex<Streams<F>>,
+    pc: u32,
+    instruction: &Instruction<F>,
+    opcode_name: impl FnOnce() -> String,
+    memory_log: &[MemoryLogEntry<F>],
+) -> Result<(), ExecutionError> {
+    let hint_reads = streams
+        .lock()
+        .unwrap()
+        .hint_reads
+        .as_mut()
+        .map(std::mem::take)
+        .unwrap_or_default();
+    recorder
+        .lock()
+        .unwrap()
+        .record(pc, instruction, opcode_name, memory_log, &hint_reads)
+        .map_err(|source| ExecutionError::Recording { pc, source })
 }
@@ -1,6 +1,7 @@
 use std::{
     borrow::Borrow,
     collections::{HashMap, VecDeque},
+    io,
     marker::PhantomData,
     mem,
     sync::Arc,
@@ -25,8 +26,9 @@ use thiserror::Error;
 use tracing::info_span;
 
 use super::{
-    ExecutionError, VmComplexTraceHeights, VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID,
-    PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX,
+    profiler::SharedGuestProfiler, recording::SharedExecutionRecorder, ExecutionError,
+    GuestBacktraceState, InputStream, KvStore, SharedExecutionHook, VmComplexTraceHeights,
+    VmConfig, CONNECTOR_AIR_ID, MERKLE_AIR_ID, PROGRAM_AIR_ID, PROGRAM_CACHED_TRACE_INDEX,
 };
 #[cfg(feature = "bench-metrics")]
 use crate::metrics::VmMetrics;
@@ -55,35 +57,41 @@ pub enum GenerationError {
 /// VM memory state for continuations.
 pub type VmMemoryState<F> = MemoryImage<F>;
 
-/// A trait for key-value store for `Streams`.
-pub trait KvStore: Send + Sync {
-    fn get(&self, key: &[u8]) -> Option<&[u8]>;
-}
-
-impl KvStore for HashMap<Vec<u8>, Vec<u8>> {
-    fn get(&self, key: &[u8]) -> Option<&[u8]> {
-        self.get(key).map(|v| v.as_slice())
-    }
-}
-
 #[derive(Clone)]
 pub struct Streams<F> {
-    pub input_stream: VecDeque<Vec<F>>,
+    pub input_stream: InputStream<F>,
     pub hint_stream: VecDeque<F>,
     pub hint_space: Vec<Vec<F>>,
     /// The key-value store for hints. Both key and value are byte arrays. Executors which
     /// read `kv_store` need to encode the key and decode the value.
     pub kv_store: Arc<dyn KvStore>,
+    /// Words popped with [pop_hint](Self::pop_hint), if set. The execution sets it to log the
+    /// hint reads of each instruction for an
+    /// [ExecutionRecorder](super::recording::ExecutionRecorder).
+    pub hint_reads: Option<Vec<F>>,
 }
 
 impl<F> Streams<F> {
     pub fn new(input_stream: impl Into<VecDeque<Vec<F>>>) -> Self {
         Self {
-            input_stream: input_stream.into(),
+            input_stream: InputStream::new(input_stream),
             hint_stream: VecDeque::default(),
             hint_space: Vec::default(),
             kv_store: Arc::new(HashMap::new()),
+            hint_reads: None,
+        }
+    }
+}
+
+impl<F: Copy> Streams<F> {
+    /// Pops the next word of the hint stream. Executors should consume hints through this method,
+    /// so that they are logged in `hint_reads`.
+    pub fn pop_hint(&mut self) -> Option<F> {
+        let word = self.hint_stream.pop_front()?;
+        if let Some(hint_reads) = &mut self.hint_reads {
+            hint_reads.push(word);
         }
+        Some(word)
     }
 }
 
@@ -109,6 +117,12 @@ pub struct VmExecutor<F, VC> {
     pub config: VC,
     pub overridden_heights: Option<VmComplexTraceHeights>,
     pub trace_height_constraints: Vec<LinearConstraint>,
+    /// Hook called before each executed instruction, e.g. by a debugger.
+    pub execution_hook: Option<SharedExecutionHook<F>>,
+    /// Records the executed instructions, if set.
+    pub execution_recorder: Option<SharedExecutionRecorder>,
+    /// Profiles the executed instructions by guest function, if set.
+    pub execution_profiler: Option<SharedGuestProfiler>,
     _marker: PhantomData<F>,
 }
 
@@ -129,6 +143,8 @@ pub struct VmExecutorNextSegmentState<F: PrimeField32> {
     pub memory: MemoryImage<F>,
     pub input: Streams<F>,
     pub pc: u32,
+    /// Execution history for guest backtraces, carried over from the previous segment.
+    pub backtrace: GuestBacktraceState,
     #[cfg(feature = "bench-metrics")]
     pub metrics: VmMetrics,
 }
@@ -139,10 +155,54 @@ impl<F: PrimeField32> VmExecutorNextSegmentState<F> {
             memory,
             input: input.into(),
             pc,
+            backtrace: Default::default(),
             #[cfg(feature = "bench-metrics")]
             metrics: VmMetrics::default(),
         }
     }
+
+    /// Returns the part of the state which is needed to resume execution in another process.
+    pub fn checkpoint(&self) -> VmSegmentCheckpoint<F> {
+        VmSegmentCheckpoint {
+            memory: self.memory.clone(),
+            pc: self.pc,
+            input_buffer: self.input.input_stream.buffered().cloned().collect(),
+            provider_inputs_read: self.input.input_stream.provider_inputs_read(),
+            hint_stream: self.input.hint_stream.iter().copied().collect(),
+            hint_space: self.input.hint_space.clone(),
+        }
+    }
+
+    /// Restores the state saved by [Self::checkpoint]. `input` must be the input of the
+    /// execution the checkpoint was taken from: its input provider is advanced past the inputs
+    /// which were already read and its key-value store is kept. Guest backtraces only cover the
+    /// execution after the checkpoint.
+    pub fn from_checkpoint(
+        checkpoint: VmSegmentCheckpoint<F>,
+        input: impl Into<Streams<F>>,
+    ) -> io::Result<Self> {
+        let mut input = input.into();
+        input
+            .input_stream
+            .restore(checkpoint.input_buffer, checkpoint.provider_inputs_read)?;
+        input.hint_stream = checkpoint.hint_stream.into();
+        input.hint_space = checkpoint.hint_space;
+        Ok(Self::new(checkpoint.memory, input, checkpoint.pc))
+    }
+}
+
+/// The state of the VM at the start of a segment, without the parts which cannot be serialized:
+/// the input provider, the key-value store and the guest backtrace.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VmSegmentCheckpoint<F> {
+    pub memory: MemoryImage<F>,
+    pub pc: u32,
+    /// Inputs in memory which have not been read yet.
+    pub input_buffer: Vec<Vec<F>>,
+    /// Number of inputs read from the input provider.
+    pub provider_inputs_read: u64,
+    pub hint_stream: Vec<F>,
+    pub hint_space: Vec<Vec<F>>,
 }
 
 pub struct VmExecutorOneSegmentResult<F: PrimeField32, VC: VmConfig<F>> {
@@ -166,6 +226,22 @@ where
         self.overridden_heights = Some(overridden_heights);
     }
 
+    pub fn set_execution_hook(&mut self, hook: SharedExecutionHook<F>) {
+        self.execution_hook = Some(hook);
+    }
+
+    /// Records every executed instruction with `recorder`. The recorder must be flushed after
+    /// execution.
+    pub fn set_execution_recorder(&mut self, recorder: SharedExecutionRecorder) {
+        self.execution_recorder = Some(recorder);
+    }
+
+    /// Attributes every executed instruction and the trace cells it added to the enclosing guest
+    /// functions with `profiler`.
+    pub fn set_execution_profiler(&mut self, profiler: SharedGuestProfiler) {
+        self.execution_profiler = Some(profiler);
+    }
+
     pub fn new_with_overridden_trace_heights(
         config: VC,
         overridden_heights: Option<VmComplexTraceHeights>,
@@ -174,6 +250,9 @@ where
             config,
             overridden_heights,
             trace_height_constraints: vec![],
+            execution_hook: None,
+            execution_recorder: None,
+            execution_profiler: None,
             _marker: Default::default(),
         }
     }
@@ -194,23 +273,9 @@ where
         mut f: impl FnMut(usize, ExecutionSegment<F, VC>) -> Result<R, E>,
         map_err: impl Fn(ExecutionError) -> E,
     ) -> Result<Vec<R>, E> {
-        let mem_config = self.config.system().memory_config;
         let exe = exe.into();
         let mut segment_results = vec![];
-        let memory = AddressMap::from_iter(
-            mem_config.as_offset,
-            1 << mem_config.as_height,
-            1 << mem_config.pointer_max_bits,
-            exe.init_memory.clone(),
-        );
-        let pc = exe.pc_start;
-        let mut state = VmExecutorNextSegmentState::new(memory, input, pc);
-
-        #[cfg(feature = "bench-metrics")]
-        {
-            state.metrics.fn_bounds = exe.fn_bounds.clone();
-        }
-
+        let mut state = self.initial_state(&exe, input);
         let mut segment_idx = 0;
 
         loop {
@@ -232,6 +297,28 @@ where
         Ok(segment_results)
     }
 
+    /// Returns the state to execute the first segment of `exe` from.
+    pub fn initial_state(
+        &self,
+        exe: &VmExe<F>,
+        input: impl Into<Streams<F>>,
+    ) -> VmExecutorNextSegmentState<F> {
+        let mem_config = self.config.system().memory_config;
+        let memory = AddressMap::from_iter(
+            mem_config.as_offset,
+            1 << mem_config.as_height,
+            1 << mem_config.pointer_max_bits,
+            exe.init_memory.clone(),
+        );
+        #[allow(unused_mut)]
+        let mut state = VmExecutorNextSegmentState::new(memory, input, exe.pc_start);
+        #[cfg(feature = "bench-metrics")]
+        {
+            state.metrics.fn_bounds = exe.fn_bounds.clone();
+        }
+        state
+    }
+
     pub fn execute_segments(
         &self,
         exe: impl Into<VmExe<F>>,
@@ -258,6 +345,10 @@ where
             self.trace_height_constraints.clone(),
             exe.fn_bounds.clone(),
         );
+        segment.backtrace.state = from_state.backtrace;
+        segment.execution_hook = self.execution_hook.clone();
+        segment.execution_recorder = self.execution_recorder.clone();
+        segment.execution_profiler = self.execution_profiler.clone();
         #[cfg(feature = "bench-metrics")]
         {
             segment.metrics = from_state.metrics;
@@ -295,6 +386,7 @@ where
                 memory: final_memory,
                 input: streams,
                 pc: state.pc,
+                backtrace: mem::take(&mut segment.backtrace.state),
                 #[cfg(feature = "bench-metrics")]
                 metrics,
             }),
@@ -317,16 +409,18 @@ where
             |err| err,
         )?;
         let last = last.expect("at least one segment must be executed");
-        let final_memory = last.final_memory;
         let end_state =
             last.chip_complex.connector_chip().boundary_states[1].expect("end state must be set");
         if end_state.is_terminate != 1 {
             return Err(ExecutionError::DidNotTerminate);
         }
         if end_state.exit_code != ExitCode::Success as u32 {
-            return Err(ExecutionError::FailedWithExitCode(end_state.exit_code));
+            return Err(ExecutionError::FailedWithExitCode {
+                exit_code: end_state.exit_code,
+                backtrace: last.guest_backtrace(end_state.pc),
+            });
         }
-        Ok(final_memory)
+        Ok(last.final_memory)
     }
 
     pub fn execute_and_generate<SC: StarkGenericConfig>(
@@ -1,14 +1,20 @@
 use std::{
+    array,
     borrow::{Borrow, BorrowMut},
-    marker::PhantomData,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use openvm_circuit_primitives::var_range::{
     SharedVariableRangeCheckerChip, VariableRangeCheckerBus,
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
-use openvm_instructions::LocalOpcode;
+use openvm_instructions::{
+    riscv::{
+        RV32_CELL_BITS, RV32_CYCLE_BASE_NUM_LIMBS, RV32_CYCLE_BASE_PTR, RV32_REGISTER_AS,
+        RV32_REGISTER_NUM_LIMBS,
+    },
+    LocalOpcode,
+};
 use openvm_stark_backend::{
     config::{StarkGenericConfig, Val},
     interaction::InteractionBuilder,
@@ -23,7 +29,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     arch::{instructions::SystemOpcode::TERMINATE, ExecutionBus, ExecutionState},
-    system::program::ProgramBus,
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryWriteAuxCols},
+            MemoryAddress, MemoryController, OfflineMemory, RecordId,
+        },
+        program::ProgramBus,
+    },
 };
 
 #[cfg(test)]
@@ -38,6 +50,9 @@ pub struct VmConnectorAir {
     pub execution_bus: ExecutionBus,
     pub program_bus: ProgramBus,
     pub range_bus: VariableRangeCheckerBus,
+    /// If set, the end row adds the number of cycles of the segment to the cycle base at
+    /// \[RV32_CYCLE_BASE_PTR:8\]_1, so that counter CSRs continue across segments.
+    pub cycle_base_bridge: Option<MemoryBridge>,
     /// The final timestamp will be constrained to be in the range [0, 2^timestamp_max_bits).
     timestamp_max_bits: usize,
 }
@@ -79,7 +94,7 @@ impl<F: Field> BaseAirWithPublicValues<F> for VmConnectorAir {
 impl<F: Field> PartitionedBaseAir<F> for VmConnectorAir {}
 impl<F: Field> BaseAir<F> for VmConnectorAir {
     fn width(&self) -> usize {
-        5
+        self.trace_width()
     }
 
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
@@ -88,6 +103,18 @@ impl<F: Field> BaseAir<F> for VmConnectorAir {
 }
 
 impl VmConnectorAir {
+    fn trace_width(&self) -> usize {
+        ConnectorCols::<u8>::width()
+            + self
+                .cycle_base_bridge
+                .map_or(0, |_| CycleBaseCols::<u8>::width())
+    }
+
+    /// Returns the number of bits of the most significant limb of `timestamp - 1`.
+    fn cycles_high_limb_bits(&self) -> usize {
+        self.timestamp_max_bits - (RV32_REGISTER_NUM_LIMBS - 1) * RV32_CELL_BITS
+    }
+
     /// Returns (low_bits, high_bits) to range check.
     fn timestamp_limb_bits(&self) -> (usize, usize) {
         let range_max_bits = self.range_bus.range_max_bits;
@@ -132,6 +159,17 @@ impl<T: Copy> ConnectorCols<T> {
     }
 }
 
+/// Columns updating the cycle base at the end of a segment. Unused on the begin row.
+#[derive(Debug, Copy, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct CycleBaseCols<T> {
+    /// Little-endian limbs of `timestamp - 1`, the number of cycles of the segment.
+    pub cycles: [T; RV32_REGISTER_NUM_LIMBS],
+    pub new_base: [T; RV32_CYCLE_BASE_NUM_LIMBS],
+    /// The previous cycle base is `write_aux.prev_data`.
+    pub write_aux: MemoryWriteAuxCols<T, RV32_CYCLE_BASE_NUM_LIMBS>,
+}
+
 impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB> for VmConnectorAir {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
@@ -139,8 +177,10 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
         let prep_local = preprocessed.row_slice(0);
         let (begin, end) = (main.row_slice(0), main.row_slice(1));
 
-        let begin: &ConnectorCols<AB::Var> = (*begin).borrow();
-        let end: &ConnectorCols<AB::Var> = (*end).borrow();
+        let (begin, _) = begin.split_at(ConnectorCols::<AB::Var>::width());
+        let (end, end_cycle_base) = end.split_at(ConnectorCols::<AB::Var>::width());
+        let begin: &ConnectorCols<AB::Var> = begin.borrow();
+        let end: &ConnectorCols<AB::Var> = end.borrow();
 
         let &VmConnectorPvs {
             initial_pc,
@@ -175,6 +215,66 @@ impl<AB: InteractionBuilder + PairBuilder + AirBuilderWithPublicValues> Air<AB>
             (AB::Expr::ONE - prep_local[0]) * end.is_terminate,
         );
 
+        if let Some(memory_bridge) = self.cycle_base_bridge {
+            let cols: &CycleBaseCols<AB::Var> = end_cycle_base.borrow();
+            let is_end = AB::Expr::ONE - prep_local[0];
+
+            // `cycles` is range checked such that it composes to a value less than
+            // 2^timestamp_max_bits, so it is uniquely determined by the end timestamp.
+            let cycles = cols
+                .cycles
+                .iter()
+                .enumerate()
+                .fold(AB::Expr::ZERO, |acc, (i, &limb)| {
+                    acc + limb * AB::Expr::from_canonical_u32(1 << (i * RV32_CELL_BITS))
+                });
+            builder
+                .when_transition()
+                .assert_eq(cycles, end.timestamp - AB::Expr::ONE);
+
+            // new_base = prev_base + cycles modulo 2^64, with boolean carries as in the RV32 ADD.
+            let carry_divide = AB::F::from_canonical_usize(1 << RV32_CELL_BITS).inverse();
+            let prev_base = cols.write_aux.prev_data();
+            let mut carry = AB::Expr::ZERO;
+            for i in 0..RV32_CYCLE_BASE_NUM_LIMBS {
+                let cycles_limb = cols
+                    .cycles
+                    .get(i)
+                    .map_or(AB::Expr::ZERO, |&limb| limb.into());
+                carry = AB::Expr::from(carry_divide)
+                    * (prev_base[i] + cycles_limb + carry - cols.new_base[i]);
+                builder.when_transition().assert_bool(carry.clone());
+            }
+
+            for (i, &limb) in cols.cycles.iter().enumerate() {
+                let bits = if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                    self.cycles_high_limb_bits()
+                } else {
+                    RV32_CELL_BITS
+                };
+                self.range_bus
+                    .range_check(limb, bits)
+                    .eval(builder, is_end.clone());
+            }
+            for limb in cols.new_base {
+                self.range_bus
+                    .range_check(limb, RV32_CELL_BITS)
+                    .eval(builder, is_end.clone());
+            }
+
+            memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                        AB::F::from_canonical_u32(RV32_CYCLE_BASE_PTR),
+                    ),
+                    cols.new_base,
+                    end.timestamp,
+                    &cols.write_aux,
+                )
+                .eval(builder, is_end);
+        }
+
         // The following constraints hold on every row, so we rename `begin` to `local` to avoid
         // confusion.
         let local = begin;
@@ -197,7 +297,8 @@ pub struct VmConnectorChip<F> {
     pub air: VmConnectorAir,
     pub range_checker: SharedVariableRangeCheckerChip,
     pub boundary_states: [Option<ConnectorCols<u32>>; 2],
-    _marker: PhantomData<F>,
+    offline_memory: Option<Arc<Mutex<OfflineMemory<F>>>>,
+    cycle_base_write: Option<RecordId>,
 }
 
 impl<F: PrimeField32> VmConnectorChip<F> {
@@ -218,14 +319,33 @@ impl<F: PrimeField32> VmConnectorChip<F> {
                 execution_bus,
                 program_bus,
                 range_bus: range_checker.bus(),
+                cycle_base_bridge: None,
                 timestamp_max_bits,
             },
             range_checker,
             boundary_states: [None, None],
-            _marker: PhantomData,
+            offline_memory: None,
+            cycle_base_write: None,
         }
     }
 
+    /// Adds the number of cycles of each segment to the cycle base in memory at the end of the
+    /// segment.
+    pub fn with_cycle_base(
+        mut self,
+        memory_bridge: MemoryBridge,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        assert!(
+            self.air.timestamp_max_bits > (RV32_REGISTER_NUM_LIMBS - 1) * RV32_CELL_BITS
+                && self.air.timestamp_max_bits <= RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS
+        );
+        assert!(self.range_checker.range_max_bits() >= RV32_CELL_BITS);
+        self.air.cycle_base_bridge = Some(memory_bridge);
+        self.offline_memory = Some(offline_memory);
+        self
+    }
+
     pub fn begin(&mut self, state: ExecutionState<u32>) {
         self.boundary_states[0] = Some(ConnectorCols {
             pc: state.pc,
@@ -236,7 +356,28 @@ impl<F: PrimeField32> VmConnectorChip<F> {
         });
     }
 
-    pub fn end(&mut self, state: ExecutionState<u32>, exit_code: Option<u32>) {
+    pub fn end(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        state: ExecutionState<u32>,
+        exit_code: Option<u32>,
+    ) {
+        if self.air.cycle_base_bridge.is_some() {
+            debug_assert_eq!(memory.timestamp(), state.timestamp);
+            let address_space = F::from_canonical_u32(RV32_REGISTER_AS);
+            let pointer = F::from_canonical_u32(RV32_CYCLE_BASE_PTR);
+            let prev_base = memory.unsafe_read::<RV32_CYCLE_BASE_NUM_LIMBS>(address_space, pointer);
+            let prev_base = prev_base.iter().rev().fold(0u64, |acc, limb| {
+                (acc << RV32_CELL_BITS) | limb.as_canonical_u32() as u64
+            });
+            let new_base = prev_base.wrapping_add((state.timestamp - 1) as u64);
+            let (record_id, _) = memory.write(
+                address_space,
+                pointer,
+                new_base.to_le_bytes().map(F::from_canonical_u8),
+            );
+            self.cycle_base_write = Some(record_id);
+        }
         self.boundary_states[1] = Some(ConnectorCols {
             pc: state.pc,
             timestamp: state.timestamp,
@@ -271,10 +412,38 @@ where
             state.map(Val::<SC>::from_canonical_u32)
         });
 
-        let trace = RowMajorMatrix::new(
-            [initial_state.flatten(), final_state.flatten()].concat(),
-            self.trace_width(),
-        );
+        let width = self.air.trace_width();
+        let mut trace = Val::<SC>::zero_vec(2 * width);
+        trace[..ConnectorCols::<u8>::width()].copy_from_slice(&initial_state.flatten());
+        trace[width..width + ConnectorCols::<u8>::width()].copy_from_slice(&final_state.flatten());
+        if let Some(offline_memory) = &self.offline_memory {
+            let offline_memory = offline_memory.lock().unwrap();
+            let record = offline_memory.record_by_id(self.cycle_base_write.unwrap());
+            let cols: &mut CycleBaseCols<Val<SC>> =
+                trace[width + ConnectorCols::<u8>::width()..].borrow_mut();
+
+            let cycles = final_state.timestamp.as_canonical_u32() - 1;
+            let cycles_limbs: [u32; RV32_REGISTER_NUM_LIMBS] =
+                array::from_fn(|i| (cycles >> (i * RV32_CELL_BITS)) & ((1 << RV32_CELL_BITS) - 1));
+            for (i, &limb) in cycles_limbs.iter().enumerate() {
+                let bits = if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                    self.air.cycles_high_limb_bits()
+                } else {
+                    RV32_CELL_BITS
+                };
+                self.range_checker.add_count(limb, bits);
+            }
+            cols.cycles = cycles_limbs.map(Val::<SC>::from_canonical_u32);
+            cols.new_base = array::from_fn(|i| record.data_slice()[i]);
+            for limb in cols.new_base {
+                self.range_checker
+                    .add_count(limb.as_canonical_u32(), RV32_CELL_BITS);
+            }
+            offline_memory
+                .aux_cols_factory()
+                .generate_write_aux(record, &mut cols.write_aux);
+        }
+        let trace = RowMajorMatrix::new(trace, width);
 
         let mut public_values = Val::<SC>::zero_vec(VmConnectorPvs::<Val<SC>>::width());
         *public_values.as_mut_slice().borrow_mut() = VmConnectorPvs {
@@ -301,6 +470,6 @@ impl<F: PrimeField32> ChipUsageGetter for VmConnectorChip<F> {
     }
 
     fn trace_width(&self) -> usize {
-        5
+        self.air.trace_width()
     }
 }
@@ -98,7 +98,6 @@ impl<F> AccessAdapterInventory<F> {
             .map(|chip| chip.current_trace_height())
             .collect()
     }
-    #[allow(dead_code)]
     pub fn get_widths(&self) -> Vec<usize> {
         self.chips.iter().map(|chip| chip.trace_width()).collect()
     }
@@ -695,6 +695,23 @@ impl<F: PrimeField32> MemoryController<F> {
         ret
     }
 
+    pub fn trace_widths(&self) -> Vec<usize> {
+        let mut ret = Vec::new();
+        match &self.interface_chip {
+            MemoryInterface::Volatile { boundary_chip } => ret.push(boundary_chip.trace_width()),
+            MemoryInterface::Persistent {
+                boundary_chip,
+                merkle_chip,
+                ..
+            } => {
+                ret.push(boundary_chip.trace_width());
+                ret.push(merkle_chip.trace_width());
+            }
+        }
+        ret.extend(self.access_adapters.get_widths());
+        ret
+    }
+
     /// Returns a reference to the offline memory.
     ///
     /// Until `finalize` is called, the `OfflineMemory` does not contain useful state, and should
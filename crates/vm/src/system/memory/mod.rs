@@ -11,11 +11,13 @@ mod persistent;
 #[cfg(test)]
 mod tests;
 pub mod tree;
+mod view;
 mod volatile;
 
 pub use controller::*;
 pub use offline::*;
 pub use paged_vec::*;
+pub use view::*;
 
 #[derive(PartialEq, Copy, Clone, Debug, Eq)]
 pub enum OpType {
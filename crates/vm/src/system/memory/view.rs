@@ -0,0 +1,193 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use openvm_instructions::riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::p3_field::PrimeField32;
+
+use super::{MemoryImage, PagedVec, PAGE_SIZE};
+
+/// Read-only, byte-addressed view over the RV32 address spaces of a [MemoryImage].
+///
+/// Each cell of the RV32 register and memory address spaces holds one byte. Reads of cells that
+/// were never touched return `0`.
+///
+/// # Panics
+/// Reads panic if an address is outside of the address space, as configured by
+/// `MemoryConfig::pointer_max_bits`.
+#[derive(Clone, Copy)]
+pub struct Rv32MemoryView<'a, F> {
+    image: &'a MemoryImage<F>,
+    symbols: Option<&'a BTreeMap<String, u32>>,
+}
+
+impl<'a, F: PrimeField32> Rv32MemoryView<'a, F> {
+    pub fn new(image: &'a MemoryImage<F>) -> Self {
+        Self {
+            image,
+            symbols: None,
+        }
+    }
+
+    /// Attaches a symbol table, such as `Elf::symbols`, to enable lookups by symbol name.
+    pub fn with_symbols(mut self, symbols: &'a BTreeMap<String, u32>) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    pub fn read_u8(&self, addr: u32) -> u8 {
+        self.image
+            .get(&(RV32_MEMORY_AS, addr))
+            .map_or(0, |byte| byte.as_canonical_u32() as u8)
+    }
+
+    /// Reads a little-endian `u32`. The address does not need to be aligned.
+    pub fn read_u32(&self, addr: u32) -> u32 {
+        u32::from_le_bytes(self.read_array(addr))
+    }
+
+    pub fn read_array<const N: usize>(&self, addr: u32) -> [u8; N] {
+        self.image
+            .get_range::<N>(&(RV32_MEMORY_AS, addr))
+            .map(|byte| byte.as_canonical_u32() as u8)
+    }
+
+    pub fn read_bytes(&self, addr: u32, len: usize) -> Vec<u8> {
+        if len == 0 {
+            return vec![];
+        }
+        let start = addr as usize;
+        self.paged_vec()
+            .range_vec(start..start + len)
+            .into_iter()
+            .map(|byte| byte.as_canonical_u32() as u8)
+            .collect()
+    }
+
+    pub fn read_range(&self, range: Range<u32>) -> Vec<u8> {
+        self.read_bytes(range.start, range.len())
+    }
+
+    /// Number of bytes in the RV32 memory address space. Reads must stay below this bound.
+    pub fn memory_len(&self) -> usize {
+        self.paged_vec().pages.len() * PAGE_SIZE
+    }
+
+    /// Reads `num_words` consecutive little-endian `u32` words starting at `addr`.
+    pub fn read_words(&self, addr: u32, num_words: usize) -> Vec<u32> {
+        self.read_bytes(addr, num_words * 4)
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Reads the value of register `x{index}`.
+    pub fn read_register(&self, index: usize) -> u32 {
+        let bytes = self
+            .image
+            .get_range::<RV32_REGISTER_NUM_LIMBS>(&(
+                RV32_REGISTER_AS,
+                (index * RV32_REGISTER_NUM_LIMBS) as u32,
+            ))
+            .map(|byte| byte.as_canonical_u32() as u8);
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Reads all 32 registers, with `x0` first.
+    pub fn registers(&self) -> [u32; 32] {
+        std::array::from_fn(|i| self.read_register(i))
+    }
+
+    /// Iterates over the address ranges of the pages of RV32 memory that were touched, in
+    /// increasing order.
+    pub fn touched_pages(&self) -> impl Iterator<Item = Range<u32>> + 'a {
+        self.paged_vec()
+            .pages
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.is_some())
+            .map(|(i, _)| {
+                let start = (i * PAGE_SIZE) as u32;
+                start..start + PAGE_SIZE as u32
+            })
+    }
+
+    /// Returns the address of `name` in the attached symbol table.
+    pub fn symbol(&self, name: &str) -> Option<u32> {
+        self.symbols?.get(name).copied()
+    }
+
+    pub fn read_symbol_u32(&self, name: &str) -> Option<u32> {
+        self.symbol(name).map(|addr| self.read_u32(addr))
+    }
+
+    /// Reads the bytes in `[begin, end)`, where `begin` and `end` are symbol names.
+    pub fn read_symbol_range(&self, begin: &str, end: &str) -> Option<Vec<u8>> {
+        let (begin, end) = (self.symbol(begin)?, self.symbol(end)?);
+        (begin <= end).then(|| self.read_range(begin..end))
+    }
+
+    fn paged_vec(&self) -> &'a PagedVec<F, PAGE_SIZE> {
+        let image = self.image;
+        &image.paged_vecs[(RV32_MEMORY_AS - image.as_offset) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_backend::p3_field::FieldAlgebra;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+    use super::*;
+    use crate::arch::MemoryConfig;
+
+    type F = BabyBear;
+
+    fn image_with_bytes(addr: u32, bytes: &[u8]) -> MemoryImage<F> {
+        let mut image = MemoryImage::from_mem_config(&MemoryConfig::default());
+        for (i, &byte) in bytes.iter().enumerate() {
+            image.insert(
+                &(RV32_MEMORY_AS, addr + i as u32),
+                F::from_canonical_u8(byte),
+            );
+        }
+        image
+    }
+
+    #[test]
+    fn test_read_bytes_and_words() {
+        let image = image_with_bytes(0x1ffe, &[0x78, 0x56, 0x34, 0x12, 0xff]);
+        let view = Rv32MemoryView::new(&image);
+        assert_eq!(view.read_u8(0x2002), 0xff);
+        assert_eq!(view.read_u32(0x1ffe), 0x12345678);
+        assert_eq!(view.read_bytes(0x1ffc, 4), [0, 0, 0x78, 0x56]);
+        assert_eq!(view.read_words(0x1ffe, 2), [0x12345678, 0xff]);
+        assert_eq!(view.read_range(0x2000..0x2000), Vec::<u8>::new());
+        // Untouched memory reads as zero.
+        assert_eq!(view.read_u32(0x8000), 0);
+    }
+
+    #[test]
+    fn test_touched_pages() {
+        let image = image_with_bytes(PAGE_SIZE as u32 - 1, &[1, 2]);
+        let view = Rv32MemoryView::new(&image);
+        let pages: Vec<_> = view.touched_pages().collect();
+        assert_eq!(
+            pages,
+            [0..PAGE_SIZE as u32, PAGE_SIZE as u32..2 * PAGE_SIZE as u32]
+        );
+    }
+
+    #[test]
+    fn test_symbols() {
+        let image = image_with_bytes(0x100, &[1, 0, 0, 0, 2, 0, 0, 0]);
+        let symbols = BTreeMap::from([("begin".to_string(), 0x100), ("end".to_string(), 0x108)]);
+        let view = Rv32MemoryView::new(&image).with_symbols(&symbols);
+        assert_eq!(view.read_symbol_u32("end"), Some(0));
+        assert_eq!(view.read_symbol_u32("missing"), None);
+        assert_eq!(
+            view.read_symbol_range("begin", "end"),
+            Some(vec![1, 0, 0, 0, 2, 0, 0, 0])
+        );
+        assert_eq!(view.read_symbol_range("end", "begin"), None);
+        assert_eq!(Rv32MemoryView::new(&image).symbol("begin"), None);
+    }
+}
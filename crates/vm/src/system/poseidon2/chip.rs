@@ -1,6 +1,6 @@
 use std::{
     array,
-    sync::{atomic::AtomicU32, Arc},
+    sync::{atomic::AtomicU32, Arc, Mutex},
 };
 
 use openvm_poseidon2_air::{Poseidon2Config, Poseidon2SubChip};
@@ -20,6 +20,9 @@ pub struct Poseidon2PeripheryBaseChip<F: PrimeField32, const SBOX_REGISTERS: usi
     pub air: Arc<Poseidon2PeripheryAir<F, SBOX_REGISTERS>>,
     pub subchip: Poseidon2SubChip<F, SBOX_REGISTERS>,
     pub records: FxHashMap<[F; PERIPHERY_POSEIDON2_WIDTH], AtomicU32>,
+    /// Compressions requested by other chips through a [SharedPoseidon2PeripheryChip]. These are
+    /// merged into `records` during trace generation.
+    pub shared: SharedPoseidon2PeripheryChip<F>,
 }
 
 impl<F: PrimeField32, const SBOX_REGISTERS: usize> Poseidon2PeripheryBaseChip<F, SBOX_REGISTERS> {
@@ -32,10 +35,53 @@ impl<F: PrimeField32, const SBOX_REGISTERS: usize> Poseidon2PeripheryBaseChip<F,
             )),
             subchip,
             records: FxHashMap::default(),
+            shared: SharedPoseidon2PeripheryChip::new(poseidon2_config, bus_idx),
         }
     }
 }
 
+/// A cloneable handle to a Poseidon2 periphery chip, for executors which look up `compress`
+/// on the periphery bus. Each [compress_and_record](Self::compress_and_record) adds one to the
+/// multiplicity of the corresponding row of the periphery trace.
+#[derive(Clone, Debug)]
+pub struct SharedPoseidon2PeripheryChip<F: PrimeField32> {
+    bus: LookupBus,
+    subchip: Arc<Poseidon2SubChip<F, 0>>,
+    pub(super) records: Arc<Mutex<FxHashMap<[F; PERIPHERY_POSEIDON2_WIDTH], u32>>>,
+}
+
+impl<F: PrimeField32> SharedPoseidon2PeripheryChip<F> {
+    fn new(poseidon2_config: Poseidon2Config<F>, bus_idx: BusIndex) -> Self {
+        Self {
+            bus: LookupBus::new(bus_idx),
+            subchip: Arc::new(Poseidon2SubChip::new(poseidon2_config.constants)),
+            records: Default::default(),
+        }
+    }
+
+    /// The bus on which the periphery chip receives `lhs || rhs || compress(lhs, rhs)`.
+    pub fn bus(&self) -> LookupBus {
+        self.bus
+    }
+
+    /// Compresses `lhs` and `rhs` and records the lookup so that the periphery chip adds a
+    /// matching row to its trace.
+    pub fn compress_and_record(
+        &self,
+        lhs: &[F; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+        rhs: &[F; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+    ) -> [F; PERIPHERY_POSEIDON2_CHUNK_SIZE] {
+        let mut input = [F::ZERO; PERIPHERY_POSEIDON2_WIDTH];
+        input[..PERIPHERY_POSEIDON2_CHUNK_SIZE].copy_from_slice(lhs);
+        input[PERIPHERY_POSEIDON2_CHUNK_SIZE..].copy_from_slice(rhs);
+
+        *self.records.lock().unwrap().entry(input).or_insert(0) += 1;
+
+        let output = self.subchip.permute(input);
+        array::from_fn(|i| output[i])
+    }
+}
+
 impl<F: PrimeField32, const SBOX_REGISTERS: usize> Hasher<PERIPHERY_POSEIDON2_CHUNK_SIZE, F>
     for Poseidon2PeripheryBaseChip<F, SBOX_REGISTERS>
 {
@@ -47,6 +47,14 @@ impl<F: PrimeField32> Poseidon2PeripheryChip<F> {
             Self::Register1(Poseidon2PeripheryBaseChip::new(poseidon2_config, bus_idx))
         }
     }
+
+    /// Returns a handle through which other chips can request compressions from this chip.
+    pub fn shared(&self) -> SharedPoseidon2PeripheryChip<F> {
+        match self {
+            Poseidon2PeripheryChip::Register0(chip) => chip.shared.clone(),
+            Poseidon2PeripheryChip::Register1(chip) => chip.shared.clone(),
+        }
+    }
 }
 
 impl<SC: StarkGenericConfig> Chip<SC> for Poseidon2PeripheryChip<Val<SC>>
@@ -1,5 +1,8 @@
 use openvm_poseidon2_air::Poseidon2Config;
-use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    ChipUsageGetter,
+};
 use openvm_stark_sdk::{
     dummy_airs::interaction::dummy_interaction_air::{DummyInteractionChip, DummyInteractionData},
     p3_baby_bear::BabyBear,
@@ -127,3 +130,76 @@ fn poseidon2_periphery_duplicate_hashes_test() {
         .load(dummy_interaction_chip)
         .finalize();
 }
+
+/// Test that compressions requested through the shared handle are merged with the ones recorded
+/// directly, including when both record the same input.
+#[test]
+fn poseidon2_periphery_shared_test() {
+    let mut rng = create_seeded_rng();
+    const NUM_OPS: usize = 50;
+    let hashes: [(
+        [BabyBear; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+        [BabyBear; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+    ); NUM_OPS] = std::array::from_fn(|_| {
+        (
+            std::array::from_fn(|_| BabyBear::from_canonical_u32(rng.next_u32() % (1 << 30))),
+            std::array::from_fn(|_| BabyBear::from_canonical_u32(rng.next_u32() % (1 << 30))),
+        )
+    });
+
+    let mut chip = Poseidon2PeripheryChip::<BabyBear>::new(
+        Poseidon2Config::default(),
+        POSEIDON2_DIRECT_BUS,
+        3,
+    );
+    let shared = chip.shared();
+
+    // Even inputs are recorded directly, inputs divisible by 3 through the shared handle.
+    let mut counts = vec![0; NUM_OPS];
+    let outs: [[BabyBear; PERIPHERY_POSEIDON2_CHUNK_SIZE]; NUM_OPS] = std::array::from_fn(|i| {
+        if i % 2 == 0 {
+            chip.compress_and_record(&hashes[i].0, &hashes[i].1);
+            counts[i] += 1;
+        }
+        if i % 3 == 0 {
+            let out = shared.compress_and_record(&hashes[i].0, &hashes[i].1);
+            assert_eq!(out, chip.compress(&hashes[i].0, &hashes[i].1));
+            counts[i] += 1;
+        }
+        chip.compress(&hashes[i].0, &hashes[i].1)
+    });
+    assert_eq!(
+        chip.current_trace_height(),
+        counts.iter().filter(|&&count| count > 0).count()
+    );
+
+    let mut dummy_interaction_chip = DummyInteractionChip::new_without_partition(
+        PERIPHERY_POSEIDON2_WIDTH + PERIPHERY_POSEIDON2_WIDTH / 2,
+        true,
+        POSEIDON2_DIRECT_BUS,
+    );
+    let fields = hashes
+        .iter()
+        .zip(outs)
+        .map(|((hash1, hash2), out)| {
+            hash1
+                .iter()
+                .chain(hash2.iter())
+                .chain(out.iter())
+                .map(|y| y.as_canonical_u32())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    dummy_interaction_chip.load_data(DummyInteractionData {
+        count: counts,
+        fields,
+    });
+
+    let tester = VmChipTestBuilder::default();
+    let tester = tester
+        .build()
+        .load(dummy_interaction_chip)
+        .load(chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
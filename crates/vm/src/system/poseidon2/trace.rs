@@ -1,4 +1,7 @@
-use std::borrow::BorrowMut;
+use std::{
+    borrow::BorrowMut,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use openvm_circuit_primitives::utils::next_power_of_two_or_zero;
 use openvm_stark_backend::{
@@ -27,12 +30,19 @@ where
         let height = next_power_of_two_or_zero(self.current_trace_height());
         let width = self.trace_width();
 
+        let mut records = self.records;
+        for (input, count) in std::mem::take(&mut *self.shared.records.lock().unwrap()) {
+            records
+                .entry(input)
+                .or_insert(AtomicU32::new(0))
+                .fetch_add(count, Ordering::Relaxed);
+        }
+
         let mut inputs = Vec::with_capacity(height);
         let mut multiplicities = Vec::with_capacity(height);
-        let (actual_inputs, actual_multiplicities): (Vec<_>, Vec<_>) = self
-            .records
+        let (actual_inputs, actual_multiplicities): (Vec<_>, Vec<_>) = records
             .into_par_iter()
-            .map(|(input, mult)| (input, mult.load(std::sync::atomic::Ordering::Relaxed)))
+            .map(|(input, mult)| (input, mult.load(Ordering::Relaxed)))
             .unzip();
         inputs.extend(actual_inputs);
         multiplicities.extend(actual_multiplicities);
@@ -67,7 +77,12 @@ impl<F: PrimeField32, const SBOX_REGISTERS: usize> ChipUsageGetter
     }
 
     fn current_trace_height(&self) -> usize {
+        let shared_records = self.shared.records.lock().unwrap();
         self.records.len()
+            + shared_records
+                .keys()
+                .filter(|input| !self.records.contains_key(*input))
+                .count()
     }
 
     fn trace_width(&self) -> usize {
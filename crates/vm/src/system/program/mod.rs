@@ -84,6 +84,12 @@ impl<F: PrimeField64> ProgramChip<F> {
                 program_len: self.program.len(),
             })
     }
+
+    /// Looks up the instruction at `pc` without counting it as executed.
+    pub fn instruction_at(&self, pc: u32) -> Option<&(Instruction<F>, Option<DebugInfo>)> {
+        let pc_index = self.get_pc_index(pc).ok()?;
+        self.program.get_instruction_and_debug_info(pc_index)
+    }
 }
 
 impl<F: PrimeField64> ChipUsageGetter for ProgramChip<F> {
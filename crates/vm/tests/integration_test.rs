@@ -1,15 +1,19 @@
 use std::{
     collections::{BTreeMap, VecDeque},
+    io::{self, Cursor},
     iter::zip,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use openvm_circuit::{
     arch::{
         hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
-        ChipId, ExecutionSegment, MemoryConfig, SingleSegmentVmExecutor, SystemConfig,
-        SystemTraceHeights, VirtualMachine, VmComplexTraceHeights, VmConfig,
-        VmInventoryTraceHeights,
+        recording::{
+            first_divergence, ExecutionRecordReader, ExecutionRecorder, SharedRecordingBuffer,
+        },
+        ChipId, ExecutionError, ExecutionSegment, MemoryConfig, SingleSegmentVmExecutor,
+        SystemConfig, SystemTraceHeights, VirtualMachine, VmComplexTraceHeights, VmConfig,
+        VmExecutor, VmInventoryTraceHeights, DEFAULT_BACKTRACE_LEN,
     },
     system::{
         memory::{MemoryTraceHeights, VolatileMemoryTraceHeights, CHUNK},
@@ -18,13 +22,14 @@ use openvm_circuit::{
     utils::{air_test, air_test_with_min_segments},
 };
 use openvm_instructions::{
-    exe::VmExe,
+    exe::{FnBound, VmExe},
     instruction::Instruction,
     program::{Program, DEFAULT_PC_STEP},
     LocalOpcode, PhantomDiscriminant,
     PublishOpcode::PUBLISH,
     SysPhantom,
     SystemOpcode::*,
+    TrapCause,
 };
 use openvm_native_circuit::NativeConfig;
 use openvm_native_compiler::{
@@ -33,7 +38,7 @@ use openvm_native_compiler::{
 };
 use openvm_rv32im_transpiler::BranchEqualOpcode::*;
 use openvm_stark_backend::{
-    config::StarkGenericConfig, engine::StarkEngine, p3_field::FieldAlgebra,
+    config::StarkGenericConfig, engine::StarkEngine, p3_field::FieldAlgebra, p3_matrix::Matrix,
 };
 use openvm_stark_sdk::{
     config::{
@@ -330,6 +335,153 @@ fn test_vm_initial_memory() {
     air_test(config, exe);
 }
 
+#[test]
+fn test_vm_trap() {
+    type F = BabyBear;
+    let ecall = 0x00000073;
+    let instructions = vec![
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::Nop as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        ),
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::Trap as u16),
+            F::from_canonical_u32(ecall & 0xffff),
+            F::from_canonical_u32(ecall >> 16),
+            TrapCause::EnvironmentCall as u16,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+
+    let program = Program::from_instructions(&instructions);
+    let mut segment = ExecutionSegment::new(
+        &test_native_config(),
+        program,
+        vec![].into(),
+        None,
+        vec![],
+        Default::default(),
+    );
+    match segment.execute_from_pc(0) {
+        Err(ExecutionError::Trap {
+            pc,
+            cause,
+            instruction,
+            backtrace,
+        }) => {
+            assert!(backtrace.is_none());
+            assert_eq!(pc, DEFAULT_PC_STEP);
+            assert_eq!(cause, TrapCause::EnvironmentCall);
+            assert_eq!(instruction, ecall);
+        }
+        Err(err) => panic!("expected trap, got {err:?}"),
+        Ok(_) => panic!("expected trap"),
+    }
+}
+
+#[test]
+fn test_vm_trap_invalid_cause() {
+    type F = BabyBear;
+    let instructions = vec![
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::Trap as u16),
+            F::ZERO,
+            F::ZERO,
+            0xff,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+
+    let program = Program::from_instructions(&instructions);
+    let mut segment = ExecutionSegment::new(
+        &test_native_config(),
+        program,
+        vec![].into(),
+        None,
+        vec![],
+        Default::default(),
+    );
+    assert!(matches!(
+        segment.execute_from_pc(0),
+        Err(ExecutionError::InvalidTrapCause { pc: 0, cause: 0xff })
+    ));
+}
+
+#[test]
+fn test_vm_fail_guest_backtrace() {
+    type F = BabyBear;
+    // `main` calls `fail` at pc 8, which panics.
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, 7, 0, 4, 0, 0, 0),
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::Nop as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        ),
+        Instruction::from_isize(
+            NativeBranchEqualOpcode(BEQ).global_opcode(),
+            0,
+            0,
+            2 * DEFAULT_PC_STEP as isize,
+            4,
+            4,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+        Instruction::phantom(
+            PhantomDiscriminant(SysPhantom::DebugPanic as u16),
+            F::ZERO,
+            F::ZERO,
+            0,
+        ),
+    ];
+    let fn_bounds = [("main", 0, 3), ("fail", 4, 4)]
+        .into_iter()
+        .map(|(name, start, end)| {
+            let bound = FnBound {
+                start: start * DEFAULT_PC_STEP,
+                end: end * DEFAULT_PC_STEP,
+                name: name.to_string(),
+            };
+            (bound.start, bound)
+        })
+        .collect();
+
+    let program = Program::from_instructions(&instructions);
+    let mut config = test_native_config();
+    config.system = config.system.with_backtrace_len(DEFAULT_BACKTRACE_LEN);
+    let mut segment =
+        ExecutionSegment::new(&config, program, vec![].into(), None, vec![], fn_bounds);
+    match segment.execute_from_pc(0) {
+        Err(ExecutionError::Fail {
+            pc,
+            backtrace: Some(guest_backtrace),
+        }) => {
+            assert_eq!(pc, 4 * DEFAULT_PC_STEP);
+            let frames: Vec<_> = guest_backtrace
+                .frames
+                .iter()
+                .map(|frame| (frame.pc, frame.function.as_deref()))
+                .collect();
+            assert_eq!(
+                frames,
+                [(pc, Some("fail")), (2 * DEFAULT_PC_STEP, Some("main"))]
+            );
+            let pcs: Vec<_> = guest_backtrace
+                .recent_instructions
+                .iter()
+                .map(|instruction| instruction.pc)
+                .collect();
+            assert_eq!(pcs, [0, DEFAULT_PC_STEP, 2 * DEFAULT_PC_STEP, pc]);
+            assert_eq!(guest_backtrace.recent_instructions[0].operands[1], 7);
+        }
+        Err(err) => panic!("expected failure with a guest backtrace, got {err:?}"),
+        Ok(_) => panic!("expected failure"),
+    }
+}
+
 #[test]
 fn test_vm_1_persistent() {
     let engine = BabyBearPoseidon2Engine::new(FriParameters::standard_fast());
@@ -393,6 +545,53 @@ fn test_vm_1_persistent() {
         .expect("Verification failed");
 }
 
+#[test]
+fn test_vm_air_trace_shapes() {
+    let config = test_native_continuations_config();
+    let n = 6;
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 0, n, 0, 4, 0, 0, 0),
+        Instruction::large_from_isize(SUB.global_opcode(), 0, 0, 1, 4, 4, 0, 0),
+        Instruction::from_isize(
+            NativeBranchEqualOpcode(BNE).global_opcode(),
+            0,
+            0,
+            -(DEFAULT_PC_STEP as isize),
+            4,
+            0,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let executor = VmExecutor::<BabyBear, _>::new(config);
+    let mut segment = executor
+        .execute_segments(program, vec![])
+        .unwrap()
+        .pop()
+        .unwrap();
+    let shapes = segment.air_trace_shapes();
+    let proof_input = segment
+        .generate_proof_input::<BabyBearPoseidon2Config>(None)
+        .unwrap();
+
+    let proven_air_ids: Vec<_> = proof_input.per_air.iter().map(|(id, _)| *id).collect();
+    for (air_id, shape) in shapes.iter().enumerate() {
+        assert_eq!(
+            proven_air_ids.contains(&air_id),
+            shape.height > 0,
+            "{}",
+            shape.air_name
+        );
+    }
+    for (air_id, input) in &proof_input.per_air {
+        if let Some(main) = input.raw.common_main.as_ref() {
+            assert_eq!(main.height(), shapes[*air_id].height.next_power_of_two());
+            assert_eq!(main.width(), shapes[*air_id].width);
+        }
+    }
+}
+
 #[test]
 fn test_vm_without_field_arithmetic() {
     /*
@@ -698,6 +897,106 @@ fn test_vm_hint() {
     air_test_with_min_segments(config, program, input_stream, 1);
 }
 
+/// Records a program that stores the length of its input hint into memory.
+fn record_hint_program(input: Vec<BabyBear>) -> ExecutionRecordReader<Cursor<Vec<u8>>> {
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 32, 100, 0, 4, 0, 0, 0),
+        Instruction::from_isize(
+            PHANTOM.global_opcode(),
+            0,
+            0,
+            NativePhantom::HintInput as isize,
+            0,
+            0,
+        ),
+        Instruction::from_isize(HINT_STOREW.global_opcode(), 32, 0, 0, 4, 4),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let buffer = SharedRecordingBuffer::default();
+    let recorder = Arc::new(Mutex::new(ExecutionRecorder::new(buffer.clone()).unwrap()));
+    let mut executor = VmExecutor::new(test_native_config());
+    executor.set_execution_recorder(recorder.clone());
+    executor.execute(program, vec![input]).unwrap();
+    recorder.lock().unwrap().flush().unwrap();
+    buffer.reader().unwrap()
+}
+
+#[test]
+fn test_vm_execution_recording() {
+    type F = BabyBear;
+    let records: Vec<_> = record_hint_program(vec![F::from_canonical_u32(7)])
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(records.len(), 4);
+    assert_eq!(
+        records.iter().map(|record| record.pc).collect::<Vec<_>>(),
+        [0, 1, 2, 3].map(|i| i * DEFAULT_PC_STEP)
+    );
+    // The length prefix of the input is read from the hint stream and written to memory.
+    assert_eq!(records[2].hint_reads, [1]);
+    assert!(records[2]
+        .memory_writes
+        .iter()
+        .any(|write| write.data == [1]));
+    assert_eq!(records[3].opcode_name.as_deref(), Some("TERMINATE"));
+
+    let divergence = first_divergence(
+        &mut record_hint_program(vec![F::ONE]),
+        &mut record_hint_program(vec![F::ONE, F::TWO]),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(divergence.index, 2);
+    assert_eq!(
+        divergence
+            .left
+            .unwrap()
+            .differences(&divergence.right.unwrap()),
+        ["memory writes", "hint reads"]
+    );
+    assert!(first_divergence(
+        &mut record_hint_program(vec![F::ONE]),
+        &mut record_hint_program(vec![F::TWO]),
+    )
+    .unwrap()
+    .is_none());
+}
+
+#[test]
+fn test_vm_execution_recording_failure() {
+    let instructions = vec![
+        Instruction::large_from_isize(ADD.global_opcode(), 32, 100, 0, 4, 0, 0, 0),
+        Instruction::from_isize(
+            PHANTOM.global_opcode(),
+            0,
+            0,
+            SysPhantom::DebugPanic as isize,
+            0,
+            0,
+        ),
+        Instruction::from_isize(TERMINATE.global_opcode(), 0, 0, 0, 0, 0),
+    ];
+    let program = Program::from_instructions(&instructions);
+
+    let buffer = SharedRecordingBuffer::default();
+    let recorder = Arc::new(Mutex::new(ExecutionRecorder::new(buffer.clone()).unwrap()));
+    let mut executor = VmExecutor::new(test_native_config());
+    executor.set_execution_recorder(recorder.clone());
+    assert!(matches!(
+        executor.execute(program, Vec::<Vec<BabyBear>>::new()),
+        Err(ExecutionError::Fail { .. })
+    ));
+    recorder.lock().unwrap().flush().unwrap();
+
+    // The failing instruction is the last one recorded.
+    let records: Vec<_> = buffer.reader().unwrap().collect::<io::Result<_>>().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].pc, DEFAULT_PC_STEP);
+    assert_eq!(records[1].opcode_name.as_deref(), Some("PHANTOM"));
+}
+
 #[test]
 fn test_hint_load_1() {
     type F = BabyBear;
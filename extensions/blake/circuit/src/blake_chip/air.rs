@@ -0,0 +1,536 @@
+use std::{array::from_fn, borrow::Borrow, marker::PhantomData};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{offline_checker::MemoryBridge, MemoryAddress},
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::BitwiseOperationLookupBus, utils::assert_array_eq,
+};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra},
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+
+use super::{
+    blake_output_words, blake_timestamp_delta, columns::*, BlakeConfig, BLAKE_ACCESS_SIZE,
+    BLAKE_CV_WORDS, BLAKE_G_INDICES, BLAKE_IV, BLAKE_PARAMS_U8S, BLAKE_PARAMS_WORDS,
+    BLAKE_STATE_WORDS, BLAKE_WORD_U8S,
+};
+
+/// A word as little-endian bytes
+type Word<T> = [T; BLAKE_WORD_U8S];
+
+/// AIR for a BLAKE compression function given by `C`, see [super::BlakeVmChip].
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct BlakeVmAir<C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send XOR and 8-bit range check requests to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+    #[new(default)]
+    _phantom: PhantomData<C>,
+}
+
+impl<F, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> BaseAirWithPublicValues<F>
+    for BlakeVmAir<C, ROUNDS, NUM_WRITES>
+{
+}
+impl<F, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> PartitionedBaseAir<F>
+    for BlakeVmAir<C, ROUNDS, NUM_WRITES>
+{
+}
+impl<F, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> BaseAir<F>
+    for BlakeVmAir<C, ROUNDS, NUM_WRITES>
+{
+    fn width(&self) -> usize {
+        num_blake_vm_cols::<ROUNDS, NUM_WRITES>()
+    }
+}
+
+impl<AB: InteractionBuilder, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> Air<AB>
+    for BlakeVmAir<C, ROUNDS, NUM_WRITES>
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES> = (*local).borrow();
+        let next: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES> = (*next).borrow();
+
+        self.eval_round_flags(builder, local, next);
+        let is_enabled = sum_flags::<AB>(&local.round_flags);
+        let final_state = self.eval_round(builder, local, is_enabled);
+        self.constrain_consistency_across_rounds(builder, local, next, &final_state);
+        self.eval_initial_state(builder, local);
+
+        // Interactions:
+        let start_read_timestamp = self.eval_instruction(builder, local);
+        let start_write_timestamp = self.constrain_reads(builder, local, start_read_timestamp);
+        self.constrain_output(builder, local, &final_state, start_write_timestamp);
+    }
+}
+
+impl<C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize>
+    BlakeVmAir<C, ROUNDS, NUM_WRITES>
+{
+    /// Every compression takes `ROUNDS` consecutive rows with the round flags set in order,
+    /// starting either on the first row or right after the last round of another compression.
+    /// The remaining rows are padding rows with all round flags zero.
+    pub fn eval_round_flags<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        next: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+    ) {
+        for flag in local.round_flags {
+            builder.assert_bool(flag);
+        }
+        builder.assert_bool(sum_flags::<AB>(&local.round_flags));
+
+        builder
+            .when_first_row()
+            .assert_zero(sum_flags::<AB>(&local.round_flags[1..]));
+        for round in 0..ROUNDS - 1 {
+            builder
+                .when_transition()
+                .when(local.round_flags[round])
+                .assert_one(next.round_flags[round + 1]);
+            builder
+                .when_transition()
+                .when(next.round_flags[round + 1])
+                .assert_one(local.round_flags[round]);
+        }
+        // The trace can't end in the middle of a compression
+        builder
+            .when_last_row()
+            .assert_zero(sum_flags::<AB>(&local.round_flags[..ROUNDS - 1]));
+    }
+
+    /// Constrains the 8 `G` functions of the round and returns the working state at the end of
+    /// the round.
+    ///
+    /// All constraints hold on padding rows, where every column is zero, so only the
+    /// interactions are gated by `is_enabled`.
+    pub fn eval_round<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        is_enabled: AB::Expr,
+    ) -> [Word<AB::Expr>; BLAKE_STATE_WORDS] {
+        let mut state: [Word<AB::Expr>; BLAKE_STATE_WORDS] =
+            local.state.map(|word| word.map(Into::into));
+        for (i, (g, idx)) in local.g.iter().zip(BLAKE_G_INDICES).enumerate() {
+            let out = self.eval_g(
+                builder,
+                g,
+                idx.map(|j| state[j].clone()),
+                local.message[2 * i].map(Into::into),
+                local.message[2 * i + 1].map(Into::into),
+                is_enabled.clone(),
+            );
+            for (j, word) in idx.into_iter().zip(out) {
+                state[j] = word;
+            }
+        }
+        state
+    }
+
+    /// Constrains the `G` function on `[a, b, c, d]` with message words `x` and `y` and returns
+    /// the new `[a, b, c, d]`.
+    ///
+    /// The inputs are expected to be bytes. All the outputs are bytes: the sums are inputs of
+    /// the XOR lookups, the XOR results are outputs of the lookups and the rotated words are
+    /// range checked in [Self::eval_rotr12] and [Self::eval_rotr7].
+    fn eval_g<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        g: &BlakeGCols<AB::Var>,
+        [a, b, c, d]: [Word<AB::Expr>; 4],
+        x: Word<AB::Expr>,
+        y: Word<AB::Expr>,
+        is_enabled: AB::Expr,
+    ) -> [Word<AB::Expr>; 4] {
+        assert_add::<AB>(builder, g.a1, &[a, b.clone(), x]);
+        let a1: Word<AB::Expr> = g.a1.map(Into::into);
+        self.send_xor(builder, &d, &a1, g.d_xor_a1, is_enabled.clone());
+        // rotate right by 16
+        let d1: Word<AB::Expr> = from_fn(|j| g.d_xor_a1[(j + 2) % BLAKE_WORD_U8S].into());
+
+        assert_add::<AB>(builder, g.c1, &[c, d1.clone()]);
+        let c1: Word<AB::Expr> = g.c1.map(Into::into);
+        self.send_xor(builder, &b, &c1, g.b_xor_c1, is_enabled.clone());
+        let b1 = self.eval_rotr12(builder, g.b_xor_c1, g.b_xor_c1_hi, is_enabled.clone());
+
+        assert_add::<AB>(builder, g.a2, &[a1, b1.clone(), y]);
+        let a2: Word<AB::Expr> = g.a2.map(Into::into);
+        self.send_xor(builder, &d1, &a2, g.d1_xor_a2, is_enabled.clone());
+        // rotate right by 8
+        let d2: Word<AB::Expr> = from_fn(|j| g.d1_xor_a2[(j + 1) % BLAKE_WORD_U8S].into());
+
+        assert_add::<AB>(builder, g.c2, &[c1, d2.clone()]);
+        let c2: Word<AB::Expr> = g.c2.map(Into::into);
+        self.send_xor(builder, &b1, &c2, g.b1_xor_c2, is_enabled.clone());
+        let b2 = self.eval_rotr7(builder, g.b1_xor_c2, g.b1_xor_c2_msb, is_enabled);
+
+        [a2, b2, c2, d2]
+    }
+
+    /// Rotates the word `z` right by 12 bits, given the high nibble `hi` of each byte.
+    ///
+    /// With `lo = z - 16 * hi`, byte `j` of the result is `hi[j + 1] + 16 * lo[j + 2]`. We range
+    /// check `(hi, 16 * lo)`: since `z` is a byte and `hi < 256`, `16 * lo < 256` implies
+    /// `lo < 16` and then `hi < 16`.
+    fn eval_rotr12<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        z: Word<AB::Var>,
+        hi: Word<AB::Var>,
+        is_enabled: AB::Expr,
+    ) -> Word<AB::Expr> {
+        let lo: Word<AB::Expr> = from_fn(|i| z[i] - hi[i] * AB::F::from_canonical_u32(16));
+        for i in 0..BLAKE_WORD_U8S {
+            self.bitwise_lookup_bus
+                .send_range(hi[i], lo[i].clone() * AB::F::from_canonical_u32(16))
+                .eval(builder, is_enabled.clone());
+        }
+        from_fn(|j| {
+            hi[(j + 1) % BLAKE_WORD_U8S]
+                + lo[(j + 2) % BLAKE_WORD_U8S].clone() * AB::F::from_canonical_u32(16)
+        })
+    }
+
+    /// Rotates the word `z` right by 7 bits, given the most significant bit `msb` of each byte.
+    ///
+    /// With `rest = z - 128 * msb`, byte `j` of the result is `msb[j] + 2 * rest[j + 1]`. We
+    /// range check `2 * rest`, which implies `rest < 128` since `z` is a byte.
+    fn eval_rotr7<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        z: Word<AB::Var>,
+        msb: Word<AB::Var>,
+        is_enabled: AB::Expr,
+    ) -> Word<AB::Expr> {
+        for bit in msb {
+            builder.assert_bool(bit);
+        }
+        let rest: Word<AB::Expr> = from_fn(|i| z[i] - msb[i] * AB::F::from_canonical_u32(128));
+        for pair in rest.chunks_exact(2) {
+            self.bitwise_lookup_bus
+                .send_range(pair[0].clone() * AB::F::TWO, pair[1].clone() * AB::F::TWO)
+                .eval(builder, is_enabled.clone());
+        }
+        from_fn(|j| msb[j] + rest[(j + 1) % BLAKE_WORD_U8S].clone() * AB::F::TWO)
+    }
+
+    /// Sends the byte-wise XOR `x ^ y = z` to the bitwise lookup. This also range checks the
+    /// bytes of `x` and `y`.
+    fn send_xor<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        x: &Word<AB::Expr>,
+        y: &Word<AB::Expr>,
+        z: Word<AB::Var>,
+        count: AB::Expr,
+    ) {
+        for i in 0..BLAKE_WORD_U8S {
+            self.bitwise_lookup_bus
+                .send_xor(x[i].clone(), y[i].clone(), z[i])
+                .eval(builder, count.clone());
+        }
+    }
+
+    /// Within a compression, the instruction and the chaining value are constant, the next
+    /// working state is the output of the round and the message is permuted according to the
+    /// message schedule.
+    pub fn constrain_consistency_across_rounds<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        next: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        final_state: &[Word<AB::Expr>; BLAKE_STATE_WORDS],
+    ) {
+        let mut transition_builder = builder.when_transition();
+        let mut round_builder =
+            transition_builder.when(sum_flags::<AB>(&local.round_flags[..ROUNDS - 1]));
+        local
+            .instruction
+            .assert_eq(&mut round_builder, next.instruction);
+        for (&local_word, next_word) in local.cv.iter().zip(next.cv) {
+            assert_array_eq(&mut round_builder, local_word, next_word);
+        }
+        for (word, next_word) in final_state.iter().zip(next.state) {
+            assert_array_eq(&mut round_builder, word.clone(), next_word);
+        }
+
+        for round in 0..ROUNDS - 1 {
+            let mut round_builder = builder.when_transition();
+            let mut round_builder = round_builder.when(local.round_flags[round]);
+            for (j, next_word) in next.message.into_iter().enumerate() {
+                let word_idx = C::SCHEDULE[round + 1][j];
+                let k = C::SCHEDULE[round]
+                    .iter()
+                    .position(|&idx| idx == word_idx)
+                    .expect("message schedule is a permutation");
+                assert_array_eq(&mut round_builder, local.message[k], next_word);
+            }
+        }
+    }
+
+    /// On the first round the working state is the chaining value, the first half of the IV and
+    /// then `v[12..16]` read from memory.
+    pub fn eval_initial_state<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+    ) {
+        let mut first_round_builder = builder.when(local.round_flags[0]);
+        for i in 0..BLAKE_CV_WORDS {
+            assert_array_eq(&mut first_round_builder, local.state[i], local.cv[i]);
+        }
+        for (i, iv) in BLAKE_IV[..BLAKE_CV_WORDS - BLAKE_PARAMS_WORDS]
+            .iter()
+            .enumerate()
+        {
+            assert_array_eq(
+                &mut first_round_builder,
+                local.state[BLAKE_CV_WORDS + i],
+                iv.to_le_bytes().map(AB::Expr::from_canonical_u8),
+            );
+        }
+    }
+
+    /// Receive the instruction itself on program bus. Send+receive on execution bus.
+    /// Then does memory reads in addr space 1 to get the pointers from the registers.
+    ///
+    /// Adds range check interactions for the most significant limbs of the register values
+    /// using BitwiseOperationLookupBus.
+    ///
+    /// Returns `start_read_timestamp` which is only relevant on the first round.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+    ) -> AB::Expr {
+        let instruction = local.instruction;
+        // Only receive opcode on the first round of a compression
+        let should_receive = local.round_flags[0];
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(C::OPCODE as usize + self.offset),
+                [
+                    instruction.rd_ptr.into(),
+                    instruction.rs1_ptr.into(),
+                    instruction.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(blake_timestamp_delta(NUM_WRITES)),
+            )
+            .eval(builder, should_receive);
+
+        let mut timestamp: AB::Expr = instruction.start_timestamp.into();
+        for ((ptr, data), aux) in [
+            (instruction.rd_ptr, instruction.dst_ptr),
+            (instruction.rs1_ptr, instruction.src_ptr),
+            (instruction.rs2_ptr, instruction.params_ptr),
+        ]
+        .into_iter()
+        .zip(&local.mem_oc.register_aux)
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, should_receive);
+            timestamp += AB::Expr::ONE;
+        }
+
+        // See Rv32VecHeapAdapterAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.dst_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                instruction.src_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, should_receive);
+        self.bitwise_lookup_bus
+            .send_range(
+                instruction.params_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, should_receive);
+
+        timestamp
+    }
+
+    /// Constrain reading the chaining value, the message block and `v[12..16]` on the first
+    /// round. Memory cells in address space 2 are bytes, so everything read is a byte.
+    ///
+    /// Returns the `start_write_timestamp` which is the timestamp to start from
+    /// for writing the output to memory.
+    pub fn constrain_reads<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        start_read_timestamp: AB::Expr,
+    ) -> AB::Expr {
+        let should_read = local.round_flags[0];
+        let mem = &local.mem_oc;
+        let memory_as = AB::Expr::from_canonical_u32(RV32_MEMORY_AS);
+        let mut timestamp = start_read_timestamp;
+
+        let dst_ptr = abstract_compose::<AB::Expr, _>(local.instruction.dst_ptr);
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(memory_as.clone(), dst_ptr),
+                flatten::<AB, BLAKE_ACCESS_SIZE>(&local.cv),
+                timestamp.clone(),
+                &mem.cv_read_aux,
+            )
+            .eval(builder, should_read);
+        timestamp += AB::Expr::ONE;
+
+        let src_ptr = abstract_compose::<AB::Expr, _>(local.instruction.src_ptr);
+        for (i, (words, aux)) in local
+            .message
+            .chunks_exact(BLAKE_CV_WORDS)
+            .zip(&mem.block_reads_aux)
+            .enumerate()
+        {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        memory_as.clone(),
+                        src_ptr.clone() + AB::F::from_canonical_usize(i * BLAKE_ACCESS_SIZE),
+                    ),
+                    flatten::<AB, BLAKE_ACCESS_SIZE>(words),
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, should_read);
+            timestamp += AB::Expr::ONE;
+        }
+
+        let params_ptr = abstract_compose::<AB::Expr, _>(local.instruction.params_ptr);
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(memory_as, params_ptr),
+                flatten::<AB, BLAKE_PARAMS_U8S>(
+                    &local.state[BLAKE_STATE_WORDS - BLAKE_PARAMS_WORDS..],
+                ),
+                timestamp.clone(),
+                &mem.params_read_aux,
+            )
+            .eval(builder, should_read);
+
+        timestamp + AB::Expr::ONE
+    }
+
+    /// Constrain the output of the compression function on the last round and write it to
+    /// memory, starting at the chaining value pointer.
+    ///
+    /// The first half of the output is `v[i] ^ v[i + 8]` and the second half is `cv[i] ^ v[i] ^
+    /// v[i + 8]` or `cv[i] ^ v[i + 8]`, see [super::blake_compress]. The output bytes are
+    /// outputs of the XOR lookups.
+    pub fn constrain_output<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &BlakeVmCols<AB::Var, ROUNDS, NUM_WRITES>,
+        final_state: &[Word<AB::Expr>; BLAKE_STATE_WORDS],
+        start_write_timestamp: AB::Expr,
+    ) {
+        let is_last_round: AB::Expr = local.round_flags[ROUNDS - 1].into();
+        for i in 0..BLAKE_CV_WORDS {
+            self.send_xor(
+                builder,
+                &final_state[i],
+                &final_state[i + BLAKE_CV_WORDS],
+                local.output[i],
+                is_last_round.clone(),
+            );
+            let rhs = if C::EXTENDED_OUTPUT {
+                final_state[i + BLAKE_CV_WORDS].clone()
+            } else {
+                local.output[i].map(Into::into)
+            };
+            self.send_xor(
+                builder,
+                &local.cv[i].map(Into::into),
+                &rhs,
+                local.output[i + BLAKE_CV_WORDS],
+                is_last_round.clone(),
+            );
+        }
+
+        let dst_ptr = abstract_compose::<AB::Expr, _>(local.instruction.dst_ptr);
+        for (i, (words, aux)) in blake_output_words::<C>()
+            .chunks_exact(BLAKE_CV_WORDS)
+            .zip(&local.mem_oc.writes_aux)
+            .enumerate()
+        {
+            let data: [AB::Expr; BLAKE_ACCESS_SIZE] =
+                from_fn(|j| local.output[words[j / BLAKE_WORD_U8S]][j % BLAKE_WORD_U8S].into());
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        dst_ptr.clone() + AB::F::from_canonical_usize(i * BLAKE_ACCESS_SIZE),
+                    ),
+                    data,
+                    start_write_timestamp.clone() + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, is_last_round.clone());
+        }
+    }
+}
+
+/// Constrains `sum = summands[0] + summands[1] + ...` modulo `2^32` on little-endian bytes, where
+/// `sum` is range checked elsewhere. The carry of each byte is at most `summands.len() - 1`.
+fn assert_add<AB: AirBuilder>(builder: &mut AB, sum: Word<AB::Var>, summands: &[Word<AB::Expr>]) {
+    let inv = AB::F::from_canonical_u32(1 << RV32_CELL_BITS).inverse();
+    let mut carry = AB::Expr::ZERO;
+    for i in 0..BLAKE_WORD_U8S {
+        carry = (summands
+            .iter()
+            .map(|word| word[i].clone())
+            .sum::<AB::Expr>()
+            + carry
+            - sum[i])
+            * inv;
+        builder.assert_zero(
+            (0..summands.len())
+                .map(|k| carry.clone() - AB::F::from_canonical_usize(k))
+                .product::<AB::Expr>(),
+        );
+    }
+}
+
+fn sum_flags<AB: AirBuilder>(flags: &[AB::Var]) -> AB::Expr {
+    flags
+        .iter()
+        .fold(AB::Expr::ZERO, |acc, &flag| acc + flag.into())
+}
+
+/// Flattens `N / BLAKE_WORD_U8S` words into `N` bytes
+fn flatten<AB: AirBuilder, const N: usize>(words: &[Word<AB::Var>]) -> [AB::Expr; N] {
+    debug_assert_eq!(words.len() * BLAKE_WORD_U8S, N);
+    from_fn(|i| words[i / BLAKE_WORD_U8S][i % BLAKE_WORD_U8S].into())
+}
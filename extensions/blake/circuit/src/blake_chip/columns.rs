@@ -0,0 +1,106 @@
+//! WARNING: the order of fields in the structs is important, do not change it
+
+use openvm_circuit::system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols};
+use openvm_circuit_primitives::{utils::assert_array_eq, AlignedBorrow};
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_stark_backend::p3_air::AirBuilder;
+
+use super::{
+    BLAKE_ACCESS_SIZE, BLAKE_BLOCK_READS, BLAKE_BLOCK_WORDS, BLAKE_CV_WORDS, BLAKE_NUM_G,
+    BLAKE_REGISTER_READS, BLAKE_STATE_WORDS, BLAKE_WORD_U8S,
+};
+
+/// Every compression takes `ROUNDS` consecutive rows, one per round. All words are stored as
+/// little-endian bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeVmCols<T, const ROUNDS: usize, const NUM_WRITES: usize> {
+    /// One-hot encoding of the round of this row. All zero on padding rows.
+    pub round_flags: [T; ROUNDS],
+    pub instruction: BlakeInstructionCols<T>,
+    /// The working state at the start of the round
+    pub state: [[T; BLAKE_WORD_U8S]; BLAKE_STATE_WORDS],
+    /// The message block permuted by the message schedule of the round
+    pub message: [[T; BLAKE_WORD_U8S]; BLAKE_BLOCK_WORDS],
+    /// The input chaining value, constant across rounds
+    pub cv: [[T; BLAKE_WORD_U8S]; BLAKE_CV_WORDS],
+    pub g: [BlakeGCols<T>; BLAKE_NUM_G],
+    /// The output of the compression function. Only used on the last round.
+    pub output: [[T; BLAKE_WORD_U8S]; BLAKE_STATE_WORDS],
+    pub mem_oc: BlakeMemoryCols<T, NUM_WRITES>,
+}
+
+/// The intermediate values of a `G` function. The rotations by 16 and 8 bits are byte
+/// permutations; for the rotations by 12 and 7 bits each byte is split at the rotation point.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeGCols<T> {
+    /// `a1 = a + b + x`
+    pub a1: [T; BLAKE_WORD_U8S],
+    /// `d ^ a1`, which is rotated right by 16 to get `d1`
+    pub d_xor_a1: [T; BLAKE_WORD_U8S],
+    /// `c1 = c + d1`
+    pub c1: [T; BLAKE_WORD_U8S],
+    /// `b ^ c1`, which is rotated right by 12 to get `b1`
+    pub b_xor_c1: [T; BLAKE_WORD_U8S],
+    /// The high nibble of each byte of `b_xor_c1`
+    pub b_xor_c1_hi: [T; BLAKE_WORD_U8S],
+    /// `a2 = a1 + b1 + y`
+    pub a2: [T; BLAKE_WORD_U8S],
+    /// `d1 ^ a2`, which is rotated right by 8 to get `d2`
+    pub d1_xor_a2: [T; BLAKE_WORD_U8S],
+    /// `c2 = c1 + d2`
+    pub c2: [T; BLAKE_WORD_U8S],
+    /// `b1 ^ c2`, which is rotated right by 7 to get `b2`
+    pub b1_xor_c2: [T; BLAKE_WORD_U8S],
+    /// The most significant bit of each byte of `b1_xor_c2`
+    pub b1_xor_c2_msb: [T; BLAKE_WORD_U8S],
+}
+
+/// The instruction columns, constant across rounds
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeInstructionCols<T> {
+    pub pc: T,
+    pub start_timestamp: T,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    /// Pointer to the chaining value, which is overwritten by the output
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Pointer to the message block
+    pub src_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    /// Pointer to `v[12..16]` of the initial working state
+    pub params_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+/// The reads are constrained on the first round and the writes on the last round
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct BlakeMemoryCols<T, const NUM_WRITES: usize> {
+    pub register_aux: [MemoryReadAuxCols<T>; BLAKE_REGISTER_READS],
+    pub cv_read_aux: MemoryReadAuxCols<T>,
+    pub block_reads_aux: [MemoryReadAuxCols<T>; BLAKE_BLOCK_READS],
+    pub params_read_aux: MemoryReadAuxCols<T>,
+    pub writes_aux: [MemoryWriteAuxCols<T, BLAKE_ACCESS_SIZE>; NUM_WRITES],
+}
+
+impl<T: Copy> BlakeInstructionCols<T> {
+    pub fn assert_eq<AB: AirBuilder>(&self, builder: &mut AB, other: Self)
+    where
+        T: Into<AB::Expr>,
+    {
+        builder.assert_eq(self.pc, other.pc);
+        builder.assert_eq(self.start_timestamp, other.start_timestamp);
+        builder.assert_eq(self.rd_ptr, other.rd_ptr);
+        builder.assert_eq(self.rs1_ptr, other.rs1_ptr);
+        builder.assert_eq(self.rs2_ptr, other.rs2_ptr);
+        assert_array_eq(builder, self.dst_ptr, other.dst_ptr);
+        assert_array_eq(builder, self.src_ptr, other.src_ptr);
+        assert_array_eq(builder, self.params_ptr, other.params_ptr);
+    }
+}
+
+pub const fn num_blake_vm_cols<const ROUNDS: usize, const NUM_WRITES: usize>() -> usize {
+    size_of::<BlakeVmCols<u8, ROUNDS, NUM_WRITES>>()
+}
@@ -0,0 +1,407 @@
+//! BLAKE2s and BLAKE3 compression functions on a chaining value and a message block stored in VM
+//! memory. Both hashes share the same `G` function on a 16-word working state and differ in the
+//! number of rounds, the message schedule and how the output is formed. Padding, counters, flags
+//! and (for BLAKE3) the tree structure are left to the guest.
+use std::{
+    array::from_fn,
+    sync::{Arc, Mutex},
+};
+
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionError, ExecutionState, InstructionExecutor, SystemPort},
+    system::memory::{MemoryController, OfflineMemory, RecordId},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+
+mod air;
+mod columns;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of bytes in a word
+pub const BLAKE_WORD_U8S: usize = 4;
+/// Number of words in the working state
+pub const BLAKE_STATE_WORDS: usize = 16;
+/// Number of words in the chaining value
+pub const BLAKE_CV_WORDS: usize = 8;
+/// Number of words in a message block
+pub const BLAKE_BLOCK_WORDS: usize = 16;
+/// Number of words of the working state that are read from memory: `v[12..16]`
+pub const BLAKE_PARAMS_WORDS: usize = 4;
+/// Number of `G` function applications per round
+pub const BLAKE_NUM_G: usize = 8;
+
+pub const BLAKE2S_ROUNDS: usize = 10;
+pub const BLAKE3_ROUNDS: usize = 7;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the chaining value, block and params pointers
+const BLAKE_REGISTER_READS: usize = 3;
+/// Number of cells to read or write in a single memory access of the chaining value, the block
+/// or the output
+pub const BLAKE_ACCESS_SIZE: usize = BLAKE_CV_WORDS * BLAKE_WORD_U8S;
+/// Number of [BLAKE_ACCESS_SIZE] reads of the message block
+const BLAKE_BLOCK_READS: usize = BLAKE_BLOCK_WORDS * BLAKE_WORD_U8S / BLAKE_ACCESS_SIZE;
+/// Number of cells to read for `v[12..16]`
+const BLAKE_PARAMS_U8S: usize = BLAKE_PARAMS_WORDS * BLAKE_WORD_U8S;
+/// Number of memory reads, including the register reads
+const BLAKE_NUM_READS: usize = BLAKE_REGISTER_READS + 1 + BLAKE_BLOCK_READS + 1;
+/// Number of [BLAKE_ACCESS_SIZE] writes of the output for BLAKE2s
+pub const BLAKE2S_NUM_WRITES: usize = 1;
+/// Number of [BLAKE_ACCESS_SIZE] writes of the output for BLAKE3
+pub const BLAKE3_NUM_WRITES: usize = 2;
+
+/// The initialization vector shared by BLAKE2s and BLAKE3
+pub const BLAKE_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The working state words `[a, b, c, d]` of each `G` function in a round: first the columns,
+/// then the diagonals. `G` function `i` takes message words `2 * i` and `2 * i + 1` of the round.
+pub const BLAKE_G_INDICES: [[usize; 4]; BLAKE_NUM_G] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The BLAKE2s message schedule `SIGMA`
+pub const BLAKE2S_SCHEDULE: [[usize; BLAKE_BLOCK_WORDS]; BLAKE2S_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The BLAKE3 message schedule, i.e. the message permutation applied `r` times for round `r`
+pub const BLAKE3_SCHEDULE: [[usize; BLAKE_BLOCK_WORDS]; BLAKE3_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+/// A BLAKE compression function that is executed by a [BlakeVmChip]
+pub trait BlakeConfig: Clone + Copy + std::fmt::Debug + Send + Sync + 'static {
+    /// The opcode that the chip handles
+    const OPCODE: Rv32BlakeOpcode;
+    /// The name of the opcode
+    const OPCODE_NAME: &'static str;
+    /// Number of rounds of the compression function
+    const ROUNDS: usize;
+    /// `SCHEDULE[r][j]` is the index of the message word used at position `j` of round `r`
+    const SCHEDULE: &'static [[usize; BLAKE_BLOCK_WORDS]];
+    /// Whether the full 16-word output is written to memory (BLAKE3) or only the new chaining
+    /// value (BLAKE2s). See [blake_compress].
+    const EXTENDED_OUTPUT: bool;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Blake2sConfig;
+
+impl BlakeConfig for Blake2sConfig {
+    const OPCODE: Rv32BlakeOpcode = Rv32BlakeOpcode::BLAKE2S_COMPRESS;
+    const OPCODE_NAME: &'static str = "BLAKE2S_COMPRESS";
+    const ROUNDS: usize = BLAKE2S_ROUNDS;
+    const SCHEDULE: &'static [[usize; BLAKE_BLOCK_WORDS]] = &BLAKE2S_SCHEDULE;
+    const EXTENDED_OUTPUT: bool = false;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Blake3Config;
+
+impl BlakeConfig for Blake3Config {
+    const OPCODE: Rv32BlakeOpcode = Rv32BlakeOpcode::BLAKE3_COMPRESS;
+    const OPCODE_NAME: &'static str = "BLAKE3_COMPRESS";
+    const ROUNDS: usize = BLAKE3_ROUNDS;
+    const SCHEDULE: &'static [[usize; BLAKE_BLOCK_WORDS]] = &BLAKE3_SCHEDULE;
+    const EXTENDED_OUTPUT: bool = true;
+}
+
+/// The chip for a BLAKE compression function, with the parameters given by `C`.
+/// `ROUNDS` must be [BlakeConfig::ROUNDS] and `NUM_WRITES` is the number of [BLAKE_ACCESS_SIZE]
+/// writes of the output.
+pub struct BlakeVmChip<
+    F: PrimeField32,
+    C: BlakeConfig,
+    const ROUNDS: usize,
+    const NUM_WRITES: usize,
+> {
+    pub air: BlakeVmAir<C, ROUNDS, NUM_WRITES>,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<BlakeRecord<F>>,
+    pub offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+
+    offset: usize,
+}
+
+pub type Blake2sVmChip<F> = BlakeVmChip<F, Blake2sConfig, BLAKE2S_ROUNDS, BLAKE2S_NUM_WRITES>;
+pub type Blake3VmChip<F> = BlakeVmChip<F, Blake3Config, BLAKE3_ROUNDS, BLAKE3_NUM_WRITES>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BlakeRecord<F> {
+    pub pc: F,
+    pub dst_read: RecordId,
+    pub src_read: RecordId,
+    pub params_ptr_read: RecordId,
+    pub cv_read: RecordId,
+    pub block_reads: [RecordId; BLAKE_BLOCK_READS],
+    pub params_read: RecordId,
+    pub output_writes: Vec<RecordId>,
+    pub cv: [u32; BLAKE_CV_WORDS],
+    pub block: [u32; BLAKE_BLOCK_WORDS],
+    pub params: [u32; BLAKE_PARAMS_WORDS],
+}
+
+impl<F: PrimeField32, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize>
+    BlakeVmChip<F, C, ROUNDS, NUM_WRITES>
+{
+    pub fn new(
+        SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        }: SystemPort,
+        address_bits: usize,
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+        offset: usize,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        assert_eq!(ROUNDS, C::ROUNDS);
+        assert_eq!(C::SCHEDULE.len(), C::ROUNDS);
+        assert_eq!(NUM_WRITES * BLAKE_CV_WORDS, blake_output_words::<C>().len());
+        Self {
+            air: BlakeVmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                address_bits,
+                offset,
+            ),
+            bitwise_lookup_chip,
+            records: Vec::new(),
+            offset,
+            offline_memory,
+        }
+    }
+}
+
+impl<F: PrimeField32, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize>
+    InstructionExecutor<F> for BlakeVmChip<F, C, ROUNDS, NUM_WRITES>
+{
+    fn execute(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        let local_opcode = opcode.local_opcode_idx(self.offset);
+        debug_assert_eq!(local_opcode, C::OPCODE.local_usize());
+        debug_assert_eq!(d, F::from_canonical_u32(RV32_REGISTER_AS));
+        debug_assert_eq!(e, F::from_canonical_u32(RV32_MEMORY_AS));
+
+        let (dst_read, dst) = read_rv32_register(memory, d, a);
+        let (src_read, src) = read_rv32_register(memory, d, b);
+        let (params_ptr_read, params_ptr) = read_rv32_register(memory, d, c);
+
+        #[cfg(debug_assertions)]
+        {
+            assert!(dst < (1 << self.air.ptr_max_bits));
+            assert!(src < (1 << self.air.ptr_max_bits));
+            assert!(params_ptr < (1 << self.air.ptr_max_bits));
+        }
+
+        let (cv_read, cv) = memory.read::<BLAKE_ACCESS_SIZE>(e, F::from_canonical_u32(dst));
+        let mut block = [0u32; BLAKE_BLOCK_WORDS];
+        let block_reads = from_fn(|i| {
+            let (record_id, data) = memory.read::<BLAKE_ACCESS_SIZE>(
+                e,
+                F::from_canonical_usize(src as usize + i * BLAKE_ACCESS_SIZE),
+            );
+            for (j, word) in bytes_to_words(&data).into_iter().enumerate() {
+                block[i * BLAKE_CV_WORDS + j] = word;
+            }
+            record_id
+        });
+        let (params_read, params) =
+            memory.read::<BLAKE_PARAMS_U8S>(e, F::from_canonical_u32(params_ptr));
+
+        let cv: [u32; BLAKE_CV_WORDS] = bytes_to_words(&cv).try_into().unwrap();
+        let params: [u32; BLAKE_PARAMS_WORDS] = bytes_to_words(&params).try_into().unwrap();
+        let output = blake_compress::<C>(&cv, &block, &params);
+        let output_writes = blake_output_words::<C>()
+            .chunks_exact(BLAKE_CV_WORDS)
+            .enumerate()
+            .map(|(i, words)| {
+                let data: [F; BLAKE_ACCESS_SIZE] = from_fn(|j| {
+                    let word = output[words[j / BLAKE_WORD_U8S]];
+                    F::from_canonical_u8(word.to_le_bytes()[j % BLAKE_WORD_U8S])
+                });
+                memory
+                    .write(
+                        e,
+                        F::from_canonical_usize(dst as usize + i * BLAKE_ACCESS_SIZE),
+                        data,
+                    )
+                    .0
+            })
+            .collect();
+        tracing::trace!("[runtime] {} output: {:?}", C::OPCODE_NAME, output);
+
+        self.records.push(BlakeRecord {
+            pc: F::from_canonical_u32(from_state.pc),
+            dst_read,
+            src_read,
+            params_ptr_read,
+            cv_read,
+            block_reads,
+            params_read,
+            output_writes,
+            cv,
+            block,
+            params,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: from_state.timestamp + blake_timestamp_delta(NUM_WRITES) as u32,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        C::OPCODE_NAME.to_string()
+    }
+}
+
+/// Amount to advance timestamp by after execution of one opcode instruction.
+pub const fn blake_timestamp_delta(num_writes: usize) -> usize {
+    BLAKE_NUM_READS + num_writes
+}
+
+/// Converts memory cells to little-endian words
+fn bytes_to_words<F: PrimeField32>(data: &[F]) -> Vec<u32> {
+    data.chunks_exact(BLAKE_WORD_U8S)
+        .map(|word| {
+            u32::from_le_bytes(from_fn(|i| {
+                word[i]
+                    .as_canonical_u32()
+                    .try_into()
+                    .expect("Memory cell not a byte")
+            }))
+        })
+        .collect()
+}
+
+/// The indices of the words of the [blake_compress] output that are written to memory, in order
+pub fn blake_output_words<C: BlakeConfig>() -> Vec<usize> {
+    if C::EXTENDED_OUTPUT {
+        (0..BLAKE_STATE_WORDS).collect()
+    } else {
+        (BLAKE_CV_WORDS..BLAKE_STATE_WORDS).collect()
+    }
+}
+
+/// The `G` function on the working state words `[a, b, c, d]` with message words `x` and `y`
+pub fn blake_g([a, b, c, d]: [u32; 4], x: u32, y: u32) -> [u32; 4] {
+    let a = a.wrapping_add(b).wrapping_add(x);
+    let d = (d ^ a).rotate_right(16);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_right(12);
+    let a = a.wrapping_add(b).wrapping_add(y);
+    let d = (d ^ a).rotate_right(8);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_right(7);
+    [a, b, c, d]
+}
+
+/// One round of the compression function on the working state `v`, where `message` is the block
+/// permuted by the round's message schedule
+pub fn blake_round(v: &mut [u32; BLAKE_STATE_WORDS], message: &[u32; BLAKE_BLOCK_WORDS]) {
+    for (i, idx) in BLAKE_G_INDICES.iter().enumerate() {
+        let out = blake_g(idx.map(|j| v[j]), message[2 * i], message[2 * i + 1]);
+        for (&j, word) in idx.iter().zip(out) {
+            v[j] = word;
+        }
+    }
+}
+
+/// The compression function of `C` on the chaining value `cv` and message `block`, where `params`
+/// are the last four words `v[12..16]` of the initial working state. For BLAKE2s these are the
+/// counter and finalization flags XOR-ed with `BLAKE_IV[4..8]`, for BLAKE3 they are the counter,
+/// block length and domain flags.
+///
+/// Returns the 16 output words. The first 8 words are `v[i] ^ v[i + 8]`. The last 8 words are
+/// `cv[i] ^ v[i] ^ v[i + 8]` for BLAKE2s, which is the new chaining value, and
+/// `cv[i] ^ v[i + 8]` for BLAKE3, which is the second half of the extended output.
+pub fn blake_compress<C: BlakeConfig>(
+    cv: &[u32; BLAKE_CV_WORDS],
+    block: &[u32; BLAKE_BLOCK_WORDS],
+    params: &[u32; BLAKE_PARAMS_WORDS],
+) -> [u32; BLAKE_STATE_WORDS] {
+    let mut v = blake_initial_state(cv, params);
+    for schedule in C::SCHEDULE {
+        blake_round(&mut v, &schedule.map(|j| block[j]));
+    }
+    let mut output = [0u32; BLAKE_STATE_WORDS];
+    for i in 0..BLAKE_CV_WORDS {
+        output[i] = v[i] ^ v[i + BLAKE_CV_WORDS];
+        output[i + BLAKE_CV_WORDS] = cv[i]
+            ^ if C::EXTENDED_OUTPUT {
+                v[i + BLAKE_CV_WORDS]
+            } else {
+                output[i]
+            };
+    }
+    output
+}
+
+/// The working state before the first round: `cv`, the first half of [BLAKE_IV], then `params`
+pub fn blake_initial_state(
+    cv: &[u32; BLAKE_CV_WORDS],
+    params: &[u32; BLAKE_PARAMS_WORDS],
+) -> [u32; BLAKE_STATE_WORDS] {
+    let mut v = [0u32; BLAKE_STATE_WORDS];
+    v[..BLAKE_CV_WORDS].copy_from_slice(cv);
+    v[BLAKE_CV_WORDS..BLAKE_STATE_WORDS - BLAKE_PARAMS_WORDS]
+        .copy_from_slice(&BLAKE_IV[..BLAKE_CV_WORDS - BLAKE_PARAMS_WORDS]);
+    v[BLAKE_STATE_WORDS - BLAKE_PARAMS_WORDS..].copy_from_slice(params);
+    v
+}
@@ -0,0 +1,237 @@
+use std::{array::from_fn, borrow::BorrowMut};
+
+use openvm_blake_transpiler::Rv32BlakeOpcode;
+use openvm_circuit::arch::{
+    testing::{memory::gen_pointer, VmChipTestBuilder, VmChipTester, BITWISE_OP_LOOKUP_BUS},
+    InstructionExecutor, SystemPort,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, LocalOpcode};
+use openvm_stark_backend::{
+    p3_field::FieldAlgebra, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    blake_compress, blake_output_words, Blake2sConfig, Blake2sVmChip, Blake3Config, Blake3VmChip,
+    BlakeConfig, BlakeVmCols, BLAKE2S_NUM_WRITES, BLAKE2S_ROUNDS, BLAKE_ACCESS_SIZE,
+    BLAKE_BLOCK_WORDS, BLAKE_CV_WORDS, BLAKE_IV, BLAKE_PARAMS_WORDS, BLAKE_WORD_U8S,
+};
+
+type F = BabyBear;
+
+fn write_words(tester: &mut VmChipTestBuilder<F>, ptr: usize, words: &[u32]) {
+    for (i, word) in words.iter().enumerate() {
+        tester.write(
+            2,
+            ptr + i * BLAKE_WORD_U8S,
+            word.to_le_bytes().map(F::from_canonical_u8),
+        );
+    }
+}
+
+fn set_and_execute<C: BlakeConfig, E: InstructionExecutor<F>>(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut E,
+    rng: &mut StdRng,
+) {
+    let cv: [u32; BLAKE_CV_WORDS] = rng.gen();
+    let block: [u32; BLAKE_BLOCK_WORDS] = rng.gen();
+    let params: [u32; BLAKE_PARAMS_WORDS] = rng.gen();
+
+    let rd = gen_pointer(rng, 4);
+    let rs1 = gen_pointer(rng, 4);
+    let rs2 = gen_pointer(rng, 4);
+
+    // the output may be 64 bytes long
+    let dst_ptr = gen_pointer(rng, 2 * BLAKE_ACCESS_SIZE);
+    let src_ptr = gen_pointer(rng, 2 * BLAKE_ACCESS_SIZE);
+    let params_ptr = gen_pointer(rng, BLAKE_PARAMS_WORDS * BLAKE_WORD_U8S);
+    tester.write(
+        1,
+        rd,
+        (dst_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+    );
+    tester.write(
+        1,
+        rs1,
+        (src_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+    );
+    tester.write(
+        1,
+        rs2,
+        (params_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+    );
+    write_words(tester, dst_ptr, &cv);
+    write_words(tester, src_ptr, &block);
+    write_words(tester, params_ptr, &params);
+
+    tester.execute(
+        chip,
+        &Instruction::from_usize(C::OPCODE.global_opcode(), [rd, rs1, rs2, 1, 2]),
+    );
+
+    let output = blake_compress::<C>(&cv, &block, &params);
+    for (i, word_idx) in blake_output_words::<C>().into_iter().enumerate() {
+        assert_eq!(
+            output[word_idx].to_le_bytes().map(F::from_canonical_u8),
+            tester.read::<BLAKE_WORD_U8S>(2, dst_ptr + i * BLAKE_WORD_U8S)
+        );
+    }
+}
+
+fn new_chip_args(
+    tester: &VmChipTestBuilder<F>,
+) -> (
+    SystemPort,
+    usize,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    (
+        SystemPort {
+            execution_bus: tester.execution_bus(),
+            program_bus: tester.program_bus(),
+            memory_bridge: tester.memory_bridge(),
+        },
+        tester.address_bits(),
+        bitwise_chip,
+    )
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_blake2s_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (port, address_bits, bitwise_chip) = new_chip_args(&tester);
+    let mut chip = Blake2sVmChip::new(
+        port,
+        address_bits,
+        bitwise_chip.clone(),
+        Rv32BlakeOpcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..3 {
+        set_and_execute::<Blake2sConfig, _>(&mut tester, &mut chip, &mut rng);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_blake3_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (port, address_bits, bitwise_chip) = new_chip_args(&tester);
+    let mut chip = Blake3VmChip::new(
+        port,
+        address_bits,
+        bitwise_chip.clone(),
+        Rv32BlakeOpcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..3 {
+        set_and_execute::<Blake3Config, _>(&mut tester, &mut chip, &mut rng);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+/// Single block hashes of "abc", checked against the BLAKE2s and BLAKE3 test vectors
+#[test]
+fn blake_compress_test_vectors() {
+    let mut block = [0u32; BLAKE_BLOCK_WORDS];
+    block[0] = u32::from_le_bytes(*b"abc\0");
+
+    // BLAKE2s-256 with no key: parameter block XOR-ed into the IV, final block flag set
+    let mut cv = BLAKE_IV;
+    cv[0] ^= 0x01010020;
+    let params: [u32; BLAKE_PARAMS_WORDS] = from_fn(|i| BLAKE_IV[4 + i] ^ [3, 0, u32::MAX, 0][i]);
+    let output = blake_compress::<Blake2sConfig>(&cv, &block, &params);
+    let digest: Vec<u8> = output[BLAKE_CV_WORDS..]
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    assert_eq!(
+        digest,
+        hex_to_bytes("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982")
+    );
+
+    // BLAKE3 with flags CHUNK_START | CHUNK_END | ROOT
+    let output = blake_compress::<Blake3Config>(&BLAKE_IV, &block, &[0, 0, 3, 0b1011]);
+    let digest: Vec<u8> = output[..BLAKE_CV_WORDS]
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    assert_eq!(
+        digest,
+        hex_to_bytes("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")
+    );
+}
+
+fn hex_to_bytes(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// NEGATIVE TESTS
+///
+/// Given a fake trace of a single operation, setup a chip and run the test. We replace
+/// part of the trace and check that the chip throws the expected error.
+///////////////////////////////////////////////////////////////////////////////////////
+fn run_blake2s_prank_output_test() -> VmChipTester<BabyBearBlake3Config> {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (port, address_bits, bitwise_chip) = new_chip_args(&tester);
+    let mut chip = Blake2sVmChip::new(
+        port,
+        address_bits,
+        bitwise_chip.clone(),
+        Rv32BlakeOpcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+    set_and_execute::<Blake2sConfig, _>(&mut tester, &mut chip, &mut rng);
+
+    let mut tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    let trace = tester.air_proof_inputs[2]
+        .1
+        .raw
+        .common_main
+        .as_mut()
+        .unwrap();
+    let last_row: &mut BlakeVmCols<F, BLAKE2S_ROUNDS, BLAKE2S_NUM_WRITES> =
+        trace.row_mut(BLAKE2S_ROUNDS - 1).borrow_mut();
+    // breaks the XOR lookup of the output and the memory write
+    last_row.output[BLAKE_CV_WORDS][0] += F::ONE;
+    tester
+}
+
+#[test]
+fn negative_blake2s_output_test() {
+    let tester = run_blake2s_prank_output_test();
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::ChallengePhaseError)
+    );
+}
@@ -0,0 +1,219 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::system::memory::MemoryRecord;
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::SharedBitwiseOperationLookupChip, utils::next_power_of_two_or_zero,
+};
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::dense::RowMajorMatrix,
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::get_air_name,
+    AirRef, Chip, ChipUsageGetter,
+};
+
+use super::{
+    blake_compress, blake_initial_state, columns::*, BlakeConfig, BlakeVmChip, BLAKE_CV_WORDS,
+    BLAKE_G_INDICES, BLAKE_WORD_U8S,
+};
+
+impl<SC: StarkGenericConfig, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> Chip<SC>
+    for BlakeVmChip<Val<SC>, C, ROUNDS, NUM_WRITES>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> AirRef<SC> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let width = self.trace_width();
+        let height = next_power_of_two_or_zero(self.current_trace_height());
+        let mut trace = RowMajorMatrix::new(Val::<SC>::zero_vec(height * width), width);
+        if height == 0 {
+            return AirProofInput::simple_no_pis(trace);
+        }
+        let memory = self.offline_memory.lock().unwrap();
+        let aux_cols_factory = memory.aux_cols_factory();
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+        let bitwise_lookup_chip = &self.bitwise_lookup_chip;
+
+        // Padding rows are left as zeros
+        trace
+            .values
+            .par_chunks_mut(width * ROUNDS)
+            .zip(self.records.par_iter())
+            .for_each(|(rows, record)| {
+                let dst_read = memory.record_by_id(record.dst_read);
+                let src_read = memory.record_by_id(record.src_read);
+                let params_ptr_read = memory.record_by_id(record.params_ptr_read);
+                let msl = |read: &MemoryRecord<Val<SC>>| {
+                    read.data_slice().last().unwrap().as_canonical_u32() << limb_shift_bits
+                };
+                bitwise_lookup_chip.request_range(msl(dst_read), msl(src_read));
+                bitwise_lookup_chip.request_range(msl(params_ptr_read), 0);
+
+                let instruction = BlakeInstructionCols {
+                    pc: record.pc,
+                    start_timestamp: Val::<SC>::from_canonical_u32(dst_read.timestamp),
+                    rd_ptr: dst_read.pointer,
+                    rs1_ptr: src_read.pointer,
+                    rs2_ptr: params_ptr_read.pointer,
+                    dst_ptr: dst_read.data_slice().try_into().unwrap(),
+                    src_ptr: src_read.data_slice().try_into().unwrap(),
+                    params_ptr: params_ptr_read.data_slice().try_into().unwrap(),
+                };
+
+                let mut state = blake_initial_state(&record.cv, &record.params);
+                for (round, row) in rows.chunks_exact_mut(width).enumerate() {
+                    let cols: &mut BlakeVmCols<Val<SC>, ROUNDS, NUM_WRITES> = row.borrow_mut();
+                    cols.round_flags[round] = Val::<SC>::ONE;
+                    cols.instruction = instruction;
+                    cols.state = state.map(word_to_field);
+                    cols.cv = record.cv.map(word_to_field);
+                    let message = C::SCHEDULE[round].map(|j| record.block[j]);
+                    cols.message = message.map(word_to_field);
+                    for (i, (g, idx)) in cols.g.iter_mut().zip(BLAKE_G_INDICES).enumerate() {
+                        let out = generate_g_cols(
+                            bitwise_lookup_chip,
+                            g,
+                            idx.map(|j| state[j]),
+                            message[2 * i],
+                            message[2 * i + 1],
+                        );
+                        for (j, word) in idx.into_iter().zip(out) {
+                            state[j] = word;
+                        }
+                    }
+                }
+
+                let first_row: &mut BlakeVmCols<Val<SC>, ROUNDS, NUM_WRITES> =
+                    rows[..width].borrow_mut();
+                let mem = &mut first_row.mem_oc;
+                for (read, aux) in [dst_read, src_read, params_ptr_read]
+                    .into_iter()
+                    .zip(mem.register_aux.iter_mut())
+                {
+                    aux_cols_factory.generate_read_aux(read, aux);
+                }
+                aux_cols_factory
+                    .generate_read_aux(memory.record_by_id(record.cv_read), &mut mem.cv_read_aux);
+                for (&id, aux) in record
+                    .block_reads
+                    .iter()
+                    .zip(mem.block_reads_aux.iter_mut())
+                {
+                    aux_cols_factory.generate_read_aux(memory.record_by_id(id), aux);
+                }
+                aux_cols_factory.generate_read_aux(
+                    memory.record_by_id(record.params_read),
+                    &mut mem.params_read_aux,
+                );
+
+                let output = blake_compress::<C>(&record.cv, &record.block, &record.params);
+                for i in 0..BLAKE_CV_WORDS {
+                    let lo = state[i];
+                    let hi = state[i + BLAKE_CV_WORDS];
+                    request_xor(bitwise_lookup_chip, lo, hi);
+                    request_xor(
+                        bitwise_lookup_chip,
+                        record.cv[i],
+                        if C::EXTENDED_OUTPUT { hi } else { lo ^ hi },
+                    );
+                }
+                let last_row: &mut BlakeVmCols<Val<SC>, ROUNDS, NUM_WRITES> =
+                    rows[(ROUNDS - 1) * width..].borrow_mut();
+                last_row.output = output.map(word_to_field);
+                for (&id, aux) in record
+                    .output_writes
+                    .iter()
+                    .zip(last_row.mem_oc.writes_aux.iter_mut())
+                {
+                    aux_cols_factory.generate_write_aux(memory.record_by_id(id), aux);
+                }
+            });
+
+        AirProofInput::simple_no_pis(trace)
+    }
+}
+
+impl<F: PrimeField32, C: BlakeConfig, const ROUNDS: usize, const NUM_WRITES: usize> ChipUsageGetter
+    for BlakeVmChip<F, C, ROUNDS, NUM_WRITES>
+{
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * ROUNDS
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
+
+/// Fills the columns of a `G` function on `[a, b, c, d]` with message words `x` and `y`,
+/// requesting the lookups of [super::BlakeVmAir], and returns the new `[a, b, c, d]`.
+fn generate_g_cols<F: PrimeField32>(
+    bitwise_lookup_chip: &SharedBitwiseOperationLookupChip<8>,
+    g: &mut BlakeGCols<F>,
+    [a, b, c, d]: [u32; 4],
+    x: u32,
+    y: u32,
+) -> [u32; 4] {
+    let a1 = a.wrapping_add(b).wrapping_add(x);
+    let d_xor_a1 = request_xor(bitwise_lookup_chip, d, a1);
+    let d1 = d_xor_a1.rotate_right(16);
+    let c1 = c.wrapping_add(d1);
+    let b_xor_c1 = request_xor(bitwise_lookup_chip, b, c1);
+    let b1 = b_xor_c1.rotate_right(12);
+    for byte in b_xor_c1.to_le_bytes() {
+        bitwise_lookup_chip.request_range((byte >> 4) as u32, ((byte & 0xf) as u32) << 4);
+    }
+
+    let a2 = a1.wrapping_add(b1).wrapping_add(y);
+    let d1_xor_a2 = request_xor(bitwise_lookup_chip, d1, a2);
+    let d2 = d1_xor_a2.rotate_right(8);
+    let c2 = c1.wrapping_add(d2);
+    let b1_xor_c2 = request_xor(bitwise_lookup_chip, b1, c2);
+    let b2 = b1_xor_c2.rotate_right(7);
+    for pair in b1_xor_c2.to_le_bytes().chunks_exact(2) {
+        bitwise_lookup_chip.request_range(
+            ((pair[0] & 0x7f) as u32) << 1,
+            ((pair[1] & 0x7f) as u32) << 1,
+        );
+    }
+
+    g.a1 = word_to_field(a1);
+    g.d_xor_a1 = word_to_field(d_xor_a1);
+    g.c1 = word_to_field(c1);
+    g.b_xor_c1 = word_to_field(b_xor_c1);
+    g.b_xor_c1_hi = b_xor_c1
+        .to_le_bytes()
+        .map(|byte| F::from_canonical_u8(byte >> 4));
+    g.a2 = word_to_field(a2);
+    g.d1_xor_a2 = word_to_field(d1_xor_a2);
+    g.c2 = word_to_field(c2);
+    g.b1_xor_c2 = word_to_field(b1_xor_c2);
+    g.b1_xor_c2_msb = b1_xor_c2
+        .to_le_bytes()
+        .map(|byte| F::from_canonical_u8(byte >> 7));
+
+    [a2, b2, c2, d2]
+}
+
+/// Requests the byte-wise XOR of `x` and `y` and returns `x ^ y`
+fn request_xor(bitwise_lookup_chip: &SharedBitwiseOperationLookupChip<8>, x: u32, y: u32) -> u32 {
+    for (x, y) in x.to_le_bytes().into_iter().zip(y.to_le_bytes()) {
+        bitwise_lookup_chip.request_xor(x as u32, y as u32);
+    }
+    x ^ y
+}
+
+fn word_to_field<F: FieldAlgebra>(word: u32) -> [F; BLAKE_WORD_U8S] {
+    word.to_le_bytes().map(F::from_canonical_u8)
+}
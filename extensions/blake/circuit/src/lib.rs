@@ -0,0 +1,5 @@
+mod blake_chip;
+pub use blake_chip::*;
+
+mod extension;
+pub use extension::*;
@@ -0,0 +1,46 @@
+#![no_std]
+
+/// This is custom-0 defined in RISC-V spec document
+pub const OPCODE: u8 = 0x0b;
+pub const BLAKE_FUNCT3: u8 = 0b100;
+pub const BLAKE2S_FUNCT7: u8 = 0x5;
+pub const BLAKE3_FUNCT7: u8 = 0x6;
+
+/// zkvm native implementation of the BLAKE2s compression function
+/// # Safety
+///
+/// The VM reads the 32-byte chaining value `h`, the 64-byte message block and the last four
+/// words `v[12..16]` of the initial working state, and overwrites `h` with the compressed
+/// chaining value. All words are little-endian `u32`s.
+/// - `h` must point to a buffer that is at least 32-bytes long.
+/// - `block` must point to a buffer that is at least 64-bytes long.
+/// - `params` must point to a buffer that is at least 16-bytes long. For BLAKE2s these are the
+///   counter and finalization flags already XOR-ed with the last four words of the IV.
+///
+/// [`blake2s`]: https://www.rfc-editor.org/rfc/rfc7693
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn native_blake2s_compress(h: *mut u8, block: *const u8, params: *const u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = BLAKE_FUNCT3, funct7 = BLAKE2S_FUNCT7, rd = In h, rs1 = In block, rs2 = In params);
+}
+
+/// zkvm native implementation of the BLAKE3 compression function
+/// # Safety
+///
+/// The VM reads the 32-byte chaining value `cv`, the 64-byte message block and the last four
+/// words `v[12..16]` of the initial working state, and writes the full 64-byte compression
+/// output starting at `cv`. The first 32 bytes of the output are the new chaining value. All
+/// words are little-endian `u32`s.
+/// - `cv` must point to a buffer that is at least 64-bytes long.
+/// - `block` must point to a buffer that is at least 64-bytes long.
+/// - `params` must point to a buffer that is at least 16-bytes long, holding the counter (low
+///   word first), the block length and the domain flags.
+///
+/// [`blake3`]: https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn native_blake3_compress(cv: *mut u8, block: *const u8, params: *const u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = BLAKE_FUNCT3, funct7 = BLAKE3_FUNCT7, rd = In cv, rs1 = In block, rs2 = In params);
+}
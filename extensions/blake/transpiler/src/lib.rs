@@ -0,0 +1,50 @@
+use openvm_blake_guest::{BLAKE2S_FUNCT7, BLAKE3_FUNCT7, BLAKE_FUNCT3, OPCODE};
+use openvm_instructions::{riscv::RV32_MEMORY_AS, LocalOpcode};
+use openvm_instructions_derive::LocalOpcode;
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
+use rrs_lib::instruction_formats::RType;
+use strum::{EnumCount, EnumIter, FromRepr};
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x340]
+#[allow(non_camel_case_types)]
+#[repr(usize)]
+pub enum Rv32BlakeOpcode {
+    BLAKE2S_COMPRESS,
+    BLAKE3_COMPRESS,
+}
+
+#[derive(Default)]
+pub struct BlakeTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for BlakeTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, BLAKE_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+
+        let local_opcode = match dec_insn.funct7 as u8 {
+            BLAKE2S_FUNCT7 => Rv32BlakeOpcode::BLAKE2S_COMPRESS,
+            BLAKE3_FUNCT7 => Rv32BlakeOpcode::BLAKE3_COMPRESS,
+            _ => return None,
+        };
+        let instruction = from_r_type(
+            local_opcode.global_opcode().as_usize(),
+            RV32_MEMORY_AS as usize,
+            &dec_insn,
+            true,
+        );
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
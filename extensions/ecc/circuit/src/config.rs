@@ -49,3 +49,46 @@ impl InitFileGenerator for Rv32WeierstrassConfig {
         ))
     }
 }
+
+#[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+pub struct Rv32EdwardsConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub mul: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub modular: ModularExtension,
+    #[extension]
+    pub edwards: TwistedEdwardsExtension,
+}
+
+impl Rv32EdwardsConfig {
+    pub fn new(curves: Vec<TeCurveConfig>) -> Self {
+        let primes: Vec<_> = curves
+            .iter()
+            .flat_map(|c| [c.modulus.clone(), c.scalar.clone()])
+            .collect();
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            base: Default::default(),
+            mul: Default::default(),
+            io: Default::default(),
+            modular: ModularExtension::new(primes),
+            edwards: TwistedEdwardsExtension::new(curves),
+        }
+    }
+}
+
+impl InitFileGenerator for Rv32EdwardsConfig {
+    fn generate_init_file_contents(&self) -> Option<String> {
+        Some(format!(
+            "// This file is automatically generated by cargo openvm. Do not rename or edit.\n{}\n{}\n",
+            self.modular.generate_moduli_init(),
+            self.edwards.generate_te_init()
+        ))
+    }
+}
@@ -0,0 +1,43 @@
+use std::{cell::RefCell, rc::Rc};
+
+use num_bigint::BigUint;
+use num_traits::One;
+use openvm_circuit_primitives::var_range::VariableRangeCheckerBus;
+use openvm_mod_circuit_builder::{ExprBuilder, ExprBuilderConfig, FieldExpr};
+
+// Assumes that (x1, y1), (x2, y2) both lie on the curve. The unified addition formula is complete
+// for twisted Edwards curves with `a` a square and `d` a non-square, so the identity and doubling
+// need no special handling.
+pub fn te_add_expr(
+    config: ExprBuilderConfig, // The coordinate field.
+    range_bus: VariableRangeCheckerBus,
+    a_biguint: BigUint,
+    d_biguint: BigUint,
+) -> FieldExpr {
+    config.check_valid();
+    let builder = ExprBuilder::new(config, range_bus.range_max_bits);
+    let builder = Rc::new(RefCell::new(builder));
+
+    let x1 = ExprBuilder::new_input(builder.clone());
+    let y1 = ExprBuilder::new_input(builder.clone());
+    let x2 = ExprBuilder::new_input(builder.clone());
+    let y2 = ExprBuilder::new_input(builder.clone());
+    let a = ExprBuilder::new_const(builder.clone(), a_biguint.clone());
+    let d = ExprBuilder::new_const(builder.clone(), d_biguint.clone());
+    let one = ExprBuilder::new_const(builder.clone(), BigUint::one());
+
+    // On the setup opcode x1 is the modulus, so x1 * x2 = 0 and both denominators are one.
+    let x1x2 = x1.clone() * x2.clone();
+    let y1y2 = y1.clone() * y2.clone();
+    let dxy = d * x1x2.clone() * y1y2.clone();
+
+    // x3 = (x1 * y2 + x2 * y1) / (1 + d * x1 * x2 * y1 * y2)
+    let mut x3 = (x1 * y2 + x2 * y1) / (one.clone() + dxy.clone());
+    x3.save_output();
+    // y3 = (y1 * y2 - a * x1 * x2) / (1 - d * x1 * x2 * y1 * y2)
+    let mut y3 = (y1y2 - a * x1x2) / (one - dxy);
+    y3.save_output();
+
+    let builder = builder.borrow().clone();
+    FieldExpr::new_with_setup_values(builder, range_bus, true, vec![a_biguint, d_biguint])
+}
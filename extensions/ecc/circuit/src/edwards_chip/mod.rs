@@ -0,0 +1,62 @@
+mod add;
+
+use std::sync::Arc;
+
+pub use add::*;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Mutex;
+
+use num_bigint::BigUint;
+use openvm_circuit::{arch::VmChipWrapper, system::memory::OfflineMemory};
+use openvm_circuit_derive::InstructionExecutor;
+use openvm_circuit_primitives::var_range::SharedVariableRangeCheckerChip;
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_ecc_transpiler::Rv32EdwardsOpcode;
+use openvm_mod_circuit_builder::{ExprBuilderConfig, FieldExpressionCoreChip};
+use openvm_rv32_adapters::Rv32VecHeapAdapterChip;
+use openvm_stark_backend::p3_field::PrimeField32;
+
+/// BLOCK_SIZE: how many cells do we read at a time, must be a power of 2.
+/// BLOCKS: how many blocks do we need to represent one input or output
+/// For example, for ed25519, BLOCK_SIZE = 32 and with two elements per input point, BLOCKS = 2.
+#[derive(Chip, ChipUsageGetter, InstructionExecutor)]
+pub struct TeAddChip<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>(
+    pub  VmChipWrapper<
+        F,
+        Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        FieldExpressionCoreChip,
+    >,
+);
+
+impl<F: PrimeField32, const BLOCKS: usize, const BLOCK_SIZE: usize>
+    TeAddChip<F, BLOCKS, BLOCK_SIZE>
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        adapter: Rv32VecHeapAdapterChip<F, 2, BLOCKS, BLOCKS, BLOCK_SIZE, BLOCK_SIZE>,
+        config: ExprBuilderConfig,
+        offset: usize,
+        a: BigUint,
+        d: BigUint,
+        range_checker: SharedVariableRangeCheckerChip,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        let expr = te_add_expr(config, range_checker.bus(), a, d);
+        let core = FieldExpressionCoreChip::new(
+            expr,
+            offset,
+            vec![
+                Rv32EdwardsOpcode::TE_ADD as usize,
+                Rv32EdwardsOpcode::SETUP_TE_ADD as usize,
+            ],
+            vec![],
+            range_checker,
+            "TeAdd",
+            true,
+        );
+        Self(VmChipWrapper::new(adapter, core, offline_memory))
+    }
+}
@@ -0,0 +1,146 @@
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use num_traits::One;
+use openvm_circuit::arch::testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_ecc_transpiler::Rv32EdwardsOpcode;
+use openvm_instructions::{riscv::RV32_CELL_BITS, LocalOpcode};
+use openvm_mod_circuit_builder::{test_utils::biguint_to_limbs, ExprBuilderConfig, FieldExpr};
+use openvm_rv32_adapters::{rv32_write_heap_default, Rv32VecHeapAdapterChip};
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+use super::TeAddChip;
+use crate::{ED25519_A, ED25519_D, ED25519_MODULUS};
+
+const NUM_LIMBS: usize = 32;
+const LIMB_BITS: usize = 8;
+const BLOCK_SIZE: usize = 32;
+type F = BabyBear;
+
+lazy_static::lazy_static! {
+    // The Ed25519 base point B from RFC 8032, its multiples 2B, 3B and the identity point
+    pub static ref SampleEdPoints: Vec<(BigUint, BigUint)> = {
+        let x1 = BigUint::from_str(
+            "15112221349535400772501151409588531511454012693041857206046113283949847762202",
+        )
+        .unwrap();
+        let y1 = BigUint::from_str(
+            "46316835694926478169428394003475163141307993866256225615783033603165251855960",
+        )
+        .unwrap();
+        let x2 = BigUint::from_str(
+            "24727413235106541002554574571675588834622768167397638456726423682521233608206",
+        )
+        .unwrap();
+        let y2 = BigUint::from_str(
+            "15549675580280190176352668710449542251549572066445060580507079593062643049417",
+        )
+        .unwrap();
+        let x3 = BigUint::from_str(
+            "46896733464454938657123544595386787789046198280132665686241321779790909858396",
+        )
+        .unwrap();
+        let y3 = BigUint::from_str(
+            "8324843778533443976490377120369201138301417226297555316741202210403726505172",
+        )
+        .unwrap();
+        // The identity point
+        let x4 = BigUint::ZERO;
+        let y4 = BigUint::one();
+        vec![(x1, y1), (x2, y2), (x3, y3), (x4, y4)]
+    };
+}
+
+fn prime_limbs(expr: &FieldExpr) -> Vec<BabyBear> {
+    expr.prime_limbs
+        .iter()
+        .map(|n| BabyBear::from_canonical_usize(*n))
+        .collect::<Vec<_>>()
+}
+
+fn to_limbs(x: &BigUint) -> [BabyBear; NUM_LIMBS] {
+    biguint_to_limbs::<NUM_LIMBS>(x.clone(), LIMB_BITS).map(BabyBear::from_canonical_u32)
+}
+
+fn test_add(p1: usize, p2: usize, expected: usize) {
+    let mut tester: VmChipTestBuilder<F> = VmChipTestBuilder::default();
+    let config = ExprBuilderConfig {
+        modulus: ED25519_MODULUS.clone(),
+        num_limbs: NUM_LIMBS,
+        limb_bits: LIMB_BITS,
+    };
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let adapter = Rv32VecHeapAdapterChip::<F, 2, 2, 2, BLOCK_SIZE, BLOCK_SIZE>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        bitwise_chip.clone(),
+    );
+    let mut chip = TeAddChip::new(
+        adapter,
+        config,
+        Rv32EdwardsOpcode::CLASS_OFFSET,
+        ED25519_A.clone(),
+        ED25519_D.clone(),
+        tester.range_checker(),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let (p1_x, p1_y) = SampleEdPoints[p1].clone();
+    let (p2_x, p2_y) = SampleEdPoints[p2].clone();
+
+    let r = chip.0.core.expr().execute_with_output(
+        vec![p1_x.clone(), p1_y.clone(), p2_x.clone(), p2_y.clone()],
+        vec![true],
+    );
+    assert_eq!(r.len(), 2); // x3, y3
+    assert_eq!(r[0], SampleEdPoints[expected].0);
+    assert_eq!(r[1], SampleEdPoints[expected].1);
+
+    let prime_limbs: [BabyBear; NUM_LIMBS] = prime_limbs(chip.0.core.expr()).try_into().unwrap();
+    let mut one_limbs = [BabyBear::ZERO; NUM_LIMBS];
+    one_limbs[0] = BabyBear::ONE;
+    let setup_instruction = rv32_write_heap_default(
+        &mut tester,
+        // inputs[0] = prime, inputs[1] = a, inputs[2] = d, inputs[3] doesn't matter
+        vec![prime_limbs, to_limbs(&ED25519_A)],
+        vec![to_limbs(&ED25519_D), one_limbs],
+        chip.0.core.air.offset + Rv32EdwardsOpcode::SETUP_TE_ADD as usize,
+    );
+    tester.execute(&mut chip, &setup_instruction);
+
+    let instruction = rv32_write_heap_default(
+        &mut tester,
+        vec![to_limbs(&p1_x), to_limbs(&p1_y)],
+        vec![to_limbs(&p2_x), to_limbs(&p2_y)],
+        chip.0.core.air.offset + Rv32EdwardsOpcode::TE_ADD as usize,
+    );
+    tester.execute(&mut chip, &instruction);
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_te_add() {
+    // 2B + B = 3B
+    test_add(1, 0, 2);
+}
+
+#[test]
+fn test_te_double() {
+    // B + B = 2B
+    test_add(0, 0, 1);
+}
+
+#[test]
+fn test_te_add_identity() {
+    // B + O = B
+    test_add(0, 3, 0);
+}
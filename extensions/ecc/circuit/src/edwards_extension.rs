@@ -0,0 +1,199 @@
+use derive_more::derive::From;
+use hex_literal::hex;
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use once_cell::sync::Lazy;
+use openvm_circuit::{
+    arch::{SystemPort, VmExtension, VmInventory, VmInventoryBuilder, VmInventoryError},
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_ecc_transpiler::Rv32EdwardsOpcode;
+use openvm_instructions::{LocalOpcode, VmOpcode};
+use openvm_mod_circuit_builder::ExprBuilderConfig;
+use openvm_rv32_adapters::Rv32VecHeapAdapterChip;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use strum::EnumCount;
+
+use super::TeAddChip;
+
+#[serde_as]
+#[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
+pub struct TeCurveConfig {
+    /// The name of the curve struct as defined by te_declare.
+    pub struct_name: String,
+    /// The coordinate modulus of the curve.
+    #[serde_as(as = "DisplayFromStr")]
+    pub modulus: BigUint,
+    /// The scalar field modulus of the curve.
+    #[serde_as(as = "DisplayFromStr")]
+    pub scalar: BigUint,
+    /// The coefficient a of ax^2 + y^2 = 1 + dx^2y^2.
+    #[serde_as(as = "DisplayFromStr")]
+    pub a: BigUint,
+    /// The coefficient d of ax^2 + y^2 = 1 + dx^2y^2.
+    #[serde_as(as = "DisplayFromStr")]
+    pub d: BigUint,
+}
+
+pub static ED25519_CONFIG: Lazy<TeCurveConfig> = Lazy::new(|| TeCurveConfig {
+    struct_name: ED25519_ECC_STRUCT_NAME.to_string(),
+    modulus: ED25519_MODULUS.clone(),
+    scalar: ED25519_ORDER.clone(),
+    a: ED25519_A.clone(),
+    d: ED25519_D.clone(),
+});
+
+#[derive(Clone, Debug, derive_new::new, Serialize, Deserialize)]
+pub struct TwistedEdwardsExtension {
+    pub supported_curves: Vec<TeCurveConfig>,
+}
+
+impl TwistedEdwardsExtension {
+    pub fn generate_te_init(&self) -> String {
+        let supported_curves = self
+            .supported_curves
+            .iter()
+            .map(|curve_config| curve_config.struct_name.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        format!("openvm_ecc_guest::te_macros::te_init! {{ {supported_curves} }}")
+    }
+}
+
+#[derive(Chip, ChipUsageGetter, InstructionExecutor, AnyEnum)]
+pub enum TwistedEdwardsExtensionExecutor<F: PrimeField32> {
+    // 32 limbs prime
+    TeAddRv32_32(TeAddChip<F, 2, 32>),
+    // 48 limbs prime
+    TeAddRv32_48(TeAddChip<F, 6, 16>),
+}
+
+#[derive(ChipUsageGetter, Chip, AnyEnum, From)]
+pub enum TwistedEdwardsExtensionPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for TwistedEdwardsExtension {
+    type Executor = TwistedEdwardsExtensionExecutor<F>;
+    type Periphery = TwistedEdwardsExtensionPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = builder.system_port();
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+        let offline_memory = builder.system_base().offline_memory();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let pointer_bits = builder.system_config().memory_config.pointer_max_bits;
+        let te_add_opcodes =
+            (Rv32EdwardsOpcode::TE_ADD as usize)..=(Rv32EdwardsOpcode::SETUP_TE_ADD as usize);
+
+        for (i, curve) in self.supported_curves.iter().enumerate() {
+            let start_offset = Rv32EdwardsOpcode::CLASS_OFFSET + i * Rv32EdwardsOpcode::COUNT;
+            let bytes = curve.modulus.bits().div_ceil(8);
+            let config32 = ExprBuilderConfig {
+                modulus: curve.modulus.clone(),
+                num_limbs: 32,
+                limb_bits: 8,
+            };
+            let config48 = ExprBuilderConfig {
+                modulus: curve.modulus.clone(),
+                num_limbs: 48,
+                limb_bits: 8,
+            };
+            if bytes <= 32 {
+                let add_chip = TeAddChip::new(
+                    Rv32VecHeapAdapterChip::<F, 2, 2, 2, 32, 32>::new(
+                        execution_bus,
+                        program_bus,
+                        memory_bridge,
+                        pointer_bits,
+                        bitwise_lu_chip.clone(),
+                    ),
+                    config32.clone(),
+                    start_offset,
+                    curve.a.clone(),
+                    curve.d.clone(),
+                    range_checker.clone(),
+                    offline_memory.clone(),
+                );
+                inventory.add_executor(
+                    TwistedEdwardsExtensionExecutor::TeAddRv32_32(add_chip),
+                    te_add_opcodes
+                        .clone()
+                        .map(|x| VmOpcode::from_usize(x + start_offset)),
+                )?;
+            } else if bytes <= 48 {
+                let add_chip = TeAddChip::new(
+                    Rv32VecHeapAdapterChip::<F, 2, 6, 6, 16, 16>::new(
+                        execution_bus,
+                        program_bus,
+                        memory_bridge,
+                        pointer_bits,
+                        bitwise_lu_chip.clone(),
+                    ),
+                    config48.clone(),
+                    start_offset,
+                    curve.a.clone(),
+                    curve.d.clone(),
+                    range_checker.clone(),
+                    offline_memory.clone(),
+                );
+                inventory.add_executor(
+                    TwistedEdwardsExtensionExecutor::TeAddRv32_48(add_chip),
+                    te_add_opcodes
+                        .clone()
+                        .map(|x| VmOpcode::from_usize(x + start_offset)),
+                )?;
+            } else {
+                panic!("Modulus too large");
+            }
+        }
+
+        Ok(inventory)
+    }
+}
+
+// Convenience constants for constructors
+lazy_static! {
+    // The constants are taken from: https://datatracker.ietf.org/doc/html/rfc8032#section-5.1
+    pub static ref ED25519_MODULUS: BigUint = BigUint::from_bytes_be(&hex!(
+        "7FFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFED"
+    ));
+    pub static ref ED25519_ORDER: BigUint = BigUint::from_bytes_be(&hex!(
+        "10000000 00000000 00000000 00000000 14DEF9DE A2F79CD6 5812631A 5CF5D3ED"
+    ));
+    pub static ref ED25519_A: BigUint = BigUint::from_bytes_be(&hex!(
+        "7FFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFEC"
+    ));
+    pub static ref ED25519_D: BigUint = BigUint::from_bytes_be(&hex!(
+        "52036CEE 2B6FFE73 8CC74079 7779E898 00700A4D 4141D8AB 75EB4DCA 135978A3"
+    ));
+}
+
+pub const ED25519_ECC_STRUCT_NAME: &str = "Ed25519Point";
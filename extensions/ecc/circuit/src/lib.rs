@@ -4,5 +4,11 @@ pub use weierstrass_chip::*;
 mod weierstrass_extension;
 pub use weierstrass_extension::*;
 
+mod edwards_chip;
+pub use edwards_chip::*;
+
+mod edwards_extension;
+pub use edwards_extension::*;
+
 mod config;
 pub use config::*;
@@ -0,0 +1,179 @@
+use core::ops::Mul;
+
+use openvm_algebra_guest::Field;
+
+/// Twisted Edwards curve affine point.
+///
+/// The curve equation is `a x^2 + y^2 = 1 + d x^2 y^2`. The addition formula is complete when `a`
+/// is a square and `d` is a non-square in the coordinate field, so no special handling of the
+/// identity `(0, 1)` or of doubling is required.
+pub trait TwistedEdwardsPoint: Clone + Sized {
+    /// The `a` coefficient in the twisted Edwards curve equation `a x^2 + y^2 = 1 + d x^2 y^2`.
+    const CURVE_A: Self::Coordinate;
+    /// The `d` coefficient in the twisted Edwards curve equation `a x^2 + y^2 = 1 + d x^2 y^2`.
+    const CURVE_D: Self::Coordinate;
+    const IDENTITY: Self;
+
+    type Coordinate: Field;
+
+    /// The concatenated `x, y` coordinates of the affine point, where
+    /// coordinates are in little endian.
+    ///
+    /// **Warning**: The memory layout of `Self` is expected to pack
+    /// `x` and `y` contiguously with no unallocated space in between.
+    fn as_le_bytes(&self) -> &[u8];
+
+    /// Raw constructor without asserting point is on the curve.
+    fn from_xy_unchecked(x: Self::Coordinate, y: Self::Coordinate) -> Self;
+    fn into_coords(self) -> (Self::Coordinate, Self::Coordinate);
+    fn x(&self) -> &Self::Coordinate;
+    fn y(&self) -> &Self::Coordinate;
+
+    /// Calls any setup required for this curve. The implementation should internally use `OnceBool`
+    /// to ensure that setup is only called once.
+    fn set_up_once();
+
+    /// Add implementation. Since the twisted Edwards addition formula is complete, this handles
+    /// the identity and doubling.
+    ///
+    /// # Safety
+    /// - If `CHECK_SETUP` is true, checks if setup has been called for this curve and if not, calls
+    ///   `Self::set_up_once()`. Only set `CHECK_SETUP` to `false` if you are sure that setup has
+    ///   been called already.
+    fn add_impl<const CHECK_SETUP: bool>(&self, p2: &Self) -> Self;
+
+    #[inline(always)]
+    fn from_xy(x: Self::Coordinate, y: Self::Coordinate) -> Option<Self>
+    where
+        for<'a> &'a Self::Coordinate: Mul<&'a Self::Coordinate, Output = Self::Coordinate>,
+    {
+        let x2 = &x * &x;
+        let y2 = &y * &y;
+        let lhs = &Self::CURVE_A * &x2 + &y2;
+        let rhs = Self::Coordinate::ONE + &Self::CURVE_D * &(&x2 * &y2);
+        if lhs != rhs {
+            return None;
+        }
+        Some(Self::from_xy_unchecked(x, y))
+    }
+}
+
+pub trait FromCompressed<Coordinate> {
+    /// Decompresses a point from its y-coordinate and a sign bit which indicates the parity of the
+    /// x-coordinate. Given the y-coordinate, this function attempts to find the corresponding
+    /// x-coordinate that satisfies the curve equation. If successful, it returns the point as an
+    /// instance of Self. If the point cannot be decompressed, it returns None.
+    fn decompress(y: Coordinate, sign: &u8) -> Option<Self>
+    where
+        Self: core::marker::Sized;
+}
+
+/// Implements `Group` on `$struct_name` assuming that `$struct_name` implements
+/// `TwistedEdwardsPoint`. Assumes that `Neg` is implemented for `&$struct_name`.
+#[macro_export]
+macro_rules! impl_te_group_ops {
+    ($struct_name:ident, $field:ty) => {
+        impl $crate::Group for $struct_name {
+            type SelfRef<'a> = &'a Self;
+
+            const IDENTITY: Self = <Self as $crate::edwards::TwistedEdwardsPoint>::IDENTITY;
+
+            #[inline(always)]
+            fn double(&self) -> Self {
+                self.add_impl::<true>(self)
+            }
+
+            #[inline(always)]
+            fn double_assign(&mut self) {
+                *self = self.add_impl::<true>(self);
+            }
+
+            #[inline(always)]
+            fn is_identity(&self) -> bool {
+                self == &<Self as $crate::Group>::IDENTITY
+            }
+        }
+
+        impl core::ops::Add<&$struct_name> for $struct_name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, p2: &$struct_name) -> Self::Output {
+                self.add_impl::<true>(p2)
+            }
+        }
+
+        impl core::ops::Add for $struct_name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self::Output {
+                self.add_impl::<true>(&rhs)
+            }
+        }
+
+        impl core::ops::Add<&$struct_name> for &$struct_name {
+            type Output = $struct_name;
+
+            #[inline(always)]
+            fn add(self, p2: &$struct_name) -> Self::Output {
+                self.add_impl::<true>(p2)
+            }
+        }
+
+        impl core::ops::AddAssign<&$struct_name> for $struct_name {
+            #[inline(always)]
+            fn add_assign(&mut self, p2: &$struct_name) {
+                *self = self.add_impl::<true>(p2);
+            }
+        }
+
+        impl core::ops::AddAssign for $struct_name {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                *self = self.add_impl::<true>(&rhs);
+            }
+        }
+
+        impl core::ops::Sub<&$struct_name> for $struct_name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: &$struct_name) -> Self::Output {
+                self.add_impl::<true>(&core::ops::Neg::neg(rhs))
+            }
+        }
+
+        impl core::ops::Sub for $struct_name {
+            type Output = $struct_name;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.add_impl::<true>(&core::ops::Neg::neg(rhs))
+            }
+        }
+
+        impl core::ops::Sub<&$struct_name> for &$struct_name {
+            type Output = $struct_name;
+
+            #[inline(always)]
+            fn sub(self, p2: &$struct_name) -> Self::Output {
+                self.add_impl::<true>(&core::ops::Neg::neg(p2))
+            }
+        }
+
+        impl core::ops::SubAssign<&$struct_name> for $struct_name {
+            #[inline(always)]
+            fn sub_assign(&mut self, p2: &$struct_name) {
+                *self = self.add_impl::<true>(&core::ops::Neg::neg(p2));
+            }
+        }
+
+        impl core::ops::SubAssign for $struct_name {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = self.add_impl::<true>(&core::ops::Neg::neg(rhs));
+            }
+        }
+    };
+}
@@ -0,0 +1,366 @@
+extern crate proc_macro;
+
+use openvm_macros_common::MacroArgs;
+use proc_macro::TokenStream;
+use quote::format_ident;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Expr, ExprPath, Path, Token,
+};
+
+/// This macro generates the code to setup the twisted Edwards elliptic curve for a given modular
+/// type. Usage:
+/// ```
+/// te_declare! {
+///     Ed25519Point { mod_type = Ed25519Coord, a = CURVE_A, d = CURVE_D },
+/// }
+/// ```
+///
+/// For this macro to work, you must import the `openvm_algebra_guest` crate and the
+/// `openvm_ecc_guest` crate.
+#[proc_macro]
+pub fn te_declare(input: TokenStream) -> TokenStream {
+    let MacroArgs { items } = parse_macro_input!(input as MacroArgs);
+
+    let mut output = Vec::new();
+
+    let span = proc_macro::Span::call_site();
+
+    for item in items.into_iter() {
+        let struct_name = item.name.to_string();
+        let struct_name = syn::Ident::new(&struct_name, span.into());
+        let struct_path: syn::Path = syn::parse_quote!(#struct_name);
+        let mut intmod_type: Option<syn::Path> = None;
+        let mut const_a: Option<syn::Expr> = None;
+        let mut const_d: Option<syn::Expr> = None;
+        for param in item.params {
+            match param.name.to_string().as_str() {
+                // Note that mod_type must have NUM_LIMBS divisible by 4
+                "mod_type" => {
+                    if let syn::Expr::Path(ExprPath { path, .. }) = param.value {
+                        intmod_type = Some(path)
+                    } else {
+                        return syn::Error::new_spanned(param.value, "Expected a type")
+                            .to_compile_error()
+                            .into();
+                    }
+                }
+                "a" => {
+                    // We currently leave it to the compiler to check if the expression is actually
+                    // a constant
+                    const_a = Some(param.value);
+                }
+                "d" => {
+                    // We currently leave it to the compiler to check if the expression is actually
+                    // a constant
+                    const_d = Some(param.value);
+                }
+                _ => {
+                    panic!("Unknown parameter {}", param.name);
+                }
+            }
+        }
+
+        let intmod_type = intmod_type.expect("mod_type parameter is required");
+        let const_a = const_a.expect("constant a coefficient is required");
+        let const_d = const_d.expect("constant d coefficient is required");
+
+        macro_rules! create_extern_func {
+            ($name:ident) => {
+                let $name = syn::Ident::new(
+                    &format!(
+                        "{}_{}",
+                        stringify!($name),
+                        struct_path
+                            .segments
+                            .iter()
+                            .map(|x| x.ident.to_string())
+                            .collect::<Vec<_>>()
+                            .join("_")
+                    ),
+                    span.into(),
+                );
+            };
+        }
+        create_extern_func!(te_add_extern_func);
+        create_extern_func!(te_setup_extern_func);
+
+        let group_ops_mod_name = format_ident!("{}_ops", struct_name.to_string().to_lowercase());
+
+        let result = TokenStream::from(quote::quote_spanned! { span.into() =>
+            extern "C" {
+                fn #te_add_extern_func(rd: usize, rs1: usize, rs2: usize);
+                fn #te_setup_extern_func();
+            }
+
+            #[derive(Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+            #[repr(C)]
+            pub struct #struct_name {
+                x: #intmod_type,
+                y: #intmod_type,
+            }
+            #[allow(non_upper_case_globals)]
+
+            impl #struct_name {
+                const fn identity() -> Self {
+                    Self {
+                        x: <#intmod_type as openvm_algebra_guest::IntMod>::ZERO,
+                        y: <#intmod_type as openvm_algebra_guest::IntMod>::ONE,
+                    }
+                }
+                // Below is the wrapper function for the intrinsic instruction.
+                // Should not be called directly.
+                #[inline(always)]
+                fn add_chip<const CHECK_SETUP: bool>(p1: &#struct_name, p2: &#struct_name) -> #struct_name {
+                    #[cfg(not(target_os = "zkvm"))]
+                    {
+                        use openvm_algebra_guest::{DivUnsafe, IntMod};
+                        let curve_a: #intmod_type = #const_a;
+                        let curve_d: #intmod_type = #const_d;
+                        let x1x2 = &p1.x * &p2.x;
+                        let y1y2 = &p1.y * &p2.y;
+                        let dxy = &curve_d * &x1x2 * &y1y2;
+                        let x3 = (&p1.x * &p2.y + &p1.y * &p2.x)
+                            .div_unsafe(&(<#intmod_type as IntMod>::ONE + &dxy));
+                        let y3 = (y1y2 - &curve_a * &x1x2)
+                            .div_unsafe(&(<#intmod_type as IntMod>::ONE - &dxy));
+                        #struct_name { x: x3, y: y3 }
+                    }
+                    #[cfg(target_os = "zkvm")]
+                    {
+                        if CHECK_SETUP {
+                            Self::set_up_once();
+                        }
+                        let mut uninit: core::mem::MaybeUninit<#struct_name> = core::mem::MaybeUninit::uninit();
+                        unsafe {
+                            #te_add_extern_func(
+                                uninit.as_mut_ptr() as usize,
+                                p1 as *const #struct_name as usize,
+                                p2 as *const #struct_name as usize
+                            );
+                            uninit.assume_init()
+                        }
+                    }
+                }
+
+                // Helper function to call the setup instruction on first use
+                #[inline(always)]
+                #[cfg(target_os = "zkvm")]
+                fn set_up_once() {
+                    static is_setup: ::openvm_ecc_guest::once_cell::race::OnceBool = ::openvm_ecc_guest::once_cell::race::OnceBool::new();
+                    is_setup.get_or_init(|| {
+                        unsafe { #te_setup_extern_func(); }
+                        <#intmod_type as openvm_algebra_guest::IntMod>::set_up_once();
+                        true
+                    });
+                }
+
+                #[inline(always)]
+                #[cfg(not(target_os = "zkvm"))]
+                fn set_up_once() {
+                    // No-op for non-ZKVM targets
+                }
+            }
+
+            impl ::openvm_ecc_guest::edwards::TwistedEdwardsPoint for #struct_name {
+                const CURVE_A: #intmod_type = #const_a;
+                const CURVE_D: #intmod_type = #const_d;
+                const IDENTITY: Self = Self::identity();
+                type Coordinate = #intmod_type;
+
+                /// SAFETY: assumes that #intmod_type has a memory representation
+                /// such that with repr(C), two coordinates are packed contiguously.
+                #[inline(always)]
+                fn as_le_bytes(&self) -> &[u8] {
+                    unsafe { &*core::ptr::slice_from_raw_parts(self as *const Self as *const u8, <#intmod_type as openvm_algebra_guest::IntMod>::NUM_LIMBS * 2) }
+                }
+
+                #[inline(always)]
+                fn from_xy_unchecked(x: Self::Coordinate, y: Self::Coordinate) -> Self {
+                    Self { x, y }
+                }
+
+                #[inline(always)]
+                fn x(&self) -> &Self::Coordinate {
+                    &self.x
+                }
+
+                #[inline(always)]
+                fn y(&self) -> &Self::Coordinate {
+                    &self.y
+                }
+
+                #[inline(always)]
+                fn into_coords(self) -> (Self::Coordinate, Self::Coordinate) {
+                    (self.x, self.y)
+                }
+
+                #[inline(always)]
+                fn set_up_once() {
+                    Self::set_up_once();
+                }
+
+                #[inline(always)]
+                fn add_impl<const CHECK_SETUP: bool>(&self, p2: &Self) -> Self {
+                    Self::add_chip::<CHECK_SETUP>(self, p2)
+                }
+            }
+
+            impl core::ops::Neg for #struct_name {
+                type Output = Self;
+
+                fn neg(self) -> Self::Output {
+                    #struct_name {
+                        x: -self.x,
+                        y: self.y,
+                    }
+                }
+            }
+
+            impl core::ops::Neg for &#struct_name {
+                type Output = #struct_name;
+
+                fn neg(self) -> #struct_name {
+                    #struct_name {
+                        x: core::ops::Neg::neg(&self.x),
+                        y: self.y.clone(),
+                    }
+                }
+            }
+
+            mod #group_ops_mod_name {
+                use ::openvm_ecc_guest::{edwards::{TwistedEdwardsPoint, FromCompressed}, impl_te_group_ops, algebra::IntMod};
+                use super::*;
+
+                impl_te_group_ops!(#struct_name, #intmod_type);
+
+                impl FromCompressed<#intmod_type> for #struct_name {
+                    fn decompress(y: #intmod_type, sign: &u8) -> Option<Self> {
+                        use openvm_algebra_guest::{DivUnsafe, Sqrt};
+                        // a x^2 + y^2 = 1 + d x^2 y^2, so x^2 = (y^2 - 1) / (d y^2 - a)
+                        let y_squared = &y * &y;
+                        let denom = &<#struct_name as TwistedEdwardsPoint>::CURVE_D * &y_squared
+                            - &<#struct_name as TwistedEdwardsPoint>::CURVE_A;
+                        if denom == <#intmod_type as IntMod>::ZERO {
+                            return None;
+                        }
+                        let x_squared = (y_squared - &<#intmod_type as IntMod>::ONE).div_unsafe(&denom);
+                        let x = x_squared.sqrt()?;
+                        let correct_x = if x.as_le_bytes()[0] & 1 == *sign & 1 {
+                            x
+                        } else {
+                            -x
+                        };
+                        // If x = 0 then negating x doesn't change its parity
+                        if correct_x.as_le_bytes()[0] & 1 != *sign & 1 {
+                            return None;
+                        }
+                        // In order for sqrt() to return Some, we are guaranteed that x * x == x_squared, which already proves (correct_x, y) is on the curve
+                        Some(<#struct_name as TwistedEdwardsPoint>::from_xy_unchecked(correct_x, y))
+                    }
+                }
+            }
+        });
+        output.push(result);
+    }
+
+    TokenStream::from_iter(output)
+}
+
+struct TeDefine {
+    items: Vec<Path>,
+}
+
+impl Parse for TeDefine {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let items = input.parse_terminated(<Expr as Parse>::parse, Token![,])?;
+        Ok(Self {
+            items: items
+                .into_iter()
+                .map(|e| {
+                    if let Expr::Path(p) = e {
+                        p.path
+                    } else {
+                        panic!("expected path");
+                    }
+                })
+                .collect(),
+        })
+    }
+}
+
+#[proc_macro]
+pub fn te_init(input: TokenStream) -> TokenStream {
+    let TeDefine { items } = parse_macro_input!(input as TeDefine);
+
+    let mut externs = Vec::new();
+
+    let span = proc_macro::Span::call_site();
+
+    for (ec_idx, item) in items.into_iter().enumerate() {
+        let str_path = item
+            .segments
+            .iter()
+            .map(|x| x.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("_");
+        let add_extern_func =
+            syn::Ident::new(&format!("te_add_extern_func_{}", str_path), span.into());
+        let setup_extern_func =
+            syn::Ident::new(&format!("te_setup_extern_func_{}", str_path), span.into());
+
+        externs.push(quote::quote_spanned! { span.into() =>
+            #[no_mangle]
+            extern "C" fn #add_extern_func(rd: usize, rs1: usize, rs2: usize) {
+                openvm::platform::custom_insn_r!(
+                    opcode = OPCODE,
+                    funct3 = TE_FUNCT3 as usize,
+                    funct7 = TeBaseFunct7::TeAdd as usize + #ec_idx
+                        * (TeBaseFunct7::TWISTED_EDWARDS_MAX_KINDS as usize),
+                    rd = In rd,
+                    rs1 = In rs1,
+                    rs2 = In rs2
+                );
+            }
+
+            #[no_mangle]
+            extern "C" fn #setup_extern_func() {
+                #[cfg(target_os = "zkvm")]
+                {
+                    use super::#item;
+                    let modulus_bytes = <<#item as openvm_ecc_guest::edwards::TwistedEdwardsPoint>::Coordinate as openvm_algebra_guest::IntMod>::MODULUS;
+                    let mut one = [0u8; <<#item as openvm_ecc_guest::edwards::TwistedEdwardsPoint>::Coordinate as openvm_algebra_guest::IntMod>::NUM_LIMBS];
+                    one[0] = 1;
+                    let curve_a_bytes = openvm_algebra_guest::IntMod::as_le_bytes(&<#item as openvm_ecc_guest::edwards::TwistedEdwardsPoint>::CURVE_A);
+                    let curve_d_bytes = openvm_algebra_guest::IntMod::as_le_bytes(&<#item as openvm_ecc_guest::edwards::TwistedEdwardsPoint>::CURVE_D);
+                    // p1 should be (p, a)
+                    let p1 = [modulus_bytes.as_ref(), curve_a_bytes.as_ref()].concat();
+                    // p2 should be (d, _). Since x1 = p is zero in the coordinate field, both
+                    // denominators of the addition formula are one.
+                    let p2 = [curve_d_bytes.as_ref(), one.as_ref()].concat();
+                    let mut uninit: core::mem::MaybeUninit<[#item; 2]> = core::mem::MaybeUninit::uninit();
+                    openvm::platform::custom_insn_r!(
+                        opcode = ::openvm_ecc_guest::OPCODE,
+                        funct3 = ::openvm_ecc_guest::TE_FUNCT3 as usize,
+                        funct7 = ::openvm_ecc_guest::TeBaseFunct7::TeSetup as usize
+                            + #ec_idx
+                                * (::openvm_ecc_guest::TeBaseFunct7::TWISTED_EDWARDS_MAX_KINDS as usize),
+                        rd = In uninit.as_mut_ptr(),
+                        rs1 = In p1.as_ptr(),
+                        rs2 = In p2.as_ptr()
+                    );
+                }
+            }
+        });
+    }
+
+    TokenStream::from(quote::quote_spanned! { span.into() =>
+        #[allow(non_snake_case)]
+        #[cfg(target_os = "zkvm")]
+        mod openvm_intrinsics_ffi_te {
+            use ::openvm_ecc_guest::{OPCODE, TE_FUNCT3, TeBaseFunct7};
+
+            #(#externs)*
+        }
+    })
+}
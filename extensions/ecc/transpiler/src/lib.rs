@@ -1,4 +1,4 @@
-use openvm_ecc_guest::{SwBaseFunct7, OPCODE, SW_FUNCT3};
+use openvm_ecc_guest::{SwBaseFunct7, TeBaseFunct7, OPCODE, SW_FUNCT3, TE_FUNCT3};
 use openvm_instructions::{
     instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, VmOpcode,
 };
@@ -21,6 +21,17 @@ pub enum Rv32WeierstrassOpcode {
     SETUP_EC_DOUBLE,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x680]
+#[allow(non_camel_case_types)]
+#[repr(usize)]
+pub enum Rv32EdwardsOpcode {
+    TE_ADD,
+    SETUP_TE_ADD,
+}
+
 #[derive(Default)]
 pub struct EccTranspilerExtension;
 
@@ -85,3 +96,43 @@ impl<F: PrimeField32> TranspilerExtension<F> for EccTranspilerExtension {
         instruction.map(TranspilerOutput::one_to_one)
     }
 }
+
+#[derive(Default)]
+pub struct EdwardsTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for EdwardsTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if opcode != OPCODE {
+            return None;
+        }
+        if funct3 != TE_FUNCT3 {
+            return None;
+        }
+
+        let instruction = {
+            // twisted edwards ec
+            assert!(Rv32EdwardsOpcode::COUNT <= TeBaseFunct7::TWISTED_EDWARDS_MAX_KINDS as usize);
+            let dec_insn = RType::new(instruction_u32);
+            let base_funct7 = (dec_insn.funct7 as u8) % TeBaseFunct7::TWISTED_EDWARDS_MAX_KINDS;
+            let curve_idx =
+                ((dec_insn.funct7 as u8) / TeBaseFunct7::TWISTED_EDWARDS_MAX_KINDS) as usize;
+            let curve_idx_shift = curve_idx * Rv32EdwardsOpcode::COUNT;
+            let local_opcode = match TeBaseFunct7::from_repr(base_funct7) {
+                Some(TeBaseFunct7::TeAdd) => Rv32EdwardsOpcode::TE_ADD,
+                Some(TeBaseFunct7::TeSetup) => Rv32EdwardsOpcode::SETUP_TE_ADD,
+                // Unknown functions are left to the other transpiler extensions.
+                _ => return None,
+            };
+            let global_opcode = local_opcode.global_opcode().as_usize() + curve_idx_shift;
+            Some(from_r_type(global_opcode, 2, &dec_insn, true))
+        };
+        instruction.map(TranspilerOutput::one_to_one)
+    }
+}
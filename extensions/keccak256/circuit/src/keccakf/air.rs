@@ -0,0 +1,265 @@
+use std::{array::from_fn, borrow::Borrow};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionState},
+    system::memory::{
+        offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+        MemoryAddress,
+    },
+};
+use openvm_circuit_primitives::{bitwise_op_lookup::BitwiseOperationLookupBus, utils::not};
+use openvm_instructions::riscv::{
+    RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS,
+};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::abstract_compose;
+use openvm_stark_backend::{
+    air_builders::sub::SubAirBuilder,
+    interaction::InteractionBuilder,
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::FieldAlgebra,
+    p3_matrix::Matrix,
+    rap::{BaseAirWithPublicValues, PartitionedBaseAir},
+};
+use p3_keccak_air::{KeccakAir, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, U64_LIMBS};
+
+use super::{
+    columns::{KeccakfVmCols, NUM_KECCAKF_VM_COLS},
+    KECCAKF_STATE_ACCESSES, KECCAKF_TIMESTAMP_DELTA, KECCAKF_WORD_SIZE,
+};
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct KeccakfVmAir {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    /// Bus to send 8-bit range check requests to.
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    /// Maximum number of bits allowed for an address pointer
+    pub ptr_max_bits: usize,
+    pub(super) offset: usize,
+}
+
+impl<F> BaseAirWithPublicValues<F> for KeccakfVmAir {}
+impl<F> PartitionedBaseAir<F> for KeccakfVmAir {}
+impl<F> BaseAir<F> for KeccakfVmAir {
+    fn width(&self) -> usize {
+        NUM_KECCAKF_VM_COLS
+    }
+}
+
+impl<AB: InteractionBuilder> Air<AB> for KeccakfVmAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &KeccakfVmCols<AB::Var> = (*local).borrow();
+        let next: &KeccakfVmCols<AB::Var> = (*next).borrow();
+
+        builder.assert_bool(local.instruction.is_enabled);
+        builder.assert_eq(
+            local.instruction.is_enabled_first_round,
+            local.instruction.is_enabled * local.is_first_round(),
+        );
+        // since keccak-f AIR has this column, we might as well use it
+        builder.assert_eq(
+            local.inner.export,
+            local.instruction.is_enabled * local.is_last_round(),
+        );
+
+        self.eval_keccak_f(builder);
+        self.constrain_consistency_across_rounds(builder, local, next);
+
+        let mem = &local.mem_oc;
+        // Interactions:
+        let start_read_timestamp = self.eval_instruction(builder, local, &mem.register_aux);
+        let start_write_timestamp =
+            self.constrain_state_read(builder, local, start_read_timestamp, &mem.state_reads);
+        self.constrain_state_write(builder, local, start_write_timestamp, &mem.state_writes);
+    }
+}
+
+impl KeccakfVmAir {
+    /// Evaluate the keccak-f permutation constraints.
+    ///
+    /// WARNING: The keccak-f AIR columns **must** be the first columns in the main AIR.
+    #[inline]
+    pub fn eval_keccak_f<AB: AirBuilder>(&self, builder: &mut AB) {
+        let keccak_f_air = KeccakAir {};
+        let mut sub_builder =
+            SubAirBuilder::<AB, KeccakAir, AB::Var>::new(builder, 0..NUM_KECCAK_PERM_COLS);
+        keccak_f_air.eval(&mut sub_builder);
+    }
+
+    /// The instruction columns are expected to be the same on all rounds of a permutation.
+    /// Unlike the sponge AIR, there is nothing carried over between permutations.
+    pub fn constrain_consistency_across_rounds<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        next: &KeccakfVmCols<AB::Var>,
+    ) {
+        let mut transition_builder = builder.when_transition();
+        let mut round_builder = transition_builder.when(not(local.is_last_round()));
+        local
+            .instruction
+            .assert_eq(&mut round_builder, next.instruction);
+    }
+
+    /// Receive the instruction itself on program bus. Send+receive on execution bus.
+    /// Then does memory read in addr space 1 to get `state_ptr` from memory.
+    ///
+    /// Adds a range check interaction for the most significant limb of the register value
+    /// using BitwiseOperationLookupBus.
+    ///
+    /// Returns `start_read_timestamp` which is only relevant when `local.instruction.is_enabled`.
+    pub fn eval_instruction<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        register_aux: &MemoryReadAuxCols<AB::Var>,
+    ) -> AB::Expr {
+        let instruction = local.instruction;
+        // Only receive opcode on the first round of an enabled permutation
+        let should_receive = instruction.is_enabled_first_round;
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(Rv32KeccakfOpcode::KECCAKF as usize + self.offset),
+                [
+                    instruction.rd_ptr.into(),
+                    AB::Expr::ZERO,
+                    AB::Expr::ZERO,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                ExecutionState::new(instruction.pc, instruction.start_timestamp),
+                AB::Expr::from_canonical_usize(KECCAKF_TIMESTAMP_DELTA),
+            )
+            .eval(builder, should_receive);
+
+        let timestamp: AB::Expr = instruction.start_timestamp.into();
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    instruction.rd_ptr,
+                ),
+                instruction.state_ptr,
+                timestamp.clone(),
+                register_aux,
+            )
+            .eval(builder, should_receive);
+
+        // See Rv32VecHeapAdapterAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.ptr_max_bits),
+        );
+        self.bitwise_lookup_bus
+            .send_range(
+                *instruction.state_ptr.last().unwrap() * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, should_receive);
+
+        timestamp + AB::Expr::ONE
+    }
+
+    /// Constrain reading the preimage from memory, one `u64` lane per memory access.
+    ///
+    /// We keep the `u16` limbs of the keccak-f AIR and use the same trick as the sponge AIR: we
+    /// provide the hi byte `hi = x >> 8` of each limb `x` and use `lo = x - hi * 256` for the low
+    /// byte. The preimage limbs are `u16`s by the keccak-f AIR and the memory cells in address
+    /// space 2 are bytes, so the byte decomposition read from memory is valid.
+    ///
+    /// Returns the `start_write_timestamp` which is the timestamp to start from
+    /// for writing the postimage to memory.
+    pub fn constrain_state_read<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        start_read_timestamp: AB::Expr,
+        mem_aux: &[MemoryReadAuxCols<AB::Var>; KECCAKF_STATE_ACCESSES],
+    ) -> AB::Expr {
+        let state_ptr = abstract_compose::<AB::Expr, _>(local.instruction.state_ptr);
+        let mut timestamp = start_read_timestamp;
+        for (i, aux) in mem_aux.iter().enumerate() {
+            let y = i / 5;
+            let x = i % 5;
+            // Conversion from bytes to u64 is little-endian
+            let lane: [AB::Expr; KECCAKF_WORD_SIZE] = from_fn(|j| {
+                let limb = j / 2;
+                let hi = local.state_hi[i * U64_LIMBS + limb];
+                if j % 2 == 0 {
+                    local.inner.preimage[y][x][limb] - hi * AB::F::from_canonical_u64(1 << 8)
+                } else {
+                    hi.into()
+                }
+            });
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        state_ptr.clone() + AB::F::from_canonical_usize(i * KECCAKF_WORD_SIZE),
+                    ),
+                    lane,
+                    timestamp.clone(),
+                    aux,
+                )
+                .eval(builder, local.instruction.is_enabled_first_round);
+
+            timestamp += AB::Expr::ONE;
+        }
+        timestamp
+    }
+
+    /// Constrain writing the postimage to memory, one `u64` lane per memory access.
+    ///
+    /// The postimage limbs are given by `a_prime_prime_prime()` in `u16` limbs, and we
+    /// decompose them into bytes as in [Self::constrain_state_read]. Unlike the preimage, both
+    /// bytes must be range checked, which is done via the bitwise lookup.
+    ///
+    /// The state pointer and timestamp are constant across rounds, so we can use the last round
+    /// values directly.
+    pub fn constrain_state_write<AB: InteractionBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakfVmCols<AB::Var>,
+        start_write_timestamp: AB::Expr,
+        mem_aux: &[MemoryWriteAuxCols<AB::Var, KECCAKF_WORD_SIZE>; KECCAKF_STATE_ACCESSES],
+    ) {
+        let state_ptr = abstract_compose::<AB::Expr, _>(local.instruction.state_ptr);
+        for (i, aux) in mem_aux.iter().enumerate() {
+            let y = i / 5;
+            let x = i % 5;
+            let limbs: [(AB::Expr, AB::Expr); U64_LIMBS] = from_fn(|limb| {
+                let hi = local.state_hi[i * U64_LIMBS + limb];
+                let lo = local.postimage(y, x, limb) - hi * AB::F::from_canonical_u64(1 << 8);
+                (lo, hi.into())
+            });
+            for (lo, hi) in limbs.iter() {
+                self.bitwise_lookup_bus
+                    .send_range(lo.clone(), hi.clone())
+                    .eval(builder, local.inner.export);
+            }
+            // Conversion from bytes to u64 is little-endian
+            let lane: [AB::Expr; KECCAKF_WORD_SIZE] = from_fn(|j| {
+                let (lo, hi) = &limbs[j / 2];
+                if j % 2 == 0 {
+                    lo.clone()
+                } else {
+                    hi.clone()
+                }
+            });
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        state_ptr.clone() + AB::F::from_canonical_usize(i * KECCAKF_WORD_SIZE),
+                    ),
+                    lane,
+                    start_write_timestamp.clone() + AB::F::from_canonical_usize(i),
+                    aux,
+                )
+                .eval(builder, local.inner.export);
+        }
+    }
+}
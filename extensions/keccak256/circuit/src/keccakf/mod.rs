@@ -0,0 +1,156 @@
+//! Raw keccak-f[1600] permutation on a 200-byte state stored in VM memory.
+//! The sponge (padding, absorb, squeeze) is left to the guest, which allows SHA-3, SHAKE and other
+//! keccak based constructions to share the same chip.
+use std::{
+    array::from_fn,
+    sync::{Arc, Mutex},
+};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{offline_checker::MemoryBridge, MemoryController, OfflineMemory, RecordId},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::bitwise_op_lookup::SharedBitwiseOperationLookupChip;
+use openvm_instructions::{instruction::Instruction, program::DEFAULT_PC_STEP, LocalOpcode};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::keccakf;
+
+use crate::KECCAK_WIDTH_BYTES;
+
+mod air;
+mod columns;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+
+#[cfg(test)]
+mod tests;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get the state pointer
+const KECCAKF_REGISTER_READS: usize = 1;
+/// Number of cells to read/write in a single memory access: one `u64` lane of the state
+const KECCAKF_WORD_SIZE: usize = 8;
+/// Number of `u64` lanes in the state
+pub const KECCAKF_STATE_LANES: usize = KECCAK_WIDTH_BYTES / KECCAKF_WORD_SIZE;
+/// Memory reads of the preimage, and memory writes of the postimage, per permutation
+const KECCAKF_STATE_ACCESSES: usize = KECCAKF_STATE_LANES;
+/// Amount to advance timestamp by after execution of one opcode instruction.
+pub const KECCAKF_TIMESTAMP_DELTA: usize = KECCAKF_REGISTER_READS + 2 * KECCAKF_STATE_ACCESSES;
+
+pub struct KeccakfVmChip<F: PrimeField32> {
+    pub air: KeccakfVmAir,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<KeccakfRecord<F>>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+
+    offset: usize,
+
+    offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct KeccakfRecord<F> {
+    pub pc: F,
+    pub state_ptr_read: RecordId,
+    pub state_reads: [RecordId; KECCAKF_STATE_ACCESSES],
+    pub state_writes: [RecordId; KECCAKF_STATE_ACCESSES],
+    /// The state before the permutation, as `u64` lanes
+    pub preimage: [u64; KECCAKF_STATE_LANES],
+}
+
+impl<F: PrimeField32> KeccakfVmChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_bridge: MemoryBridge,
+        address_bits: usize,
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+        offset: usize,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        Self {
+            air: KeccakfVmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                address_bits,
+                offset,
+            ),
+            bitwise_lookup_chip,
+            records: Vec::new(),
+            offset,
+            offline_memory,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for KeccakfVmChip<F> {
+    fn execute(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let &Instruction {
+            opcode, a, d, e, ..
+        } = instruction;
+        let local_opcode = Rv32KeccakfOpcode::from_usize(opcode.local_opcode_idx(self.offset));
+        debug_assert_eq!(local_opcode, Rv32KeccakfOpcode::KECCAKF);
+
+        let (state_ptr_read, state_ptr) = read_rv32_register(memory, d, a);
+        debug_assert!(state_ptr < (1 << self.air.ptr_max_bits));
+        let state_ptr = state_ptr as usize;
+
+        let mut state = [0u64; KECCAKF_STATE_LANES];
+        let state_reads = from_fn(|i| {
+            let (record_id, lane) = memory.read::<KECCAKF_WORD_SIZE>(
+                e,
+                F::from_canonical_usize(state_ptr + i * KECCAKF_WORD_SIZE),
+            );
+            // u64 <-> bytes conversion is little-endian
+            state[i] = u64::from_le_bytes(lane.map(|x| {
+                x.as_canonical_u32()
+                    .try_into()
+                    .expect("Memory cell not a byte")
+            }));
+            record_id
+        });
+        let preimage = state;
+        keccakf(&mut state);
+        let state_writes = from_fn(|i| {
+            memory
+                .write::<KECCAKF_WORD_SIZE>(
+                    e,
+                    F::from_canonical_usize(state_ptr + i * KECCAKF_WORD_SIZE),
+                    state[i].to_le_bytes().map(F::from_canonical_u8),
+                )
+                .0
+        });
+        tracing::trace!("[runtime] keccakf output: {:?}", state);
+
+        self.records.push(KeccakfRecord {
+            pc: F::from_canonical_u32(from_state.pc),
+            state_ptr_read,
+            state_reads,
+            state_writes,
+            preimage,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: from_state.timestamp + KECCAKF_TIMESTAMP_DELTA as u32,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        "KECCAKF".to_string()
+    }
+}
@@ -0,0 +1,122 @@
+use std::borrow::BorrowMut;
+
+use openvm_circuit::arch::testing::{VmChipTestBuilder, VmChipTester, BITWISE_OP_LOOKUP_BUS};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_keccak256_transpiler::Rv32KeccakfOpcode;
+use openvm_stark_backend::{
+    p3_field::FieldAlgebra, utils::disable_debug_builder, verifier::VerificationError,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_blake3::BabyBearBlake3Config, p3_baby_bear::BabyBear,
+    utils::create_seeded_rng,
+};
+use p3_keccak_air::NUM_ROUNDS;
+use rand::Rng;
+
+use super::{columns::KeccakfVmCols, KeccakfVmChip, KECCAKF_STATE_LANES};
+use crate::utils::keccak_f;
+
+type F = BabyBear;
+// io is vector of (state, prank_output) where prank_output is Some if the trace will be replaced
+fn build_keccakf_test(
+    io: Vec<([u64; KECCAKF_STATE_LANES], Option<u16>)>,
+) -> VmChipTester<BabyBearBlake3Config> {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<8>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = KeccakfVmChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        bitwise_chip.clone(),
+        Rv32KeccakfOpcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let mut state_ptr = 0;
+    for (state, _) in &io {
+        let a = 0;
+        let [d, e] = [1, 2];
+
+        tester.write(
+            d,
+            a,
+            (state_ptr as u32).to_le_bytes().map(F::from_canonical_u8),
+        );
+        for (i, lane) in state.iter().enumerate() {
+            for (j, byte) in lane.to_le_bytes().into_iter().enumerate() {
+                tester.write_cell(e, state_ptr + 8 * i + j, F::from_canonical_u8(byte));
+            }
+        }
+
+        tester.execute(
+            &mut chip,
+            &Instruction::from_isize(
+                Rv32KeccakfOpcode::KECCAKF.global_opcode(),
+                a as isize,
+                0,
+                0,
+                d as isize,
+                e as isize,
+            ),
+        );
+        let expected = keccak_f(*state);
+        for (i, lane) in expected.iter().enumerate() {
+            for (j, byte) in lane.to_le_bytes().into_iter().enumerate() {
+                assert_eq!(
+                    tester.read_cell(e, state_ptr + 8 * i + j),
+                    F::from_canonical_u8(byte)
+                );
+            }
+        }
+        // shift the state to not deal with timestamps for pranking
+        state_ptr += 200;
+    }
+    let mut tester = tester.build().load(chip).load(bitwise_chip).finalize();
+
+    let keccakf_trace = tester.air_proof_inputs[2]
+        .1
+        .raw
+        .common_main
+        .as_mut()
+        .unwrap();
+    for (idx, (_, prank_output)) in io.into_iter().enumerate() {
+        if let Some(out_limb) = prank_output {
+            let last_row: &mut KeccakfVmCols<_> = keccakf_trace
+                .row_mut((idx + 1) * NUM_ROUNDS - 1)
+                .borrow_mut();
+            last_row.inner.a_prime_prime_prime_0_0_limbs[0] = F::from_canonical_u16(out_limb);
+        }
+    }
+
+    tester
+}
+
+#[test]
+fn test_keccakf_positive() {
+    let mut rng = create_seeded_rng();
+    let mut io = vec![([0u64; KECCAKF_STATE_LANES], None)];
+    for _ in 0..4 {
+        io.push((rng.gen(), None));
+    }
+    let tester = build_keccakf_test(io);
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn test_keccakf_negative() {
+    let mut rng = create_seeded_rng();
+    let state: [u64; KECCAKF_STATE_LANES] = rng.gen();
+    let out_limb = (keccak_f(state)[0] as u16).wrapping_add(1);
+    let tester = build_keccakf_test(vec![(state, Some(out_limb))]);
+    disable_debug_builder();
+    assert_eq!(
+        tester.simple_test().err(),
+        Some(VerificationError::OodEvaluationMismatch)
+    );
+}
@@ -0,0 +1,152 @@
+use std::{array::from_fn, borrow::BorrowMut, sync::Arc};
+
+use openvm_instructions::riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_air::BaseAir,
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    p3_maybe_rayon::prelude::*,
+    prover::types::AirProofInput,
+    rap::get_air_name,
+    AirRef, Chip, ChipUsageGetter,
+};
+use p3_keccak_air::{
+    generate_trace_rows, NUM_KECCAK_COLS as NUM_KECCAK_PERM_COLS, NUM_ROUNDS, U64_LIMBS,
+};
+use tiny_keccak::keccakf;
+
+use super::{columns::KeccakfVmCols, KeccakfVmChip};
+use crate::KECCAK_WIDTH_U16S;
+
+impl<SC: StarkGenericConfig> Chip<SC> for KeccakfVmChip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> AirRef<SC> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        let trace_width = self.trace_width();
+        let records = self.records;
+        let memory = self.offline_memory.lock().unwrap();
+
+        // We need to transpose state matrices due to a plonky3 issue: https://github.com/Plonky3/Plonky3/issues/672
+        // See the sponge chip trace generation for details.
+        let p3_states = records
+            .iter()
+            .map(|record| {
+                // transpose of 5x5 matrix
+                from_fn(|i| {
+                    let x = i / 5;
+                    let y = i % 5;
+                    record.preimage[x + 5 * y]
+                })
+            })
+            .collect();
+        let p3_keccak_trace: RowMajorMatrix<Val<SC>> = generate_trace_rows(p3_states, 0);
+        let num_rows = p3_keccak_trace.height();
+        // Every `NUM_ROUNDS` rows corresponds to one permutation
+        let num_blocks = num_rows.div_ceil(NUM_ROUNDS);
+        // Resize with dummy `is_enabled = 0`
+        let mut records = records.into_iter().map(Some).collect::<Vec<_>>();
+        records.resize(num_blocks, None);
+
+        let aux_cols_factory = memory.aux_cols_factory();
+
+        let mut trace =
+            RowMajorMatrix::new(Val::<SC>::zero_vec(num_rows * trace_width), trace_width);
+        let limb_shift_bits = RV32_CELL_BITS * RV32_REGISTER_NUM_LIMBS - self.air.ptr_max_bits;
+
+        trace
+            .values
+            .par_chunks_mut(trace_width * NUM_ROUNDS)
+            .zip(
+                p3_keccak_trace
+                    .values
+                    .par_chunks(NUM_KECCAK_PERM_COLS * NUM_ROUNDS),
+            )
+            .zip(records.into_par_iter())
+            .for_each(|((rows, p3_keccak_mat), record)| {
+                let height = rows.len() / trace_width;
+                for (row, p3_keccak_row) in rows
+                    .chunks_exact_mut(trace_width)
+                    .zip(p3_keccak_mat.chunks_exact(NUM_KECCAK_PERM_COLS))
+                {
+                    // Safety: `KeccakPermCols` **must** be the first field in `KeccakfVmCols`
+                    row[..NUM_KECCAK_PERM_COLS].copy_from_slice(p3_keccak_row);
+                }
+                let Some(record) = record else {
+                    return;
+                };
+
+                let state_ptr_read = memory.record_by_id(record.state_ptr_read);
+                let state_ptr_msl = state_ptr_read.data_slice().last().unwrap();
+                self.bitwise_lookup_chip
+                    .request_range(state_ptr_msl.as_canonical_u32() << limb_shift_bits, 0);
+
+                let mut state = record.preimage;
+                let pre_hi: [u8; KECCAK_WIDTH_U16S] =
+                    from_fn(|i| (state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8);
+                keccakf(&mut state);
+                let post_hi: [u8; KECCAK_WIDTH_U16S] =
+                    from_fn(|i| (state[i / U64_LIMBS] >> ((i % U64_LIMBS) * 16 + 8)) as u8);
+                // Range check the postimage bytes, as (lo, hi) pairs of each u16 limb
+                for lane in state {
+                    for limb in lane.to_le_bytes().chunks_exact(2) {
+                        self.bitwise_lookup_chip
+                            .request_range(limb[0] as u32, limb[1] as u32);
+                    }
+                }
+
+                for row in rows.chunks_exact_mut(trace_width) {
+                    let row_mut: &mut KeccakfVmCols<Val<SC>> = row.borrow_mut();
+                    row_mut.instruction.pc = record.pc;
+                    row_mut.instruction.is_enabled = Val::<SC>::ONE;
+                    row_mut.instruction.start_timestamp =
+                        Val::<SC>::from_canonical_u32(state_ptr_read.timestamp);
+                    row_mut.instruction.rd_ptr = state_ptr_read.pointer;
+                    row_mut.instruction.state_ptr = state_ptr_read.data_slice().try_into().unwrap();
+                }
+
+                let first_row: &mut KeccakfVmCols<Val<SC>> = rows[..trace_width].borrow_mut();
+                first_row.instruction.is_enabled_first_round = Val::<SC>::ONE;
+                first_row.state_hi = pre_hi.map(Val::<SC>::from_canonical_u8);
+                aux_cols_factory
+                    .generate_read_aux(state_ptr_read, &mut first_row.mem_oc.register_aux);
+                for (i, id) in record.state_reads.into_iter().enumerate() {
+                    aux_cols_factory.generate_read_aux(
+                        memory.record_by_id(id),
+                        &mut first_row.mem_oc.state_reads[i],
+                    );
+                }
+
+                let last_row: &mut KeccakfVmCols<Val<SC>> =
+                    rows[(height - 1) * trace_width..].borrow_mut();
+                last_row.state_hi = post_hi.map(Val::<SC>::from_canonical_u8);
+                last_row.inner.export = Val::<SC>::ONE;
+                for (i, id) in record.state_writes.into_iter().enumerate() {
+                    aux_cols_factory.generate_write_aux(
+                        memory.record_by_id(id),
+                        &mut last_row.mem_oc.state_writes[i],
+                    );
+                }
+            });
+
+        AirProofInput::simple_no_pis(trace)
+    }
+}
+
+impl<F: PrimeField32> ChipUsageGetter for KeccakfVmChip<F> {
+    fn air_name(&self) -> String {
+        get_air_name(&self.air)
+    }
+    fn current_trace_height(&self) -> usize {
+        self.records.len() * NUM_ROUNDS
+    }
+
+    fn trace_width(&self) -> usize {
+        BaseAir::<F>::width(&self.air)
+    }
+}
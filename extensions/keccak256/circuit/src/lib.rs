@@ -15,6 +15,7 @@ use utils::num_keccak_f;
 
 pub mod air;
 pub mod columns;
+pub mod keccakf;
 pub mod trace;
 pub mod utils;
 
@@ -25,6 +26,7 @@ pub use extension::*;
 mod tests;
 
 pub use air::KeccakVmAir;
+pub use keccakf::{KeccakfVmAir, KeccakfVmChip};
 use openvm_circuit::{
     arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
     system::{
@@ -4,6 +4,8 @@
 pub const OPCODE: u8 = 0x0b;
 pub const KECCAK256_FUNCT3: u8 = 0b100;
 pub const KECCAK256_FUNCT7: u8 = 0;
+pub const KECCAKF_FUNCT3: u8 = 0b100;
+pub const KECCAKF_FUNCT7: u8 = 0x4;
 
 /// Native hook for keccak256 for use with `alloy-primitives` "native-keccak" feature.
 ///
@@ -30,3 +32,26 @@ pub extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8
         rs2 = In len
     );
 }
+
+/// Native hook for the keccak-f[1600] permutation.
+///
+/// # Safety
+///
+/// The VM permutes in place the 200-byte state stored at `state`, where lane `x + 5 * y` of the
+/// state is stored as a little-endian `u64` at byte offset `8 * (x + 5 * y)`.
+/// - `state` must point to a buffer that is at least 200-bytes long.
+///
+/// [`keccak-f`]: https://keccak.team/keccak_specs_summary.html
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn native_keccakf(state: *mut u8) {
+    openvm_platform::custom_insn_r!(
+        opcode = OPCODE,
+        funct3 = KECCAKF_FUNCT3,
+        funct7 = KECCAKF_FUNCT7,
+        rd = In state,
+        rs1 = Const "x0",
+        rs2 = Const "x0"
+    );
+}
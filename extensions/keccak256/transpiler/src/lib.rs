@@ -1,6 +1,8 @@
-use openvm_instructions::LocalOpcode;
+use openvm_instructions::{instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode};
 use openvm_instructions_derive::LocalOpcode;
-use openvm_keccak256_guest::{KECCAK256_FUNCT3, KECCAK256_FUNCT7, OPCODE};
+use openvm_keccak256_guest::{
+    KECCAK256_FUNCT3, KECCAK256_FUNCT7, KECCAKF_FUNCT3, KECCAKF_FUNCT7, OPCODE,
+};
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
 use rrs_lib::instruction_formats::RType;
@@ -15,6 +17,15 @@ pub enum Rv32KeccakOpcode {
     KECCAK256,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x318]
+#[repr(usize)]
+pub enum Rv32KeccakfOpcode {
+    KECCAKF,
+}
+
 #[derive(Default)]
 pub struct Keccak256TranspilerExtension;
 
@@ -43,3 +54,37 @@ impl<F: PrimeField32> TranspilerExtension<F> for Keccak256TranspilerExtension {
         Some(TranspilerOutput::one_to_one(instruction))
     }
 }
+
+#[derive(Default)]
+pub struct KeccakfTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for KeccakfTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, KECCAKF_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        if dec_insn.funct7 != KECCAKF_FUNCT7 as u32 {
+            return None;
+        }
+        // Only `rd` is used: it holds the pointer to the state. `rs1` and `rs2` are ignored.
+        let instruction = Instruction::new(
+            Rv32KeccakfOpcode::KECCAKF.global_opcode(),
+            F::from_canonical_usize(RV32_REGISTER_NUM_LIMBS * dec_insn.rd),
+            F::ZERO,
+            F::ZERO,
+            F::ONE,
+            F::TWO,
+            F::ZERO,
+            F::ZERO,
+        );
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
@@ -260,7 +260,7 @@ pub(crate) mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let hint = match streams.input_stream.pop_front() {
+            let hint = match streams.input_stream.pop_front()? {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
@@ -285,7 +285,7 @@ pub(crate) mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let hint = match streams.input_stream.pop_front() {
+            let hint = match streams.input_stream.pop_front()? {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
@@ -351,7 +351,7 @@ pub(crate) mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let payload = match streams.input_stream.pop_front() {
+            let payload = match streams.input_stream.pop_front()? {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
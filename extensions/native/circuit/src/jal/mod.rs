@@ -230,11 +230,17 @@ impl<F: PrimeField32> InstructionExecutor<F> for JalRangeCheckChip<F> {
             debug_assert!(!self.debug || c <= 14);
             let x = a_val & ((1 << 16) - 1);
             if !self.debug && x >= 1 << b {
-                return Err(ExecutionError::Fail { pc: from_state.pc });
+                return Err(ExecutionError::Fail {
+                    pc: from_state.pc,
+                    backtrace: None,
+                });
             }
             let y = a_val >> 16;
             if !self.debug && y >= 1 << c {
-                return Err(ExecutionError::Fail { pc: from_state.pc });
+                return Err(ExecutionError::Fail {
+                    pc: from_state.pc,
+                    backtrace: None,
+                });
             }
             self.records.push(JalRangeCheckRecord {
                 state: from_state,
@@ -164,7 +164,7 @@ where
             if streams.hint_stream.len() < NUM_CELLS {
                 return Err(ExecutionError::HintOutOfBounds { pc: from_pc });
             }
-            array::from_fn(|_| streams.hint_stream.pop_front().unwrap())
+            array::from_fn(|_| streams.pop_hint().unwrap())
         } else {
             data_read
         };
@@ -0,0 +1,299 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::ByteOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct ByteCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+
+    pub opcode_sext_b_flag: T,
+    pub opcode_sext_h_flag: T,
+    pub opcode_zext_h_flag: T,
+    pub opcode_rev8_flag: T,
+    pub opcode_orc_b_flag: T,
+
+    // Sign bit of the byte or halfword being sign extended
+    pub sign: T,
+
+    // For ORC.B, b_nonzero[i] = 1 iff b[i] != 0, and b_inv[i] is the inverse of b[i] if it
+    // exists
+    pub b_nonzero: [T; NUM_LIMBS],
+    pub b_inv: [T; NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ByteCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for ByteCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        ByteCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for ByteCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for ByteCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ByteCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_sext_b_flag,
+            cols.opcode_sext_h_flag,
+            cols.opcode_zext_h_flag,
+            cols.opcode_rev8_flag,
+            cols.opcode_orc_b_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let mask = AB::F::from_canonical_u32((1 << LIMB_BITS) - 1);
+
+        // Check sign & msb_limb == sign using XOR, where msb_limb is the most significant limb
+        // of the byte or halfword being sign extended.
+        let sign_extend = cols.opcode_sext_b_flag + cols.opcode_sext_h_flag;
+        builder.assert_bool(cols.sign);
+        builder
+            .when(not::<AB::Expr>(sign_extend.clone()))
+            .assert_zero(cols.sign);
+        let msb_limb = cols.opcode_sext_b_flag * b[0] + cols.opcode_sext_h_flag * b[1];
+        let sign_mask = AB::F::from_canonical_u32(1 << (LIMB_BITS - 1));
+        self.bus
+            .send_xor(
+                msb_limb.clone(),
+                sign_mask,
+                msb_limb + AB::Expr::from(sign_mask)
+                    - (AB::Expr::from_canonical_u32(2) * cols.sign * sign_mask),
+            )
+            .eval(builder, sign_extend);
+
+        // b_inv[i] can only exist if b[i] != 0, and b_nonzero[i] must be 1 if b[i] != 0.
+        for i in 0..NUM_LIMBS {
+            let mut when_orc_b = builder.when(cols.opcode_orc_b_flag);
+            when_orc_b.assert_eq(cols.b_nonzero[i], b[i] * cols.b_inv[i]);
+            when_orc_b.assert_zero(b[i] * not::<AB::Expr>(cols.b_nonzero[i]));
+        }
+
+        for i in 0..NUM_LIMBS {
+            let sign_limb = cols.sign * mask;
+            let expected_a = cols.opcode_sext_b_flag
+                * if i == 0 {
+                    b[0].into()
+                } else {
+                    sign_limb.clone()
+                }
+                + cols.opcode_sext_h_flag * if i < 2 { b[i].into() } else { sign_limb }
+                + cols.opcode_zext_h_flag * if i < 2 { b[i].into() } else { AB::Expr::ZERO }
+                + cols.opcode_rev8_flag * b[NUM_LIMBS - 1 - i]
+                + cols.opcode_orc_b_flag * cols.b_nonzero[i] * mask;
+            builder.assert_eq(a[i], expected_a);
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(ByteOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        // The rs2 operand must be the immediate 0.
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), array::from_fn(|_| AB::Expr::ZERO)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct ByteCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: ByteOpcode,
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    pub sign: T,
+}
+
+pub struct ByteCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: ByteCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> ByteCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        assert!(NUM_LIMBS >= 2, "Number of limbs must be at least 2");
+        Self {
+            air: ByteCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for ByteCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = ByteCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = ByteCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = ByteOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let (a, sign) = run_byte::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b);
+
+        let msb_limb = match local_opcode {
+            ByteOpcode::SEXT_B => Some(b[0]),
+            ByteOpcode::SEXT_H => Some(b[1]),
+            _ => None,
+        };
+        if let Some(msb_limb) = msb_limb {
+            self.bitwise_lookup_chip
+                .request_xor(msb_limb, 1 << (LIMB_BITS - 1));
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            sign: F::from_bool(sign),
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", ByteOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ByteCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        let is_orc_b = record.opcode == ByteOpcode::ORC_B;
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.opcode_sext_b_flag = F::from_bool(record.opcode == ByteOpcode::SEXT_B);
+        row_slice.opcode_sext_h_flag = F::from_bool(record.opcode == ByteOpcode::SEXT_H);
+        row_slice.opcode_zext_h_flag = F::from_bool(record.opcode == ByteOpcode::ZEXT_H);
+        row_slice.opcode_rev8_flag = F::from_bool(record.opcode == ByteOpcode::REV8);
+        row_slice.opcode_orc_b_flag = F::from_bool(is_orc_b);
+        row_slice.sign = record.sign;
+        row_slice.b_nonzero = record.b.map(|x| F::from_bool(is_orc_b && !x.is_zero()));
+        row_slice.b_inv = record.b.map(|x| {
+            if is_orc_b {
+                x.try_inverse().unwrap_or(F::ZERO)
+            } else {
+                F::ZERO
+            }
+        });
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, sign)
+pub(super) fn run_byte<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: ByteOpcode,
+    x: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], bool) {
+    let mask = (1 << LIMB_BITS) - 1;
+    let sign_of = |limb: u32| limb >> (LIMB_BITS - 1) == 1;
+    match opcode {
+        ByteOpcode::SEXT_B => {
+            let sign = sign_of(x[0]);
+            let fill = if sign { mask } else { 0 };
+            (array::from_fn(|i| if i == 0 { x[0] } else { fill }), sign)
+        }
+        ByteOpcode::SEXT_H => {
+            let sign = sign_of(x[1]);
+            let fill = if sign { mask } else { 0 };
+            (array::from_fn(|i| if i < 2 { x[i] } else { fill }), sign)
+        }
+        ByteOpcode::ZEXT_H => (array::from_fn(|i| if i < 2 { x[i] } else { 0 }), false),
+        ByteOpcode::REV8 => (array::from_fn(|i| x[NUM_LIMBS - 1 - i]), false),
+        ByteOpcode::ORC_B => (array::from_fn(|i| if x[i] == 0 { 0 } else { mask }), false),
+    }
+}
@@ -0,0 +1,122 @@
+use openvm_circuit::{
+    arch::testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::LocalOpcode;
+use openvm_rv32b_transpiler::ByteOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_byte, ByteCoreChip, Rv32ByteChip};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_byte_rand_test(opcode: ByteOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32ByteChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        ByteCoreChip::new(bitwise_chip.clone(), ByteOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        // Unary instructions take the immediate 0 as rs2
+        let (c_imm, c) = (Some(0), [0; RV32_REGISTER_NUM_LIMBS]);
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let (a, _) = run_byte::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_sext_b_rand_test() {
+    run_rv32_byte_rand_test(ByteOpcode::SEXT_B, 100);
+}
+
+#[test]
+fn rv32_sext_h_rand_test() {
+    run_rv32_byte_rand_test(ByteOpcode::SEXT_H, 100);
+}
+
+#[test]
+fn rv32_zext_h_rand_test() {
+    run_rv32_byte_rand_test(ByteOpcode::ZEXT_H, 100);
+}
+
+#[test]
+fn rv32_rev8_rand_test() {
+    run_rv32_byte_rand_test(ByteOpcode::REV8, 100);
+}
+
+#[test]
+fn rv32_orc_b_rand_test() {
+    run_rv32_byte_rand_test(ByteOpcode::ORC_B, 100);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_byte_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+    for _ in 0..100 {
+        let x: u32 = rng.gen::<u32>() & rng.gen::<u32>() & rng.gen::<u32>();
+        let orc_b = u32::from_le_bytes(x.to_le_bytes().map(|b| if b == 0 { 0 } else { 0xff }));
+        for (opcode, expected) in [
+            (ByteOpcode::SEXT_B, x as i8 as u32),
+            (ByteOpcode::SEXT_H, x as i16 as u32),
+            (ByteOpcode::ZEXT_H, x & 0xffff),
+            (ByteOpcode::REV8, x.swap_bytes()),
+            (ByteOpcode::ORC_B, orc_b),
+        ] {
+            let (result, _) =
+                run_byte::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &to_limbs(x));
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
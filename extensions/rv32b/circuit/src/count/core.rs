@@ -0,0 +1,360 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::CountOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct CountCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+
+    pub opcode_clz_flag: T,
+    pub opcode_ctz_flag: T,
+    pub opcode_cpop_flag: T,
+
+    // For CLZ (resp. CTZ), boolean columns that are 1 exactly at the index of the most (resp.
+    // least) significant nonzero limb of b, and at the index of the most (resp. least)
+    // significant one bit within that limb. All markers are 0 if b is zero.
+    pub limb_marker: [T; NUM_LIMBS],
+    pub bit_marker: [T; LIMB_BITS],
+    // The limb of b at limb_marker, and 2^(index of bit_marker)
+    pub marked_limb: T,
+    pub bit_multiplier: T,
+
+    // For CPOP, little-endian bit decomposition of each limb of b. Byte lookups cannot count
+    // bits, so CPOP is the only operation that decomposes b.
+    pub b_bits: [[T; LIMB_BITS]; NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct CountCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for CountCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        CountCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for CountCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for CountCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &CountCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_clz_flag,
+            cols.opcode_ctz_flag,
+            cols.opcode_cpop_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let mut is_nonzero = AB::Expr::ZERO;
+        let mut limb_index = AB::Expr::ZERO;
+        let mut marked_limb = AB::Expr::ZERO;
+        for i in 0..NUM_LIMBS {
+            builder.assert_bool(cols.limb_marker[i]);
+            is_nonzero += cols.limb_marker[i].into();
+            limb_index += AB::Expr::from_canonical_usize(i) * cols.limb_marker[i];
+            marked_limb += cols.limb_marker[i] * cols.b[i];
+        }
+        builder.assert_bool(is_nonzero.clone());
+        builder.assert_eq(cols.marked_limb, marked_limb);
+        builder
+            .when(cols.opcode_cpop_flag)
+            .assert_zero(is_nonzero.clone());
+
+        // Constrain that bit_multiplier = 1 << bit_index, as in ShiftCoreAir.
+        let mut bit_marker_sum = AB::Expr::ZERO;
+        let mut bit_index = AB::Expr::ZERO;
+        // 2^(LIMB_BITS - bit_index)
+        let mut bit_scale = AB::Expr::ZERO;
+        for j in 0..LIMB_BITS {
+            builder.assert_bool(cols.bit_marker[j]);
+            bit_marker_sum += cols.bit_marker[j].into();
+            bit_index += AB::Expr::from_canonical_usize(j) * cols.bit_marker[j];
+            bit_scale += AB::Expr::from_canonical_usize(1 << (LIMB_BITS - j)) * cols.bit_marker[j];
+            builder
+                .when(cols.bit_marker[j])
+                .assert_eq(cols.bit_multiplier, AB::Expr::from_canonical_usize(1 << j));
+        }
+        builder.assert_eq(bit_marker_sum, is_nonzero.clone());
+
+        // For CLZ, all limbs above the marked limb are zero, and for CTZ, all limbs below it. If
+        // there is no marked limb, b is zero.
+        for i in 0..NUM_LIMBS {
+            let marked_at_or_below = cols.limb_marker[..=i]
+                .iter()
+                .fold(AB::Expr::ZERO, |acc, &marker| acc + marker.into());
+            let marked_at_or_above = cols.limb_marker[i..]
+                .iter()
+                .fold(AB::Expr::ZERO, |acc, &marker| acc + marker.into());
+            builder
+                .when(cols.opcode_clz_flag)
+                .assert_zero(cols.b[i] * (AB::Expr::ONE - marked_at_or_above));
+            builder
+                .when(cols.opcode_ctz_flag)
+                .assert_zero(cols.b[i] * (AB::Expr::ONE - marked_at_or_below));
+        }
+
+        // For CLZ, the highest one bit of the marked limb is at bit_index iff
+        // 0 <= marked_limb - 2^bit_index < 2^bit_index, i.e. both it and its product with
+        // 2^(LIMB_BITS - bit_index) are bytes.
+        let low_bits = cols.marked_limb - cols.bit_multiplier;
+        self.bus
+            .send_range(low_bits.clone(), low_bits * bit_scale)
+            .eval(builder, cols.opcode_clz_flag * is_nonzero.clone());
+        // For CTZ, the lowest one bit of the marked limb is at bit_index iff
+        // marked_limb ^ (marked_limb - 1) = 2^(bit_index + 1) - 1.
+        self.bus
+            .send_xor(
+                cols.marked_limb,
+                cols.marked_limb - AB::Expr::ONE,
+                cols.bit_multiplier * AB::F::TWO - AB::Expr::ONE,
+            )
+            .eval(builder, cols.opcode_ctz_flag * is_nonzero.clone());
+
+        // Constrain the bit decomposition of b for CPOP. Limbs of b are read from registers, so
+        // the decomposition is unique.
+        let mut num_ones = AB::Expr::ZERO;
+        for i in 0..NUM_LIMBS {
+            let mut limb = AB::Expr::ZERO;
+            for j in 0..LIMB_BITS {
+                builder.assert_bool(cols.b_bits[i][j]);
+                limb += AB::Expr::from_canonical_u32(1 << j) * cols.b_bits[i][j];
+                num_ones += cols.b_bits[i][j].into();
+            }
+            builder
+                .when(cols.opcode_cpop_flag)
+                .assert_eq(cols.b[i], limb);
+        }
+
+        // If b is zero, both CLZ and CTZ are num_bits, and otherwise they are determined by the
+        // position of the marked bit.
+        let bit_position = limb_index * AB::F::from_canonical_usize(LIMB_BITS) + bit_index;
+        let num_bits = AB::Expr::from_canonical_usize(NUM_LIMBS * LIMB_BITS);
+        let is_zero = AB::Expr::ONE - is_nonzero.clone();
+        let count = cols.opcode_clz_flag * (num_bits.clone() - is_nonzero - bit_position.clone())
+            + cols.opcode_ctz_flag * (bit_position + is_zero * num_bits)
+            + cols.opcode_cpop_flag * num_ones;
+        builder.assert_eq(cols.a[0], count);
+        for i in 1..NUM_LIMBS {
+            builder.assert_zero(cols.a[i]);
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(CountOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        // The rs2 operand must be the immediate 0.
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), array::from_fn(|_| AB::Expr::ZERO)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct CountCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: CountOpcode,
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+}
+
+pub struct CountCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: CountCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> CountCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: CountCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for CountCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = CountCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = CountCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = CountOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let a = run_count::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b);
+
+        if let Some((limb_index, bit_index)) = marked_bit::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b)
+        {
+            let marked_limb = b[limb_index];
+            match local_opcode {
+                CountOpcode::CLZ => {
+                    let low_bits = marked_limb - (1 << bit_index);
+                    self.bitwise_lookup_chip
+                        .request_range(low_bits, low_bits << (LIMB_BITS - bit_index));
+                }
+                CountOpcode::CTZ => {
+                    self.bitwise_lookup_chip
+                        .request_xor(marked_limb, marked_limb - 1);
+                }
+                CountOpcode::CPOP => unreachable!(),
+            }
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", CountOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut CountCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        let b = record.b.map(|x| x.as_canonical_u32());
+        let marked = marked_bit::<NUM_LIMBS, LIMB_BITS>(record.opcode, &b);
+        let is_cpop = record.opcode == CountOpcode::CPOP;
+
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.opcode_clz_flag = F::from_bool(record.opcode == CountOpcode::CLZ);
+        row_slice.opcode_ctz_flag = F::from_bool(record.opcode == CountOpcode::CTZ);
+        row_slice.opcode_cpop_flag = F::from_bool(is_cpop);
+        row_slice.limb_marker =
+            array::from_fn(|i| F::from_bool(marked.is_some_and(|(l, _)| l == i)));
+        row_slice.bit_marker =
+            array::from_fn(|j| F::from_bool(marked.is_some_and(|(_, k)| k == j)));
+        row_slice.marked_limb = F::from_canonical_u32(marked.map_or(0, |(l, _)| b[l]));
+        row_slice.bit_multiplier = F::from_canonical_u32(marked.map_or(0, |(_, k)| 1 << k));
+        row_slice.b_bits =
+            array::from_fn(|i| array::from_fn(|j| F::from_bool(is_cpop && (b[i] >> j) & 1 == 1)));
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub(super) fn run_count<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: CountOpcode,
+    x: &[u32; NUM_LIMBS],
+) -> [u32; NUM_LIMBS] {
+    let bit = |k: usize| (x[k / LIMB_BITS] >> (k % LIMB_BITS)) & 1 == 1;
+    let num_bits = NUM_LIMBS * LIMB_BITS;
+    let count = match opcode {
+        CountOpcode::CLZ => (0..num_bits).rev().take_while(|&k| !bit(k)).count(),
+        CountOpcode::CTZ => (0..num_bits).take_while(|&k| !bit(k)).count(),
+        CountOpcode::CPOP => (0..num_bits).filter(|&k| bit(k)).count(),
+    };
+    let mut result = [0u32; NUM_LIMBS];
+    result[0] = count as u32;
+    result
+}
+
+/// Returns the limb and bit index within the limb of the most significant one bit of `x` for CLZ,
+/// and of the least significant one bit for CTZ. Returns `None` if `x` is zero or for CPOP.
+fn marked_bit<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: CountOpcode,
+    x: &[u32; NUM_LIMBS],
+) -> Option<(usize, usize)> {
+    let bit = |k: usize| (x[k / LIMB_BITS] >> (k % LIMB_BITS)) & 1 == 1;
+    let num_bits = NUM_LIMBS * LIMB_BITS;
+    let position = match opcode {
+        CountOpcode::CLZ => (0..num_bits).rev().find(|&k| bit(k)),
+        CountOpcode::CTZ => (0..num_bits).find(|&k| bit(k)),
+        CountOpcode::CPOP => None,
+    };
+    position.map(|k| (k / LIMB_BITS, k % LIMB_BITS))
+}
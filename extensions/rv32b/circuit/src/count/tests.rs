@@ -0,0 +1,246 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::CountOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::FieldAlgebra,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_count, CountCoreChip, CountCoreCols, Rv32CountChip};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_count_rand_test(opcode: CountOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32CountChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        CountCoreChip::new(bitwise_chip.clone(), CountOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for i in 0..num_ops {
+        // Include zero and values with zero limbs on either end
+        let b = match i % 3 {
+            0 if i == 0 => [0; RV32_REGISTER_NUM_LIMBS],
+            0 => generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            _ => {
+                let x: u32 = rng.gen::<u32>() >> rng.gen_range(0..32) << rng.gen_range(0..32);
+                x.to_le_bytes().map(u32::from)
+            }
+        };
+        // Unary instructions take the immediate 0 as rs2
+        let (c_imm, c) = (Some(0), [0; RV32_REGISTER_NUM_LIMBS]);
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let a = run_count::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_clz_rand_test() {
+    run_rv32_count_rand_test(CountOpcode::CLZ, 100);
+}
+
+#[test]
+fn rv32_ctz_rand_test() {
+    run_rv32_count_rand_test(CountOpcode::CTZ, 100);
+}
+
+#[test]
+fn rv32_cpop_rand_test() {
+    run_rv32_count_rand_test(CountOpcode::CPOP, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the marked bit and the write part of the trace and check that the core chip throws the
+// expected error. A dummy adapter is used so memory interactions don't indirectly cause false
+// passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32CountTestChip<F> =
+    VmChipWrapper<F, TestAdapterChip<F>, CountCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>>;
+
+fn run_rv32_count_negative_test(
+    opcode: CountOpcode,
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    marked_limb: usize,
+    marked_bit: usize,
+    expected_error: VerificationError,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32CountTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[
+                b.map(F::from_canonical_u32),
+                [F::ZERO; RV32_REGISTER_NUM_LIMBS],
+            ]
+            .concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        CountCoreChip::new(bitwise_chip.clone(), CountOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    tester.execute(
+        &mut chip,
+        &Instruction::from_usize(opcode.global_opcode(), [0, 0, 0, 1, 1]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    let position = marked_limb * RV32_CELL_BITS + marked_bit;
+    let count = match opcode {
+        CountOpcode::CLZ => RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - 1 - position,
+        _ => position,
+    };
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut CountCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.limb_marker = array::from_fn(|i| F::from_bool(i == marked_limb));
+        cols.bit_marker = array::from_fn(|j| F::from_bool(j == marked_bit));
+        cols.marked_limb = F::from_canonical_u32(b[marked_limb]);
+        cols.bit_multiplier = F::from_canonical_u32(1 << marked_bit);
+        cols.a[0] = F::from_canonical_usize(count);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn rv32_clz_wrong_limb_negative_test() {
+    run_rv32_count_negative_test(
+        CountOpcode::CLZ,
+        [0x01, 0x00, 0x10, 0x00],
+        0,
+        0,
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn rv32_clz_wrong_bit_negative_test() {
+    run_rv32_count_negative_test(
+        CountOpcode::CLZ,
+        [0x01, 0x00, 0x13, 0x00],
+        2,
+        1,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn rv32_ctz_wrong_limb_negative_test() {
+    run_rv32_count_negative_test(
+        CountOpcode::CTZ,
+        [0x00, 0x04, 0x10, 0x00],
+        2,
+        4,
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn rv32_ctz_wrong_bit_negative_test() {
+    run_rv32_count_negative_test(
+        CountOpcode::CTZ,
+        [0x00, 0x0c, 0x10, 0x00],
+        1,
+        3,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_count_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+    for _ in 0..100 {
+        // Bias towards values with runs of zero bits on either end
+        let x: u32 = rng.gen::<u32>() >> rng.gen_range(0..32) << rng.gen_range(0..32);
+        for (opcode, expected) in [
+            (CountOpcode::CLZ, x.leading_zeros()),
+            (CountOpcode::CTZ, x.trailing_zeros()),
+            (CountOpcode::CPOP, x.count_ones()),
+        ] {
+            let result = run_count::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &to_limbs(x));
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use derive_more::derive::From;
+use openvm_circuit::{
+    arch::{
+        InitFileGenerator, SystemConfig, SystemPort, VmExtension, VmInventory, VmInventoryBuilder,
+        VmInventoryError,
+    },
+    system::phantom::PhantomChip,
+};
+use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
+use openvm_instructions::LocalOpcode;
+use openvm_rv32b_transpiler::{
+    ByteOpcode, CountOpcode, LogicNotOpcode, MinMaxOpcode, RotateOpcode, ShAddOpcode,
+    SingleBitOpcode,
+};
+use openvm_rv32im_circuit::{
+    adapters::Rv32BaseAluAdapterChip, Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor,
+    Rv32IoPeriphery, Rv32M, Rv32MExecutor, Rv32MPeriphery,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::*;
+
+/// Config for a VM with the RV32IM extensions and the bit-manipulation extension
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Rv32BConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub rv32b: Rv32B,
+}
+
+// Default implementation uses no init file
+impl InitFileGenerator for Rv32BConfig {}
+
+impl Default for Rv32BConfig {
+    fn default() -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            rv32b: Rv32B,
+        }
+    }
+}
+
+/// RISC-V 32-bit Bit-Manipulation Extension, covering the Zba, Zbb and Zbs instructions.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32B;
+
+/// RISC-V 32-bit Bit-Manipulation Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32BExecutor<F: PrimeField32> {
+    LogicNot(Rv32LogicNotChip<F>),
+    MinMax(Rv32MinMaxChip<F>),
+    ShAdd(Rv32ShAddChip<F>),
+    Count(Rv32CountChip<F>),
+    Byte(Rv32ByteChip<F>),
+    Rotate(Rv32RotateChip<F>),
+    SingleBit(Rv32SingleBitChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32BPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32B {
+    type Executor = Rv32BExecutor<F>;
+    type Periphery = Rv32BPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let offline_memory = builder.system_base().offline_memory();
+
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+        let adapter = || {
+            Rv32BaseAluAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_bridge,
+                bitwise_lu_chip.clone(),
+            )
+        };
+
+        let logic_not_chip = Rv32LogicNotChip::new(
+            adapter(),
+            LogicNotCoreChip::new(bitwise_lu_chip.clone(), LogicNotOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            logic_not_chip,
+            LogicNotOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let min_max_chip = Rv32MinMaxChip::new(
+            adapter(),
+            MinMaxCoreChip::new(bitwise_lu_chip.clone(), MinMaxOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            min_max_chip,
+            MinMaxOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let sh_add_chip = Rv32ShAddChip::new(
+            adapter(),
+            ShAddCoreChip::new(bitwise_lu_chip.clone(), ShAddOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(sh_add_chip, ShAddOpcode::iter().map(|x| x.global_opcode()))?;
+
+        let count_chip = Rv32CountChip::new(
+            adapter(),
+            CountCoreChip::new(bitwise_lu_chip.clone(), CountOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(count_chip, CountOpcode::iter().map(|x| x.global_opcode()))?;
+
+        let byte_chip = Rv32ByteChip::new(
+            adapter(),
+            ByteCoreChip::new(bitwise_lu_chip.clone(), ByteOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(byte_chip, ByteOpcode::iter().map(|x| x.global_opcode()))?;
+
+        let rotate_chip = Rv32RotateChip::new(
+            adapter(),
+            RotateCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker,
+                RotateOpcode::CLASS_OFFSET,
+            ),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(rotate_chip, RotateOpcode::iter().map(|x| x.global_opcode()))?;
+
+        let single_bit_chip = Rv32SingleBitChip::new(
+            adapter(),
+            SingleBitCoreChip::new(bitwise_lu_chip.clone(), SingleBitOpcode::CLASS_OFFSET),
+            offline_memory,
+        );
+        inventory.add_executor(
+            single_bit_chip,
+            SingleBitOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        Ok(inventory)
+    }
+}
@@ -0,0 +1,18 @@
+mod byte;
+mod count;
+mod logic_not;
+mod min_max;
+mod rotate;
+mod sh_add;
+mod single_bit;
+
+pub use byte::*;
+pub use count::*;
+pub use logic_not::*;
+pub use min_max::*;
+pub use rotate::*;
+pub use sh_add::*;
+pub use single_bit::*;
+
+mod extension;
+pub use extension::*;
@@ -0,0 +1,240 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::LogicNotOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct LogicNotCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_andn_flag: T,
+    pub opcode_orn_flag: T,
+    pub opcode_xnor_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct LogicNotCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for LogicNotCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        LogicNotCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for LogicNotCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for LogicNotCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &LogicNotCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_andn_flag,
+            cols.opcode_orn_flag,
+            cols.opcode_xnor_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // Each operation is expressed in terms of x ^ y for x = b[i] and y = !c[i], using
+        // x & y = (x + y - (x ^ y)) / 2 and x | y = (x + y + (x ^ y)) / 2. The XOR lookup
+        // also range checks b[i] and c[i].
+        let mask = AB::Expr::from_canonical_u32((1 << LIMB_BITS) - 1);
+        for i in 0..NUM_LIMBS {
+            let c_not = mask.clone() - c[i];
+            let x_xor_y = cols.opcode_andn_flag
+                * (b[i] + c_not.clone() - (AB::Expr::from_canonical_u32(2) * a[i]))
+                + cols.opcode_orn_flag
+                    * ((AB::Expr::from_canonical_u32(2) * a[i]) - b[i] - c_not.clone())
+                + cols.opcode_xnor_flag * a[i];
+            self.bus
+                .send_xor(b[i], c_not, x_xor_y)
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(LogicNotOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct LogicNotCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: LogicNotOpcode,
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub c: [T; NUM_LIMBS],
+}
+
+pub struct LogicNotCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: LogicNotCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> LogicNotCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: LogicNotCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for LogicNotCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = LogicNotCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = LogicNotCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = LogicNotOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let a = run_logic_not::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b, &c);
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [a.map(F::from_canonical_u32)].into(),
+        };
+
+        let mask = (1 << LIMB_BITS) - 1;
+        for (b_val, c_val) in b.iter().zip(c.iter()) {
+            self.bitwise_lookup_chip.request_xor(*b_val, mask - *c_val);
+        }
+
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", LogicNotOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut LogicNotCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.opcode_andn_flag = F::from_bool(record.opcode == LogicNotOpcode::ANDN);
+        row_slice.opcode_orn_flag = F::from_bool(record.opcode == LogicNotOpcode::ORN);
+        row_slice.opcode_xnor_flag = F::from_bool(record.opcode == LogicNotOpcode::XNOR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub(super) fn run_logic_not<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: LogicNotOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> [u32; NUM_LIMBS] {
+    let mask = (1 << LIMB_BITS) - 1;
+    array::from_fn(|i| match opcode {
+        LogicNotOpcode::ANDN => x[i] & !y[i] & mask,
+        LogicNotOpcode::ORN => (x[i] | !y[i]) & mask,
+        LogicNotOpcode::XNOR => !(x[i] ^ y[i]) & mask,
+    })
+}
@@ -0,0 +1,16 @@
+use openvm_circuit::arch::VmChipWrapper;
+use openvm_rv32im_circuit::adapters::{
+    Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS,
+};
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32LogicNotChip<F> = VmChipWrapper<
+    F,
+    Rv32BaseAluAdapterChip<F>,
+    LogicNotCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
@@ -0,0 +1,210 @@
+use std::borrow::BorrowMut;
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::LogicNotOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::FieldAlgebra,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_logic_not, LogicNotCoreChip, LogicNotCoreCols, Rv32LogicNotChip};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_logic_not_rand_test(opcode: LogicNotOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32LogicNotChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        LogicNotCoreChip::new(bitwise_chip.clone(), LogicNotOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let a = run_logic_not::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_andn_rand_test() {
+    run_rv32_logic_not_rand_test(LogicNotOpcode::ANDN, 100);
+}
+
+#[test]
+fn rv32_orn_rand_test() {
+    run_rv32_logic_not_rand_test(LogicNotOpcode::ORN, 100);
+}
+
+#[test]
+fn rv32_xnor_rand_test() {
+    run_rv32_logic_not_rand_test(LogicNotOpcode::XNOR, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the write part of the trace and check that the core chip throws the expected error.
+// A dummy adapter is used so memory interactions don't indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32LogicNotTestChip<F> =
+    VmChipWrapper<F, TestAdapterChip<F>, LogicNotCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>>;
+
+fn run_rv32_logic_not_negative_test(
+    opcode: LogicNotOpcode,
+    a: [u32; RV32_REGISTER_NUM_LIMBS],
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    c: [u32; RV32_REGISTER_NUM_LIMBS],
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32LogicNotTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[b.map(F::from_canonical_u32), c.map(F::from_canonical_u32)].concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        LogicNotCoreChip::new(bitwise_chip.clone(), LogicNotOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    tester.execute(
+        &mut chip,
+        &Instruction::from_usize(opcode.global_opcode(), [0, 0, 0, 1, 1]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut LogicNotCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a = a.map(F::from_canonical_u32);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
+
+#[test]
+fn rv32_andn_wrong_negative_test() {
+    run_rv32_logic_not_negative_test(
+        LogicNotOpcode::ANDN,
+        [0xf1, 0x0f, 0x00, 0xff],
+        [0xff, 0x0f, 0x00, 0xff],
+        [0x0f, 0x00, 0x00, 0x00],
+    );
+}
+
+#[test]
+fn rv32_xnor_wrong_negative_test() {
+    run_rv32_logic_not_negative_test(
+        LogicNotOpcode::XNOR,
+        [0x00, 0xff, 0xff, 0xff],
+        [0xff, 0x00, 0x00, 0x00],
+        [0x00, 0x00, 0x00, 0x01],
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_logic_not_sanity_test() {
+    let mut rng = create_seeded_rng();
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+        for (opcode, expected) in [
+            (LogicNotOpcode::ANDN, x & !y),
+            (LogicNotOpcode::ORN, x | !y),
+            (LogicNotOpcode::XNOR, !(x ^ y)),
+        ] {
+            let result = run_logic_not::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode,
+                &to_limbs(x),
+                &to_limbs(y),
+            );
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::MinMaxOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct MinMaxCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    /// Whether b < c, as signed integers for MAX and MIN.
+    pub cmp_result: T,
+
+    pub opcode_max_flag: T,
+    pub opcode_maxu_flag: T,
+    pub opcode_min_flag: T,
+    pub opcode_minu_flag: T,
+
+    // Most significant limb of b and c respectively as a field element, will be range
+    // checked to be within [-128, 127) if signed, [0, 256) if unsigned.
+    pub b_msb_f: T,
+    pub c_msb_f: T,
+
+    // 1 at the most significant index i such that b[i] != c[i], otherwise 0. If such
+    // an i exists, diff_val = c[i] - b[i] if c[i] > b[i] or b[i] - c[i] else.
+    pub diff_marker: [T; NUM_LIMBS],
+    pub diff_val: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MinMaxCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for MinMaxCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        MinMaxCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for MinMaxCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for MinMaxCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &MinMaxCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_max_flag,
+            cols.opcode_maxu_flag,
+            cols.opcode_min_flag,
+            cols.opcode_minu_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+        builder.assert_bool(cols.cmp_result);
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+        let is_signed = cols.opcode_max_flag + cols.opcode_min_flag;
+        let is_max = cols.opcode_max_flag + cols.opcode_maxu_flag;
+        let is_min = cols.opcode_min_flag + cols.opcode_minu_flag;
+
+        // MAX selects c if b < c, and MIN selects b if b < c.
+        for i in 0..NUM_LIMBS {
+            let expected_a = is_max.clone() * (b[i] + cols.cmp_result * (c[i] - b[i]))
+                + is_min.clone() * (c[i] + cols.cmp_result * (b[i] - c[i]));
+            builder.assert_eq(a[i], expected_a);
+        }
+
+        // The comparison is constrained in the same way as in LessThanCoreAir.
+        let marker = &cols.diff_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+
+        let b_diff = b[NUM_LIMBS - 1] - cols.b_msb_f;
+        let c_diff = c[NUM_LIMBS - 1] - cols.c_msb_f;
+        builder
+            .assert_zero(b_diff.clone() * (AB::Expr::from_canonical_u32(1 << LIMB_BITS) - b_diff));
+        builder
+            .assert_zero(c_diff.clone() * (AB::Expr::from_canonical_u32(1 << LIMB_BITS) - c_diff));
+
+        for i in (0..NUM_LIMBS).rev() {
+            let diff = (if i == NUM_LIMBS - 1 {
+                cols.c_msb_f - cols.b_msb_f
+            } else {
+                c[i] - b[i]
+            }) * (AB::Expr::from_canonical_u8(2) * cols.cmp_result - AB::Expr::ONE);
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.diff_val, diff);
+        }
+
+        builder.assert_bool(prefix_sum.clone());
+        builder
+            .when(not::<AB::Expr>(prefix_sum.clone()))
+            .assert_zero(cols.cmp_result);
+
+        // Check if b_msb_f and c_msb_f are in [-128, 127) if signed, [0, 256) if unsigned.
+        self.bus
+            .send_range(
+                cols.b_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (LIMB_BITS - 1)) * is_signed.clone(),
+                cols.c_msb_f + AB::Expr::from_canonical_u32(1 << (LIMB_BITS - 1)) * is_signed,
+            )
+            .eval(builder, is_valid.clone());
+
+        // Range check to ensure diff_val is non-zero.
+        self.bus
+            .send_range(cols.diff_val - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, prefix_sum);
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(MinMaxOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct MinMaxCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: MinMaxOpcode,
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub c: [T; NUM_LIMBS],
+    pub cmp_result: T,
+    pub b_msb_f: T,
+    pub c_msb_f: T,
+    pub diff_val: T,
+    pub diff_idx: usize,
+}
+
+pub struct MinMaxCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: MinMaxCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> MinMaxCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: MinMaxCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for MinMaxCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = MinMaxCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = MinMaxCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = MinMaxOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, cmp_result, diff_idx, b_sign, c_sign) =
+            run_min_max::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b, &c);
+
+        // We range check (msb_f + 128) if signed and msb_f if not, as in LessThanCoreChip
+        let signed = is_signed(local_opcode);
+        let msb = |x: &[u32; NUM_LIMBS], sign: bool| {
+            let limb = x[NUM_LIMBS - 1];
+            if sign {
+                (
+                    -F::from_canonical_u32((1 << LIMB_BITS) - limb),
+                    limb - (1 << (LIMB_BITS - 1)),
+                )
+            } else {
+                (
+                    F::from_canonical_u32(limb),
+                    limb + ((signed as u32) << (LIMB_BITS - 1)),
+                )
+            }
+        };
+        let (b_msb_f, b_msb_range) = msb(&b, b_sign);
+        let (c_msb_f, c_msb_range) = msb(&c, c_sign);
+        self.bitwise_lookup_chip
+            .request_range(b_msb_range, c_msb_range);
+
+        let diff_val = if diff_idx == NUM_LIMBS {
+            0
+        } else if diff_idx == (NUM_LIMBS - 1) {
+            if cmp_result {
+                c_msb_f - b_msb_f
+            } else {
+                b_msb_f - c_msb_f
+            }
+            .as_canonical_u32()
+        } else if cmp_result {
+            c[diff_idx] - b[diff_idx]
+        } else {
+            b[diff_idx] - c[diff_idx]
+        };
+
+        if diff_idx != NUM_LIMBS {
+            self.bitwise_lookup_chip.request_range(diff_val - 1, 0);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = MinMaxCoreRecord {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            cmp_result: F::from_bool(cmp_result),
+            b_msb_f,
+            c_msb_f,
+            diff_val: F::from_canonical_u32(diff_val),
+            diff_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", MinMaxOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut MinMaxCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.cmp_result = record.cmp_result;
+        row_slice.b_msb_f = record.b_msb_f;
+        row_slice.c_msb_f = record.c_msb_f;
+        row_slice.diff_val = record.diff_val;
+        row_slice.opcode_max_flag = F::from_bool(record.opcode == MinMaxOpcode::MAX);
+        row_slice.opcode_maxu_flag = F::from_bool(record.opcode == MinMaxOpcode::MAXU);
+        row_slice.opcode_min_flag = F::from_bool(record.opcode == MinMaxOpcode::MIN);
+        row_slice.opcode_minu_flag = F::from_bool(record.opcode == MinMaxOpcode::MINU);
+        row_slice.diff_marker = array::from_fn(|i| F::from_bool(i == record.diff_idx));
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+fn is_signed(opcode: MinMaxOpcode) -> bool {
+    matches!(opcode, MinMaxOpcode::MAX | MinMaxOpcode::MIN)
+}
+
+// Returns (result, cmp_result, diff_idx, x_sign, y_sign), where cmp_result = x < y
+pub(super) fn run_min_max<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: MinMaxOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], bool, usize, bool, bool) {
+    let signed = is_signed(opcode);
+    let x_sign = (x[NUM_LIMBS - 1] >> (LIMB_BITS - 1) == 1) && signed;
+    let y_sign = (y[NUM_LIMBS - 1] >> (LIMB_BITS - 1) == 1) && signed;
+    let (cmp_result, diff_idx) = (0..NUM_LIMBS)
+        .rev()
+        .find(|&i| x[i] != y[i])
+        .map_or((false, NUM_LIMBS), |i| ((x[i] < y[i]) ^ x_sign ^ y_sign, i));
+    let result = match (opcode, cmp_result) {
+        (MinMaxOpcode::MAX | MinMaxOpcode::MAXU, true)
+        | (MinMaxOpcode::MIN | MinMaxOpcode::MINU, false) => *y,
+        _ => *x,
+    };
+    (result, cmp_result, diff_idx, x_sign, y_sign)
+}
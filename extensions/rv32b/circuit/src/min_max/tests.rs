@@ -0,0 +1,120 @@
+use openvm_circuit::{
+    arch::testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::LocalOpcode;
+use openvm_rv32b_transpiler::MinMaxOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::rv32_rand_write_register_or_imm,
+};
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_min_max, MinMaxCoreChip, Rv32MinMaxChip};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_min_max_rand_test(opcode: MinMaxOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MinMaxChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        MinMaxCoreChip::new(bitwise_chip.clone(), MinMaxOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let c_imm = None;
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let (a, _, _, _, _) =
+            run_min_max::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_max_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MAX, 100);
+}
+
+#[test]
+fn rv32_maxu_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MAXU, 100);
+}
+
+#[test]
+fn rv32_min_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MIN, 100);
+}
+
+#[test]
+fn rv32_minu_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MINU, 100);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_min_max_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        for (opcode, expected) in [
+            (MinMaxOpcode::MAX, (x as i32).max(y as i32) as u32),
+            (MinMaxOpcode::MAXU, x.max(y)),
+            (MinMaxOpcode::MIN, (x as i32).min(y as i32) as u32),
+            (MinMaxOpcode::MINU, x.min(y)),
+        ] {
+            let (result, ..) = run_min_max::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode,
+                &to_limbs(x),
+                &to_limbs(y),
+            );
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
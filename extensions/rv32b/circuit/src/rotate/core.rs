@@ -0,0 +1,332 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    var_range::{SharedVariableRangeCheckerChip, VariableRangeCheckerBus},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::RotateOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct RotateCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_rol_flag: T,
+    pub opcode_ror_flag: T,
+
+    // bit_multiplier = 2^bit_shift
+    pub bit_multiplier: T,
+
+    // Boolean columns that are 1 exactly at the index of the bit/limb rotation amount. Both
+    // ROL and ROR are constrained as a left rotation, by (-c) mod 32 for ROR.
+    pub bit_shift_marker: [T; LIMB_BITS],
+    pub limb_shift_marker: [T; NUM_LIMBS],
+
+    // Part of each b[i] that gets bit rotated to the next limb
+    pub bit_shift_carry: [T; NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct RotateCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    pub offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        RotateCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &RotateCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [cols.opcode_rol_flag, cols.opcode_ror_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // Constrain that bit_multiplier = 1 << bit_shift. Because the sum of all
+        // bit_shift_marker[i] is constrained to be 1, bit_shift is guaranteed to be in range.
+        let mut bit_marker_sum = AB::Expr::ZERO;
+        let mut bit_shift = AB::Expr::ZERO;
+
+        for i in 0..LIMB_BITS {
+            builder.assert_bool(cols.bit_shift_marker[i]);
+            bit_marker_sum += cols.bit_shift_marker[i].into();
+            bit_shift += AB::Expr::from_canonical_usize(i) * cols.bit_shift_marker[i];
+
+            builder
+                .when(cols.bit_shift_marker[i])
+                .assert_eq(cols.bit_multiplier, AB::Expr::from_canonical_usize(1 << i));
+        }
+        builder.when(is_valid.clone()).assert_one(bit_marker_sum);
+
+        // Check that a = b rotated left by limb_shift limbs and then by bit_shift bits. Each
+        // b[i] * bit_multiplier is split into a low part, which stays in the same limb, and
+        // bit_shift_carry[i], which is carried into the next limb.
+        let mut limb_marker_sum = AB::Expr::ZERO;
+        let mut limb_shift = AB::Expr::ZERO;
+        for i in 0..NUM_LIMBS {
+            builder.assert_bool(cols.limb_shift_marker[i]);
+            limb_marker_sum += cols.limb_shift_marker[i].into();
+            limb_shift += AB::Expr::from_canonical_usize(i) * cols.limb_shift_marker[i];
+
+            let mut when_limb_shift = builder.when(cols.limb_shift_marker[i]);
+            for j in 0..NUM_LIMBS {
+                let src = (j + NUM_LIMBS - i) % NUM_LIMBS;
+                let prev = (src + NUM_LIMBS - 1) % NUM_LIMBS;
+                when_limb_shift.assert_eq(
+                    a[j],
+                    b[src] * cols.bit_multiplier
+                        - AB::Expr::from_canonical_usize(1 << LIMB_BITS)
+                            * cols.bit_shift_carry[src]
+                        + cols.bit_shift_carry[prev],
+                );
+            }
+        }
+        builder.when(is_valid.clone()).assert_one(limb_marker_sum);
+
+        // Check that the rotation amount is c[0] mod NUM_BITS for ROL and -c[0] mod NUM_BITS
+        // for ROR. The quotient below is at most 2^LIMB_BITS / NUM_BITS + 1 in both cases.
+        let num_bits = AB::F::from_canonical_usize(NUM_LIMBS * LIMB_BITS);
+        let rotation = limb_shift * AB::F::from_canonical_usize(LIMB_BITS) + bit_shift.clone();
+        self.range_bus
+            .range_check(
+                (c[0] - cols.opcode_rol_flag * rotation.clone() + cols.opcode_ror_flag * rotation)
+                    * num_bits.inverse(),
+                LIMB_BITS + 1 - ((NUM_LIMBS * LIMB_BITS) as u32).ilog2() as usize,
+            )
+            .eval(builder, is_valid.clone());
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bitwise_lookup_bus
+                .send_range(a[i * 2], a[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        for carry in cols.bit_shift_carry {
+            self.range_bus
+                .send(carry, bit_shift.clone())
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags
+                .iter()
+                .zip(RotateOpcode::iter())
+                .fold(AB::Expr::ZERO, |acc, (flag, opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(opcode as u8)
+                }),
+        );
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct RotateCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub c: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub bit_shift_carry: [u32; NUM_LIMBS],
+    pub bit_shift: usize,
+    pub limb_shift: usize,
+    pub opcode: RotateOpcode,
+}
+
+pub struct RotateCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: RotateCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+    pub range_checker_chip: SharedVariableRangeCheckerChip,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> RotateCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        range_checker_chip: SharedVariableRangeCheckerChip,
+        offset: usize,
+    ) -> Self {
+        assert_eq!(NUM_LIMBS % 2, 0, "Number of limbs must be divisible by 2");
+        Self {
+            air: RotateCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for RotateCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = RotateCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = RotateCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let rotate_opcode = RotateOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, limb_shift, bit_shift) = run_rotate::<NUM_LIMBS, LIMB_BITS>(rotate_opcode, &b, &c);
+
+        let bit_shift_carry = array::from_fn(|i| b[i] >> (LIMB_BITS - bit_shift));
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bitwise_lookup_chip
+                .request_range(a[i * 2], a[i * 2 + 1]);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = RotateCoreRecord {
+            opcode: rotate_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            bit_shift_carry,
+            bit_shift,
+            limb_shift,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", RotateOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        for carry_val in record.bit_shift_carry {
+            self.range_checker_chip
+                .add_count(carry_val, record.bit_shift);
+        }
+
+        let num_bits_log = (NUM_LIMBS * LIMB_BITS).ilog2();
+        let rotation = record.bit_shift + record.limb_shift * LIMB_BITS;
+        let c = record.c[0].as_canonical_u32() as usize;
+        let quotient = match record.opcode {
+            RotateOpcode::ROL => c - rotation,
+            RotateOpcode::ROR => c + rotation,
+        } >> num_bits_log;
+        self.range_checker_chip
+            .add_count(quotient as u32, LIMB_BITS + 1 - num_bits_log as usize);
+
+        let row_slice: &mut RotateCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.bit_multiplier = F::from_canonical_usize(1 << record.bit_shift);
+        row_slice.bit_shift_marker = array::from_fn(|i| F::from_bool(i == record.bit_shift));
+        row_slice.limb_shift_marker = array::from_fn(|i| F::from_bool(i == record.limb_shift));
+        row_slice.bit_shift_carry = record.bit_shift_carry.map(F::from_canonical_u32);
+        row_slice.opcode_rol_flag = F::from_bool(record.opcode == RotateOpcode::ROL);
+        row_slice.opcode_ror_flag = F::from_bool(record.opcode == RotateOpcode::ROR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, limb_shift, bit_shift) of the equivalent left rotation
+pub(super) fn run_rotate<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: RotateOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], usize, usize) {
+    let num_bits = NUM_LIMBS * LIMB_BITS;
+    let shift = (y[0] as usize) % num_bits;
+    let rotation = match opcode {
+        RotateOpcode::ROL => shift,
+        RotateOpcode::ROR => (num_bits - shift) % num_bits,
+    };
+    let (limb_shift, bit_shift) = (rotation / LIMB_BITS, rotation % LIMB_BITS);
+
+    let result = array::from_fn(|i| {
+        let src = (i + NUM_LIMBS - limb_shift) % NUM_LIMBS;
+        let prev = (src + NUM_LIMBS - 1) % NUM_LIMBS;
+        ((x[src] << bit_shift) + (x[prev] >> (LIMB_BITS - bit_shift))) % (1 << LIMB_BITS)
+    });
+    (result, limb_shift, bit_shift)
+}
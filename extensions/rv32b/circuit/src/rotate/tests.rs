@@ -0,0 +1,118 @@
+use openvm_circuit::{
+    arch::testing::{VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::LocalOpcode;
+use openvm_rv32b_transpiler::RotateOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+use openvm_stark_backend::p3_field::FieldAlgebra;
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_rotate, RotateCoreChip, Rv32RotateChip};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_rotate_rand_test(opcode: RotateOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32RotateChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        RotateCoreChip::new(
+            bitwise_chip.clone(),
+            tester.memory_controller().borrow().range_checker.clone(),
+            RotateOpcode::CLASS_OFFSET,
+        ),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let (a, _, _) = run_rotate::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_rol_rand_test() {
+    run_rv32_rotate_rand_test(RotateOpcode::ROL, 100);
+}
+
+#[test]
+fn rv32_ror_rand_test() {
+    run_rv32_rotate_rand_test(RotateOpcode::ROR, 100);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_rotate_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        for (opcode, expected) in [
+            (RotateOpcode::ROL, x.rotate_left(y & 31)),
+            (RotateOpcode::ROR, x.rotate_right(y & 31)),
+        ] {
+            let (result, ..) = run_rotate::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode,
+                &to_limbs(x),
+                &to_limbs(y),
+            );
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::ShAddOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct ShAddCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_sh1add_flag: T,
+    pub opcode_sh2add_flag: T,
+    pub opcode_sh3add_flag: T,
+
+    // carry[i] is the part of (b[i] << n) + c[i] + carry[i - 1] that overflows a[i]
+    pub carry: [T; NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ShAddCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        ShAddCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ShAddCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_sh1add_flag,
+            cols.opcode_sh2add_flag,
+            cols.opcode_sh3add_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+        let multiplier = flags
+            .iter()
+            .enumerate()
+            .fold(AB::Expr::ZERO, |acc, (i, &flag)| {
+                acc + AB::Expr::from_canonical_u32(1 << (i + 1)) * flag
+            });
+
+        // Because a[i] and carry[i] are range checked to LIMB_BITS bits, both sides of the
+        // equation below are less than 2^(2 * LIMB_BITS) and hence equal as integers. This
+        // makes a[i] the unique remainder modulo 2^LIMB_BITS.
+        for i in 0..NUM_LIMBS {
+            let carry_in = if i > 0 {
+                cols.carry[i - 1].into()
+            } else {
+                AB::Expr::ZERO
+            };
+            builder.assert_eq(
+                a[i] + AB::Expr::from_canonical_u32(1 << LIMB_BITS) * cols.carry[i],
+                b[i] * multiplier.clone() + c[i] + carry_in,
+            );
+        }
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bus
+                .send_range(a[i * 2], a[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+            self.bus
+                .send_range(cols.carry[i * 2], cols.carry[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(ShAddOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct ShAddCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: ShAddOpcode,
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub c: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub carry: [T; NUM_LIMBS],
+}
+
+pub struct ShAddCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: ShAddCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> ShAddCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        assert_eq!(NUM_LIMBS % 2, 0, "Number of limbs must be divisible by 2");
+        Self {
+            air: ShAddCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for ShAddCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = ShAddCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = ShAddCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = ShAddOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, carry) = run_sh_add::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b, &c);
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [a.map(F::from_canonical_u32)].into(),
+        };
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bitwise_lookup_chip
+                .request_range(a[i * 2], a[i * 2 + 1]);
+            self.bitwise_lookup_chip
+                .request_range(carry[i * 2], carry[i * 2 + 1]);
+        }
+
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            carry: carry.map(F::from_canonical_u32),
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", ShAddOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ShAddCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.carry = record.carry;
+        row_slice.opcode_sh1add_flag = F::from_bool(record.opcode == ShAddOpcode::SH1ADD);
+        row_slice.opcode_sh2add_flag = F::from_bool(record.opcode == ShAddOpcode::SH2ADD);
+        row_slice.opcode_sh3add_flag = F::from_bool(record.opcode == ShAddOpcode::SH3ADD);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, carry)
+pub(super) fn run_sh_add<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: ShAddOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], [u32; NUM_LIMBS]) {
+    let shift = opcode as usize + 1;
+    let mut result = [0u32; NUM_LIMBS];
+    let mut carry = [0u32; NUM_LIMBS];
+    for i in 0..NUM_LIMBS {
+        let sum = (x[i] << shift) + y[i] + if i > 0 { carry[i - 1] } else { 0 };
+        result[i] = sum & ((1 << LIMB_BITS) - 1);
+        carry[i] = sum >> LIMB_BITS;
+    }
+    (result, carry)
+}
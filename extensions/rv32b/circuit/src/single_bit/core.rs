@@ -0,0 +1,360 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::SingleBitOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_big_array::BigArray;
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct SingleBitCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_bclr_flag: T,
+    pub opcode_bset_flag: T,
+    pub opcode_binv_flag: T,
+    pub opcode_bext_flag: T,
+
+    // bit_multiplier = 2^bit_index
+    pub bit_multiplier: T,
+
+    // Boolean columns that are 1 exactly at the index of the bit within its limb, and at the
+    // index of the limb containing the bit
+    pub bit_index_marker: [T; LIMB_BITS],
+    pub limb_index_marker: [T; NUM_LIMBS],
+
+    // The indexed limb of b is high * 2^(bit_index + 1) + bit * 2^bit_index + low
+    pub bit: T,
+    pub low: T,
+    pub high: T,
+
+    // Signed change of the indexed limb of b, which is 0 for BEXT
+    pub delta: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SingleBitCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    pub offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for SingleBitCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        SingleBitCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for SingleBitCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for SingleBitCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &SingleBitCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_bclr_flag,
+            cols.opcode_bset_flag,
+            cols.opcode_binv_flag,
+            cols.opcode_bext_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // Constrain that bit_multiplier = 1 << bit_index, as in ShiftCoreAir.
+        let mut bit_marker_sum = AB::Expr::ZERO;
+        let mut bit_index = AB::Expr::ZERO;
+        let mut low_shift = AB::Expr::ZERO;
+        for i in 0..LIMB_BITS {
+            builder.assert_bool(cols.bit_index_marker[i]);
+            bit_marker_sum += cols.bit_index_marker[i].into();
+            bit_index += AB::Expr::from_canonical_usize(i) * cols.bit_index_marker[i];
+            low_shift +=
+                AB::Expr::from_canonical_usize(1 << (LIMB_BITS - i)) * cols.bit_index_marker[i];
+
+            builder
+                .when(cols.bit_index_marker[i])
+                .assert_eq(cols.bit_multiplier, AB::Expr::from_canonical_usize(1 << i));
+        }
+        builder.when(is_valid.clone()).assert_one(bit_marker_sum);
+
+        let mut limb_marker_sum = AB::Expr::ZERO;
+        let mut limb_index = AB::Expr::ZERO;
+        let mut indexed_limb = AB::Expr::ZERO;
+        for i in 0..NUM_LIMBS {
+            builder.assert_bool(cols.limb_index_marker[i]);
+            limb_marker_sum += cols.limb_index_marker[i].into();
+            limb_index += AB::Expr::from_canonical_usize(i) * cols.limb_index_marker[i];
+            indexed_limb += cols.limb_index_marker[i] * b[i];
+        }
+        builder.when(is_valid.clone()).assert_one(limb_marker_sum);
+
+        // Because low and high are range checked to bit_index and LIMB_BITS - 1 - bit_index
+        // bits respectively, this uniquely determines bit. Both checks are done by shifting
+        // into the top bits of a limb, i.e. low * 2^(LIMB_BITS - bit_index) and
+        // high * 2^(bit_index + 1) must both be valid limbs.
+        builder.assert_bool(cols.bit);
+        builder.assert_eq(
+            indexed_limb,
+            cols.high * cols.bit_multiplier * AB::F::TWO
+                + cols.bit * cols.bit_multiplier
+                + cols.low,
+        );
+        self.bus
+            .send_range(
+                cols.low * low_shift,
+                cols.high * cols.bit_multiplier * AB::F::TWO,
+            )
+            .eval(builder, is_valid.clone());
+
+        // Check that the bit index is c[0] mod NUM_BITS, i.e. that the quotient q satisfies
+        // q < 2^(LIMB_BITS - log2(NUM_BITS)), by checking q and q * NUM_BITS are valid limbs.
+        let num_bits = AB::F::from_canonical_usize(NUM_LIMBS * LIMB_BITS);
+        let shifted_quotient =
+            c[0] - limb_index * AB::F::from_canonical_usize(LIMB_BITS) - bit_index;
+        self.bus
+            .send_range(
+                shifted_quotient.clone() * num_bits.inverse(),
+                shifted_quotient,
+            )
+            .eval(builder, is_valid.clone());
+
+        builder.assert_eq(
+            cols.delta,
+            cols.bit_multiplier
+                * (cols.opcode_bset_flag * not::<AB::Expr>(cols.bit)
+                    - cols.opcode_bclr_flag * cols.bit
+                    + cols.opcode_binv_flag * (AB::Expr::ONE - AB::Expr::TWO * cols.bit)),
+        );
+        for i in 0..NUM_LIMBS {
+            let extracted = if i == 0 {
+                cols.opcode_bext_flag * cols.bit
+            } else {
+                AB::Expr::ZERO
+            };
+            builder.assert_eq(
+                a[i],
+                not::<AB::Expr>(cols.opcode_bext_flag) * b[i]
+                    + cols.limb_index_marker[i] * cols.delta
+                    + extracted,
+            );
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(SingleBitOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(opcode as u8)
+                },
+            ),
+        );
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+pub struct SingleBitCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    #[serde(with = "BigArray")]
+    pub a: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub b: [T; NUM_LIMBS],
+    #[serde(with = "BigArray")]
+    pub c: [T; NUM_LIMBS],
+    pub bit_index: usize,
+    pub limb_index: usize,
+    pub opcode: SingleBitOpcode,
+}
+
+pub struct SingleBitCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: SingleBitCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> SingleBitCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<LIMB_BITS>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: SingleBitCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for SingleBitCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = SingleBitCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = SingleBitCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let single_bit_opcode =
+            SingleBitOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, limb_index, bit_index) =
+            run_single_bit::<NUM_LIMBS, LIMB_BITS>(single_bit_opcode, &b, &c);
+
+        let indexed_limb = b[limb_index];
+        let low = indexed_limb & ((1 << bit_index) - 1);
+        let high = indexed_limb >> (bit_index + 1);
+        self.bitwise_lookup_chip
+            .request_range(low << (LIMB_BITS - bit_index), high << (bit_index + 1));
+        let num_bits_log = (NUM_LIMBS * LIMB_BITS).ilog2();
+        let quotient = c[0] >> num_bits_log;
+        self.bitwise_lookup_chip
+            .request_range(quotient, quotient << num_bits_log);
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = SingleBitCoreRecord {
+            opcode: single_bit_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            bit_index,
+            limb_index,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            SingleBitOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let indexed_limb = record.b[record.limb_index].as_canonical_u32();
+        let bit = (indexed_limb >> record.bit_index) & 1;
+        let low = indexed_limb & ((1 << record.bit_index) - 1);
+        let high = indexed_limb >> (record.bit_index + 1);
+
+        let multiplier = F::from_canonical_u32(1 << record.bit_index);
+        let delta = match (record.opcode, bit) {
+            (SingleBitOpcode::BCLR | SingleBitOpcode::BINV, 1) => -multiplier,
+            (SingleBitOpcode::BSET | SingleBitOpcode::BINV, 0) => multiplier,
+            _ => F::ZERO,
+        };
+
+        let row_slice: &mut SingleBitCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.opcode_bclr_flag = F::from_bool(record.opcode == SingleBitOpcode::BCLR);
+        row_slice.opcode_bset_flag = F::from_bool(record.opcode == SingleBitOpcode::BSET);
+        row_slice.opcode_binv_flag = F::from_bool(record.opcode == SingleBitOpcode::BINV);
+        row_slice.opcode_bext_flag = F::from_bool(record.opcode == SingleBitOpcode::BEXT);
+        row_slice.bit_multiplier = multiplier;
+        row_slice.bit_index_marker = array::from_fn(|i| F::from_bool(i == record.bit_index));
+        row_slice.limb_index_marker = array::from_fn(|i| F::from_bool(i == record.limb_index));
+        row_slice.bit = F::from_canonical_u32(bit);
+        row_slice.low = F::from_canonical_u32(low);
+        row_slice.high = F::from_canonical_u32(high);
+        row_slice.delta = delta;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, limb_index, bit_index)
+pub(super) fn run_single_bit<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: SingleBitOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], usize, usize) {
+    let index = (y[0] as usize) % (NUM_LIMBS * LIMB_BITS);
+    let (limb_index, bit_index) = (index / LIMB_BITS, index % LIMB_BITS);
+    let mask = 1 << bit_index;
+
+    let mut result = *x;
+    match opcode {
+        SingleBitOpcode::BCLR => result[limb_index] &= !mask,
+        SingleBitOpcode::BSET => result[limb_index] |= mask,
+        SingleBitOpcode::BINV => result[limb_index] ^= mask,
+        SingleBitOpcode::BEXT => {
+            result = [0; NUM_LIMBS];
+            result[0] = (x[limb_index] >> bit_index) & 1;
+        }
+    }
+    (result, limb_index, bit_index)
+}
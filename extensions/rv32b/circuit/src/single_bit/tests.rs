@@ -0,0 +1,242 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, LocalOpcode};
+use openvm_rv32b_transpiler::SingleBitOpcode;
+use openvm_rv32im_circuit::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::FieldAlgebra,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_single_bit, Rv32SingleBitChip, SingleBitCoreChip, SingleBitCoreCols};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_single_bit_rand_test(opcode: SingleBitOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32SingleBitChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_bridge(),
+            bitwise_chip.clone(),
+        ),
+        SingleBitCoreChip::new(bitwise_chip.clone(), SingleBitOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            c,
+            c_imm,
+            opcode.global_opcode().as_usize(),
+            &mut rng,
+        );
+        tester.execute(&mut chip, &instruction);
+
+        let (a, _, _) = run_single_bit::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_bclr_rand_test() {
+    run_rv32_single_bit_rand_test(SingleBitOpcode::BCLR, 100);
+}
+
+#[test]
+fn rv32_bset_rand_test() {
+    run_rv32_single_bit_rand_test(SingleBitOpcode::BSET, 100);
+}
+
+#[test]
+fn rv32_binv_rand_test() {
+    run_rv32_single_bit_rand_test(SingleBitOpcode::BINV, 100);
+}
+
+#[test]
+fn rv32_bext_rand_test() {
+    run_rv32_single_bit_rand_test(SingleBitOpcode::BEXT, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the decomposition of the indexed limb and the write part of the trace and check that the
+// core chip throws the expected error. A dummy adapter is used so memory interactions don't
+// indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32SingleBitTestChip<F> = VmChipWrapper<
+    F,
+    TestAdapterChip<F>,
+    SingleBitCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+
+#[allow(clippy::too_many_arguments)]
+fn run_rv32_bext_negative_test(
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    c: [u32; RV32_REGISTER_NUM_LIMBS],
+    limb_index: usize,
+    bit_index: usize,
+    bit: u32,
+    low: u32,
+    high: u32,
+    expected_error: VerificationError,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32SingleBitTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[b.map(F::from_canonical_u32), c.map(F::from_canonical_u32)].concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        SingleBitCoreChip::new(bitwise_chip.clone(), SingleBitOpcode::CLASS_OFFSET),
+        tester.offline_memory_mutex_arc(),
+    );
+
+    tester.execute(
+        &mut chip,
+        &Instruction::from_usize(SingleBitOpcode::BEXT.global_opcode(), [0, 0, 0, 1, 1]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut SingleBitCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.bit_multiplier = F::from_canonical_u32(1 << bit_index);
+        cols.bit_index_marker = array::from_fn(|i| F::from_bool(i == bit_index));
+        cols.limb_index_marker = array::from_fn(|i| F::from_bool(i == limb_index));
+        cols.bit = F::from_canonical_u32(bit);
+        cols.low = F::from_canonical_u32(low);
+        cols.high = F::from_canonical_u32(high);
+        cols.a[0] = F::from_canonical_u32(bit);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn rv32_bext_wrong_bit_negative_test() {
+    // The indexed limb is 0b100, so bit 2 is set but is claimed to be part of low
+    run_rv32_bext_negative_test(
+        [4, 0, 0, 0],
+        [2, 0, 0, 0],
+        0,
+        2,
+        0,
+        4,
+        0,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn rv32_bext_wrong_index_negative_test() {
+    // Bit 10 is set but the instruction indexes bit 2
+    run_rv32_bext_negative_test(
+        [0, 4, 0, 0],
+        [2, 0, 0, 0],
+        1,
+        2,
+        1,
+        0,
+        0,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_single_bit_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let to_limbs = |v: u32| v.to_le_bytes().map(u32::from);
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        let mask = 1u32 << (y & 31);
+        for (opcode, expected) in [
+            (SingleBitOpcode::BCLR, x & !mask),
+            (SingleBitOpcode::BSET, x | mask),
+            (SingleBitOpcode::BINV, x ^ mask),
+            (SingleBitOpcode::BEXT, (x >> (y & 31)) & 1),
+        ] {
+            let (result, ..) = run_single_bit::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode,
+                &to_limbs(x),
+                &to_limbs(y),
+            );
+            assert_eq!(result, to_limbs(expected));
+        }
+    }
+}
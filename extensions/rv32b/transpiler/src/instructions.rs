@@ -0,0 +1,179 @@
+// =================================================================================================
+// RV32 bit-manipulation (Zba, Zbb, Zbs) support opcodes.
+// =================================================================================================
+
+use openvm_instructions::LocalOpcode;
+use openvm_instructions_derive::LocalOpcode;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, EnumIter, FromRepr};
+
+/// Bitwise operations with an inverted operand: `ANDN`, `ORN` and `XNOR`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2c0]
+#[repr(usize)]
+pub enum LogicNotOpcode {
+    ANDN,
+    ORN,
+    XNOR,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2c4]
+#[repr(usize)]
+pub enum MinMaxOpcode {
+    MAX,
+    MAXU,
+    MIN,
+    MINU,
+}
+
+/// Shift-and-add: `SHnADD rd, rs1, rs2` computes `(rs1 << n) + rs2`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2c8]
+#[repr(usize)]
+pub enum ShAddOpcode {
+    SH1ADD,
+    SH2ADD,
+    SH3ADD,
+}
+
+/// Bit counting. These are unary, the `rs2` operand is the immediate 0.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2cc]
+#[repr(usize)]
+pub enum CountOpcode {
+    CLZ,
+    CTZ,
+    CPOP,
+}
+
+/// Byte-level sign extension, zero extension and permutations. These are unary, the `rs2`
+/// operand is the immediate 0.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2d0]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum ByteOpcode {
+    SEXT_B,
+    SEXT_H,
+    ZEXT_H,
+    REV8,
+    ORC_B,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2d8]
+#[repr(usize)]
+pub enum RotateOpcode {
+    ROL,
+    ROR,
+}
+
+/// Single-bit operations on the bit of `rs1` indexed by `rs2`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x2dc]
+#[repr(usize)]
+pub enum SingleBitOpcode {
+    BCLR,
+    BSET,
+    BINV,
+    BEXT,
+}
@@ -0,0 +1,124 @@
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode, VmOpcode,
+};
+use openvm_rv32im_guest::RV32_ALU_OPCODE;
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{
+    util::{from_i_type_shamt, from_r_type, nop},
+    TranspilerExtension, TranspilerOutput,
+};
+use rrs_lib::instruction_formats::{ITypeShamt, RType};
+
+mod instructions;
+pub use instructions::*;
+
+const RV32_ALU_IMM_OPCODE: u8 = 0b0010011;
+
+const LOGIC_NOT_FUNCT7: u8 = 0b0100000;
+const SH_ADD_FUNCT7: u8 = 0b0010000;
+const MIN_MAX_FUNCT7: u8 = 0b0000101;
+const ZEXT_H_FUNCT7: u8 = 0b0000100;
+const ROTATE_FUNCT7: u8 = 0b0110000;
+const BCLR_FUNCT7: u8 = 0b0100100;
+const BINV_FUNCT7: u8 = 0b0110100;
+const BSET_FUNCT7: u8 = 0b0010100;
+
+/// Shift amount field of `REV8` on RV32.
+const REV8_SHAMT: u32 = 0b11000;
+/// Shift amount field of `ORC.B`.
+const ORC_B_SHAMT: u32 = 0b00111;
+
+/// Transpiler extension for the Zba, Zbb and Zbs bit-manipulation extensions, which together make
+/// up the B extension.
+#[derive(Default)]
+pub struct Rv32BTranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32BTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+        let funct7 = (instruction_u32 >> 25) as u8;
+
+        let instruction = match opcode {
+            RV32_ALU_OPCODE => {
+                let dec_insn = RType::new(instruction_u32);
+                let global_opcode = match (funct7, funct3) {
+                    (LOGIC_NOT_FUNCT7, 0b111) => LogicNotOpcode::ANDN.global_opcode(),
+                    (LOGIC_NOT_FUNCT7, 0b110) => LogicNotOpcode::ORN.global_opcode(),
+                    (LOGIC_NOT_FUNCT7, 0b100) => LogicNotOpcode::XNOR.global_opcode(),
+                    (SH_ADD_FUNCT7, 0b010) => ShAddOpcode::SH1ADD.global_opcode(),
+                    (SH_ADD_FUNCT7, 0b100) => ShAddOpcode::SH2ADD.global_opcode(),
+                    (SH_ADD_FUNCT7, 0b110) => ShAddOpcode::SH3ADD.global_opcode(),
+                    (MIN_MAX_FUNCT7, 0b100) => MinMaxOpcode::MIN.global_opcode(),
+                    (MIN_MAX_FUNCT7, 0b101) => MinMaxOpcode::MINU.global_opcode(),
+                    (MIN_MAX_FUNCT7, 0b110) => MinMaxOpcode::MAX.global_opcode(),
+                    (MIN_MAX_FUNCT7, 0b111) => MinMaxOpcode::MAXU.global_opcode(),
+                    (ZEXT_H_FUNCT7, 0b100) if dec_insn.rs2 == 0 => {
+                        let instruction = from_unary(
+                            ByteOpcode::ZEXT_H.global_opcode(),
+                            dec_insn.rd,
+                            dec_insn.rs1,
+                        );
+                        return Some(TranspilerOutput::one_to_one(instruction));
+                    }
+                    (ROTATE_FUNCT7, 0b001) => RotateOpcode::ROL.global_opcode(),
+                    (ROTATE_FUNCT7, 0b101) => RotateOpcode::ROR.global_opcode(),
+                    (BCLR_FUNCT7, 0b001) => SingleBitOpcode::BCLR.global_opcode(),
+                    (BCLR_FUNCT7, 0b101) => SingleBitOpcode::BEXT.global_opcode(),
+                    (BINV_FUNCT7, 0b001) => SingleBitOpcode::BINV.global_opcode(),
+                    (BSET_FUNCT7, 0b001) => SingleBitOpcode::BSET.global_opcode(),
+                    _ => return None,
+                };
+                from_r_type(global_opcode.as_usize(), 1, &dec_insn, false)
+            }
+            RV32_ALU_IMM_OPCODE => {
+                let dec_insn = ITypeShamt::new(instruction_u32);
+                let unary = |opcode: VmOpcode| from_unary(opcode, dec_insn.rd, dec_insn.rs1);
+                let shamt = |opcode: VmOpcode| from_i_type_shamt(opcode.as_usize(), &dec_insn);
+                match (funct7, funct3, dec_insn.shamt) {
+                    (ROTATE_FUNCT7, 0b001, 0b00000) => unary(CountOpcode::CLZ.global_opcode()),
+                    (ROTATE_FUNCT7, 0b001, 0b00001) => unary(CountOpcode::CTZ.global_opcode()),
+                    (ROTATE_FUNCT7, 0b001, 0b00010) => unary(CountOpcode::CPOP.global_opcode()),
+                    (ROTATE_FUNCT7, 0b001, 0b00100) => unary(ByteOpcode::SEXT_B.global_opcode()),
+                    (ROTATE_FUNCT7, 0b001, 0b00101) => unary(ByteOpcode::SEXT_H.global_opcode()),
+                    (BINV_FUNCT7, 0b101, REV8_SHAMT) => unary(ByteOpcode::REV8.global_opcode()),
+                    (BSET_FUNCT7, 0b101, ORC_B_SHAMT) => unary(ByteOpcode::ORC_B.global_opcode()),
+                    (ROTATE_FUNCT7, 0b101, _) => shamt(RotateOpcode::ROR.global_opcode()),
+                    (BCLR_FUNCT7, 0b001, _) => shamt(SingleBitOpcode::BCLR.global_opcode()),
+                    (BCLR_FUNCT7, 0b101, _) => shamt(SingleBitOpcode::BEXT.global_opcode()),
+                    (BINV_FUNCT7, 0b001, _) => shamt(SingleBitOpcode::BINV.global_opcode()),
+                    (BSET_FUNCT7, 0b001, _) => shamt(SingleBitOpcode::BSET.global_opcode()),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
+
+/// Create a new [`Instruction`] for a unary operation on `rs1`. The `rs2` operand is the
+/// immediate 0, so that the instruction can be executed with the base ALU adapter.
+fn from_unary<F: PrimeField32>(opcode: VmOpcode, rd: usize, rs1: usize) -> Instruction<F> {
+    if rd == 0 {
+        return nop();
+    }
+    Instruction::from_usize(
+        opcode,
+        [
+            RV32_REGISTER_NUM_LIMBS * rd,
+            RV32_REGISTER_NUM_LIMBS * rs1,
+            0,
+            RV32_REGISTER_AS as usize,
+            0,
+        ],
+    )
+}
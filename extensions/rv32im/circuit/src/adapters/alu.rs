@@ -57,11 +57,18 @@ impl<F: PrimeField32> Rv32BaseAluAdapterChip<F> {
                 execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
                 memory_bridge,
                 bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                pc_step: DEFAULT_PC_STEP,
             },
             bitwise_lookup_chip,
             _marker: PhantomData,
         }
     }
+
+    /// Sets the amount by which the pc is advanced, which is 2 for compressed instructions.
+    pub fn with_pc_step(mut self, pc_step: u32) -> Self {
+        self.air.pc_step = pc_step;
+        self
+    }
 }
 
 #[repr(C)]
@@ -107,6 +114,7 @@ pub struct Rv32BaseAluAdapterAir {
     pub(super) execution_bridge: ExecutionBridge,
     pub(super) memory_bridge: MemoryBridge,
     bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pc_step: u32,
 }
 
 impl<F: Field> BaseAir<F> for Rv32BaseAluAdapterAir {
@@ -202,7 +210,7 @@ impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32BaseAluAdapterAir {
                 ],
                 local.from_state,
                 AB::F::from_canonical_usize(timestamp_delta),
-                (DEFAULT_PC_STEP, ctx.to_pc),
+                (self.pc_step, ctx.to_pc),
             )
             .eval(builder, ctx.instruction.is_valid);
     }
@@ -292,7 +300,7 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32BaseAluAdapterChip<F> {
 
         Ok((
             ExecutionState {
-                pc: from_state.pc + DEFAULT_PC_STEP,
+                pc: from_state.pc + self.air.pc_step,
                 timestamp: memory.timestamp(),
             },
             Self::WriteRecord { from_state, rd },
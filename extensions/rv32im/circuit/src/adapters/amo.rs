@@ -0,0 +1,395 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+};
+
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
+        MinimalInstruction, Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{
+                MemoryBaseAuxCols, MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols,
+            },
+            MemoryAddress, MemoryController, OfflineMemory, RecordId,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::var_range::{
+    SharedVariableRangeCheckerChip, VariableRangeCheckerBus,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// The AMO adapter separates Runtime and Air AdapterInterfaces in the same way as the LoadStore
+/// adapter: `prev_data` of the memory word is owned by the core chip and must be an `AB::Var`
+/// in the AIR to complete the memory write aux columns.
+///
+/// Reads are `(prev_data, rs2)`. At runtime the shift of the memory pointer from a word boundary
+/// is also passed to the core chip, which raises a trap if it is non-zero.
+/// Writes are `[new memory word, rd]`.
+pub struct Rv32AmoAdapterRuntimeInterface<T>(PhantomData<T>);
+impl<T> VmAdapterInterface<T> for Rv32AmoAdapterRuntimeInterface<T> {
+    type Reads = ([[T; RV32_REGISTER_NUM_LIMBS]; 2], T);
+    type Writes = [[T; RV32_REGISTER_NUM_LIMBS]; 2];
+    type ProcessedInstruction = ();
+}
+pub struct Rv32AmoAdapterAirInterface<AB: InteractionBuilder>(PhantomData<AB>);
+
+/// Using AB::Var for prev_data and AB::Expr for rs2
+impl<AB: InteractionBuilder> VmAdapterInterface<AB::Expr> for Rv32AmoAdapterAirInterface<AB> {
+    type Reads = (
+        [AB::Var; RV32_REGISTER_NUM_LIMBS],
+        [AB::Expr; RV32_REGISTER_NUM_LIMBS],
+    );
+    type Writes = [[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2];
+    type ProcessedInstruction = MinimalInstruction<AB::Expr>;
+}
+
+/// This chip reads rs1 and rs2, then reads and writes the word at memory address rs1 in a single
+/// memory access, and writes the previous memory word (or the core's result) to rd.
+/// The memory address must be 4 byte aligned.
+pub struct Rv32AmoAdapterChip<F: Field> {
+    pub air: Rv32AmoAdapterAir,
+    pub range_checker_chip: SharedVariableRangeCheckerChip,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv32AmoAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_bridge: MemoryBridge,
+        pointer_max_bits: usize,
+        range_checker_chip: SharedVariableRangeCheckerChip,
+    ) -> Self {
+        assert!(range_checker_chip.range_max_bits() >= RV32_CELL_BITS);
+        assert!(pointer_max_bits > RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1));
+        Self {
+            air: Rv32AmoAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                range_bus: range_checker_chip.bus(),
+                pointer_max_bits,
+            },
+            range_checker_chip,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rv32AmoReadRecord {
+    pub rs1: RecordId,
+    pub rs2: RecordId,
+    pub mem_ptr: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rv32AmoWriteRecord {
+    pub from_state: ExecutionState<u32>,
+    pub mem_write: RecordId,
+    pub rd_id: Option<RecordId>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32AmoAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rs1_aux_cols: MemoryReadAuxCols<T>,
+    pub rs2_aux_cols: MemoryReadAuxCols<T>,
+    /// prev_data will be provided by the core chip to make a complete MemoryWriteAuxCols
+    pub mem_write_base_aux: MemoryBaseAuxCols<T>,
+    pub rd_aux_cols: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    /// Only writes rd if `needs_write`.
+    /// Sets `needs_write` to 0 iff `rd == x0`
+    pub needs_write: T,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32AmoAdapterAir {
+    pub(super) memory_bridge: MemoryBridge,
+    pub(super) execution_bridge: ExecutionBridge,
+    pub range_bus: VariableRangeCheckerBus,
+    pointer_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32AmoAdapterAir {
+    fn width(&self) -> usize {
+        Rv32AmoAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32AmoAdapterAir {
+    type Interface = Rv32AmoAdapterAirInterface<AB>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local_cols: &Rv32AmoAdapterCols<AB::Var> = local.borrow();
+
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::Expr::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let is_valid = ctx.instruction.is_valid;
+        let write_count = local_cols.needs_write;
+
+        // rd is only skipped when it is x0
+        builder.assert_bool(write_count);
+        builder.when(write_count).assert_one(is_valid.clone());
+        builder
+            .when(is_valid.clone() - write_count)
+            .assert_zero(local_cols.rd_ptr);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rs1_ptr,
+                ),
+                local_cols.rs1_data,
+                timestamp_pp(),
+                &local_cols.rs1_aux_cols,
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rs2_ptr,
+                ),
+                ctx.reads.1,
+                timestamp_pp(),
+                &local_cols.rs2_aux_cols,
+            )
+            .eval(builder, is_valid.clone());
+
+        // The memory pointer is rs1, which must be word aligned and < 2^pointer_max_bits.
+        // rs1_data[0] / 4 < 2^6 implies rs1_data[0] is a multiple of 4.
+        self.range_bus
+            .range_check(
+                local_cols.rs1_data[0] * AB::F::from_canonical_u32(4).inverse(),
+                RV32_CELL_BITS - 2,
+            )
+            .eval(builder, is_valid.clone());
+        self.range_bus
+            .range_check(
+                local_cols.rs1_data[RV32_REGISTER_NUM_LIMBS - 1],
+                self.pointer_max_bits - RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1),
+            )
+            .eval(builder, is_valid.clone());
+        let mem_ptr = local_cols
+            .rs1_data
+            .iter()
+            .rev()
+            .fold(AB::Expr::ZERO, |acc, &limb| {
+                acc * AB::F::from_canonical_u32(1 << RV32_CELL_BITS) + limb
+            });
+
+        let mem_write_aux =
+            MemoryWriteAuxCols::from_base(local_cols.mem_write_base_aux, ctx.reads.0);
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), mem_ptr),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &mem_write_aux,
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rd_ptr,
+                ),
+                ctx.writes[1].clone(),
+                timestamp_pp(),
+                &local_cols.rd_aux_cols,
+            )
+            .eval(builder, write_count);
+
+        self.execution_bridge
+            .execute(
+                ctx.instruction.opcode,
+                [
+                    local_cols.rd_ptr.into(),
+                    local_cols.rs1_ptr.into(),
+                    local_cols.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                    write_count.into(),
+                ],
+                local_cols.from_state,
+                ExecutionState {
+                    pc: local_cols.from_state.pc + AB::F::from_canonical_u32(DEFAULT_PC_STEP),
+                    timestamp: timestamp + AB::F::from_canonical_usize(timestamp_delta),
+                },
+            )
+            .eval(builder, is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32AmoAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32AmoAdapterChip<F> {
+    type ReadRecord = Rv32AmoReadRecord;
+    type WriteRecord = Rv32AmoWriteRecord;
+    type Air = Rv32AmoAdapterAir;
+    type Interface = Rv32AmoAdapterRuntimeInterface<F>;
+
+    #[allow(clippy::type_complexity)]
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, e, .. } = *instruction;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let rs1 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let rs2 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, c);
+
+        let ptr_val = compose(rs1.1);
+        assert!(
+            ptr_val < (1 << self.air.pointer_max_bits),
+            "ptr_val: {ptr_val} >= 2 ** {}",
+            self.air.pointer_max_bits
+        );
+        let shift_amount = ptr_val % 4;
+        let mem_ptr = ptr_val - shift_amount;
+
+        // The previous word is read as part of the memory write in `postprocess`.
+        let prev_data = std::array::from_fn(|i| {
+            memory.unsafe_read_cell(e, F::from_canonical_u32(mem_ptr + i as u32))
+        });
+
+        Ok((
+            ([prev_data, rs2.1], F::from_canonical_u32(shift_amount)),
+            Self::ReadRecord {
+                rs1: rs1.0,
+                rs2: rs2.0,
+                mem_ptr,
+            },
+        ))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction {
+            a,
+            d,
+            e,
+            f: enabled,
+            ..
+        } = *instruction;
+
+        let (mem_write, _) = memory.write(
+            e,
+            F::from_canonical_u32(read_record.mem_ptr),
+            output.writes[0],
+        );
+        let rd_id = if enabled != F::ZERO {
+            let (record_id, _) = memory.write(d, a, output.writes[1]);
+            Some(record_id)
+        } else {
+            memory.increment_timestamp();
+            None
+        };
+
+        Ok((
+            ExecutionState {
+                pc: from_state.pc + DEFAULT_PC_STEP,
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                mem_write,
+                rd_id,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        memory: &OfflineMemory<F>,
+    ) {
+        let aux_cols_factory = memory.aux_cols_factory();
+        let adapter_cols: &mut Rv32AmoAdapterCols<_> = row_slice.borrow_mut();
+        adapter_cols.from_state = write_record.from_state.map(F::from_canonical_u32);
+
+        let rs1 = memory.record_by_id(read_record.rs1);
+        adapter_cols.rs1_ptr = rs1.pointer;
+        adapter_cols.rs1_data.copy_from_slice(rs1.data_slice());
+        aux_cols_factory.generate_read_aux(rs1, &mut adapter_cols.rs1_aux_cols);
+        self.range_checker_chip
+            .add_count(rs1.data_at(0).as_canonical_u32() / 4, RV32_CELL_BITS - 2);
+        self.range_checker_chip.add_count(
+            rs1.data_at(RV32_REGISTER_NUM_LIMBS - 1).as_canonical_u32(),
+            self.air.pointer_max_bits - RV32_CELL_BITS * (RV32_REGISTER_NUM_LIMBS - 1),
+        );
+
+        let rs2 = memory.record_by_id(read_record.rs2);
+        adapter_cols.rs2_ptr = rs2.pointer;
+        aux_cols_factory.generate_read_aux(rs2, &mut adapter_cols.rs2_aux_cols);
+
+        let mem_write = memory.record_by_id(write_record.mem_write);
+        aux_cols_factory.generate_base_aux(mem_write, &mut adapter_cols.mem_write_base_aux);
+
+        if let Some(id) = write_record.rd_id {
+            let rd = memory.record_by_id(id);
+            adapter_cols.rd_ptr = rd.pointer;
+            adapter_cols.needs_write = F::ONE;
+            aux_cols_factory.generate_write_aux(rd, &mut adapter_cols.rd_aux_cols);
+        }
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    marker::PhantomData,
+};
+
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
+        ImmInstruction, Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryController, OfflineMemory, RecordId,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CYCLE_BASE_NUM_LIMBS, RV32_CYCLE_BASE_PTR, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+};
+use serde::{Deserialize, Serialize};
+
+use super::RV32_REGISTER_NUM_LIMBS;
+
+/// Reads are `(cycle_base, timestamp)`, where `cycle_base` is read from
+/// \[RV32_CYCLE_BASE_PTR:8\]_1 and `timestamp` is the one of the execution state.
+/// Writes are `[rd]`.
+pub struct Rv32CsrAdapterInterface<T>(PhantomData<T>);
+impl<T> VmAdapterInterface<T> for Rv32CsrAdapterInterface<T> {
+    type Reads = ([T; RV32_CYCLE_BASE_NUM_LIMBS], T);
+    type Writes = [[T; RV32_REGISTER_NUM_LIMBS]; 1];
+    type ProcessedInstruction = ImmInstruction<T>;
+}
+
+/// This adapter reads the cycle base and the timestamp of the execution state, and writes to
+/// \[a:4\]_d, where d == 1
+#[derive(Debug)]
+pub struct Rv32CsrAdapterChip<F: Field> {
+    pub air: Rv32CsrAdapterAir,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv32CsrAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_bridge: MemoryBridge,
+    ) -> Self {
+        Self {
+            air: Rv32CsrAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rv32CsrReadRecord {
+    pub cycle_base: RecordId,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rv32CsrWriteRecord {
+    pub from_state: ExecutionState<u32>,
+    pub rd_id: RecordId,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32CsrAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub cycle_base_aux_cols: MemoryReadAuxCols<T>,
+    pub rd_aux_cols: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32CsrAdapterAir {
+    pub(super) memory_bridge: MemoryBridge,
+    pub(super) execution_bridge: ExecutionBridge,
+}
+
+impl<F: Field> BaseAir<F> for Rv32CsrAdapterAir {
+    fn width(&self) -> usize {
+        Rv32CsrAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32CsrAdapterAir {
+    type Interface = Rv32CsrAdapterInterface<AB::Expr>;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local_cols: &Rv32CsrAdapterCols<AB::Var> = (*local).borrow();
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let (cycle_base, read_timestamp) = ctx.reads;
+        builder
+            .when(ctx.instruction.is_valid.clone())
+            .assert_eq(read_timestamp, timestamp);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::F::from_canonical_u32(RV32_CYCLE_BASE_PTR),
+                ),
+                cycle_base,
+                timestamp_pp(),
+                &local_cols.cycle_base_aux_cols,
+            )
+            .eval(builder, ctx.instruction.is_valid.clone());
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(
+                    AB::F::from_canonical_u32(RV32_REGISTER_AS),
+                    local_cols.rd_ptr,
+                ),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &local_cols.rd_aux_cols,
+            )
+            .eval(builder, ctx.instruction.is_valid.clone());
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                ctx.instruction.opcode,
+                [
+                    local_cols.rd_ptr.into(),
+                    AB::Expr::ZERO,
+                    ctx.instruction.immediate,
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                ],
+                local_cols.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+            )
+            .eval(builder, ctx.instruction.is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32CsrAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32CsrAdapterChip<F> {
+    type ReadRecord = Rv32CsrReadRecord;
+    type WriteRecord = Rv32CsrWriteRecord;
+    type Air = Rv32CsrAdapterAir;
+    type Interface = Rv32CsrAdapterInterface<F>;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let d = instruction.d;
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+
+        let timestamp = F::from_canonical_u32(memory.timestamp());
+        let (cycle_base, cycle_base_data) =
+            memory.read(d, F::from_canonical_u32(RV32_CYCLE_BASE_PTR));
+
+        Ok((
+            (cycle_base_data, timestamp),
+            Self::ReadRecord { cycle_base },
+        ))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        _read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, .. } = *instruction;
+        let (rd_id, _) = memory.write(d, a, output.writes[0]);
+
+        Ok((
+            ExecutionState {
+                pc: from_state.pc + DEFAULT_PC_STEP,
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord { from_state, rd_id },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        memory: &OfflineMemory<F>,
+    ) {
+        let aux_cols_factory = memory.aux_cols_factory();
+        let adapter_cols: &mut Rv32CsrAdapterCols<F> = row_slice.borrow_mut();
+        adapter_cols.from_state = write_record.from_state.map(F::from_canonical_u32);
+        let cycle_base = memory.record_by_id(read_record.cycle_base);
+        aux_cols_factory.generate_read_aux(cycle_base, &mut adapter_cols.cycle_base_aux_cols);
+        let rd = memory.record_by_id(write_record.rd_id);
+        adapter_cols.rd_ptr = rd.pointer;
+        aux_cols_factory.generate_write_aux(rd, &mut adapter_cols.rd_aux_cols);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
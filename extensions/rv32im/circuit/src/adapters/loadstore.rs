@@ -6,8 +6,8 @@ use std::{
 
 use openvm_circuit::{
     arch::{
-        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionState,
-        Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
+        AdapterAirContext, AdapterRuntimeContext, ExecutionBridge, ExecutionBus, ExecutionError,
+        ExecutionState, Result, VmAdapterAir, VmAdapterChip, VmAdapterInterface,
     },
     system::{
         memory::{
@@ -28,9 +28,12 @@ use openvm_instructions::{
     instruction::Instruction,
     program::DEFAULT_PC_STEP,
     riscv::{RV32_IMM_AS, RV32_REGISTER_AS},
-    LocalOpcode,
+    LocalOpcode, TrapCause,
+};
+use openvm_rv32im_transpiler::{
+    Rv32CLoadStoreOpcode,
+    Rv32LoadStoreOpcode::{self, *},
 };
-use openvm_rv32im_transpiler::Rv32LoadStoreOpcode::{self, *};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
     p3_air::{AirBuilder, BaseAir},
@@ -98,6 +101,7 @@ impl<AB: InteractionBuilder> VmAdapterInterface<AB::Expr> for Rv32LoadStoreAdapt
 pub struct Rv32LoadStoreAdapterChip<F: Field> {
     pub air: Rv32LoadStoreAdapterAir,
     pub range_checker_chip: SharedVariableRangeCheckerChip,
+    offset: usize,
     _marker: PhantomData<F>,
 }
 
@@ -116,11 +120,25 @@ impl<F: PrimeField32> Rv32LoadStoreAdapterChip<F> {
                 memory_bridge,
                 range_bus: range_checker_chip.bus(),
                 pointer_max_bits,
+                pc_step: DEFAULT_PC_STEP,
             },
             range_checker_chip,
+            offset: Rv32LoadStoreOpcode::CLASS_OFFSET,
             _marker: PhantomData,
         }
     }
+
+    /// Sets the offset of the [Rv32LoadStoreOpcode]s handled by the adapter.
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the amount by which the pc is advanced, which is 2 for compressed instructions.
+    pub fn with_pc_step(mut self, pc_step: u32) -> Self {
+        self.air.pc_step = pc_step;
+        self
+    }
 }
 
 #[repr(C)]
@@ -184,6 +202,7 @@ pub struct Rv32LoadStoreAdapterAir {
     pub(super) execution_bridge: ExecutionBridge,
     pub range_bus: VariableRangeCheckerBus,
     pointer_max_bits: usize,
+    pc_step: u32,
 }
 
 impl<F: Field> BaseAir<F> for Rv32LoadStoreAdapterAir {
@@ -338,7 +357,7 @@ impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32LoadStoreAdapterAir {
 
         let to_pc = ctx
             .to_pc
-            .unwrap_or(local_cols.from_state.pc + AB::F::from_canonical_u32(DEFAULT_PC_STEP));
+            .unwrap_or(local_cols.from_state.pc + AB::F::from_canonical_u32(self.pc_step));
         self.execution_bridge
             .execute(
                 ctx.instruction.opcode,
@@ -394,9 +413,7 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32LoadStoreAdapterChip<F> {
         debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
         debug_assert!(e.as_canonical_u32() != RV32_IMM_AS);
 
-        let local_opcode = Rv32LoadStoreOpcode::from_usize(
-            opcode.local_opcode_idx(Rv32LoadStoreOpcode::CLASS_OFFSET),
-        );
+        let local_opcode = Rv32LoadStoreOpcode::from_usize(opcode.local_opcode_idx(self.offset));
         let rs1_record = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
 
         let rs1_val = compose(rs1_record.1);
@@ -467,9 +484,7 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32LoadStoreAdapterChip<F> {
             ..
         } = *instruction;
 
-        let local_opcode = Rv32LoadStoreOpcode::from_usize(
-            opcode.local_opcode_idx(Rv32LoadStoreOpcode::CLASS_OFFSET),
-        );
+        let local_opcode = Rv32LoadStoreOpcode::from_usize(opcode.local_opcode_idx(self.offset));
 
         let write_id = if enabled != F::ZERO {
             let (record_id, _) = match local_opcode {
@@ -489,7 +504,7 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32LoadStoreAdapterChip<F> {
 
         Ok((
             ExecutionState {
-                pc: output.to_pc.unwrap_or(from_state.pc + DEFAULT_PC_STEP),
+                pc: output.to_pc.unwrap_or(from_state.pc + self.air.pc_step),
                 timestamp: memory.timestamp(),
             },
             Self::WriteRecord {
@@ -541,3 +556,86 @@ impl<F: PrimeField32> VmAdapterChip<F> for Rv32LoadStoreAdapterChip<F> {
         &self.air
     }
 }
+
+/// Returns [ExecutionError::Trap] if the load or store `instruction`, whose pointer is `shift`
+/// bytes past a word boundary, is not aligned to its access size.
+pub(crate) fn check_alignment<F: PrimeField32>(
+    instruction: &Instruction<F>,
+    opcode: Rv32LoadStoreOpcode,
+    pc: u32,
+    shift: u32,
+) -> Result<()> {
+    let (is_aligned, cause) = match opcode {
+        LOADW => (shift == 0, TrapCause::LoadAddressMisaligned),
+        LOADH | LOADHU => (shift % 2 == 0, TrapCause::LoadAddressMisaligned),
+        LOADB | LOADBU => (true, TrapCause::LoadAddressMisaligned),
+        STOREW => (shift == 0, TrapCause::StoreAddressMisaligned),
+        STOREH => (shift % 2 == 0, TrapCause::StoreAddressMisaligned),
+        STOREB => (true, TrapCause::StoreAddressMisaligned),
+    };
+    if is_aligned {
+        return Ok(());
+    }
+    Err(ExecutionError::Trap {
+        pc,
+        cause,
+        instruction: encode_load_store(instruction, opcode),
+        backtrace: None,
+    })
+}
+
+/// Recovers the RISC-V encoding of a transpiled load or store, which is the 16-bit encoding for
+/// [Rv32CLoadStoreOpcode]s.
+fn encode_load_store<F: PrimeField32>(
+    instruction: &Instruction<F>,
+    opcode: Rv32LoadStoreOpcode,
+) -> u32 {
+    let rd_rs2 = instruction.a.as_canonical_u32() / RV32_REGISTER_NUM_LIMBS as u32;
+    let rs1 = instruction.b.as_canonical_u32() / RV32_REGISTER_NUM_LIMBS as u32;
+    let imm = instruction.c.as_canonical_u32() & 0xfff;
+    if instruction.opcode == Rv32CLoadStoreOpcode(opcode).global_opcode() {
+        return encode_compressed_load_store(rd_rs2, rs1, imm, opcode == LOADW);
+    }
+    let (is_load, funct3) = match opcode {
+        LOADB => (true, 0b000),
+        LOADH => (true, 0b001),
+        LOADW => (true, 0b010),
+        LOADBU => (true, 0b100),
+        LOADHU => (true, 0b101),
+        STOREB => (false, 0b000),
+        STOREH => (false, 0b001),
+        STOREW => (false, 0b010),
+    };
+    if is_load {
+        (imm << 20) | (rs1 << 15) | (funct3 << 12) | (rd_rs2 << 7) | 0b0000011
+    } else {
+        ((imm >> 5) << 25)
+            | (rd_rs2 << 20)
+            | (rs1 << 15)
+            | (funct3 << 12)
+            | ((imm & 0x1f) << 7)
+            | 0b0100011
+    }
+}
+
+/// Encodes `C.LW`/`C.SW`, or `C.LWSP`/`C.SWSP` when the base register is `sp`.
+fn encode_compressed_load_store(rd_rs2: u32, rs1: u32, imm: u32, is_load: bool) -> u32 {
+    const SP: u32 = 2;
+    let funct3 = if is_load { 0b010 } else { 0b110 };
+    let bit = |i: u32| (imm >> i) & 1;
+    if rs1 == SP {
+        let fields = if is_load {
+            (bit(5) << 12) | (rd_rs2 << 7) | (((imm >> 2) & 0b111) << 4) | ((imm >> 6) << 2)
+        } else {
+            (((imm >> 2) & 0b1111) << 9) | ((imm >> 6) << 7) | (rd_rs2 << 2)
+        };
+        (funct3 << 13) | fields | 0b10
+    } else {
+        (funct3 << 13)
+            | (((imm >> 3) & 0b111) << 10)
+            | ((rs1 - 8) << 7)
+            | (bit(2) << 6)
+            | (bit(6) << 5)
+            | ((rd_rs2 - 8) << 2)
+    }
+}
@@ -4,14 +4,18 @@ use openvm_circuit::system::memory::{MemoryController, RecordId};
 use openvm_stark_backend::p3_field::{FieldAlgebra, PrimeField32};
 
 mod alu;
+mod amo;
 mod branch;
+mod csr;
 mod jalr;
 mod loadstore;
 mod mul;
 mod rdwrite;
 
 pub use alu::*;
+pub use amo::*;
 pub use branch::*;
+pub use csr::*;
 pub use jalr::*;
 pub use loadstore::*;
 pub use mul::*;
@@ -0,0 +1,433 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ExecutionError, MinimalInstruction, Result,
+    VmAdapterInterface, VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    utils::not,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, LocalOpcode, TrapCause};
+use openvm_rv32im_transpiler::{LessThanOpcode, Rv32AmoOpcode};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::{
+    adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    less_than::run_less_than,
+};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32AmoCoreCols<T> {
+    /// The memory word before the instruction, which is written to rd (except for `SC.W`).
+    pub prev_data: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The value of rs2.
+    pub rs2_data: [T; RV32_REGISTER_NUM_LIMBS],
+    /// The memory word after the instruction.
+    pub write_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub opcode_flags: [T; Rv32AmoOpcode::COUNT],
+
+    // Comparison of prev_data < rs2_data, signed for AMOMIN/AMOMAX and unsigned otherwise.
+    // The columns follow LessThanCoreCols and are filled for every opcode.
+    pub cmp_result: T,
+    pub prev_msb_f: T,
+    pub rs2_msb_f: T,
+    pub diff_marker: [T; RV32_REGISTER_NUM_LIMBS],
+    pub diff_val: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32AmoCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+}
+
+impl<F: Field> BaseAir<F> for Rv32AmoCoreAir {
+    fn width(&self) -> usize {
+        Rv32AmoCoreCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32AmoCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32AmoCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<(
+        [AB::Var; RV32_REGISTER_NUM_LIMBS],
+        [AB::Expr; RV32_REGISTER_NUM_LIMBS],
+    )>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 2]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32AmoCoreCols<AB::Var> = local_core.borrow();
+        let flags = &cols.opcode_flags;
+        let flag = |opcode: Rv32AmoOpcode| flags[opcode as usize];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+        builder.assert_bool(cols.cmp_result);
+
+        let prev = &cols.prev_data;
+        let rs2 = &cols.rs2_data;
+        let write = &cols.write_data;
+
+        // SWAP and SC store rs2
+        let is_swap = flag(Rv32AmoOpcode::AMOSWAP_W) + flag(Rv32AmoOpcode::SC_W);
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder.when(is_swap.clone()).assert_eq(write[i], rs2[i]);
+        }
+
+        // ADD is constrained as in BaseAluCoreAir, with write range checked below.
+        let carry_divide = AB::F::from_canonical_usize(1 << RV32_CELL_BITS).inverse();
+        let mut carry_add = AB::Expr::ZERO;
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            carry_add = AB::Expr::from(carry_divide) * (prev[i] + rs2[i] - write[i] + carry_add);
+            builder
+                .when(flag(Rv32AmoOpcode::AMOADD_W))
+                .assert_bool(carry_add.clone());
+        }
+
+        // MIN and MAX select one of the operands based on cmp_result = prev < rs2
+        let is_min = flag(Rv32AmoOpcode::AMOMIN_W) + flag(Rv32AmoOpcode::AMOMINU_W);
+        let is_max = flag(Rv32AmoOpcode::AMOMAX_W) + flag(Rv32AmoOpcode::AMOMAXU_W);
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder
+                .when(is_min.clone())
+                .assert_eq(write[i], rs2[i] + cols.cmp_result * (prev[i] - rs2[i]));
+            builder
+                .when(is_max.clone())
+                .assert_eq(write[i], prev[i] + cols.cmp_result * (rs2[i] - prev[i]));
+        }
+
+        // The comparison is constrained as in LessThanCoreAir for every valid row.
+        let is_signed = flag(Rv32AmoOpcode::AMOMIN_W) + flag(Rv32AmoOpcode::AMOMAX_W);
+        let marker = &cols.diff_marker;
+        let mut prefix_sum = AB::Expr::ZERO;
+
+        let prev_diff = prev[RV32_REGISTER_NUM_LIMBS - 1] - cols.prev_msb_f;
+        let rs2_diff = rs2[RV32_REGISTER_NUM_LIMBS - 1] - cols.rs2_msb_f;
+        builder.assert_zero(
+            prev_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - prev_diff),
+        );
+        builder.assert_zero(
+            rs2_diff.clone() * (AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS) - rs2_diff),
+        );
+
+        for i in (0..RV32_REGISTER_NUM_LIMBS).rev() {
+            let diff = (if i == RV32_REGISTER_NUM_LIMBS - 1 {
+                cols.rs2_msb_f - cols.prev_msb_f
+            } else {
+                rs2[i] - prev[i]
+            }) * (AB::Expr::from_canonical_u8(2) * cols.cmp_result - AB::Expr::ONE);
+            prefix_sum += marker[i].into();
+            builder.assert_bool(marker[i]);
+            builder.assert_zero(not::<AB::Expr>(prefix_sum.clone()) * diff.clone());
+            builder.when(marker[i]).assert_eq(cols.diff_val, diff);
+        }
+        builder.assert_bool(prefix_sum.clone());
+        builder
+            .when(not::<AB::Expr>(prefix_sum.clone()))
+            .assert_zero(cols.cmp_result);
+
+        // Check if prev_msb_f and rs2_msb_f are in [-128, 127) if signed, [0, 256) if unsigned.
+        self.bus
+            .send_range(
+                cols.prev_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed.clone(),
+                cols.rs2_msb_f
+                    + AB::Expr::from_canonical_u32(1 << (RV32_CELL_BITS - 1)) * is_signed,
+            )
+            .eval(builder, is_valid.clone());
+
+        // Range check to ensure diff_val is non-zero.
+        self.bus
+            .send_range(cols.diff_val - AB::Expr::ONE, AB::F::ZERO)
+            .eval(builder, prefix_sum);
+
+        // Interaction with BitwiseOperationLookup to constrain write for XOR, OR and AND, and
+        // range check it otherwise.
+        let bitwise = flag(Rv32AmoOpcode::AMOXOR_W)
+            + flag(Rv32AmoOpcode::AMOOR_W)
+            + flag(Rv32AmoOpcode::AMOAND_W);
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            let x = not::<AB::Expr>(bitwise.clone()) * write[i] + bitwise.clone() * prev[i];
+            let y = not::<AB::Expr>(bitwise.clone()) * write[i] + bitwise.clone() * rs2[i];
+            let x_xor_y = flag(Rv32AmoOpcode::AMOXOR_W) * write[i]
+                + flag(Rv32AmoOpcode::AMOOR_W)
+                    * ((AB::Expr::from_canonical_u32(2) * write[i]) - prev[i] - rs2[i])
+                + flag(Rv32AmoOpcode::AMOAND_W)
+                    * (prev[i] + rs2[i] - (AB::Expr::from_canonical_u32(2) * write[i]));
+            self.bus
+                .send_xor(x, y, x_xor_y)
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
+            self,
+            flags.iter().zip(Rv32AmoOpcode::iter()).fold(
+                AB::Expr::ZERO,
+                |acc, (flag, local_opcode)| {
+                    acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+                },
+            ),
+        );
+
+        // rd receives the previous memory word, or 0 for SC
+        let not_sc = is_valid.clone() - flag(Rv32AmoOpcode::SC_W);
+        let rd_data = prev.map(|x| not_sc.clone() * x);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: (cols.prev_data, cols.rs2_data.map(Into::into)).into(),
+            writes: [write.map(Into::into), rd_data].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        Rv32AmoOpcode::CLASS_OFFSET
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
+pub struct Rv32AmoCoreRecord<F> {
+    pub opcode: Rv32AmoOpcode,
+    pub prev_data: [F; RV32_REGISTER_NUM_LIMBS],
+    pub rs2_data: [F; RV32_REGISTER_NUM_LIMBS],
+    pub write_data: [F; RV32_REGISTER_NUM_LIMBS],
+    pub cmp_result: F,
+    pub prev_msb_f: F,
+    pub rs2_msb_f: F,
+    pub diff_val: F,
+    pub diff_idx: usize,
+}
+
+pub struct Rv32AmoCoreChip {
+    pub air: Rv32AmoCoreAir,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+}
+
+impl Rv32AmoCoreChip {
+    pub fn new(bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>) -> Self {
+        Self {
+            air: Rv32AmoCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for Rv32AmoCoreChip
+where
+    I::Reads: Into<([[F; RV32_REGISTER_NUM_LIMBS]; 2], F)>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 2]>,
+{
+    type Record = Rv32AmoCoreRecord<F>;
+    type Air = Rv32AmoCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let local_opcode = Rv32AmoOpcode::from_usize(
+            instruction
+                .opcode
+                .local_opcode_idx(Rv32AmoOpcode::CLASS_OFFSET),
+        );
+
+        let (data, shift_amount) = reads.into();
+        if shift_amount != F::ZERO {
+            return Err(ExecutionError::Trap {
+                pc: from_pc,
+                cause: TrapCause::StoreAddressMisaligned,
+                instruction: encode_amo(instruction, local_opcode),
+                backtrace: None,
+            });
+        }
+        let prev = data[0].map(|x| x.as_canonical_u32());
+        let rs2 = data[1].map(|x| x.as_canonical_u32());
+
+        let lt_opcode = if is_signed(local_opcode) {
+            LessThanOpcode::SLT
+        } else {
+            LessThanOpcode::SLTU
+        };
+        let (cmp_result, diff_idx, prev_sign, rs2_sign) =
+            run_less_than::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(lt_opcode, &prev, &rs2);
+        let write = run_amo(local_opcode, &prev, &rs2);
+
+        // We range check (msb_f + 128) if signed and msb_f if not, as in LessThanCoreChip
+        let msb = |x: &[u32; RV32_REGISTER_NUM_LIMBS], sign: bool| {
+            let limb = x[RV32_REGISTER_NUM_LIMBS - 1];
+            if sign {
+                (
+                    -F::from_canonical_u32((1 << RV32_CELL_BITS) - limb),
+                    limb - (1 << (RV32_CELL_BITS - 1)),
+                )
+            } else {
+                (
+                    F::from_canonical_u32(limb),
+                    limb + ((lt_opcode == LessThanOpcode::SLT) as u32)
+                        * (1 << (RV32_CELL_BITS - 1)),
+                )
+            }
+        };
+        let (prev_msb_f, prev_msb_range) = msb(&prev, prev_sign);
+        let (rs2_msb_f, rs2_msb_range) = msb(&rs2, rs2_sign);
+        self.bitwise_lookup_chip
+            .request_range(prev_msb_range, rs2_msb_range);
+
+        let diff_val = if diff_idx == RV32_REGISTER_NUM_LIMBS {
+            0
+        } else if diff_idx == RV32_REGISTER_NUM_LIMBS - 1 {
+            if cmp_result {
+                rs2_msb_f - prev_msb_f
+            } else {
+                prev_msb_f - rs2_msb_f
+            }
+            .as_canonical_u32()
+        } else if cmp_result {
+            rs2[diff_idx] - prev[diff_idx]
+        } else {
+            prev[diff_idx] - rs2[diff_idx]
+        };
+        if diff_idx != RV32_REGISTER_NUM_LIMBS {
+            self.bitwise_lookup_chip.request_range(diff_val - 1, 0);
+        }
+
+        match local_opcode {
+            Rv32AmoOpcode::AMOXOR_W | Rv32AmoOpcode::AMOOR_W | Rv32AmoOpcode::AMOAND_W => {
+                for (x, y) in prev.iter().zip(rs2.iter()) {
+                    self.bitwise_lookup_chip.request_xor(*x, *y);
+                }
+            }
+            _ => {
+                for x in write {
+                    self.bitwise_lookup_chip.request_xor(x, x);
+                }
+            }
+        }
+
+        let write_data = write.map(F::from_canonical_u32);
+        let rd_data = if local_opcode == Rv32AmoOpcode::SC_W {
+            [F::ZERO; RV32_REGISTER_NUM_LIMBS]
+        } else {
+            data[0]
+        };
+        let output = AdapterRuntimeContext::without_pc([write_data, rd_data]);
+
+        Ok((
+            output,
+            Rv32AmoCoreRecord {
+                opcode: local_opcode,
+                prev_data: data[0],
+                rs2_data: data[1],
+                write_data,
+                cmp_result: F::from_bool(cmp_result),
+                prev_msb_f,
+                rs2_msb_f,
+                diff_val: F::from_canonical_u32(diff_val),
+                diff_idx,
+            },
+        ))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32AmoOpcode::from_usize(opcode - Rv32AmoOpcode::CLASS_OFFSET)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let core_cols: &mut Rv32AmoCoreCols<F> = row_slice.borrow_mut();
+        core_cols.prev_data = record.prev_data;
+        core_cols.rs2_data = record.rs2_data;
+        core_cols.write_data = record.write_data;
+        core_cols.opcode_flags = array::from_fn(|i| F::from_bool(i == record.opcode as usize));
+        core_cols.cmp_result = record.cmp_result;
+        core_cols.prev_msb_f = record.prev_msb_f;
+        core_cols.rs2_msb_f = record.rs2_msb_f;
+        core_cols.diff_marker = array::from_fn(|i| F::from_bool(i == record.diff_idx));
+        core_cols.diff_val = record.diff_val;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+fn is_signed(opcode: Rv32AmoOpcode) -> bool {
+    matches!(opcode, Rv32AmoOpcode::AMOMIN_W | Rv32AmoOpcode::AMOMAX_W)
+}
+
+/// Returns the memory word written by `opcode`, given the previous word `x` and rs2 `y`.
+pub(super) fn run_amo(
+    opcode: Rv32AmoOpcode,
+    x: &[u32; RV32_REGISTER_NUM_LIMBS],
+    y: &[u32; RV32_REGISTER_NUM_LIMBS],
+) -> [u32; RV32_REGISTER_NUM_LIMBS] {
+    let compose = |limbs: &[u32; RV32_REGISTER_NUM_LIMBS]| {
+        limbs
+            .iter()
+            .rev()
+            .fold(0u32, |acc, &limb| (acc << RV32_CELL_BITS) | limb)
+    };
+    let (x, y) = (compose(x), compose(y));
+    let z = match opcode {
+        Rv32AmoOpcode::AMOSWAP_W | Rv32AmoOpcode::SC_W => y,
+        Rv32AmoOpcode::AMOADD_W => x.wrapping_add(y),
+        Rv32AmoOpcode::AMOXOR_W => x ^ y,
+        Rv32AmoOpcode::AMOAND_W => x & y,
+        Rv32AmoOpcode::AMOOR_W => x | y,
+        Rv32AmoOpcode::AMOMIN_W => (x as i32).min(y as i32) as u32,
+        Rv32AmoOpcode::AMOMAX_W => (x as i32).max(y as i32) as u32,
+        Rv32AmoOpcode::AMOMINU_W => x.min(y),
+        Rv32AmoOpcode::AMOMAXU_W => x.max(y),
+    };
+    z.to_le_bytes().map(u32::from)
+}
+
+/// Recovers the RISC-V encoding of a transpiled atomic memory operation.
+fn encode_amo<F: PrimeField32>(instruction: &Instruction<F>, opcode: Rv32AmoOpcode) -> u32 {
+    let [rd, rs1, rs2] = [instruction.a, instruction.b, instruction.c]
+        .map(|x| x.as_canonical_u32() / RV32_REGISTER_NUM_LIMBS as u32);
+    ((opcode.funct5() as u32) << 27)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (0b010 << 12)
+        | (rd << 7)
+        | 0b0101111
+}
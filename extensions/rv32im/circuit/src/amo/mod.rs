@@ -0,0 +1,11 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use crate::adapters::Rv32AmoAdapterChip;
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32AmoChip<F> = VmChipWrapper<F, Rv32AmoAdapterChip<F>, Rv32AmoCoreChip>;
@@ -0,0 +1,291 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::arch::{
+    testing::VmChipTestBuilder, ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, LocalOpcode, TrapCause};
+use openvm_rv32im_transpiler::Rv32AmoOpcode::{self, *};
+use openvm_stark_backend::{
+    interaction::BusIndex,
+    p3_air::BaseAir,
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    Chip, ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use strum::IntoEnumIterator;
+
+use super::{run_amo, Rv32AmoChip, Rv32AmoCoreChip, Rv32AmoCoreCols};
+use crate::adapters::{Rv32AmoAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+const BITWISE_OP_LOOKUP_BUS: BusIndex = 9;
+
+type F = BabyBear;
+
+/// Returns the instruction for `opcode` with distinct registers, after writing `ptr` to rs1, `rs2`
+/// to rs2 and `prev` to memory.
+fn prepare(
+    tester: &mut VmChipTestBuilder<F>,
+    rng: &mut StdRng,
+    opcode: Rv32AmoOpcode,
+    ptr: u32,
+    rs2: [u32; RV32_REGISTER_NUM_LIMBS],
+    prev: [u32; RV32_REGISTER_NUM_LIMBS],
+    write_rd: bool,
+) -> Instruction<F> {
+    let mut regs: Vec<usize> = (1..32).collect();
+    regs.shuffle(rng);
+    let [rd, rs1_ptr, rs2_ptr] = [regs[0], regs[1], regs[2]].map(|r| r * RV32_REGISTER_NUM_LIMBS);
+    let rd = if write_rd { rd } else { 0 };
+
+    tester.write(1, rs1_ptr, ptr.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2_ptr, rs2.map(F::from_canonical_u32));
+    tester.write(2, (ptr & !3) as usize, prev.map(F::from_canonical_u32));
+
+    Instruction::from_usize(
+        opcode.global_opcode(),
+        [rd, rs1_ptr, rs2_ptr, 1, 2, write_rd as usize],
+    )
+}
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32AmoChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32AmoOpcode,
+    rs2: Option<[u32; RV32_REGISTER_NUM_LIMBS]>,
+    prev: Option<[u32; RV32_REGISTER_NUM_LIMBS]>,
+) {
+    let ptr = rng.gen_range(0..(1 << (tester.address_bits() - 2))) << 2;
+    let rs2 = rs2.unwrap_or(array::from_fn(|_| rng.gen_range(0..(1 << RV32_CELL_BITS))));
+    let prev = prev.unwrap_or(array::from_fn(|_| rng.gen_range(0..(1 << RV32_CELL_BITS))));
+    let write_rd = rng.gen_bool(0.9);
+    let instruction = prepare(tester, rng, opcode, ptr, rs2, prev, write_rd);
+
+    tester.execute(chip, &instruction);
+
+    let write_data = run_amo(opcode, &prev, &rs2);
+    assert_eq!(
+        write_data.map(F::from_canonical_u32),
+        tester.read::<4>(2, ptr as usize)
+    );
+    let rd_data = match (write_rd, opcode) {
+        (false, _) | (true, SC_W) => [F::ZERO; RV32_REGISTER_NUM_LIMBS],
+        (true, _) => prev.map(F::from_canonical_u32),
+    };
+    assert_eq!(
+        rd_data,
+        tester.read::<4>(1, instruction.a.as_canonical_u32() as usize)
+    );
+}
+
+fn setup(
+    tester: &VmChipTestBuilder<F>,
+) -> (
+    Rv32AmoChip<F>,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let adapter = Rv32AmoAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        tester.range_checker(),
+    );
+    let core = Rv32AmoCoreChip::new(bitwise_chip.clone());
+    let chip = Rv32AmoChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
+    (chip, bitwise_chip)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rand_amo_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = setup(&tester);
+
+    let num_tests: usize = 20;
+    for _ in 0..num_tests {
+        for opcode in Rv32AmoOpcode::iter() {
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, None, None);
+        }
+    }
+    // Equal operands and operands that differ only in sign
+    for opcode in [AMOMIN_W, AMOMAX_W, AMOMINU_W, AMOMAXU_W] {
+        let x = [1, 2, 3, 4];
+        set_and_execute(&mut tester, &mut chip, &mut rng, opcode, Some(x), Some(x));
+        let y = [1, 2, 3, 0x84];
+        set_and_execute(&mut tester, &mut chip, &mut rng, opcode, Some(x), Some(y));
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the core part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_amo_test(
+    opcode: Rv32AmoOpcode,
+    rs2: [u32; RV32_REGISTER_NUM_LIMBS],
+    prev: [u32; RV32_REGISTER_NUM_LIMBS],
+    write_data: [u32; RV32_REGISTER_NUM_LIMBS],
+    cmp_result: Option<bool>,
+    expected_error: VerificationError,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = setup(&tester);
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    set_and_execute(
+        &mut tester,
+        &mut chip,
+        &mut rng,
+        opcode,
+        Some(rs2),
+        Some(prev),
+    );
+
+    let tester = tester.build();
+
+    let amo_trace_width = chip.trace_width();
+    let air = chip.air();
+    let mut chip_input = chip.generate_air_proof_input();
+    let amo_trace = chip_input.raw.common_main.as_mut().unwrap();
+    {
+        let mut trace_row = amo_trace.row_slice(0).to_vec();
+        let (_, core_row) = trace_row.split_at_mut(adapter_width);
+        let core_cols: &mut Rv32AmoCoreCols<F> = core_row.borrow_mut();
+        core_cols.write_data = write_data.map(F::from_canonical_u32);
+        if let Some(cmp_result) = cmp_result {
+            core_cols.cmp_result = F::from_bool(cmp_result);
+        }
+        *amo_trace = RowMajorMatrix::new(trace_row, amo_trace_width);
+    }
+    disable_debug_builder();
+    let tester = tester
+        .load_air_proof_input((air, chip_input))
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn amo_wrong_write_negative_tests() {
+    let rs2 = [1, 2, 3, 0x84];
+    let prev = [5, 6, 7, 8];
+    run_negative_amo_test(
+        AMOSWAP_W,
+        rs2,
+        prev,
+        prev,
+        None,
+        VerificationError::OodEvaluationMismatch,
+    );
+    run_negative_amo_test(
+        AMOADD_W,
+        rs2,
+        prev,
+        [6, 8, 10, 0x8d],
+        None,
+        VerificationError::OodEvaluationMismatch,
+    );
+    run_negative_amo_test(
+        AMOXOR_W,
+        rs2,
+        prev,
+        [4, 4, 4, 0x8d],
+        None,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn amo_min_max_negative_tests() {
+    // Signed, rs2 < prev, so MIN must write rs2.
+    let rs2 = [1, 2, 3, 0x84];
+    let prev = [5, 6, 7, 8];
+    run_negative_amo_test(
+        AMOMIN_W,
+        rs2,
+        prev,
+        prev,
+        Some(true),
+        VerificationError::OodEvaluationMismatch,
+    );
+    // Unsigned, prev < rs2, so MAXU must write rs2.
+    run_negative_amo_test(
+        AMOMAXU_W,
+        rs2,
+        prev,
+        prev,
+        Some(false),
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn amo_misaligned_trap_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, _) = setup(&tester);
+
+    let instruction = prepare(&mut tester, &mut rng, AMOADD_W, 0x102, [0; 4], [0; 4], true);
+    let timestamp = tester.memory_controller().borrow().timestamp();
+    let result = chip.execute(
+        &mut tester.memory_controller().borrow_mut(),
+        &instruction,
+        ExecutionState::new(0x20, timestamp),
+    );
+    match result {
+        Err(ExecutionError::Trap {
+            pc: 0x20,
+            cause: TrapCause::StoreAddressMisaligned,
+            ..
+        }) => {}
+        Err(err) => panic!("unexpected error: {err}"),
+        Ok(_) => panic!("misaligned AMO must trap"),
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_amo_sanity_test() {
+    let x = 0xfffffff0u32.to_le_bytes().map(u32::from);
+    let y = 0x00000010u32.to_le_bytes().map(u32::from);
+    let result = |opcode| u32::from_le_bytes(run_amo(opcode, &x, &y).map(|b| b as u8));
+    assert_eq!(result(AMOSWAP_W), 0x10);
+    assert_eq!(result(SC_W), 0x10);
+    assert_eq!(result(AMOADD_W), 0);
+    assert_eq!(result(AMOXOR_W), 0xffffffe0);
+    assert_eq!(result(AMOAND_W), 0x10);
+    assert_eq!(result(AMOOR_W), 0xfffffff0);
+    assert_eq!(result(AMOMIN_W), 0xfffffff0);
+    assert_eq!(result(AMOMAX_W), 0x10);
+    assert_eq!(result(AMOMINU_W), 0x10);
+    assert_eq!(result(AMOMAXU_W), 0xfffffff0);
+}
@@ -0,0 +1,305 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, ImmInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction, riscv::RV32_CYCLE_BASE_NUM_LIMBS, LocalOpcode,
+};
+use openvm_rv32im_transpiler::Rv32CsrOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+
+use crate::adapters::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+const RV32_LIMB_MAX: u32 = (1 << RV32_CELL_BITS) - 1;
+
+/// Counter CSRs are derived from the VM timestamp: every counter equals the cycle base plus the
+/// timestamp at the start of the instruction. The cycle base is carried across segments by the VM
+/// connector, so counters continue across segments, and `cycle`, `time` and `instret` all read
+/// the same value.
+#[repr(C)]
+#[derive(Debug, Clone, AlignedBorrow)]
+pub struct Rv32CsrCoreCols<T> {
+    pub opcode_flags: [T; Rv32CsrOpcode::COUNT],
+    /// Little-endian limbs of the timestamp.
+    pub timestamp: [T; RV32_REGISTER_NUM_LIMBS],
+    pub cycle_base: [T; RV32_CYCLE_BASE_NUM_LIMBS],
+    /// `cycle_base + timestamp` as a 64-bit value.
+    pub counter: [T; RV32_CYCLE_BASE_NUM_LIMBS],
+    pub rd_data: [T; RV32_REGISTER_NUM_LIMBS],
+}
+
+#[derive(Debug, Clone)]
+pub struct Rv32CsrCoreAir {
+    pub bus: BitwiseOperationLookupBus,
+    /// All timestamps are less than `2^clk_max_bits`.
+    pub clk_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32CsrCoreAir {
+    fn width(&self) -> usize {
+        Rv32CsrCoreCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32CsrCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for Rv32CsrCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<([AB::Expr; RV32_CYCLE_BASE_NUM_LIMBS], AB::Expr)>,
+    I::Writes: From<[[AB::Expr; RV32_REGISTER_NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<ImmInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &Rv32CsrCoreCols<AB::Var> = (*local_core).borrow();
+
+        let mut is_valid = AB::Expr::ZERO;
+        let mut is_high = AB::Expr::ZERO;
+        let mut expected_opcode = AB::Expr::ZERO;
+        for (opcode, &flag) in Rv32CsrOpcode::iter().zip(cols.opcode_flags.iter()) {
+            builder.assert_bool(flag);
+            is_valid += flag.into();
+            if opcode.is_high() {
+                is_high += flag.into();
+            }
+            expected_opcode += flag * AB::F::from_canonical_usize(opcode.local_usize());
+        }
+        builder.assert_bool(is_valid.clone());
+        let is_low = is_valid.clone() - is_high.clone();
+
+        // counter = cycle_base + timestamp modulo 2^64. Define carry[i] = (cycle_base[i] +
+        // timestamp[i] + carry[i - 1] - counter[i]) / 2^RV32_CELL_BITS. If each carry[i] is boolean
+        // and all limbs are range checked, the addition is correct.
+        let carry_divide = AB::F::from_canonical_usize(1 << RV32_CELL_BITS).inverse();
+        let mut carry = AB::Expr::ZERO;
+        for i in 0..RV32_CYCLE_BASE_NUM_LIMBS {
+            let timestamp_limb = cols
+                .timestamp
+                .get(i)
+                .map_or(AB::Expr::ZERO, |&limb| limb.into());
+            carry = AB::Expr::from(carry_divide)
+                * (cols.cycle_base[i] + timestamp_limb + carry - cols.counter[i]);
+            builder.assert_bool(carry.clone());
+        }
+
+        // rd is the lower or the upper half of the counter.
+        for i in 0..RV32_REGISTER_NUM_LIMBS {
+            builder
+                .when(is_low.clone())
+                .assert_eq(cols.rd_data[i], cols.counter[i]);
+            builder
+                .when(is_high.clone())
+                .assert_eq(cols.rd_data[i], cols.counter[i + RV32_REGISTER_NUM_LIMBS]);
+        }
+
+        // Range check the limbs of the counter, which also range checks rd_data.
+        for i in (0..RV32_CYCLE_BASE_NUM_LIMBS).step_by(2) {
+            self.bus
+                .send_range(cols.counter[i], cols.counter[i + 1])
+                .eval(builder, is_valid.clone());
+        }
+        // Range check the limbs of the timestamp. The most significant limb is limited such that
+        // the composed value is less than 2^clk_max_bits, so it is uniquely determined by the
+        // timestamp of the execution state.
+        self.bus
+            .send_range(cols.timestamp[0], cols.timestamp[1])
+            .eval(builder, is_valid.clone());
+        self.bus
+            .send_range(
+                cols.timestamp[2].into(),
+                cols.timestamp[3]
+                    * AB::F::from_canonical_usize(
+                        1 << (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.clk_max_bits),
+                    ),
+            )
+            .eval(builder, is_valid.clone());
+
+        let timestamp = cols
+            .timestamp
+            .iter()
+            .enumerate()
+            .fold(AB::Expr::ZERO, |acc, (i, &limb)| {
+                acc + limb * AB::Expr::from_canonical_u32(1 << (i * RV32_CELL_BITS))
+            });
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: (cols.cycle_base.map(|x| x.into()), timestamp).into(),
+            writes: [cols.rd_data.map(|x| x.into())].into(),
+            instruction: ImmInstruction {
+                is_valid,
+                opcode: expected_opcode
+                    + AB::Expr::from_canonical_usize(Rv32CsrOpcode::CLASS_OFFSET),
+                immediate: AB::Expr::ZERO,
+            }
+            .into(),
+        }
+    }
+
+    fn start_offset(&self) -> usize {
+        Rv32CsrOpcode::CLASS_OFFSET
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rv32CsrCoreRecord<F> {
+    pub opcode: Rv32CsrOpcode,
+    pub timestamp: u32,
+    pub cycle_base: [F; RV32_CYCLE_BASE_NUM_LIMBS],
+    pub counter: [u32; RV32_CYCLE_BASE_NUM_LIMBS],
+}
+
+pub struct Rv32CsrCoreChip {
+    pub air: Rv32CsrCoreAir,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+}
+
+impl Rv32CsrCoreChip {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        clk_max_bits: usize,
+    ) -> Self {
+        assert!(
+            clk_max_bits > (RV32_REGISTER_NUM_LIMBS - 1) * RV32_CELL_BITS
+                && clk_max_bits <= RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS
+        );
+        Self {
+            air: Rv32CsrCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                clk_max_bits,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for Rv32CsrCoreChip
+where
+    I::Reads: Into<([F; RV32_CYCLE_BASE_NUM_LIMBS], F)>,
+    I::Writes: From<[[F; RV32_REGISTER_NUM_LIMBS]; 1]>,
+{
+    type Record = Rv32CsrCoreRecord<F>;
+    type Air = Rv32CsrCoreAir;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let local_opcode = Rv32CsrOpcode::from_usize(
+            instruction
+                .opcode
+                .local_opcode_idx(Rv32CsrOpcode::CLASS_OFFSET),
+        );
+        let (cycle_base, timestamp) = reads.into();
+        let timestamp = timestamp.as_canonical_u32();
+        let (rd_data, counter) = run_csr(
+            local_opcode,
+            cycle_base.map(|x| x.as_canonical_u32()),
+            timestamp,
+        );
+
+        for i in (0..RV32_CYCLE_BASE_NUM_LIMBS).step_by(2) {
+            self.bitwise_lookup_chip
+                .request_range(counter[i], counter[i + 1]);
+        }
+        let timestamp_limbs = timestamp_to_limbs(timestamp);
+        self.bitwise_lookup_chip
+            .request_range(timestamp_limbs[0], timestamp_limbs[1]);
+        self.bitwise_lookup_chip.request_range(
+            timestamp_limbs[2],
+            timestamp_limbs[3]
+                << (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.air.clk_max_bits),
+        );
+
+        let output = AdapterRuntimeContext::without_pc([rd_data.map(F::from_canonical_u32)]);
+        Ok((
+            output,
+            Self::Record {
+                opcode: local_opcode,
+                timestamp,
+                cycle_base,
+                counter,
+            },
+        ))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32CsrOpcode::from_usize(opcode - Rv32CsrOpcode::CLASS_OFFSET)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let core_cols: &mut Rv32CsrCoreCols<F> = row_slice.borrow_mut();
+        core_cols.opcode_flags = array::from_fn(|i| F::from_bool(i == record.opcode as usize));
+        core_cols.timestamp = timestamp_to_limbs(record.timestamp).map(F::from_canonical_u32);
+        core_cols.cycle_base = record.cycle_base;
+        core_cols.counter = record.counter.map(F::from_canonical_u32);
+        let offset = if record.opcode.is_high() {
+            RV32_REGISTER_NUM_LIMBS
+        } else {
+            0
+        };
+        core_cols.rd_data = array::from_fn(|i| F::from_canonical_u32(record.counter[i + offset]));
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+fn timestamp_to_limbs(timestamp: u32) -> [u32; RV32_REGISTER_NUM_LIMBS] {
+    array::from_fn(|i| (timestamp >> (RV32_CELL_BITS * i)) & RV32_LIMB_MAX)
+}
+
+// returns (rd_data, counter)
+pub(super) fn run_csr(
+    opcode: Rv32CsrOpcode,
+    cycle_base: [u32; RV32_CYCLE_BASE_NUM_LIMBS],
+    timestamp: u32,
+) -> (
+    [u32; RV32_REGISTER_NUM_LIMBS],
+    [u32; RV32_CYCLE_BASE_NUM_LIMBS],
+) {
+    let cycle_base = cycle_base
+        .iter()
+        .rev()
+        .fold(0u64, |acc, &limb| (acc << RV32_CELL_BITS) | limb as u64);
+    let counter = cycle_base.wrapping_add(timestamp as u64);
+    let rd = if opcode.is_high() {
+        (counter >> 32) as u32
+    } else {
+        counter as u32
+    };
+    (
+        array::from_fn(|i| (rd >> (RV32_CELL_BITS * i)) & RV32_LIMB_MAX),
+        array::from_fn(|i| ((counter >> (RV32_CELL_BITS * i)) as u32) & RV32_LIMB_MAX),
+    )
+}
@@ -0,0 +1,11 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use crate::adapters::Rv32CsrAdapterChip;
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type Rv32CsrChip<F> = VmChipWrapper<F, Rv32CsrAdapterChip<F>, Rv32CsrCoreChip>;
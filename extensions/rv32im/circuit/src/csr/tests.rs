@@ -0,0 +1,236 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::arch::{testing::VmChipTestBuilder, MemoryConfig, VmAdapterChip};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CYCLE_BASE_NUM_LIMBS, RV32_CYCLE_BASE_PTR, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_rv32im_transpiler::Rv32CsrOpcode::{self, *};
+use openvm_stark_backend::{
+    interaction::BusIndex,
+    p3_air::BaseAir,
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    Chip, ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+use strum::IntoEnumIterator;
+
+use super::{run_csr, Rv32CsrChip, Rv32CsrCoreChip, Rv32CsrCoreCols};
+use crate::adapters::{Rv32CsrAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+const BITWISE_OP_LOOKUP_BUS: BusIndex = 9;
+
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32CsrChip<F>,
+    rng: &mut StdRng,
+    opcode: Rv32CsrOpcode,
+    cycle_base: Option<[u32; RV32_CYCLE_BASE_NUM_LIMBS]>,
+) {
+    let a = rng.gen_range(1..32) << 2;
+    let cycle_base = cycle_base.unwrap_or(array::from_fn(|_| rng.gen_range(0..=u8::MAX as u32)));
+    tester.write(
+        RV32_REGISTER_AS as usize,
+        RV32_CYCLE_BASE_PTR as usize,
+        cycle_base.map(F::from_canonical_u32),
+    );
+    let timestamp = tester.memory_controller().borrow().timestamp();
+
+    tester.execute(
+        chip,
+        &Instruction::from_usize(opcode.global_opcode(), [a, 0, 0, 1, 0]),
+    );
+
+    let (rd_data, _) = run_csr(opcode, cycle_base, timestamp);
+    assert_eq!(rd_data.map(F::from_canonical_u32), tester.read::<4>(1, a));
+}
+
+fn setup(
+    tester: &VmChipTestBuilder<F>,
+) -> (
+    Rv32CsrChip<F>,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let adapter = Rv32CsrAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+    );
+    let core = Rv32CsrCoreChip::new(bitwise_chip.clone(), MemoryConfig::default().clk_max_bits);
+    let chip = Rv32CsrChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
+    (chip, bitwise_chip)
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn rand_csr_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = setup(&tester);
+
+    let num_tests: usize = 20;
+    for _ in 0..num_tests {
+        for opcode in Rv32CsrOpcode::iter() {
+            set_and_execute(&mut tester, &mut chip, &mut rng, opcode, None);
+        }
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the core part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_csr_test(
+    opcode: Rv32CsrOpcode,
+    cycle_base: [u32; RV32_CYCLE_BASE_NUM_LIMBS],
+    rd_data: Option<[u32; RV32_REGISTER_NUM_LIMBS]>,
+    counter: Option<[u32; RV32_CYCLE_BASE_NUM_LIMBS]>,
+    timestamp: Option<[u32; RV32_REGISTER_NUM_LIMBS]>,
+    expected_error: VerificationError,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, bitwise_chip) = setup(&tester);
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    set_and_execute(&mut tester, &mut chip, &mut rng, opcode, Some(cycle_base));
+
+    let tester = tester.build();
+
+    let csr_trace_width = chip.trace_width();
+    let air = chip.air();
+    let mut chip_input = chip.generate_air_proof_input();
+    let csr_trace = chip_input.raw.common_main.as_mut().unwrap();
+    {
+        let mut trace_row = csr_trace.row_slice(0).to_vec();
+        let (_, core_row) = trace_row.split_at_mut(adapter_width);
+        let core_cols: &mut Rv32CsrCoreCols<F> = core_row.borrow_mut();
+
+        if let Some(data) = rd_data {
+            core_cols.rd_data = data.map(F::from_canonical_u32);
+        }
+        if let Some(counter) = counter {
+            core_cols.counter = counter.map(F::from_canonical_u32);
+        }
+        if let Some(timestamp) = timestamp {
+            core_cols.timestamp = timestamp.map(F::from_canonical_u32);
+        }
+
+        *csr_trace = RowMajorMatrix::new(trace_row, csr_trace_width);
+    }
+    disable_debug_builder();
+    let tester = tester
+        .load_air_proof_input((air, chip_input))
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn invalid_counter_negative_tests() {
+    // The write to the cycle base happens at timestamp 1, so the counter is read at timestamp 2.
+    let cycle_base = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+    // The lower half must be the lower half of the counter.
+    run_negative_csr_test(
+        RDCYCLE,
+        cycle_base,
+        Some([2, 0, 0, 0]),
+        None,
+        None,
+        VerificationError::OodEvaluationMismatch,
+    );
+    // The upper half must be the upper half of the counter.
+    run_negative_csr_test(
+        RDTIMEH,
+        cycle_base,
+        Some([0, 0, 0, 0]),
+        None,
+        None,
+        VerificationError::OodEvaluationMismatch,
+    );
+    // The counter must be the sum of the cycle base and the timestamp.
+    run_negative_csr_test(
+        RDINSTRET,
+        cycle_base,
+        Some([2, 0, 0, 0]),
+        Some([2, 0, 0, 0, 0, 0, 0, 0]),
+        None,
+        VerificationError::OodEvaluationMismatch,
+    );
+    // The timestamp must be the one of the execution state.
+    run_negative_csr_test(
+        RDINSTRET,
+        cycle_base,
+        Some([2, 0, 0, 0]),
+        Some([2, 0, 0, 0, 1, 0, 0, 0]),
+        Some([3, 0, 0, 0]),
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn overflow_negative_tests() {
+    // Composes to the timestamp 2 modulo the field, but the limbs are out of range.
+    run_negative_csr_test(
+        RDCYCLE,
+        [0; RV32_CYCLE_BASE_NUM_LIMBS],
+        Some([2 + 256, F::ORDER_U32 - 1, 0, 0]),
+        Some([2 + 256, F::ORDER_U32 - 1, 0, 0, 0, 0, 0, 0]),
+        Some([2 + 256, F::ORDER_U32 - 1, 0, 0]),
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_csr_sanity_test() {
+    let cycle_base = [0xff, 0xff, 0xff, 0xff, 0x01, 0, 0, 0];
+    assert_eq!(
+        run_csr(RDCYCLE, cycle_base, 0x1234567),
+        (
+            [0x66, 0x45, 0x23, 0x01],
+            [0x66, 0x45, 0x23, 0x01, 0x02, 0, 0, 0]
+        )
+    );
+    assert_eq!(
+        run_csr(RDTIME, cycle_base, 0x1234567).0,
+        [0x66, 0x45, 0x23, 0x01]
+    );
+    assert_eq!(
+        run_csr(RDINSTRETH, cycle_base, 0x1234567).0,
+        [0x02, 0, 0, 0]
+    );
+    assert_eq!(
+        run_csr(RDCYCLEH, [0xff; RV32_CYCLE_BASE_NUM_LIMBS], 1).0,
+        [0, 0, 0, 0]
+    );
+}
@@ -1,10 +1,10 @@
 use derive_more::derive::From;
 use openvm_circuit::{
     arch::{
-        InitFileGenerator, SystemConfig, SystemPort, VmExtension, VmInventory, VmInventoryBuilder,
-        VmInventoryError,
+        vm_poseidon2_config, InitFileGenerator, SystemConfig, SystemPort, VmExtension, VmInventory,
+        VmInventoryBuilder, VmInventoryError,
     },
-    system::phantom::PhantomChip,
+    system::{phantom::PhantomChip, poseidon2::Poseidon2PeripheryChip},
 };
 use openvm_circuit_derive::{AnyEnum, InstructionExecutor, VmConfig};
 use openvm_circuit_primitives::{
@@ -12,11 +12,16 @@ use openvm_circuit_primitives::{
     range_tuple::{RangeTupleCheckerBus, SharedRangeTupleCheckerChip},
 };
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
-use openvm_instructions::{program::DEFAULT_PC_STEP, LocalOpcode, PhantomDiscriminant};
+use openvm_instructions::{
+    program::{COMPRESSED_PC_STEP, DEFAULT_PC_STEP},
+    LocalOpcode, PhantomDiscriminant,
+};
 use openvm_rv32im_transpiler::{
     BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
-    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
-    Rv32LoadStoreOpcode, Rv32Phantom, ShiftOpcode,
+    MulHOpcode, MulOpcode, Rv32AmoOpcode, Rv32AuipcOpcode, Rv32CBaseAluOpcode,
+    Rv32CBranchEqualOpcode, Rv32CJalLuiOpcode, Rv32CJalrOpcode, Rv32CLoadStoreOpcode,
+    Rv32CShiftOpcode, Rv32CsrOpcode, Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
+    Rv32LoadStoreOpcode, Rv32Phantom, Rv32Poseidon2Opcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
@@ -131,6 +136,23 @@ fn default_range_tuple_checker_sizes() -> [u32; 2] {
     [1 << 8, 8 * (1 << 8)]
 }
 
+/// RISC-V 32-bit Atomic Instructions (RV32A) Extension.
+/// `LR.W` is handled by the load chip of [Rv32I]; there is a single hart, so `SC.W` always
+/// succeeds.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32A;
+
+/// RISC-V 32-bit Compressed Instructions (RV32C) Extension.
+/// Compressed instructions are executed as the [Rv32I] instructions they expand to, by separate
+/// chips that advance the pc by 2.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32C;
+
+/// RISC-V Extension for BabyBear Poseidon2 compression, executed by the system Poseidon2 chip
+/// used for persistent memory. The chip is added if continuations are disabled.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Poseidon2;
+
 // ============ Executor and Periphery Enums for Extension ============
 
 /// RISC-V 32-bit Base (RV32I) Instruction Executors
@@ -147,6 +169,7 @@ pub enum Rv32IExecutor<F: PrimeField32> {
     JalLui(Rv32JalLuiChip<F>),
     Jalr(Rv32JalrChip<F>),
     Auipc(Rv32AuipcChip<F>),
+    Csr(Rv32CsrChip<F>),
 }
 
 /// RISC-V 32-bit Multiplication Extension (RV32M) Instruction Executors
@@ -157,12 +180,35 @@ pub enum Rv32MExecutor<F: PrimeField32> {
     DivRem(Rv32DivRemChip<F>),
 }
 
+/// RISC-V 32-bit Atomic Instructions (RV32A) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32AExecutor<F: PrimeField32> {
+    Amo(Rv32AmoChip<F>),
+}
+
+/// RISC-V 32-bit Compressed Instructions (RV32C) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32CExecutor<F: PrimeField32> {
+    BaseAlu(Rv32BaseAluChip<F>),
+    Shift(Rv32ShiftChip<F>),
+    LoadStore(Rv32LoadStoreChip<F>),
+    BranchEqual(Rv32BranchEqualChip<F>),
+    JalLui(Rv32JalLuiChip<F>),
+    Jalr(Rv32JalrChip<F>),
+}
+
 /// RISC-V 32-bit Io Instruction Executors
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
 pub enum Rv32IoExecutor<F: PrimeField32> {
     HintStore(Rv32HintStoreChip<F>),
 }
 
+/// RISC-V Poseidon2 Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32Poseidon2Executor<F: PrimeField32> {
+    Poseidon2(Rv32Poseidon2Chip<F>),
+}
+
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Rv32IPeriphery<F: PrimeField32> {
     BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
@@ -179,6 +225,20 @@ pub enum Rv32MPeriphery<F: PrimeField32> {
     Phantom(PhantomChip<F>),
 }
 
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32APeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32CPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Rv32IoPeriphery<F: PrimeField32> {
     BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
@@ -186,6 +246,15 @@ pub enum Rv32IoPeriphery<F: PrimeField32> {
     Phantom(PhantomChip<F>),
 }
 
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32Poseidon2Periphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    /// Only present if continuations are disabled
+    Poseidon2(Poseidon2PeripheryChip<F>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
 // ============ VmExtension Implementations ============
 
 impl<F: PrimeField32> VmExtension<F> for Rv32I {
@@ -206,6 +275,8 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
         let range_checker = builder.system_base().range_checker_chip.clone();
         let offline_memory = builder.system_base().offline_memory();
         let pointer_max_bits = builder.system_config().memory_config.pointer_max_bits;
+        let trap_misaligned = builder.system_config().trap_misaligned;
+        let clk_max_bits = builder.system_config().memory_config.clk_max_bits;
 
         let bitwise_lu_chip = if let Some(&chip) = builder
             .find_chip::<SharedBitwiseOperationLookupChip<8>>()
@@ -270,7 +341,8 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
                 pointer_max_bits,
                 range_checker.clone(),
             ),
-            LoadStoreCoreChip::new(Rv32LoadStoreOpcode::CLASS_OFFSET),
+            LoadStoreCoreChip::new(Rv32LoadStoreOpcode::CLASS_OFFSET)
+                .with_misaligned_traps(trap_misaligned),
             offline_memory.clone(),
         );
         inventory.add_executor(
@@ -288,7 +360,8 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
                 pointer_max_bits,
                 range_checker.clone(),
             ),
-            LoadSignExtendCoreChip::new(range_checker.clone()),
+            LoadSignExtendCoreChip::new(range_checker.clone())
+                .with_misaligned_traps(trap_misaligned),
             offline_memory.clone(),
         );
         inventory.add_executor(
@@ -321,7 +394,11 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
 
         let jal_lui_chip = Rv32JalLuiChip::new(
             Rv32CondRdWriteAdapterChip::new(execution_bus, program_bus, memory_bridge),
-            Rv32JalLuiCoreChip::new(bitwise_lu_chip.clone()),
+            Rv32JalLuiCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32JalLuiOpcode::CLASS_OFFSET,
+                DEFAULT_PC_STEP,
+            ),
             offline_memory.clone(),
         );
         inventory.add_executor(
@@ -331,7 +408,12 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
 
         let jalr_chip = Rv32JalrChip::new(
             Rv32JalrAdapterChip::new(execution_bus, program_bus, memory_bridge),
-            Rv32JalrCoreChip::new(bitwise_lu_chip.clone(), range_checker.clone()),
+            Rv32JalrCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32JalrOpcode::CLASS_OFFSET,
+                DEFAULT_PC_STEP,
+            ),
             offline_memory.clone(),
         );
         inventory.add_executor(jalr_chip, Rv32JalrOpcode::iter().map(|x| x.global_opcode()))?;
@@ -346,6 +428,13 @@ impl<F: PrimeField32> VmExtension<F> for Rv32I {
             Rv32AuipcOpcode::iter().map(|x| x.global_opcode()),
         )?;
 
+        let csr_chip = Rv32CsrChip::new(
+            Rv32CsrAdapterChip::new(execution_bus, program_bus, memory_bridge),
+            Rv32CsrCoreChip::new(bitwise_lu_chip.clone(), clk_max_bits),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(csr_chip, Rv32CsrOpcode::iter().map(|x| x.global_opcode()))?;
+
         // There is no downside to adding phantom sub-executors, so we do it in the base extension.
         builder.add_phantom_sub_executor(
             phantom::Rv32HintInputSubEx,
@@ -444,6 +533,182 @@ impl<F: PrimeField32> VmExtension<F> for Rv32M {
     }
 }
 
+impl<F: PrimeField32> VmExtension<F> for Rv32A {
+    type Executor = Rv32AExecutor<F>;
+    type Periphery = Rv32APeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let offline_memory = builder.system_base().offline_memory();
+        let pointer_max_bits = builder.system_config().memory_config.pointer_max_bits;
+
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let amo_chip = Rv32AmoChip::new(
+            Rv32AmoAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_bridge,
+                pointer_max_bits,
+                range_checker,
+            ),
+            Rv32AmoCoreChip::new(bitwise_lu_chip),
+            offline_memory,
+        );
+        inventory.add_executor(amo_chip, Rv32AmoOpcode::iter().map(|x| x.global_opcode()))?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32C {
+    type Executor = Rv32CExecutor<F>;
+    type Periphery = Rv32CPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let offline_memory = builder.system_base().offline_memory();
+        let pointer_max_bits = builder.system_config().memory_config.pointer_max_bits;
+        let trap_misaligned = builder.system_config().trap_misaligned;
+
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let base_alu_chip = Rv32BaseAluChip::new(
+            Rv32BaseAluAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_bridge,
+                bitwise_lu_chip.clone(),
+            )
+            .with_pc_step(COMPRESSED_PC_STEP),
+            BaseAluCoreChip::new(bitwise_lu_chip.clone(), Rv32CBaseAluOpcode::CLASS_OFFSET),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            base_alu_chip,
+            Rv32CBaseAluOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let shift_chip = Rv32ShiftChip::new(
+            Rv32BaseAluAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_bridge,
+                bitwise_lu_chip.clone(),
+            )
+            .with_pc_step(COMPRESSED_PC_STEP),
+            ShiftCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                Rv32CShiftOpcode::CLASS_OFFSET,
+            ),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            shift_chip,
+            Rv32CShiftOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let load_store_chip = Rv32LoadStoreChip::new(
+            Rv32LoadStoreAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_bridge,
+                pointer_max_bits,
+                range_checker.clone(),
+            )
+            .with_offset(Rv32CLoadStoreOpcode::CLASS_OFFSET)
+            .with_pc_step(COMPRESSED_PC_STEP),
+            LoadStoreCoreChip::new(Rv32CLoadStoreOpcode::CLASS_OFFSET)
+                .with_misaligned_traps(trap_misaligned),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            load_store_chip,
+            Rv32CLoadStoreOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let beq_chip = Rv32BranchEqualChip::new(
+            Rv32BranchAdapterChip::new(execution_bus, program_bus, memory_bridge),
+            BranchEqualCoreChip::new(Rv32CBranchEqualOpcode::CLASS_OFFSET, COMPRESSED_PC_STEP),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            beq_chip,
+            Rv32CBranchEqualOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let jal_lui_chip = Rv32JalLuiChip::new(
+            Rv32CondRdWriteAdapterChip::new(execution_bus, program_bus, memory_bridge),
+            Rv32JalLuiCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32CJalLuiOpcode::CLASS_OFFSET,
+                COMPRESSED_PC_STEP,
+            ),
+            offline_memory.clone(),
+        );
+        inventory.add_executor(
+            jal_lui_chip,
+            Rv32CJalLuiOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        let jalr_chip = Rv32JalrChip::new(
+            Rv32JalrAdapterChip::new(execution_bus, program_bus, memory_bridge),
+            Rv32JalrCoreChip::new(
+                bitwise_lu_chip,
+                range_checker,
+                Rv32CJalrOpcode::CLASS_OFFSET,
+                COMPRESSED_PC_STEP,
+            ),
+            offline_memory,
+        );
+        inventory.add_executor(
+            jalr_chip,
+            Rv32CJalrOpcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
 impl<F: PrimeField32> VmExtension<F> for Rv32Io {
     type Executor = Rv32IoExecutor<F>;
     type Periphery = Rv32IoPeriphery<F>;
@@ -492,6 +757,68 @@ impl<F: PrimeField32> VmExtension<F> for Rv32Io {
     }
 }
 
+impl<F: PrimeField32> VmExtension<F> for Rv32Poseidon2 {
+    type Executor = Rv32Poseidon2Executor<F>;
+    type Periphery = Rv32Poseidon2Periphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        } = builder.system_port();
+        let offline_memory = builder.system_base().offline_memory();
+
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        // With continuations the system already has a Poseidon2 chip for persistent memory
+        let poseidon2_chip =
+            if let Some(&chip) = builder.find_chip::<Poseidon2PeripheryChip<F>>().first() {
+                chip.shared()
+            } else {
+                let chip = Poseidon2PeripheryChip::new(
+                    vm_poseidon2_config(),
+                    builder.new_bus_idx(),
+                    builder.system_config().max_constraint_degree,
+                );
+                let shared = chip.shared();
+                inventory.add_periphery_chip(chip);
+                shared
+            };
+
+        let poseidon2_executor = Rv32Poseidon2Chip::new(
+            execution_bus,
+            program_bus,
+            bitwise_lu_chip,
+            poseidon2_chip,
+            memory_bridge,
+            offline_memory,
+            builder.system_config().memory_config.pointer_max_bits,
+            Rv32Poseidon2Opcode::CLASS_OFFSET,
+        );
+        inventory.add_executor(
+            poseidon2_executor,
+            Rv32Poseidon2Opcode::iter().map(|x| x.global_opcode()),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
 /// Phantom sub-executors
 mod phantom {
     use eyre::bail;
@@ -527,7 +854,7 @@ mod phantom {
             _: F,
             _: u16,
         ) -> eyre::Result<()> {
-            let mut hint = match streams.input_stream.pop_front() {
+            let mut hint = match streams.input_stream.pop_front()? {
                 Some(hint) => hint,
                 None => {
                     bail!("EndOfInputStream");
@@ -611,8 +938,8 @@ mod phantom {
                         .as_canonical_u32() as u8
                 })
                 .collect();
-            if let Some(val) = streams.kv_store.get(&key) {
-                let to_push = hint_load_by_key_decode::<F>(val);
+            if let Some(val) = streams.kv_store.try_get(&key)? {
+                let to_push = hint_load_by_key_decode::<F>(&val);
                 for input in to_push.into_iter().rev() {
                     streams.input_stream.push_front(input);
                 }
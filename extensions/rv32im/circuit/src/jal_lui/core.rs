@@ -11,11 +11,7 @@ use openvm_circuit_primitives::bitwise_op_lookup::{
     BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
-use openvm_instructions::{
-    instruction::Instruction,
-    program::{DEFAULT_PC_STEP, PC_BITS},
-    LocalOpcode,
-};
+use openvm_instructions::{instruction::Instruction, program::PC_BITS, LocalOpcode};
 use openvm_rv32im_transpiler::Rv32JalLuiOpcode::{self, *};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
@@ -39,6 +35,8 @@ pub struct Rv32JalLuiCoreCols<T> {
 #[derive(Debug, Clone)]
 pub struct Rv32JalLuiCoreAir {
     pub bus: BitwiseOperationLookupBus,
+    pub offset: usize,
+    pub pc_step: u32,
 }
 
 impl<F: Field> BaseAir<F> for Rv32JalLuiCoreAir {
@@ -107,14 +105,14 @@ where
         );
 
         let intermed_val = rd[0] + intermed_val * AB::Expr::from_canonical_u32(1 << RV32_CELL_BITS);
-        // Constrain that from_pc + DEFAULT_PC_STEP is the correct composition of intermed_val in
-        // case of JAL
+        // Constrain that from_pc + pc_step is the correct composition of intermed_val in case of
+        // JAL
         builder.when(is_jal).assert_eq(
             intermed_val,
-            from_pc + AB::F::from_canonical_u32(DEFAULT_PC_STEP),
+            from_pc + AB::F::from_canonical_u32(self.pc_step),
         );
 
-        let to_pc = from_pc + is_lui * AB::F::from_canonical_u32(DEFAULT_PC_STEP) + is_jal * imm;
+        let to_pc = from_pc + is_lui * AB::F::from_canonical_u32(self.pc_step) + is_jal * imm;
 
         let expected_opcode = VmCoreAir::<AB, I>::expr_to_global_expr(
             self,
@@ -136,7 +134,7 @@ where
     }
 
     fn start_offset(&self) -> usize {
-        Rv32JalLuiOpcode::CLASS_OFFSET
+        self.offset
     }
 }
 
@@ -156,10 +154,16 @@ pub struct Rv32JalLuiCoreChip {
 }
 
 impl Rv32JalLuiCoreChip {
-    pub fn new(bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>) -> Self {
+    pub fn new(
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        offset: usize,
+        pc_step: u32,
+    ) -> Self {
         Self {
             air: Rv32JalLuiCoreAir {
                 bus: bitwise_lookup_chip.bus(),
+                offset,
+                pc_step,
             },
             bitwise_lookup_chip,
         }
@@ -180,11 +184,8 @@ where
         from_pc: u32,
         _reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
-        let local_opcode = Rv32JalLuiOpcode::from_usize(
-            instruction
-                .opcode
-                .local_opcode_idx(Rv32JalLuiOpcode::CLASS_OFFSET),
-        );
+        let local_opcode =
+            Rv32JalLuiOpcode::from_usize(instruction.opcode.local_opcode_idx(self.air.offset));
         let imm = instruction.c;
 
         let signed_imm = match local_opcode {
@@ -196,7 +197,7 @@ where
             }
             LUI => imm.as_canonical_u32() as i32,
         };
-        let (to_pc, rd_data) = run_jal_lui(local_opcode, from_pc, signed_imm);
+        let (to_pc, rd_data) = run_jal_lui(local_opcode, from_pc, signed_imm, self.air.pc_step);
 
         for i in 0..(RV32_REGISTER_NUM_LIMBS / 2) {
             self.bitwise_lookup_chip
@@ -231,7 +232,7 @@ where
     fn get_opcode_name(&self, opcode: usize) -> String {
         format!(
             "{:?}",
-            Rv32JalLuiOpcode::from_usize(opcode - Rv32JalLuiOpcode::CLASS_OFFSET)
+            Rv32JalLuiOpcode::from_usize(opcode - self.air.offset)
         )
     }
 
@@ -253,12 +254,12 @@ pub(super) fn run_jal_lui(
     opcode: Rv32JalLuiOpcode,
     pc: u32,
     imm: i32,
+    pc_step: u32,
 ) -> (u32, [u32; RV32_REGISTER_NUM_LIMBS]) {
     match opcode {
         JAL => {
-            let rd_data = array::from_fn(|i| {
-                ((pc + DEFAULT_PC_STEP) >> (8 * i)) & ((1 << RV32_CELL_BITS) - 1)
-            });
+            let rd_data =
+                array::from_fn(|i| ((pc + pc_step) >> (8 * i)) & ((1 << RV32_CELL_BITS) - 1));
             let next_pc = pc as i32 + imm;
             assert!(next_pc >= 0);
             (next_pc as u32, rd_data)
@@ -268,7 +269,7 @@ pub(super) fn run_jal_lui(
             let rd = imm << 12;
             let rd_data =
                 array::from_fn(|i| (rd >> (RV32_CELL_BITS * i)) & ((1 << RV32_CELL_BITS) - 1));
-            (pc + DEFAULT_PC_STEP, rd_data)
+            (pc + pc_step, rd_data)
         }
     }
 }
@@ -7,7 +7,11 @@ use openvm_circuit::arch::{
 use openvm_circuit_primitives::bitwise_op_lookup::{
     BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
 };
-use openvm_instructions::{instruction::Instruction, program::PC_BITS, LocalOpcode};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::{COMPRESSED_PC_STEP, DEFAULT_PC_STEP, PC_BITS},
+    LocalOpcode,
+};
 use openvm_rv32im_transpiler::Rv32JalLuiOpcode::{self, *};
 use openvm_stark_backend::{
     p3_air::BaseAir,
@@ -67,7 +71,7 @@ fn set_and_execute(
     let initial_pc = tester.execution.last_from_pc().as_canonical_u32();
     let final_pc = tester.execution.last_to_pc().as_canonical_u32();
 
-    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm);
+    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm, DEFAULT_PC_STEP);
     let rd_data = if needs_write { rd_data } else { [0; 4] };
 
     assert_eq!(next_pc, final_pc);
@@ -93,7 +97,11 @@ fn rand_jal_lui_test() {
         tester.program_bus(),
         tester.memory_bridge(),
     );
-    let core = Rv32JalLuiCoreChip::new(bitwise_chip.clone());
+    let core = Rv32JalLuiCoreChip::new(
+        bitwise_chip.clone(),
+        Rv32JalLuiOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalLuiChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
 
     let num_tests: usize = 100;
@@ -136,7 +144,11 @@ fn run_negative_jal_lui_test(
         tester.memory_bridge(),
     );
     let adapter_width = BaseAir::<F>::width(adapter.air());
-    let core = Rv32JalLuiCoreChip::new(bitwise_chip.clone());
+    let core = Rv32JalLuiCoreChip::new(
+        bitwise_chip.clone(),
+        Rv32JalLuiOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalLuiChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
 
     set_and_execute(
@@ -319,7 +331,11 @@ fn execute_roundtrip_sanity_test() {
         tester.program_bus(),
         tester.memory_bridge(),
     );
-    let core = Rv32JalLuiCoreChip::new(bitwise_chip);
+    let core = Rv32JalLuiCoreChip::new(
+        bitwise_chip,
+        Rv32JalLuiOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalLuiChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
     let num_tests: usize = 10;
     for _ in 0..num_tests {
@@ -350,7 +366,7 @@ fn run_jal_sanity_test() {
     let opcode = JAL;
     let initial_pc = 28120;
     let imm = -2048;
-    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm);
+    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm, DEFAULT_PC_STEP);
     assert_eq!(next_pc, 26072);
     assert_eq!(rd_data, [220, 109, 0, 0]);
 }
@@ -360,7 +376,17 @@ fn run_lui_sanity_test() {
     let opcode = LUI;
     let initial_pc = 456789120;
     let imm = 853679;
-    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm);
+    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm, DEFAULT_PC_STEP);
     assert_eq!(next_pc, 456789124);
     assert_eq!(rd_data, [0, 240, 106, 208]);
 }
+
+#[test]
+fn run_compressed_jal_sanity_test() {
+    let opcode = JAL;
+    let initial_pc = 28120;
+    let imm = -2048;
+    let (next_pc, rd_data) = run_jal_lui(opcode, initial_pc, imm, COMPRESSED_PC_STEP);
+    assert_eq!(next_pc, 26072);
+    assert_eq!(rd_data, [218, 109, 0, 0]);
+}
@@ -12,11 +12,7 @@ use openvm_circuit_primitives::{
     var_range::{SharedVariableRangeCheckerChip, VariableRangeCheckerBus},
 };
 use openvm_circuit_primitives_derive::AlignedBorrow;
-use openvm_instructions::{
-    instruction::Instruction,
-    program::{DEFAULT_PC_STEP, PC_BITS},
-    LocalOpcode,
-};
+use openvm_instructions::{instruction::Instruction, program::PC_BITS, LocalOpcode};
 use openvm_rv32im_transpiler::Rv32JalrOpcode::{self, *};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
@@ -61,6 +57,8 @@ pub struct Rv32JalrCoreRecord<F> {
 pub struct Rv32JalrCoreAir {
     pub bitwise_lookup_bus: BitwiseOperationLookupBus,
     pub range_bus: VariableRangeCheckerBus,
+    pub offset: usize,
+    pub pc_step: u32,
 }
 
 impl<F: Field> BaseAir<F> for Rv32JalrCoreAir {
@@ -106,14 +104,14 @@ where
                 acc + val * AB::Expr::from_canonical_u32(1 << ((i + 1) * RV32_CELL_BITS))
             });
 
-        let least_sig_limb = from_pc + AB::F::from_canonical_u32(DEFAULT_PC_STEP) - composed;
+        let least_sig_limb = from_pc + AB::F::from_canonical_u32(self.pc_step) - composed;
 
-        // rd_data is the final decomposition of `from_pc + DEFAULT_PC_STEP` we need.
+        // rd_data is the final decomposition of `from_pc + pc_step` we need.
         // The range check on `least_sig_limb` also ensures that `rd_data` correctly represents
-        // `from_pc + DEFAULT_PC_STEP`. Specifically, if `rd_data` does not match the
+        // `from_pc + pc_step`. Specifically, if `rd_data` does not match the
         // expected limb, then `least_sig_limb` becomes the real `least_sig_limb` plus the
         // difference between `composed` and the three most significant limbs of `from_pc +
-        // DEFAULT_PC_STEP`. In that case, `least_sig_limb` >= 2^RV32_CELL_BITS.
+        // pc_step`. In that case, `least_sig_limb` >= 2^RV32_CELL_BITS.
         let rd_data = array::from_fn(|i| {
             if i == 0 {
                 least_sig_limb.clone()
@@ -177,7 +175,7 @@ where
     }
 
     fn start_offset(&self) -> usize {
-        Rv32JalrOpcode::CLASS_OFFSET
+        self.offset
     }
 }
 
@@ -191,12 +189,16 @@ impl Rv32JalrCoreChip {
     pub fn new(
         bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
         range_checker_chip: SharedVariableRangeCheckerChip,
+        offset: usize,
+        pc_step: u32,
     ) -> Self {
         assert!(range_checker_chip.range_max_bits() >= 16);
         Self {
             air: Rv32JalrCoreAir {
                 bitwise_lookup_bus: bitwise_lookup_chip.bus(),
                 range_bus: range_checker_chip.bus(),
+                offset,
+                pc_step,
             },
             bitwise_lookup_chip,
             range_checker_chip,
@@ -220,8 +222,7 @@ where
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let Instruction { opcode, c, g, .. } = *instruction;
-        let local_opcode =
-            Rv32JalrOpcode::from_usize(opcode.local_opcode_idx(Rv32JalrOpcode::CLASS_OFFSET));
+        let local_opcode = Rv32JalrOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
 
         let imm = c.as_canonical_u32();
         let imm_sign = g.as_canonical_u32();
@@ -230,7 +231,13 @@ where
         let rs1 = reads.into()[0];
         let rs1_val = compose(rs1);
 
-        let (to_pc, rd_data) = run_jalr(local_opcode, from_pc, imm_extended, rs1_val);
+        let (to_pc, rd_data) = run_jalr(
+            local_opcode,
+            from_pc,
+            imm_extended,
+            rs1_val,
+            self.air.pc_step,
+        );
 
         self.bitwise_lookup_chip
             .request_range(rd_data[0], rd_data[1]);
@@ -265,10 +272,7 @@ where
     }
 
     fn get_opcode_name(&self, opcode: usize) -> String {
-        format!(
-            "{:?}",
-            Rv32JalrOpcode::from_usize(opcode - Rv32JalrOpcode::CLASS_OFFSET)
-        )
+        format!("{:?}", Rv32JalrOpcode::from_usize(opcode - self.air.offset))
     }
 
     fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
@@ -296,12 +300,13 @@ pub(super) fn run_jalr(
     pc: u32,
     imm: u32,
     rs1: u32,
+    pc_step: u32,
 ) -> (u32, [u32; RV32_REGISTER_NUM_LIMBS]) {
     let to_pc = rs1.wrapping_add(imm);
     let to_pc = to_pc - (to_pc & 1);
     assert!(to_pc < (1 << PC_BITS));
     (
         to_pc,
-        array::from_fn(|i: usize| ((pc + DEFAULT_PC_STEP) >> (RV32_CELL_BITS * i)) & RV32_LIMB_MAX),
+        array::from_fn(|i: usize| ((pc + pc_step) >> (RV32_CELL_BITS * i)) & RV32_LIMB_MAX),
     )
 }
@@ -7,7 +7,11 @@ use openvm_circuit::arch::{
 use openvm_circuit_primitives::bitwise_op_lookup::{
     BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
 };
-use openvm_instructions::{instruction::Instruction, program::PC_BITS, LocalOpcode};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::{COMPRESSED_PC_STEP, DEFAULT_PC_STEP, PC_BITS},
+    LocalOpcode,
+};
 use openvm_rv32im_transpiler::Rv32JalrOpcode::{self, *};
 use openvm_stark_backend::{
     p3_air::BaseAir,
@@ -76,7 +80,7 @@ fn set_and_execute(
 
     let rs1 = compose(rs1);
 
-    let (next_pc, rd_data) = run_jalr(opcode, initial_pc, imm_ext, rs1);
+    let (next_pc, rd_data) = run_jalr(opcode, initial_pc, imm_ext, rs1, DEFAULT_PC_STEP);
     let rd_data = if a == 0 { [0; 4] } else { rd_data };
 
     assert_eq!(next_pc, final_pc);
@@ -102,7 +106,12 @@ fn rand_jalr_test() {
         tester.program_bus(),
         tester.memory_bridge(),
     );
-    let inner = Rv32JalrCoreChip::new(bitwise_chip.clone(), range_checker_chip.clone());
+    let inner = Rv32JalrCoreChip::new(
+        bitwise_chip.clone(),
+        range_checker_chip.clone(),
+        Rv32JalrOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalrChip::<F>::new(adapter, inner, tester.offline_memory_mutex_arc());
 
     let num_tests: usize = 100;
@@ -158,7 +167,12 @@ fn run_negative_jalr_test(
         tester.memory_bridge(),
     );
     let adapter_width = BaseAir::<F>::width(adapter.air());
-    let inner = Rv32JalrCoreChip::new(bitwise_chip.clone(), range_checker_chip.clone());
+    let inner = Rv32JalrCoreChip::new(
+        bitwise_chip.clone(),
+        range_checker_chip.clone(),
+        Rv32JalrOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalrChip::<F>::new(adapter, inner, tester.offline_memory_mutex_arc());
 
     set_and_execute(
@@ -314,7 +328,12 @@ fn execute_roundtrip_sanity_test() {
         tester.program_bus(),
         tester.memory_bridge(),
     );
-    let inner = Rv32JalrCoreChip::new(bitwise_chip, range_checker_chip);
+    let inner = Rv32JalrCoreChip::new(
+        bitwise_chip,
+        range_checker_chip,
+        Rv32JalrOpcode::CLASS_OFFSET,
+        DEFAULT_PC_STEP,
+    );
     let mut chip = Rv32JalrChip::<F>::new(adapter, inner, tester.offline_memory_mutex_arc());
 
     let num_tests: usize = 10;
@@ -338,7 +357,18 @@ fn run_jalr_sanity_test() {
     let initial_pc = 789456120;
     let imm = -1235_i32 as u32;
     let rs1 = 736482910;
-    let (next_pc, rd_data) = run_jalr(opcode, initial_pc, imm, rs1);
+    let (next_pc, rd_data) = run_jalr(opcode, initial_pc, imm, rs1, DEFAULT_PC_STEP);
+    assert_eq!(next_pc, 736481674);
+    assert_eq!(rd_data, [252, 36, 14, 47]);
+}
+
+#[test]
+fn run_compressed_jalr_sanity_test() {
+    let opcode = JALR;
+    let initial_pc = 789456122;
+    let imm = -1235_i32 as u32;
+    let rs1 = 736482910;
+    let (next_pc, rd_data) = run_jalr(opcode, initial_pc, imm, rs1, COMPRESSED_PC_STEP);
     assert_eq!(next_pc, 736481674);
     assert_eq!(rd_data, [252, 36, 14, 47]);
 }
@@ -1,9 +1,11 @@
 pub mod adapters;
 
+mod amo;
 mod auipc;
 mod base_alu;
 mod branch_eq;
 mod branch_lt;
+mod csr;
 mod divrem;
 mod hintstore;
 mod jal_lui;
@@ -13,12 +15,15 @@ mod load_sign_extend;
 mod loadstore;
 mod mul;
 mod mulh;
+mod poseidon2;
 mod shift;
 
+pub use amo::*;
 pub use auipc::*;
 pub use base_alu::*;
 pub use branch_eq::*;
 pub use branch_lt::*;
+pub use csr::*;
 pub use divrem::*;
 pub use hintstore::*;
 pub use jal_lui::*;
@@ -28,10 +33,11 @@ pub use load_sign_extend::*;
 pub use loadstore::*;
 pub use mul::*;
 pub use mulh::*;
+pub use poseidon2::*;
 pub use shift::*;
 
 mod extension;
 pub use extension::*;
 
 #[cfg(any(test, feature = "test-utils"))]
-mod test_utils;
+pub mod test_utils;
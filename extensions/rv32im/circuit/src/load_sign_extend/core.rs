@@ -22,7 +22,7 @@ use openvm_stark_backend::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_big_array::BigArray;
 
-use crate::adapters::LoadStoreInstruction;
+use crate::adapters::{check_alignment, LoadStoreInstruction};
 
 /// LoadSignExtend Core Chip handles byte/halfword into word conversions through sign extend
 /// This chip uses read_data to construct write_data
@@ -181,6 +181,8 @@ where
 pub struct LoadSignExtendCoreChip<const NUM_CELLS: usize, const LIMB_BITS: usize> {
     pub air: LoadSignExtendCoreAir<NUM_CELLS, LIMB_BITS>,
     pub range_checker_chip: SharedVariableRangeCheckerChip,
+    /// Whether misaligned accesses raise a trap instead of being unsupported.
+    pub trap_misaligned: bool,
 }
 
 impl<const NUM_CELLS: usize, const LIMB_BITS: usize> LoadSignExtendCoreChip<NUM_CELLS, LIMB_BITS> {
@@ -190,8 +192,14 @@ impl<const NUM_CELLS: usize, const LIMB_BITS: usize> LoadSignExtendCoreChip<NUM_
                 range_bus: range_checker_chip.bus(),
             },
             range_checker_chip,
+            trap_misaligned: false,
         }
     }
+
+    pub fn with_misaligned_traps(mut self, trap_misaligned: bool) -> Self {
+        self.trap_misaligned = trap_misaligned;
+        self
+    }
 }
 
 impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_CELLS: usize, const LIMB_BITS: usize>
@@ -207,7 +215,7 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let local_opcode = Rv32LoadStoreOpcode::from_usize(
@@ -218,6 +226,9 @@ where
 
         let (data, shift_amount) = reads.into();
         let shift_amount = shift_amount.as_canonical_u32();
+        if self.trap_misaligned {
+            check_alignment(instruction, local_opcode, from_pc, shift_amount)?;
+        }
         let write_data: [F; NUM_CELLS] = run_write_data_sign_extend::<_, NUM_CELLS, LIMB_BITS>(
             local_opcode,
             data[1],
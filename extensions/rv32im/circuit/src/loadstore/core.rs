@@ -15,7 +15,7 @@ use openvm_stark_backend::{
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_big_array::BigArray;
 
-use crate::adapters::LoadStoreInstruction;
+use crate::adapters::{check_alignment, LoadStoreInstruction};
 
 #[derive(Debug, Clone, Copy)]
 enum InstructionOpcode {
@@ -249,14 +249,22 @@ where
 #[derive(Debug)]
 pub struct LoadStoreCoreChip<const NUM_CELLS: usize> {
     pub air: LoadStoreCoreAir<NUM_CELLS>,
+    /// Whether misaligned accesses raise a trap instead of being unsupported.
+    pub trap_misaligned: bool,
 }
 
 impl<const NUM_CELLS: usize> LoadStoreCoreChip<NUM_CELLS> {
     pub fn new(offset: usize) -> Self {
         Self {
             air: LoadStoreCoreAir { offset },
+            trap_misaligned: false,
         }
     }
+
+    pub fn with_misaligned_traps(mut self, trap_misaligned: bool) -> Self {
+        self.trap_misaligned = trap_misaligned;
+        self
+    }
 }
 
 impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_CELLS: usize> VmCoreChip<F, I>
@@ -272,7 +280,7 @@ where
     fn execute_instruction(
         &self,
         instruction: &Instruction<F>,
-        _from_pc: u32,
+        from_pc: u32,
         reads: I::Reads,
     ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
         let local_opcode =
@@ -280,6 +288,9 @@ where
 
         let (reads, shift_amount) = reads.into();
         let shift = shift_amount.as_canonical_u32();
+        if self.trap_misaligned {
+            check_alignment(instruction, local_opcode, from_pc, shift)?;
+        }
         let prev_data = reads[0];
         let read_data = reads[1];
         let write_data = run_write_data(local_opcode, read_data, prev_data, shift);
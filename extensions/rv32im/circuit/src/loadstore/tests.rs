@@ -3,12 +3,17 @@ use std::{array, borrow::BorrowMut};
 use openvm_circuit::{
     arch::{
         testing::{memory::gen_pointer, VmChipTestBuilder},
-        VmAdapterChip,
+        ExecutionError, ExecutionState, InstructionExecutor, VmAdapterChip,
     },
     utils::u32_into_limbs,
 };
-use openvm_instructions::{instruction::Instruction, LocalOpcode};
-use openvm_rv32im_transpiler::Rv32LoadStoreOpcode::{self, *};
+use openvm_instructions::{
+    instruction::Instruction, program::COMPRESSED_PC_STEP, LocalOpcode, TrapCause,
+};
+use openvm_rv32im_transpiler::{
+    Rv32CLoadStoreOpcode,
+    Rv32LoadStoreOpcode::{self, *},
+};
 use openvm_stark_backend::{
     p3_air::BaseAir,
     p3_field::FieldAlgebra,
@@ -427,6 +432,89 @@ fn negative_wrong_address_space_tests() {
     );
 }
 
+#[test]
+fn misaligned_loadstore_trap_test() {
+    let mut tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let adapter = Rv32LoadStoreAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        range_checker_chip,
+    );
+    let core =
+        LoadStoreCoreChip::new(Rv32LoadStoreOpcode::CLASS_OFFSET).with_misaligned_traps(true);
+    let mut chip = Rv32LoadStoreChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
+
+    tester.write(
+        1,
+        4,
+        u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(0x102).map(F::from_canonical_u32),
+    );
+    let instruction = Instruction::from_usize(LOADW.global_opcode(), [8, 4, 0, 1, 2, 1, 0]);
+    let timestamp = tester.memory_controller().borrow().timestamp();
+    let result = chip.execute(
+        &mut tester.memory_controller().borrow_mut(),
+        &instruction,
+        ExecutionState::new(0x20, timestamp),
+    );
+    match result {
+        Err(ExecutionError::Trap {
+            pc: 0x20,
+            cause: TrapCause::LoadAddressMisaligned,
+            ..
+        }) => {}
+        Err(err) => panic!("unexpected error: {err}"),
+        Ok(_) => panic!("misaligned load must trap"),
+    }
+}
+
+#[test]
+fn misaligned_compressed_load_trap_test() {
+    let mut tester = VmChipTestBuilder::default();
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    let adapter = Rv32LoadStoreAdapterChip::<F>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_bridge(),
+        tester.address_bits(),
+        range_checker_chip,
+    )
+    .with_offset(Rv32CLoadStoreOpcode::CLASS_OFFSET)
+    .with_pc_step(COMPRESSED_PC_STEP);
+    let core =
+        LoadStoreCoreChip::new(Rv32CLoadStoreOpcode::CLASS_OFFSET).with_misaligned_traps(true);
+    let mut chip = Rv32LoadStoreChip::<F>::new(adapter, core, tester.offline_memory_mutex_arc());
+
+    tester.write(
+        1,
+        44,
+        u32_into_limbs::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(0x102).map(F::from_canonical_u32),
+    );
+    // c.lw a0, 4(a1)
+    let instruction = Instruction::from_usize(
+        Rv32CLoadStoreOpcode(LOADW).global_opcode(),
+        [40, 44, 4, 1, 2, 1, 0],
+    );
+    let timestamp = tester.memory_controller().borrow().timestamp();
+    let result = chip.execute(
+        &mut tester.memory_controller().borrow_mut(),
+        &instruction,
+        ExecutionState::new(0x22, timestamp),
+    );
+    match result {
+        Err(ExecutionError::Trap {
+            pc: 0x22,
+            cause: TrapCause::LoadAddressMisaligned,
+            instruction: 0x41c8,
+            ..
+        }) => {}
+        Err(err) => panic!("unexpected error: {err}"),
+        Ok(_) => panic!("misaligned load must trap"),
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////
 /// SANITY TESTS
 ///
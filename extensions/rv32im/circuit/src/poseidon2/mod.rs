@@ -0,0 +1,486 @@
+//! Poseidon2 `compress` for RV32 guests.
+//!
+//! The chip reads two chunks of [PERIPHERY_POSEIDON2_CHUNK_SIZE] BabyBear elements from memory,
+//! each element stored as a little-endian `u32` in canonical form, and writes their compression
+//! back in the same format. The permutation itself is not constrained here: the chip looks up
+//! `lhs || rhs || compress(lhs, rhs)` on the bus of the [Poseidon2PeripheryChip].
+//!
+//! [Poseidon2PeripheryChip]: openvm_circuit::system::poseidon2::Poseidon2PeripheryChip
+use std::{
+    array::from_fn,
+    borrow::{Borrow, BorrowMut},
+    sync::{Arc, Mutex},
+};
+
+use openvm_circuit::{
+    arch::{ExecutionBridge, ExecutionBus, ExecutionError, ExecutionState, InstructionExecutor},
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryController, MemoryRecord, OfflineMemory, RecordId,
+        },
+        poseidon2::{SharedPoseidon2PeripheryChip, PERIPHERY_POSEIDON2_CHUNK_SIZE},
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip},
+    utils::next_power_of_two_or_zero,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_rv32im_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    interaction::{InteractionBuilder, LookupBus},
+    p3_air::{Air, AirBuilder, BaseAir},
+    p3_field::{Field, FieldAlgebra, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    prover::types::AirProofInput,
+    rap::{AnyRap, BaseAirWithPublicValues, PartitionedBaseAir},
+    Chip, ChipUsageGetter,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::adapters::{abstract_compose, compose, read_rv32_register};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of memory cells of a chunk of BabyBear elements
+pub const POSEIDON2_CHUNK_U8S: usize = PERIPHERY_POSEIDON2_CHUNK_SIZE * RV32_REGISTER_NUM_LIMBS;
+/// Register reads to get the output, `lhs` and `rhs` pointers
+const POSEIDON2_REGISTER_READS: usize = 3;
+/// Amount to advance timestamp by after execution of one opcode instruction: the register
+/// reads, the reads of `lhs` and `rhs` and the write of the output
+const POSEIDON2_TIMESTAMP_DELTA: usize = POSEIDON2_REGISTER_READS + 3;
+/// The most significant byte of the largest BabyBear element `p - 1 = 0x78000000`
+const BABY_BEAR_MAX_MSL: u32 = 0x78;
+
+#[repr(C)]
+#[derive(AlignedBorrow, Debug)]
+pub struct Rv32Poseidon2Cols<T> {
+    pub is_valid: T,
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub lhs_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub rhs_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub register_aux: [MemoryReadAuxCols<T>; POSEIDON2_REGISTER_READS],
+
+    pub lhs: Rv32Poseidon2ChunkCols<T>,
+    pub rhs: Rv32Poseidon2ChunkCols<T>,
+    pub output: Rv32Poseidon2ChunkCols<T>,
+    pub lhs_read_aux: MemoryReadAuxCols<T>,
+    pub rhs_read_aux: MemoryReadAuxCols<T>,
+    pub output_write_aux: MemoryWriteAuxCols<T, POSEIDON2_CHUNK_U8S>,
+}
+
+/// A chunk of BabyBear elements as little-endian bytes
+#[repr(C)]
+#[derive(AlignedBorrow, Debug)]
+pub struct Rv32Poseidon2ChunkCols<T> {
+    pub bytes: [[T; RV32_REGISTER_NUM_LIMBS]; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+    /// Whether the element is `p - 1`, the only canonical element whose most significant byte
+    /// is [BABY_BEAR_MAX_MSL]
+    pub is_max: [T; PERIPHERY_POSEIDON2_CHUNK_SIZE],
+}
+
+impl<T: Copy> Rv32Poseidon2ChunkCols<T> {
+    fn cells(&self) -> [T; POSEIDON2_CHUNK_U8S] {
+        from_fn(|i| self.bytes[i / RV32_REGISTER_NUM_LIMBS][i % RV32_REGISTER_NUM_LIMBS])
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rv32Poseidon2Air {
+    pub execution_bridge: ExecutionBridge,
+    pub memory_bridge: MemoryBridge,
+    pub bitwise_operation_lookup_bus: BitwiseOperationLookupBus,
+    /// Bus of the Poseidon2 periphery chip
+    pub poseidon2_bus: LookupBus,
+    pub offset: usize,
+    pointer_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32Poseidon2Air {
+    fn width(&self) -> usize {
+        Rv32Poseidon2Cols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for Rv32Poseidon2Air {}
+impl<F: Field> PartitionedBaseAir<F> for Rv32Poseidon2Air {}
+
+impl<AB: InteractionBuilder> Air<AB> for Rv32Poseidon2Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local_cols: &Rv32Poseidon2Cols<AB::Var> = (*local).borrow();
+        let is_valid = local_cols.is_valid;
+        builder.assert_bool(is_valid);
+
+        let timestamp: AB::Var = local_cols.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::Expr::from_canonical_usize(timestamp_delta - 1)
+        };
+
+        let registers = [
+            (local_cols.rd_ptr, local_cols.dst_ptr),
+            (local_cols.rs1_ptr, local_cols.lhs_ptr),
+            (local_cols.rs2_ptr, local_cols.rhs_ptr),
+        ];
+        for (&(ptr, data), aux) in registers.iter().zip(&local_cols.register_aux) {
+            self.memory_bridge
+                .read(
+                    MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), ptr),
+                    data,
+                    timestamp_pp(),
+                    aux,
+                )
+                .eval(builder, is_valid);
+        }
+        let [dst, lhs_ptr, rhs_ptr] =
+            registers.map(|(_, data)| abstract_compose::<AB::Expr, _>(data));
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), lhs_ptr),
+                local_cols.lhs.cells(),
+                timestamp_pp(),
+                &local_cols.lhs_read_aux,
+            )
+            .eval(builder, is_valid);
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), rhs_ptr),
+                local_cols.rhs.cells(),
+                timestamp_pp(),
+                &local_cols.rhs_read_aux,
+            )
+            .eval(builder, is_valid);
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), dst),
+                local_cols.output.cells(),
+                timestamp_pp(),
+                &local_cols.output_write_aux,
+            )
+            .eval(builder, is_valid);
+
+        self.execution_bridge
+            .execute_and_increment_pc(
+                AB::Expr::from_canonical_usize(
+                    Rv32Poseidon2Opcode::COMPRESS as usize + self.offset,
+                ),
+                [
+                    local_cols.rd_ptr.into(),
+                    local_cols.rs1_ptr.into(),
+                    local_cols.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                ],
+                local_cols.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+            )
+            .eval(builder, is_valid);
+
+        // Preventing pointer overflow, see Rv32HintStoreAir
+        let limb_shift = AB::F::from_canonical_usize(
+            1 << (RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.pointer_max_bits),
+        );
+        self.bitwise_operation_lookup_bus
+            .send_range(
+                local_cols.dst_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                local_cols.lhs_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+            )
+            .eval(builder, is_valid);
+        self.bitwise_operation_lookup_bus
+            .send_range(
+                local_cols.rhs_ptr[RV32_REGISTER_NUM_LIMBS - 1] * limb_shift,
+                AB::Expr::ZERO,
+            )
+            .eval(builder, is_valid);
+
+        // Memory cells are bytes, so only the output bytes need to be range checked
+        for pair in local_cols.output.bytes.as_flattened().chunks_exact(2) {
+            self.bitwise_operation_lookup_bus
+                .send_range(pair[0], pair[1])
+                .eval(builder, is_valid);
+        }
+
+        // Every element `x` is canonical, i.e. `x <= 0x78000000`. If `is_max` then the three low
+        // bytes are zero and the most significant byte is at most 0x78, otherwise the most
+        // significant byte is at most 0x77.
+        let chunks = [&local_cols.lhs, &local_cols.rhs, &local_cols.output];
+        let mut msl_bounds = Vec::with_capacity(chunks.len() * PERIPHERY_POSEIDON2_CHUNK_SIZE);
+        for chunk in chunks {
+            for (bytes, &is_max) in chunk.bytes.iter().zip(&chunk.is_max) {
+                builder.assert_bool(is_max);
+                builder
+                    .when(is_max)
+                    .assert_zero(bytes[0] + bytes[1] + bytes[2]);
+                msl_bounds.push(
+                    AB::Expr::from_canonical_u32(BABY_BEAR_MAX_MSL - 1) + is_max
+                        - bytes[RV32_REGISTER_NUM_LIMBS - 1],
+                );
+            }
+        }
+        for pair in msl_bounds.chunks_exact(2) {
+            self.bitwise_operation_lookup_bus
+                .send_range(pair[0].clone(), pair[1].clone())
+                .eval(builder, is_valid);
+        }
+
+        // Canonical elements are equal to the composition of their bytes in the field
+        let key = chunks
+            .into_iter()
+            .flat_map(|chunk| chunk.bytes.map(abstract_compose::<AB::Expr, _>));
+        self.poseidon2_bus.lookup_key(builder, key, is_valid);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "F: Field")]
+pub struct Rv32Poseidon2Record<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    pub instruction: Instruction<F>,
+    pub register_reads: [RecordId; POSEIDON2_REGISTER_READS],
+    pub lhs_read: RecordId,
+    pub rhs_read: RecordId,
+    pub output_write: RecordId,
+}
+
+pub struct Rv32Poseidon2Chip<F: PrimeField32> {
+    air: Rv32Poseidon2Air,
+    pub records: Vec<Rv32Poseidon2Record<F>>,
+    offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    poseidon2_chip: SharedPoseidon2PeripheryChip<F>,
+}
+
+impl<F: PrimeField32> Rv32Poseidon2Chip<F> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+        poseidon2_chip: SharedPoseidon2PeripheryChip<F>,
+        memory_bridge: MemoryBridge,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+        pointer_max_bits: usize,
+        offset: usize,
+    ) -> Self {
+        let air = Rv32Poseidon2Air {
+            execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+            memory_bridge,
+            bitwise_operation_lookup_bus: bitwise_lookup_chip.bus(),
+            poseidon2_bus: poseidon2_chip.bus(),
+            offset,
+            pointer_max_bits,
+        };
+        Self {
+            air,
+            records: vec![],
+            offline_memory,
+            bitwise_lookup_chip,
+            poseidon2_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32> InstructionExecutor<F> for Rv32Poseidon2Chip<F> {
+    fn execute(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        debug_assert_eq!(
+            opcode.local_opcode_idx(self.air.offset),
+            Rv32Poseidon2Opcode::COMPRESS as usize
+        );
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+
+        let (dst_read, dst) = read_rv32_register(memory, d, a);
+        let (lhs_ptr_read, lhs_ptr) = read_rv32_register(memory, d, b);
+        let (rhs_ptr_read, rhs_ptr) = read_rv32_register(memory, d, c);
+        debug_assert!(dst < (1 << self.air.pointer_max_bits));
+        debug_assert!(lhs_ptr < (1 << self.air.pointer_max_bits));
+        debug_assert!(rhs_ptr < (1 << self.air.pointer_max_bits));
+
+        let (lhs_read, lhs) = memory.read::<POSEIDON2_CHUNK_U8S>(e, F::from_canonical_u32(lhs_ptr));
+        let (rhs_read, rhs) = memory.read::<POSEIDON2_CHUNK_U8S>(e, F::from_canonical_u32(rhs_ptr));
+        let (Some(lhs), Some(rhs)) = (cells_to_elements(&lhs), cells_to_elements(&rhs)) else {
+            return Err(ExecutionError::Fail {
+                pc: from_state.pc,
+                backtrace: None,
+            });
+        };
+
+        let output = self.poseidon2_chip.compress_and_record(&lhs, &rhs);
+        let output_cells = from_fn(|i| {
+            let element = output[i / RV32_REGISTER_NUM_LIMBS].as_canonical_u32();
+            F::from_canonical_u8(element.to_le_bytes()[i % RV32_REGISTER_NUM_LIMBS])
+        });
+        let (output_write, _) = memory.write(e, F::from_canonical_u32(dst), output_cells);
+
+        self.records.push(Rv32Poseidon2Record {
+            from_state,
+            instruction: instruction.clone(),
+            register_reads: [dst_read, lhs_ptr_read, rhs_ptr_read],
+            lhs_read,
+            rhs_read,
+            output_write,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: from_state.timestamp + POSEIDON2_TIMESTAMP_DELTA as u32,
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        "POSEIDON2_COMPRESS".to_string()
+    }
+}
+
+/// Converts memory cells to BabyBear elements, or returns `None` if some element is not canonical
+fn cells_to_elements<F: PrimeField32>(
+    cells: &[F; POSEIDON2_CHUNK_U8S],
+) -> Option<[F; PERIPHERY_POSEIDON2_CHUNK_SIZE]> {
+    let mut elements = [F::ZERO; PERIPHERY_POSEIDON2_CHUNK_SIZE];
+    for (element, word) in elements
+        .iter_mut()
+        .zip(cells.chunks_exact(RV32_REGISTER_NUM_LIMBS))
+    {
+        let value = compose(word.try_into().unwrap());
+        if value >= F::ORDER_U32 {
+            return None;
+        }
+        *element = F::from_canonical_u32(value);
+    }
+    Some(elements)
+}
+
+impl<F: PrimeField32> ChipUsageGetter for Rv32Poseidon2Chip<F> {
+    fn air_name(&self) -> String {
+        "Rv32Poseidon2Air".to_string()
+    }
+
+    fn current_trace_height(&self) -> usize {
+        self.records.len()
+    }
+
+    fn trace_width(&self) -> usize {
+        Rv32Poseidon2Cols::<F>::width()
+    }
+}
+
+impl<F: PrimeField32> Rv32Poseidon2Chip<F> {
+    fn fill_chunk(
+        chunk: &mut Rv32Poseidon2ChunkCols<F>,
+        record: &MemoryRecord<F>,
+        bitwise_lookup_chip: &SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+    ) {
+        for (i, (bytes, word)) in chunk
+            .bytes
+            .iter_mut()
+            .zip(record.data_slice().chunks_exact(RV32_REGISTER_NUM_LIMBS))
+            .enumerate()
+        {
+            bytes.copy_from_slice(word);
+            chunk.is_max[i] = F::from_bool(compose(*bytes) == F::ORDER_U32 - 1);
+        }
+        let msl_bounds: [u32; PERIPHERY_POSEIDON2_CHUNK_SIZE] = from_fn(|i| {
+            BABY_BEAR_MAX_MSL - 1 + chunk.is_max[i].as_canonical_u32()
+                - chunk.bytes[i][RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32()
+        });
+        for pair in msl_bounds.chunks_exact(2) {
+            bitwise_lookup_chip.request_range(pair[0], pair[1]);
+        }
+    }
+
+    fn generate_trace(self) -> RowMajorMatrix<F> {
+        let width = self.trace_width();
+        let height = next_power_of_two_or_zero(self.records.len());
+        let mut flat_trace = F::zero_vec(width * height);
+
+        let memory = self.offline_memory.lock().unwrap();
+        let aux_cols_factory = memory.aux_cols_factory();
+        let limb_shift_bits = RV32_REGISTER_NUM_LIMBS * RV32_CELL_BITS - self.air.pointer_max_bits;
+
+        for (record, row) in self.records.iter().zip(flat_trace.chunks_exact_mut(width)) {
+            let cols: &mut Rv32Poseidon2Cols<F> = row.borrow_mut();
+            cols.is_valid = F::ONE;
+            cols.from_state = record.from_state.map(F::from_canonical_u32);
+            cols.rd_ptr = record.instruction.a;
+            cols.rs1_ptr = record.instruction.b;
+            cols.rs2_ptr = record.instruction.c;
+
+            let register_reads = record.register_reads.map(|id| memory.record_by_id(id));
+            for (read, aux) in register_reads.iter().zip(cols.register_aux.iter_mut()) {
+                aux_cols_factory.generate_read_aux(read, aux);
+            }
+            let [dst_ptr, lhs_ptr, rhs_ptr] = register_reads
+                .map(|read| <[F; RV32_REGISTER_NUM_LIMBS]>::try_from(read.data_slice()).unwrap());
+            cols.dst_ptr = dst_ptr;
+            cols.lhs_ptr = lhs_ptr;
+            cols.rhs_ptr = rhs_ptr;
+            let msl = |ptr: [F; RV32_REGISTER_NUM_LIMBS]| {
+                ptr[RV32_REGISTER_NUM_LIMBS - 1].as_canonical_u32() << limb_shift_bits
+            };
+            self.bitwise_lookup_chip
+                .request_range(msl(dst_ptr), msl(lhs_ptr));
+            self.bitwise_lookup_chip.request_range(msl(rhs_ptr), 0);
+
+            let lhs_read = memory.record_by_id(record.lhs_read);
+            let rhs_read = memory.record_by_id(record.rhs_read);
+            let output_write = memory.record_by_id(record.output_write);
+            aux_cols_factory.generate_read_aux(lhs_read, &mut cols.lhs_read_aux);
+            aux_cols_factory.generate_read_aux(rhs_read, &mut cols.rhs_read_aux);
+            aux_cols_factory.generate_write_aux(output_write, &mut cols.output_write_aux);
+            Self::fill_chunk(&mut cols.lhs, lhs_read, &self.bitwise_lookup_chip);
+            Self::fill_chunk(&mut cols.rhs, rhs_read, &self.bitwise_lookup_chip);
+            Self::fill_chunk(&mut cols.output, output_write, &self.bitwise_lookup_chip);
+            for pair in output_write.data_slice().chunks_exact(2) {
+                self.bitwise_lookup_chip
+                    .request_range(pair[0].as_canonical_u32(), pair[1].as_canonical_u32());
+            }
+        }
+        // padding rows can just be all zeros
+        RowMajorMatrix::new(flat_trace, width)
+    }
+}
+
+impl<SC: StarkGenericConfig> Chip<SC> for Rv32Poseidon2Chip<Val<SC>>
+where
+    Val<SC>: PrimeField32,
+{
+    fn air(&self) -> Arc<dyn AnyRap<SC>> {
+        Arc::new(self.air)
+    }
+
+    fn generate_air_proof_input(self) -> AirProofInput<SC> {
+        AirProofInput::simple_no_pis(self.generate_trace())
+    }
+}
@@ -0,0 +1,228 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::{
+        hasher::Hasher,
+        testing::{
+            memory::gen_pointer, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS, POSEIDON2_DIRECT_BUS,
+        },
+        vm_poseidon2_config, ExecutionError, ExecutionState, InstructionExecutor,
+    },
+    system::poseidon2::{Poseidon2PeripheryChip, PERIPHERY_POSEIDON2_CHUNK_SIZE},
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode,
+};
+use openvm_rv32im_transpiler::Rv32Poseidon2Opcode;
+use openvm_stark_backend::{
+    p3_field::{FieldAlgebra, PrimeField32},
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+};
+use openvm_stark_sdk::{config::setup_tracing, p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{Rv32Poseidon2Chip, Rv32Poseidon2Cols, POSEIDON2_CHUNK_U8S};
+use crate::adapters::decompose;
+
+type F = BabyBear;
+type Chunk = [u32; PERIPHERY_POSEIDON2_CHUNK_SIZE];
+
+fn setup(
+    tester: &VmChipTestBuilder<F>,
+) -> (
+    Rv32Poseidon2Chip<F>,
+    Poseidon2PeripheryChip<F>,
+    SharedBitwiseOperationLookupChip<RV32_CELL_BITS>,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let poseidon2_chip =
+        Poseidon2PeripheryChip::new(vm_poseidon2_config(), POSEIDON2_DIRECT_BUS, 3);
+    let chip = Rv32Poseidon2Chip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        bitwise_chip.clone(),
+        poseidon2_chip.shared(),
+        tester.memory_bridge(),
+        tester.offline_memory_mutex_arc(),
+        tester.address_bits(),
+        Rv32Poseidon2Opcode::CLASS_OFFSET,
+    );
+    (chip, poseidon2_chip, bitwise_chip)
+}
+
+fn gen_chunk(rng: &mut StdRng) -> Chunk {
+    array::from_fn(|_| match rng.gen_range(0..4) {
+        // Exercise the largest elements, which have a special case in the canonical check
+        0 => F::ORDER_U32 - 1 - rng.gen_range(0..2),
+        _ => rng.gen_range(0..F::ORDER_U32),
+    })
+}
+
+fn chunk_to_cells(chunk: &Chunk) -> [F; POSEIDON2_CHUNK_U8S] {
+    array::from_fn(|i| {
+        F::from_canonical_u8(
+            chunk[i / RV32_REGISTER_NUM_LIMBS].to_le_bytes()[i % RV32_REGISTER_NUM_LIMBS],
+        )
+    })
+}
+
+fn gen_memory_pointer(tester: &VmChipTestBuilder<F>, rng: &mut StdRng) -> u32 {
+    let pointer_max_bits = tester
+        .memory_controller()
+        .borrow()
+        .mem_config()
+        .pointer_max_bits;
+    rng.gen_range(0..(1 << (pointer_max_bits - 6))) << 5
+}
+
+/// Writes `lhs`, `rhs` and the pointers to memory and returns the instruction and the output
+/// pointer
+fn set_instruction(
+    tester: &mut VmChipTestBuilder<F>,
+    rng: &mut StdRng,
+    lhs: &Chunk,
+    rhs: &Chunk,
+) -> (Instruction<F>, usize) {
+    let [rd, rs1, rs2] = [0; 3].map(|_| gen_pointer(rng, RV32_REGISTER_NUM_LIMBS));
+    let [dst, lhs_ptr, rhs_ptr] = [0; 3].map(|_| gen_memory_pointer(tester, rng));
+
+    tester.write(1, rd, decompose(dst));
+    tester.write(1, rs1, decompose(lhs_ptr));
+    tester.write(1, rs2, decompose(rhs_ptr));
+    tester.write(2, lhs_ptr as usize, chunk_to_cells(lhs));
+    tester.write(2, rhs_ptr as usize, chunk_to_cells(rhs));
+
+    let instruction = Instruction::from_usize(
+        Rv32Poseidon2Opcode::COMPRESS.global_opcode(),
+        [rd, rs1, rs2, 1, 2],
+    );
+    (instruction, dst as usize)
+}
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut Rv32Poseidon2Chip<F>,
+    poseidon2_chip: &Poseidon2PeripheryChip<F>,
+    rng: &mut StdRng,
+) {
+    let lhs = gen_chunk(rng);
+    let rhs = gen_chunk(rng);
+    let (instruction, dst) = set_instruction(tester, rng, &lhs, &rhs);
+    tester.execute(chip, &instruction);
+
+    let expected = poseidon2_chip
+        .compress(
+            &lhs.map(F::from_canonical_u32),
+            &rhs.map(F::from_canonical_u32),
+        )
+        .map(|x| x.as_canonical_u32());
+    assert_eq!(
+        chunk_to_cells(&expected),
+        tester.read::<POSEIDON2_CHUNK_U8S>(2, dst)
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// POSITIVE TESTS
+///
+/// Randomly generate computations and execute, ensuring that the generated trace
+/// passes all constraints.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn rand_poseidon2_test() {
+    setup_tracing();
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, poseidon2_chip, bitwise_chip) = setup(&tester);
+
+    let num_tests: usize = 20;
+    for _ in 0..num_tests {
+        set_and_execute(&mut tester, &mut chip, &poseidon2_chip, &mut rng);
+    }
+
+    let tester = tester
+        .build()
+        .load(chip)
+        .load(poseidon2_chip)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the output of the trace and check that the constraints or interactions fail.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_negative_poseidon2_test(
+    modify_cols: impl Fn(&mut Rv32Poseidon2Cols<F>),
+    expected_error: VerificationError,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, poseidon2_chip, bitwise_chip) = setup(&tester);
+
+    set_and_execute(&mut tester, &mut chip, &poseidon2_chip, &mut rng);
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut trace_row = trace.row_slice(0).to_vec();
+        let cols: &mut Rv32Poseidon2Cols<F> = trace_row.as_mut_slice().borrow_mut();
+        modify_cols(cols);
+        *trace = RowMajorMatrix::new(trace_row, trace.width());
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(poseidon2_chip)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn negative_poseidon2_wrong_output_test() {
+    run_negative_poseidon2_test(
+        |cols| cols.output.bytes[3][0] += F::ONE,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that execution rejects non-canonical inputs.
+///////////////////////////////////////////////////////////////////////////////////////
+#[test]
+fn poseidon2_non_canonical_input_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let (mut chip, _, _) = setup(&tester);
+
+    let lhs = gen_chunk(&mut rng);
+    let mut rhs = gen_chunk(&mut rng);
+    rhs[5] = F::ORDER_U32 + rng.gen_range(0..(u32::MAX - F::ORDER_U32));
+    let (instruction, _) = set_instruction(&mut tester, &mut rng, &lhs, &rhs);
+
+    let from_state = ExecutionState::new(0, tester.memory_controller().borrow().timestamp());
+    let result = chip.execute(
+        &mut tester.memory_controller().borrow_mut(),
+        &instruction,
+        from_state,
+    );
+    assert!(matches!(result, Err(ExecutionError::Fail { .. })));
+}
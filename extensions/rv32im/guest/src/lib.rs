@@ -7,6 +7,11 @@ mod io;
 
 #[cfg(target_os = "zkvm")]
 pub use io::*;
+/// Poseidon2 compression of BabyBear elements.
+#[cfg(target_os = "zkvm")]
+mod poseidon2;
+#[cfg(target_os = "zkvm")]
+pub use poseidon2::*;
 use strum_macros::FromRepr;
 
 /// This is custom-0 defined in RISC-V spec document
@@ -24,6 +29,22 @@ pub const HINT_BUFFER_IMM: u32 = 1;
 pub const REVEAL_FUNCT3: u8 = 0b010;
 pub const PHANTOM_FUNCT3: u8 = 0b011;
 pub const CSRRW_FUNCT3: u8 = 0b001;
+pub const CSRRS_FUNCT3: u8 = 0b010;
+/// The Poseidon2 compression shares funct3 with the hash precompiles, which use funct7 `0..=6`.
+pub const POSEIDON2_FUNCT3: u8 = 0b100;
+pub const POSEIDON2_FUNCT7: u8 = 0x7;
+
+/// Zicntr counter CSRs, readable with `rdcycle`, `rdtime` and `rdinstret` and their `h` variants.
+pub const CSR_CYCLE: u16 = 0xc00;
+pub const CSR_TIME: u16 = 0xc01;
+pub const CSR_INSTRET: u16 = 0xc02;
+pub const CSR_CYCLEH: u16 = 0xc80;
+pub const CSR_TIMEH: u16 = 0xc81;
+pub const CSR_INSTRETH: u16 = 0xc82;
+
+/// The AMO major opcode of the RV32A extension, and the funct3 of its word-sized instructions.
+pub const AMO_OPCODE: u8 = 0b0101111;
+pub const AMO_W_FUNCT3: u8 = 0b010;
 
 /// imm options for system phantom instructions
 #[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
@@ -0,0 +1,23 @@
+use crate::{POSEIDON2_FUNCT3, POSEIDON2_FUNCT7, SYSTEM_OPCODE};
+
+/// Writes the Poseidon2 compression of `lhs` and `rhs` to `output`, using the same permutation
+/// and round constants as the VM memory Merkle tree.
+///
+/// Each of `lhs`, `rhs` and `output` consists of 8 BabyBear elements, stored as little-endian
+/// `u32` words in canonical form. The VM terminates with an error if an input word is not less
+/// than the BabyBear modulus.
+/// # Safety
+///
+/// - `lhs` and `rhs` must point to buffers that are at least 32-bytes long.
+/// - `output` must point to a buffer that is at least 32-bytes long.
+#[inline(always)]
+pub fn native_poseidon2_compress(output: *mut u32, lhs: *const u32, rhs: *const u32) {
+    openvm_custom_insn::custom_insn_r!(
+        opcode = SYSTEM_OPCODE,
+        funct3 = POSEIDON2_FUNCT3,
+        funct7 = POSEIDON2_FUNCT7,
+        rd = In output,
+        rs1 = In lhs,
+        rs2 = In rhs
+    );
+}
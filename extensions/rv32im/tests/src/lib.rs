@@ -4,7 +4,10 @@ mod tests {
 
     use eyre::Result;
     use openvm_circuit::{
-        arch::{hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, Streams, VmExecutor},
+        arch::{
+            hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, Streams, VmExecutor,
+            DEFAULT_BACKTRACE_LEN,
+        },
         system::memory::tree::public_values::UserPublicValuesProof,
         utils::{air_test, air_test_with_min_segments},
     };
@@ -201,7 +204,7 @@ mod tests {
 
     #[test]
     fn test_heap_overflow() -> Result<()> {
-        let config = Rv32ImConfig::default();
+        let mut config = Rv32ImConfig::default();
         let elf = build_example_program_at_path(get_programs_dir!(), "heap_overflow", &config)?;
         let exe = VmExe::from_elf(
             elf,
@@ -212,11 +215,30 @@ mod tests {
         )?;
 
         let executor = VmExecutor::<F, _>::new(config.clone());
-        match executor.execute(exe, vec![[0, 0, 0, 1].map(F::from_canonical_u8).to_vec()]) {
-            Err(ExecutionError::FailedWithExitCode(_)) => Ok(()),
+        match executor.execute(
+            exe.clone(),
+            vec![[0, 0, 0, 1].map(F::from_canonical_u8).to_vec()],
+        ) {
+            Err(ExecutionError::FailedWithExitCode {
+                backtrace: None, ..
+            }) => {}
             Err(_) => panic!("should fail with `FailedWithExitCode`"),
             Ok(_) => panic!("should fail"),
         }
+
+        config.rv32i.system.backtrace_len = DEFAULT_BACKTRACE_LEN;
+        let executor = VmExecutor::<F, _>::new(config);
+        match executor.execute(exe, vec![[0, 0, 0, 1].map(F::from_canonical_u8).to_vec()]) {
+            Err(ExecutionError::FailedWithExitCode {
+                backtrace: Some(backtrace),
+                ..
+            }) => {
+                assert!(!backtrace.recent_instructions.is_empty());
+                Ok(())
+            }
+            Err(_) => panic!("should fail with a guest backtrace"),
+            Ok(_) => panic!("should fail"),
+        }
     }
 
     #[test]
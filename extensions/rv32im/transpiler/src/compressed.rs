@@ -0,0 +1,83 @@
+use openvm_instructions::{
+    instruction::Instruction, riscv::RV32_REGISTER_AS, LocalOpcode, SysPhantom, SystemOpcode,
+    VmOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::CompressedHandler;
+use strum::EnumCount;
+
+use crate::{
+    BaseAluOpcode, BranchEqualOpcode, Rv32CBaseAluOpcode, Rv32CBranchEqualOpcode,
+    Rv32CJalLuiOpcode, Rv32CJalrOpcode, Rv32CLoadStoreOpcode, Rv32CShiftOpcode, Rv32JalLuiOpcode,
+    Rv32JalrOpcode, Rv32LoadStoreOpcode, ShiftOpcode,
+};
+
+/// Support for RV32C compressed instructions, to be installed with
+/// [Transpiler::with_compressed_handler](openvm_transpiler::transpiler::Transpiler::with_compressed_handler).
+///
+/// The RV32I instructions that compressed instructions expand to are mapped to the `Rv32C*`
+/// opcodes, which are executed by the `Rv32C` VM extension. A `NOP` is mapped to `ADD x0, x0, 0`,
+/// since the phantom `NOP` always advances the pc by 4.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rv32CompressedHandler;
+
+impl<F: PrimeField32> CompressedHandler<F> for Rv32CompressedHandler {
+    fn compress(&self, instruction: Instruction<F>) -> Option<Instruction<F>> {
+        let opcode = instruction.opcode;
+        if opcode == SystemOpcode::TERMINATE.global_opcode() {
+            return Some(instruction);
+        }
+        if opcode == SystemOpcode::PHANTOM.global_opcode() {
+            let discriminant = instruction.c.as_canonical_u32() as u16;
+            return match SysPhantom::from_repr(discriminant)? {
+                SysPhantom::Nop => Some(Instruction::from_usize(
+                    Rv32CBaseAluOpcode(BaseAluOpcode::ADD).global_opcode(),
+                    [0, 0, 0, RV32_REGISTER_AS as usize, 0],
+                )),
+                // Traps do not advance the pc.
+                SysPhantom::Trap => Some(instruction),
+                _ => None,
+            };
+        }
+        Some(Instruction {
+            opcode: compressed_opcode(opcode)?,
+            ..instruction
+        })
+    }
+}
+
+/// Returns the `Rv32C*` opcode corresponding to the RV32I `opcode`, if any.
+fn compressed_opcode(opcode: VmOpcode) -> Option<VmOpcode> {
+    let local_opcode = |class_offset: usize, count: usize| {
+        opcode
+            .as_usize()
+            .checked_sub(class_offset)
+            .filter(|&idx| idx < count)
+    };
+    if let Some(idx) = local_opcode(BaseAluOpcode::CLASS_OFFSET, BaseAluOpcode::COUNT) {
+        Some(Rv32CBaseAluOpcode(BaseAluOpcode::from_usize(idx)).global_opcode())
+    } else if let Some(idx) = local_opcode(ShiftOpcode::CLASS_OFFSET, ShiftOpcode::COUNT) {
+        Some(Rv32CShiftOpcode(ShiftOpcode::from_usize(idx)).global_opcode())
+    } else if let Some(idx) = local_opcode(
+        Rv32LoadStoreOpcode::CLASS_OFFSET,
+        Rv32LoadStoreOpcode::COUNT,
+    ) {
+        let local_opcode = Rv32LoadStoreOpcode::from_usize(idx);
+        matches!(
+            local_opcode,
+            Rv32LoadStoreOpcode::LOADW | Rv32LoadStoreOpcode::STOREW
+        )
+        .then(|| Rv32CLoadStoreOpcode(local_opcode).global_opcode())
+    } else if let Some(idx) =
+        local_opcode(BranchEqualOpcode::CLASS_OFFSET, BranchEqualOpcode::COUNT)
+    {
+        Some(Rv32CBranchEqualOpcode(BranchEqualOpcode::from_usize(idx)).global_opcode())
+    } else if let Some(idx) = local_opcode(Rv32JalLuiOpcode::CLASS_OFFSET, Rv32JalLuiOpcode::COUNT)
+    {
+        Some(Rv32CJalLuiOpcode(Rv32JalLuiOpcode::from_usize(idx)).global_opcode())
+    } else if let Some(idx) = local_opcode(Rv32JalrOpcode::CLASS_OFFSET, Rv32JalrOpcode::COUNT) {
+        Some(Rv32CJalrOpcode(Rv32JalrOpcode::from_usize(idx)).global_opcode())
+    } else {
+        None
+    }
+}
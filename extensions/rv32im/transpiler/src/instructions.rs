@@ -9,8 +9,9 @@
 
 use openvm_instructions::LocalOpcode;
 use openvm_instructions_derive::LocalOpcode;
+use openvm_rv32im_guest::{CSR_CYCLE, CSR_CYCLEH, CSR_INSTRET, CSR_INSTRETH, CSR_TIME, CSR_TIMEH};
 use serde::{Deserialize, Serialize};
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy,
@@ -191,6 +192,55 @@ pub enum Rv32AuipcOpcode {
     AUIPC,
 }
 
+/// Reads of the counter CSRs. All counters are the 64-bit VM timestamp at the start of the
+/// instruction, counted from the start of the program across continuation segments.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x245]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32CsrOpcode {
+    RDCYCLE,
+    RDCYCLEH,
+    RDTIME,
+    RDTIMEH,
+    RDINSTRET,
+    RDINSTRETH,
+}
+
+impl Rv32CsrOpcode {
+    /// Returns the opcode reading the CSR with address `csr`, if it is supported.
+    pub fn from_csr(csr: u16) -> Option<Self> {
+        match csr {
+            CSR_CYCLE => Some(Self::RDCYCLE),
+            CSR_CYCLEH => Some(Self::RDCYCLEH),
+            CSR_TIME => Some(Self::RDTIME),
+            CSR_TIMEH => Some(Self::RDTIMEH),
+            CSR_INSTRET => Some(Self::RDINSTRET),
+            CSR_INSTRETH => Some(Self::RDINSTRETH),
+            _ => None,
+        }
+    }
+
+    /// Whether the opcode reads the upper 32 bits of a counter.
+    pub fn is_high(&self) -> bool {
+        matches!(self, Self::RDCYCLEH | Self::RDTIMEH | Self::RDINSTRETH)
+    }
+}
+
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
 )]
@@ -250,6 +300,145 @@ pub enum DivRemOpcode {
     REMU,
 }
 
+/// Atomic memory operations of the RV32A extension. `LR.W` is transpiled to `LOADW`.
+///
+/// The VM is single-threaded, so `SC.W` always succeeds: it stores rs2 and writes 0 to rd.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumCount,
+    EnumIter,
+    FromRepr,
+    LocalOpcode,
+    Serialize,
+    Deserialize,
+)]
+#[opcode_offset = 0x270]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32AmoOpcode {
+    AMOSWAP_W,
+    AMOADD_W,
+    AMOXOR_W,
+    AMOAND_W,
+    AMOOR_W,
+    AMOMIN_W,
+    AMOMAX_W,
+    AMOMINU_W,
+    AMOMAXU_W,
+    SC_W,
+}
+
+impl Rv32AmoOpcode {
+    /// Returns the opcode with the given `funct5`, or `None` for `LR.W` and unknown values.
+    pub fn from_funct5(funct5: u8) -> Option<Self> {
+        match funct5 {
+            0b00001 => Some(Self::AMOSWAP_W),
+            0b00000 => Some(Self::AMOADD_W),
+            0b00100 => Some(Self::AMOXOR_W),
+            0b01100 => Some(Self::AMOAND_W),
+            0b01000 => Some(Self::AMOOR_W),
+            0b10000 => Some(Self::AMOMIN_W),
+            0b10100 => Some(Self::AMOMAX_W),
+            0b11000 => Some(Self::AMOMINU_W),
+            0b11100 => Some(Self::AMOMAXU_W),
+            0b00011 => Some(Self::SC_W),
+            _ => None,
+        }
+    }
+
+    /// Inverse of [Self::from_funct5].
+    pub fn funct5(&self) -> u8 {
+        match self {
+            Self::AMOSWAP_W => 0b00001,
+            Self::AMOADD_W => 0b00000,
+            Self::AMOXOR_W => 0b00100,
+            Self::AMOAND_W => 0b01100,
+            Self::AMOOR_W => 0b01000,
+            Self::AMOMIN_W => 0b10000,
+            Self::AMOMAX_W => 0b10100,
+            Self::AMOMINU_W => 0b11000,
+            Self::AMOMAXU_W => 0b11100,
+            Self::SC_W => 0b00011,
+        }
+    }
+}
+
+// =================================================================================================
+// RV32C support opcodes.
+// Compressed instructions are executed as their 32-bit equivalents, except that the next pc and
+// link address are `pc + 2`. The opcodes below are those of the 32-bit instructions at a
+// different offset, for chips with a pc step of 2.
+// =================================================================================================
+
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x280]
+pub struct Rv32CBaseAluOpcode(pub BaseAluOpcode);
+
+impl Rv32CBaseAluOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BaseAluOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x285]
+pub struct Rv32CShiftOpcode(pub ShiftOpcode);
+
+impl Rv32CShiftOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        ShiftOpcode::iter().map(Self)
+    }
+}
+
+/// Only `LOADW` and `STOREW` have compressed encodings.
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x290]
+pub struct Rv32CLoadStoreOpcode(pub Rv32LoadStoreOpcode);
+
+impl Rv32CLoadStoreOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Rv32LoadStoreOpcode::LOADW, Rv32LoadStoreOpcode::STOREW]
+            .into_iter()
+            .map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x2a0]
+pub struct Rv32CBranchEqualOpcode(pub BranchEqualOpcode);
+
+impl Rv32CBranchEqualOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BranchEqualOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x2b0]
+pub struct Rv32CJalLuiOpcode(pub Rv32JalLuiOpcode);
+
+impl Rv32CJalLuiOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32JalLuiOpcode::iter().map(Self)
+    }
+}
+
+#[derive(Copy, Clone, Debug, LocalOpcode)]
+#[opcode_offset = 0x2b5]
+pub struct Rv32CJalrOpcode(pub Rv32JalrOpcode);
+
+impl Rv32CJalrOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Rv32JalrOpcode::iter().map(Self)
+    }
+}
+
 // =================================================================================================
 // Rv32HintStore Instruction
 // =================================================================================================
@@ -265,6 +454,22 @@ pub enum Rv32HintStoreOpcode {
     HINT_BUFFER,
 }
 
+// =================================================================================================
+// Rv32Poseidon2 Instruction
+// =================================================================================================
+
+/// Poseidon2 compression of two chunks of 8 BabyBear elements, each element stored in memory as
+/// a little-endian `u32` word.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x2e0]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32Poseidon2Opcode {
+    COMPRESS,
+}
+
 // =================================================================================================
 // Phantom opcodes
 // =================================================================================================
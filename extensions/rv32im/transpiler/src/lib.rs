@@ -1,13 +1,15 @@
 use std::marker::PhantomData;
 
 use openvm_instructions::{
-    instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, LocalOpcode, PhantomDiscriminant,
-    SystemOpcode,
+    instruction::Instruction,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
+    LocalOpcode, PhantomDiscriminant, SystemOpcode,
 };
 use openvm_rv32im_guest::{
-    PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_BUFFER_IMM, HINT_FUNCT3, HINT_STOREW_IMM,
-    NATIVE_STOREW_FUNCT3, NATIVE_STOREW_FUNCT7, PHANTOM_FUNCT3, REVEAL_FUNCT3, RV32M_FUNCT7,
-    RV32_ALU_OPCODE, SYSTEM_OPCODE, TERMINATE_FUNCT3,
+    PhantomImm, AMO_OPCODE, AMO_W_FUNCT3, CSRRS_FUNCT3, CSRRW_FUNCT3, CSR_OPCODE, HINT_BUFFER_IMM,
+    HINT_FUNCT3, HINT_STOREW_IMM, NATIVE_STOREW_FUNCT3, NATIVE_STOREW_FUNCT7, PHANTOM_FUNCT3,
+    POSEIDON2_FUNCT3, POSEIDON2_FUNCT7, REVEAL_FUNCT3, RV32M_FUNCT7, RV32_ALU_OPCODE,
+    SYSTEM_OPCODE, TERMINATE_FUNCT3,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{
@@ -20,9 +22,13 @@ use rrs_lib::{
     process_instruction,
 };
 
+mod compressed;
 mod instructions;
 pub mod rrs;
+mod trap;
+pub use compressed::*;
 pub use instructions::*;
+pub use trap::*;
 
 #[derive(Default)]
 pub struct Rv32ITranspilerExtension;
@@ -33,6 +39,12 @@ pub struct Rv32MTranspilerExtension;
 #[derive(Default)]
 pub struct Rv32IoTranspilerExtension;
 
+#[derive(Default)]
+pub struct Rv32ATranspilerExtension;
+
+#[derive(Default)]
+pub struct Rv32Poseidon2TranspilerExtension;
+
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
         let mut transpiler = InstructionTranspiler::<F>(PhantomData);
@@ -46,6 +58,21 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
 
         let instruction = match (opcode, funct3) {
             (CSR_OPCODE, _) => {
+                if let Some((csr_opcode, rd)) = decode_csr_read(instruction_u32) {
+                    // Reading a counter has no side effects.
+                    if rd == 0 {
+                        return Some(TranspilerOutput::one_to_one(nop()));
+                    }
+                    return Some(TranspilerOutput::one_to_one(Instruction::from_usize(
+                        csr_opcode.global_opcode(),
+                        [
+                            RV32_REGISTER_NUM_LIMBS * rd,
+                            0,
+                            0,
+                            RV32_REGISTER_AS as usize,
+                        ],
+                    )));
+                }
                 let dec_insn = IType::new(instruction_u32);
                 if dec_insn.funct3 as u8 == CSRRW_FUNCT3 {
                     // CSRRW
@@ -116,6 +143,18 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
     }
 }
 
+/// Decodes `csrrs rd, csr, x0` for a supported counter `csr` into the opcode and `rd`.
+pub(crate) fn decode_csr_read(instruction_u32: u32) -> Option<(Rv32CsrOpcode, usize)> {
+    let dec_insn = IType::new(instruction_u32);
+    if (instruction_u32 & 0x7f) as u8 != CSR_OPCODE
+        || dec_insn.funct3 as u8 != CSRRS_FUNCT3
+        || dec_insn.rs1 != 0
+    {
+        return None;
+    }
+    Rv32CsrOpcode::from_csr((instruction_u32 >> 20) as u16).map(|opcode| (opcode, dec_insn.rd))
+}
+
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32MTranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
         if instruction_stream.is_empty() {
@@ -219,3 +258,83 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
         instruction.map(TranspilerOutput::one_to_one)
     }
 }
+
+/// `funct5` of `LR.W`, which is transpiled to `LOADW` since reservations always succeed.
+const LR_W_FUNCT5: u8 = 0b00010;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ATranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+        if opcode != AMO_OPCODE || funct3 != AMO_W_FUNCT3 {
+            return None;
+        }
+
+        // The aq and rl bits are ignored: there is only one hart.
+        let dec_insn = RType::new(instruction_u32);
+        let funct5 = (dec_insn.funct7 >> 2) as u8;
+        let instruction = if funct5 == LR_W_FUNCT5 {
+            if dec_insn.rs2 != 0 {
+                return None;
+            }
+            Instruction::large_from_isize(
+                Rv32LoadStoreOpcode::LOADW.global_opcode(),
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rs1) as isize,
+                0,
+                RV32_REGISTER_AS as isize,
+                RV32_MEMORY_AS as isize,
+                (dec_insn.rd != 0) as isize,
+                0,
+            )
+        } else {
+            let amo_opcode = Rv32AmoOpcode::from_funct5(funct5)?;
+            Instruction::large_from_isize(
+                amo_opcode.global_opcode(),
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rs1) as isize,
+                (RV32_REGISTER_NUM_LIMBS * dec_insn.rs2) as isize,
+                RV32_REGISTER_AS as isize,
+                RV32_MEMORY_AS as isize,
+                (dec_insn.rd != 0) as isize,
+                0,
+            )
+        };
+
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32Poseidon2TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+        if opcode != SYSTEM_OPCODE || funct3 != POSEIDON2_FUNCT3 {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+        if dec_insn.funct7 != POSEIDON2_FUNCT7 as u32 {
+            return None;
+        }
+
+        let instruction = Instruction::from_isize(
+            Rv32Poseidon2Opcode::COMPRESS.global_opcode(),
+            (RV32_REGISTER_NUM_LIMBS * dec_insn.rd) as isize,
+            (RV32_REGISTER_NUM_LIMBS * dec_insn.rs1) as isize,
+            (RV32_REGISTER_NUM_LIMBS * dec_insn.rs2) as isize,
+            RV32_REGISTER_AS as isize,
+            RV32_MEMORY_AS as isize,
+        );
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
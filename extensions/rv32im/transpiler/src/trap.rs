@@ -0,0 +1,91 @@
+use openvm_instructions::{instruction::Instruction, LocalOpcode, TrapCause};
+use openvm_rv32im_guest::{CSRRW_FUNCT3, CSR_OPCODE};
+use openvm_stark_backend::p3_field::PrimeField32;
+use openvm_transpiler::{
+    transpiler::TranspilerError,
+    util::{from_j_type, trap},
+    TrapHandler,
+};
+use rrs_lib::instruction_formats::{IType, JType};
+use serde::{Deserialize, Serialize};
+
+use crate::{decode_csr_read, Rv32JalLuiOpcode};
+
+pub const ECALL: u32 = 0x00000073;
+pub const EBREAK: u32 = 0x00100073;
+/// Register receiving the address of the instruction following the trapping instruction when
+/// jumping to a trap vector. This is `t0`, the alternate link register of the RISC-V calling
+/// convention.
+pub const TRAP_LINK_REGISTER: usize = 5;
+
+/// Largest jump distance, in bytes, of `JAL`.
+const JAL_MAX_OFFSET: i64 = 1 << 20;
+
+/// Trap semantics for RV32 guests, to be installed with
+/// [Transpiler::with_trap_handler](openvm_transpiler::transpiler::Transpiler::with_trap_handler).
+///
+/// `ecall`, `ebreak`, unsupported system instructions and instructions that no extension can
+/// transpile raise a trap. By default, a trap stops execution with `ExecutionError::Trap`. If
+/// `trap_vector` is set, a trap with cause `c` instead jumps to `trap_vector + 4 * c` and writes
+/// the address of the next instruction to [TRAP_LINK_REGISTER], similar to the vectored mode of
+/// `mtvec`. The trap vector must be within `JAL` range of every trapping instruction.
+///
+/// Misaligned loads and stores are only detected at runtime: they stop execution with
+/// `ExecutionError::Trap` if `SystemConfig::trap_misaligned` is set, which `SdkVmConfig` derives
+/// from whether a trap handler is configured.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rv32TrapHandler {
+    #[serde(default)]
+    pub trap_vector: Option<u32>,
+}
+
+impl Rv32TrapHandler {
+    pub fn with_trap_vector(trap_vector: u32) -> Self {
+        Self {
+            trap_vector: Some(trap_vector),
+        }
+    }
+}
+
+impl<F: PrimeField32> TrapHandler<F> for Rv32TrapHandler {
+    fn trap_cause(&self, instruction_u32: u32) -> Option<TrapCause> {
+        match instruction_u32 {
+            ECALL => Some(TrapCause::EnvironmentCall),
+            EBREAK => Some(TrapCause::Breakpoint),
+            _ if (instruction_u32 & 0x7f) as u8 == CSR_OPCODE => {
+                if decode_csr_read(instruction_u32).is_some() {
+                    return None;
+                }
+                let dec_insn = IType::new(instruction_u32);
+                // `csrrw x0, csr, x0` is transpiled to a nop by the RV32I extension.
+                let is_nop =
+                    dec_insn.funct3 as u8 == CSRRW_FUNCT3 && dec_insn.rs1 == 0 && dec_insn.rd == 0;
+                (!is_nop).then_some(TrapCause::IllegalInstruction)
+            }
+            _ => None,
+        }
+    }
+
+    fn trap(
+        &self,
+        pc: u32,
+        cause: TrapCause,
+        instruction_u32: u32,
+    ) -> Result<Instruction<F>, TranspilerError> {
+        let Some(trap_vector) = self.trap_vector else {
+            return Ok(trap(cause, instruction_u32));
+        };
+        let target = trap_vector as i64 + 4 * cause as i64;
+        let offset = target - pc as i64;
+        if !(-JAL_MAX_OFFSET..JAL_MAX_OFFSET).contains(&offset) {
+            return Err(TranspilerError::TrapVectorOutOfRange { pc, trap_vector });
+        }
+        Ok(from_j_type(
+            Rv32JalLuiOpcode::JAL.global_opcode().as_usize(),
+            &JType {
+                imm: offset as i32,
+                rd: TRAP_LINK_REGISTER,
+            },
+        ))
+    }
+}
@@ -16,7 +16,7 @@ use openvm_rv32im_circuit::{
     Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
     Rv32MExecutor, Rv32MPeriphery,
 };
-use openvm_sha256_transpiler::Rv32Sha256Opcode;
+use openvm_sha256_transpiler::{Rv32Sha256Opcode, Rv32Sha512Opcode};
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
@@ -52,6 +52,35 @@ impl Default for Sha256Rv32Config {
 // Default implementation uses no init file
 impl InitFileGenerator for Sha256Rv32Config {}
 
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Sha512Rv32Config {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub rv32i: Rv32I,
+    #[extension]
+    pub rv32m: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub sha512: Sha512,
+}
+
+impl Default for Sha512Rv32Config {
+    fn default() -> Self {
+        Self {
+            system: SystemConfig::default().with_continuations(),
+            rv32i: Rv32I,
+            rv32m: Rv32M::default(),
+            io: Rv32Io,
+            sha512: Sha512,
+        }
+    }
+}
+
+// Default implementation uses no init file
+impl InitFileGenerator for Sha512Rv32Config {}
+
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct Sha256;
 
@@ -103,3 +132,63 @@ impl<F: PrimeField32> VmExtension<F> for Sha256 {
         Ok(inventory)
     }
 }
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Sha512;
+
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Sha512Executor<F: PrimeField32> {
+    Sha512(Sha512VmChip<F>),
+    Sha384(Sha384VmChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Sha512Periphery<F: PrimeField32> {
+    BitwiseOperationLookup(SharedBitwiseOperationLookupChip<8>),
+    Phantom(PhantomChip<F>),
+}
+
+impl<F: PrimeField32> VmExtension<F> for Sha512 {
+    type Executor = Sha512Executor<F>;
+    type Periphery = Sha512Periphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Self::Executor, Self::Periphery>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let bitwise_lu_chip = if let Some(&chip) = builder
+            .find_chip::<SharedBitwiseOperationLookupChip<8>>()
+            .first()
+        {
+            chip.clone()
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = SharedBitwiseOperationLookupChip::new(bitwise_lu_bus);
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let sha512_chip = Sha512VmChip::new(
+            builder.system_port(),
+            builder.system_config().memory_config.pointer_max_bits,
+            bitwise_lu_chip.clone(),
+            builder.new_bus_idx(),
+            Rv32Sha512Opcode::CLASS_OFFSET,
+            builder.system_base().offline_memory(),
+        );
+        inventory.add_executor(sha512_chip, [Rv32Sha512Opcode::SHA512.global_opcode()])?;
+
+        let sha384_chip = Sha384VmChip::new(
+            builder.system_port(),
+            builder.system_config().memory_config.pointer_max_bits,
+            bitwise_lu_chip,
+            builder.new_bus_idx(),
+            Rv32Sha512Opcode::CLASS_OFFSET,
+            builder.system_base().offline_memory(),
+        );
+        inventory.add_executor(sha384_chip, [Rv32Sha512Opcode::SHA384.global_opcode()])?;
+
+        Ok(inventory)
+    }
+}
@@ -1,4 +1,8 @@
-use std::{array, borrow::Borrow, cmp::min};
+use std::{
+    array,
+    borrow::Borrow,
+    cmp::{max, min},
+};
 
 use openvm_circuit::{
     arch::ExecutionBridge,
@@ -11,11 +15,7 @@ use openvm_instructions::{
     riscv::{RV32_CELL_BITS, RV32_MEMORY_AS, RV32_REGISTER_AS, RV32_REGISTER_NUM_LIMBS},
     LocalOpcode,
 };
-use openvm_sha256_air::{
-    compose, Sha256Air, SHA256_BLOCK_U8S, SHA256_HASH_WORDS, SHA256_ROUNDS_PER_ROW,
-    SHA256_WORD_U16S, SHA256_WORD_U8S,
-};
-use openvm_sha256_transpiler::Rv32Sha256Opcode;
+use openvm_sha256_air::{compose, Sha2Air};
 use openvm_stark_backend::{
     interaction::InteractionBuilder,
     p3_air::{Air, AirBuilder, BaseAir},
@@ -25,14 +25,22 @@ use openvm_stark_backend::{
 };
 
 use super::{
-    Sha256VmDigestCols, Sha256VmRoundCols, SHA256VM_CONTROL_WIDTH, SHA256VM_DIGEST_WIDTH,
-    SHA256VM_ROUND_WIDTH, SHA256VM_WIDTH, SHA256_READ_SIZE,
+    Sha2ChipConfig, Sha2VmControlCols, Sha2VmDigestCols, Sha2VmRoundCols, SHA2_WRITE_SIZE,
 };
 
-/// Sha256VmAir does all constraints related to message padding and
-/// the Sha256Air subair constrains the actual hash
+/// Sha2VmAir does all constraints related to message padding and
+/// the Sha2Air subair constrains the actual hash
 #[derive(Clone, Debug, derive_new::new)]
-pub struct Sha256VmAir {
+pub struct Sha2VmAir<
+    C: Sha2ChipConfig,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+    const READ_SIZE: usize,
+    const PAD_VAR_CNT: usize,
+    const NUM_WRITES: usize,
+> {
     pub execution_bridge: ExecutionBridge,
     pub memory_bridge: MemoryBridge,
     /// Bus to send byte checks to
@@ -40,64 +48,132 @@ pub struct Sha256VmAir {
     /// Maximum number of bits allowed for an address pointer
     /// Must be at least 24
     pub ptr_max_bits: usize,
-    pub(super) sha256_subair: Sha256Air,
+    pub(super) sha2_subair: Sha2Air<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
     pub(super) padding_encoder: Encoder,
 }
 
-impl<F: Field> BaseAirWithPublicValues<F> for Sha256VmAir {}
-impl<F: Field> PartitionedBaseAir<F> for Sha256VmAir {}
-impl<F: Field> BaseAir<F> for Sha256VmAir {
+impl<
+        F: Field,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    > BaseAirWithPublicValues<F>
+    for Sha2VmAir<
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
+}
+impl<
+        F: Field,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    > PartitionedBaseAir<F>
+    for Sha2VmAir<
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
+}
+impl<
+        F: Field,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    > BaseAir<F>
+    for Sha2VmAir<
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
     fn width(&self) -> usize {
-        SHA256VM_WIDTH
+        max(Self::ROUND_WIDTH, Self::DIGEST_WIDTH)
     }
 }
 
-impl<AB: InteractionBuilder> Air<AB> for Sha256VmAir {
+impl<
+        AB: InteractionBuilder,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    > Air<AB>
+    for Sha2VmAir<
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
     fn eval(&self, builder: &mut AB) {
         self.eval_padding(builder);
         self.eval_transitions(builder);
         self.eval_reads(builder);
         self.eval_last_row(builder);
 
-        self.sha256_subair.eval(builder, SHA256VM_CONTROL_WIDTH);
+        self.sha2_subair.eval(builder, Self::CONTROL_WIDTH);
     }
 }
 
-#[allow(dead_code, non_camel_case_types)]
-pub(super) enum PaddingFlags {
+/// The padding flags of a row, encoded using the padding encoder of [Sha2VmAir].
+/// The flags are laid out according to the number of cells read per row, `read_size`, see
+/// [PaddingFlags::idx].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingFlags {
     /// Not considered for padding - W's are not constrained
     NotConsidered,
     /// Not padding - W's should be equal to the message
     NotPadding,
-    /// FIRST_PADDING_i: it is the first row with padding and there are i cells of non-padding
-    FirstPadding0,
-    FirstPadding1,
-    FirstPadding2,
-    FirstPadding3,
-    FirstPadding4,
-    FirstPadding5,
-    FirstPadding6,
-    FirstPadding7,
-    FirstPadding8,
-    FirstPadding9,
-    FirstPadding10,
-    FirstPadding11,
-    FirstPadding12,
-    FirstPadding13,
-    FirstPadding14,
-    FirstPadding15,
-    /// FIRST_PADDING_i_LastRow: it is the first row with padding and there are i cells of
-    /// non-padding                          AND it is the last reading row of the message
-    /// NOTE: if the Last row has padding it has to be at least 9 cells since the last 8 cells are
-    /// padded with the message length
-    FirstPadding0_LastRow,
-    FirstPadding1_LastRow,
-    FirstPadding2_LastRow,
-    FirstPadding3_LastRow,
-    FirstPadding4_LastRow,
-    FirstPadding5_LastRow,
-    FirstPadding6_LastRow,
-    FirstPadding7_LastRow,
+    /// FirstPadding(i): it is the first row with padding and there are i cells of non-padding,
+    /// for 0 <= i < read_size
+    FirstPadding(usize),
+    /// FirstPaddingLastRow(i): it is the first row with padding and there are i cells of
+    /// non-padding AND it is the last reading row of the message, for 0 <= i < read_size / 2
+    /// NOTE: if the Last row has padding it has to be at least read_size / 2 + 1 cells since the
+    /// last read_size / 2 cells are padded with the message length
+    FirstPaddingLastRow(usize),
     /// The entire row is padding AND it is not the first row with padding
     /// AND it is the 4th row of the last block of the message
     EntirePaddingLastRow,
@@ -106,18 +182,75 @@ pub(super) enum PaddingFlags {
 }
 
 impl PaddingFlags {
+    /// The index of the flag in the padding encoder, when reading `read_size` cells per row
+    pub const fn idx(self, read_size: usize) -> usize {
+        match self {
+            NotConsidered => 0,
+            NotPadding => 1,
+            FirstPadding(i) => 2 + i,
+            FirstPaddingLastRow(i) => 2 + read_size + i,
+            EntirePaddingLastRow => 2 + read_size + read_size / 2,
+            EntirePadding => 3 + read_size + read_size / 2,
+        }
+    }
+
     /// The number of padding flags (including NotConsidered)
-    pub const COUNT: usize = EntirePadding as usize + 1;
+    pub const fn count(read_size: usize) -> usize {
+        EntirePadding.idx(read_size) + 1
+    }
 }
 
 use PaddingFlags::*;
-impl Sha256VmAir {
+impl<
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    >
+    Sha2VmAir<C, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT, READ_SIZE, PAD_VAR_CNT, NUM_WRITES>
+{
+    pub(super) const CONTROL_WIDTH: usize = Sha2VmControlCols::<u8, PAD_VAR_CNT>::width();
+    pub(super) const ROUND_WIDTH: usize =
+        Sha2VmRoundCols::<u8, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT, PAD_VAR_CNT>::width();
+    pub(super) const DIGEST_WIDTH: usize = Sha2VmDigestCols::<
+        u8,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >::width();
+
+    /// The index of `flag` in the padding encoder
+    pub(super) const fn flag_idx(flag: PaddingFlags) -> usize {
+        flag.idx(READ_SIZE)
+    }
+
     /// Implement all necessary constraints for the padding
     fn eval_padding<AB: InteractionBuilder>(&self, builder: &mut AB) {
         let main = builder.main();
         let (local, next) = (main.row_slice(0), main.row_slice(1));
-        let local_cols: &Sha256VmRoundCols<AB::Var> = local[..SHA256VM_ROUND_WIDTH].borrow();
-        let next_cols: &Sha256VmRoundCols<AB::Var> = next[..SHA256VM_ROUND_WIDTH].borrow();
+        let local_cols: &Sha2VmRoundCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+        > = local[..Self::ROUND_WIDTH].borrow();
+        let next_cols: &Sha2VmRoundCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+        > = next[..Self::ROUND_WIDTH].borrow();
 
         // Constrain the sanity of the padding flags
         self.padding_encoder
@@ -125,7 +258,7 @@ impl Sha256VmAir {
 
         builder.assert_one(self.padding_encoder.contains_flag_range::<AB>(
             &local_cols.control.pad_flags,
-            NotConsidered as usize..=EntirePadding as usize,
+            Self::flag_idx(NotConsidered)..=Self::flag_idx(EntirePadding),
         ));
 
         Self::eval_padding_transitions(self, builder, local_cols, next_cols);
@@ -135,8 +268,8 @@ impl Sha256VmAir {
     fn eval_padding_transitions<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256VmRoundCols<AB::Var>,
-        next: &Sha256VmRoundCols<AB::Var>,
+        local: &Sha2VmRoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT, PAD_VAR_CNT>,
+        next: &Sha2VmRoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT, PAD_VAR_CNT>,
     ) {
         let next_is_last_row = next.inner.flags.is_digest_row * next.inner.flags.is_last_block;
 
@@ -177,7 +310,7 @@ impl Sha256VmAir {
         let next_is_first_padding_row =
             next.control.padding_occurred - local.control.padding_occurred;
         // Row index if its between 0..4, else 0
-        let next_row_idx = self.sha256_subair.row_idx_encoder.flag_with_val::<AB>(
+        let next_row_idx = self.sha2_subair.row_idx_encoder.flag_with_val::<AB>(
             &next.inner.flags.row_idx,
             &(0..4).map(|x| (x, x)).collect::<Vec<_>>(),
         );
@@ -185,13 +318,13 @@ impl Sha256VmAir {
         // Will be 0 on non-padding rows.
         let next_padding_offset = self.padding_encoder.flag_with_val::<AB>(
             &next.control.pad_flags,
-            &(0..16)
-                .map(|i| (FirstPadding0 as usize + i, i))
+            &(0..READ_SIZE)
+                .map(|i| (Self::flag_idx(FirstPadding(i)), i))
                 .collect::<Vec<_>>(),
         ) + self.padding_encoder.flag_with_val::<AB>(
             &next.control.pad_flags,
-            &(0..8)
-                .map(|i| (FirstPadding0_LastRow as usize + i, i))
+            &(0..READ_SIZE / 2)
+                .map(|i| (Self::flag_idx(FirstPaddingLastRow(i)), i))
                 .collect::<Vec<_>>(),
         );
 
@@ -201,8 +334,8 @@ impl Sha256VmAir {
         //   - and next_padding_offset = 0 since `pad_flags = NotConsidered`
         let expected_len = next.inner.flags.local_block_idx
             * next.control.padding_occurred
-            * AB::Expr::from_canonical_usize(SHA256_BLOCK_U8S)
-            + next_row_idx * AB::Expr::from_canonical_usize(SHA256_READ_SIZE)
+            * AB::Expr::from_canonical_usize(C::BLOCK_U8S)
+            + next_row_idx * AB::Expr::from_canonical_usize(READ_SIZE)
             + next_padding_offset;
 
         // Note: `next_is_first_padding_row` is either -1,0,1
@@ -216,29 +349,30 @@ impl Sha256VmAir {
         // Constrain the padding flags are of correct type (eg is not padding or first padding)
         let is_next_first_padding = self.padding_encoder.contains_flag_range::<AB>(
             &next.control.pad_flags,
-            FirstPadding0 as usize..=FirstPadding7_LastRow as usize,
+            Self::flag_idx(FirstPadding(0))
+                ..=Self::flag_idx(FirstPaddingLastRow(READ_SIZE / 2 - 1)),
         );
 
         let is_next_last_padding = self.padding_encoder.contains_flag_range::<AB>(
             &next.control.pad_flags,
-            FirstPadding0_LastRow as usize..=EntirePaddingLastRow as usize,
+            Self::flag_idx(FirstPaddingLastRow(0))..=Self::flag_idx(EntirePaddingLastRow),
         );
 
         let is_next_entire_padding = self.padding_encoder.contains_flag_range::<AB>(
             &next.control.pad_flags,
-            EntirePaddingLastRow as usize..=EntirePadding as usize,
+            Self::flag_idx(EntirePaddingLastRow)..=Self::flag_idx(EntirePadding),
         );
 
         let is_next_not_considered = self
             .padding_encoder
-            .contains_flag::<AB>(&next.control.pad_flags, &[NotConsidered as usize]);
+            .contains_flag::<AB>(&next.control.pad_flags, &[Self::flag_idx(NotConsidered)]);
 
         let is_next_not_padding = self
             .padding_encoder
-            .contains_flag::<AB>(&next.control.pad_flags, &[NotPadding as usize]);
+            .contains_flag::<AB>(&next.control.pad_flags, &[Self::flag_idx(NotPadding)]);
 
         let is_next_4th_row = self
-            .sha256_subair
+            .sha2_subair
             .row_idx_encoder
             .contains_flag::<AB>(&next.inner.flags.row_idx, &[3]);
 
@@ -275,41 +409,42 @@ impl Sha256VmAir {
     fn eval_padding_row<AB: InteractionBuilder>(
         &self,
         builder: &mut AB,
-        local: &Sha256VmRoundCols<AB::Var>,
+        local: &Sha2VmRoundCols<AB::Var, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT, PAD_VAR_CNT>,
     ) {
-        let message: [AB::Var; SHA256_READ_SIZE] = array::from_fn(|i| {
-            local.inner.message_schedule.carry_or_buffer[i / (SHA256_WORD_U8S)]
-                [i % (SHA256_WORD_U8S)]
+        let message: [AB::Var; READ_SIZE] = array::from_fn(|i| {
+            local.inner.message_schedule.carry_or_buffer[i / WORD_U8S][i % WORD_U8S]
         });
 
         let get_ith_byte = |i: usize| {
-            let word_idx = i / SHA256_ROUNDS_PER_ROW;
+            let word_idx = i / WORD_U8S;
             let word = local.inner.message_schedule.w[word_idx].map(|x| x.into());
             // Need to reverse the byte order to match the endianness of the memory
-            let byte_idx = 4 - i % 4 - 1;
+            let byte_idx = WORD_U8S - i % WORD_U8S - 1;
             compose::<AB::Expr>(&word[byte_idx * 8..(byte_idx + 1) * 8], 1)
         };
 
         let is_not_padding = self
             .padding_encoder
-            .contains_flag::<AB>(&local.control.pad_flags, &[NotPadding as usize]);
+            .contains_flag::<AB>(&local.control.pad_flags, &[Self::flag_idx(NotPadding)]);
 
         // Check the `w`s on case by case basis
         for (i, message_byte) in message.iter().enumerate() {
             let w = get_ith_byte(i);
             let should_be_message = is_not_padding.clone()
-                + if i < 15 {
+                + if i < READ_SIZE - 1 {
                     self.padding_encoder.contains_flag_range::<AB>(
                         &local.control.pad_flags,
-                        FirstPadding0 as usize + i + 1..=FirstPadding15 as usize,
+                        Self::flag_idx(FirstPadding(i + 1))
+                            ..=Self::flag_idx(FirstPadding(READ_SIZE - 1)),
                     )
                 } else {
                     AB::Expr::ZERO
                 }
-                + if i < 7 {
+                + if i < READ_SIZE / 2 - 1 {
                     self.padding_encoder.contains_flag_range::<AB>(
                         &local.control.pad_flags,
-                        FirstPadding0_LastRow as usize + i + 1..=FirstPadding7_LastRow as usize,
+                        Self::flag_idx(FirstPaddingLastRow(i + 1))
+                            ..=Self::flag_idx(FirstPaddingLastRow(READ_SIZE / 2 - 1)),
                     )
                 } else {
                     AB::Expr::ZERO
@@ -320,19 +455,19 @@ impl Sha256VmAir {
 
             let should_be_zero = self
                 .padding_encoder
-                .contains_flag::<AB>(&local.control.pad_flags, &[EntirePadding as usize])
-                + if i < 12 {
+                .contains_flag::<AB>(&local.control.pad_flags, &[Self::flag_idx(EntirePadding)])
+                + if i < READ_SIZE - 4 {
                     self.padding_encoder.contains_flag::<AB>(
                         &local.control.pad_flags,
-                        &[EntirePaddingLastRow as usize],
+                        &[Self::flag_idx(EntirePaddingLastRow)],
                     ) + if i > 0 {
                         self.padding_encoder.contains_flag_range::<AB>(
                             &local.control.pad_flags,
-                            FirstPadding0_LastRow as usize
-                                ..=min(
-                                    FirstPadding0_LastRow as usize + i - 1,
-                                    FirstPadding7_LastRow as usize,
-                                ),
+                            Self::flag_idx(FirstPaddingLastRow(0))
+                                ..=Self::flag_idx(FirstPaddingLastRow(min(
+                                    i - 1,
+                                    READ_SIZE / 2 - 1,
+                                ))),
                         )
                     } else {
                         AB::Expr::ZERO
@@ -343,7 +478,7 @@ impl Sha256VmAir {
                 + if i > 0 {
                     self.padding_encoder.contains_flag_range::<AB>(
                         &local.control.pad_flags,
-                        FirstPadding0 as usize..=FirstPadding0 as usize + i - 1,
+                        Self::flag_idx(FirstPadding(0))..=Self::flag_idx(FirstPadding(i - 1)),
                     )
                 } else {
                     AB::Expr::ZERO
@@ -354,11 +489,11 @@ impl Sha256VmAir {
             // This is true because the message is given as &[u8]
             let should_be_128 = self
                 .padding_encoder
-                .contains_flag::<AB>(&local.control.pad_flags, &[FirstPadding0 as usize + i])
-                + if i < 8 {
+                .contains_flag::<AB>(&local.control.pad_flags, &[Self::flag_idx(FirstPadding(i))])
+                + if i < READ_SIZE / 2 {
                     self.padding_encoder.contains_flag::<AB>(
                         &local.control.pad_flags,
-                        &[FirstPadding0_LastRow as usize + i],
+                        &[Self::flag_idx(FirstPaddingLastRow(i))],
                     )
                 } else {
                     AB::Expr::ZERO
@@ -372,10 +507,10 @@ impl Sha256VmAir {
         }
         let appended_len = compose::<AB::Expr>(
             &[
-                get_ith_byte(15),
-                get_ith_byte(14),
-                get_ith_byte(13),
-                get_ith_byte(12),
+                get_ith_byte(READ_SIZE - 1),
+                get_ith_byte(READ_SIZE - 2),
+                get_ith_byte(READ_SIZE - 3),
+                get_ith_byte(READ_SIZE - 4),
             ],
             RV32_CELL_BITS,
         );
@@ -384,7 +519,7 @@ impl Sha256VmAir {
 
         let is_last_padding_row = self.padding_encoder.contains_flag_range::<AB>(
             &local.control.pad_flags,
-            FirstPadding0_LastRow as usize..=EntirePaddingLastRow as usize,
+            Self::flag_idx(FirstPaddingLastRow(0))..=Self::flag_idx(EntirePaddingLastRow),
         );
 
         builder.when(is_last_padding_row.clone()).assert_eq(
@@ -400,9 +535,9 @@ impl Sha256VmAir {
         );
 
         // We can't support messages longer than 2^30 bytes because the length has to fit in a field
-        // element. So, constrain that the first 4 bytes of the length are 0.
+        // element. So, constrain that all but the last 4 bytes of the length are 0.
         // Thus, the bit-length is < 2^32 so the message is < 2^29 bytes.
-        for i in 8..12 {
+        for i in READ_SIZE / 2..READ_SIZE - 4 {
             builder
                 .when(is_last_padding_row.clone())
                 .assert_zero(get_ith_byte(i));
@@ -412,8 +547,22 @@ impl Sha256VmAir {
     fn eval_transitions<AB: InteractionBuilder>(&self, builder: &mut AB) {
         let main = builder.main();
         let (local, next) = (main.row_slice(0), main.row_slice(1));
-        let local_cols: &Sha256VmRoundCols<AB::Var> = local[..SHA256VM_ROUND_WIDTH].borrow();
-        let next_cols: &Sha256VmRoundCols<AB::Var> = next[..SHA256VM_ROUND_WIDTH].borrow();
+        let local_cols: &Sha2VmRoundCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+        > = local[..Self::ROUND_WIDTH].borrow();
+        let next_cols: &Sha2VmRoundCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+        > = next[..Self::ROUND_WIDTH].borrow();
 
         let is_last_row =
             local_cols.inner.flags.is_last_block * local_cols.inner.flags.is_digest_row;
@@ -424,10 +573,10 @@ impl Sha256VmAir {
             .when(not::<AB::Expr>(is_last_row.clone()))
             .assert_eq(next_cols.control.len, local_cols.control.len);
 
-        // Read ptr should increment by [SHA256_READ_SIZE] for the first 4 rows and stay the same
+        // Read ptr should increment by [READ_SIZE] for the first 4 rows and stay the same
         // otherwise
-        let read_ptr_delta = local_cols.inner.flags.is_first_4_rows
-            * AB::Expr::from_canonical_usize(SHA256_READ_SIZE);
+        let read_ptr_delta =
+            local_cols.inner.flags.is_first_4_rows * AB::Expr::from_canonical_usize(READ_SIZE);
         builder
             .when_transition()
             .when(not::<AB::Expr>(is_last_row.clone()))
@@ -451,11 +600,17 @@ impl Sha256VmAir {
     fn eval_reads<AB: InteractionBuilder>(&self, builder: &mut AB) {
         let main = builder.main();
         let local = main.row_slice(0);
-        let local_cols: &Sha256VmRoundCols<AB::Var> = local[..SHA256VM_ROUND_WIDTH].borrow();
-
-        let message: [AB::Var; SHA256_READ_SIZE] = array::from_fn(|i| {
-            local_cols.inner.message_schedule.carry_or_buffer[i / (SHA256_WORD_U16S * 2)]
-                [i % (SHA256_WORD_U16S * 2)]
+        let local_cols: &Sha2VmRoundCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+        > = local[..Self::ROUND_WIDTH].borrow();
+
+        let message: [AB::Var; READ_SIZE] = array::from_fn(|i| {
+            local_cols.inner.message_schedule.carry_or_buffer[i / WORD_U8S][i % WORD_U8S]
         });
 
         self.memory_bridge
@@ -474,7 +629,15 @@ impl Sha256VmAir {
     fn eval_last_row<AB: InteractionBuilder>(&self, builder: &mut AB) {
         let main = builder.main();
         let local = main.row_slice(0);
-        let local_cols: &Sha256VmDigestCols<AB::Var> = local[..SHA256VM_DIGEST_WIDTH].borrow();
+        let local_cols: &Sha2VmDigestCols<
+            AB::Var,
+            WORD_BITS,
+            WORD_U8S,
+            WORD_U16S,
+            ROW_VAR_CNT,
+            PAD_VAR_CNT,
+            NUM_WRITES,
+        > = local[..Self::DIGEST_WIDTH].borrow();
 
         let timestamp: AB::Var = local_cols.from_state.timestamp;
         let mut timestamp_delta: usize = 0;
@@ -541,14 +704,8 @@ impl Sha256VmAir {
         // the number of reads that happened to read the entire message: we do 4 reads per block
         let time_delta = (local_cols.inner.flags.local_block_idx + AB::Expr::ONE)
             * AB::Expr::from_canonical_usize(4);
-        // Every time we read the message we increment the read pointer by SHA256_READ_SIZE
-        let read_ptr_delta = time_delta.clone() * AB::Expr::from_canonical_usize(SHA256_READ_SIZE);
-
-        let result: [AB::Var; SHA256_WORD_U8S * SHA256_HASH_WORDS] = array::from_fn(|i| {
-            // The limbs are written in big endian order to the memory so need to be reversed
-            local_cols.inner.final_hash[i / SHA256_WORD_U8S]
-                [SHA256_WORD_U8S - i % SHA256_WORD_U8S - 1]
-        });
+        // Every time we read the message we increment the read pointer by READ_SIZE
+        let read_ptr_delta = time_delta.clone() * AB::Expr::from_canonical_usize(READ_SIZE);
 
         let dst_ptr_val =
             compose::<AB::Expr>(&local_cols.dst_ptr.map(|x| x.into()), RV32_CELL_BITS);
@@ -556,18 +713,29 @@ impl Sha256VmAir {
         // Note: revisit in the future to do 2 block writes of 16 cells instead of 1 block write of
         // 32 cells       This could be beneficial as the output is often an input for
         // another hash
-        self.memory_bridge
-            .write(
-                MemoryAddress::new(AB::Expr::from_canonical_u32(RV32_MEMORY_AS), dst_ptr_val),
-                result,
-                timestamp_pp() + time_delta.clone(),
-                &local_cols.writes_aux,
-            )
-            .eval(builder, is_last_row.clone());
+        for (write_idx, writes_aux) in local_cols.writes_aux.iter().enumerate() {
+            let result: [AB::Var; SHA2_WRITE_SIZE] = array::from_fn(|i| {
+                let i = write_idx * SHA2_WRITE_SIZE + i;
+                // The limbs are written in big endian order to the memory so need to be reversed
+                local_cols.inner.final_hash[i / WORD_U8S][WORD_U8S - i % WORD_U8S - 1]
+            });
+            self.memory_bridge
+                .write(
+                    MemoryAddress::new(
+                        AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                        dst_ptr_val.clone()
+                            + AB::Expr::from_canonical_usize(write_idx * SHA2_WRITE_SIZE),
+                    ),
+                    result,
+                    timestamp_pp() + time_delta.clone(),
+                    writes_aux,
+                )
+                .eval(builder, is_last_row.clone());
+        }
 
         self.execution_bridge
             .execute_and_increment_pc(
-                AB::Expr::from_canonical_usize(Rv32Sha256Opcode::SHA256.global_opcode().as_usize()),
+                AB::Expr::from_canonical_usize(C::OPCODE.global_opcode().as_usize()),
                 [
                     local_cols.rd_ptr.into(),
                     local_cols.rs1_ptr.into(),
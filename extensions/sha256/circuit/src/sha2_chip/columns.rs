@@ -0,0 +1,143 @@
+//! WARNING: the order of fields in the structs is important, do not change it
+
+use openvm_circuit::{
+    arch::ExecutionState,
+    system::memory::offline_checker::{MemoryReadAuxCols, MemoryWriteAuxCols},
+};
+use openvm_circuit_primitives::AlignedBorrow;
+use openvm_instructions::riscv::RV32_REGISTER_NUM_LIMBS;
+use openvm_sha256_air::{
+    Sha2DigestCols, Sha2RoundCols, SHA256_ROW_VAR_CNT, SHA256_WORD_BITS, SHA256_WORD_U16S,
+    SHA256_WORD_U8S, SHA512_ROW_VAR_CNT, SHA512_WORD_BITS, SHA512_WORD_U16S, SHA512_WORD_U8S,
+};
+
+use super::{
+    SHA256_NUM_WRITES, SHA256_PAD_VAR_CNT, SHA2_REGISTER_READS, SHA2_WRITE_SIZE, SHA512_NUM_WRITES,
+    SHA512_PAD_VAR_CNT,
+};
+
+/// the first [openvm_sha256_air::Sha2Config::ROUND_ROWS] rows of every SHA-2 block will be of type
+/// Sha2VmRoundCols and the last row will be of type Sha2VmDigestCols
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha2VmRoundCols<
+    T,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+    const PAD_VAR_CNT: usize,
+> {
+    pub control: Sha2VmControlCols<T, PAD_VAR_CNT>,
+    pub inner: Sha2RoundCols<T, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+    pub read_aux: MemoryReadAuxCols<T>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha2VmDigestCols<
+    T,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+    const PAD_VAR_CNT: usize,
+    const NUM_WRITES: usize,
+> {
+    pub control: Sha2VmControlCols<T, PAD_VAR_CNT>,
+    pub inner: Sha2DigestCols<T, WORD_BITS, WORD_U8S, WORD_U16S, ROW_VAR_CNT>,
+
+    pub from_state: ExecutionState<T>,
+    /// It is counter intuitive, but we will constrain the register reads on the very last row of
+    /// every message
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub dst_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub src_ptr: [T; RV32_REGISTER_NUM_LIMBS],
+    pub len_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub register_reads_aux: [MemoryReadAuxCols<T>; SHA2_REGISTER_READS],
+    /// The final hash is written in `NUM_WRITES` consecutive chunks of [SHA2_WRITE_SIZE] cells
+    pub writes_aux: [MemoryWriteAuxCols<T, SHA2_WRITE_SIZE>; NUM_WRITES],
+}
+
+/// These are the columns that are used on both round and digest rows
+#[repr(C)]
+#[derive(Clone, Copy, Debug, AlignedBorrow)]
+pub struct Sha2VmControlCols<T, const PAD_VAR_CNT: usize> {
+    /// Note: We will use the buffer in `inner.message_schedule` as the message data
+    /// This is the length of the entire message in bytes
+    pub len: T,
+    /// Need to keep timestamp and read_ptr since block reads don't have the necessary information
+    pub cur_timestamp: T,
+    pub read_ptr: T,
+    /// Padding flags which will be used to encode the the number of non-padding cells in the
+    /// current row
+    pub pad_flags: [T; PAD_VAR_CNT],
+    /// A boolean flag that indicates whether a padding already occurred
+    pub padding_occurred: T,
+}
+
+pub type Sha256VmRoundCols<T> = Sha2VmRoundCols<
+    T,
+    SHA256_WORD_BITS,
+    SHA256_WORD_U8S,
+    SHA256_WORD_U16S,
+    SHA256_ROW_VAR_CNT,
+    SHA256_PAD_VAR_CNT,
+>;
+pub type Sha256VmDigestCols<T> = Sha2VmDigestCols<
+    T,
+    SHA256_WORD_BITS,
+    SHA256_WORD_U8S,
+    SHA256_WORD_U16S,
+    SHA256_ROW_VAR_CNT,
+    SHA256_PAD_VAR_CNT,
+    SHA256_NUM_WRITES,
+>;
+pub type Sha256VmControlCols<T> = Sha2VmControlCols<T, SHA256_PAD_VAR_CNT>;
+
+pub type Sha512VmRoundCols<T> = Sha2VmRoundCols<
+    T,
+    SHA512_WORD_BITS,
+    SHA512_WORD_U8S,
+    SHA512_WORD_U16S,
+    SHA512_ROW_VAR_CNT,
+    SHA512_PAD_VAR_CNT,
+>;
+pub type Sha512VmDigestCols<T> = Sha2VmDigestCols<
+    T,
+    SHA512_WORD_BITS,
+    SHA512_WORD_U8S,
+    SHA512_WORD_U16S,
+    SHA512_ROW_VAR_CNT,
+    SHA512_PAD_VAR_CNT,
+    SHA512_NUM_WRITES,
+>;
+pub type Sha512VmControlCols<T> = Sha2VmControlCols<T, SHA512_PAD_VAR_CNT>;
+
+/// Width of the Sha256VmControlCols
+pub const SHA256VM_CONTROL_WIDTH: usize = Sha256VmControlCols::<u8>::width();
+/// Width of the Sha256VmRoundCols
+pub const SHA256VM_ROUND_WIDTH: usize = Sha256VmRoundCols::<u8>::width();
+/// Width of the Sha256VmDigestCols
+pub const SHA256VM_DIGEST_WIDTH: usize = Sha256VmDigestCols::<u8>::width();
+/// Width of the Sha256 VM trace
+pub const SHA256VM_WIDTH: usize = if SHA256VM_ROUND_WIDTH > SHA256VM_DIGEST_WIDTH {
+    SHA256VM_ROUND_WIDTH
+} else {
+    SHA256VM_DIGEST_WIDTH
+};
+
+/// Width of the Sha512VmControlCols
+pub const SHA512VM_CONTROL_WIDTH: usize = Sha512VmControlCols::<u8>::width();
+/// Width of the Sha512VmRoundCols
+pub const SHA512VM_ROUND_WIDTH: usize = Sha512VmRoundCols::<u8>::width();
+/// Width of the Sha512VmDigestCols
+pub const SHA512VM_DIGEST_WIDTH: usize = Sha512VmDigestCols::<u8>::width();
+/// Width of the Sha512 (and Sha384) VM trace
+pub const SHA512VM_WIDTH: usize = if SHA512VM_ROUND_WIDTH > SHA512VM_DIGEST_WIDTH {
+    SHA512VM_ROUND_WIDTH
+} else {
+    SHA512VM_DIGEST_WIDTH
+};
@@ -0,0 +1,378 @@
+//! SHA-2 hasher. Handles full SHA-2 hashing (SHA256, SHA512 and SHA384) with padding.
+//! variable length inputs read from VM memory.
+use std::{
+    array,
+    cmp::{max, min},
+    sync::{Arc, Mutex},
+};
+
+use openvm_circuit::arch::{
+    ExecutionBridge, ExecutionError, ExecutionState, InstructionExecutor, SystemPort,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::SharedBitwiseOperationLookupChip, encoder::Encoder,
+};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::DEFAULT_PC_STEP,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+    LocalOpcode,
+};
+use openvm_rv32im_circuit::adapters::read_rv32_register;
+use openvm_sha256_air::{
+    Sha256Config, Sha2Air, Sha2Config, Sha384Config, Sha512Config, SHA256_ROW_VAR_CNT,
+    SHA256_WORD_BITS, SHA256_WORD_U16S, SHA256_WORD_U8S, SHA2_MESSAGE_ROWS, SHA512_ROW_VAR_CNT,
+    SHA512_WORD_BITS, SHA512_WORD_U16S, SHA512_WORD_U8S,
+};
+use openvm_sha256_transpiler::{Rv32Sha256Opcode, Rv32Sha512Opcode};
+use openvm_stark_backend::{interaction::BusIndex, p3_field::PrimeField32};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+mod air;
+mod columns;
+mod trace;
+
+pub use air::*;
+pub use columns::*;
+use openvm_circuit::system::memory::{MemoryController, OfflineMemory, RecordId};
+
+#[cfg(test)]
+mod tests;
+
+// ==== Constants for register/memory adapter ====
+/// Register reads to get dst, src, len
+const SHA2_REGISTER_READS: usize = 3;
+/// Number of cells to write in a single memory access
+const SHA2_WRITE_SIZE: usize = 32;
+/// Number of rows we will do a read on for each SHA-2 block
+pub const SHA2_NUM_READ_ROWS: usize = SHA2_MESSAGE_ROWS;
+
+/// Number of cells to read in a single memory access for SHA256
+pub const SHA256_READ_SIZE: usize = 4 * SHA256_WORD_U8S;
+/// Number of cells used to encode the padding flags for SHA256
+pub const SHA256_PAD_VAR_CNT: usize = 6;
+/// Number of [SHA2_WRITE_SIZE] writes of the final hash for SHA256
+pub const SHA256_NUM_WRITES: usize = 1;
+/// Number of cells to read in a single memory access for SHA512 and SHA384
+pub const SHA512_READ_SIZE: usize = 4 * SHA512_WORD_U8S;
+/// Number of cells used to encode the padding flags for SHA512 and SHA384
+pub const SHA512_PAD_VAR_CNT: usize = 9;
+/// Number of [SHA2_WRITE_SIZE] writes of the final hash for SHA512 and SHA384
+pub const SHA512_NUM_WRITES: usize = 2;
+
+/// A SHA-2 variant that is executed by a [Sha2VmChip]
+pub trait Sha2ChipConfig: Sha2Config {
+    /// The opcode class that the opcode belongs to
+    type Opcode: LocalOpcode;
+    /// The opcode that the chip handles
+    const OPCODE: Self::Opcode;
+    /// The name of the opcode
+    const OPCODE_NAME: &'static str;
+}
+
+impl Sha2ChipConfig for Sha256Config {
+    type Opcode = Rv32Sha256Opcode;
+    const OPCODE: Rv32Sha256Opcode = Rv32Sha256Opcode::SHA256;
+    const OPCODE_NAME: &'static str = "SHA256";
+}
+
+impl Sha2ChipConfig for Sha512Config {
+    type Opcode = Rv32Sha512Opcode;
+    const OPCODE: Rv32Sha512Opcode = Rv32Sha512Opcode::SHA512;
+    const OPCODE_NAME: &'static str = "SHA512";
+}
+
+impl Sha2ChipConfig for Sha384Config {
+    type Opcode = Rv32Sha512Opcode;
+    const OPCODE: Rv32Sha512Opcode = Rv32Sha512Opcode::SHA384;
+    const OPCODE_NAME: &'static str = "SHA384";
+}
+
+/// The chip for a SHA-2 hash function, with the parameters given by `C`.
+/// `READ_SIZE` is the number of cells read per row (four words), `PAD_VAR_CNT` is the number of
+/// cells encoding the [PaddingFlags] and `NUM_WRITES` is the number of [SHA2_WRITE_SIZE] writes
+/// needed to write the final hash state. See [Sha2Air] for the other const generics.
+pub struct Sha2VmChip<
+    F: PrimeField32,
+    C: Sha2ChipConfig,
+    const WORD_BITS: usize,
+    const WORD_U8S: usize,
+    const WORD_U16S: usize,
+    const ROW_VAR_CNT: usize,
+    const READ_SIZE: usize,
+    const PAD_VAR_CNT: usize,
+    const NUM_WRITES: usize,
+> {
+    pub air: Sha2VmAir<
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >,
+    /// IO and memory data necessary for each opcode call
+    pub records: Vec<Sha2Record<F>>,
+    pub offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    pub bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+
+    offset: usize,
+}
+
+pub type Sha256VmChip<F> = Sha2VmChip<
+    F,
+    Sha256Config,
+    SHA256_WORD_BITS,
+    SHA256_WORD_U8S,
+    SHA256_WORD_U16S,
+    SHA256_ROW_VAR_CNT,
+    SHA256_READ_SIZE,
+    SHA256_PAD_VAR_CNT,
+    SHA256_NUM_WRITES,
+>;
+pub type Sha512VmChip<F> = Sha2VmChip<
+    F,
+    Sha512Config,
+    SHA512_WORD_BITS,
+    SHA512_WORD_U8S,
+    SHA512_WORD_U16S,
+    SHA512_ROW_VAR_CNT,
+    SHA512_READ_SIZE,
+    SHA512_PAD_VAR_CNT,
+    SHA512_NUM_WRITES,
+>;
+pub type Sha384VmChip<F> = Sha2VmChip<
+    F,
+    Sha384Config,
+    SHA512_WORD_BITS,
+    SHA512_WORD_U8S,
+    SHA512_WORD_U16S,
+    SHA512_ROW_VAR_CNT,
+    SHA512_READ_SIZE,
+    SHA512_PAD_VAR_CNT,
+    SHA512_NUM_WRITES,
+>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Sha2Record<F> {
+    pub from_state: ExecutionState<F>,
+    pub dst_read: RecordId,
+    pub src_read: RecordId,
+    pub len_read: RecordId,
+    pub input_records: Vec<[RecordId; SHA2_NUM_READ_ROWS]>,
+    /// The bytes read for each block, [Sha2Config::BLOCK_U8S] bytes per block
+    pub input_message: Vec<Vec<u8>>,
+    pub digest_writes: Vec<RecordId>,
+}
+
+impl<
+        F: PrimeField32,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    >
+    Sha2VmChip<
+        F,
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
+    pub fn new(
+        SystemPort {
+            execution_bus,
+            program_bus,
+            memory_bridge,
+        }: SystemPort,
+        address_bits: usize,
+        bitwise_lookup_chip: SharedBitwiseOperationLookupChip<8>,
+        self_bus_idx: BusIndex,
+        offset: usize,
+        offline_memory: Arc<Mutex<OfflineMemory<F>>>,
+    ) -> Self {
+        assert_eq!(READ_SIZE, C::BLOCK_U8S / SHA2_NUM_READ_ROWS);
+        assert_eq!(NUM_WRITES * SHA2_WRITE_SIZE, 8 * C::WORD_U8S);
+        let padding_encoder = Encoder::new(PaddingFlags::count(READ_SIZE), 2, false);
+        assert_eq!(padding_encoder.width(), PAD_VAR_CNT);
+        Self {
+            air: Sha2VmAir::new(
+                ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                bitwise_lookup_chip.bus(),
+                address_bits,
+                Sha2Air::new(bitwise_lookup_chip.bus(), self_bus_idx),
+                padding_encoder,
+            ),
+            bitwise_lookup_chip,
+            records: Vec::new(),
+            offset,
+            offline_memory,
+        }
+    }
+}
+
+impl<
+        F: PrimeField32,
+        C: Sha2ChipConfig,
+        const WORD_BITS: usize,
+        const WORD_U8S: usize,
+        const WORD_U16S: usize,
+        const ROW_VAR_CNT: usize,
+        const READ_SIZE: usize,
+        const PAD_VAR_CNT: usize,
+        const NUM_WRITES: usize,
+    > InstructionExecutor<F>
+    for Sha2VmChip<
+        F,
+        C,
+        WORD_BITS,
+        WORD_U8S,
+        WORD_U16S,
+        ROW_VAR_CNT,
+        READ_SIZE,
+        PAD_VAR_CNT,
+        NUM_WRITES,
+    >
+{
+    fn execute(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+    ) -> Result<ExecutionState<u32>, ExecutionError> {
+        let &Instruction {
+            opcode,
+            a,
+            b,
+            c,
+            d,
+            e,
+            ..
+        } = instruction;
+        let local_opcode = opcode.local_opcode_idx(self.offset);
+        debug_assert_eq!(local_opcode, C::OPCODE.local_usize());
+        debug_assert_eq!(d, F::from_canonical_u32(RV32_REGISTER_AS));
+        debug_assert_eq!(e, F::from_canonical_u32(RV32_MEMORY_AS));
+
+        debug_assert_eq!(from_state.timestamp, memory.timestamp());
+
+        let (dst_read, dst) = read_rv32_register(memory, d, a);
+        let (src_read, src) = read_rv32_register(memory, d, b);
+        let (len_read, len) = read_rv32_register(memory, d, c);
+
+        #[cfg(debug_assertions)]
+        {
+            assert!(dst < (1 << self.air.ptr_max_bits));
+            assert!(src < (1 << self.air.ptr_max_bits));
+            assert!(len < (1 << self.air.ptr_max_bits));
+        }
+
+        // need to pad with one 1 bit, 2 * [Sha2Config::WORD_BITS] bits for the message length and
+        // then pad until the length is divisible by [Sha2Config::BLOCK_BITS]
+        let num_blocks = ((len << 3) as usize + 1 + 2 * C::WORD_BITS).div_ceil(C::BLOCK_BITS);
+
+        // we will read [num_blocks] * [Sha2Config::BLOCK_U8S] cells but only [len] cells will be
+        // used
+        debug_assert!(src as usize + num_blocks * C::BLOCK_U8S <= (1 << self.air.ptr_max_bits));
+        let mut message: Vec<u8> = Vec::with_capacity(len as usize);
+        let mut input_records = Vec::with_capacity(num_blocks);
+        let mut input_message = Vec::with_capacity(num_blocks);
+        let mut read_ptr = src;
+        for _ in 0..num_blocks {
+            let block_reads_records: [(RecordId, [F; READ_SIZE]); SHA2_NUM_READ_ROWS] =
+                array::from_fn(|i| {
+                    memory.read(e, F::from_canonical_u32(read_ptr + (i * READ_SIZE) as u32))
+                });
+            let mut block_reads_bytes = Vec::with_capacity(C::BLOCK_U8S);
+            for (_, row_input) in block_reads_records.iter() {
+                // we add to the message only the bytes that are part of the message
+                let num_reads = min(READ_SIZE, (max(read_ptr, src + len) - read_ptr) as usize);
+                let row_input: [u8; READ_SIZE] =
+                    row_input.map(|x| x.as_canonical_u32().try_into().unwrap());
+                message.extend_from_slice(&row_input[..num_reads]);
+                block_reads_bytes.extend_from_slice(&row_input);
+                read_ptr += READ_SIZE as u32;
+            }
+            input_records.push(block_reads_records.map(|x| x.0));
+            input_message.push(block_reads_bytes);
+        }
+
+        let digest = sha2_solve::<C>(&message);
+        let digest_writes = digest
+            .chunks_exact(SHA2_WRITE_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let chunk: [u8; SHA2_WRITE_SIZE] = chunk.try_into().unwrap();
+                let (digest_write, _) = memory.write(
+                    e,
+                    F::from_canonical_u32(dst + (i * SHA2_WRITE_SIZE) as u32),
+                    chunk.map(|b| F::from_canonical_u8(b)),
+                );
+                digest_write
+            })
+            .collect();
+
+        self.records.push(Sha2Record {
+            from_state: from_state.map(F::from_canonical_u32),
+            dst_read,
+            src_read,
+            len_read,
+            input_records,
+            input_message,
+            digest_writes,
+        });
+
+        Ok(ExecutionState {
+            pc: from_state.pc + DEFAULT_PC_STEP,
+            timestamp: memory.timestamp(),
+        })
+    }
+
+    fn get_opcode_name(&self, _: usize) -> String {
+        C::OPCODE_NAME.to_string()
+    }
+}
+
+pub fn sha256_solve(input_message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(input_message);
+    let mut output = [0u8; 32];
+    output.copy_from_slice(hasher.finalize().as_ref());
+    output
+}
+
+/// Pads `input_message` and returns the final hash state as big-endian bytes. This is what
+/// [Sha2VmChip] writes to memory: the full `8 * WORD_U8S` bytes of the state, of which the first
+/// [Sha2Config::DIGEST_BYTES] bytes are the digest.
+pub fn sha2_solve<C: Sha2Config>(input_message: &[u8]) -> Vec<u8> {
+    // the message length is appended as a big-endian integer of 2 words
+    let len_u8s = 2 * C::WORD_U8S;
+    let num_blocks = (input_message.len() + 1 + len_u8s).div_ceil(C::BLOCK_U8S);
+    let mut padded_message = input_message.to_vec();
+    padded_message.resize(num_blocks * C::BLOCK_U8S, 0);
+    padded_message[input_message.len()] = 1 << 7;
+    let bit_len = (input_message.len() as u128) << 3;
+    let len_start = padded_message.len() - len_u8s;
+    padded_message[len_start..].copy_from_slice(&bit_len.to_be_bytes()[16 - len_u8s..]);
+
+    let mut hash = C::H;
+    for block in padded_message.chunks_exact(C::BLOCK_U8S) {
+        C::compress(&mut hash, block);
+    }
+    hash.iter()
+        .flat_map(|word| word.to_be_bytes()[8 - C::WORD_U8S..].to_vec())
+        .collect()
+}
@@ -1,27 +1,32 @@
+use std::array;
+
 use openvm_circuit::arch::{
     testing::{memory::gen_pointer, VmChipTestBuilder, BITWISE_OP_LOOKUP_BUS},
-    SystemPort,
+    InstructionExecutor, SystemPort,
 };
 use openvm_circuit_primitives::bitwise_op_lookup::{
     BitwiseOperationLookupBus, SharedBitwiseOperationLookupChip,
 };
 use openvm_instructions::{instruction::Instruction, riscv::RV32_CELL_BITS, LocalOpcode};
-use openvm_sha256_air::get_random_message;
-use openvm_sha256_transpiler::Rv32Sha256Opcode::{self, *};
+use openvm_sha256_air::{get_random_message, Sha256Config, Sha384Config, Sha512Config};
+use openvm_sha256_transpiler::{Rv32Sha256Opcode, Rv32Sha512Opcode};
 use openvm_stark_backend::{interaction::BusIndex, p3_field::FieldAlgebra};
 use openvm_stark_sdk::{config::setup_tracing, p3_baby_bear::BabyBear, utils::create_seeded_rng};
 use rand::{rngs::StdRng, Rng};
+use sha2::{Digest, Sha384, Sha512};
 
-use super::Sha256VmChip;
-use crate::{sha256_solve, Sha256VmDigestCols, Sha256VmRoundCols};
+use super::{Sha256VmChip, Sha2ChipConfig, SHA2_WRITE_SIZE};
+use crate::{
+    sha256_solve, sha2_solve, Sha256VmDigestCols, Sha256VmRoundCols, Sha384VmChip, Sha512VmChip,
+    Sha512VmDigestCols, Sha512VmRoundCols,
+};
 
 type F = BabyBear;
 const BUS_IDX: BusIndex = 28;
-fn set_and_execute(
+fn set_and_execute<C: Sha2ChipConfig, E: InstructionExecutor<F>>(
     tester: &mut VmChipTestBuilder<F>,
-    chip: &mut Sha256VmChip<F>,
+    chip: &mut E,
     rng: &mut StdRng,
-    opcode: Rv32Sha256Opcode,
     message: Option<&[u8]>,
     len: Option<usize>,
 ) {
@@ -40,7 +45,8 @@ fn set_and_execute(
             .borrow()
             .mem_config()
             .pointer_max_bits;
-    let dst_ptr = rng.gen_range(0..max_mem_ptr);
+    // leave room for the full hash state of SHA512
+    let dst_ptr = rng.gen_range(0..(max_mem_ptr - 64));
     let dst_ptr = dst_ptr ^ (dst_ptr & 3);
     tester.write(1, rd, dst_ptr.to_le_bytes().map(F::from_canonical_u8));
     let src_ptr = rng.gen_range(0..(max_mem_ptr - len as u32));
@@ -54,14 +60,17 @@ fn set_and_execute(
 
     tester.execute(
         chip,
-        &Instruction::from_usize(opcode.global_opcode(), [rd, rs1, rs2, 1, 2]),
+        &Instruction::from_usize(C::OPCODE.global_opcode(), [rd, rs1, rs2, 1, 2]),
     );
 
-    let output = sha256_solve(message);
-    assert_eq!(
-        output.map(F::from_canonical_u8),
-        tester.read::<32>(2, dst_ptr as usize)
-    );
+    let output = sha2_solve::<C>(message);
+    for (i, chunk) in output.chunks_exact(SHA2_WRITE_SIZE).enumerate() {
+        let expected: [F; SHA2_WRITE_SIZE] = array::from_fn(|j| F::from_canonical_u8(chunk[j]));
+        assert_eq!(
+            expected,
+            tester.read::<SHA2_WRITE_SIZE>(2, dst_ptr as usize + i * SHA2_WRITE_SIZE)
+        );
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////
@@ -92,7 +101,65 @@ fn rand_sha256_test() {
 
     let num_tests: usize = 3;
     for _ in 0..num_tests {
-        set_and_execute(&mut tester, &mut chip, &mut rng, SHA256, None, None);
+        set_and_execute::<Sha256Config, _>(&mut tester, &mut chip, &mut rng, None, None);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_sha512_test() {
+    setup_tracing();
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let mut chip = Sha512VmChip::new(
+        SystemPort {
+            execution_bus: tester.execution_bus(),
+            program_bus: tester.program_bus(),
+            memory_bridge: tester.memory_bridge(),
+        },
+        tester.address_bits(),
+        bitwise_chip.clone(),
+        BUS_IDX,
+        Rv32Sha512Opcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let num_tests: usize = 3;
+    for _ in 0..num_tests {
+        set_and_execute::<Sha512Config, _>(&mut tester, &mut chip, &mut rng, None, None);
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rand_sha384_test() {
+    setup_tracing();
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = SharedBitwiseOperationLookupChip::<RV32_CELL_BITS>::new(bitwise_bus);
+    let mut chip = Sha384VmChip::new(
+        SystemPort {
+            execution_bus: tester.execution_bus(),
+            program_bus: tester.program_bus(),
+            memory_bridge: tester.memory_bridge(),
+        },
+        tester.address_bits(),
+        bitwise_chip.clone(),
+        BUS_IDX,
+        Rv32Sha512Opcode::CLASS_OFFSET,
+        tester.offline_memory_mutex_arc(),
+    );
+
+    let num_tests: usize = 3;
+    for _ in 0..num_tests {
+        set_and_execute::<Sha384Config, _>(&mut tester, &mut chip, &mut rng, None, None);
     }
 
     let tester = tester.build().load(chip).load(bitwise_chip).finalize();
@@ -131,9 +198,17 @@ fn execute_roundtrip_sanity_test() {
         "Sha256VmRoundCols::width(): {}",
         Sha256VmRoundCols::<F>::width()
     );
+    println!(
+        "Sha512VmDigestCols::width(): {}",
+        Sha512VmDigestCols::<F>::width()
+    );
+    println!(
+        "Sha512VmRoundCols::width(): {}",
+        Sha512VmRoundCols::<F>::width()
+    );
     let num_tests: usize = 1;
     for _ in 0..num_tests {
-        set_and_execute(&mut tester, &mut chip, &mut rng, SHA256, None, None);
+        set_and_execute::<Sha256Config, _>(&mut tester, &mut chip, &mut rng, None, None);
     }
 }
 
@@ -147,3 +222,17 @@ fn sha256_solve_sanity_check() {
     ];
     assert_eq!(output, expected);
 }
+
+#[test]
+fn sha2_solve_sanity_check() {
+    let input = b"Axiom is the best! Axiom is the best! Axiom is the best! Axiom is the best!";
+    assert_eq!(sha2_solve::<Sha256Config>(input), sha256_solve(input));
+    assert_eq!(
+        sha2_solve::<Sha512Config>(input),
+        Sha512::digest(input).as_slice()
+    );
+    assert_eq!(
+        sha2_solve::<Sha384Config>(input)[..48],
+        *Sha384::digest(input).as_slice()
+    );
+}
@@ -4,6 +4,9 @@
 pub const OPCODE: u8 = 0x0b;
 pub const SHA256_FUNCT3: u8 = 0b100;
 pub const SHA256_FUNCT7: u8 = 0x1;
+pub const SHA512_FUNCT3: u8 = 0b100;
+pub const SHA512_FUNCT7: u8 = 0x2;
+pub const SHA384_FUNCT7: u8 = 0x3;
 
 /// zkvm native implementation of sha256
 /// # Safety
@@ -20,3 +23,35 @@ pub const SHA256_FUNCT7: u8 = 0x1;
 pub extern "C" fn zkvm_sha256_impl(bytes: *const u8, len: usize, output: *mut u8) {
     openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA256_FUNCT3, funct7 = SHA256_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
 }
+
+/// zkvm native implementation of sha512
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// 64-byte hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 64-bytes long.
+///
+/// [`sha2-512`]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn zkvm_sha512_impl(bytes: *const u8, len: usize, output: *mut u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA512_FUNCT3, funct7 = SHA512_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
+}
+
+/// zkvm native implementation of sha384
+/// # Safety
+///
+/// The VM accepts the preimage by pointer and length, and writes the
+/// full 64-byte SHA-512 state, of which the first 48 bytes are the hash.
+/// - `bytes` must point to an input buffer at least `len` long.
+/// - `output` must point to a buffer that is at least 64-bytes long.
+///
+/// [`sha2-384`]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+#[cfg(target_os = "zkvm")]
+#[inline(always)]
+#[no_mangle]
+pub extern "C" fn zkvm_sha384_impl(bytes: *const u8, len: usize, output: *mut u8) {
+    openvm_platform::custom_insn_r!(opcode = OPCODE, funct3 = SHA512_FUNCT3, funct7 = SHA384_FUNCT7, rd = In output, rs1 = In bytes, rs2 = In len);
+}
@@ -1,6 +1,8 @@
 use openvm_instructions::{riscv::RV32_MEMORY_AS, LocalOpcode};
 use openvm_instructions_derive::LocalOpcode;
-use openvm_sha256_guest::{OPCODE, SHA256_FUNCT3, SHA256_FUNCT7};
+use openvm_sha256_guest::{
+    OPCODE, SHA256_FUNCT3, SHA256_FUNCT7, SHA384_FUNCT7, SHA512_FUNCT3, SHA512_FUNCT7,
+};
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{util::from_r_type, TranspilerExtension, TranspilerOutput};
 use rrs_lib::instruction_formats::RType;
@@ -15,6 +17,16 @@ pub enum Rv32Sha256Opcode {
     SHA256,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, LocalOpcode,
+)]
+#[opcode_offset = 0x330]
+#[repr(usize)]
+pub enum Rv32Sha512Opcode {
+    SHA512,
+    SHA384,
+}
+
 #[derive(Default)]
 pub struct Sha256TranspilerExtension;
 
@@ -44,3 +56,35 @@ impl<F: PrimeField32> TranspilerExtension<F> for Sha256TranspilerExtension {
         Some(TranspilerOutput::one_to_one(instruction))
     }
 }
+
+#[derive(Default)]
+pub struct Sha512TranspilerExtension;
+
+impl<F: PrimeField32> TranspilerExtension<F> for Sha512TranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<TranspilerOutput<F>> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let funct3 = ((instruction_u32 >> 12) & 0b111) as u8;
+
+        if (opcode, funct3) != (OPCODE, SHA512_FUNCT3) {
+            return None;
+        }
+        let dec_insn = RType::new(instruction_u32);
+
+        let local_opcode = match dec_insn.funct7 as u8 {
+            SHA512_FUNCT7 => Rv32Sha512Opcode::SHA512,
+            SHA384_FUNCT7 => Rv32Sha512Opcode::SHA384,
+            _ => return None,
+        };
+        let instruction = from_r_type(
+            local_opcode.global_opcode().as_usize(),
+            RV32_MEMORY_AS as usize,
+            &dec_insn,
+            true,
+        );
+        Some(TranspilerOutput::one_to_one(instruction))
+    }
+}
@@ -0,0 +1,272 @@
+#![no_std]
+
+/// Length in bytes of a message block.
+pub const BLAKE_BLOCK_LEN: usize = 64;
+/// Length in bytes of a BLAKE2s-256 digest.
+pub const BLAKE2S_256_OUT_LEN: usize = 32;
+/// Length in bytes of a BLAKE3 digest.
+pub const BLAKE3_OUT_LEN: usize = 32;
+/// Length in bytes of a BLAKE3 chunk, the leaves of the BLAKE3 tree.
+pub const BLAKE3_CHUNK_LEN: usize = 1024;
+
+/// BLAKE3 domain flag of the first block of a chunk.
+pub const BLAKE3_CHUNK_START: u32 = 1 << 0;
+/// BLAKE3 domain flag of the last block of a chunk.
+pub const BLAKE3_CHUNK_END: u32 = 1 << 1;
+/// BLAKE3 domain flag of a parent node.
+pub const BLAKE3_PARENT: u32 = 1 << 2;
+/// BLAKE3 domain flag of the root node.
+pub const BLAKE3_ROOT: u32 = 1 << 3;
+
+/// The initialization vector shared by BLAKE2s and BLAKE3.
+pub const BLAKE_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Maximum depth of the BLAKE3 tree for inputs of less than `2^64` bytes.
+const BLAKE3_MAX_DEPTH: usize = 54;
+
+/// The BLAKE2s compression function, updating the chaining value `h` in place.
+///
+/// `t` is the number of input bytes hashed so far, including this block, and `last` is set on
+/// the final block. See [RFC 7693](https://www.rfc-editor.org/rfc/rfc7693).
+#[inline(always)]
+pub fn blake2s_compress(h: &mut [u32; 8], block: &[u8; BLAKE_BLOCK_LEN], t: u64, last: bool) {
+    let params = [
+        BLAKE_IV[4] ^ t as u32,
+        BLAKE_IV[5] ^ (t >> 32) as u32,
+        BLAKE_IV[6] ^ if last { u32::MAX } else { 0 },
+        BLAKE_IV[7],
+    ];
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let v = host::compress(h, block, &params, &host::BLAKE2S_SIGMA);
+        for (i, word) in h.iter_mut().enumerate() {
+            *word ^= v[i] ^ v[i + 8];
+        }
+    }
+    // The zkvm is little-endian, so the in-memory layout of `h` and `params` is the byte layout
+    // the intrinsic expects.
+    #[cfg(target_os = "zkvm")]
+    openvm_blake_guest::native_blake2s_compress(
+        h.as_mut_ptr() as *mut u8,
+        block.as_ptr(),
+        params.as_ptr() as *const u8,
+    );
+}
+
+/// The BLAKE3 compression function, returning the full 16-word output. The first 8 words are
+/// the new chaining value.
+///
+/// See the [BLAKE3 specification](https://github.com/BLAKE3-team/BLAKE3-specs/blob/master/blake3.pdf).
+#[inline(always)]
+pub fn blake3_compress(
+    cv: &[u32; 8],
+    block: &[u8; BLAKE_BLOCK_LEN],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let params = [counter as u32, (counter >> 32) as u32, block_len, flags];
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        let v = host::compress(cv, block, &params, &host::BLAKE3_SCHEDULE);
+        core::array::from_fn(|i| {
+            if i < 8 {
+                v[i] ^ v[i + 8]
+            } else {
+                v[i] ^ cv[i - 8]
+            }
+        })
+    }
+    // The output is written over the chaining value, so it needs a 64-byte buffer
+    #[cfg(target_os = "zkvm")]
+    {
+        let mut output = [0u32; 16];
+        output[..8].copy_from_slice(cv);
+        openvm_blake_guest::native_blake3_compress(
+            output.as_mut_ptr() as *mut u8,
+            block.as_ptr(),
+            params.as_ptr() as *const u8,
+        );
+        output
+    }
+}
+
+/// Computes the unkeyed BLAKE2s-256 hash of `input`.
+pub fn blake2s256(input: &[u8]) -> [u8; BLAKE2S_256_OUT_LEN] {
+    let mut output = [0u8; BLAKE2S_256_OUT_LEN];
+    set_blake2s256(input, &mut output);
+    output
+}
+
+/// Sets `output` to the unkeyed BLAKE2s-256 hash of `input`.
+pub fn set_blake2s256(input: &[u8], output: &mut [u8; BLAKE2S_256_OUT_LEN]) {
+    let mut h = BLAKE_IV;
+    // parameter block: digest length 32, no key, fanout 1, depth 1
+    h[0] ^= 0x01010000 ^ BLAKE2S_256_OUT_LEN as u32;
+
+    // The empty input is hashed as a single zero block
+    let num_blocks = input.len().div_ceil(BLAKE_BLOCK_LEN).max(1);
+    for i in 0..num_blocks {
+        let block = &input[i * BLAKE_BLOCK_LEN..input.len().min((i + 1) * BLAKE_BLOCK_LEN)];
+        let t = (i * BLAKE_BLOCK_LEN + block.len()) as u64;
+        blake2s_compress(&mut h, &pad_block(block), t, i == num_blocks - 1);
+    }
+    *output = words_to_bytes(&h);
+}
+
+/// Computes the BLAKE3 hash of `input`, with the default 32-byte output.
+pub fn blake3(input: &[u8]) -> [u8; BLAKE3_OUT_LEN] {
+    let mut output = [0u8; BLAKE3_OUT_LEN];
+    set_blake3(input, &mut output);
+    output
+}
+
+/// Sets `output` to the BLAKE3 hash of `input`, with the default 32-byte output.
+pub fn set_blake3(input: &[u8], output: &mut [u8; BLAKE3_OUT_LEN]) {
+    // The chaining values of the complete subtrees to the left of the current chunk, in
+    // decreasing order of size
+    let mut stack = [[0u32; 8]; BLAKE3_MAX_DEPTH];
+    let mut stack_len = 0;
+
+    let num_chunks = input.len().div_ceil(BLAKE3_CHUNK_LEN).max(1);
+    for i in 0..num_chunks {
+        let chunk = &input[i * BLAKE3_CHUNK_LEN..input.len().min((i + 1) * BLAKE3_CHUNK_LEN)];
+        let mut cv = blake3_chunk_cv(chunk, i as u64, num_chunks == 1);
+        if i < num_chunks - 1 {
+            // Merge the subtrees that are complete once this chunk is added
+            let mut total_chunks = i + 1;
+            while total_chunks % 2 == 0 {
+                stack_len -= 1;
+                cv = blake3_parent_cv(&stack[stack_len], &cv, false);
+                total_chunks /= 2;
+            }
+            stack[stack_len] = cv;
+            stack_len += 1;
+        } else {
+            // Merge all remaining subtrees, the last merge being the root
+            while stack_len > 0 {
+                stack_len -= 1;
+                cv = blake3_parent_cv(&stack[stack_len], &cv, stack_len == 0);
+            }
+            *output = words_to_bytes(&cv);
+        }
+    }
+}
+
+/// The chaining value of the chunk with index `counter`.
+fn blake3_chunk_cv(chunk: &[u8], counter: u64, root: bool) -> [u32; 8] {
+    let mut cv = BLAKE_IV;
+    // The empty input is hashed as a single zero block of length 0
+    let num_blocks = chunk.len().div_ceil(BLAKE_BLOCK_LEN).max(1);
+    for i in 0..num_blocks {
+        let block = &chunk[i * BLAKE_BLOCK_LEN..chunk.len().min((i + 1) * BLAKE_BLOCK_LEN)];
+        let mut flags = 0;
+        if i == 0 {
+            flags |= BLAKE3_CHUNK_START;
+        }
+        if i == num_blocks - 1 {
+            flags |= BLAKE3_CHUNK_END;
+            if root {
+                flags |= BLAKE3_ROOT;
+            }
+        }
+        let output = blake3_compress(&cv, &pad_block(block), counter, block.len() as u32, flags);
+        cv.copy_from_slice(&output[..8]);
+    }
+    cv
+}
+
+/// The chaining value of the parent node of `left` and `right`.
+fn blake3_parent_cv(left: &[u32; 8], right: &[u32; 8], root: bool) -> [u32; 8] {
+    let mut block = [0u8; BLAKE_BLOCK_LEN];
+    block[..32].copy_from_slice(&words_to_bytes(left));
+    block[32..].copy_from_slice(&words_to_bytes(right));
+    let flags = BLAKE3_PARENT | if root { BLAKE3_ROOT } else { 0 };
+    let output = blake3_compress(&BLAKE_IV, &block, 0, BLAKE_BLOCK_LEN as u32, flags);
+    core::array::from_fn(|i| output[i])
+}
+
+/// Pads a block of at most [BLAKE_BLOCK_LEN] bytes with zeros.
+fn pad_block(bytes: &[u8]) -> [u8; BLAKE_BLOCK_LEN] {
+    let mut block = [0u8; BLAKE_BLOCK_LEN];
+    block[..bytes.len()].copy_from_slice(bytes);
+    block
+}
+
+fn words_to_bytes(words: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(not(target_os = "zkvm"))]
+mod host {
+    use super::{BLAKE_BLOCK_LEN, BLAKE_IV};
+
+    pub(super) const BLAKE2S_SIGMA: [[usize; 16]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+        [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+        [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+        [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+        [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+        [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+        [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+        [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+        [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    ];
+
+    /// The BLAKE3 message permutation applied `r` times for round `r`
+    pub(super) const BLAKE3_SCHEDULE: [[usize; 16]; 7] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+        [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+        [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+        [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+        [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+        [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+    ];
+
+    /// Runs the rounds given by `schedule` on the initial working state
+    /// `cv || IV[0..4] || params` and returns the final working state.
+    pub(super) fn compress(
+        cv: &[u32; 8],
+        block: &[u8; BLAKE_BLOCK_LEN],
+        params: &[u32; 4],
+        schedule: &[[usize; 16]],
+    ) -> [u32; 16] {
+        let m: [u32; 16] = core::array::from_fn(|i| {
+            u32::from_le_bytes(block[4 * i..4 * i + 4].try_into().unwrap())
+        });
+        let mut v = [0u32; 16];
+        v[..8].copy_from_slice(cv);
+        v[8..12].copy_from_slice(&BLAKE_IV[..4]);
+        v[12..].copy_from_slice(params);
+        for s in schedule {
+            g(&mut v, [0, 4, 8, 12], m[s[0]], m[s[1]]);
+            g(&mut v, [1, 5, 9, 13], m[s[2]], m[s[3]]);
+            g(&mut v, [2, 6, 10, 14], m[s[4]], m[s[5]]);
+            g(&mut v, [3, 7, 11, 15], m[s[6]], m[s[7]]);
+            g(&mut v, [0, 5, 10, 15], m[s[8]], m[s[9]]);
+            g(&mut v, [1, 6, 11, 12], m[s[10]], m[s[11]]);
+            g(&mut v, [2, 7, 8, 13], m[s[12]], m[s[13]]);
+            g(&mut v, [3, 4, 9, 14], m[s[14]], m[s[15]]);
+        }
+        v
+    }
+
+    fn g(v: &mut [u32; 16], [a, b, c, d]: [usize; 4], x: u32, y: u32) {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(12);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(8);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(7);
+    }
+}
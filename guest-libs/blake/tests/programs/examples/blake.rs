@@ -0,0 +1,63 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use openvm_blake::{blake2s256, blake3};
+
+openvm::entry!(main);
+
+pub fn main() {
+    let inputs: [Vec<u8>; 6] = [
+        Vec::new(),
+        b"abc".to_vec(),
+        // exactly one block
+        (0..64u32).map(|i| (i % 251) as u8).collect(),
+        // one byte more than a BLAKE3 chunk
+        (0..1025u32).map(|i| (i % 251) as u8).collect(),
+        // two BLAKE3 chunks under a root parent
+        (0..2048u32).map(|i| (i % 251) as u8).collect(),
+        // an unbalanced BLAKE3 tree
+        (0..5000u32).map(|i| (i % 251) as u8).collect(),
+    ];
+    // (BLAKE2s-256, BLAKE3)
+    let expected = [
+        (
+            "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        ),
+        (
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        ),
+        (
+            "56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e",
+            "4eed7141ea4a5cd4b788606bd23f46e212af9cacebacdc7d1f4c6dc7f2511b98",
+        ),
+        (
+            "9b4b1bfb89177545cc59b321be5403774c58f061db927f04d206116b8278d2b4",
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+        ),
+        (
+            "e0edc36d40bfa488e118fb944ad9361e1ec72fe8f24570e4ef64876b7e3d1a49",
+            "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+        ),
+        (
+            "5b73fde71a575695f52be841b2673288e9e797465cec0ae40c231d97810d8da5",
+            "ee78d92070de3df1c57c37002abf0a6b1a6589acdeef4d8ffac7cf3d9e8f2836",
+        ),
+    ];
+    for (input, (blake2s_out, blake3_out)) in inputs.iter().zip(expected.iter()) {
+        let input = black_box(input);
+        if blake2s256(input).to_vec() != Vec::from_hex(blake2s_out).unwrap() {
+            panic!();
+        }
+        if blake3(input).to_vec() != Vec::from_hex(blake3_out).unwrap() {
+            panic!();
+        }
+    }
+}
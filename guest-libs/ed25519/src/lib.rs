@@ -0,0 +1,197 @@
+//! Ed25519 signature verification ([RFC 8032](https://datatracker.ietf.org/doc/html/rfc8032))
+//! that uses zkvm instructions for the curve and field arithmetic.
+
+#![no_std]
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use hex_literal::hex;
+use openvm_algebra_guest::IntMod;
+use openvm_algebra_moduli_macros::moduli_declare;
+use openvm_ecc_guest::{
+    edwards::{FromCompressed, TwistedEdwardsPoint},
+    msm, CyclicGroup,
+};
+use openvm_ecc_te_macros::te_declare;
+use openvm_sha2::sha512;
+
+// --- Define the OpenVM modular arithmetic and ecc types ---
+
+moduli_declare! {
+    Ed25519Coord { modulus = "0x7FFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFED" },
+    Ed25519Scalar { modulus = "0x10000000 00000000 00000000 00000000 14DEF9DE A2F79CD6 5812631A 5CF5D3ED" },
+}
+
+// The constants are taken from: https://datatracker.ietf.org/doc/html/rfc8032#section-5.1
+// from_const_bytes takes a little endian byte string
+const CURVE_A: Ed25519Coord = Ed25519Coord::from_const_bytes(hex!(
+    "ECFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7F"
+));
+const CURVE_D: Ed25519Coord = Ed25519Coord::from_const_bytes(hex!(
+    "A3785913CA4DEB75ABD841414D0A700098E879777940C78C73FE6F2BEE6C0352"
+));
+
+te_declare! {
+    Ed25519Point { mod_type = Ed25519Coord, a = CURVE_A, d = CURVE_D },
+}
+
+impl CyclicGroup for Ed25519Point {
+    const GENERATOR: Self = Ed25519Point {
+        x: Ed25519Coord::from_const_bytes(hex!(
+            "1AD5258F602D56C9B2A7259560C72C695CDCD6FD31E2A4C0FE536ECDD3366921"
+        )),
+        y: Ed25519Coord::from_const_bytes(hex!(
+            "5866666666666666666666666666666666666666666666666666666666666666"
+        )),
+    };
+    const NEG_GENERATOR: Self = Ed25519Point {
+        x: Ed25519Coord::from_const_bytes(hex!(
+            "D32ADA709FD2A9364D58DA6A9F38D396A3232902CE1D5B3F01AC91322CC9965E"
+        )),
+        y: Ed25519Coord::from_const_bytes(hex!(
+            "5866666666666666666666666666666666666666666666666666666666666666"
+        )),
+    };
+}
+
+/// `2^256 mod l`, used to reduce 512-bit hash outputs modulo the group order `l`.
+const TWO_POW_256_MOD_L: Ed25519Scalar = Ed25519Scalar::from_const_bytes(hex!(
+    "1D95988D7431ECD670CF7D73F45BEFC6FEFFFFFFFFFFFFFFFFFFFFFFFFFFFF0F"
+));
+
+/// Length in bytes of an encoded public key.
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+/// Length in bytes of an encoded signature.
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// Error returned when decoding or verifying a signature fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The encoding of a point is not a valid curve point.
+    InvalidPoint,
+    /// The `s` component of the signature is not reduced modulo the group order.
+    InvalidScalar,
+    /// The signature equation does not hold.
+    InvalidSignature,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::InvalidPoint => write!(f, "invalid curve point encoding"),
+            Error::InvalidScalar => write!(f, "signature scalar is not reduced"),
+            Error::InvalidSignature => write!(f, "signature verification failed"),
+        }
+    }
+}
+
+/// Decodes a point from its 32-byte compressed encoding: the little endian `y`-coordinate with
+/// the sign of `x` stored in the most significant bit.
+pub fn decompress_point(bytes: &[u8; 32]) -> Result<Ed25519Point, Error> {
+    let mut y_bytes = *bytes;
+    let sign = y_bytes[31] >> 7;
+    y_bytes[31] &= 0x7f;
+    // Non-canonical encodings with y >= p are rejected
+    let y = Ed25519Coord::from_le_bytes(&y_bytes).ok_or(Error::InvalidPoint)?;
+    Ed25519Point::decompress(y, &sign).ok_or(Error::InvalidPoint)
+}
+
+/// Encodes a point in its 32-byte compressed form.
+pub fn compress_point(point: &Ed25519Point) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(point.y().as_le_bytes());
+    bytes[31] |= (point.x().as_le_bytes()[0] & 1) << 7;
+    bytes
+}
+
+/// An Ed25519 public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    bytes: [u8; PUBLIC_KEY_LENGTH],
+    point: Ed25519Point,
+}
+
+impl VerifyingKey {
+    /// Decodes a public key from its compressed encoding.
+    pub fn from_bytes(bytes: &[u8; PUBLIC_KEY_LENGTH]) -> Result<Self, Error> {
+        let point = decompress_point(bytes)?;
+        Ok(Self {
+            bytes: *bytes,
+            point,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PUBLIC_KEY_LENGTH] {
+        &self.bytes
+    }
+
+    pub fn as_point(&self) -> &Ed25519Point {
+        &self.point
+    }
+
+    /// Verifies `signature` on `message` using the cofactorless check `[s]B = R + [k]A` where
+    /// `k = SHA-512(R || A || M) mod l`.
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error> {
+        let r = decompress_point(&signature.r_bytes)?;
+        let s = Ed25519Scalar::from_le_bytes(&signature.s_bytes).ok_or(Error::InvalidScalar)?;
+
+        let mut preimage = Vec::with_capacity(64 + message.len());
+        preimage.extend_from_slice(&signature.r_bytes);
+        preimage.extend_from_slice(&self.bytes);
+        preimage.extend_from_slice(message);
+        let k = reduce_wide(&sha512(&preimage));
+
+        // [s]B - [k]A
+        let neg_a = core::ops::Neg::neg(&self.point);
+        let expected_r = msm(&[s, k], &[Ed25519Point::GENERATOR, neg_a]);
+        if expected_r == r {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+/// An Ed25519 signature `(R, s)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    r_bytes: [u8; 32],
+    s_bytes: [u8; 32],
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_LENGTH]) -> Self {
+        let mut r_bytes = [0u8; 32];
+        let mut s_bytes = [0u8; 32];
+        r_bytes.copy_from_slice(&bytes[..32]);
+        s_bytes.copy_from_slice(&bytes[32..]);
+        Self { r_bytes, s_bytes }
+    }
+
+    pub fn r_bytes(&self) -> &[u8; 32] {
+        &self.r_bytes
+    }
+
+    pub fn s_bytes(&self) -> &[u8; 32] {
+        &self.s_bytes
+    }
+
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(&self.r_bytes);
+        bytes[32..].copy_from_slice(&self.s_bytes);
+        bytes
+    }
+}
+
+/// Reduces a 512-bit little endian integer modulo the group order `l`.
+fn reduce_wide(bytes: &[u8; 64]) -> Ed25519Scalar {
+    // Both halves are less than 2^256, which the modular arithmetic accepts as unreduced inputs.
+    let lo = Ed25519Scalar::from_le_bytes_unchecked(&bytes[..32]);
+    let hi = Ed25519Scalar::from_le_bytes_unchecked(&bytes[32..]);
+    let k = lo + hi * TWO_POW_256_MOD_L;
+    // Scalar multiplication reads the bytes of `k`, so it must be canonical.
+    k.assert_reduced();
+    k
+}
@@ -0,0 +1,157 @@
+mod guest_tests {
+    use ed25519_config::Ed25519Config;
+    use eyre::Result;
+    use openvm_algebra_transpiler::ModularTranspilerExtension;
+    use openvm_circuit::{arch::instructions::exe::VmExe, utils::air_test};
+    use openvm_ecc_circuit::{Rv32EdwardsConfig, ED25519_CONFIG};
+    use openvm_ecc_transpiler::EdwardsTranspilerExtension;
+    use openvm_rv32im_transpiler::{
+        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    };
+    use openvm_sha256_transpiler::Sha512TranspilerExtension;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+    use openvm_toolchain_tests::{build_example_program_at_path, get_programs_dir};
+    use openvm_transpiler::{transpiler::Transpiler, FromElf};
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_add() -> Result<()> {
+        let config = Rv32EdwardsConfig::new(vec![ED25519_CONFIG.clone()]);
+        let elf =
+            build_example_program_at_path(get_programs_dir!("tests/programs"), "add", &config)?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(EdwardsTranspilerExtension)
+                .with_extension(ModularTranspilerExtension),
+        )?;
+        air_test(config, openvm_exe);
+        Ok(())
+    }
+
+    mod ed25519_config {
+        use eyre::Result;
+        use openvm_algebra_circuit::{
+            ModularExtension, ModularExtensionExecutor, ModularExtensionPeriphery,
+        };
+        use openvm_circuit::{
+            arch::{InitFileGenerator, SystemConfig},
+            derive::VmConfig,
+        };
+        use openvm_ecc_circuit::{
+            TeCurveConfig, TwistedEdwardsExtension, TwistedEdwardsExtensionExecutor,
+            TwistedEdwardsExtensionPeriphery,
+        };
+        use openvm_rv32im_circuit::{
+            Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
+            Rv32MExecutor, Rv32MPeriphery,
+        };
+        use openvm_sha256_circuit::{Sha512, Sha512Executor, Sha512Periphery};
+        use openvm_stark_backend::p3_field::PrimeField32;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Clone, Debug, VmConfig, Serialize, Deserialize)]
+        pub struct Ed25519Config {
+            #[system]
+            pub system: SystemConfig,
+            #[extension]
+            pub base: Rv32I,
+            #[extension]
+            pub mul: Rv32M,
+            #[extension]
+            pub io: Rv32Io,
+            #[extension]
+            pub modular: ModularExtension,
+            #[extension]
+            pub edwards: TwistedEdwardsExtension,
+            #[extension]
+            pub sha512: Sha512,
+        }
+
+        impl Ed25519Config {
+            pub fn new(curves: Vec<TeCurveConfig>) -> Self {
+                let primes: Vec<_> = curves
+                    .iter()
+                    .flat_map(|c| [c.modulus.clone(), c.scalar.clone()])
+                    .collect();
+                Self {
+                    system: SystemConfig::default().with_continuations(),
+                    base: Default::default(),
+                    mul: Default::default(),
+                    io: Default::default(),
+                    modular: ModularExtension::new(primes),
+                    edwards: TwistedEdwardsExtension::new(curves),
+                    sha512: Default::default(),
+                }
+            }
+        }
+
+        impl InitFileGenerator for Ed25519Config {
+            fn generate_init_file_contents(&self) -> Option<String> {
+                Some(format!(
+                    "// This file is automatically generated by cargo openvm. Do not rename or edit.\n{}\n{}\n",
+                    self.modular.generate_moduli_init(),
+                    self.edwards.generate_te_init()
+                ))
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        let config = Ed25519Config::new(vec![ED25519_CONFIG.clone()]);
+
+        let elf =
+            build_example_program_at_path(get_programs_dir!("tests/programs"), "verify", &config)?;
+        let openvm_exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(EdwardsTranspilerExtension)
+                .with_extension(ModularTranspilerExtension)
+                .with_extension(Sha512TranspilerExtension),
+        )?;
+        air_test(config, openvm_exe);
+        Ok(())
+    }
+}
+
+mod host_tests {
+    use hex_literal::hex;
+    use openvm_algebra_guest::IntMod;
+    use openvm_ecc_guest::{edwards::TwistedEdwardsPoint, CyclicGroup, Group};
+    use openvm_ed25519::{compress_point, Ed25519Coord, Ed25519Point};
+
+    #[test]
+    fn test_host_ed25519() {
+        let b = Ed25519Point::GENERATOR;
+        // 2B, little endian coordinates
+        let x2 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+            "0ECE43284EA1C5835FA4D715458E0D08ACE733187D3B043D6C045A9F4C38AB36"
+        ));
+        let y2 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+            "C9A3F86AAE465F0E56513864510F3997561FA2C9E85EA21DC2292309F3CD6022"
+        ));
+        let p2 = Ed25519Point::from_xy(x2, y2).unwrap();
+
+        #[allow(clippy::op_ref)]
+        let sum = &b + &b;
+        assert_eq!(sum, p2);
+        assert_eq!(b.double(), p2);
+        #[allow(clippy::op_ref)]
+        let identity = &b + &Ed25519Point::NEG_GENERATOR;
+        assert_eq!(identity, <Ed25519Point as Group>::IDENTITY);
+
+        // The standard encoding of the base point
+        assert_eq!(
+            compress_point(&b),
+            hex!("5866666666666666666666666666666666666666666666666666666666666666")
+        );
+    }
+}
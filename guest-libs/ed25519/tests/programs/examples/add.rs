@@ -0,0 +1,46 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use hex_literal::hex;
+use openvm_algebra_guest::IntMod;
+use openvm_ecc_guest::{edwards::TwistedEdwardsPoint, msm, CyclicGroup, Group};
+use openvm_ed25519::{Ed25519Coord, Ed25519Point, Ed25519Scalar};
+
+openvm::init!("openvm_init_add.rs");
+
+openvm::entry!(main);
+
+pub fn main() {
+    let b = Ed25519Point::GENERATOR;
+    // 2B and 3B, little endian coordinates
+    let x2 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+        "0ECE43284EA1C5835FA4D715458E0D08ACE733187D3B043D6C045A9F4C38AB36"
+    ));
+    let y2 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+        "C9A3F86AAE465F0E56513864510F3997561FA2C9E85EA21DC2292309F3CD6022"
+    ));
+    let x3 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+        "5CE2F8D35F4862AC86486281199843633AC8DA3E74AEF41F498F92224A9CAE67"
+    ));
+    let y3 = Ed25519Coord::from_le_bytes_unchecked(&hex!(
+        "D4B4F5784868C3020403246717EC169FF79E26608EA126A1AB69EE77D1B16712"
+    ));
+    let p2 = Ed25519Point::from_xy(x2, y2).unwrap();
+    let p3 = Ed25519Point::from_xy(x3, y3).unwrap();
+
+    // The addition formula is complete, so doubling and the identity need no special handling.
+    assert_eq!(&b + &b, p2);
+    assert_eq!(b.double(), p2);
+    assert_eq!(&p2 + &b, p3);
+    assert_eq!(&p3 - &b, p2);
+    assert_eq!(&b + &<Ed25519Point as Group>::IDENTITY, b);
+    assert_eq!(&b - &b, <Ed25519Point as Group>::IDENTITY);
+    assert_eq!(
+        &b + &Ed25519Point::NEG_GENERATOR,
+        <Ed25519Point as Group>::IDENTITY
+    );
+
+    // Scalar multiplication
+    let result = msm(&[Ed25519Scalar::from_u32(3)], &[b]);
+    assert_eq!(result, p3);
+}
@@ -0,0 +1,57 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use hex_literal::hex;
+// clippy thinks this is unused, but it's used in the init! macro
+#[allow(unused)]
+use openvm_ed25519::Ed25519Point;
+use openvm_ed25519::{Error, Signature, VerifyingKey};
+
+openvm::init!("openvm_init_verify.rs");
+
+openvm::entry!(main);
+
+// Test vectors 1-3 from https://datatracker.ietf.org/doc/html/rfc8032#section-7.1
+const TEST_VECTORS: [(&[u8; 32], &[u8], &[u8; 64]); 3] = [
+    (
+        &hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+        &[],
+        &hex!(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        ),
+    ),
+    (
+        &hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+        &hex!("72"),
+        &hex!(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+        ),
+    ),
+    (
+        &hex!("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025"),
+        &hex!("af82"),
+        &hex!(
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"
+        ),
+    ),
+];
+
+pub fn main() {
+    for (pk, msg, sig) in TEST_VECTORS {
+        let vk = VerifyingKey::from_bytes(pk).unwrap();
+        let sig = Signature::from_bytes(sig);
+        vk.verify(msg, &sig).unwrap();
+        // A modified message must be rejected
+        assert_eq!(vk.verify(b"tampered", &sig), Err(Error::InvalidSignature));
+    }
+
+    // s >= l must be rejected
+    let (pk, msg, sig) = TEST_VECTORS[0];
+    let mut sig = *sig;
+    sig[63] |= 0xf0;
+    let vk = VerifyingKey::from_bytes(pk).unwrap();
+    assert_eq!(
+        vk.verify(msg, &Signature::from_bytes(&sig)),
+        Err(Error::InvalidScalar)
+    );
+}
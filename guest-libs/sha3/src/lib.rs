@@ -0,0 +1,176 @@
+#![no_std]
+
+/// Number of `u64` lanes in the keccak-f\[1600\] state.
+pub const KECCAK_STATE_LANES: usize = 25;
+
+/// Rate in bytes of SHA3-256.
+pub const SHA3_256_RATE: usize = 136;
+/// Rate in bytes of SHA3-512.
+pub const SHA3_512_RATE: usize = 72;
+/// Rate in bytes of SHAKE128.
+pub const SHAKE128_RATE: usize = 168;
+/// Rate in bytes of SHAKE256.
+pub const SHAKE256_RATE: usize = 136;
+
+/// Domain separation and first padding bit of the original Keccak submission.
+pub const KECCAK_DELIM: u8 = 0x01;
+/// Domain separation and first padding bit of SHA-3.
+pub const SHA3_DELIM: u8 = 0x06;
+/// Domain separation and first padding bit of SHAKE.
+pub const SHAKE_DELIM: u8 = 0x1f;
+
+/// The keccak-f\[1600\] permutation, applied in place.
+///
+/// Lane `x + 5 * y` of `state` is the lane `A[x, y]` of the keccak specification. Bytes are
+/// absorbed into and squeezed from the lanes in little-endian order.
+#[inline(always)]
+pub fn keccakf(state: &mut [u64; KECCAK_STATE_LANES]) {
+    #[cfg(not(target_os = "zkvm"))]
+    tiny_keccak::keccakf(state);
+    // The zkvm is little-endian, so the in-memory layout of `state` is the byte layout the
+    // intrinsic expects.
+    #[cfg(target_os = "zkvm")]
+    openvm_keccak256_guest::native_keccakf(state.as_mut_ptr() as *mut u8);
+}
+
+/// A keccak sponge over the keccak-f\[1600\] permutation, parameterized by its rate and the
+/// domain separation byte that starts the padding.
+///
+/// Use [KeccakSponge::absorb] to feed input and [KeccakSponge::squeeze] to read output. The input
+/// is padded on the first call to [KeccakSponge::squeeze], after which no more input can be
+/// absorbed. Squeezing can be repeated to get an arbitrary length output, as in SHAKE.
+#[derive(Clone, Debug)]
+pub struct KeccakSponge {
+    state: [u64; KECCAK_STATE_LANES],
+    rate: usize,
+    delim: u8,
+    /// Position in the current rate block, in bytes.
+    offset: usize,
+    squeezing: bool,
+}
+
+impl KeccakSponge {
+    /// Creates a sponge with `rate` bytes of rate and the domain separation byte `delim`.
+    ///
+    /// # Panics
+    /// If `rate` is zero, is not a multiple of 8 or is not smaller than the 200-byte state.
+    pub const fn new(rate: usize, delim: u8) -> Self {
+        assert!(rate > 0 && rate % 8 == 0 && rate < KECCAK_STATE_LANES * 8);
+        Self {
+            state: [0; KECCAK_STATE_LANES],
+            rate,
+            delim,
+            offset: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Sponge for SHA3-256.
+    pub const fn sha3_256() -> Self {
+        Self::new(SHA3_256_RATE, SHA3_DELIM)
+    }
+
+    /// Sponge for SHA3-512.
+    pub const fn sha3_512() -> Self {
+        Self::new(SHA3_512_RATE, SHA3_DELIM)
+    }
+
+    /// Sponge for the SHAKE128 extendable output function.
+    pub const fn shake128() -> Self {
+        Self::new(SHAKE128_RATE, SHAKE_DELIM)
+    }
+
+    /// Sponge for the SHAKE256 extendable output function.
+    pub const fn shake256() -> Self {
+        Self::new(SHAKE256_RATE, SHAKE_DELIM)
+    }
+
+    /// Absorbs `input` into the sponge.
+    ///
+    /// # Panics
+    /// If the sponge has already started squeezing.
+    pub fn absorb(&mut self, input: &[u8]) {
+        assert!(!self.squeezing, "cannot absorb after squeezing");
+        for &byte in input {
+            self.xor_byte(self.offset, byte);
+            self.offset += 1;
+            if self.offset == self.rate {
+                keccakf(&mut self.state);
+                self.offset = 0;
+            }
+        }
+    }
+
+    /// Fills `output` with the next bytes squeezed from the sponge.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        if !self.squeezing {
+            self.pad();
+        }
+        for byte in output.iter_mut() {
+            if self.offset == self.rate {
+                keccakf(&mut self.state);
+                self.offset = 0;
+            }
+            *byte = (self.state[self.offset / 8] >> (8 * (self.offset % 8))) as u8;
+            self.offset += 1;
+        }
+    }
+
+    /// Applies the pad10*1 rule after the domain separation bits and permutes the last block.
+    fn pad(&mut self) {
+        self.xor_byte(self.offset, self.delim);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccakf(&mut self.state);
+        self.offset = 0;
+        self.squeezing = true;
+    }
+
+    #[inline(always)]
+    fn xor_byte(&mut self, idx: usize, byte: u8) {
+        self.state[idx / 8] ^= (byte as u64) << (8 * (idx % 8));
+    }
+}
+
+/// The SHA3-256 cryptographic hash function.
+#[inline(always)]
+pub fn sha3_256(input: &[u8]) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    set_sha3_256(input, &mut output);
+    output
+}
+
+/// Sets `output` to the SHA3-256 hash of `input`.
+pub fn set_sha3_256(input: &[u8], output: &mut [u8; 32]) {
+    let mut sponge = KeccakSponge::sha3_256();
+    sponge.absorb(input);
+    sponge.squeeze(output);
+}
+
+/// The SHA3-512 cryptographic hash function.
+#[inline(always)]
+pub fn sha3_512(input: &[u8]) -> [u8; 64] {
+    let mut output = [0u8; 64];
+    set_sha3_512(input, &mut output);
+    output
+}
+
+/// Sets `output` to the SHA3-512 hash of `input`.
+pub fn set_sha3_512(input: &[u8], output: &mut [u8; 64]) {
+    let mut sponge = KeccakSponge::sha3_512();
+    sponge.absorb(input);
+    sponge.squeeze(output);
+}
+
+/// Fills `output` with the SHAKE128 extendable output of `input`.
+pub fn shake128(input: &[u8], output: &mut [u8]) {
+    let mut sponge = KeccakSponge::shake128();
+    sponge.absorb(input);
+    sponge.squeeze(output);
+}
+
+/// Fills `output` with the SHAKE256 extendable output of `input`.
+pub fn shake256(input: &[u8], output: &mut [u8]) {
+    let mut sponge = KeccakSponge::shake256();
+    sponge.absorb(input);
+    sponge.squeeze(output);
+}
@@ -0,0 +1,83 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::hint::black_box;
+
+use hex::FromHex;
+use openvm_sha3::{sha3_256, sha3_512, shake128, shake256, KeccakSponge};
+
+openvm::entry!(main);
+
+pub fn main() {
+    let inputs: [Vec<u8>; 4] = [
+        Vec::new(),
+        b"abc".to_vec(),
+        // one byte short of the SHA3-256 rate, so the padding is a single byte
+        (0..135u8).collect(),
+        // spans several blocks for all rates
+        (0..300u32).map(|i| ((i * 7 + 3) % 256) as u8).collect(),
+    ];
+    // (SHA3-256, SHA3-512, SHAKE128 with 200 bytes of output, SHAKE256 with 64 bytes of output)
+    let expected = [
+        (
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef263cb1eea988004b93103cfb0aeefd2a686e01fa4a58e8a3639ca8a1e3f9ae57e235b8cc873c23dc62b8d260169afa2f75ab916a58d974918835d25e6a435085b2badfd6dfaac359a5efbb7bcc4b59d538df9a04302e10c8bc1cbf1a0b3a5120ea17cda7cfad765f5623474d368ccca8af0007cd9f5e4c849f167a580b14aabdefaee7eef47cb0fca9767be1fda69419dfb927e9df07348b196691abaeb580b32def58538b8d23f877",
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+        ),
+        (
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+            "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc844c50af32acd3f2cdd066568706f509bc1bdde58295dae3f891a9a0fca5783789a41f8611214ce612394df286a62d1a2252aa94db9c538956c717dc2bed4f232a0294c857c730aa16067ac1062f1201fb0d377cfb9cde4c63599b27f3462bba4a0ed296c801f9ff7f57302bb3076ee145f97a32ae68e76ab66c48d51675bd49acc29082f5647584e6aa01b3f5af057805f973ff8ecb8b226ac32ada6f01c1fcd4818cb006aa5b4cd",
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+        ),
+        (
+            "fded8fd9d6551c601eeb3b7c6bc5e5cfd8aad1d015b7e9aaa9c9b9475231d5e2",
+            "d942df0df09ac042cd3b641144c98d8fda0980bb037fc5c0e7f2e9a073b073dc4bb8a8c1f4cb5b45f5805c6523741ed0571d6779b15829b2faa280fc60b50645",
+            "d11fafa27f42a8162b8ae013535771de81722c0abc8aa2bca01825462e2f89718b195581302da8bc6d4a3c186fab0ecc4ffec0f46caa11d4643bdedfdf8911dfd03e60ba35a951c1c8604ea3debe03031b4d2c2b48f784c54cb3baef10353f9c307083237bf55aa6151439d1e640a66b549c21b51ae2237f274d7dff45716c5e86729ec2016313944e9e44230c245c3fa1fc49e981666bc4959c53688ef8274ecf91ca6d556242b754608d6428643be959522029b779e8abf5ce47bd78beb0c949eb837694aa43c9",
+            "c45dae624ad8a2f5aa7bac9d7557737fd91c96eedb70a6be5574d57a844eade07f4056bf081a1098101cea8132188c422136feb4687d1e2209f3fd28bedfb8f4",
+        ),
+        (
+            "064af3405aacb53d5d77ee858fec1e6e225480de3f14f06444e2b33d92d61879",
+            "f85b76d4d2c98f6ec82f06651127d19643e2b1117420b700c42a8cdd55379152ca391df72e30886e70beb58cf28c05ae6bdb1197a91542e57fac331233246b93",
+            "e1fe170edec7f0d2283385445abd2568cda326428ff81c2a0aad59d2e25e5088ceec9655e15cde1d5ad914565e11b6020c7849fb6c2eaf62322e281c0d8d40ec85b2102953c841e4f0a8394be580b0db99f5019ee5e7ab29b1abadfb5baba8ad1aacf763d2cf5dd28b587fb4e4d817e997e5874f8166ac273007f8d4838ee52d28485b4dd039c9a95d0b84c4ce51c8fabc9c1bcc42cdb9a18932896d4eb76c8e08a31db2e2cd4b2d7152a5cf93f2f6d00813793fbabdfee8dc2f1ef3ff3bd99f88a11dffb8a3c5ea",
+            "685d9873233fd4c7ce4bb15d7b947c9841f0e5cc18847a4ef07769ccb13022be75ab878b1c49a037276714755b87c8e553c98b24721f93b444598fb0d5826391",
+        ),
+    ];
+    for (input, (sha3_256_out, sha3_512_out, shake128_out, shake256_out)) in
+        inputs.iter().zip(expected.iter())
+    {
+        let input = black_box(input);
+        if sha3_256(input).to_vec() != Vec::from_hex(sha3_256_out).unwrap() {
+            panic!();
+        }
+        if sha3_512(input).to_vec() != Vec::from_hex(sha3_512_out).unwrap() {
+            panic!();
+        }
+
+        let mut output = [0u8; 200];
+        shake128(input, &mut output);
+        let shake128_out = Vec::from_hex(shake128_out).unwrap();
+        if output.to_vec() != shake128_out {
+            panic!();
+        }
+        // squeezing incrementally must give the same output
+        let mut sponge = KeccakSponge::shake128();
+        sponge.absorb(input);
+        let (first, second) = output.split_at_mut(100);
+        sponge.squeeze(first);
+        sponge.squeeze(second);
+        if output.to_vec() != shake128_out {
+            panic!();
+        }
+
+        let mut output = [0u8; 64];
+        shake256(input, &mut output);
+        if output.to_vec() != Vec::from_hex(shake256_out).unwrap() {
+            panic!();
+        }
+    }
+}
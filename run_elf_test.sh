@@ -14,7 +14,7 @@ SIG_FILE=${2:-"${ELF_FILE}.signature"}
 
 echo "Testing ELF: $ELF_FILE"
 echo "Signature output: $SIG_FILE"
-echo "Signature region will be extracted from ELF symbols (begin_signature/end_signature)"
+echo "Signature region is read from the begin_signature/end_signature ELF symbols"
 
 # Use a fixed directory
 cd /home/cody/openvm/elf-test